@@ -1,6 +1,6 @@
 use crate::InstructionReader;
 use koto_memory::Ptr;
-use koto_parser::{ConstantPool, Span};
+use koto_parser::{format_source_excerpt, ConstantIndex, ConstantPool, Span, Warning};
 use std::{
     fmt::{self, Write},
     path::{Path, PathBuf},
@@ -9,7 +9,11 @@ use std::{
 /// Debug information for a Koto program
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct DebugInfo {
-    source_map: Vec<(u32, Span)>,
+    pub(crate) source_map: Vec<(u32, Span)>,
+    // The names of each function's top-level args, keyed by the ip at the start of the function
+    pub(crate) function_args: Vec<(u32, Box<[Option<ConstantIndex>]>)>,
+    // The messages of functions annotated with `@deprecated`, keyed by the function's start ip
+    pub(crate) function_deprecations: Vec<(u32, ConstantIndex)>,
     /// The source of the program that the debug info was derived from
     pub source: String,
 }
@@ -46,6 +50,38 @@ impl DebugInfo {
         }
         result
     }
+
+    /// Adds the arg names for the function that starts at the given ip
+    ///
+    /// Args without names (e.g. wildcards or unpacked tuples) are represented by `None`.
+    pub fn push_function_args(&mut self, ip: u32, args: Box<[Option<ConstantIndex>]>) {
+        // Functions are compiled in order, so the entries remain sorted by ip
+        debug_assert!(!matches!(self.function_args.last(), Some((last, _)) if *last >= ip));
+        self.function_args.push((ip, args));
+    }
+
+    /// Returns the arg names for the function that starts at the given ip
+    pub fn get_function_args(&self, ip: u32) -> Option<&[Option<ConstantIndex>]> {
+        self.function_args
+            .binary_search_by_key(&ip, |(function_ip, _)| *function_ip)
+            .ok()
+            .map(|index| self.function_args[index].1.as_ref())
+    }
+
+    /// Adds the deprecation message for the function that starts at the given ip
+    pub fn push_function_deprecation(&mut self, ip: u32, message: ConstantIndex) {
+        // Functions are compiled in order, so the entries remain sorted by ip
+        debug_assert!(!matches!(self.function_deprecations.last(), Some((last, _)) if *last >= ip));
+        self.function_deprecations.push((ip, message));
+    }
+
+    /// Returns the deprecation message for the function that starts at the given ip
+    pub fn get_function_deprecation(&self, ip: u32) -> Option<ConstantIndex> {
+        self.function_deprecations
+            .binary_search_by_key(&ip, |(function_ip, _)| *function_ip)
+            .ok()
+            .map(|index| self.function_deprecations[index].1)
+    }
 }
 
 /// A compiled chunk of bytecode, along with its associated constants and metadata
@@ -59,6 +95,8 @@ pub struct Chunk {
     pub source_path: Option<PathBuf>,
     /// Debug information associated with the chunk's bytecode
     pub debug_info: DebugInfo,
+    /// Warnings that were produced while compiling the chunk
+    pub warnings: Vec<Warning>,
 }
 
 impl Chunk {
@@ -74,7 +112,28 @@ impl Chunk {
             constants,
             source_path: source_path.map(Path::to_path_buf),
             debug_info,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Returns a [String] displaying the chunk's warnings, along with their source excerpts
+    pub fn warnings_as_string(&self) -> String {
+        let mut result = String::new();
+
+        for warning in self.warnings.iter() {
+            writeln!(
+                result,
+                "Warning: {warning}.\n{}",
+                format_source_excerpt(
+                    &self.debug_info.source,
+                    &warning.span,
+                    self.source_path.as_deref()
+                )
+            )
+            .ok();
         }
+
+        result
     }
 
     /// Returns a [String] displaying the instructions contained in the compiled [Chunk]
@@ -0,0 +1,319 @@
+use crate::{Chunk, DebugInfo};
+use koto_parser::{Constant, ConstantIndex, ConstantPool, Position, Span};
+use std::path::Path;
+use thiserror::Error;
+
+// Identifies data produced by Chunk::to_bytes, followed by the version of Koto that produced it
+const CHUNK_MARKER: &[u8; 4] = b"KOTO";
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const CONSTANT_F64: u8 = 0;
+const CONSTANT_I64: u8 = 1;
+const CONSTANT_STR: u8 = 2;
+
+/// An error that occurred while restoring a [Chunk] with [Chunk::from_bytes]
+#[derive(Error, Clone, Debug)]
+pub enum ChunkBytesError {
+    /// The chunk was produced by a different version of Koto
+    #[error("the chunk was compiled with a different version of Koto ({0})")]
+    VersionMismatch(String),
+    /// The data doesn't contain a valid chunk
+    #[error("the chunk's data is invalid")]
+    InvalidData,
+}
+
+impl Chunk {
+    /// Serializes the chunk's bytecode, constants, and debug info
+    ///
+    /// The chunk can be restored with [Chunk::from_bytes], using the same version of Koto.
+    /// The chunk's warnings and source path aren't included.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = ChunkWriter::default();
+
+        writer.bytes(CHUNK_MARKER);
+        writer.string(VERSION);
+
+        writer.len(self.bytes.len());
+        writer.bytes(&self.bytes);
+
+        writer.len(self.constants.size());
+        for constant in self.constants.iter() {
+            match constant {
+                Constant::F64(n) => {
+                    writer.u8(CONSTANT_F64);
+                    writer.bytes(&n.to_le_bytes());
+                }
+                Constant::I64(n) => {
+                    writer.u8(CONSTANT_I64);
+                    writer.bytes(&n.to_le_bytes());
+                }
+                Constant::Str(s) => {
+                    writer.u8(CONSTANT_STR);
+                    writer.string(s);
+                }
+            }
+        }
+
+        let debug_info = &self.debug_info;
+
+        writer.len(debug_info.source_map.len());
+        for (ip, span) in debug_info.source_map.iter() {
+            writer.u32(*ip);
+            writer.span(span);
+        }
+
+        writer.len(debug_info.function_args.len());
+        for (ip, args) in debug_info.function_args.iter() {
+            writer.u32(*ip);
+            writer.len(args.len());
+            for arg in args.iter() {
+                match arg {
+                    Some(index) => {
+                        writer.u8(1);
+                        writer.u32((*index).into());
+                    }
+                    None => writer.u8(0),
+                }
+            }
+        }
+
+        writer.len(debug_info.function_deprecations.len());
+        for (ip, message) in debug_info.function_deprecations.iter() {
+            writer.u32(*ip);
+            writer.u32((*message).into());
+        }
+
+        writer.string(&debug_info.source);
+
+        writer.result
+    }
+
+    /// Restores a chunk that was serialized with [Chunk::to_bytes]
+    ///
+    /// The source path is used when reporting errors, and when importing modules that are
+    /// relative to the chunk's script.
+    pub fn from_bytes(bytes: &[u8], source_path: Option<&Path>) -> Result<Self, ChunkBytesError> {
+        let mut reader = ChunkReader { bytes };
+
+        if reader.bytes(CHUNK_MARKER.len())? != CHUNK_MARKER {
+            return Err(ChunkBytesError::InvalidData);
+        }
+        let version = reader.string()?;
+        if version != VERSION {
+            return Err(ChunkBytesError::VersionMismatch(version.into()));
+        }
+
+        let bytecode_len = reader.len()?;
+        let bytecode = reader.bytes(bytecode_len)?;
+
+        // Vecs are grown as entries are read rather than being allocated with the counts
+        // contained in the data, which might be invalid.
+        let constant_count = reader.len()?;
+        let mut constants = Vec::new();
+        for _ in 0..constant_count {
+            let constant = match reader.u8()? {
+                CONSTANT_F64 => Constant::F64(f64::from_le_bytes(reader.array()?)),
+                CONSTANT_I64 => Constant::I64(i64::from_le_bytes(reader.array()?)),
+                CONSTANT_STR => Constant::Str(reader.string()?),
+                _ => return Err(ChunkBytesError::InvalidData),
+            };
+            constants.push(constant);
+        }
+        let constants =
+            ConstantPool::from_constants(constants).ok_or(ChunkBytesError::InvalidData)?;
+
+        let mut debug_info = DebugInfo::default();
+
+        let source_map_len = reader.len()?;
+        for _ in 0..source_map_len {
+            let ip = reader.u32()?;
+            let span = reader.span()?;
+            debug_info.source_map.push((ip, span));
+        }
+
+        let function_args_len = reader.len()?;
+        for _ in 0..function_args_len {
+            let ip = reader.u32()?;
+            let arg_count = reader.len()?;
+            let mut args = Vec::new();
+            for _ in 0..arg_count {
+                let arg = match reader.u8()? {
+                    0 => None,
+                    1 => Some(reader.constant_index(&constants)?),
+                    _ => return Err(ChunkBytesError::InvalidData),
+                };
+                args.push(arg);
+            }
+            debug_info.function_args.push((ip, args.into()));
+        }
+
+        let function_deprecations_len = reader.len()?;
+        for _ in 0..function_deprecations_len {
+            let ip = reader.u32()?;
+            let message = reader.constant_index(&constants)?;
+            debug_info.function_deprecations.push((ip, message));
+        }
+
+        debug_info.source = reader.string()?.into();
+
+        if !reader.bytes.is_empty() {
+            return Err(ChunkBytesError::InvalidData);
+        }
+
+        Ok(Chunk::new(
+            bytecode.into(),
+            constants,
+            source_path,
+            debug_info,
+        ))
+    }
+}
+
+#[derive(Default)]
+struct ChunkWriter {
+    result: Vec<u8>,
+}
+
+impl ChunkWriter {
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.result.extend_from_slice(bytes);
+    }
+
+    fn u8(&mut self, n: u8) {
+        self.result.push(n);
+    }
+
+    fn u32(&mut self, n: u32) {
+        self.bytes(&n.to_le_bytes());
+    }
+
+    fn len(&mut self, len: usize) {
+        self.bytes(&(len as u64).to_le_bytes());
+    }
+
+    fn string(&mut self, s: &str) {
+        self.len(s.len());
+        self.bytes(s.as_bytes());
+    }
+
+    fn span(&mut self, span: &Span) {
+        self.u32(span.start.line);
+        self.u32(span.start.column);
+        self.u32(span.end.line);
+        self.u32(span.end.column);
+    }
+}
+
+struct ChunkReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ChunkReader<'a> {
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], ChunkBytesError> {
+        if count > self.bytes.len() {
+            return Err(ChunkBytesError::InvalidData);
+        }
+        let (result, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+        Ok(result)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ChunkBytesError> {
+        // The slice has the requested length, so the conversion can't fail
+        Ok(self.bytes(N)?.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8, ChunkBytesError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, ChunkBytesError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn len(&mut self) -> Result<usize, ChunkBytesError> {
+        usize::try_from(u64::from_le_bytes(self.array()?)).map_err(|_| ChunkBytesError::InvalidData)
+    }
+
+    fn constant_index(
+        &mut self,
+        constants: &ConstantPool,
+    ) -> Result<ConstantIndex, ChunkBytesError> {
+        let index = self.u32()?;
+        if (index as usize) < constants.size() {
+            Ok(index.into())
+        } else {
+            Err(ChunkBytesError::InvalidData)
+        }
+    }
+
+    fn string(&mut self) -> Result<&'a str, ChunkBytesError> {
+        let len = self.len()?;
+        std::str::from_utf8(self.bytes(len)?).map_err(|_| ChunkBytesError::InvalidData)
+    }
+
+    fn span(&mut self) -> Result<Span, ChunkBytesError> {
+        Ok(Span {
+            start: Position {
+                line: self.u32()?,
+                column: self.u32()?,
+            },
+            end: Position {
+                line: self.u32()?,
+                column: self.u32()?,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CompilerSettings, Loader};
+
+    fn compile(script: &str) -> Chunk {
+        let chunk = Loader::default()
+            .compile_script(script, None, CompilerSettings::default())
+            .unwrap();
+        Chunk::clone(&chunk)
+    }
+
+    #[test]
+    fn round_trip() {
+        let script = "
+@deprecated 'use g'
+f = |a, (b, c), d...|
+  x = a + b * c
+  '{x} {d}'
+export g = |n| f n, (2.5, -1), 'abc', 99
+";
+        let chunk = compile(script);
+        assert!(!chunk.debug_info.function_args.is_empty());
+        assert!(!chunk.debug_info.function_deprecations.is_empty());
+        let restored = Chunk::from_bytes(&chunk.to_bytes(), None).unwrap();
+        assert!(restored == chunk);
+    }
+
+    #[test]
+    fn truncated_data() {
+        let bytes = compile("x = 'hello'").to_bytes();
+        for len in 0..bytes.len() {
+            assert!(matches!(
+                Chunk::from_bytes(&bytes[..len], None),
+                Err(ChunkBytesError::InvalidData)
+            ));
+        }
+    }
+
+    #[test]
+    fn oversized_count() {
+        let mut bytes = compile("x = 'hello'").to_bytes();
+        // Replace the bytecode length with a huge value
+        let len_offset = CHUNK_MARKER.len() + 8 + VERSION.len();
+        bytes[len_offset..len_offset + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(
+            Chunk::from_bytes(&bytes, None),
+            Err(ChunkBytesError::InvalidData)
+        ));
+    }
+}
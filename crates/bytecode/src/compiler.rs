@@ -1,5 +1,5 @@
 use crate::{
-    frame::{Arg, AssignedOrReserved, Frame, FrameError},
+    frame::{Arg, AssignedOrReserved, Frame, FrameError, TryBlock},
     DebugInfo, FunctionFlags, Op, StringFormatFlags,
 };
 use koto_parser::{
@@ -18,6 +18,8 @@ enum ErrorKind {
     UnexpectedNode { expected: String, unexpected: Node },
     #[error("attempting to assign to a temporary value")]
     AssigningToATemporaryValue,
+    #[error("attempting to assign to an optional access")]
+    AssigningToAnOptionalAccess,
     #[error("invalid {kind} op ({op:?})")]
     InvalidBinaryOp { kind: String, op: AstBinaryOp },
     #[error("`{0}` used outside of loop")]
@@ -44,6 +46,8 @@ enum ErrorKind {
     MissingNextChainNode,
     #[error("missing chain parent register")]
     MissingChainParentRegister,
+    #[error("the contents of '{0}' haven't been loaded for embedding")]
+    MissingEmbeddedFileContents(String),
     #[error("missing result register")]
     MissingResultRegister,
     #[error("missing String nodes")]
@@ -71,8 +75,16 @@ enum ErrorKind {
     TooManyContainerEntries(usize),
     #[error("The result of this `break` expression will be ignored")]
     UnassignedBreakValue,
+    #[error("unexpected default argument")]
+    UnexpectedDefaultArg,
     #[error("unexpected Ellipsis")]
     UnexpectedEllipsis,
+    #[error("type patterns are only allowed in match arms")]
+    UnexpectedMatchType,
+    #[error("unexpected named argument")]
+    UnexpectedNamedArg,
+    #[error("unexpected spread value")]
+    UnexpectedSpread,
     #[error("unexpected Wildcard")]
     UnexpectedWildcard,
     #[error("expected {expected} patterns in match arm, found {unexpected}")]
@@ -203,6 +215,14 @@ pub struct CompilerSettings {
     /// This is used by the REPL to automatically export values so that they're available between
     /// chunks.
     pub export_top_level_ids: bool,
+    /// Requires new bindings to be explicitly declared with `let`
+    ///
+    /// See [ParserSettings::strict](koto_parser::ParserSettings::strict).
+    pub strict: bool,
+    /// The flags that enable `@cfg` blocks
+    ///
+    /// Blocks with flags that aren't included here are left out of the compiled bytecode.
+    pub cfg_flags: Vec<String>,
 }
 
 /// The compiler used by the Koto language
@@ -325,6 +345,7 @@ impl Compiler {
                 start,
                 end,
                 inclusive,
+                step,
             } => {
                 let result = self.assign_result_register(ctx)?;
 
@@ -349,10 +370,22 @@ impl Compiler {
                         self.pop_register()?;
                     }
 
+                    if let Some(step) = step {
+                        let step_result = self.compile_node(*step, ctx.with_any_register())?;
+                        self.push_op(RangeStep, &[result_register, step_result.unwrap(self)?]);
+                        if step_result.is_temporary {
+                            self.pop_register()?;
+                        }
+                    }
+
                     result
                 } else {
                     self.compile_node(*start, ctx.compile_for_side_effects())?;
-                    self.compile_node(*end, ctx.compile_for_side_effects())?
+                    let end_result = self.compile_node(*end, ctx.compile_for_side_effects())?;
+                    match step {
+                        Some(step) => self.compile_node(*step, ctx.compile_for_side_effects())?,
+                        None => end_result,
+                    }
                 }
             }
             Node::RangeFrom { start } => {
@@ -410,7 +443,46 @@ impl Compiler {
                 self.compile_make_sequence(elements, Op::SequenceToTuple, ctx)?
             }
             Node::TempTuple(elements) => self.compile_make_temp_tuple(elements, ctx)?,
-            Node::Function(f) => self.compile_function(f, ctx)?,
+            Node::Function(f) => self.compile_function(f, None, ctx)?,
+            Node::Deprecated { message, function } => match ctx.node(*function) {
+                Node::Function(f) => self.compile_function(f, Some(*message), ctx)?,
+                unexpected => {
+                    return self.error(ErrorKind::UnexpectedNode {
+                        expected: "Function".into(),
+                        unexpected: unexpected.clone(),
+                    })
+                }
+            },
+            Node::Cfg { flag, body } => {
+                let flag = ctx.ast.constants().get_str(*flag);
+                if self
+                    .settings
+                    .cfg_flags
+                    .iter()
+                    .any(|enabled| enabled == flag)
+                {
+                    self.compile_node(*body, ctx)?
+                } else {
+                    let result = self.assign_result_register(ctx)?;
+                    if let Some(result) = result.register {
+                        self.push_op(SetNull, &[result]);
+                    }
+                    result
+                }
+            }
+            Node::Embed { path, contents } => match contents {
+                Some(contents) => {
+                    let result = self.assign_result_register(ctx)?;
+                    if let Some(result_register) = result.register {
+                        self.compile_load_string_constant(result_register, *contents);
+                    }
+                    result
+                }
+                None => {
+                    let path = ctx.ast.constants().get_str(*path);
+                    return self.error(ErrorKind::MissingEmbeddedFileContents(path.into()));
+                }
+            },
             Node::Import { from, items } => self.compile_import(from, items, ctx)?,
             Node::Export(expression) => self.compile_export(*expression, ctx)?,
             Node::Assign { target, expression } => {
@@ -425,8 +497,12 @@ impl Compiler {
             Node::If(ast_if) => self.compile_if(ast_if, ctx)?,
             Node::Match { expression, arms } => self.compile_match(*expression, arms, ctx)?,
             Node::Switch(arms) => self.compile_switch(arms, ctx)?,
+            Node::DefaultArg { .. } => return self.error(ErrorKind::UnexpectedDefaultArg),
+            Node::NamedArg { .. } => return self.error(ErrorKind::UnexpectedNamedArg),
+            Node::Spread(_) => return self.error(ErrorKind::UnexpectedSpread),
             Node::Ellipsis(_) => return self.error(ErrorKind::UnexpectedEllipsis),
             Node::Wildcard(_) => return self.error(ErrorKind::UnexpectedWildcard),
+            Node::MatchType { .. } => return self.error(ErrorKind::UnexpectedMatchType),
             Node::For(ast_for) => self.compile_for(ast_for, ctx)?,
             Node::While { condition, body } => {
                 self.compile_loop(Some((*condition, false)), *body, ctx)?
@@ -438,6 +514,7 @@ impl Compiler {
             Node::Break(expression) => match self.frame().current_loop() {
                 Some(loop_info) => {
                     let loop_result_register = loop_info.result_register;
+                    let try_depth = loop_info.try_depth;
 
                     match (loop_result_register, expression) {
                         (Some(loop_result_register), Some(expression)) => {
@@ -453,6 +530,7 @@ impl Compiler {
                         (None, None) => {}
                     }
 
+                    self.compile_try_exits(try_depth, ctx)?;
                     self.push_op(Jump, &[]);
                     self.push_loop_jump_placeholder()?;
 
@@ -464,10 +542,12 @@ impl Compiler {
                 Some(loop_info) => {
                     let loop_result_register = loop_info.result_register;
                     let loop_start_ip = loop_info.start_ip;
+                    let try_depth = loop_info.try_depth;
 
                     if let Some(result_register) = loop_result_register {
                         self.push_op(SetNull, &[result_register]);
                     }
+                    self.compile_try_exits(try_depth, ctx)?;
                     self.push_jump_back_op(JumpBack, &[], loop_start_ip);
 
                     CompileNodeOutput::none()
@@ -475,6 +555,7 @@ impl Compiler {
                 None => return self.error(ErrorKind::InvalidLoopKeyword("continue".into())),
             },
             Node::Return(None) => {
+                self.compile_pending_finally_blocks(ctx)?;
                 let result = self.assign_result_register(ctx)?;
                 match result.register {
                     Some(result_register) => {
@@ -494,27 +575,44 @@ impl Compiler {
                 let expression_result = self.compile_node(*expression, ctx.with_any_register())?;
                 let expression_register = expression_result.unwrap(self)?;
 
-                match ctx.result_register {
+                // The return value is preserved in a temporary register while finally blocks
+                // are run, in case the finally blocks reassign the returned value.
+                let preserved_register = if self.frame().has_pending_finally_blocks() {
+                    let register = self.push_register()?;
+                    self.push_op(Copy, &[register, expression_register]);
+                    self.compile_pending_finally_blocks(ctx)?;
+                    Some(register)
+                } else {
+                    None
+                };
+                let return_register = preserved_register.unwrap_or(expression_register);
+
+                let result = match ctx.result_register {
                     ResultRegister::Any => {
-                        self.push_op(Return, &[expression_register]);
+                        self.push_op(Return, &[return_register]);
                         expression_result
                     }
                     ResultRegister::Fixed(result) => {
-                        self.push_op(Copy, &[result, expression_register]);
+                        self.push_op(Copy, &[result, return_register]);
                         self.push_op(Return, &[result]);
-                        if expression_result.is_temporary {
-                            self.pop_register()?;
-                        }
                         CompileNodeOutput::with_assigned(result)
                     }
                     ResultRegister::None => {
-                        self.push_op(Return, &[expression_register]);
-                        if expression_result.is_temporary {
-                            self.pop_register()?;
-                        }
+                        self.push_op(Return, &[return_register]);
                         CompileNodeOutput::none()
                     }
+                };
+
+                if preserved_register.is_some() {
+                    self.pop_register()?;
                 }
+                if expression_result.is_temporary
+                    && !matches!(ctx.result_register, ResultRegister::Any)
+                {
+                    self.pop_register()?;
+                }
+
+                result
             }
             Node::Yield(expression) => {
                 let result = self.assign_result_register(ctx)?;
@@ -550,6 +648,34 @@ impl Compiler {
 
                 result
             }
+            Node::Propagate(expression) => {
+                let result = self.assign_result_register(ctx)?;
+
+                let expression_result = self.compile_node(*expression, ctx.with_any_register())?;
+                let expression_register = expression_result.unwrap(self)?;
+
+                // The unwrapped value needs a target register even if the result is unused,
+                // the expression's register could belong to a local value.
+                let propagate_register = match result.register {
+                    Some(result_register) => result_register,
+                    None => self.push_register()?,
+                };
+
+                self.push_op(Propagate, &[propagate_register, expression_register]);
+                let ok_offset = self.push_offset_placeholder();
+                self.compile_err_return(propagate_register, ctx)?;
+                self.update_offset_placeholder(ok_offset)?;
+
+                if result.register.is_none() {
+                    self.pop_register()?;
+                }
+
+                if expression_result.is_temporary {
+                    self.pop_register()?;
+                }
+
+                result
+            }
             Node::Try(try_expression) => self.compile_try_expression(try_expression, ctx)?,
             Node::Debug {
                 expression_string,
@@ -620,6 +746,22 @@ impl Compiler {
             }
         }
 
+        // assign default values to missing or null args
+        for (arg_index, arg) in args.iter().enumerate() {
+            let arg_node = ctx.node_with_span(*arg);
+            if let Node::DefaultArg { default, .. } = &arg_node.node {
+                self.push_span(arg_node, ctx.ast);
+
+                let arg_register = arg_index as u8 + 1;
+                self.push_op(Op::JumpIfNotNull, &[arg_register]);
+                let jump_placeholder = self.push_offset_placeholder();
+                self.compile_node(*default, ctx.with_fixed_register(arg_register))?;
+                self.update_offset_placeholder(jump_placeholder)?;
+
+                self.pop_span();
+            }
+        }
+
         let result_register = if allow_implicit_return {
             ResultRegister::Any
         } else {
@@ -666,7 +808,9 @@ impl Compiler {
 
         for arg in args.iter() {
             match &ast.node(*arg).node {
-                Node::Id(id_index) => result.push(Arg::Local(*id_index)),
+                Node::Id(id_index) | Node::DefaultArg { id: id_index, .. } => {
+                    result.push(Arg::Local(*id_index))
+                }
                 Node::Wildcard(_) => result.push(Arg::Placeholder),
                 Node::Tuple(nested) => {
                     result.push(Arg::Placeholder);
@@ -813,7 +957,11 @@ impl Compiler {
     ) -> Result<Option<u8>> {
         let result = match ctx.node(target) {
             Node::Id(constant_index) => Some(self.reserve_local_register(*constant_index)?),
-            Node::Meta { .. } | Node::Chain(_) | Node::Wildcard(_) => None,
+            Node::Meta { .. }
+            | Node::Chain(_)
+            | Node::Wildcard(_)
+            | Node::Tuple(_)
+            | Node::List(_) => None,
             unexpected => {
                 return self.error(ErrorKind::UnexpectedNode {
                     expected: "ID".into(),
@@ -873,6 +1021,28 @@ impl Compiler {
                 self.compile_meta_export(*meta_id, *name, value_register)?;
             }
             Node::Wildcard(_) => {}
+            Node::Tuple(targets) | Node::List(targets) => {
+                if value_result.is_temporary {
+                    self.compile_unpack_nested_targets(
+                        value_register,
+                        targets,
+                        export_assignment,
+                        ctx,
+                    )?;
+                } else {
+                    // The value is copied so that it's unaffected by assignments to the targets,
+                    // e.g. `(x, y) = x`
+                    let container_register = self.push_register()?;
+                    self.push_op(Copy, &[container_register, value_register]);
+                    self.compile_unpack_nested_targets(
+                        container_register,
+                        targets,
+                        export_assignment,
+                        ctx,
+                    )?;
+                    self.pop_register()?;
+                }
+            }
             unexpected => {
                 return self.error(ErrorKind::UnexpectedNode {
                     expected: "ID or Chain".into(),
@@ -1003,6 +1173,23 @@ impl Compiler {
                         self.push_op(IterNextQuiet, &[iter_register, 0, 0]);
                     }
                 }
+                Node::Tuple(nested_targets) | Node::List(nested_targets) => {
+                    let value_register = self.push_register()?;
+
+                    if rhs_is_temp_tuple {
+                        self.push_op(TempIndex, &[value_register, iter_register, i as u8]);
+                    } else {
+                        self.push_op(IterUnpack, &[value_register, iter_register]);
+                    }
+
+                    self.compile_unpack_nested_targets(value_register, nested_targets, false, ctx)?;
+
+                    if result.register.is_some() {
+                        self.push_op(SequencePush, &[value_register]);
+                    }
+
+                    self.pop_register()?; // value_register
+                }
                 unexpected => {
                     return self.error(ErrorKind::UnexpectedNode {
                         expected: "ID or Chain".into(),
@@ -1021,6 +1208,58 @@ impl Compiler {
         Ok(result)
     }
 
+    // Unpacks a container into nested assignment targets, e.g. `(a, (b, _)), c = x`
+    //
+    // The container's size is checked to make sure that it matches the number of targets.
+    fn compile_unpack_nested_targets(
+        &mut self,
+        container_register: u8,
+        targets: &[AstIndex],
+        export_assignment: bool,
+        ctx: CompileNodeContext,
+    ) -> Result<()> {
+        use Op::*;
+
+        if targets.len() > i8::MAX as usize {
+            return self.error(ErrorKind::TooManyAssignmentTargets(targets.len()));
+        }
+
+        self.push_op(CheckSizeEqual, &[container_register, targets.len() as u8]);
+
+        for (i, target) in targets.iter().enumerate() {
+            match ctx.node(*target) {
+                Node::Id(id_index) => {
+                    let target_register = self.assign_local_register(*id_index)?;
+                    self.push_op(TempIndex, &[target_register, container_register, i as u8]);
+
+                    if export_assignment || self.force_export_assignment() {
+                        self.compile_value_export(*id_index, target_register)?;
+                    }
+                }
+                Node::Tuple(nested_targets) | Node::List(nested_targets) => {
+                    let value_register = self.push_register()?;
+                    self.push_op(TempIndex, &[value_register, container_register, i as u8]);
+                    self.compile_unpack_nested_targets(
+                        value_register,
+                        nested_targets,
+                        export_assignment,
+                        ctx,
+                    )?;
+                    self.pop_register()?; // value_register
+                }
+                Node::Wildcard(_) => {}
+                unexpected => {
+                    return self.error(ErrorKind::UnexpectedNode {
+                        expected: "ID or nested assignment target".into(),
+                        unexpected: unexpected.clone(),
+                    })
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn compile_load_id(
         &mut self,
         id: ConstantIndex,
@@ -1402,6 +1641,10 @@ impl Compiler {
         self.push_op(TryStart, &[catch_register]);
         // The catch block start point is defined via an offset from the current byte
         let catch_offset = self.push_offset_placeholder();
+        self.frame_mut().try_stack.push(TryBlock {
+            finally_block: *finally_block,
+            in_catch_block: false,
+        });
 
         let try_result_register = match result.register {
             Some(result_register) if finally_block.is_none() => {
@@ -1427,9 +1670,13 @@ impl Compiler {
         // - if the catch block has been entered, then it needs to be de-registered in case there
         //   are errors thrown in the catch block.
         self.push_op(TryEnd, &[]);
+        if let Some(try_block) = self.frame_mut().try_stack.last_mut() {
+            try_block.in_catch_block = true;
+        }
 
         self.compile_node(*catch_block, ctx.with_register(try_result_register))?;
         self.pop_span();
+        self.frame_mut().try_stack.pop();
 
         if pop_catch_register {
             self.pop_register()?;
@@ -1448,6 +1695,58 @@ impl Compiler {
         }
     }
 
+    // Compiles the finally blocks that need to be run before returning from the current frame
+    fn compile_pending_finally_blocks(&mut self, ctx: CompileNodeContext) -> Result<()> {
+        // Returning from the frame clears the frame's catch points, so the try expressions only
+        // need to be exited when there are finally blocks to run.
+        if self.frame().has_pending_finally_blocks() {
+            self.compile_try_exits(0, ctx)
+        } else {
+            Ok(())
+        }
+    }
+
+    // Compiles the return of an `err` result that was encountered by a `?` or `?.` operation
+    //
+    // The result is preserved in a temporary register while finally blocks are run, in case the
+    // finally blocks reassign the result register's value.
+    fn compile_err_return(&mut self, err_register: u8, ctx: CompileNodeContext) -> Result<()> {
+        if self.frame().has_pending_finally_blocks() {
+            let register = self.push_register()?;
+            self.push_op(Op::Copy, &[register, err_register]);
+            self.compile_pending_finally_blocks(ctx)?;
+            self.push_op(Op::Return, &[register]);
+            self.pop_register()?;
+        } else {
+            self.push_op(Op::Return, &[err_register]);
+        }
+        Ok(())
+    }
+
+    // Compiles the exits from the try expressions that are being compiled above the given depth
+    //
+    // This is used when returning from the frame (with a depth of 0), and when breaking or
+    // continuing out of try expressions in a loop.
+    //
+    // The try expressions' catch points are cleared before their finally blocks are run,
+    // so that errors thrown in the finally blocks aren't caught by the expressions.
+    fn compile_try_exits(&mut self, try_depth: usize, ctx: CompileNodeContext) -> Result<()> {
+        let try_stack = self.frame().try_stack.clone();
+        for (i, try_block) in try_stack.iter().enumerate().skip(try_depth).rev() {
+            // Exiting from within a finally block shouldn't compile the finally block again
+            self.frame_mut().try_stack.truncate(i);
+            if !try_block.in_catch_block {
+                self.push_op(Op::TryEnd, &[]);
+            }
+            if let Some(finally_block) = try_block.finally_block {
+                self.compile_node(finally_block, ctx.with_register(ResultRegister::None))?;
+            }
+        }
+        self.frame_mut().try_stack = try_stack;
+
+        Ok(())
+    }
+
     fn compile_unary_op(
         &mut self,
         op: AstUnaryOp,
@@ -1463,6 +1762,7 @@ impl Compiler {
             let op_code = match op {
                 AstUnaryOp::Negate => Op::Negate,
                 AstUnaryOp::Not => Op::Not,
+                AstUnaryOp::BitwiseNot => Op::BitwiseNot,
             };
 
             self.push_op(op_code, &[result_register, value_register]);
@@ -1485,16 +1785,15 @@ impl Compiler {
         use AstBinaryOp::*;
 
         match op {
-            Add | Subtract | Multiply | Divide | Remainder => {
-                self.compile_arithmetic_op(op, lhs, rhs, ctx)
-            }
+            Add | Subtract | Multiply | Divide | Remainder | BitwiseAnd | BitwiseOr
+            | BitwiseXor | ShiftLeft | ShiftRight => self.compile_arithmetic_op(op, lhs, rhs, ctx),
             AddAssign | SubtractAssign | MultiplyAssign | DivideAssign | RemainderAssign => {
                 self.compile_compound_assignment_op(op, lhs, rhs, ctx)
             }
             Less | LessOrEqual | Greater | GreaterOrEqual | Equal | NotEqual => {
                 self.compile_comparison_op(op, lhs, rhs, ctx)
             }
-            And | Or => self.compile_logic_op(op, lhs, rhs, ctx),
+            And | Or | NullCoalesce => self.compile_logic_op(op, lhs, rhs, ctx),
             Pipe => self.compile_piped_call(lhs, rhs, ctx),
         }
     }
@@ -1514,6 +1813,11 @@ impl Compiler {
             Multiply => Op::Multiply,
             Divide => Op::Divide,
             Remainder => Op::Remainder,
+            BitwiseAnd => Op::BitwiseAnd,
+            BitwiseOr => Op::BitwiseOr,
+            BitwiseXor => Op::BitwiseXor,
+            ShiftLeft => Op::ShiftLeft,
+            ShiftRight => Op::ShiftRight,
             _ => {
                 return self.error(ErrorKind::InvalidBinaryOp {
                     kind: "arithmetic".into(),
@@ -1725,6 +2029,7 @@ impl Compiler {
         let jump_op = match op {
             AstBinaryOp::And => Op::JumpIfFalse,
             AstBinaryOp::Or => Op::JumpIfTrue,
+            AstBinaryOp::NullCoalesce => Op::JumpIfNotNull,
             _ => unreachable!(),
         };
 
@@ -1914,8 +2219,26 @@ impl Compiler {
             self.push_op(SequenceStart, &[]);
             self.push_var_u32(size_hint);
 
+            let has_spread_elements = elements
+                .iter()
+                .any(|element| matches!(ctx.node(*element), Node::Spread(_)));
+
             match elements {
                 [] => {}
+                _ if has_spread_elements => {
+                    // Spread elements are added to the sequence individually with SequenceExtend
+                    for element in elements.iter() {
+                        let (value_node, push_op) = match ctx.node(*element) {
+                            Node::Spread(value) => (*value, SequenceExtend),
+                            _ => (*element, SequencePush),
+                        };
+                        let value = self.compile_node(value_node, ctx.with_any_register())?;
+                        self.push_op_without_span(push_op, &[value.unwrap(self)?]);
+                        if value.is_temporary {
+                            self.pop_register()?;
+                        }
+                    }
+                }
                 [single_element] => {
                     let element = self.compile_node(*single_element, ctx.with_any_register())?;
                     self.push_op_without_span(SequencePush, &[element.unwrap(self)?]);
@@ -1953,7 +2276,11 @@ impl Compiler {
         } else {
             // Compile the element nodes for side-effects
             for element_node in elements.iter() {
-                self.compile_node(*element_node, ctx.compile_for_side_effects())?;
+                let element_node = match ctx.node(*element_node) {
+                    Node::Spread(value) => *value,
+                    _ => *element_node,
+                };
+                self.compile_node(element_node, ctx.compile_for_side_effects())?;
             }
         };
 
@@ -1981,6 +2308,26 @@ impl Compiler {
         if result.register.is_some() || export_entries {
             for (key, maybe_value_node) in entries.iter() {
                 let key_node = ctx.node(*key);
+
+                if let Node::Spread(source) = key_node {
+                    // Exported maps insert each entry individually, so spreading isn't supported
+                    if export_entries {
+                        return self.error(ErrorKind::UnexpectedSpread);
+                    }
+
+                    let source = self.compile_node(*source, ctx.with_any_register())?;
+                    if let Some(result_register) = result.register {
+                        self.push_op_without_span(
+                            Op::MapExtend,
+                            &[result_register, source.unwrap(self)?],
+                        );
+                    }
+                    if source.is_temporary {
+                        self.pop_register()?;
+                    }
+                    continue;
+                }
+
                 let value = match (key_node, maybe_value_node) {
                     // A value has been provided for the entry
                     (_, Some(value_node)) => {
@@ -2028,6 +2375,7 @@ impl Compiler {
     fn compile_function(
         &mut self,
         function: &Function,
+        deprecation: Option<ConstantIndex>,
         ctx: CompileNodeContext,
     ) -> Result<CompileNodeOutput> {
         use Op::*;
@@ -2065,6 +2413,7 @@ impl Compiler {
                 variadic: function.is_variadic,
                 generator: function.is_generator,
                 arg_is_unpacked_tuple,
+                deprecated: deprecation.is_some(),
             }
             .as_byte();
 
@@ -2074,6 +2423,22 @@ impl Compiler {
             );
             let function_size_ip = self.push_offset_placeholder();
 
+            // Keep track of the function's arg names, which are used when binding named args
+            let arg_names = function
+                .args
+                .iter()
+                .map(|arg| match ctx.node(*arg) {
+                    Node::Id(id) | Node::DefaultArg { id, .. } => Some(*id),
+                    _ => None,
+                })
+                .collect();
+            self.debug_info
+                .push_function_args(self.bytes.len() as u32, arg_names);
+            if let Some(message) = deprecation {
+                self.debug_info
+                    .push_function_deprecation(self.bytes.len() as u32, message);
+            }
+
             let local_count = match u8::try_from(function.local_count) {
                 Ok(x) => x,
                 Err(_) => {
@@ -2139,7 +2504,7 @@ impl Compiler {
     // The loop keeps track of the temporary values that are the result of each chain node.
     //
     // piped_arg_register - used when a value is being piped into the chain,
-    //   e.g. `f x >> foo.bar 123`, should be equivalent to `foo.bar 123, (f x)`
+    //   e.g. `f x -> foo.bar 123`, should be equivalent to `foo.bar 123, (f x)`
     //
     // rhs - used when assigning to the result of a chain,
     //   e.g. `foo.bar += 42`, or `foo[123] = bar`
@@ -2185,7 +2550,12 @@ impl Compiler {
 
         let mut chain_node = root_node.clone();
 
+        // Set to true when a `?` is encountered, making the following access optional
+        let mut optional_access = false;
+
         while next_node_index.is_some() {
+            let optional = std::mem::take(&mut optional_access);
+
             match &chain_node {
                 ChainNode::Root(root_node) => {
                     if !node_registers.is_empty() {
@@ -2209,7 +2579,16 @@ impl Compiler {
 
                     let node_register = self.push_register()?;
                     node_registers.push(node_register);
-                    self.compile_access_id(node_register, parent_register, *id);
+                    if optional {
+                        self.compile_access_optional(
+                            node_register,
+                            parent_register,
+                            &chain_node,
+                            ctx,
+                        )?;
+                    } else {
+                        self.compile_access_id(node_register, parent_register, *id);
+                    }
                 }
                 ChainNode::Str(ref access_string) => {
                     // Access by string
@@ -2225,12 +2604,30 @@ impl Compiler {
 
                     let node_register = self.push_register()?;
                     node_registers.push(node_register);
-                    self.compile_access_string(
-                        node_register,
-                        parent_register,
-                        &access_string.contents,
-                        ctx,
-                    )?;
+                    if optional {
+                        self.compile_access_optional(
+                            node_register,
+                            parent_register,
+                            &chain_node,
+                            ctx,
+                        )?;
+                    } else {
+                        self.compile_access_string(
+                            node_register,
+                            parent_register,
+                            &access_string.contents,
+                            ctx,
+                        )?;
+                    }
+                }
+                ChainNode::Optional => {
+                    // Optional access
+                    // e.g. x?.foo
+                    //    - x = Root
+                    //    - ? = Optional, the following access will be optional
+                    //    - foo = Id
+
+                    optional_access = true;
                 }
                 ChainNode::Index(index_node) => {
                     // Indexing into a value
@@ -2313,14 +2710,24 @@ impl Compiler {
         let simple_assignment = rhs.is_some() && rhs_op.is_none();
         let access_assignment = rhs.is_some() && rhs_op.is_some();
 
-        let string_key = if let ChainNode::Str(access_string) = &last_node {
-            let key_register = self.push_register()?;
-            self.compile_string(
-                &access_string.contents,
-                ctx.with_fixed_register(key_register),
-            )?
-        } else {
-            CompileNodeOutput::none()
+        if optional_access {
+            if rhs.is_some() {
+                return self.error(ErrorKind::AssigningToAnOptionalAccess);
+            }
+
+            self.compile_access_optional(access_register, parent_register, &last_node, ctx)?;
+            node_registers.push(access_register);
+        }
+
+        let string_key = match &last_node {
+            ChainNode::Str(access_string) if !optional_access => {
+                let key_register = self.push_register()?;
+                self.compile_string(
+                    &access_string.contents,
+                    ctx.with_fixed_register(key_register),
+                )?
+            }
+            _ => CompileNodeOutput::none(),
         };
 
         let index = if let ChainNode::Index(index_node) = last_node {
@@ -2334,6 +2741,7 @@ impl Compiler {
         // If rhs_op is None, then Yes if rhs is also None (simple access)
         // If rhs is Some and rhs_op is None, then it's a simple assignment
         match &last_node {
+            _ if optional_access => {}
             ChainNode::Id(id) if !simple_assignment => {
                 self.compile_access_id(access_register, parent_register, *id);
                 node_registers.push(access_register);
@@ -2552,6 +2960,39 @@ impl Compiler {
         self.push_var_u32(key.into());
     }
 
+    // Compiles an optional `?.` access, with the key taken from an Id or Str chain node
+    fn compile_access_optional(
+        &mut self,
+        result_register: u8,
+        value_register: u8,
+        key_node: &ChainNode,
+        ctx: CompileNodeContext,
+    ) -> Result<()> {
+        let key_register = self.push_register()?;
+
+        match key_node {
+            ChainNode::Id(id) => self.compile_load_string_constant(key_register, *id),
+            ChainNode::Str(access_string) => {
+                self.compile_string(
+                    &access_string.contents,
+                    ctx.with_fixed_register(key_register),
+                )?;
+            }
+            _ => return self.error(ErrorKind::MissingNextChainNode),
+        }
+
+        self.push_op(
+            Op::AccessOptional,
+            &[result_register, value_register, key_register],
+        );
+        let ok_offset = self.push_offset_placeholder();
+        self.compile_err_return(result_register, ctx)?;
+        self.update_offset_placeholder(ok_offset)?;
+
+        self.pop_register()?;
+        Ok(())
+    }
+
     fn compile_access_string(
         &mut self,
         result_register: u8,
@@ -2569,7 +3010,7 @@ impl Compiler {
         Ok(())
     }
 
-    // Compiles a node like `f x >> g`, compiling the lhs as the last arg for a call on the rhs
+    // Compiles a node like `f x -> g`, compiling the lhs as the last arg for a call on the rhs
     fn compile_piped_call(
         &mut self,
         lhs: AstIndex,
@@ -2655,7 +3096,12 @@ impl Compiler {
         let result = self.assign_result_register(ctx)?;
         let stack_count = self.stack_count();
 
-        let mut arg_count = args.len();
+        // The parser ensures that named args follow the positional args
+        let (named_args, positional_args): (Vec<AstIndex>, Vec<AstIndex>) = args
+            .iter()
+            .partition(|arg| matches!(ctx.node(**arg), Node::NamedArg { .. }));
+
+        let mut arg_count = positional_args.len();
 
         // The frame base is used for the instance register
         let frame_base = self.push_register()?;
@@ -2663,15 +3109,70 @@ impl Compiler {
             self.push_op(Copy, &[frame_base, instance]);
         }
 
-        for arg in args.iter() {
-            let arg_register = self.push_register()?;
-            self.compile_node(*arg, ctx.with_fixed_register(arg_register))?;
+        let has_spread_args = positional_args
+            .iter()
+            .any(|arg| matches!(ctx.node(*arg), Node::Spread(_)));
+
+        if has_spread_args {
+            // The positional args are collected into a tuple, which is unpacked by CallSpread
+            arg_count = 1;
+            let args_register = self.push_register()?;
+            self.push_op(SequenceStart, &[]);
+            self.push_var_u32(positional_args.len() as u32 + piped_arg.is_some() as u32);
+
+            for arg in positional_args.iter() {
+                let (value_node, push_op) = match ctx.node(*arg) {
+                    Node::Spread(value) => (*value, SequenceExtend),
+                    _ => (*arg, SequencePush),
+                };
+                let value = self.compile_node(value_node, ctx.with_any_register())?;
+                self.push_op_without_span(push_op, &[value.unwrap(self)?]);
+                if value.is_temporary {
+                    self.pop_register()?;
+                }
+            }
+
+            if let Some(piped_arg) = piped_arg {
+                self.push_op(SequencePush, &[piped_arg]);
+            }
+
+            self.push_op(SequenceToTuple, &[args_register]);
+        } else {
+            for arg in positional_args.iter() {
+                let arg_register = self.push_register()?;
+                self.compile_node(*arg, ctx.with_fixed_register(arg_register))?;
+            }
+
+            if let Some(piped_arg) = piped_arg {
+                arg_count += 1;
+                let arg_register = self.push_register()?;
+                self.push_op(Copy, &[arg_register, piped_arg]);
+            }
         }
 
-        if let Some(piped_arg) = piped_arg {
+        // Named args are placed in a map following the positional args
+        if !named_args.is_empty() {
             arg_count += 1;
-            let arg_register = self.push_register()?;
-            self.push_op(Copy, &[arg_register, piped_arg]);
+            let map_register = self.push_register()?;
+            self.push_op(MakeMap, &[map_register]);
+            self.push_var_u32(named_args.len() as u32);
+
+            for arg in named_args.iter() {
+                let Node::NamedArg { id, value } = ctx.node(*arg) else {
+                    unreachable!();
+                };
+                let key_register = self.push_register()?;
+                self.compile_load_string_constant(key_register, *id);
+                let value_register = self.compile_node(*value, ctx.with_any_register())?;
+                self.push_op_without_span(
+                    MapInsert,
+                    &[map_register, key_register, value_register.unwrap(self)?],
+                );
+                if value_register.is_temporary {
+                    self.pop_register()?;
+                }
+                self.pop_register()?; // key_register
+            }
         }
 
         let call_result_register = if let Some(result_register) = result.register {
@@ -2683,8 +3184,16 @@ impl Compiler {
             frame_base
         };
 
+        let call_op = if has_spread_args {
+            CallSpread
+        } else if named_args.is_empty() {
+            Call
+        } else {
+            CallNamed
+        };
+
         self.push_op(
-            Call,
+            call_op,
             &[
                 call_result_register,
                 function_register,
@@ -3011,7 +3520,7 @@ impl Compiler {
 
     fn compile_match_arm_patterns(
         &mut self,
-        params: MatchArmParameters,
+        mut params: MatchArmParameters,
         match_is_container: bool,
         arm_patterns: &[AstIndex],
         ctx: CompileNodeContext,
@@ -3038,49 +3547,77 @@ impl Compiler {
                 | Node::Int(_)
                 | Node::Float(_)
                 | Node::Str(_)
-                | Node::Chain(_) => {
+                | Node::Chain(_)
+                | Node::Range { .. }
+                | Node::RangeFrom { .. }
+                | Node::RangeTo { .. } => {
                     let pattern_register = self.push_register()?;
                     self.compile_node(*pattern, ctx.with_fixed_register(pattern_register))?;
                     let comparison = self.push_register()?;
 
+                    let is_range_pattern = matches!(
+                        pattern_node.node,
+                        Node::Range { .. } | Node::RangeFrom { .. } | Node::RangeTo { .. }
+                    );
+                    let compare = |compiler: &mut Self, value_register| {
+                        if is_range_pattern {
+                            compiler.push_op(
+                                MatchRange,
+                                &[comparison, value_register, pattern_register],
+                            );
+                        } else {
+                            compiler
+                                .push_op(Equal, &[comparison, pattern_register, value_register]);
+                        }
+                    };
+
                     if match_is_container {
                         let element = self.push_register()?;
                         self.push_op(
                             TempIndex,
                             &[element, params.match_register, pattern_index as u8],
                         );
-                        self.push_op(Equal, &[comparison, pattern_register, element]);
+                        compare(self, element);
                         self.pop_register()?; // element
                     } else {
-                        self.push_op(
-                            Equal,
-                            &[comparison, pattern_register, params.match_register],
-                        );
+                        compare(self, params.match_register);
                     }
 
-                    if params.is_last_alternative {
-                        // If there's no match on the last alternative,
-                        // then jump to the end of the arm
-                        self.push_op(JumpIfFalse, &[comparison]);
-                        params.jumps.arm_end.push(self.push_offset_placeholder());
-                    } else if params.has_last_pattern && is_last_pattern {
-                        // If there's a match with remaining alternative matches,
-                        // then jump to the end of the alternatives
-                        self.push_op(JumpIfTrue, &[comparison]);
-                        params.jumps.match_end.push(self.push_offset_placeholder());
-                    } else {
-                        // If there's no match but there remaining alternative matches,
-                        // then jump to the next alternative
-                        self.push_op(JumpIfFalse, &[comparison]);
-                        params
-                            .jumps
-                            .alternative_end
-                            .push(self.push_offset_placeholder());
-                    }
+                    self.compile_match_comparison_jump(&mut params, comparison, is_last_pattern);
 
                     self.pop_register()?; // comparison_register
                     self.pop_register()?; // pattern_register
                 }
+                Node::MatchType { pattern, type_name } => {
+                    let value_register = if match_is_container {
+                        let element = self.push_register()?;
+                        self.push_op(
+                            TempIndex,
+                            &[element, params.match_register, pattern_index as u8],
+                        );
+                        element
+                    } else {
+                        params.match_register
+                    };
+
+                    // The value is assigned before the type is checked,
+                    // so that it's available if the check jumps to the end of the alternatives.
+                    if let Node::Id(id) = ctx.node(*pattern) {
+                        let id_register = self.assign_local_register(*id)?;
+                        self.push_op(Copy, &[id_register, value_register]);
+                    }
+
+                    let comparison = self.push_register()?;
+                    self.push_op(MatchType, &[comparison, value_register]);
+                    self.push_var_u32((*type_name).into());
+
+                    self.compile_match_comparison_jump(&mut params, comparison, is_last_pattern);
+
+                    self.pop_register()?; // comparison
+                    if match_is_container {
+                        self.pop_register()?; // element
+                    }
+                }
                 Node::Id(id) => {
                     let id_register = self.assign_local_register(*id)?;
                     if match_is_container {
@@ -3167,6 +3704,36 @@ impl Compiler {
         Ok(())
     }
 
+    // Jumps to the appropriate location following a comparison in a match arm's pattern
+    fn compile_match_comparison_jump(
+        &mut self,
+        params: &mut MatchArmParameters,
+        comparison: u8,
+        is_last_pattern: bool,
+    ) {
+        use Op::*;
+
+        if params.is_last_alternative {
+            // If there's no match on the last alternative,
+            // then jump to the end of the arm
+            self.push_op(JumpIfFalse, &[comparison]);
+            params.jumps.arm_end.push(self.push_offset_placeholder());
+        } else if params.has_last_pattern && is_last_pattern {
+            // If there's a match with remaining alternative matches,
+            // then jump to the end of the alternatives
+            self.push_op(JumpIfTrue, &[comparison]);
+            params.jumps.match_end.push(self.push_offset_placeholder());
+        } else {
+            // If there's no match but there remaining alternative matches,
+            // then jump to the next alternative
+            self.push_op(JumpIfFalse, &[comparison]);
+            params
+                .jumps
+                .alternative_end
+                .push(self.push_offset_placeholder());
+        }
+    }
+
     fn compile_nested_match_arm_patterns(
         &mut self,
         params: MatchArmParameters,
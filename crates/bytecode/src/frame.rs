@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use koto_parser::{ConstantIndex, Span};
+use koto_parser::{AstIndex, ConstantIndex, Span};
 use thiserror::Error;
 
 /// The different error types that can be thrown while compiling a [Frame]
@@ -45,6 +45,17 @@ pub(crate) struct Loop {
     pub start_ip: usize,
     // Placeholders for jumps to the end of the loop, updated when the loop compilation is complete
     pub jump_placeholders: Vec<usize>,
+    // The number of try expressions that were being compiled when the loop started,
+    // used to exit from try expressions in the loop when breaking or continuing
+    pub try_depth: usize,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct TryBlock {
+    // The try expression's finally block, which needs to be run before returning
+    pub finally_block: Option<AstIndex>,
+    // True while the catch block is being compiled, when the catch point has already been cleared
+    pub in_catch_block: bool,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -80,6 +91,10 @@ pub(crate) struct Frame {
     // This is a coarse check, e.g. we currently don't check if the last expression
     // returns in all branches, but it'll do for now as an optimization for simple cases.
     pub last_node_was_return: bool,
+    // The try expressions that are currently being compiled in the frame,
+    // used to run finally blocks when returning early from a try or catch block, or when
+    // breaking or continuing out of a try or catch block in a loop.
+    pub try_stack: Vec<TryBlock>,
 }
 
 impl Frame {
@@ -311,6 +326,7 @@ impl Frame {
             start_ip: loop_start_ip,
             result_register,
             jump_placeholders: Vec::new(),
+            try_depth: self.try_stack.len(),
         });
     }
 
@@ -331,4 +347,11 @@ impl Frame {
     pub fn pop_loop(&mut self) -> Result<Loop, FrameError> {
         self.loop_stack.pop().ok_or(FrameError::EmptyLoopInfoStack)
     }
+
+    // True if finally blocks need to be run when returning from the frame
+    pub fn has_pending_finally_blocks(&self) -> bool {
+        self.try_stack
+            .iter()
+            .any(|try_block| try_block.finally_block.is_some())
+    }
 }
@@ -72,6 +72,9 @@ pub enum Instruction {
         start: u8,
         count: u8,
     },
+    SequenceExtend {
+        iterable: u8,
+    },
     SequenceToList {
         register: u8,
     },
@@ -103,6 +106,10 @@ pub enum Instruction {
     RangeFull {
         register: u8,
     },
+    RangeStep {
+        register: u8,
+        step: u8,
+    },
     MakeIterator {
         register: u8,
         iterable: u8,
@@ -114,6 +121,7 @@ pub enum Instruction {
         variadic: bool,
         generator: bool,
         arg_is_unpacked_tuple: bool,
+        deprecated: bool,
         size: u16,
     },
     Capture {
@@ -218,12 +226,28 @@ pub enum Instruction {
         register: u8,
         offset: u16,
     },
+    JumpIfNotNull {
+        register: u8,
+        offset: u16,
+    },
     Call {
         result: u8,
         function: u8,
         frame_base: u8,
         arg_count: u8,
     },
+    CallNamed {
+        result: u8,
+        function: u8,
+        frame_base: u8,
+        arg_count: u8,
+    },
+    CallSpread {
+        result: u8,
+        function: u8,
+        frame_base: u8,
+        arg_count: u8,
+    },
     Return {
         register: u8,
     },
@@ -233,6 +257,11 @@ pub enum Instruction {
     Throw {
         register: u8,
     },
+    Propagate {
+        register: u8,
+        value: u8,
+        offset: u16,
+    },
     Size {
         register: u8,
         value: u8,
@@ -273,6 +302,10 @@ pub enum Instruction {
         key: u8,
         value: u8,
     },
+    MapExtend {
+        register: u8,
+        source: u8,
+    },
     MetaInsert {
         register: u8,
         value: u8,
@@ -303,6 +336,41 @@ pub enum Instruction {
         value: u8,
         key: u8,
     },
+    AccessOptional {
+        register: u8,
+        value: u8,
+        key: u8,
+        offset: u16,
+    },
+    BitwiseNot {
+        register: u8,
+        value: u8,
+    },
+    BitwiseAnd {
+        register: u8,
+        lhs: u8,
+        rhs: u8,
+    },
+    BitwiseOr {
+        register: u8,
+        lhs: u8,
+        rhs: u8,
+    },
+    BitwiseXor {
+        register: u8,
+        lhs: u8,
+        rhs: u8,
+    },
+    ShiftLeft {
+        register: u8,
+        lhs: u8,
+        rhs: u8,
+    },
+    ShiftRight {
+        register: u8,
+        lhs: u8,
+        rhs: u8,
+    },
     TryStart {
         arg_register: u8,
         catch_offset: u16,
@@ -320,6 +388,16 @@ pub enum Instruction {
         register: u8,
         size: usize,
     },
+    MatchRange {
+        register: u8,
+        value: u8,
+        range: u8,
+    },
+    MatchType {
+        register: u8,
+        value: u8,
+        type_name: ConstantIndex,
+    },
     StringStart {
         size_hint: u32,
     },
@@ -340,6 +418,8 @@ pub struct FunctionFlags {
     pub generator: bool,
     /// True if the function has a single argument which is an unpacked tuple
     pub arg_is_unpacked_tuple: bool,
+    /// True if the function has been annotated with `@deprecated`
+    pub deprecated: bool,
 }
 
 impl FunctionFlags {
@@ -349,6 +429,8 @@ impl FunctionFlags {
     pub const GENERATOR: u8 = 1 << 1;
     /// Corresponding to [FunctionFlags::arg_is_unpacked_tuple]
     pub const ARG_IS_UNPACKED_TUPLE: u8 = 1 << 2;
+    /// Corresponding to [FunctionFlags::deprecated]
+    pub const DEPRECATED: u8 = 1 << 3;
 
     /// Initializes a flags struct from a byte
     pub fn from_byte(byte: u8) -> Self {
@@ -356,6 +438,7 @@ impl FunctionFlags {
             variadic: byte & Self::VARIADIC != 0,
             generator: byte & Self::GENERATOR != 0,
             arg_is_unpacked_tuple: byte & Self::ARG_IS_UNPACKED_TUPLE != 0,
+            deprecated: byte & Self::DEPRECATED != 0,
         }
     }
 
@@ -371,6 +454,9 @@ impl FunctionFlags {
         if self.arg_is_unpacked_tuple {
             result |= Self::ARG_IS_UNPACKED_TUPLE;
         }
+        if self.deprecated {
+            result |= Self::DEPRECATED;
+        }
         result
     }
 }
@@ -496,6 +582,7 @@ impl fmt::Debug for Instruction {
             SequencePushN { start, count } => {
                 write!(f, "SequencePushN\tstart: {start}\tcount: {count}",)
             }
+            SequenceExtend { iterable } => write!(f, "SequenceExtend\titerable: {iterable}"),
             SequenceToList { register } => write!(f, "SequenceToList\tresult: {register}"),
             SequenceToTuple { register } => write!(f, "SequenceToTuple\tresult: {register}"),
             Range {
@@ -519,6 +606,9 @@ impl fmt::Debug for Instruction {
                 write!(f, "RangeFrom\tresult: {register}\tstart: {start}")
             }
             RangeFull { register } => write!(f, "RangeFull\tresult: {register}"),
+            RangeStep { register, step } => {
+                write!(f, "RangeStep\tresult: {register}\tstep: {step}")
+            }
             MakeIterator { register, iterable } => {
                 write!(f, "MakeIterator\tresult: {register}\titerable: {iterable}",)
             }
@@ -529,13 +619,15 @@ impl fmt::Debug for Instruction {
                 variadic,
                 generator,
                 arg_is_unpacked_tuple,
+                deprecated,
                 size,
             } => write!(
                 f,
                 "Function\tresult: {register}\targs: {arg_count}\
                  \t\tcaptures: {capture_count}
                  \t\t\tsize: {size} \tgenerator: {generator}
-                 \t\t\tvariadic: {variadic}\targ_is_unpacked_tuple: {arg_is_unpacked_tuple}",
+                 \t\t\tvariadic: {variadic}\targ_is_unpacked_tuple: {arg_is_unpacked_tuple}
+                 \t\t\tdeprecated: {deprecated}",
             ),
             Capture {
                 function,
@@ -609,6 +701,9 @@ impl fmt::Debug for Instruction {
             JumpIfFalse { register, offset } => {
                 write!(f, "JumpIfFalse\tresult: {register}\toffset: {offset}")
             }
+            JumpIfNotNull { register, offset } => {
+                write!(f, "JumpIfNotNull\tvalue: {register}\toffset: {offset}")
+            }
             Call {
                 result,
                 function,
@@ -619,9 +714,37 @@ impl fmt::Debug for Instruction {
                 "Call\t\tresult: {result}\tfunction: {function}\t\
                  frame base: {frame_base}\targs: {arg_count}",
             ),
+            CallNamed {
+                result,
+                function,
+                frame_base,
+                arg_count,
+            } => write!(
+                f,
+                "CallNamed\tresult: {result}\tfunction: {function}\t\
+                 frame base: {frame_base}\targs: {arg_count}",
+            ),
+            CallSpread {
+                result,
+                function,
+                frame_base,
+                arg_count,
+            } => write!(
+                f,
+                "CallSpread\tresult: {result}\tfunction: {function}\t\
+                 frame base: {frame_base}\targs: {arg_count}",
+            ),
             Return { register } => write!(f, "Return\t\tresult: {register}"),
             Yield { register } => write!(f, "Yield\t\tresult: {register}"),
             Throw { register } => write!(f, "Throw\t\tresult: {register}"),
+            Propagate {
+                register,
+                value,
+                offset,
+            } => write!(
+                f,
+                "Propagate\tresult: {register}\tvalue: {value}\toffset: {offset}"
+            ),
             Size { register, value } => write!(f, "Size\t\tresult: {register}\tvalue: {value}"),
             IterNext {
                 result,
@@ -682,6 +805,9 @@ impl fmt::Debug for Instruction {
                 f,
                 "MapInsert\tmap: {register}\t\tvalue: {value}\tkey: {key}"
             ),
+            MapExtend { register, source } => {
+                write!(f, "MapExtend\tmap: {register}\t\tsource: {source}")
+            }
             MetaInsert {
                 register,
                 value,
@@ -720,6 +846,42 @@ impl fmt::Debug for Instruction {
                 f,
                 "AccessString\tresult: {register}\tsource: {value}\tkey: {key}"
             ),
+            AccessOptional {
+                register,
+                value,
+                key,
+                offset,
+            } => write!(
+                f,
+                "AccessOptional\tresult: {register}\tsource: {value}\tkey: {key}\toffset: {offset}"
+            ),
+            BitwiseNot { register, value } => {
+                write!(f, "BitwiseNot\tresult: {register}\tsource: {value}")
+            }
+            BitwiseAnd { register, lhs, rhs } => {
+                write!(
+                    f,
+                    "BitwiseAnd\tresult: {register}\tlhs: {lhs}\t\trhs: {rhs}"
+                )
+            }
+            BitwiseOr { register, lhs, rhs } => {
+                write!(f, "BitwiseOr\tresult: {register}\tlhs: {lhs}\t\trhs: {rhs}")
+            }
+            BitwiseXor { register, lhs, rhs } => {
+                write!(
+                    f,
+                    "BitwiseXor\tresult: {register}\tlhs: {lhs}\t\trhs: {rhs}"
+                )
+            }
+            ShiftLeft { register, lhs, rhs } => {
+                write!(f, "ShiftLeft\tresult: {register}\tlhs: {lhs}\t\trhs: {rhs}")
+            }
+            ShiftRight { register, lhs, rhs } => {
+                write!(
+                    f,
+                    "ShiftRight\tresult: {register}\tlhs: {lhs}\t\trhs: {rhs}"
+                )
+            }
             TryStart {
                 arg_register,
                 catch_offset,
@@ -737,6 +899,22 @@ impl fmt::Debug for Instruction {
             CheckSizeMin { register, size } => {
                 write!(f, "CheckSizeMin\tvalue: {register}\tsize: {size}")
             }
+            MatchRange {
+                register,
+                value,
+                range,
+            } => write!(
+                f,
+                "MatchRange\tresult: {register}\tvalue: {value}\trange: {range}"
+            ),
+            MatchType {
+                register,
+                value,
+                type_name,
+            } => write!(
+                f,
+                "MatchType\tresult: {register}\tvalue: {value}\ttype: {type_name}"
+            ),
             StringStart { size_hint } => {
                 write!(f, "StringStart\tsize hint: {size_hint}")
             }
@@ -166,6 +166,9 @@ impl Iterator for InstructionReader {
                 start: get_u8!(),
                 count: get_u8!(),
             }),
+            Op::SequenceExtend => Some(SequenceExtend {
+                iterable: get_u8!(),
+            }),
             Op::SequenceToList => Some(SequenceToList {
                 register: get_u8!(),
             }),
@@ -197,6 +200,10 @@ impl Iterator for InstructionReader {
             Op::RangeFull => Some(RangeFull {
                 register: get_u8!(),
             }),
+            Op::RangeStep => Some(RangeStep {
+                register: get_u8!(),
+                step: get_u8!(),
+            }),
             Op::MakeIterator => Some(MakeIterator {
                 register: get_u8!(),
                 iterable: get_u8!(),
@@ -215,6 +222,7 @@ impl Iterator for InstructionReader {
                     variadic: flags.variadic,
                     generator: flags.generator,
                     arg_is_unpacked_tuple: flags.arg_is_unpacked_tuple,
+                    deprecated: flags.deprecated,
                     size,
                 })
             }
@@ -316,12 +324,28 @@ impl Iterator for InstructionReader {
                 register: get_u8!(),
                 offset: get_u16!(),
             }),
+            Op::JumpIfNotNull => Some(JumpIfNotNull {
+                register: get_u8!(),
+                offset: get_u16!(),
+            }),
             Op::Call => Some(Call {
                 result: get_u8!(),
                 function: get_u8!(),
                 frame_base: get_u8!(),
                 arg_count: get_u8!(),
             }),
+            Op::CallNamed => Some(CallNamed {
+                result: get_u8!(),
+                function: get_u8!(),
+                frame_base: get_u8!(),
+                arg_count: get_u8!(),
+            }),
+            Op::CallSpread => Some(CallSpread {
+                result: get_u8!(),
+                function: get_u8!(),
+                frame_base: get_u8!(),
+                arg_count: get_u8!(),
+            }),
             Op::Return => Some(Return {
                 register: get_u8!(),
             }),
@@ -331,6 +355,11 @@ impl Iterator for InstructionReader {
             Op::Throw => Some(Throw {
                 register: get_u8!(),
             }),
+            Op::Propagate => Some(Propagate {
+                register: get_u8!(),
+                value: get_u8!(),
+                offset: get_u16!(),
+            }),
             Op::Size => Some(Size {
                 register: get_u8!(),
                 value: get_u8!(),
@@ -389,6 +418,10 @@ impl Iterator for InstructionReader {
                 key: get_u8!(),
                 value: get_u8!(),
             }),
+            Op::MapExtend => Some(MapExtend {
+                register: get_u8!(),
+                source: get_u8!(),
+            }),
             Op::MetaInsert => {
                 let register = get_u8!();
                 let meta_id = get_u8!();
@@ -464,6 +497,41 @@ impl Iterator for InstructionReader {
                 value: get_u8!(),
                 key: get_u8!(),
             }),
+            Op::AccessOptional => Some(AccessOptional {
+                register: get_u8!(),
+                value: get_u8!(),
+                key: get_u8!(),
+                offset: get_u16!(),
+            }),
+            Op::BitwiseNot => Some(BitwiseNot {
+                register: get_u8!(),
+                value: get_u8!(),
+            }),
+            Op::BitwiseAnd => Some(BitwiseAnd {
+                register: get_u8!(),
+                lhs: get_u8!(),
+                rhs: get_u8!(),
+            }),
+            Op::BitwiseOr => Some(BitwiseOr {
+                register: get_u8!(),
+                lhs: get_u8!(),
+                rhs: get_u8!(),
+            }),
+            Op::BitwiseXor => Some(BitwiseXor {
+                register: get_u8!(),
+                lhs: get_u8!(),
+                rhs: get_u8!(),
+            }),
+            Op::ShiftLeft => Some(ShiftLeft {
+                register: get_u8!(),
+                lhs: get_u8!(),
+                rhs: get_u8!(),
+            }),
+            Op::ShiftRight => Some(ShiftRight {
+                register: get_u8!(),
+                lhs: get_u8!(),
+                rhs: get_u8!(),
+            }),
             Op::TryStart => Some(TryStart {
                 arg_register: get_u8!(),
                 catch_offset: get_u16!(),
@@ -481,6 +549,16 @@ impl Iterator for InstructionReader {
                 register: get_u8!(),
                 size: get_u8!() as usize,
             }),
+            Op::MatchRange => Some(MatchRange {
+                register: get_u8!(),
+                value: get_u8!(),
+                range: get_u8!(),
+            }),
+            Op::MatchType => Some(MatchType {
+                register: get_u8!(),
+                value: get_u8!(),
+                type_name: get_var_u32!().into(),
+            }),
             Op::StringStart => Some(StringStart {
                 size_hint: get_var_u32!(),
             }),
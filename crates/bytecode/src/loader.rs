@@ -1,7 +1,7 @@
 use crate::{Chunk, Compiler, CompilerError, CompilerSettings};
 use dunce::canonicalize;
 use koto_memory::Ptr;
-use koto_parser::{format_source_excerpt, Parser, Span};
+use koto_parser::{format_source_excerpt, Parser, ParserSettings, Span};
 use rustc_hash::FxHasher;
 use std::{
     collections::HashMap,
@@ -129,9 +129,22 @@ impl From<LoaderErrorKind> for LoaderError {
 #[derive(Clone, Default)]
 pub struct Loader {
     chunks: HashMap<PathBuf, Ptr<Chunk>, BuildHasherDefault<FxHasher>>,
+    cfg_flags: Vec<String>,
 }
 
 impl Loader {
+    /// Sets the flags that enable `@cfg` blocks in imported modules
+    ///
+    /// Modules that were compiled with different flags are removed from the cache.
+    ///
+    /// See [CompilerSettings::cfg_flags].
+    pub fn set_cfg_flags(&mut self, flags: Vec<String>) {
+        if flags != self.cfg_flags {
+            self.clear_cache();
+            self.cfg_flags = flags;
+        }
+    }
+
     /// Compiles a script
     pub fn compile_script(
         &mut self,
@@ -139,7 +152,16 @@ impl Loader {
         script_path: Option<&Path>,
         settings: CompilerSettings,
     ) -> Result<Ptr<Chunk>, LoaderError> {
-        match Parser::parse(script) {
+        let load_embedded_file = |path: &str| {
+            self.load_embedded_file(path, script_path)
+                .map_err(|error| error.error.to_string())
+        };
+        let parser_settings = ParserSettings {
+            strict: settings.strict,
+            embed_loader: Some(&load_embedded_file),
+        };
+
+        match Parser::parse_with_settings(script, parser_settings) {
             Ok(ast) => {
                 let (bytes, mut debug_info) = match Compiler::compile(&ast, settings) {
                     Ok((bytes, debug_info)) => (bytes, debug_info),
@@ -148,7 +170,11 @@ impl Loader {
 
                 debug_info.source = script.to_string();
 
-                Ok(Chunk::new(bytes, ast.consume_constants(), script_path, debug_info).into())
+                let warnings = ast.warnings().to_vec();
+                let mut chunk = Chunk::new(bytes, ast.consume_constants(), script_path, debug_info);
+                chunk.warnings = warnings;
+
+                Ok(chunk.into())
             }
             Err(e) => Err(LoaderError::from_parser_error(e, script, script_path)),
         }
@@ -160,46 +186,44 @@ impl Loader {
         name: &str,
         load_from_path: Option<&Path>,
     ) -> Result<CompileModuleResult, LoaderError> {
-        // Get either the directory of the provided path, or the current working directory
-        let search_folder = match &load_from_path {
-            Some(path) => match canonicalize(path)? {
-                canonicalized if canonicalized.is_file() => match canonicalized.parent() {
-                    Some(parent_dir) => parent_dir.to_path_buf(),
-                    None => return Err(LoaderErrorKind::FailedToGetPathParent(path.into()).into()),
-                },
-                canonicalized => canonicalized,
-            },
-            None => std::env::current_dir()?,
-        };
+        let module_path = self.find_module(name, load_from_path)?;
 
-        let mut load_module_from_path = |module_path: PathBuf| {
-            let module_path = module_path.canonicalize()?;
+        match self.chunks.get(&module_path) {
+            Some(chunk) => Ok(CompileModuleResult {
+                chunk: chunk.clone(),
+                path: module_path,
+                loaded_from_cache: true,
+            }),
+            None => {
+                let script = std::fs::read_to_string(&module_path)?;
+
+                let settings = CompilerSettings {
+                    cfg_flags: self.cfg_flags.clone(),
+                    ..Default::default()
+                };
+                let chunk = self.compile_script(&script, Some(&module_path), settings)?;
 
-            match self.chunks.get(&module_path) {
-                Some(chunk) => Ok(CompileModuleResult {
-                    chunk: chunk.clone(),
+                self.chunks.insert(module_path.clone(), chunk.clone());
+
+                Ok(CompileModuleResult {
+                    chunk,
                     path: module_path,
-                    loaded_from_cache: true,
-                }),
-                None => {
-                    let script = std::fs::read_to_string(&module_path)?;
-
-                    let chunk = self.compile_script(
-                        &script,
-                        Some(&module_path),
-                        CompilerSettings::default(),
-                    )?;
-
-                    self.chunks.insert(module_path.clone(), chunk.clone());
-
-                    Ok(CompileModuleResult {
-                        chunk,
-                        path: module_path,
-                        loaded_from_cache: false,
-                    })
-                }
+                    loaded_from_cache: false,
+                })
             }
-        };
+        }
+    }
+
+    /// Finds a module from its name, returning the module's canonicalized path
+    ///
+    /// The module is searched for relative to `load_from_path`, or the current working directory
+    /// if no path is provided.
+    pub fn find_module(
+        &self,
+        name: &str,
+        load_from_path: Option<&Path>,
+    ) -> Result<PathBuf, LoaderError> {
+        let search_folder = search_folder(load_from_path)?;
 
         let extension = "koto";
         let named_path = search_folder.join(name);
@@ -207,19 +231,37 @@ impl Loader {
         // First, check for a neighbouring file with a matching name.
         let module_path = named_path.with_extension(extension);
         if module_path.exists() {
-            load_module_from_path(module_path)
+            Ok(module_path.canonicalize()?)
         } else {
             // Alternatively, check for a neighbouring directory with a matching name,
             // that also contains a main file.
             let module_path = named_path.join("main").with_extension(extension);
             if module_path.exists() {
-                load_module_from_path(module_path)
+                Ok(module_path.canonicalize()?)
             } else {
                 Err(LoaderErrorKind::UnableToFindModule(name.into()).into())
             }
         }
     }
 
+    /// Loads the contents of a file that's embedded in a script with `@embed`
+    ///
+    /// As with modules, the file is searched for relative to `load_from_path`, or the current
+    /// working directory if no path is provided.
+    pub fn load_embedded_file(
+        &self,
+        path: &str,
+        load_from_path: Option<&Path>,
+    ) -> Result<String, LoaderError> {
+        let file_path = search_folder(load_from_path)?.join(path);
+        Ok(std::fs::read_to_string(file_path)?)
+    }
+
+    /// Removes a compiled module from the cache, returning the module's chunk if it was cached
+    pub fn remove_module(&mut self, path: &Path) -> Option<Ptr<Chunk>> {
+        self.chunks.remove(path)
+    }
+
     /// Clears the compiled module cache
     pub fn clear_cache(&mut self) {
         self.chunks.clear();
@@ -231,3 +273,17 @@ pub struct CompileModuleResult {
     pub path: PathBuf,
     pub loaded_from_cache: bool,
 }
+
+// Returns either the directory of the provided path, or the current working directory
+fn search_folder(load_from_path: Option<&Path>) -> Result<PathBuf, LoaderError> {
+    match load_from_path {
+        Some(path) => match canonicalize(path)? {
+            canonicalized if canonicalized.is_file() => match canonicalized.parent() {
+                Some(parent_dir) => Ok(parent_dir.to_path_buf()),
+                None => Err(LoaderErrorKind::FailedToGetPathParent(path.into()).into()),
+            },
+            canonicalized => Ok(canonicalized),
+        },
+        None => Ok(std::env::current_dir()?),
+    }
+}
@@ -201,6 +201,11 @@ pub enum Op {
     /// `[*target]`
     RangeFull,
 
+    /// Sets the step of the Range in the target register
+    ///
+    /// `[*target, *step]`
+    RangeStep,
+
     /// Negates a value
     ///
     /// Used for the unary negation operator, i.e. `x = -y`
@@ -333,6 +338,18 @@ pub enum Op {
     /// `[*error]`
     Throw,
 
+    /// Unwraps an `ok` result, or prepares to return an `err` result from the current frame
+    ///
+    /// If the value is an `ok` result, then the result's value is placed in the result register,
+    /// and the instruction jumps forward by the given offset.
+    ///
+    /// If the value is an `err` result, then the result is placed in the result register, and
+    /// execution continues with the following instructions, which run any pending `finally`
+    /// blocks before returning the result from the frame.
+    ///
+    /// `[*result, *value, offset[2]]`
+    Propagate,
+
     /// Gets the next value from an Iterator
     ///
     /// The output from the iterator is placed in the output register.
@@ -485,23 +502,102 @@ pub enum Op {
     /// `[*value, size]`
     CheckSizeMin,
 
+    /// Checks if a value is a Number that's contained in a range
+    ///
+    /// Used when matching against range patterns in match arms.
+    ///
+    /// `[*result, *value, *range]`
+    MatchRange,
+
+    /// Causes the instruction pointer to jump forward, if a value is not Null
+    ///
+    /// Used when assigning default values to function arguments.
+    ///
+    /// `[*value, offset[2]]`
+    JumpIfNotNull,
+
+    /// Calls a function with named arguments
+    ///
+    /// The named arguments are provided in a Map that follows the positional arguments,
+    /// and are placed in the argument registers that match the function's argument names.
+    /// Native functions receive the Map as their last argument.
+    ///
+    /// `[*result, *function, *frame base, arg count]`
+    CallNamed,
+
+    /// Calls a function with spread arguments
+    ///
+    /// The positional arguments are provided in a Tuple in the first argument register, and are
+    /// moved into the argument registers before making the call.
+    /// If the arg count is 2 then the second argument register contains a Map of named arguments,
+    /// see [Op::CallNamed].
+    ///
+    /// `[*result, *function, *frame base, arg count]`
+    CallSpread,
+
+    /// Pushes the values contained in an iterable value to the end of the current sequence
+    ///
+    /// `[*iterable]`
+    SequenceExtend,
+
+    /// Inserts the entries of a map into another map
+    ///
+    /// `[*map, *source]`
+    MapExtend,
+
+    /// Access a contained value via a string key, with null returned if the key isn't found
+    ///
+    /// Used in `?.` optional access operations, e.g. `foo?.bar`.
+    /// If the value is null then the result is null, and `ok` results are unwrapped before the
+    /// access. After the access the instruction jumps forward by the given offset.
+    ///
+    /// If the value is an `err` result, then it's handled in the same way as in [Op::Propagate],
+    /// with the result placed in the result register and execution continuing with the following
+    /// instructions, which return the result from the frame.
+    ///
+    /// `[*result, *value, *key, offset[2]]`
+    AccessOptional,
+
+    /// Flips the bits of an integer
+    ///
+    /// Used for the unary bitwise not operator, i.e. `x = ~y`
+    ///
+    /// `[*target, *source]`
+    BitwiseNot,
+
+    /// Performs a bitwise and with lhs and rhs
+    ///
+    /// `[*result, *lhs, *rhs]`
+    BitwiseAnd,
+
+    /// Performs a bitwise or with lhs and rhs
+    ///
+    /// `[*result, *lhs, *rhs]`
+    BitwiseOr,
+
+    /// Performs a bitwise xor with lhs and rhs
+    ///
+    /// `[*result, *lhs, *rhs]`
+    BitwiseXor,
+
+    /// Shifts the bits of lhs to the left by rhs
+    ///
+    /// `[*result, *lhs, *rhs]`
+    ShiftLeft,
+
+    /// Shifts the bits of lhs to the right by rhs
+    ///
+    /// `[*result, *lhs, *rhs]`
+    ShiftRight,
+
+    /// Checks if a value has the type named by a string constant
+    ///
+    /// Used when matching against type patterns in match arms, e.g. `n: Number`.
+    ///
+    /// `[*result, *value, @type name constant]`
+    MatchType,
+
     // Unused opcodes, allowing for a direct transmutation from a byte to an Op.
-    Unused82,
-    Unused83,
-    Unused84,
-    Unused85,
-    Unused86,
-    Unused87,
-    Unused88,
-    Unused89,
-    Unused90,
-    Unused91,
-    Unused92,
-    Unused93,
-    Unused94,
-    Unused95,
-    Unused96,
-    Unused97,
     Unused98,
     Unused99,
     Unused100,
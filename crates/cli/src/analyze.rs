@@ -0,0 +1,1054 @@
+use anyhow::{bail, Context, Result};
+use koto::{
+    bytecode::Loader,
+    parser::{
+        Ast, AstBinaryOp, AstIndex, AstString, ChainNode, ConstantIndex, ImportItem, Node, Parser,
+        StringContents, StringNode,
+    },
+};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+// The output formats supported by `koto analyze --call-graph`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraphFormat {
+    Json,
+    Dot,
+}
+
+impl GraphFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(Self::Json),
+            "dot" => Some(Self::Dot),
+            _ => None,
+        }
+    }
+}
+
+// The static call graph of a script, along with the graph of the modules that it imports
+//
+// Calls are resolved by name, so calls made to functions that have been passed around as values
+// (e.g. to callbacks) can't be found. References to named functions are included in the graph,
+// which allows functions that are passed as values to be distinguished from dead functions.
+pub struct CallGraph {
+    // The analyzed modules, starting with the main script
+    modules: Vec<Module>,
+    // The calls and references that were found, in the order they were encountered
+    calls: Vec<Call>,
+}
+
+struct Module {
+    path: PathBuf,
+    // The module's path, relative to the main script's directory where possible
+    name: String,
+    imports: Vec<Import>,
+    functions: Vec<Function>,
+}
+
+struct Import {
+    // The name used by the import expression
+    name: String,
+    // The index of the imported module, or `None` when the module wasn't found on disk,
+    // e.g. for modules from the core library, or modules provided by the host
+    module: Option<usize>,
+}
+
+struct Function {
+    // The name the function is assigned to, map entries have names like `map.entry`
+    name: String,
+    // The index of the function that the function is defined in, `None` for top-level functions
+    parent: Option<usize>,
+    line: u32,
+    exported: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct FunctionId {
+    module: usize,
+    // `None` refers to the module's top-level code
+    function: Option<usize>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum CallKind {
+    // The function is called directly
+    Call,
+    // The function is accessed as a value, e.g. passed as an argument to another function
+    Reference,
+}
+
+struct Call {
+    caller: FunctionId,
+    callee: FunctionId,
+    line: u32,
+    kind: CallKind,
+}
+
+// The target of a name that has been brought into scope with an import expression
+#[derive(Clone)]
+enum ImportTarget {
+    Module(usize),
+    Item { module: usize, path: Vec<String> },
+}
+
+impl CallGraph {
+    // Analyzes the script at the given path, along with the modules that it imports
+    pub fn analyze(script_path: &Path) -> Result<Self> {
+        let script_path = script_path
+            .canonicalize()
+            .with_context(|| format!("Failed to find '{}'", script_path.display()))?;
+        let root_dir = script_path.parent().map(Path::to_path_buf);
+        let loader = Loader::default();
+
+        let mut modules = Vec::new();
+        let mut asts = Vec::new();
+        let mut imported_names = Vec::new();
+        let mut module_indices = HashMap::new();
+        module_indices.insert(script_path.clone(), 0);
+        let mut pending = VecDeque::from([script_path]);
+
+        // Parse each module and collect its functions, discovering imported modules along the way.
+        // Modules are indexed in the order that they're discovered, which matches the order in
+        // which they're parsed.
+        while let Some(path) = pending.pop_front() {
+            let script = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to load '{}'", path.display()))?;
+            let ast = match Parser::parse(&script) {
+                Ok(ast) => ast,
+                Err(error) => bail!("Failed to parse '{}': {error}", path.display()),
+            };
+
+            let mut collector = Collector {
+                ast: &ast,
+                functions: Vec::new(),
+                function_nodes: HashMap::new(),
+                imports: Vec::new(),
+            };
+            if let Some(entry_point) = ast.entry_point() {
+                collector.collect(entry_point, None);
+            }
+            let Collector {
+                functions,
+                function_nodes,
+                imports: collected_imports,
+                ..
+            } = collector;
+
+            let mut imports = Vec::<Import>::new();
+            let mut names = HashMap::new();
+            for CollectedImport {
+                module_name,
+                local_name,
+                item_path,
+            } in collected_imports
+            {
+                let module = match loader.find_module(&module_name, Some(&path)) {
+                    Ok(module_path) => Some(match module_indices.get(&module_path) {
+                        Some(index) => *index,
+                        None => {
+                            let index = module_indices.len();
+                            module_indices.insert(module_path.clone(), index);
+                            pending.push_back(module_path);
+                            index
+                        }
+                    }),
+                    Err(_) => None,
+                };
+
+                if !imports.iter().any(|import| import.name == module_name) {
+                    imports.push(Import {
+                        name: module_name,
+                        module,
+                    });
+                }
+
+                if let (Some(local_name), Some(module)) = (local_name, module) {
+                    let target = match item_path {
+                        Some(path) => ImportTarget::Item { module, path },
+                        None => ImportTarget::Module(module),
+                    };
+                    names.insert(local_name, target);
+                }
+            }
+
+            let name = match root_dir
+                .as_ref()
+                .and_then(|dir| path.strip_prefix(dir).ok())
+            {
+                Some(relative) => relative.to_string_lossy().into_owned(),
+                None => path.to_string_lossy().into_owned(),
+            };
+
+            modules.push(Module {
+                path,
+                name,
+                imports,
+                functions,
+            });
+            asts.push((ast, function_nodes));
+            imported_names.push(names);
+        }
+
+        // Resolve the calls made in each module, now that all of the functions are known
+        let mut calls = Vec::new();
+        for (module_index, (ast, function_nodes)) in asts.iter().enumerate() {
+            let mut resolver = Resolver {
+                ast,
+                module: module_index,
+                modules: &modules,
+                function_nodes,
+                imports: &imported_names[module_index],
+                calls: &mut calls,
+            };
+            if let Some(entry_point) = ast.entry_point() {
+                resolver.visit(entry_point, None);
+            }
+        }
+
+        Ok(Self { modules, calls })
+    }
+
+    // Renders the graph in the given format
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Json => self.render_json(),
+            GraphFormat::Dot => self.render_dot(),
+        }
+    }
+
+    // Returns the id that's used for the function in the rendered graph
+    //
+    // Module ids are the module's name, and function ids are the module's name followed by the
+    // function's name, with nested functions following the name of their parent function,
+    // e.g. `main.koto::outer::inner`.
+    fn id(&self, id: FunctionId) -> String {
+        let module = &self.modules[id.module];
+        let mut result = module.name.clone();
+        let mut names = Vec::new();
+        let mut function = id.function;
+        while let Some(index) = function {
+            let f = &module.functions[index];
+            names.push(f.name.as_str());
+            function = f.parent;
+        }
+        for name in names.iter().rev() {
+            result.push_str("::");
+            result.push_str(name);
+        }
+        result
+    }
+
+    // Returns true if the function is neither exported, or called or referenced by other functions
+    fn is_unused(&self, id: FunctionId) -> bool {
+        let Some(index) = id.function else {
+            return false;
+        };
+        let function = &self.modules[id.module].functions[index];
+        let is_entry_point = function
+            .name
+            .rsplit('.')
+            .next()
+            .is_some_and(|name| name.starts_with('@'));
+
+        !function.exported
+            && !is_entry_point
+            && !self
+                .calls
+                .iter()
+                .any(|call| call.callee == id && call.caller != id)
+    }
+
+    fn render_json(&self) -> String {
+        let mut result = String::from("{\n  \"modules\": [");
+
+        for (module_index, module) in self.modules.iter().enumerate() {
+            if module_index > 0 {
+                result.push(',');
+            }
+            let module_id = FunctionId {
+                module: module_index,
+                function: None,
+            };
+            write!(
+                result,
+                "\n    {{\n      \"id\": {},\n      \"path\": {},\n      \"imports\": [",
+                json_string(&self.id(module_id)),
+                json_string(&module.path.to_string_lossy()),
+            )
+            .ok();
+
+            for (i, import) in module.imports.iter().enumerate() {
+                let imported = match import.module {
+                    Some(index) => json_string(&self.modules[index].name),
+                    None => "null".into(),
+                };
+                write!(
+                    result,
+                    "{}\n        {{ \"name\": {}, \"module\": {imported} }}",
+                    if i > 0 { "," } else { "" },
+                    json_string(&import.name),
+                )
+                .ok();
+            }
+            if !module.imports.is_empty() {
+                result.push_str("\n      ");
+            }
+            result.push_str("],\n      \"functions\": [");
+
+            for (i, function) in module.functions.iter().enumerate() {
+                let id = FunctionId {
+                    module: module_index,
+                    function: Some(i),
+                };
+                write!(
+                    result,
+                    "{}\n        {{ \"id\": {}, \"name\": {}, \"line\": {}, \"exported\": {}, \
+                     \"unused\": {} }}",
+                    if i > 0 { "," } else { "" },
+                    json_string(&self.id(id)),
+                    json_string(&function.name),
+                    function.line,
+                    function.exported,
+                    self.is_unused(id),
+                )
+                .ok();
+            }
+            if !module.functions.is_empty() {
+                result.push_str("\n      ");
+            }
+            result.push_str("]\n    }");
+        }
+
+        result.push_str("\n  ],\n  \"calls\": [");
+        for (i, call) in self.calls.iter().enumerate() {
+            write!(
+                result,
+                "{}\n    {{ \"caller\": {}, \"callee\": {}, \"line\": {}, \"kind\": \"{}\" }}",
+                if i > 0 { "," } else { "" },
+                json_string(&self.id(call.caller)),
+                json_string(&self.id(call.callee)),
+                call.line,
+                match call.kind {
+                    CallKind::Call => "call",
+                    CallKind::Reference => "reference",
+                }
+            )
+            .ok();
+        }
+        if !self.calls.is_empty() {
+            result.push_str("\n  ");
+        }
+        result.push_str("]\n}\n");
+
+        result
+    }
+
+    fn render_dot(&self) -> String {
+        let mut result = String::from("digraph koto {\n  rankdir=LR;\n");
+
+        for (module_index, module) in self.modules.iter().enumerate() {
+            let module_id = self.id(FunctionId {
+                module: module_index,
+                function: None,
+            });
+            writeln!(
+                result,
+                "  subgraph \"cluster_{module_index}\" {{\n    label={};",
+                dot_string(&module.name)
+            )
+            .ok();
+            // The module's node represents the module's top-level code
+            writeln!(
+                result,
+                "    {} [shape=box, label={}];",
+                dot_string(&module_id),
+                dot_string(&module.name)
+            )
+            .ok();
+
+            for (i, function) in module.functions.iter().enumerate() {
+                let id = FunctionId {
+                    module: module_index,
+                    function: Some(i),
+                };
+                writeln!(
+                    result,
+                    "    {} [label={}{}];",
+                    dot_string(&self.id(id)),
+                    dot_string(&function.name),
+                    if self.is_unused(id) {
+                        ", style=dashed"
+                    } else {
+                        ""
+                    }
+                )
+                .ok();
+            }
+
+            result.push_str("  }\n");
+        }
+
+        for (module_index, module) in self.modules.iter().enumerate() {
+            for imported in module.imports.iter().filter_map(|import| import.module) {
+                writeln!(
+                    result,
+                    "  {} -> {} [style=bold, label=\"import\"];",
+                    dot_string(&self.id(FunctionId {
+                        module: module_index,
+                        function: None
+                    })),
+                    dot_string(&self.id(FunctionId {
+                        module: imported,
+                        function: None
+                    })),
+                )
+                .ok();
+            }
+        }
+
+        // Multiple calls between the same functions are rendered as a single edge
+        let mut rendered = HashSet::new();
+        for call in self.calls.iter() {
+            if rendered.insert((call.caller, call.callee, call.kind)) {
+                writeln!(
+                    result,
+                    "  {} -> {}{};",
+                    dot_string(&self.id(call.caller)),
+                    dot_string(&self.id(call.callee)),
+                    match call.kind {
+                        CallKind::Call => "",
+                        CallKind::Reference => " [style=dotted]",
+                    }
+                )
+                .ok();
+            }
+        }
+
+        result.push_str("}\n");
+        result
+    }
+}
+
+struct CollectedImport {
+    // The name of the module that's being imported
+    module_name: String,
+    // The name that the import is assigned to in the importing module
+    local_name: Option<String>,
+    // The path to the imported item within the module, `None` when the module itself is imported
+    item_path: Option<Vec<String>>,
+}
+
+// Collects the named functions and imports in a module's AST
+struct Collector<'a> {
+    ast: &'a Ast,
+    functions: Vec<Function>,
+    // Maps function nodes to their index in `functions`
+    function_nodes: HashMap<u32, usize>,
+    imports: Vec<CollectedImport>,
+}
+
+impl Collector<'_> {
+    fn collect(&mut self, node_index: AstIndex, scope: Option<usize>) {
+        let ast = self.ast;
+        match &ast.node(node_index).node {
+            Node::Assign { target, expression } => match assignment_name(ast, *target) {
+                Some(name) => self.define(*expression, name, scope, false),
+                None => {
+                    self.collect(*target, scope);
+                    self.collect(*expression, scope);
+                }
+            },
+            Node::Export(exported) => match &ast.node(*exported).node {
+                Node::Assign { target, expression } => match assignment_name(ast, *target) {
+                    Some(name) => self.define(*expression, name, scope, true),
+                    None => self.collect(*expression, scope),
+                },
+                Node::Map(entries) => {
+                    for (key, value) in entries.iter() {
+                        match (key_name(ast, *key), value) {
+                            (Some(name), Some(value)) => self.define(*value, name, scope, true),
+                            (_, Some(value)) => self.collect(*value, scope),
+                            _ => {}
+                        }
+                    }
+                }
+                _ => self.collect(*exported, scope),
+            },
+            Node::Import { from, items } => self.collect_import(from, items),
+            _ => {
+                for child in children(ast, node_index) {
+                    self.collect(child, scope);
+                }
+            }
+        }
+    }
+
+    // Defines a named value, registering the value as a function if it's a function
+    fn define(&mut self, node_index: AstIndex, name: String, scope: Option<usize>, exported: bool) {
+        let ast = self.ast;
+        let function_node = match &ast.node(node_index).node {
+            Node::Function(_) => node_index,
+            Node::Deprecated { function, .. } => *function,
+            Node::Map(entries) => {
+                for (key, value) in entries.iter() {
+                    match (key_name(ast, *key), value) {
+                        (Some(key), Some(value)) => {
+                            self.define(*value, format!("{name}.{key}"), scope, exported)
+                        }
+                        (_, Some(value)) => self.collect(*value, scope),
+                        _ => {}
+                    }
+                }
+                return;
+            }
+            _ => return self.collect(node_index, scope),
+        };
+
+        let index = self.functions.len();
+        self.functions.push(Function {
+            name,
+            parent: scope,
+            line: line(ast, node_index),
+            exported: exported && scope.is_none(),
+        });
+        self.function_nodes.insert(function_node.into(), index);
+
+        for child in children(ast, function_node) {
+            self.collect(child, Some(index));
+        }
+    }
+
+    fn collect_import(&mut self, from: &[AstIndex], items: &[ImportItem]) {
+        let ast = self.ast;
+        let from_path: Vec<String> = from
+            .iter()
+            .filter_map(|node| import_name(ast, *node))
+            .collect();
+
+        for item in items.iter() {
+            let Some(item_name) = import_name(ast, item.item) else {
+                continue;
+            };
+            let local_name = match item.name {
+                Some(name) => import_name(ast, name),
+                None => Some(item_name.clone()),
+            };
+
+            self.imports.push(match from_path.split_first() {
+                Some((module_name, nested)) => {
+                    let mut item_path = nested.to_vec();
+                    item_path.push(item_name);
+                    CollectedImport {
+                        module_name: module_name.clone(),
+                        local_name,
+                        item_path: Some(item_path),
+                    }
+                }
+                None => CollectedImport {
+                    module_name: item_name,
+                    local_name,
+                    item_path: None,
+                },
+            });
+        }
+    }
+}
+
+// Resolves the calls and references made to the named functions in a module's AST
+struct Resolver<'a> {
+    ast: &'a Ast,
+    module: usize,
+    modules: &'a [Module],
+    function_nodes: &'a HashMap<u32, usize>,
+    imports: &'a HashMap<String, ImportTarget>,
+    calls: &'a mut Vec<Call>,
+}
+
+impl Resolver<'_> {
+    fn visit(&mut self, node_index: AstIndex, scope: Option<usize>) {
+        let ast = self.ast;
+        match &ast.node(node_index).node {
+            Node::Function(f) => {
+                let scope = match self.function_nodes.get(&node_index.into()) {
+                    Some(index) => Some(*index),
+                    None => scope,
+                };
+                for arg in f.args.iter() {
+                    if let Node::DefaultArg { default, .. } = &ast.node(*arg).node {
+                        self.visit(*default, scope);
+                    }
+                }
+                self.visit(f.body, scope);
+            }
+            Node::Id(id) => {
+                let path = [self.constant(*id).to_string()];
+                self.add_call(&path, None, scope, node_index, CallKind::Reference);
+            }
+            Node::Chain(_) => self.visit_chain(node_index, scope),
+            Node::BinaryOp {
+                op: AstBinaryOp::Pipe,
+                lhs,
+                rhs,
+            } => {
+                self.visit(*lhs, scope);
+                match &ast.node(*rhs).node {
+                    Node::Id(id) => {
+                        let path = [self.constant(*id).to_string()];
+                        self.add_call(&path, None, scope, *rhs, CallKind::Call);
+                    }
+                    _ => self.visit(*rhs, scope),
+                }
+            }
+            Node::Map(entries) => {
+                for (key, value) in entries.iter() {
+                    match value {
+                        Some(value) => self.visit(*value, scope),
+                        // Shorthand entries like `{foo}` refer to the value with the same name
+                        None => self.visit(*key, scope),
+                    }
+                }
+            }
+            // Assignment targets, function args, loop args, and import items aren't references
+            Node::Assign { expression, .. } | Node::MultiAssign { expression, .. } => {
+                self.visit(*expression, scope)
+            }
+            Node::For(for_loop) => {
+                self.visit(for_loop.iterable, scope);
+                self.visit(for_loop.body, scope);
+            }
+            Node::Match { expression, arms } => {
+                self.visit(*expression, scope);
+                for arm in arms.iter() {
+                    if let Some(condition) = arm.condition {
+                        self.visit(condition, scope);
+                    }
+                    self.visit(arm.expression, scope);
+                }
+            }
+            Node::Try(try_expression) => {
+                self.visit(try_expression.try_block, scope);
+                self.visit(try_expression.catch_block, scope);
+                if let Some(finally_block) = try_expression.finally_block {
+                    self.visit(finally_block, scope);
+                }
+            }
+            Node::Import { .. } => {}
+            _ => {
+                for child in children(ast, node_index) {
+                    self.visit(child, scope);
+                }
+            }
+        }
+    }
+
+    fn visit_chain(&mut self, node_index: AstIndex, scope: Option<usize>) {
+        let ast = self.ast;
+
+        let mut chain = Vec::new();
+        let mut next = Some(node_index);
+        while let Some(Node::Chain((chain_node, following))) = next.map(|i| &ast.node(i).node) {
+            chain.push(chain_node);
+            next = *following;
+        }
+
+        let Some((ChainNode::Root(root), chain)) = chain.split_first() else {
+            return;
+        };
+
+        // Gather the chain's leading ids, e.g. `foo.bar.baz` in `foo.bar.baz(42)`
+        let mut path = Vec::new();
+        let self_prefix = match &ast.node(*root).node {
+            Node::Id(id) => {
+                path.push(self.constant(*id).to_string());
+                None
+            }
+            // Functions in maps can call other functions in the same map via `self`
+            Node::Self_ => scope.and_then(|scope| {
+                let name = &self.modules[self.module].functions[scope].name;
+                name.rsplit_once('.').map(|(prefix, _)| prefix.to_string())
+            }),
+            _ => {
+                self.visit(*root, scope);
+                None
+            }
+        };
+
+        if !path.is_empty() || self_prefix.is_some() {
+            for chain_node in chain.iter() {
+                match chain_node {
+                    ChainNode::Id(id) => path.push(self.constant(*id).to_string()),
+                    ChainNode::Str(AstString {
+                        contents: StringContents::Literal(id),
+                        ..
+                    }) => path.push(self.constant(*id).to_string()),
+                    _ => break,
+                }
+            }
+
+            let call_follows = matches!(
+                chain.get(path.len() - usize::from(self_prefix.is_none())),
+                Some(ChainNode::Call { .. })
+            );
+
+            self.resolve_chain(
+                &path,
+                self_prefix.as_deref(),
+                scope,
+                node_index,
+                call_follows,
+            );
+        }
+
+        for chain_node in chain.iter() {
+            match chain_node {
+                ChainNode::Index(index) => self.visit(*index, scope),
+                ChainNode::Call { args, .. } => {
+                    for arg in args.iter() {
+                        self.visit(*arg, scope);
+                    }
+                }
+                ChainNode::Str(s) => self.visit_string(&s.contents, scope),
+                _ => {}
+            }
+        }
+    }
+
+    // Resolves the longest prefix of the chain's path that refers to a known function
+    fn resolve_chain(
+        &mut self,
+        path: &[String],
+        self_prefix: Option<&str>,
+        scope: Option<usize>,
+        node_index: AstIndex,
+        call_follows: bool,
+    ) {
+        for len in (1..=path.len()).rev() {
+            let kind = if call_follows && len == path.len() {
+                CallKind::Call
+            } else {
+                CallKind::Reference
+            };
+            if self.add_call(&path[..len], self_prefix, scope, node_index, kind) {
+                return;
+            }
+        }
+    }
+
+    fn visit_string(&mut self, contents: &StringContents, scope: Option<usize>) {
+        if let StringContents::Interpolated(nodes) = contents {
+            for node in nodes.iter() {
+                if let StringNode::Expression { expression, .. } = node {
+                    self.visit(*expression, scope);
+                }
+            }
+        }
+    }
+
+    // Adds a call to the function with the given path, returning true if the function was found
+    fn add_call(
+        &mut self,
+        path: &[String],
+        self_prefix: Option<&str>,
+        scope: Option<usize>,
+        node_index: AstIndex,
+        kind: CallKind,
+    ) -> bool {
+        let Some(callee) = self.resolve(path, self_prefix, scope) else {
+            return false;
+        };
+
+        self.calls.push(Call {
+            caller: FunctionId {
+                module: self.module,
+                function: scope,
+            },
+            callee,
+            line: line(self.ast, node_index),
+            kind,
+        });
+        true
+    }
+
+    fn resolve(
+        &self,
+        path: &[String],
+        self_prefix: Option<&str>,
+        scope: Option<usize>,
+    ) -> Option<FunctionId> {
+        let functions = &self.modules[self.module].functions;
+        let name = match self_prefix {
+            Some(prefix) => format!("{prefix}.{}", path.join(".")),
+            None => path.join("."),
+        };
+
+        // Look for the function in the enclosing scopes
+        let mut search_scope = scope;
+        loop {
+            let found = functions
+                .iter()
+                .position(|f| f.parent == search_scope && f.name == name);
+            if let Some(function) = found {
+                return Some(FunctionId {
+                    module: self.module,
+                    function: Some(function),
+                });
+            }
+            match search_scope {
+                Some(index) => search_scope = functions[index].parent,
+                None => break,
+            }
+        }
+
+        if self_prefix.is_some() {
+            return None;
+        }
+
+        // Look for the function in the imported modules
+        let (root, rest) = path.split_first()?;
+        let (module, item_path) = match self.imports.get(root)? {
+            ImportTarget::Module(module) => (*module, rest.to_vec()),
+            ImportTarget::Item { module, path } => {
+                let mut item_path = path.clone();
+                item_path.extend_from_slice(rest);
+                (*module, item_path)
+            }
+        };
+        let name = item_path.join(".");
+        self.modules[module]
+            .functions
+            .iter()
+            .position(|f| f.parent.is_none() && f.name == name)
+            .map(|function| FunctionId {
+                module,
+                function: Some(function),
+            })
+    }
+
+    fn constant(&self, index: ConstantIndex) -> &str {
+        self.ast.constants().get_str(index)
+    }
+}
+
+// Returns the name of an assignment target, if the target refers to a named value
+fn assignment_name(ast: &Ast, target: AstIndex) -> Option<String> {
+    match &ast.node(target).node {
+        Node::Id(id) => Some(ast.constants().get_str(*id).to_string()),
+        Node::Meta(..) => key_name(ast, target),
+        Node::Chain((ChainNode::Root(root), mut next)) => {
+            let mut result = assignment_name(ast, *root)?;
+            while let Some(next_index) = next {
+                match &ast.node(next_index).node {
+                    Node::Chain((ChainNode::Id(id), following)) => {
+                        result.push('.');
+                        result.push_str(ast.constants().get_str(*id));
+                        next = *following;
+                    }
+                    _ => return None,
+                }
+            }
+            Some(result)
+        }
+        _ => None,
+    }
+}
+
+// Returns the name of a map key
+fn key_name(ast: &Ast, key: AstIndex) -> Option<String> {
+    match &ast.node(key).node {
+        Node::Id(id) => Some(ast.constants().get_str(*id).to_string()),
+        Node::Str(s) => match &s.contents {
+            StringContents::Literal(id) => Some(ast.constants().get_str(*id).to_string()),
+            _ => None,
+        },
+        Node::Meta(key, name) => Some(match name {
+            Some(name) => format!("{key} {}", ast.constants().get_str(*name)),
+            None => key.to_string(),
+        }),
+        _ => None,
+    }
+}
+
+// Returns the name used in an import expression
+fn import_name(ast: &Ast, node: AstIndex) -> Option<String> {
+    match &ast.node(node).node {
+        Node::Id(id) => Some(ast.constants().get_str(*id).to_string()),
+        Node::Str(s) => match &s.contents {
+            StringContents::Literal(id) => Some(ast.constants().get_str(*id).to_string()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// Returns the node's line number, counting from 1
+fn line(ast: &Ast, node: AstIndex) -> u32 {
+    ast.span(ast.node(node).span).start.line + 1
+}
+
+// Returns the indices of the node's child nodes
+fn children(ast: &Ast, node_index: AstIndex) -> Vec<AstIndex> {
+    let mut result = Vec::new();
+
+    match &ast.node(node_index).node {
+        Node::Nested(child)
+        | Node::RangeFrom { start: child }
+        | Node::RangeTo { end: child, .. }
+        | Node::MatchType { pattern: child, .. }
+        | Node::Deprecated {
+            function: child, ..
+        }
+        | Node::Cfg { body: child, .. }
+        | Node::Export(child)
+        | Node::UnaryOp { value: child, .. }
+        | Node::DefaultArg { default: child, .. }
+        | Node::Spread(child)
+        | Node::NamedArg { value: child, .. }
+        | Node::Loop { body: child }
+        | Node::Throw(child)
+        | Node::Propagate(child)
+        | Node::Yield(child)
+        | Node::Debug {
+            expression: child, ..
+        } => result.push(*child),
+        Node::Break(child) | Node::Return(child) => result.extend(child),
+        Node::Chain((chain_node, next)) => {
+            match chain_node {
+                ChainNode::Root(child) | ChainNode::Index(child) => result.push(*child),
+                ChainNode::Call { args, .. } => result.extend_from_slice(args),
+                ChainNode::Str(s) => push_string_children(&s.contents, &mut result),
+                ChainNode::Id(_) | ChainNode::Optional => {}
+            }
+            result.extend(next);
+        }
+        Node::Str(s) => push_string_children(&s.contents, &mut result),
+        Node::List(children)
+        | Node::Tuple(children)
+        | Node::TempTuple(children)
+        | Node::Block(children)
+        | Node::MainBlock { body: children, .. } => result.extend_from_slice(children),
+        Node::Range {
+            start, end, step, ..
+        } => {
+            result.extend([*start, *end]);
+            result.extend(step);
+        }
+        Node::Map(entries) => {
+            for (key, value) in entries.iter() {
+                result.push(*key);
+                result.extend(value);
+            }
+        }
+        Node::Function(f) => {
+            result.extend_from_slice(&f.args);
+            result.push(f.body);
+        }
+        Node::Import { from, items } => {
+            result.extend_from_slice(from);
+            for item in items.iter() {
+                result.push(item.item);
+                result.extend(item.name);
+            }
+        }
+        Node::Assign { target, expression } => result.extend([*target, *expression]),
+        Node::MultiAssign {
+            targets,
+            expression,
+        } => {
+            result.extend_from_slice(targets);
+            result.push(*expression);
+        }
+        Node::BinaryOp { lhs, rhs, .. } => result.extend([*lhs, *rhs]),
+        Node::If(if_expression) => {
+            result.push(if_expression.condition);
+            result.push(if_expression.then_node);
+            for (condition, block) in if_expression.else_if_blocks.iter() {
+                result.extend([*condition, *block]);
+            }
+            result.extend(if_expression.else_node);
+        }
+        Node::Match { expression, arms } => {
+            result.push(*expression);
+            for arm in arms.iter() {
+                result.extend_from_slice(&arm.patterns);
+                result.extend(arm.condition);
+                result.push(arm.expression);
+            }
+        }
+        Node::Switch(arms) => {
+            for arm in arms.iter() {
+                result.extend(arm.condition);
+                result.push(arm.expression);
+            }
+        }
+        Node::For(for_loop) => {
+            result.extend_from_slice(&for_loop.args);
+            result.extend([for_loop.iterable, for_loop.body]);
+        }
+        Node::While { condition, body } | Node::Until { condition, body } => {
+            result.extend([*condition, *body])
+        }
+        Node::Try(try_expression) => {
+            result.extend([
+                try_expression.try_block,
+                try_expression.catch_arg,
+                try_expression.catch_block,
+            ]);
+            result.extend(try_expression.finally_block);
+        }
+        Node::Null
+        | Node::Id(_)
+        | Node::Meta(..)
+        | Node::BoolTrue
+        | Node::BoolFalse
+        | Node::SmallInt(_)
+        | Node::Int(_)
+        | Node::Float(_)
+        | Node::RangeFull
+        | Node::Self_
+        | Node::Wildcard(_)
+        | Node::Ellipsis(_)
+        | Node::Embed { .. }
+        | Node::Continue => {}
+    }
+
+    result
+}
+
+fn push_string_children(contents: &StringContents, result: &mut Vec<AstIndex>) {
+    if let StringContents::Interpolated(nodes) = contents {
+        for node in nodes.iter() {
+            if let StringNode::Expression { expression, .. } = node {
+                result.push(*expression);
+            }
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c.is_control() => {
+                write!(result, "\\u{:04x}", c as u32).ok();
+            }
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+fn dot_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,244 @@
+use anyhow::{bail, Context, Result};
+use std::{
+    env,
+    ffi::OsStr,
+    fs::{self, File},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Component, Path},
+};
+
+// The bundle is appended to a copy of the runner binary, followed by the bundle's size and a
+// marker that identifies the executable as containing a bundle.
+const BUNDLE_MARKER: &[u8; 8] = b"KOTOBNDL";
+const TRAILER_SIZE: u64 = 16;
+
+const FLAG_STRICT: u8 = 1;
+
+// A compiled script bundled into a standalone executable, along with its assets
+pub struct Bundle {
+    // Whether or not modules imported by the script should be compiled in strict mode
+    pub strict: bool,
+    // The flags that enable `@cfg` blocks when modules are compiled
+    pub cfg_flags: Vec<String>,
+    // The file name of the main script
+    pub script_name: String,
+    // The main script's compiled chunk, serialized with `Chunk::to_bytes`
+    pub chunk: Vec<u8>,
+    // The bundled assets, with paths relative to the main script's directory
+    pub assets: Vec<(String, Vec<u8>)>,
+}
+
+impl Bundle {
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+        result.push(if self.strict { FLAG_STRICT } else { 0 });
+        result.extend_from_slice(&(self.cfg_flags.len() as u32).to_le_bytes());
+        for flag in self.cfg_flags.iter() {
+            write_bytes(&mut result, flag.as_bytes());
+        }
+        write_bytes(&mut result, self.script_name.as_bytes());
+        write_bytes(&mut result, &self.chunk);
+        result.extend_from_slice(&(self.assets.len() as u32).to_le_bytes());
+        for (name, contents) in self.assets.iter() {
+            write_bytes(&mut result, name.as_bytes());
+            write_bytes(&mut result, contents);
+        }
+        result
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = BundleReader { bytes };
+        let flags = reader.read(1)?[0];
+
+        // The counts are read from the executable so they can't be trusted,
+        // the lists are grown as entries are successfully read.
+        let cfg_flag_count = reader.read_u32()?;
+        let mut cfg_flags = Vec::new();
+        for _ in 0..cfg_flag_count {
+            cfg_flags.push(reader.read_string()?);
+        }
+
+        let script_name = reader.read_string()?;
+        let chunk = reader.read_bytes()?.to_vec();
+
+        let asset_count = reader.read_u32()?;
+        let mut assets = Vec::new();
+        for _ in 0..asset_count {
+            let name = reader.read_string()?;
+            let contents = reader.read_bytes()?.to_vec();
+            assets.push((name, contents));
+        }
+
+        Ok(Self {
+            strict: flags & FLAG_STRICT != 0,
+            cfg_flags,
+            script_name,
+            chunk,
+            assets,
+        })
+    }
+
+    // Adds an asset to the bundle, or all of the files in the asset's directory
+    pub fn add_asset(&mut self, script_dir: &Path, asset: &Path) -> Result<()> {
+        let asset = asset
+            .canonicalize()
+            .with_context(|| format!("Failed to find asset '{}'", asset.display()))?;
+
+        if asset.is_dir() {
+            let mut entries = fs::read_dir(&asset)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()?;
+            entries.sort();
+            for entry in entries {
+                self.add_asset(script_dir, &entry)?;
+            }
+        } else {
+            let name = asset_name(script_dir, &asset)?;
+            if name == self.script_name || self.assets.iter().any(|(existing, _)| *existing == name)
+            {
+                return Ok(());
+            }
+            let contents = fs::read(&asset)
+                .with_context(|| format!("Failed to read asset '{}'", asset.display()))?;
+            self.assets.push((name, contents));
+        }
+
+        Ok(())
+    }
+
+    // Writes the bundle to a copy of the runner executable
+    pub fn write_executable(&self, runner: &Path, output: &Path) -> Result<()> {
+        if is_runner(output) {
+            bail!(
+                "The executable can't be named '{}'",
+                output.file_name().unwrap_or_default().to_string_lossy()
+            );
+        }
+
+        fs::copy(runner, output)
+            .with_context(|| format!("Failed to copy the runner to '{}'", output.display()))?;
+
+        let bytes = self.to_bytes();
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(output)
+            .with_context(|| format!("Failed to open '{}'", output.display()))?;
+        file.write_all(&bytes)?;
+        file.write_all(&(bytes.len() as u64).to_le_bytes())?;
+        file.write_all(BUNDLE_MARKER)?;
+        Ok(())
+    }
+
+    // Loads the bundle that was appended to the current executable, if there is one
+    pub fn from_current_exe() -> Result<Option<Self>> {
+        let exe = env::current_exe()?;
+
+        // Standalone executables can't share the runner's name, so the runner itself can be
+        // started without reading its executable.
+        if is_runner(&exe) {
+            return Ok(None);
+        }
+
+        let mut file = File::open(exe)?;
+        let file_size = file.metadata()?.len();
+        if file_size < TRAILER_SIZE {
+            return Ok(None);
+        }
+
+        let mut trailer = [0; TRAILER_SIZE as usize];
+        file.seek(SeekFrom::End(-(TRAILER_SIZE as i64)))?;
+        file.read_exact(&mut trailer)?;
+        if &trailer[8..] != BUNDLE_MARKER {
+            return Ok(None);
+        }
+
+        let bundle_size = u64::from_le_bytes(trailer[..8].try_into()?);
+        if bundle_size > file_size - TRAILER_SIZE {
+            bail!("The executable's bundle is corrupted");
+        }
+
+        let mut bytes = vec![0; bundle_size as usize];
+        file.seek(SeekFrom::End(-((bundle_size + TRAILER_SIZE) as i64)))?;
+        file.read_exact(&mut bytes)?;
+        Self::from_bytes(&bytes).map(Some)
+    }
+
+    // Writes the bundled assets into the given directory
+    pub fn extract_assets(&self, dir: &Path) -> Result<()> {
+        for (name, contents) in self.assets.iter() {
+            let path = dir.join(name);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, contents)
+                .with_context(|| format!("Failed to extract '{}'", path.display()))?;
+        }
+
+        Ok(())
+    }
+}
+
+// Returns true if the path's file name matches the name of the koto executable
+fn is_runner(path: &Path) -> bool {
+    path.file_stem() == Some(OsStr::new(env!("CARGO_BIN_NAME")))
+}
+
+fn write_bytes(result: &mut Vec<u8>, bytes: &[u8]) {
+    result.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+    result.extend_from_slice(bytes);
+}
+
+// Returns the path of an asset relative to the main script's directory
+//
+// Assets need to be contained in the script's directory so that they can be extracted alongside
+// the script.
+fn asset_name(script_dir: &Path, asset: &Path) -> Result<String> {
+    let relative = asset.strip_prefix(script_dir).unwrap_or(asset);
+    if relative.is_absolute()
+        || relative
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        bail!(
+            "Assets must be contained in the script's directory ('{}')",
+            asset.display()
+        );
+    }
+
+    Ok(relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
+struct BundleReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> BundleReader<'a> {
+    fn read(&mut self, count: usize) -> Result<&'a [u8]> {
+        if count > self.bytes.len() {
+            bail!("The executable's bundle is corrupted");
+        }
+        let (result, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+        Ok(result)
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.read(4)?.try_into()?))
+    }
+
+    fn read_bytes(&mut self) -> Result<&'a [u8]> {
+        let len = u64::from_le_bytes(self.read(8)?.try_into()?);
+        match usize::try_from(len) {
+            Ok(len) => self.read(len),
+            Err(_) => bail!("The executable's bundle is corrupted"),
+        }
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        Ok(String::from_utf8(self.read_bytes()?.to_vec())?)
+    }
+}
@@ -0,0 +1,196 @@
+//! Support for running the Koto code blocks in markdown documents, see `--doc`
+
+use anyhow::{bail, Result};
+use koto::{prelude::*, Ptr, PtrMut};
+use std::{ops::Deref, path::Path};
+
+// Lines in a code block that start with this prefix declare the block's expected output
+const EXPECTED_OUTPUT_PREFIX: &str = "# ->";
+
+/// A `koto` code block that was found in a markdown document
+#[derive(Debug, PartialEq)]
+pub struct CodeBlock {
+    /// The line number of the block's first line of code, starting from 1
+    pub line: usize,
+    /// The block's script
+    pub script: String,
+    /// The output that's expected from the block, if `# ->` comments were found
+    pub expected_output: Option<String>,
+}
+
+/// Extracts the `koto` code blocks from a markdown document
+///
+/// Blocks tagged with `koto,skip_run` are skipped, and the expected output for blocks tagged
+/// with `koto,skip_check` is ignored.
+pub fn extract_code_blocks(markdown: &str) -> Vec<CodeBlock> {
+    use pulldown_cmark::{CodeBlockKind, Event::*, Parser, Tag};
+
+    let mut result = Vec::new();
+    let mut current_block: Option<(CodeBlock, bool)> = None;
+
+    for (event, range) in Parser::new(markdown).into_offset_iter() {
+        match event {
+            Start(Tag::CodeBlock(CodeBlockKind::Fenced(lang))) => {
+                let mut lang_info = lang.deref().split(',');
+                if lang_info.next() == Some("koto") {
+                    let modifier = lang_info.next();
+                    if modifier != Some("skip_run") {
+                        let block = CodeBlock {
+                            // The block's code starts on the line following the opening fence
+                            line: markdown[..range.start].lines().count() + 2,
+                            script: String::new(),
+                            expected_output: None,
+                        };
+                        current_block = Some((block, modifier == Some("skip_check")));
+                    }
+                }
+            }
+            Text(text) => {
+                if let Some((block, _)) = current_block.as_mut() {
+                    block.script.push_str(&text);
+                }
+            }
+            End(Tag::CodeBlock(_)) => {
+                if let Some((mut block, skip_check)) = current_block.take() {
+                    if !skip_check {
+                        block.expected_output = expected_output(&block.script);
+                    }
+                    result.push(block);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    result
+}
+
+// Collects the expected output from a script's `# ->` comments
+fn expected_output(script: &str) -> Option<String> {
+    let mut result: Option<String> = None;
+
+    for line in script.lines() {
+        if let Some(expected) = line.trim_start().strip_prefix(EXPECTED_OUTPUT_PREFIX) {
+            let output = result.get_or_insert_with(String::new);
+            output.push_str(expected.strip_prefix(' ').unwrap_or(expected));
+            output.push('\n');
+        }
+    }
+
+    result
+}
+
+/// Runs the code blocks in a markdown document
+///
+/// The blocks are run in order, sharing the same scope. If a block declares its expected output
+/// with `# ->` comments, then the block's output is checked against the expected output.
+///
+/// By default the blocks' output is printed, and running stops at the first failed block.
+/// When `test_mode` is enabled, the blocks' output is only used for checking, and all blocks are
+/// run with failures reported in a summary.
+pub fn run_document(
+    markdown: &str,
+    path: Option<&Path>,
+    mut settings: KotoSettings,
+    test_mode: bool,
+) -> Result<()> {
+    let output = DocOutput::default();
+    settings.export_top_level_ids = true;
+    settings.vm_settings.stdout = make_ptr!(output.clone());
+
+    let mut koto = Koto::with_settings(settings);
+    if let Err(error) = koto.set_script_path(path) {
+        bail!("{error}");
+    }
+    super::add_modules(&koto);
+
+    let name = path.map_or_else(|| "<doc>".to_string(), |path| path.display().to_string());
+    let blocks = extract_code_blocks(markdown);
+    let mut failed = 0;
+
+    for block in blocks.iter() {
+        output.clear();
+
+        let result = match koto.compile_and_run(&block.script) {
+            Ok(_) => {
+                let actual = output.contents();
+                match &block.expected_output {
+                    Some(expected) if *expected != actual => Err(format!(
+                        "Output mismatch\n\nExpected:\n{expected}\nActual:\n{actual}"
+                    )),
+                    _ => Ok(()),
+                }
+            }
+            Err(error) => Err(error.to_string()),
+        };
+
+        if !test_mode {
+            print!("{}", output.contents());
+        }
+
+        if let Err(error) = result {
+            if test_mode {
+                failed += 1;
+                println!("FAILED: {name}:{}\n{error}\n", block.line);
+            } else {
+                bail!("Error in code block at {name}:{}\n{error}", block.line);
+            }
+        }
+    }
+
+    if test_mode {
+        println!(
+            "{} of {} code blocks passed in {name}",
+            blocks.len() - failed,
+            blocks.len()
+        );
+        if failed > 0 {
+            bail!("{failed} code block(s) failed");
+        }
+    }
+
+    Ok(())
+}
+
+// Captures the output of a document's code blocks
+#[derive(Clone, Default)]
+struct DocOutput {
+    output: PtrMut<String>,
+}
+
+impl DocOutput {
+    fn clear(&self) {
+        self.output.borrow_mut().clear();
+    }
+
+    fn contents(&self) -> String {
+        self.output.borrow().clone()
+    }
+}
+
+impl KotoFile for DocOutput {
+    fn id(&self) -> KString {
+        "_stdout_".into()
+    }
+}
+
+impl KotoRead for DocOutput {}
+impl KotoWrite for DocOutput {
+    fn write(&self, bytes: &[u8]) -> koto::Result<()> {
+        self.output
+            .borrow_mut()
+            .push_str(&String::from_utf8_lossy(bytes));
+        Ok(())
+    }
+
+    fn write_line(&self, output: &str) -> koto::Result<()> {
+        let mut buffer = self.output.borrow_mut();
+        buffer.push_str(output);
+        buffer.push('\n');
+        Ok(())
+    }
+
+    fn flush(&self) -> koto::Result<()> {
+        Ok(())
+    }
+}
@@ -66,13 +66,48 @@ impl Help {
         }
 
         let extra_lib_files = [
+            include_doc!("libs/archive.md"),
+            include_doc!("libs/cache.md"),
+            include_doc!("libs/canvas.md"),
             include_doc!("libs/color.md"),
+            include_doc!("libs/containers.md"),
+            include_doc!("libs/diff.md"),
+            include_doc!("libs/ease.md"),
+            include_doc!("libs/fake.md"),
+            include_doc!("libs/fsm.md"),
+            include_doc!("libs/fuzzy.md"),
             include_doc!("libs/geometry.md"),
+            include_doc!("libs/i18n.md"),
+            include_doc!("libs/image.md"),
+            include_doc!("libs/ini.md"),
+            include_doc!("libs/ipc.md"),
             include_doc!("libs/json.md"),
+            include_doc!("libs/locale.md"),
+            include_doc!("libs/metrics.md"),
+            include_doc!("libs/midi.md"),
+            include_doc!("libs/money.md"),
+            include_doc!("libs/noise.md"),
+            include_doc!("libs/osc.md"),
+            include_doc!("libs/parquet.md"),
+            include_doc!("libs/parse.md"),
+            include_doc!("libs/plot.md"),
+            include_doc!("libs/progress.md"),
+            include_doc!("libs/protobuf.md"),
             include_doc!("libs/random.md"),
+            include_doc!("libs/readline.md"),
             include_doc!("libs/regex.md"),
+            include_doc!("libs/rules.md"),
+            include_doc!("libs/schedule.md"),
+            include_doc!("libs/semver.md"),
+            include_doc!("libs/sound.md"),
+            include_doc!("libs/spatial.md"),
+            include_doc!("libs/store.md"),
+            include_doc!("libs/table.md"),
             include_doc!("libs/tempfile.md"),
             include_doc!("libs/toml.md"),
+            include_doc!("libs/trace.md"),
+            include_doc!("libs/units.md"),
+            include_doc!("libs/validate.md"),
             include_doc!("libs/yaml.md"),
         ];
         for file_contents in extra_lib_files.iter() {
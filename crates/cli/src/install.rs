@@ -0,0 +1,129 @@
+use anyhow::{bail, Context, Result};
+use koto::{
+    parser::{Node, Parser},
+    prelude::*,
+};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+const SHEBANG: &str = "#!/usr/bin/env koto";
+const STRICT_DIRECTIVE: &str = "# koto: strict";
+
+pub struct InstallSettings {
+    // The name of the installed command, defaults to the script's file stem
+    pub name: Option<String>,
+    // The directory that the command will be installed to
+    pub dir: Option<PathBuf>,
+    // Whether or not the installed script should be run in strict mode
+    pub strict: bool,
+}
+
+// Installs a script as an executable command
+//
+// The script is checked for compilation errors, and then written to the install directory with a
+// shebang line that runs it with `koto`, along with directives for any runtime flags.
+//
+// Returns the path of the installed command.
+pub fn install_script(script_path: &Path, settings: InstallSettings) -> Result<PathBuf> {
+    let script = fs::read_to_string(script_path)
+        .with_context(|| format!("Failed to load '{}'", script_path.display()))?;
+
+    // Only the script is installed, so files that it embeds wouldn't be found when it's run
+    if embeds_files(&script) {
+        bail!(
+            "'{}' embeds files, which aren't supported by installed scripts. \
+             Try `koto build --standalone` instead",
+            script_path.display()
+        );
+    }
+
+    let mut koto = Koto::with_settings(KotoSettings {
+        strict: settings.strict,
+        ..Default::default()
+    });
+    if let Err(error) = koto.compile(&script) {
+        bail!("Failed to compile '{}': {error}", script_path.display());
+    }
+
+    let name = match settings.name {
+        Some(name) => name,
+        None => match script_path.file_stem() {
+            Some(stem) => stem.to_string_lossy().into_owned(),
+            None => bail!("Unable to get a name from '{}'", script_path.display()),
+        },
+    };
+
+    let dir = match settings.dir {
+        Some(dir) => dir,
+        None => default_install_dir()?,
+    };
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create '{}'", dir.display()))?;
+
+    let command_path = dir.join(name);
+    fs::write(&command_path, launcher_script(&script, settings.strict))
+        .with_context(|| format!("Failed to write '{}'", command_path.display()))?;
+    make_executable(&command_path)?;
+
+    Ok(command_path)
+}
+
+// Returns true if the directory is included in the PATH environment variable
+pub fn is_dir_in_path(dir: &Path) -> bool {
+    env::var_os("PATH").is_some_and(|path| env::split_paths(&path).any(|entry| entry == dir))
+}
+
+// The install directory is taken from KOTO_INSTALL_DIR, falling back to $HOME/.koto/bin
+fn default_install_dir() -> Result<PathBuf> {
+    if let Some(dir) = env::var_os("KOTO_INSTALL_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+
+    match home::home_dir() {
+        Some(mut dir) => {
+            dir.push(".koto");
+            dir.push("bin");
+            Ok(dir)
+        }
+        None => bail!("Unable to find the home directory, consider setting KOTO_INSTALL_DIR"),
+    }
+}
+
+// Prepares the installed script, replacing any existing shebang line
+fn launcher_script(script: &str, strict: bool) -> String {
+    let body = match script.strip_prefix("#!") {
+        Some(rest) => rest.split_once('\n').map_or("", |(_, body)| body),
+        None => script,
+    };
+
+    let mut result = format!("{SHEBANG}\n");
+    if strict && !body.lines().any(|line| line.trim() == STRICT_DIRECTIVE) {
+        result.push_str(STRICT_DIRECTIVE);
+        result.push('\n');
+    }
+    result.push_str(body);
+    result
+}
+
+// Returns true if the script embeds any files with `@embed`
+fn embeds_files(script: &str) -> bool {
+    Parser::parse(script).is_ok_and(|ast| {
+        ast.nodes()
+            .iter()
+            .any(|node| matches!(node.node, Node::Embed { .. }))
+    })
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+        .with_context(|| format!("Failed to make '{}' executable", path.display()))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
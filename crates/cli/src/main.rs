@@ -1,9 +1,23 @@
+mod analyze;
+mod bundle;
+mod doc;
 mod help;
+mod install;
+mod refactor;
 mod repl;
+mod signals;
+mod terminal;
 
+use analyze::{CallGraph, GraphFormat};
 use anyhow::{bail, Context, Result};
+use bundle::Bundle;
 use crossterm::tty::IsTty;
-use koto::prelude::*;
+use koto::{
+    parser::{minify, MinifySettings, RenameSettings},
+    prelude::*,
+    runtime::Diagnostic,
+    Ptr,
+};
 use repl::{Repl, ReplSettings};
 use rustyline::EditMode;
 use std::{
@@ -22,6 +36,12 @@ fn help_string() -> String {
 
 USAGE:
     koto [FLAGS] [script] [<args>...]
+    koto [FLAGS] install <script> [--name NAME] [--dir DIR]
+    koto [FLAGS] build --standalone <script> [--output PATH] [--asset PATH]...
+    koto [FLAGS] repl [--load PATH]...
+    koto analyze <script> --call-graph [--format FORMAT] [--output PATH]
+    koto refactor rename <old> <new> [--dir DIR] [--strings] [--comments] [--dry-run]
+    koto minify <script> [--output PATH] [--string-table]
 
 FLAGS:
     -e, --eval               Evaluate the script as a string instead of loading it from disk
@@ -29,6 +49,11 @@ FLAGS:
     -b, --show_bytecode      Show the script's compiled bytecode
     -t, --tests              Run the script's tests before running the script
     -T, --import_tests       Run the script's tests, along with any tests in imported modules
+    -d, --doc                Run the koto code blocks in a markdown document,
+                             checking their output against '# ->' comments.
+                             With --tests, failed blocks are reported in a summary.
+    -s, --strict             Require new bindings to be declared with 'let'
+    --cfg FLAG               Enable '@cfg' blocks with the given flag, can be used more than once
     -c, --config PATH        Config file to load when using the REPL
     -v, --version            Prints version information
     -h, --help               Prints help information
@@ -37,6 +62,69 @@ ARGS:
     <script>     The koto script to run, as a file path, or as a string when --eval is set
     <args>...    Arguments to pass into the script
 
+SCRIPT COMMANDS:
+    Scripts can define commands by exporting functions with '@command name'.
+    The first argument after the script selects the command to run,
+    and the remaining arguments are passed to the command as options and arguments,
+    e.g. `koto tool.koto build --target x86`.
+
+INSTALLING SCRIPTS:
+    `koto install <script>` installs a script as an executable command,
+    which runs the script with `koto` via a `#!/usr/bin/env koto` shebang line.
+    Flags provided before `install` (e.g. --strict) are applied to the installed script.
+
+    --name NAME  The name of the installed command (default: the script's file name)
+    --dir DIR    The directory to install to (default: $KOTO_INSTALL_DIR or $HOME/.koto/bin)
+
+STANDALONE EXECUTABLES:
+    `koto build --standalone <script>` compiles a script and bundles it into a copy of the koto
+    executable, producing a single executable that runs the script without requiring Koto to be
+    installed.
+    Flags provided before `build` (e.g. --strict, --cfg) are applied to the bundled script.
+
+    --output PATH  The path of the executable (default: the script's name in the current dir),
+                   the executable can't be named 'koto'.
+    --asset PATH   A file or directory to bundle with the script, can be used more than once.
+                   Assets are available relative to `koto.script_dir` when the script is run.
+
+ANALYZING SCRIPTS:
+    `koto analyze <script> --call-graph` reports the script's static call graph,
+    along with the graph of the modules that it imports.
+    Calls are found by name, and functions that are neither exported nor referenced by
+    another function are reported as unused.
+
+    --format FORMAT  The report's format, either 'json' (the default) or 'dot'
+    --output PATH    The file to write the report to (default: stdout)
+
+REFACTORING SCRIPTS:
+    `koto refactor rename <old> <new>` renames a top-level function or variable in all of the
+    .koto scripts in a directory and its subdirectories, along with the references to it.
+    Local values with the same name, map keys, and lookups that don't refer to an imported
+    module are left unchanged. Strings and comments are left unchanged unless enabled with flags.
+    No scripts are changed if the new name is already in use, or if any script fails to parse.
+
+    --dir DIR    The directory containing the scripts to refactor (default: the current dir)
+    --strings    Also rename matching words in strings
+    --comments   Also rename matching words in comments
+    --dry-run    Report the scripts that would be changed without writing them
+
+MINIFYING SCRIPTS:
+    `koto minify <script>` produces a smaller version of a script with the same behaviour,
+    with comments and redundant whitespace removed, and with local values given short names.
+
+    --output PATH   The file to write the minified script to (default: stdout)
+    --string-table  Move repeated strings into a table at the start of the script
+
+USING THE REPL:
+    Running `koto` without a script starts the REPL, which can also be started with `koto repl`.
+
+    --load PATH  A script to run before the first prompt, can be used more than once.
+                 The script's top-level values are made available in the REPL.
+
+    If $HOME/.koto/repl.koto exists, then it's run when the REPL starts, before any loaded scripts.
+    The inputs that were successfully run during a session can be saved to a file with
+    `:save PATH`.
+
 REPL CONFIGURATION:
     Koto will read configuration settings from $HOME/.koto/repl_config.koto,
     or from a file provided with the --config flag.
@@ -52,6 +140,7 @@ REPL CONFIGURATION:
 
 ENV VARS:
     KOTO_EDIT_MODE_VI   Enables the VI editing mode (Emacs bindings are enabled by default)
+    KOTO_INSTALL_DIR    The directory that `koto install` installs scripts to
     KOTO_MAX_HISTORY    The maximum number of entries to store in the REPL history (default: 100)
     NO_COLOR            Disables colored output (enabled by default)
 ",
@@ -70,6 +159,9 @@ struct KotoArgs {
     eval_script: bool,
     run_tests: bool,
     run_import_tests: bool,
+    run_doc: bool,
+    strict: bool,
+    cfg_flags: Vec<String>,
     show_bytecode: bool,
     show_instructions: bool,
     script: Option<String>,
@@ -78,13 +170,36 @@ struct KotoArgs {
 }
 
 fn parse_arguments() -> Result<KotoArgs> {
-    let mut args = pico_args::Arguments::from_env();
+    // Arguments following the script are passed to the script without being parsed as flags,
+    // allowing scripts to accept their own options.
+    let mut args = env::args_os().skip(1).collect::<Vec<_>>();
+    let mut script_index = None;
+    let mut skip_next = false;
+    for (i, arg) in args.iter().enumerate() {
+        if skip_next {
+            skip_next = false;
+        } else if arg == "-c" || arg == "--config" || arg == "--cfg" {
+            skip_next = true;
+        } else if !arg.to_string_lossy().starts_with('-') {
+            script_index = Some(i);
+            break;
+        }
+    }
+    let extra_args = match script_index {
+        Some(i) => args.split_off(i + 1),
+        None => Vec::new(),
+    };
+
+    let mut args = pico_args::Arguments::from_vec(args);
 
     let eval_script = args.contains(["-e", "--eval"]);
     let show_instructions = args.contains(["-i", "--show_instructions"]);
     let show_bytecode = args.contains(["-b", "--show_bytecode"]);
     let run_tests = args.contains(["-t", "--tests"]);
     let run_import_tests = args.contains(["-T", "--import_tests"]);
+    let run_doc = args.contains(["-d", "--doc"]);
+    let strict = args.contains(["-s", "--strict"]);
+    let cfg_flags = args.values_from_str("--cfg")?;
     let help = args.contains(["-h", "--help"]);
     let version = args.contains(["-v", "--version"]);
     let config_file = args.opt_value_from_str(["-c", "--config"])?;
@@ -92,7 +207,10 @@ fn parse_arguments() -> Result<KotoArgs> {
     let script = args.subcommand()?;
 
     let script_args = match args.free() {
-        Ok(extra_args) => extra_args,
+        Ok(_) => extra_args
+            .into_iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect(),
         Err(e) => match e {
             pico_args::Error::UnusedArgsLeft(unused) => {
                 bail!("Unsupported argument: {}", unused.first().unwrap())
@@ -107,6 +225,9 @@ fn parse_arguments() -> Result<KotoArgs> {
         eval_script,
         run_tests,
         run_import_tests,
+        run_doc,
+        strict,
+        cfg_flags,
         show_bytecode,
         show_instructions,
         script,
@@ -116,6 +237,11 @@ fn parse_arguments() -> Result<KotoArgs> {
 }
 
 fn main() -> Result<()> {
+    // Standalone executables run their bundled script, with all arguments passed to the script
+    if let Some(bundle) = Bundle::from_current_exe()? {
+        return run_bundle(bundle);
+    }
+
     let args = match parse_arguments() {
         Ok(args) => args,
         Err(error) => {
@@ -135,16 +261,34 @@ fn main() -> Result<()> {
 
     let koto_settings = KotoSettings {
         run_tests: args.run_tests || args.run_import_tests,
+        strict: args.strict,
+        cfg_flags: args.cfg_flags.clone(),
         vm_settings: KotoVmSettings {
             run_import_tests: args.run_import_tests,
+            stdin: stdin(),
             ..Default::default()
         },
         ..Default::default()
     };
 
+    // Subcommand names are treated as scripts when they refer to a file in the current directory
+    if let Some(name) = args.script.as_deref() {
+        if !args.eval_script && !Path::new(name).is_file() {
+            match name {
+                "install" => return run_install(&args),
+                "build" => return run_build(&args),
+                "analyze" => return run_analyze(&args),
+                "minify" => return run_minify(&args),
+                "refactor" => return run_refactor(&args),
+                "repl" => return run_repl(&args, koto_settings),
+                _ => {}
+            }
+        }
+    }
+
     let mut stdin = io::stdin();
 
-    let (script, script_path) = if let Some(script) = args.script {
+    let (script, script_path) = if let Some(script) = args.script.clone() {
         if args.eval_script {
             (Some(script), None)
         } else {
@@ -166,69 +310,472 @@ fn main() -> Result<()> {
     };
 
     if let Some(script) = script {
-        let mut koto = Koto::with_settings(koto_settings);
-        if let Err(error) = koto.set_script_path(script_path.as_deref().map(Path::new)) {
-            bail!("{error}");
+        if args.run_doc {
+            return doc::run_document(
+                &script,
+                script_path.as_deref().map(Path::new),
+                koto_settings,
+                args.run_tests,
+            );
         }
 
-        add_modules(&koto);
+        run_script(
+            &script,
+            script_path.as_deref().map(Path::new),
+            &args,
+            koto_settings,
+        )
+    } else {
+        start_repl(&args, koto_settings, Vec::new())
+    }
+}
 
-        match koto.compile(&script) {
-            Ok(chunk) => {
-                if args.show_bytecode {
-                    println!("{}\n", &Chunk::bytes_as_string(&chunk));
-                }
-                if args.show_instructions {
-                    println!("Constants\n---------\n{}\n", chunk.constants);
-
-                    let script_lines = script.lines().collect::<Vec<_>>();
-                    println!(
-                        "Instructions\n------------\n{}",
-                        Chunk::instructions_as_string(chunk, &script_lines)
-                    );
-                }
-                koto.set_args(&args.script_args)?;
-                match koto.run() {
-                    Ok(_) => {}
-                    Err(error) if error.source().is_some() => {
-                        bail!("{error}\n{}", error.source().unwrap())
-                    }
-                    Err(error) => {
-                        bail!("{error}")
-                    }
-                }
+fn run_repl(args: &KotoArgs, koto_settings: KotoSettings) -> Result<()> {
+    let mut repl_args =
+        pico_args::Arguments::from_vec(args.script_args.iter().map(Into::into).collect());
+    let load = repl_args.values_from_str::<_, PathBuf>("--load")?;
+    if let [unexpected, ..] = repl_args.free()?.as_slice() {
+        bail!("Unsupported argument: {unexpected}\n\nUSAGE:\n    koto repl [--load PATH]...");
+    }
+
+    start_repl(args, koto_settings, load)
+}
+
+fn start_repl(args: &KotoArgs, koto_settings: KotoSettings, load: Vec<PathBuf>) -> Result<()> {
+    let config = load_config(args.config_file.as_ref())?;
+
+    Repl::with_settings(
+        ReplSettings {
+            show_instructions: args.show_instructions,
+            show_bytecode: args.show_bytecode,
+            colored_output: config.colored_output,
+            edit_mode: config.edit_mode,
+            load,
+        },
+        koto_settings,
+    )?
+    .run()
+}
+
+fn run_script(
+    script: &str,
+    script_path: Option<&Path>,
+    args: &KotoArgs,
+    koto_settings: KotoSettings,
+) -> Result<()> {
+    let mut koto = init_script_runtime(script_path, koto_settings)?;
+
+    match koto.compile(script) {
+        Ok(chunk) => {
+            if !chunk.warnings.is_empty() {
+                eprint!("{}", chunk.warnings_as_string());
+            }
+            if args.show_bytecode {
+                println!("{}\n", &Chunk::bytes_as_string(&chunk));
             }
-            Err(error) => {
-                bail!("{error}")
+            if args.show_instructions {
+                println!("Constants\n---------\n{}\n", chunk.constants);
+
+                let script_lines = script.lines().collect::<Vec<_>>();
+                println!(
+                    "Instructions\n------------\n{}",
+                    Chunk::instructions_as_string(chunk, &script_lines)
+                );
             }
         }
+        Err(error) => {
+            bail!("{error}")
+        }
+    }
 
-        Ok(())
-    } else {
-        let config = load_config(args.config_file.as_ref())?;
-
-        Repl::with_settings(
-            ReplSettings {
-                show_instructions: args.show_instructions,
-                show_bytecode: args.show_bytecode,
-                colored_output: config.colored_output,
-                edit_mode: config.edit_mode,
-            },
-            koto_settings,
-        )?
+    run_compiled_script(&mut koto, args)
+}
+
+// Prepares a Koto instance for running a script
+fn init_script_runtime(script_path: Option<&Path>, koto_settings: KotoSettings) -> Result<Koto> {
+    let mut koto = Koto::with_settings(koto_settings);
+    if let Err(error) = koto.set_script_path(script_path) {
+        bail!("{error}");
+    }
+
+    add_modules(&koto);
+    signals::install_signal_handlers(koto.signals())?;
+    koto.diagnostics()
+        .set_callback(|diagnostic: &Diagnostic| eprintln!("Warning: {diagnostic}"));
+
+    Ok(koto)
+}
+
+// Runs the script that was compiled or set in the Koto instance, followed by the selected command
+fn run_compiled_script(koto: &mut Koto, args: &KotoArgs) -> Result<()> {
+    koto.set_args(&args.script_args)?;
+    let result = koto
         .run()
+        .and_then(|_| run_command(koto, &args.script_args));
+    match result {
+        Ok(_) => Ok(()),
+        Err(error) if error.source().is_some() => {
+            bail!("{error}\n{}", error.source().unwrap())
+        }
+        Err(error) => {
+            bail!("{error}")
+        }
+    }
+}
+
+// Runs a script that was bundled into a standalone executable
+fn run_bundle(bundle: Bundle) -> Result<()> {
+    // The assets are extracted to a temporary directory so that they can be found relative to the
+    // script. The directory is only accessible by the current user, and is removed when it's
+    // dropped.
+    let dir = tempfile::tempdir()?;
+    bundle.extract_assets(dir.path())?;
+
+    // The script's source is written alongside the assets so that the script has a valid path
+    let script_path = dir.path().join(&bundle.script_name);
+    let chunk = Chunk::from_bytes(&bundle.chunk, Some(&script_path))?;
+    fs::write(&script_path, &chunk.debug_info.source)?;
+
+    let args = KotoArgs {
+        strict: bundle.strict,
+        script_args: env::args().skip(1).collect(),
+        ..Default::default()
+    };
+    let koto_settings = KotoSettings {
+        run_tests: false,
+        strict: bundle.strict,
+        cfg_flags: bundle.cfg_flags.clone(),
+        vm_settings: KotoVmSettings {
+            stdin: stdin(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let mut koto = init_script_runtime(Some(&script_path), koto_settings)?;
+    koto.set_chunk(chunk.into());
+    run_compiled_script(&mut koto, &args)
+}
+
+fn run_build(args: &KotoArgs) -> Result<()> {
+    const BUILD_USAGE: &str =
+        "USAGE:\n    koto build --standalone <script> [--output PATH] [--asset PATH]...";
+
+    let mut build_args =
+        pico_args::Arguments::from_vec(args.script_args.iter().map(Into::into).collect());
+    let standalone = build_args.contains("--standalone");
+    let output = build_args.opt_value_from_str::<_, String>("--output")?;
+    let assets = build_args.values_from_str::<_, String>("--asset")?;
+    let script_path = match build_args.free()?.as_slice() {
+        [script_path] => PathBuf::from(script_path),
+        [] => bail!("Missing script path\n\n{BUILD_USAGE}"),
+        [_, unexpected, ..] => bail!("Unsupported argument: {unexpected}"),
+    };
+    if !standalone {
+        bail!("Only standalone builds are currently supported\n\n{BUILD_USAGE}");
+    }
+
+    let script = fs::read_to_string(&script_path)
+        .with_context(|| format!("Failed to load '{}'", script_path.display()))?;
+    let mut koto = Koto::with_settings(KotoSettings {
+        strict: args.strict,
+        cfg_flags: args.cfg_flags.clone(),
+        ..Default::default()
+    });
+    if let Err(error) = koto.set_script_path(Some(&script_path)) {
+        bail!("{error}");
+    }
+    let chunk = match koto.compile(&script) {
+        Ok(chunk) => chunk,
+        Err(error) => bail!("Failed to compile '{}': {error}", script_path.display()),
+    };
+    if !chunk.warnings.is_empty() {
+        eprint!("{}", chunk.warnings_as_string());
+    }
+
+    let script_path = script_path.canonicalize()?;
+    let (Some(script_dir), Some(script_name)) = (script_path.parent(), script_path.file_name())
+    else {
+        bail!("Invalid script path '{}'", script_path.display());
+    };
+
+    let mut bundle = Bundle {
+        strict: args.strict,
+        cfg_flags: args.cfg_flags.clone(),
+        script_name: script_name.to_string_lossy().into_owned(),
+        chunk: chunk.to_bytes(),
+        assets: Vec::new(),
+    };
+    for asset in assets.iter() {
+        bundle.add_asset(script_dir, Path::new(asset))?;
+    }
+
+    let output = match output {
+        Some(output) => PathBuf::from(output),
+        None => {
+            let name = script_path
+                .file_stem()
+                .unwrap_or(script_name)
+                .to_string_lossy();
+            PathBuf::from(format!("{name}{}", env::consts::EXE_SUFFIX))
+        }
+    };
+    bundle.write_executable(&env::current_exe()?, &output)?;
+
+    println!("Built '{}'", output.display());
+    Ok(())
+}
+
+fn run_analyze(args: &KotoArgs) -> Result<()> {
+    const ANALYZE_USAGE: &str =
+        "USAGE:\n    koto analyze <script> --call-graph [--format FORMAT] [--output PATH]";
+
+    let mut analyze_args =
+        pico_args::Arguments::from_vec(args.script_args.iter().map(Into::into).collect());
+    let call_graph = analyze_args.contains("--call-graph");
+    let format = analyze_args.opt_value_from_str::<_, String>("--format")?;
+    let output = analyze_args.opt_value_from_str::<_, String>("--output")?;
+    let script_path = match analyze_args.free()?.as_slice() {
+        [script_path] => PathBuf::from(script_path),
+        [] => bail!("Missing script path\n\n{ANALYZE_USAGE}"),
+        [_, unexpected, ..] => bail!("Unsupported argument: {unexpected}"),
+    };
+    if !call_graph {
+        bail!("Only call graph reports are currently supported\n\n{ANALYZE_USAGE}");
+    }
+    let format = match format.as_deref() {
+        None => GraphFormat::Json,
+        Some(name) => match GraphFormat::from_name(name) {
+            Some(format) => format,
+            None => bail!("Unsupported format '{name}', expected 'json' or 'dot'"),
+        },
+    };
+
+    let report = CallGraph::analyze(&script_path)?.render(format);
+
+    match output {
+        Some(output) => {
+            fs::write(&output, report).with_context(|| format!("Failed to write '{output}'"))?
+        }
+        None => print!("{report}"),
+    }
+
+    Ok(())
+}
+
+fn run_refactor(args: &KotoArgs) -> Result<()> {
+    const REFACTOR_USAGE: &str = "USAGE:\n    \
+        koto refactor rename <old> <new> [--dir DIR] [--strings] [--comments] [--dry-run]";
+
+    let mut refactor_args =
+        pico_args::Arguments::from_vec(args.script_args.iter().map(Into::into).collect());
+    let dir = refactor_args.opt_value_from_str::<_, String>("--dir")?;
+    let settings = RenameSettings {
+        include_strings: refactor_args.contains("--strings"),
+        include_comments: refactor_args.contains("--comments"),
+    };
+    let dry_run = refactor_args.contains("--dry-run");
+    let (old_name, new_name) = match refactor_args.free()?.as_slice() {
+        [command, old_name, new_name] if command == "rename" => {
+            (old_name.clone(), new_name.clone())
+        }
+        [command, ..] if command != "rename" => {
+            bail!("Unsupported refactoring '{command}'\n\n{REFACTOR_USAGE}")
+        }
+        [_, _, _, unexpected, ..] => bail!("Unsupported argument: {unexpected}"),
+        _ => bail!("Missing names\n\n{REFACTOR_USAGE}"),
+    };
+
+    let dir = PathBuf::from(dir.as_deref().unwrap_or("."));
+    let renamed = refactor::rename_in_dir(&dir, &old_name, &new_name, settings, dry_run)?;
+
+    let action = if dry_run { "Would rename" } else { "Renamed" };
+    for script in renamed.iter() {
+        println!(
+            "{action} {} occurrence{} in '{}'",
+            script.count,
+            if script.count == 1 { "" } else { "s" },
+            script.path.display()
+        );
+    }
+    if renamed.is_empty() {
+        println!("'{old_name}' wasn't found in '{}'", dir.display());
+    }
+
+    Ok(())
+}
+
+fn run_minify(args: &KotoArgs) -> Result<()> {
+    let mut minify_args =
+        pico_args::Arguments::from_vec(args.script_args.iter().map(Into::into).collect());
+    let output = minify_args.opt_value_from_str::<_, String>("--output")?;
+    let settings = MinifySettings {
+        string_table: minify_args.contains("--string-table"),
+    };
+    let script_path = match minify_args.free()?.as_slice() {
+        [script_path] => PathBuf::from(script_path),
+        [] => bail!(
+            "Missing script path\n\nUSAGE:\n    \
+            koto minify <script> [--output PATH] [--string-table]"
+        ),
+        [_, unexpected, ..] => bail!("Unsupported argument: {unexpected}"),
+    };
+
+    let script = fs::read_to_string(&script_path)
+        .with_context(|| format!("Failed to load '{}'", script_path.display()))?;
+    let minified = minify(&script, settings)
+        .with_context(|| format!("Failed to minify '{}'", script_path.display()))?;
+
+    match output {
+        Some(output) => {
+            fs::write(&output, minified).with_context(|| format!("Failed to write '{output}'"))?
+        }
+        None => print!("{minified}"),
+    }
+
+    Ok(())
+}
+
+fn run_install(args: &KotoArgs) -> Result<()> {
+    if !args.cfg_flags.is_empty() {
+        bail!("--cfg can't be preserved in installed scripts");
+    }
+
+    let mut install_args =
+        pico_args::Arguments::from_vec(args.script_args.iter().map(Into::into).collect());
+    let name = install_args.opt_value_from_str("--name")?;
+    let dir = install_args.opt_value_from_str::<_, String>("--dir")?;
+    let script_path = match install_args.free()?.as_slice() {
+        [script_path] => PathBuf::from(script_path),
+        [] => bail!(
+            "Missing script path\n\nUSAGE:\n    koto install <script> [--name NAME] [--dir DIR]"
+        ),
+        [_, unexpected, ..] => bail!("Unsupported argument: {unexpected}"),
+    };
+
+    let command_path = install::install_script(
+        &script_path,
+        install::InstallSettings {
+            name,
+            dir: dir.map(PathBuf::from),
+            strict: args.strict,
+        },
+    )?;
+
+    println!("Installed '{}'", command_path.display());
+    if let Some(dir) = command_path.parent() {
+        if !install::is_dir_in_path(dir) {
+            println!("Note: '{}' isn't included in PATH", dir.display());
+        }
+    }
+
+    Ok(())
+}
+
+// Runs the command selected by the first script argument, if the script defines commands
+fn run_command(koto: &mut Koto, script_args: &[String]) -> koto::Result<()> {
+    let commands = koto.commands();
+    if commands.is_empty() {
+        return Ok(());
+    }
+
+    let command_list = commands
+        .iter()
+        .map(|command| format!("    {command}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    match script_args.split_first() {
+        Some((name, command_args)) if commands.iter().any(|command| command.as_str() == name) => {
+            koto.run_command(name, command_args)?;
+        }
+        Some((name, _)) => {
+            let suggestion = koto_fuzzy::matching::best_matches(
+                name,
+                commands.iter().map(|command| command.as_str()),
+                Some(1),
+                koto_fuzzy::matching::Scoring {
+                    min_score: 0.5,
+                    ..Default::default()
+                },
+            )
+            .first()
+            .map(|(index, _)| format!(" (did you mean '{}'?)", commands[*index]))
+            .unwrap_or_default();
+            return Err(format!(
+                "Unknown command '{name}'{suggestion}\n\nCOMMANDS:\n{command_list}"
+            )
+            .into());
+        }
+        None => println!("COMMANDS:\n{command_list}"),
+    }
+
+    Ok(())
+}
+
+// Interactive input like `io.select` is supported when stdin is a terminal
+fn stdin() -> Ptr<dyn KotoFile> {
+    if io::stdin().is_tty() {
+        make_ptr!(terminal::TerminalStdin::default())
+    } else {
+        make_ptr!(koto::runtime::DefaultStdin::default())
+    }
+}
+
+// Progress bars are shown when stderr is a terminal, otherwise progress isn't reported
+fn progress_module() -> KMap {
+    if io::stderr().is_tty() {
+        koto_progress::make_module_with_reporter(koto_progress::ProgressBar::stderr())
+    } else {
+        koto_progress::make_module()
     }
 }
 
 fn add_modules(koto: &Koto) {
     let prelude = koto.prelude();
+    prelude.insert("archive", koto_archive::make_module());
+    prelude.insert("cache", koto_cache::make_module());
+    prelude.insert("canvas", koto_canvas::make_module());
     prelude.insert("color", koto_color::make_module());
+    prelude.insert("containers", koto_containers::make_module());
+    prelude.insert("diff", koto_diff::make_module());
+    prelude.insert("ease", koto_ease::make_module());
+    prelude.insert("fake", koto_fake::make_module());
+    prelude.insert("fsm", koto_fsm::make_module());
+    prelude.insert("fuzzy", koto_fuzzy::make_module());
     prelude.insert("geometry", koto_geometry::make_module());
+    prelude.insert("i18n", koto_i18n::make_module());
+    prelude.insert("image", koto_image::make_module());
+    prelude.insert("ini", koto_ini::make_module());
+    prelude.insert("ipc", koto_ipc::make_module());
     prelude.insert("json", koto_json::make_module());
+    prelude.insert("locale", koto_locale::make_module());
+    prelude.insert("metrics", koto_metrics::make_module());
+    prelude.insert("midi", koto_midi::make_module());
+    prelude.insert("money", koto_money::make_module());
+    prelude.insert("noise", koto_noise::make_module());
+    prelude.insert("osc", koto_osc::make_module());
+    #[cfg(feature = "parquet")]
+    prelude.insert("parquet", koto_parquet::make_module());
+    prelude.insert("parse", koto_parse::make_module());
+    prelude.insert("plot", koto_plot::make_module());
+    prelude.insert("progress", progress_module());
+    prelude.insert("protobuf", koto_protobuf::make_module());
     prelude.insert("random", koto_random::make_module());
+    prelude.insert("readline", koto_readline::make_module());
     prelude.insert("regex", koto_regex::make_module());
+    prelude.insert("rules", koto_rules::make_module());
+    prelude.insert("schedule", koto_schedule::make_module());
+    prelude.insert("semver", koto_semver::make_module());
+    prelude.insert("sound", koto_sound::make_module());
+    prelude.insert("spatial", koto_spatial::make_module());
+    prelude.insert("store", koto_store::make_module());
+    prelude.insert("table", koto_table::make_module());
     prelude.insert("tempfile", koto_tempfile::make_module());
     prelude.insert("toml", koto_toml::make_module());
+    prelude.insert("trace", koto_trace::make_module());
+    prelude.insert("units", koto_units::make_module());
+    prelude.insert("validate", koto_validate::make_module());
     prelude.insert("yaml", koto_yaml::make_module());
 }
 
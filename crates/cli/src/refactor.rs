@@ -0,0 +1,74 @@
+use anyhow::{Context, Result};
+use koto::parser::{rename_symbol, RenameSettings};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+// A script that was changed by `koto refactor rename`
+pub struct RenamedScript {
+    pub path: PathBuf,
+    // The number of occurrences of the symbol that were renamed
+    pub count: usize,
+}
+
+// Renames a symbol in all of the Koto scripts found in a directory and its subdirectories
+//
+// All scripts are renamed before any are written, so that scripts are left unchanged if renaming
+// fails in any of them. Scripts are only written when `dry_run` is false.
+//
+// Returns the scripts that contained the symbol.
+pub fn rename_in_dir(
+    dir: &Path,
+    old_name: &str,
+    new_name: &str,
+    settings: RenameSettings,
+    dry_run: bool,
+) -> Result<Vec<RenamedScript>> {
+    let mut scripts = Vec::new();
+    find_scripts(dir, &mut scripts)?;
+    scripts.sort();
+
+    let mut renamed = Vec::new();
+    for path in scripts {
+        let script = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to load '{}'", path.display()))?;
+        let result = rename_symbol(&script, old_name, new_name, settings)
+            .with_context(|| format!("Failed to rename '{old_name}' in '{}'", path.display()))?;
+
+        if !result.renamed.is_empty() {
+            renamed.push((path, result.source, result.renamed.len()));
+        }
+    }
+
+    if !dry_run {
+        for (path, source, _) in renamed.iter() {
+            fs::write(path, source)
+                .with_context(|| format!("Failed to write '{}'", path.display()))?;
+        }
+    }
+
+    Ok(renamed
+        .into_iter()
+        .map(|(path, _, count)| RenamedScript { path, count })
+        .collect())
+}
+
+fn find_scripts(dir: &Path, scripts: &mut Vec<PathBuf>) -> Result<()> {
+    let entries =
+        fs::read_dir(dir).with_context(|| format!("Failed to read '{}'", dir.display()))?;
+
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_scripts(&path, scripts)?;
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "koto")
+        {
+            scripts.push(path);
+        }
+    }
+
+    Ok(())
+}
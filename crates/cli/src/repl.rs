@@ -1,10 +1,10 @@
 use std::{
     fmt,
     io::{self, Stdout, Write},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use crossterm::{
     execute, style,
     terminal::{self},
@@ -31,12 +31,16 @@ const INDENT_SIZE: usize = 2;
 const HISTORY_DIR: &str = ".koto";
 const HISTORY_FILE: &str = "repl_history.txt";
 const MAX_HISTORY_ENTRIES: usize = 500;
+const RC_FILE: &str = "repl.koto";
+const SAVE_COMMAND: &str = ":save";
 
 pub struct ReplSettings {
     pub show_bytecode: bool,
     pub show_instructions: bool,
     pub colored_output: bool,
     pub edit_mode: EditMode,
+    // Scripts that should be run before the first prompt, after the rc file
+    pub load: Vec<PathBuf>,
 }
 
 pub struct Repl {
@@ -49,6 +53,8 @@ pub struct Repl {
     continued_lines: Vec<String>,
     indent: usize,
     colored_output: bool,
+    // The inputs that were successfully run during the session, used by `:save`
+    session: Vec<String>,
 }
 
 fn history_dir() -> Option<PathBuf> {
@@ -98,6 +104,7 @@ impl Repl {
             continued_lines: Vec::new(),
             indent: 0,
             colored_output,
+            session: Vec::new(),
         })
     }
 
@@ -105,6 +112,8 @@ impl Repl {
         let version = env!("CARGO_PKG_VERSION");
         writeln!(self.stdout, "Welcome to Koto v{version}")?;
 
+        self.run_startup_scripts()?;
+
         loop {
             let result = if self.continued_lines.is_empty() {
                 self.editor.readline(PROMPT)
@@ -142,7 +151,67 @@ impl Repl {
         Ok(())
     }
 
+    // Runs the rc file if it exists, followed by the scripts provided with --load
+    //
+    // Top-level IDs in the scripts are exported, making them available in the session.
+    fn run_startup_scripts(&mut self) -> Result<()> {
+        let rc_path = history_dir()
+            .map(|mut path| {
+                path.push(RC_FILE);
+                path
+            })
+            .filter(|path| path.exists());
+        let load = std::mem::take(&mut self.settings.load);
+
+        for path in rc_path.iter().chain(load.iter()) {
+            let script = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to load '{}'", path.display()))?;
+
+            // The script path is set while the script runs so that imports are resolved relative
+            // to the script's directory.
+            self.koto.set_script_path(Some(path))?;
+            let result = self.koto.compile_and_run(&script);
+            self.koto.set_script_path(None)?;
+
+            if let Err(error) = result {
+                self.print_error(&error)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Writes the inputs that were successfully run during the session to a file
+    fn save_session(&mut self, path: &str) -> Result<()> {
+        if path.is_empty() {
+            return self.print_error(&format!("Missing path, usage: {SAVE_COMMAND} PATH"));
+        }
+
+        let mut contents = self.session.join("\n");
+        contents.push('\n');
+
+        match std::fs::write(Path::new(path), contents) {
+            Ok(_) => print_wrapped!(
+                self.stdout,
+                "Saved {} input(s) to '{path}'\n\n",
+                self.session.len()
+            )?,
+            Err(error) => self.print_error(&format!("Failed to save to '{path}': {error}"))?,
+        }
+
+        Ok(())
+    }
+
     fn on_line(&mut self, line: &str) -> Result<()> {
+        if self.continued_lines.is_empty() {
+            if let Some(path) = line.trim().strip_prefix(SAVE_COMMAND) {
+                if path.is_empty() || path.starts_with(char::is_whitespace) {
+                    self.editor.add_history_entry(line)?;
+                    return self.save_session(path.trim());
+                }
+            }
+        }
+
         let input_is_whitespace = line.chars().all(|c| c.is_whitespace());
 
         let mut indent_next_line = false;
@@ -172,18 +241,22 @@ impl Repl {
                         )?;
                     }
                     match self.koto.run() {
-                        Ok(result) => match self.koto.value_to_string(result.clone()) {
-                            Ok(result_string) => {
-                                self.print_result(&result_string)?;
+                        Ok(result) => {
+                            self.session.push(input.clone());
+
+                            match self.koto.value_to_repl_string(result.clone()) {
+                                Ok(result_string) => {
+                                    self.print_result(&result_string)?;
+                                }
+                                Err(e) => {
+                                    print_wrapped!(
+                                        self.stdout,
+                                        "Error while getting display string for return value ({})",
+                                        e
+                                    )?;
+                                }
                             }
-                            Err(e) => {
-                                print_wrapped!(
-                                    self.stdout,
-                                    "Error while getting display string for return value ({})",
-                                    e
-                                )?;
-                            }
-                        },
+                        }
                         Err(error) => {
                             if let Some(help) = self.run_help(&input) {
                                 print_wrapped!(self.stdout, "{}\n", help)?;
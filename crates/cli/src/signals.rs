@@ -0,0 +1,40 @@
+use koto::runtime::Signals;
+
+// Passes process signals to the script's handlers
+//
+// Signals that the script hasn't registered a handler for retain their default behaviour,
+// e.g. Ctrl-C will still stop a script that doesn't handle `int`.
+#[cfg(unix)]
+pub fn install_signal_handlers(signals: &Signals) -> anyhow::Result<()> {
+    use koto::runtime::Signal;
+    use signal_hook::{consts::*, low_level};
+
+    for signal in Signal::ALL {
+        let signal_id = match signal {
+            Signal::Interrupt => SIGINT,
+            Signal::Terminate => SIGTERM,
+            Signal::Hangup => SIGHUP,
+            Signal::User1 => SIGUSR1,
+            Signal::User2 => SIGUSR2,
+        };
+        let signals = signals.clone();
+        // Safety: the handler only makes use of atomic operations and
+        // `emulate_default_handler`, which are safe to call from within a signal handler.
+        unsafe {
+            low_level::register(signal_id, move || {
+                if signals.is_handled(signal) {
+                    signals.raise(signal);
+                } else {
+                    let _ = low_level::emulate_default_handler(signal_id);
+                }
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn install_signal_handlers(_signals: &Signals) -> anyhow::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    terminal,
+};
+use koto::{
+    prelude::*,
+    runtime::{DefaultStdin, KeyInput},
+    Result,
+};
+
+// The stdin used when running scripts in a terminal
+//
+// Line input is provided by the default stdin, with key input and hidden line input being
+// supported by temporarily switching the terminal to raw mode.
+#[derive(Default)]
+pub struct TerminalStdin(DefaultStdin);
+
+impl KotoFile for TerminalStdin {
+    fn id(&self) -> KString {
+        self.0.id()
+    }
+
+    fn is_terminal(&self) -> bool {
+        true
+    }
+}
+
+impl KotoWrite for TerminalStdin {}
+impl KotoRead for TerminalStdin {
+    fn read_line(&self) -> Result<Option<String>> {
+        self.0.read_line()
+    }
+
+    fn read_to_string(&self) -> Result<String> {
+        self.0.read_to_string()
+    }
+
+    fn read_line_hidden(&self) -> Result<Option<String>> {
+        with_raw_mode(|| {
+            let mut line = String::new();
+            loop {
+                let key = read_key_event()?;
+                match key.code {
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        return interrupted();
+                    }
+                    KeyCode::Char('d')
+                        if key.modifiers.contains(KeyModifiers::CONTROL) && line.is_empty() =>
+                    {
+                        return Ok(None);
+                    }
+                    KeyCode::Char(c) => line.push(c),
+                    KeyCode::Backspace => {
+                        line.pop();
+                    }
+                    KeyCode::Enter => {
+                        line.push('\n');
+                        return Ok(Some(line));
+                    }
+                    _ => {}
+                }
+            }
+        })
+    }
+
+    fn read_key(&self) -> Result<KeyInput> {
+        with_raw_mode(|| {
+            let key = read_key_event()?;
+            let result = match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return interrupted();
+                }
+                KeyCode::Up | KeyCode::Char('k') => KeyInput::Up,
+                KeyCode::Down | KeyCode::Char('j') => KeyInput::Down,
+                KeyCode::Enter => KeyInput::Enter,
+                KeyCode::Esc => KeyInput::Cancel,
+                _ => KeyInput::Other,
+            };
+            Ok(result)
+        })
+    }
+}
+
+// Runs the function with the terminal in raw mode, restoring the terminal afterwards
+fn with_raw_mode<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    if let Err(e) = terminal::enable_raw_mode() {
+        return runtime_error!("failed to enable raw mode: {e}");
+    }
+    let result = f();
+    if let Err(e) = terminal::disable_raw_mode() {
+        return runtime_error!("failed to disable raw mode: {e}");
+    }
+    result
+}
+
+// Waits for the next key press, ignoring other events
+fn read_key_event() -> Result<KeyEvent> {
+    loop {
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => return Ok(key),
+            Ok(_) => {}
+            Err(e) => return runtime_error!("failed to read input: {e}"),
+        }
+    }
+}
+
+// Ctrl-C doesn't interrupt the process while in raw mode, so an error is thrown instead
+fn interrupted<T>() -> Result<T> {
+    runtime_error!("input was interrupted")
+}
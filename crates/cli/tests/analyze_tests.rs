@@ -0,0 +1,153 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+// Creates an empty temporary directory for a test
+fn test_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("koto_analyze_tests_{}_{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("Failed to create test dir");
+    dir
+}
+
+// Writes the scripts to the directory, and then runs `koto analyze` on the first script
+fn run_koto_analyze(dir: &Path, scripts: &[(&str, &str)], analyze_args: &[&str]) -> String {
+    for (name, script) in scripts {
+        fs::write(dir.join(name), script).expect("Failed to write script");
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_koto"))
+        .arg("analyze")
+        .arg(dir.join(scripts[0].0))
+        .arg("--call-graph")
+        .args(analyze_args)
+        .output()
+        .expect("Failed to run koto");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    String::from_utf8(output.stdout).expect("Invalid output")
+}
+
+mod analyze_tests {
+    use super::*;
+
+    const MAIN: &str = "\
+from helpers import double
+import string
+
+run = |x|
+  y = double x
+  y.to_string()
+
+unused = || null
+
+export main = ||
+  run 42
+";
+
+    const HELPERS: &str = "\
+export double = |x| x * 2
+dead = || 0
+";
+
+    #[test]
+    fn call_graph_as_json() {
+        let dir = test_dir("json");
+        let report = run_koto_analyze(&dir, &[("main.koto", MAIN), ("helpers.koto", HELPERS)], &[]);
+
+        for expected in [
+            r#"{ "name": "helpers", "module": "helpers.koto" }"#,
+            r#"{ "name": "string", "module": null }"#,
+            r#""id": "main.koto::unused", "name": "unused", "line": 8, "exported": false, "unused": true"#,
+            r#""id": "main.koto::main", "name": "main", "line": 10, "exported": true, "unused": false"#,
+            r#""id": "helpers.koto::dead", "name": "dead", "line": 2, "exported": false, "unused": true"#,
+            r#"{ "caller": "main.koto::run", "callee": "helpers.koto::double", "line": 5, "kind": "call" }"#,
+            r#"{ "caller": "main.koto::main", "callee": "main.koto::run", "line": 11, "kind": "call" }"#,
+        ] {
+            assert!(
+                report.contains(expected),
+                "'{expected}' not found in:\n{report}"
+            );
+        }
+    }
+
+    #[test]
+    fn call_graph_as_dot() {
+        let dir = test_dir("dot");
+        let report = run_koto_analyze(
+            &dir,
+            &[("main.koto", MAIN), ("helpers.koto", HELPERS)],
+            &["--format", "dot"],
+        );
+
+        assert!(report.starts_with("digraph koto {"), "{report}");
+        for expected in [
+            r#""main.koto" -> "helpers.koto" [style=bold, label="import"];"#,
+            r#""main.koto::run" -> "helpers.koto::double";"#,
+            r#""main.koto::unused" [label="unused", style=dashed];"#,
+        ] {
+            assert!(
+                report.contains(expected),
+                "'{expected}' not found in:\n{report}"
+            );
+        }
+    }
+
+    #[test]
+    fn functions_passed_as_values_are_referenced() {
+        let dir = test_dir("references");
+        let script = "\
+square = |x| x * x
+export squares = ||
+  (1..=3).each square
+";
+        let report = run_koto_analyze(&dir, &[("main.koto", script)], &[]);
+
+        assert!(
+            report.contains(r#""name": "square", "line": 1, "exported": false, "unused": false"#)
+        );
+        assert!(report.contains(
+            r#"{ "caller": "main.koto::squares", "callee": "main.koto::square", "line": 3, "kind": "reference" }"#
+        ));
+    }
+
+    #[test]
+    fn report_is_written_to_output_file() {
+        let dir = test_dir("output");
+        let output_path = dir.join("graph.dot");
+        let stdout = run_koto_analyze(
+            &dir,
+            &[("main.koto", MAIN), ("helpers.koto", HELPERS)],
+            &[
+                "--format",
+                "dot",
+                "--output",
+                &output_path.to_string_lossy(),
+            ],
+        );
+
+        assert!(stdout.is_empty());
+        let report = fs::read_to_string(output_path).expect("Failed to read report");
+        assert!(report.starts_with("digraph koto {"));
+    }
+
+    #[test]
+    fn missing_call_graph_flag() {
+        let dir = test_dir("missing_flag");
+        let script_path = dir.join("main.koto");
+        fs::write(&script_path, MAIN).expect("Failed to write script");
+
+        let output = Command::new(env!("CARGO_BIN_EXE_koto"))
+            .arg("analyze")
+            .arg(&script_path)
+            .output()
+            .expect("Failed to run koto");
+        assert!(!output.status.success());
+    }
+}
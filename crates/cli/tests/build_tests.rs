@@ -0,0 +1,266 @@
+use std::{env, fs, path::PathBuf, process::Command};
+
+// Creates an empty temporary directory for a test
+fn test_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("koto_build_tests_{}_{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("Failed to create test dir");
+    dir
+}
+
+fn run_koto(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_koto"))
+        .args(args)
+        .output()
+        .expect("Failed to run koto")
+}
+
+mod build_tests {
+    use super::*;
+
+    #[test]
+    fn standalone_executable_with_assets() {
+        let dir = test_dir("standalone");
+        fs::create_dir_all(dir.join("data")).unwrap();
+        fs::write(dir.join("data").join("message.txt"), "Hello from an asset").unwrap();
+        fs::write(dir.join("helper.koto"), "export answer = 42\n").unwrap();
+        let script_path = dir.join("app.koto");
+        fs::write(
+            &script_path,
+            "\
+from helper import answer
+print io.read_to_string io.extend_path koto.script_dir, 'data', 'message.txt'
+print answer
+print koto.args
+",
+        )
+        .unwrap();
+
+        let output_path = dir.join(format!("app{}", env::consts::EXE_SUFFIX));
+        let output = run_koto(&[
+            "build",
+            "--standalone",
+            &script_path.to_string_lossy(),
+            "--asset",
+            &dir.join("data").to_string_lossy(),
+            "--asset",
+            &dir.join("helper.koto").to_string_lossy(),
+            "--output",
+            &output_path.to_string_lossy(),
+        ]);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        // The script's files are removed to check that the executable only uses its bundle
+        fs::remove_dir_all(dir.join("data")).unwrap();
+        fs::remove_file(dir.join("helper.koto")).unwrap();
+        fs::remove_file(&script_path).unwrap();
+
+        let output = Command::new(&output_path)
+            .args(["a", "--b"])
+            .output()
+            .expect("Failed to run the standalone executable");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "Hello from an asset\n42\n('a', '--b')\n"
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn cfg_flags_are_applied_to_the_bundled_script() {
+        let dir = test_dir("cfg_flags");
+        fs::write(
+            dir.join("helper.koto"),
+            "@cfg 'debug'\n  print 'debug helper'\n",
+        )
+        .unwrap();
+        let script_path = dir.join("app.koto");
+        fs::write(
+            &script_path,
+            "\
+import helper
+@cfg 'debug'
+  print 'debug'
+@cfg 'release'
+  print 'release'
+",
+        )
+        .unwrap();
+
+        let output_path = dir.join(format!("app{}", env::consts::EXE_SUFFIX));
+        let output = run_koto(&[
+            "--cfg",
+            "debug",
+            "build",
+            "--standalone",
+            &script_path.to_string_lossy(),
+            "--asset",
+            &dir.join("helper.koto").to_string_lossy(),
+            "--output",
+            &output_path.to_string_lossy(),
+        ]);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let output = Command::new(&output_path)
+            .output()
+            .expect("Failed to run the standalone executable");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "debug helper\ndebug\n"
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn embedded_files_are_compiled_into_the_executable() {
+        let dir = test_dir("embedded_files");
+        fs::create_dir_all(dir.join("data")).unwrap();
+        fs::write(dir.join("data").join("table.csv"), "a,b\n1,2\n").unwrap();
+        let script_path = dir.join("app.koto");
+        fs::write(&script_path, "print (@embed 'data/table.csv').trim()\n").unwrap();
+
+        // The script is built from a different directory to check that embedded files are found
+        // relative to the script
+        let output_path = dir.join(format!("app{}", env::consts::EXE_SUFFIX));
+        let output = Command::new(env!("CARGO_BIN_EXE_koto"))
+            .args([
+                "build",
+                "--standalone",
+                &script_path.to_string_lossy(),
+                "--output",
+                &output_path.to_string_lossy(),
+            ])
+            .current_dir(env::temp_dir())
+            .output()
+            .expect("Failed to run koto");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        fs::remove_dir_all(dir.join("data")).unwrap();
+
+        let output = Command::new(&output_path)
+            .output()
+            .expect("Failed to run the standalone executable");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "a,b\n1,2\n");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn assets_outside_of_the_script_dir_are_rejected() {
+        let dir = test_dir("outside_asset");
+        fs::create_dir_all(dir.join("app")).unwrap();
+        let script_path = dir.join("app").join("app.koto");
+        fs::write(&script_path, "print 'hi'\n").unwrap();
+        fs::write(dir.join("outside.txt"), "").unwrap();
+
+        let output = run_koto(&[
+            "build",
+            "--standalone",
+            &script_path.to_string_lossy(),
+            "--asset",
+            &dir.join("outside.txt").to_string_lossy(),
+            "--output",
+            &dir.join("app_exe").to_string_lossy(),
+        ]);
+        assert!(!output.status.success());
+        assert!(!dir.join("app_exe").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn assets_are_removed_after_running() {
+        let dir = test_dir("removed_assets");
+        let script_path = dir.join("app.koto");
+        fs::write(&script_path, "print koto.script_dir\n").unwrap();
+
+        let output_path = dir.join(format!("app{}", env::consts::EXE_SUFFIX));
+        let output = run_koto(&[
+            "build",
+            "--standalone",
+            &script_path.to_string_lossy(),
+            "--output",
+            &output_path.to_string_lossy(),
+        ]);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let output = Command::new(&output_path)
+            .output()
+            .expect("Failed to run the standalone executable");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let script_dir = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+        assert!(!script_dir.starts_with(&dir));
+        assert!(!script_dir.exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn executable_cant_be_named_koto() {
+        let dir = test_dir("named_koto");
+        let script_path = dir.join("app.koto");
+        fs::write(&script_path, "print 'hi'\n").unwrap();
+
+        let output_path = dir.join(format!("koto{}", env::consts::EXE_SUFFIX));
+        let output = run_koto(&[
+            "build",
+            "--standalone",
+            &script_path.to_string_lossy(),
+            "--output",
+            &output_path.to_string_lossy(),
+        ]);
+        assert!(!output.status.success());
+        assert!(!output_path.exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn build_requires_standalone() {
+        let dir = test_dir("requires_standalone");
+        let script_path = dir.join("app.koto");
+        fs::write(&script_path, "print 'hi'\n").unwrap();
+
+        let output = run_koto(&["build", &script_path.to_string_lossy()]);
+        assert!(!output.status.success());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,144 @@
+use std::process::{Command, Output};
+
+fn run_koto_doc(flags: &[&str], markdown: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_koto"))
+        .env("RUST_BACKTRACE", "0")
+        .arg("--doc")
+        .args(flags)
+        .arg("--eval")
+        .arg(markdown)
+        .output()
+        .expect("Failed to run koto")
+}
+
+mod doc_tests {
+    use super::*;
+
+    #[test]
+    fn blocks_share_scope() {
+        let markdown = "
+# Example
+
+```koto
+x = 40
+print x
+# -> 40
+```
+
+Some text between the blocks.
+
+```koto
+print x + 2
+# -> 42
+```
+";
+        let output = run_koto_doc(&[], markdown);
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "40\n42\n");
+    }
+
+    #[test]
+    fn skipped_blocks() {
+        let markdown = "
+```koto,skip_run
+this block isn't run
+```
+
+```koto,skip_check
+print 'hello'
+# -> not checked
+```
+
+```rust
+fn not_koto() {}
+```
+";
+        let output = run_koto_doc(&[], markdown);
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "hello\n");
+    }
+
+    #[test]
+    fn output_mismatch() {
+        let markdown = "
+```koto
+print 1 + 1
+# -> 3
+```
+";
+        let output = run_koto_doc(&[], markdown);
+        assert!(!output.status.success());
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("<doc>:3"), "{stderr}");
+        assert!(stderr.contains("Expected:\n3\n"), "{stderr}");
+        assert!(stderr.contains("Actual:\n2\n"), "{stderr}");
+    }
+
+    #[test]
+    fn running_stops_after_error() {
+        let markdown = "
+```koto
+print 'first'
+```
+
+```koto
+throw 'oops'
+```
+
+```koto
+print 'not reached'
+```
+";
+        let output = run_koto_doc(&[], markdown);
+        assert!(!output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "first\n");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("<doc>:7"), "{stderr}");
+        assert!(stderr.contains("oops"), "{stderr}");
+    }
+
+    #[test]
+    fn test_mode_summary() {
+        let markdown = "
+```koto
+print 'ok'
+# -> ok
+```
+
+```koto
+throw 'oops'
+```
+
+```koto
+print 'wrong'
+# -> right
+```
+";
+        let output = run_koto_doc(&["--tests"], markdown);
+        assert!(!output.status.success());
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("FAILED: <doc>:8"), "{stdout}");
+        assert!(stdout.contains("FAILED: <doc>:12"), "{stdout}");
+        assert!(stdout.contains("1 of 3 code blocks passed"), "{stdout}");
+        assert!(!stdout.contains("ok\n"), "{stdout}");
+    }
+
+    #[test]
+    fn test_mode_success() {
+        let markdown = "
+```koto
+print 'ok'
+# -> ok
+```
+";
+        let output = run_koto_doc(&["--tests"], markdown);
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "1 of 1 code blocks passed in <doc>\n"
+        );
+    }
+}
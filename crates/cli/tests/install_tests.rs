@@ -0,0 +1,126 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+// Creates an empty temporary directory for a test
+fn test_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("koto_install_tests_{}_{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("Failed to create test dir");
+    dir
+}
+
+fn run_koto_install(dir: &Path, flags: &[&str], script: &str, install_args: &[&str]) -> String {
+    let script_path = dir.join("script.koto");
+    fs::write(&script_path, script).expect("Failed to write script");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_koto"))
+        .args(flags)
+        .arg("install")
+        .arg(&script_path)
+        .args(["--dir", &dir.join("bin").to_string_lossy()])
+        .args(install_args)
+        .output()
+        .expect("Failed to run koto");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let installed = dir
+        .join("bin")
+        .join(install_args.last().copied().unwrap_or("script"));
+    fs::read_to_string(installed).expect("Failed to read installed script")
+}
+
+mod install_tests {
+    use super::*;
+
+    #[test]
+    fn install_script() {
+        let dir = test_dir("install_script");
+        let installed = run_koto_install(&dir, &[], "print 'hello'\n", &[]);
+        assert_eq!(installed, "#!/usr/bin/env koto\nprint 'hello'\n");
+    }
+
+    #[test]
+    fn existing_shebang_is_replaced() {
+        let dir = test_dir("existing_shebang");
+        let installed = run_koto_install(
+            &dir,
+            &[],
+            "#!/usr/local/bin/koto\nprint 'hello'\n",
+            &["--name", "hello"],
+        );
+        assert_eq!(installed, "#!/usr/bin/env koto\nprint 'hello'\n");
+    }
+
+    #[test]
+    fn strict_flag_is_preserved() {
+        let dir = test_dir("strict_flag");
+        let installed = run_koto_install(&dir, &["--strict"], "let x = 1\n", &[]);
+        assert_eq!(
+            installed,
+            "#!/usr/bin/env koto\n# koto: strict\nlet x = 1\n"
+        );
+    }
+
+    #[test]
+    fn script_with_errors_isnt_installed() {
+        let dir = test_dir("script_with_errors");
+        let script_path = dir.join("script.koto");
+        fs::write(&script_path, "x = (1 +\n").expect("Failed to write script");
+
+        let output = Command::new(env!("CARGO_BIN_EXE_koto"))
+            .arg("install")
+            .arg(&script_path)
+            .args(["--dir", &dir.join("bin").to_string_lossy()])
+            .output()
+            .expect("Failed to run koto");
+        assert!(!output.status.success());
+        assert!(!dir.join("bin").join("script").exists());
+    }
+
+    #[test]
+    fn script_with_embedded_files_isnt_installed() {
+        let dir = test_dir("embedded_files");
+        let script_path = dir.join("script.koto");
+        fs::write(dir.join("data.txt"), "data").expect("Failed to write data");
+        fs::write(&script_path, "print @embed 'data.txt'\n").expect("Failed to write script");
+
+        let output = Command::new(env!("CARGO_BIN_EXE_koto"))
+            .arg("install")
+            .arg(&script_path)
+            .args(["--dir", &dir.join("bin").to_string_lossy()])
+            .output()
+            .expect("Failed to run koto");
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("koto build --standalone"));
+        assert!(!dir.join("bin").join("script").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn installed_script_is_executable() {
+        let dir = test_dir("executable");
+        run_koto_install(&dir, &[], "print koto.args\n", &["--name", "args"]);
+
+        let koto_dir = Path::new(env!("CARGO_BIN_EXE_koto")).parent().unwrap();
+        let path = env::join_paths(
+            std::iter::once(koto_dir.to_path_buf())
+                .chain(env::split_paths(&env::var_os("PATH").unwrap_or_default())),
+        )
+        .unwrap();
+
+        let output = Command::new(dir.join("bin").join("args"))
+            .args(["a", "--b"])
+            .env("PATH", path)
+            .output()
+            .expect("Failed to run the installed script");
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "('a', '--b')\n");
+    }
+}
@@ -0,0 +1,106 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+// Creates an empty temporary directory for a test
+fn test_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("koto_minify_tests_{}_{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("Failed to create test dir");
+    dir
+}
+
+// Writes the script to the directory, and then runs `koto minify` on the script
+fn run_koto_minify(dir: &Path, script: &str, minify_args: &[&str]) -> String {
+    let script_path = dir.join("script.koto");
+    fs::write(&script_path, script).expect("Failed to write script");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_koto"))
+        .arg("minify")
+        .arg(&script_path)
+        .args(minify_args)
+        .output()
+        .expect("Failed to run koto");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    String::from_utf8(output.stdout).expect("Invalid output")
+}
+
+mod minify_tests {
+    use super::*;
+
+    const SCRIPT: &str = "\
+# Greets the user
+greet = |name, greeting|
+    message = '{greeting}, {name}!'
+    print message
+
+greet 'Koto', 'Good morning' # Good morning, Koto!
+greet 'World', 'Good morning'
+";
+
+    #[test]
+    fn minified_script_is_printed() {
+        let dir = test_dir("stdout");
+        let output = run_koto_minify(&dir, SCRIPT, &[]);
+
+        assert_eq!(
+            output,
+            "\
+greet = |a, b|
+ c = '{b}, {a}!'
+ print c
+greet 'Koto', 'Good morning'
+greet 'World', 'Good morning'
+"
+        );
+    }
+
+    #[test]
+    fn minified_script_with_string_table() {
+        let dir = test_dir("string_table");
+        let output_path = dir.join("minified.koto");
+        let stdout = run_koto_minify(
+            &dir,
+            SCRIPT,
+            &["--string-table", "--output", &output_path.to_string_lossy()],
+        );
+
+        assert!(stdout.is_empty());
+        assert_eq!(
+            fs::read_to_string(output_path).expect("Failed to read minified script"),
+            "\
+a=('Good morning',)
+greet = |b, c|
+ d = '{c}, {b}!'
+ print d
+greet 'Koto', a[0]
+greet 'World', a[0]
+"
+        );
+    }
+
+    #[test]
+    fn minified_script_has_the_same_output() {
+        let dir = test_dir("same_output");
+        let minified = run_koto_minify(&dir, SCRIPT, &["--string-table"]);
+        let minified_path = dir.join("minified.koto");
+        fs::write(&minified_path, minified).expect("Failed to write script");
+
+        let run = |path: &Path| {
+            let output = Command::new(env!("CARGO_BIN_EXE_koto"))
+                .arg(path)
+                .output()
+                .expect("Failed to run koto");
+            String::from_utf8(output.stdout).expect("Invalid output")
+        };
+
+        assert_eq!(run(&dir.join("script.koto")), run(&minified_path));
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+// Creates an empty temporary directory for a test
+fn test_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("koto_refactor_tests_{}_{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("Failed to create test dir");
+    dir
+}
+
+// Writes the scripts to the directory, and then runs `koto refactor` on the directory
+fn run_koto_refactor(dir: &Path, scripts: &[(&str, &str)], refactor_args: &[&str]) -> Output {
+    for (name, script) in scripts {
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).expect("Failed to create script dir");
+        fs::write(path, script).expect("Failed to write script");
+    }
+
+    Command::new(env!("CARGO_BIN_EXE_koto"))
+        .arg("refactor")
+        .args(refactor_args)
+        .arg("--dir")
+        .arg(dir)
+        .output()
+        .expect("Failed to run koto")
+}
+
+fn read_script(dir: &Path, name: &str) -> String {
+    fs::read_to_string(dir.join(name)).expect("Failed to read script")
+}
+
+mod refactor_tests {
+    use super::*;
+
+    const MAIN: &str = "\
+from utils.math import double
+# Doubles the input
+x = double 21
+print 'double: {x}'
+";
+
+    const MATH: &str = "\
+export double = |x| x * 2
+";
+
+    #[test]
+    fn rename_across_scripts() {
+        let dir = test_dir("rename");
+        let output = run_koto_refactor(
+            &dir,
+            &[("main.koto", MAIN), ("utils/math.koto", MATH)],
+            &["rename", "double", "twice"],
+        );
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        assert_eq!(
+            read_script(&dir, "main.koto"),
+            "\
+from utils.math import twice
+# Doubles the input
+x = twice 21
+print 'double: {x}'
+"
+        );
+        assert_eq!(
+            read_script(&dir, "utils/math.koto"),
+            "export twice = |x| x * 2\n"
+        );
+    }
+
+    #[test]
+    fn rename_in_strings() {
+        let dir = test_dir("strings");
+        let output = run_koto_refactor(
+            &dir,
+            &[("main.koto", MAIN)],
+            &["rename", "double", "twice", "--strings"],
+        );
+        assert!(output.status.success());
+
+        assert!(read_script(&dir, "main.koto").contains("print 'twice: {x}'"));
+    }
+
+    #[test]
+    fn dry_run_leaves_scripts_unchanged() {
+        let dir = test_dir("dry_run");
+        let output = run_koto_refactor(
+            &dir,
+            &[("main.koto", MAIN), ("utils/math.koto", MATH)],
+            &["rename", "double", "twice", "--dry-run"],
+        );
+        assert!(output.status.success());
+
+        let stdout = String::from_utf8(output.stdout).expect("Invalid output");
+        assert!(stdout.contains("Would rename 2 occurrences"), "{stdout}");
+        assert!(stdout.contains("Would rename 1 occurrence"), "{stdout}");
+        assert_eq!(read_script(&dir, "main.koto"), MAIN);
+        assert_eq!(read_script(&dir, "utils/math.koto"), MATH);
+    }
+
+    #[test]
+    fn local_values_and_lookups_are_unchanged() {
+        let dir = test_dir("locals");
+        let script = "\
+first = |xs| xs.first()
+f = |first| first * 2
+print first [1, 2]
+";
+        let output =
+            run_koto_refactor(&dir, &[("main.koto", script)], &["rename", "first", "head"]);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        assert_eq!(
+            read_script(&dir, "main.koto"),
+            "\
+head = |xs| xs.first()
+f = |first| first * 2
+print head [1, 2]
+"
+        );
+    }
+
+    #[test]
+    fn name_in_use_leaves_scripts_unchanged() {
+        let dir = test_dir("name_in_use");
+        let output = run_koto_refactor(
+            &dir,
+            &[("main.koto", MAIN), ("utils/math.koto", MATH)],
+            &["rename", "double", "x"],
+        );
+        assert!(!output.status.success());
+
+        let stderr = String::from_utf8(output.stderr).expect("Invalid output");
+        assert!(stderr.contains("'x' is already in use"), "{stderr}");
+        assert_eq!(read_script(&dir, "main.koto"), MAIN);
+        assert_eq!(read_script(&dir, "utils/math.koto"), MATH);
+    }
+}
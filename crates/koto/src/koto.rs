@@ -1,7 +1,10 @@
 use crate::{prelude::*, Error, Ptr, Result};
 use dunce::canonicalize;
 use koto_bytecode::CompilerSettings;
-use koto_runtime::ModuleImportedCallback;
+use koto_runtime::{
+    Diagnostics, Finalizer, HostEvents, MemoryLimit, MemoryUsageCallback, ModuleImportedCallback,
+    PinnedValues, Signals, UnloadedModule, ValueHandle,
+};
 use std::{
     path::{Path, PathBuf},
     time::Duration,
@@ -33,8 +36,11 @@ pub struct Koto {
     runtime: KotoVm,
     run_tests: bool,
     export_top_level_ids: bool,
+    strict: bool,
+    cfg_flags: Vec<String>,
     script_path: Option<PathBuf>,
     chunk: Option<Ptr<Chunk>>,
+    repl_renderers: Vec<Box<dyn ReplRenderer>>,
 }
 
 impl Default for Koto {
@@ -51,12 +57,21 @@ impl Koto {
 
     /// Creates a new instance of Koto with the given settings
     pub fn with_settings(settings: KotoSettings) -> Self {
+        let runtime = KotoVm::with_settings(settings.vm_settings);
+        runtime
+            .loader()
+            .borrow_mut()
+            .set_cfg_flags(settings.cfg_flags.clone());
+
         Self {
-            runtime: KotoVm::with_settings(settings.vm_settings),
+            runtime,
             run_tests: settings.run_tests,
             export_top_level_ids: settings.export_top_level_ids,
+            strict: settings.strict,
+            cfg_flags: settings.cfg_flags,
             chunk: None,
             script_path: None,
+            repl_renderers: Vec::new(),
         }
     }
 
@@ -84,6 +99,8 @@ impl Koto {
             self.script_path.as_deref(),
             CompilerSettings {
                 export_top_level_ids: self.export_top_level_ids,
+                strict: self.strict,
+                cfg_flags: self.cfg_flags.clone(),
             },
         )?;
 
@@ -91,7 +108,16 @@ impl Koto {
         Ok(chunk)
     }
 
-    /// Runs the chunk last compiled with [compile](Koto::compile)
+    /// Sets a previously compiled chunk to be run with [run](Koto::run)
+    ///
+    /// This is useful for running chunks that were restored with
+    /// [Chunk::from_bytes](koto_bytecode::Chunk::from_bytes).
+    pub fn set_chunk(&mut self, chunk: Ptr<Chunk>) {
+        self.chunk = Some(chunk);
+    }
+
+    /// Runs the chunk last compiled with [compile](Koto::compile), or set with
+    /// [set_chunk](Koto::set_chunk)
     pub fn run(&mut self) -> Result<KValue> {
         let chunk = self.chunk.clone();
         match chunk {
@@ -137,6 +163,37 @@ impl Koto {
         self.runtime.value_to_string(&value)
     }
 
+    /// Converts a [KValue] into a [String] for display in a REPL
+    ///
+    /// If the value is a map that defines `@repl_display`, then its result is used.
+    /// Otherwise the renderers that were registered with [Koto::add_repl_renderer] are tried in
+    /// order, with [Koto::value_to_string] used as the plain-text fallback.
+    pub fn value_to_repl_string(&mut self, value: KValue) -> Result<String> {
+        if let KValue::Map(map) = &value {
+            if let Some(repl_display) = map.get_meta_value(&MetaKey::ReplDisplay) {
+                return match self.call_instance_function(value.clone(), repl_display, &[])? {
+                    KValue::Str(result) => Ok(result.to_string()),
+                    unexpected => type_error("String as @repl_display result", &unexpected),
+                };
+            }
+        }
+
+        for renderer in self.repl_renderers.iter() {
+            if let Some(result) = renderer(&value, &mut self.runtime)? {
+                return Ok(result);
+            }
+        }
+
+        self.value_to_string(value)
+    }
+
+    /// Registers a renderer that provides rich representations of values in a REPL
+    ///
+    /// See [Koto::value_to_repl_string].
+    pub fn add_repl_renderer(&mut self, renderer: impl ReplRenderer + 'static) {
+        self.repl_renderers.push(Box::new(renderer));
+    }
+
     /// Clears the loader's cached modules
     ///
     /// This is useful when a script's dependencies may have changed and need to be recompiled.
@@ -144,6 +201,156 @@ impl Koto {
         self.runtime.loader().borrow_mut().clear_cache();
     }
 
+    /// Unloads a previously imported module, allowing it to be released
+    ///
+    /// The module is found relative to the script path, in the same way as an `import`
+    /// expression. `None` is returned if the module hadn't been loaded.
+    ///
+    /// See [KotoVm::unload_module].
+    pub fn unload_module(&self, name: &str) -> Result<Option<UnloadedModule>> {
+        self.runtime
+            .unload_module(name, self.script_path.as_deref())
+    }
+
+    /// Resets the runtime, clearing the compiled script, the exports, and any loaded modules
+    ///
+    /// Any registered finalizers are called, while settings, the script path, and state owned by
+    /// the host (e.g. pinned values and REPL renderers) are retained.
+    ///
+    /// See [KotoVm::reset].
+    pub fn reset(&mut self) {
+        self.runtime.reset();
+        self.chunk = None;
+    }
+
+    /// Registers a finalizer that will be called when the runtime is dropped or reset
+    ///
+    /// Finalizers are called in the reverse order of their registration.
+    ///
+    /// See [KotoVm::add_finalizer].
+    pub fn add_finalizer(&self, finalizer: impl Finalizer + 'static) {
+        self.runtime.add_finalizer(finalizer);
+    }
+
+    /// Calls any registered finalizers without waiting for the runtime to be dropped
+    ///
+    /// See [KotoVm::run_finalizers].
+    pub fn run_finalizers(&self) {
+        self.runtime.run_finalizers();
+    }
+
+    /// The events passed between the host application and its scripts
+    ///
+    /// See [HostEvents].
+    pub fn host_events(&self) -> &HostEvents {
+        self.runtime.host_events()
+    }
+
+    /// The diagnostics that are reported while running scripts
+    ///
+    /// See [Diagnostics].
+    pub fn diagnostics(&self) -> &Diagnostics {
+        self.runtime.diagnostics()
+    }
+
+    /// Calls the script's handlers for the named event, returning the number of handlers called
+    ///
+    /// See [KotoVm::dispatch_host_event].
+    pub fn dispatch_host_event(&mut self, name: &str, data: impl Into<KValue>) -> Result<usize> {
+        self.runtime.dispatch_host_event(name, data)
+    }
+
+    /// Dispatches the events that were posted with [HostEvents::post] to the script's handlers
+    ///
+    /// See [KotoVm::process_host_events].
+    pub fn process_host_events(&mut self) -> Result<usize> {
+        self.runtime.process_host_events()
+    }
+
+    /// The values that have been pinned by the host
+    ///
+    /// See [PinnedValues].
+    pub fn pinned_values(&self) -> &PinnedValues {
+        self.runtime.pinned_values()
+    }
+
+    /// Pins a value, returning a handle that can be used to access the value
+    ///
+    /// Pinned values are kept alive until they're unpinned, and remain available when the runtime
+    /// is reset. See [PinnedValues].
+    pub fn pin(&self, value: impl Into<KValue>) -> ValueHandle {
+        self.runtime.pin(value)
+    }
+
+    /// Returns the pinned value for the handle, or `None` if the value has been unpinned
+    pub fn pinned(&self, handle: ValueHandle) -> Option<KValue> {
+        self.runtime.pinned(handle)
+    }
+
+    /// Unpins the value for the handle, returning the value if it was pinned
+    pub fn unpin(&self, handle: ValueHandle) -> Option<KValue> {
+        self.runtime.unpin(handle)
+    }
+
+    /// The process signals that are passed to the script's handlers
+    ///
+    /// See [Signals].
+    pub fn signals(&self) -> &Signals {
+        self.runtime.signals()
+    }
+
+    /// Calls the script's handlers for any signals that have been raised
+    ///
+    /// See [KotoVm::process_signals].
+    pub fn process_signals(&mut self) -> Result<usize> {
+        self.runtime.process_signals()
+    }
+
+    /// Returns the names of the commands that were exported by the script with `@command`
+    ///
+    /// The names are returned in the order they were defined.
+    pub fn commands(&self) -> Vec<KString> {
+        self.runtime
+            .exports()
+            .meta_map()
+            .map(|meta| {
+                meta.borrow()
+                    .keys()
+                    .filter_map(|key| match key {
+                        MetaKey::Command(name) => Some(name.clone()),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Calls the command that was exported by the script with `@command`
+    ///
+    /// The script needs to have been run before the command can be called.
+    ///
+    /// The command's arguments are parsed into a map of options and a tuple of positional
+    /// arguments, which are then passed to the command function.
+    ///   - `--name value` and `--name=value` are added to the options as strings.
+    ///   - `--name` without a value, or followed by another option, is added as `true`.
+    ///   - All other arguments, and any arguments following `--`, are positional.
+    pub fn run_command(&mut self, name: &str, args: &[String]) -> Result<KValue> {
+        let command = self
+            .runtime
+            .exports()
+            .get_meta_value(&MetaKey::Command(name.into()));
+
+        match command {
+            Some(command) if command.is_callable() => {
+                let (options, positional) = parse_command_args(args);
+                self.runtime
+                    .call_function(command, &[options.into(), positional])
+            }
+            Some(other) => type_error("a callable command", &other),
+            None => runtime_error!("Unknown command '{name}'"),
+        }
+    }
+
     /// Sets the arguments that can be accessed from within the script via `koto.args()`
     pub fn set_args(&mut self, args: &[String]) -> Result<()> {
         use KValue::{Map, Str, Tuple};
@@ -231,6 +438,46 @@ impl Koto {
     }
 }
 
+/// The trait used by renderers registered with [Koto::add_repl_renderer]
+///
+/// A renderer returns the value's rich representation, or `None` if the value isn't supported
+/// by the renderer.
+pub trait ReplRenderer:
+    Fn(&KValue, &mut KotoVm) -> Result<Option<String>> + KotoSend + KotoSync
+{
+}
+
+// Implement the trait for any matching function
+impl<T> ReplRenderer for T where
+    T: Fn(&KValue, &mut KotoVm) -> Result<Option<String>> + KotoSend + KotoSync
+{
+}
+
+// Parses command line arguments into a map of options and a tuple of positional arguments
+fn parse_command_args(args: &[String]) -> (KMap, KValue) {
+    let options = KMap::new();
+    let mut positional = Vec::new();
+    let mut args = args.iter().peekable();
+
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix("--") {
+            Some("") => {
+                positional.extend(args.by_ref().map(|arg| KValue::from(arg.as_str())));
+            }
+            Some(option) => match option.split_once('=') {
+                Some((name, value)) => options.insert(name, value),
+                None => match args.next_if(|next| !next.starts_with("--")) {
+                    Some(value) => options.insert(option, value.as_str()),
+                    None => options.insert(option, true),
+                },
+            },
+            None => positional.push(arg.as_str().into()),
+        }
+    }
+
+    (options, KValue::Tuple(positional.into()))
+}
+
 /// Settings used to control the behaviour of the [Koto] runtime
 pub struct KotoSettings {
     /// Whether or not tests should be run when loading a script
@@ -243,6 +490,15 @@ pub struct KotoSettings {
     /// This is used by the REPL, allowing for incremental compilation and execution of expressions
     /// that need to share declared values.
     pub export_top_level_ids: bool,
+    /// Whether or not new bindings need to be explicitly declared with `let`
+    ///
+    /// When enabled, assigning to an identifier that hasn't been declared results in a
+    /// compilation error. Individual scripts can also opt in with a `# koto: strict` directive.
+    pub strict: bool,
+    /// The flags that enable `@cfg` blocks in compiled scripts and imported modules
+    ///
+    /// Blocks with flags that aren't included here are left out of the compiled bytecode.
+    pub cfg_flags: Vec<String>,
     /// Settings that apply to the runtime
     pub vm_settings: KotoVmSettings,
 }
@@ -260,6 +516,39 @@ impl KotoSettings {
         }
     }
 
+    /// Helper for conveniently defining a maximum number of executed instructions
+    #[must_use]
+    pub fn with_instruction_limit(self, limit: u64) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                instruction_limit: Some(limit),
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
+
+    /// Helper for conveniently defining a memory limit
+    ///
+    /// See [MemoryLimit] for more information.
+    #[must_use]
+    pub fn with_memory_limit(
+        self,
+        max_bytes: usize,
+        usage: impl MemoryUsageCallback + 'static,
+    ) -> Self {
+        Self {
+            vm_settings: KotoVmSettings {
+                memory_limit: Some(MemoryLimit {
+                    max_bytes,
+                    usage: Box::new(usage),
+                }),
+                ..self.vm_settings
+            },
+            ..self
+        }
+    }
+
     /// Helper for conveniently defining a custom stdin implementation
     #[must_use]
     pub fn with_stdin(self, stdin: impl KotoFile + 'static) -> Self {
@@ -317,6 +606,8 @@ impl Default for KotoSettings {
         Self {
             run_tests: true,
             export_top_level_ids: false,
+            strict: false,
+            cfg_flags: Vec::new(),
             vm_settings: KotoVmSettings::default(),
         }
     }
@@ -68,6 +68,36 @@ mod cli {
     mod run_file {
         use super::*;
 
+        #[test]
+        fn command_with_options() {
+            check_cli_run_file(
+                &["koto", "tests", "commands.koto"],
+                &["build", "a", "--target", "x86", "--release", "--", "--b"],
+                "build ('a', '--b')\n  target: x86\n  release: true\n",
+                "",
+            );
+        }
+
+        #[test]
+        fn command_with_string_name() {
+            check_cli_run_file(
+                &["koto", "tests", "commands.koto"],
+                &["deploy-prod", "--region=eu"],
+                "deploy-prod to eu\n",
+                "",
+            );
+        }
+
+        #[test]
+        fn command_list() {
+            check_cli_run_file(
+                &["koto", "tests", "commands.koto"],
+                &[],
+                "COMMANDS:\n    build\n    deploy-prod\n",
+                "",
+            );
+        }
+
         #[test]
         fn spectral_norm() {
             check_cli_run_file(
@@ -101,6 +101,7 @@ mod koto_tests {
     koto_test!(assignment);
     koto_test!(comments);
     koto_test!(control_flow);
+    koto_test!(embed);
     koto_test!(enums);
     koto_test!(eval);
     koto_test!(function_closures);
@@ -121,6 +122,7 @@ mod koto_tests {
     koto_test!(os);
     koto_test!(primes);
     koto_test!(ranges);
+    koto_test!(rate_limiter);
     koto_test!(strings);
     koto_test!(tests);
     koto_test!(tuples);
@@ -0,0 +1,141 @@
+use koto::{prelude::*, PtrMut};
+use std::path::{Path, PathBuf};
+
+fn test_script_path() -> PathBuf {
+    [
+        env!("CARGO_MANIFEST_DIR"),
+        "..",
+        "..",
+        "koto",
+        "tests",
+        "import.koto",
+    ]
+    .iter()
+    .collect::<PathBuf>()
+    .canonicalize()
+    .unwrap()
+}
+
+fn koto_with_import_log() -> (Koto, PtrMut<Vec<PathBuf>>) {
+    let imported = PtrMut::from(Vec::new());
+
+    let mut koto = Koto::with_settings(KotoSettings::default().with_module_imported_callback({
+        let imported = imported.clone();
+        move |path: &Path| imported.borrow_mut().push(path.to_path_buf())
+    }));
+    koto.set_script_path(Some(&test_script_path())).unwrap();
+
+    (koto, imported)
+}
+
+mod unload_module {
+    use super::*;
+
+    #[test]
+    fn unloaded_module_is_released() {
+        let (mut koto, _) = koto_with_import_log();
+
+        koto.compile_and_run("m = import test_module\nm.square 9")
+            .unwrap();
+
+        let unloaded = koto.unload_module("test_module").unwrap().unwrap();
+
+        assert!(unloaded.path.ends_with("test_module/main.koto"));
+        assert!(unloaded.is_released(), "{unloaded:?}");
+    }
+
+    #[test]
+    fn live_references_are_reported() {
+        let (mut koto, _) = koto_with_import_log();
+
+        koto.compile_and_run("export square = (import test_module).square")
+            .unwrap();
+
+        let unloaded = koto.unload_module("test_module").unwrap().unwrap();
+
+        assert_eq!(unloaded.export_references, 0);
+        assert_eq!(unloaded.chunk_references, 1);
+        assert!(!unloaded.is_released());
+    }
+
+    #[test]
+    fn unloaded_module_is_reloaded_when_imported() {
+        let (mut koto, imported) = koto_with_import_log();
+        let script = "import test_module\ntest_module.foo";
+
+        koto.compile_and_run(script).unwrap();
+        koto.compile_and_run(script).unwrap();
+        let import_count = imported.borrow().len();
+
+        koto.unload_module("test_module").unwrap();
+        koto.compile_and_run(script).unwrap();
+
+        assert!(imported.borrow().len() > import_count);
+    }
+
+    #[test]
+    fn module_that_was_not_loaded() {
+        let (koto, _) = koto_with_import_log();
+
+        assert!(koto.unload_module("test_module").unwrap().is_none());
+        assert!(koto.unload_module("missing_module").is_err());
+    }
+}
+
+mod reset {
+    use super::*;
+
+    #[test]
+    fn exports_and_compiled_script_are_cleared() {
+        let (mut koto, _) = koto_with_import_log();
+
+        koto.compile_and_run("export x = 42").unwrap();
+        assert!(koto.exports().get("x").is_some());
+
+        koto.reset();
+
+        assert!(koto.exports().is_empty());
+        assert!(koto.run().is_err());
+    }
+
+    #[test]
+    fn modules_are_reloaded_after_reset() {
+        let (mut koto, imported) = koto_with_import_log();
+        let script = "import test_module\ntest_module.foo";
+
+        koto.compile_and_run(script).unwrap();
+        let import_count = imported.borrow().len();
+
+        koto.reset();
+        koto.compile_and_run(script).unwrap();
+
+        assert_eq!(imported.borrow().len(), import_count * 2);
+    }
+
+    #[test]
+    fn pinned_values_are_retained() {
+        let (mut koto, _) = koto_with_import_log();
+
+        let f = koto.compile_and_run("|x| x + 1").unwrap();
+        let handle = koto.pin(f);
+
+        koto.reset();
+
+        let f = koto.pinned(handle).unwrap();
+        let result = koto.call_function(f, &[41.into()]).unwrap();
+        assert!(matches!(result, KValue::Number(n) if n == 42));
+    }
+
+    #[test]
+    fn script_event_handlers_are_removed() {
+        let (mut koto, _) = koto_with_import_log();
+
+        koto.compile_and_run("koto.host.on 'ping', |_| null")
+            .unwrap();
+        assert!(koto.host_events().has_handler("ping"));
+
+        koto.reset();
+
+        assert!(!koto.host_events().has_handler("ping"));
+    }
+}
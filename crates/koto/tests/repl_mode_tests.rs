@@ -156,6 +156,14 @@ x: 3
         ]);
     }
 
+    #[test]
+    fn nested_assignment() {
+        run_repl_mode_test(&[
+            ("(a, (b, _)), c = (1, (2, 3)), 4", ""),
+            ("print a, b, c", "(1, 2, 4)"),
+        ]);
+    }
+
     #[test]
     fn subtract_assign_number() {
         run_repl_mode_test(&[
@@ -165,3 +173,59 @@ x: 3
         ]);
     }
 }
+
+mod repl_display {
+    use super::*;
+
+    fn check_repl_display(koto: &mut Koto, script: &str, expected: &str) {
+        let result = koto.compile_and_run(script).unwrap();
+        assert_eq!(koto.value_to_repl_string(result).unwrap(), expected);
+    }
+
+    #[test]
+    fn repl_display_meta_key() {
+        let mut koto = Koto::new();
+        check_repl_display(
+            &mut koto,
+            "
+x =
+  @display: || 'x'
+  @repl_display: || 'rich x'
+x
+",
+            "rich x",
+        );
+    }
+
+    #[test]
+    fn plain_text_fallback() {
+        let mut koto = Koto::new();
+        check_repl_display(&mut koto, "{@display: || 'plain'}", "plain");
+        check_repl_display(&mut koto, "[1, 2, 3]", "[1, 2, 3]");
+    }
+
+    #[test]
+    fn renderers() {
+        let mut koto = Koto::new();
+        koto.add_repl_renderer(|value: &KValue, _: &mut KotoVm| match value {
+            KValue::List(_) => Ok(Some("list".into())),
+            _ => Ok(None),
+        });
+        koto.add_repl_renderer(|value: &KValue, _: &mut KotoVm| match value {
+            KValue::List(_) | KValue::Tuple(_) => Ok(Some("sequence".into())),
+            _ => Ok(None),
+        });
+
+        check_repl_display(&mut koto, "[1, 2]", "list");
+        check_repl_display(&mut koto, "(1, 2)", "sequence");
+        check_repl_display(&mut koto, "42", "42");
+        check_repl_display(&mut koto, "{@repl_display: || 'map'}", "map");
+    }
+
+    #[test]
+    fn non_string_result() {
+        let mut koto = Koto::new();
+        let result = koto.compile_and_run("{@repl_display: || 42}").unwrap();
+        assert!(koto.value_to_repl_string(result).is_err());
+    }
+}
@@ -28,6 +28,7 @@ pub enum Token {
     Dot,
     Ellipsis,
     Function,
+    QuestionMark,
     RoundOpen,
     RoundClose,
     SquareOpen,
@@ -58,9 +59,19 @@ pub enum Token {
     Less,
     LessOrEqual,
 
-    // Pipe is detected by the parser instead of the lexer
+    NullCoalesce,
+
     Pipe,
 
+    BitwiseAnd,
+    BitwiseNot,
+    BitwiseXor,
+
+    // Shifts and bitwise or are detected by the parser instead of the lexer
+    ShiftLeft,
+    ShiftRight,
+    BitwiseOr,
+
     // Keywords
     As,
     And,
@@ -717,6 +728,8 @@ impl<'a> TokenLexer<'a> {
         check_symbol!(">", Greater);
         check_symbol!("<", Less);
 
+        check_symbol!("->", Pipe);
+
         check_symbol!("=", Assign);
         check_symbol!("+=", AddAssign);
         check_symbol!("-=", SubtractAssign);
@@ -730,6 +743,10 @@ impl<'a> TokenLexer<'a> {
         check_symbol!("/", Divide);
         check_symbol!("%", Remainder);
 
+        check_symbol!("&", BitwiseAnd);
+        check_symbol!("~", BitwiseNot);
+        check_symbol!("^", BitwiseXor);
+
         check_symbol!("@", At);
         check_symbol!(":", Colon);
         check_symbol!(",", Comma);
@@ -737,6 +754,8 @@ impl<'a> TokenLexer<'a> {
         check_symbol!("(", RoundOpen);
         check_symbol!(")", RoundClose);
         check_symbol!("|", Function);
+        check_symbol!("??", NullCoalesce);
+        check_symbol!("?", QuestionMark);
         check_symbol!("[", SquareOpen);
         check_symbol!("]", SquareClose);
         check_symbol!("{", CurlyOpen);
@@ -1163,6 +1182,21 @@ false #
             );
         }
 
+        #[test]
+        fn shebang() {
+            let input = "\
+#!/usr/bin/env koto
+true";
+            check_lexer_output(
+                input,
+                &[
+                    (CommentSingle, Some("#!/usr/bin/env koto"), 0),
+                    (NewLine, None, 0),
+                    (True, None, 1),
+                ],
+            );
+        }
+
         #[test]
         fn strings() {
             let input = r#"
@@ -1585,6 +1619,83 @@ else
             );
         }
 
+        #[test]
+        fn error_propagation() {
+            let input = "f(x)?.y?";
+
+            check_lexer_output(
+                input,
+                &[
+                    (Id, Some("f"), 0),
+                    (RoundOpen, None, 0),
+                    (Id, Some("x"), 0),
+                    (RoundClose, None, 0),
+                    (QuestionMark, None, 0),
+                    (Dot, None, 0),
+                    (Id, Some("y"), 0),
+                    (QuestionMark, None, 0),
+                ],
+            );
+        }
+
+        #[test]
+        fn null_coalescing() {
+            let input = "x ?? f()?";
+
+            check_lexer_output(
+                input,
+                &[
+                    (Id, Some("x"), 0),
+                    (NullCoalesce, None, 0),
+                    (Id, Some("f"), 0),
+                    (RoundOpen, None, 0),
+                    (RoundClose, None, 0),
+                    (QuestionMark, None, 0),
+                ],
+            );
+        }
+
+        #[test]
+        fn bitwise_operators() {
+            let input = "~a & b ^ c | d << e >> f";
+
+            check_lexer_output(
+                input,
+                &[
+                    (BitwiseNot, None, 0),
+                    (Id, Some("a"), 0),
+                    (BitwiseAnd, None, 0),
+                    (Id, Some("b"), 0),
+                    (BitwiseXor, None, 0),
+                    (Id, Some("c"), 0),
+                    (Function, None, 0),
+                    (Id, Some("d"), 0),
+                    (Less, None, 0),
+                    (Less, None, 0),
+                    (Id, Some("e"), 0),
+                    (Greater, None, 0),
+                    (Greater, None, 0),
+                    (Id, Some("f"), 0),
+                ],
+            );
+        }
+
+        #[test]
+        fn pipe() {
+            let input = "x -> f->g";
+
+            check_lexer_output(
+                input,
+                &[
+                    (Id, Some("x"), 0),
+                    (Pipe, None, 0),
+                    (Id, Some("f"), 0),
+                    (Pipe, None, 0),
+                    (Id, Some("g"), 0),
+                ],
+            );
+        }
+
         #[test]
         fn windows_line_endings() {
             let input = "123\r\n456\r\n789";
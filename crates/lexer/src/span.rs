@@ -1,5 +1,5 @@
 /// Represents a line/column position in a script
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Position {
     /// The position's line, counting from 0
     pub line: u32,
@@ -8,7 +8,7 @@ pub struct Position {
 }
 
 /// A span is a range in the source code, represented by a start and end position
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Span {
     /// The span's start position
     pub start: Position,
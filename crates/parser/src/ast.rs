@@ -64,6 +64,7 @@ pub struct Ast {
     nodes: Vec<AstNode>,
     spans: Vec<Span>,
     constants: ConstantPool,
+    warnings: Vec<Warning>,
 }
 
 impl Ast {
@@ -73,6 +74,7 @@ impl Ast {
             nodes: Vec::with_capacity(capacity),
             spans: Vec::with_capacity(capacity),
             constants: ConstantPool::default(),
+            warnings: Vec::new(),
         }
     }
 
@@ -119,6 +121,15 @@ impl Ast {
         self.constants = constants
     }
 
+    /// Returns the warnings that were produced while parsing
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    pub(crate) fn add_warning(&mut self, warning: Warning) {
+        self.warnings.push(warning)
+    }
+
     /// Returns the root node in the tree
     pub fn entry_point(&self) -> Option<AstIndex> {
         if self.nodes.is_empty() {
@@ -161,6 +161,27 @@ impl ConstantPool {
         }
     }
 
+    /// Creates a pool from a series of constants, e.g. the output of another pool's [iter](Self::iter)
+    ///
+    /// Repeated constants are only added to the pool once, so the constants will have the same
+    /// indices as in the original pool when the original was produced by the parser.
+    ///
+    /// `None` is returned if the pool's capacity is exceeded.
+    pub fn from_constants<'a>(constants: impl IntoIterator<Item = Constant<'a>>) -> Option<Self> {
+        let mut builder = ConstantPoolBuilder::default();
+
+        for constant in constants {
+            let result = match constant {
+                Constant::F64(n) => builder.add_f64(n),
+                Constant::I64(n) => builder.add_i64(n),
+                Constant::Str(s) => builder.add_string(s),
+            };
+            result.ok()?;
+        }
+
+        Some(builder.build())
+    }
+
     /// Provides an iterator that iterates over the pool's constants
     pub fn iter(&self) -> ConstantPoolIterator {
         ConstantPoolIterator::new(self)
@@ -408,4 +429,21 @@ mod tests {
         assert_eq!(iter.next(), Some(Constant::Str("^_^")));
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn test_from_constants() {
+        let mut builder = ConstantPoolBuilder::default();
+
+        builder.add_string("foo").unwrap();
+        builder.add_f64(1.5).unwrap();
+        builder.add_i64(42).unwrap();
+        builder.add_string("bar").unwrap();
+
+        let pool = builder.build();
+        let copy = ConstantPool::from_constants(pool.iter()).unwrap();
+
+        assert_eq!(pool, copy);
+        assert_eq!("bar", copy.get_str(3.into()));
+        assert_eq!(42, copy.get_i64(2.into()));
+    }
 }
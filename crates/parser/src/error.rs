@@ -41,6 +41,8 @@ pub enum ExpectedIndentation {
     AssignmentExpression,
     #[error("Expected indented block for catch expression")]
     CatchBody,
+    #[error("Expected indented block following @cfg")]
+    CfgBlock,
     #[error("Expected indented block for 'else'.")]
     ElseBlock,
     #[error("Expected indented block for 'else if'.")]
@@ -73,20 +75,38 @@ pub enum ExpectedIndentation {
 #[derive(Error, Clone, Debug)]
 #[allow(missing_docs)]
 pub enum SyntaxError {
+    #[error(
+        "Ambiguous '|' following a default argument value, \
+         use parentheses for a bitwise or, e.g. '|x = (a | b)|'"
+    )]
+    AmbiguousBitwiseOrInDefaultArg,
     #[error("Ascii value out of range, the maximum is \\x7f")]
     AsciiEscapeCodeOutOfRange,
+    #[error(
+        "'{0}' is declared in an outer scope, use 'let' to shadow it, \
+         or 'export' to update an exported value"
+    )]
+    AssignmentToOuterValue(String),
     #[error("Expected end of arguments ')'")]
     ExpectedArgsEnd,
     #[error("Expected target for assignment")]
     ExpectedAssignmentTarget,
+    #[error("Expected '=' assignment after 'let' declaration")]
+    ExpectedAssignmentAfterLet,
     #[error("Expected '=' assignment after meta key")]
     ExpectedAssignmentAfterMetaKey,
     #[error("Expected argument for catch expression")]
     ExpectedCatchArgument,
     #[error("Expected catch expression after try")]
     ExpectedCatch,
+    #[error("Expected a flag name string after @cfg")]
+    ExpectedCfgFlag,
+    #[error("Expected a file path string after @embed")]
+    ExpectedEmbedPath,
     #[error("Expected closing parenthesis ')'")]
     ExpectedCloseParen,
+    #[error("Expected a command name after @command")]
+    ExpectedCommandName,
     #[error("Expected expression after 'else'.")]
     ExpectedElseExpression,
     #[error("Expected condition for 'else if'.")]
@@ -103,6 +123,12 @@ pub enum SyntaxError {
     ExpectedFormatString,
     #[error("Expected end of function arguments '|'")]
     ExpectedFunctionArgsEnd,
+    #[error("Expected default value for function argument")]
+    ExpectedDefaultArgValue,
+    #[error("Expected a function assignment on the line following @deprecated")]
+    ExpectedDeprecatedFunction,
+    #[error("Expected a message string after @deprecated")]
+    ExpectedDeprecationMessage,
     #[error("Expected ID in import expression")]
     ExpectedIdInImportExpression,
     #[error("Expected condition after 'if'")]
@@ -115,6 +141,8 @@ pub enum SyntaxError {
     ExpectedIndexEnd,
     #[error("Expected index expression")]
     ExpectedIndexExpression,
+    #[error("Expected ID in 'let' declaration")]
+    ExpectedIdInLetDeclaration,
     #[error("Expected id after 'as'")]
     ExpectedIdAfterAs,
     #[error("Expected List end ']'")]
@@ -139,10 +167,18 @@ pub enum SyntaxError {
     ExpectedMatchExpression,
     #[error("Expected pattern for match arm")]
     ExpectedMatchPattern,
+    #[error("Expected a number at the end of the range pattern")]
+    ExpectedMatchRangeEnd,
+    #[error("Expected a type name after ':' in the match pattern")]
+    ExpectedMatchTypeName,
     #[error("Expected id after @meta")]
     ExpectedMetaId,
+    #[error("Expected value after ':' in named argument")]
+    ExpectedNamedArgValue,
     #[error("Expected a module path after 'from'")]
     ExpectedPathAfterFrom,
+    #[error("Expected a value after 'step' in the range")]
+    ExpectedRangeStep,
     #[error("Expected a line break before starting a map block")]
     ExpectedLineBreakBeforeMapBlock,
     #[error("Expected '}}' at end of string placeholder")]
@@ -151,14 +187,22 @@ pub enum SyntaxError {
     ExpectedSwitchArmExpression,
     #[error("Expected expression after 'then' in switch arm")]
     ExpectedSwitchArmExpressionAfterThen,
+    #[error("Expected an 'else' arm at the end of the switch expression")]
+    ExpectedSwitchElse,
     #[error("Expected a test name")]
     ExpectedTestName,
     #[error("Expected expression after 'then'")]
     ExpectedThenExpression,
+    #[error("Expected '{{' after '\\u', e.g. '\\u{{1F600}}'")]
+    ExpectedUnicodeEscapeBrace,
+    #[error("Expected a hexadecimal value in the unicode escape code, e.g. '\\u{{1F600}}'")]
+    ExpectedUnicodeEscapeValue,
     #[error("Expected condition in until loop")]
     ExpectedUntilCondition,
     #[error("Expected condition in while loop")]
     ExpectedWhileCondition,
+    #[error("Failed to embed '{path}': {error}")]
+    FailedToEmbedFile { path: String, error: String },
     #[error(transparent)]
     FormatStringError(StringFormatError),
     #[error("Non-inline if expression isn't allowed in this context")]
@@ -169,12 +213,16 @@ pub enum SyntaxError {
     MatchEllipsisOutsideOfNestedPatterns,
     #[error("'else' can only be used in the last arm in a match expression")]
     MatchElseNotInLastArm,
+    #[error("Positional arguments need to be placed before named arguments")]
+    PositionalArgAfterNamedArg,
     #[error("Keyword reserved for future use")]
     ReservedKeyword,
     #[error("'self' doesn't need to be declared as an argument")]
     SelfArg,
     #[error("'else' can only be used in the last arm in a switch expression")]
     SwitchElseNotInLastArm,
+    #[error("'{0}' needs to be declared with 'let' before it can be assigned")]
+    UndeclaredAssignment(String),
     #[error("Unexpected character in numeric escape code")]
     UnexpectedCharInNumericEscapeCode,
     #[error("'.' after imported item. You might want a 'from' import instead")]
@@ -193,6 +241,8 @@ pub enum SyntaxError {
     UnexpectedToken,
     #[error("Unicode value out of range, the maximum is \\u{{10ffff}}")]
     UnicodeEscapeCodeOutOfRange,
+    #[error("'\\{0}' isn't a valid escape code, use '\\\\' for a backslash")]
+    UnknownEscapeCode(char),
     #[error("Unterminated numeric escape code")]
     UnterminatedNumericEscapeCode,
     #[error("Unterminated string")]
@@ -235,6 +285,27 @@ impl Error {
     }
 }
 
+/// A potential problem found by the [Parser](crate::Parser) that doesn't prevent parsing
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum WarningKind {
+    #[error(
+        "Assigning to '{0}' creates a new local value that shadows the module's '{0}', \
+         use 'let' to make the shadowing explicit, or 'export' to update an exported value"
+    )]
+    ModuleValueShadowed(String),
+}
+
+/// A warning produced by the [Parser](crate::Parser), see [Ast::warnings](crate::Ast::warnings)
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+#[error("{warning}")]
+pub struct Warning {
+    /// The warning itself
+    pub warning: WarningKind,
+    /// The span in the source string that the warning refers to
+    pub span: Span,
+}
+
 /// The result type used by the [Parser](crate::Parser)
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -5,17 +5,23 @@
 mod ast;
 mod constant_pool;
 mod error;
+mod minify;
 mod node;
 mod parser;
+mod refactor;
 mod string_format_options;
 mod string_slice;
 
 pub use crate::{
     ast::*,
     constant_pool::{Constant, ConstantIndex, ConstantPool},
-    error::{format_source_excerpt, Error, Result},
+    error::{format_source_excerpt, Error, Result, Warning, WarningKind},
+    minify::{minify, MinifyError, MinifySettings},
     node::*,
-    parser::Parser,
+    parser::{EmbedLoader, Parser, ParserSettings},
+    refactor::{
+        rename_symbol, RenameError, RenameSettings, Renamed, RenamedSymbol, RenamedSymbolKind,
+    },
     string_format_options::{StringAlignment, StringFormatOptions},
     string_slice::StringSlice,
 };
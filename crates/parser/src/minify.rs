@@ -0,0 +1,948 @@
+//! A minifier for Koto scripts
+
+use crate::{
+    Ast, AstIndex, AstString, ChainNode, ConstantIndex, Error, Node, Parser, StringContents,
+    StringNode,
+};
+use koto_lexer::{is_id_continue, LexedToken, Lexer, Position, Token};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+};
+use thiserror::Error;
+
+/// Settings for [minify]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MinifySettings {
+    /// Whether or not repeated string literals should be moved into a string table
+    ///
+    /// The string table is defined as a tuple at the start of the script, with each repeated
+    /// string replaced with an index into the table.
+    pub string_table: bool,
+}
+
+/// An error that can be produced by [minify]
+#[derive(Error, Clone, Debug)]
+pub enum MinifyError {
+    /// The source couldn't be parsed
+    #[error("Failed to parse the source: {0}")]
+    InvalidSource(Error),
+    /// The source couldn't be parsed after minifying
+    #[error("The minified source failed to parse: {0}")]
+    InvalidResult(Error),
+}
+
+/// Minifies a script, producing semantically equivalent source
+///
+/// - Comments, blank lines, and redundant whitespace are removed,
+///   with indentation reduced to the minimum needed to preserve the script's structure.
+/// - Local values in functions are given short names.
+///   Top-level values, exported values, and values that can be accessed by name from outside of
+///   a function (e.g. by named arguments or `debug` expressions) keep their names.
+/// - Repeated string literals are optionally moved into a string table,
+///   see [MinifySettings::string_table].
+///
+/// The minified source is parsed before it's returned to ensure that it's valid.
+pub fn minify(source: &str, settings: MinifySettings) -> Result<String, MinifyError> {
+    let ast = Parser::parse(source).map_err(MinifyError::InvalidSource)?;
+    let tokens: Vec<LexedToken> = Lexer::new(source).collect();
+
+    // If a renamed value's assignment can't be found in the source, then minifying is repeated
+    // with the value's name left unchanged.
+    let mut pinned_names = HashSet::new();
+    let minifier = loop {
+        let mut minifier = Minifier::new(source, &ast, &tokens, pinned_names.clone());
+        let mut first_local_name = 0;
+        if settings.string_table {
+            minifier.table_name = Some(minifier.fresh_name(0));
+            first_local_name = 1;
+        }
+        if let Some(entry_point) = ast.entry_point() {
+            minifier.visit(entry_point, first_local_name);
+        }
+
+        if minifier.unlocated_names.is_empty() {
+            break minifier;
+        }
+        pinned_names.extend(minifier.unlocated_names);
+    };
+
+    let mut replacements = minifier.replacements;
+    let mut table_entries = Vec::new();
+    if let Some(table_name) = &minifier.table_name {
+        let (entries, table_replacements) = string_table(source, table_name, &minifier.strings);
+        if entries.is_empty() {
+            // Without a table, its name can be used for locals instead
+            return minify(source, MinifySettings::default());
+        }
+        table_entries = entries;
+        replacements.extend(table_replacements);
+    }
+    replacements.sort_by_key(|(range, _)| range.start);
+
+    let strict = has_strict_directive(source);
+    let mut result = String::with_capacity(source.len());
+    if strict {
+        result.push_str("# koto: strict\n");
+    }
+    if let Some(table_name) = &minifier.table_name {
+        let declaration = if strict { "let " } else { "" };
+        let trailing_comma = if table_entries.len() == 1 { "," } else { "" };
+        result.push_str(&format!(
+            "{declaration}{table_name}=({}{trailing_comma})\n",
+            table_entries.join(",")
+        ));
+    }
+    write_tokens(source, &tokens, &replacements, &mut result);
+
+    Parser::parse(&result).map_err(MinifyError::InvalidResult)?;
+
+    Ok(result)
+}
+
+// A string literal that's a candidate for the string table
+struct StringLiteral {
+    // The literal's position in the source, including quotes
+    source_bytes: Range<usize>,
+    // The literal's contents, used to find repeated strings
+    contents: String,
+}
+
+struct Minifier<'a> {
+    source: &'a str,
+    ast: &'a Ast,
+    tokens: &'a [LexedToken],
+    // The index of each token, keyed by the token's starting position
+    token_positions: HashMap<Position, usize>,
+    // The ids that are used in the script, fresh names need to avoid these
+    used_names: HashSet<&'a str>,
+    // The cache of fresh names that have been generated so far
+    fresh_names: Vec<String>,
+    // The index of the next short name to consider when generating fresh names
+    next_short_name: usize,
+    // Names that can be accessed from outside of the functions that assign them
+    pinned_names: HashSet<String>,
+    // The names that are assigned in the main block
+    top_level_names: HashSet<String>,
+    // The names that are currently visible, mapped to their new names if they've been renamed
+    scope: HashMap<String, Option<String>>,
+    // The source replacements that have been found so far
+    replacements: Vec<(Range<usize>, String)>,
+    // The name of the string table, if enabled
+    table_name: Option<String>,
+    // The string literals that could be moved into the string table
+    strings: Vec<StringLiteral>,
+    // Renamed names that couldn't be found in the source
+    unlocated_names: HashSet<String>,
+    // True while visiting match patterns, where strings need to remain as literals
+    in_pattern: bool,
+}
+
+impl<'a> Minifier<'a> {
+    fn new(
+        source: &'a str,
+        ast: &'a Ast,
+        tokens: &'a [LexedToken],
+        mut pinned_names: HashSet<String>,
+    ) -> Self {
+        let mut token_positions = HashMap::new();
+        let mut used_names = HashSet::new();
+        for (i, token) in tokens.iter().enumerate() {
+            if !token.token.is_whitespace_including_newline() {
+                token_positions.entry(token.span.start).or_insert(i);
+            }
+            if token.token == Token::Id {
+                used_names.insert(token.slice(source));
+            }
+        }
+
+        let mut result = Self {
+            source,
+            ast,
+            tokens,
+            token_positions,
+            used_names,
+            fresh_names: Vec::new(),
+            next_short_name: 0,
+            pinned_names: HashSet::new(),
+            top_level_names: HashSet::new(),
+            scope: HashMap::new(),
+            replacements: Vec::new(),
+            table_name: None,
+            strings: Vec::new(),
+            unlocated_names: HashSet::new(),
+            in_pattern: false,
+        };
+
+        if let Some(entry_point) = ast.entry_point() {
+            result.find_pinned_names(entry_point, false, &mut pinned_names);
+            result.pinned_names = pinned_names;
+
+            let mut top_level_names = HashSet::new();
+            result.find_assigned_names(entry_point, &mut top_level_names);
+            result.top_level_names = top_level_names;
+        }
+
+        result
+    }
+
+    fn visit(&mut self, node_index: AstIndex, next_name: usize) {
+        let ast = self.ast;
+        match &ast.node(node_index).node {
+            Node::Id(id) => self.rename_id(node_index, *id),
+            Node::Function(f) => {
+                let mut assigned = HashSet::new();
+                for arg in f.args.iter() {
+                    self.find_pattern_names(*arg, &mut assigned);
+                }
+                self.find_assigned_names(f.body, &mut assigned);
+
+                let mut read_before_assignment = HashSet::new();
+                self.find_reads_before_assignment(
+                    node_index,
+                    &mut HashSet::new(),
+                    &mut read_before_assignment,
+                );
+
+                // Locals are renamed in the order that they appear in the source
+                let assigned_names = assigned.clone();
+                let mut locals: Vec<_> = assigned
+                    .into_iter()
+                    .filter(|name| {
+                        !self.scope.contains_key(name)
+                            && !self.top_level_names.contains(name)
+                            && !self.pinned_names.contains(name)
+                            && !read_before_assignment.contains(name)
+                    })
+                    .map(|name| {
+                        let position = self.first_position(node_index, &name);
+                        (position, name)
+                    })
+                    .collect();
+                locals.sort();
+
+                let outer_scope = self.scope.clone();
+                let mut next_name = next_name;
+                for (_, name) in locals {
+                    let new_name = self.fresh_name(next_name);
+                    next_name += 1;
+                    self.scope.insert(name, Some(new_name));
+                }
+                // Locals that weren't renamed hide any matching names in nested functions
+                for name in assigned_names {
+                    self.scope.entry(name).or_insert(None);
+                }
+                for arg in f.args.iter() {
+                    self.visit(*arg, next_name);
+                }
+                self.visit(f.body, next_name);
+                self.scope = outer_scope;
+            }
+            Node::DefaultArg { id, default } => {
+                if let Some(new_name) = self.new_name(*id) {
+                    let name = ast.constants().get_str(*id);
+                    let range = self.default_arg_range(node_index, name);
+                    self.replace(name, range, new_name);
+                }
+                self.visit(*default, next_name);
+            }
+            Node::Ellipsis(Some(id)) => self.rename_id(node_index, *id),
+            Node::Str(s) => {
+                self.visit_string(s, next_name);
+                self.add_string_literal(node_index, s);
+            }
+            Node::Map(entries) => {
+                for (key, value) in entries.iter() {
+                    match (&ast.node(*key).node, value) {
+                        (Node::Id(id), None) => {
+                            // Shorthand entries need to be expanded when the value is renamed
+                            if let Some(new_name) = self.new_name(*id) {
+                                let name = ast.constants().get_str(*id);
+                                let range = self.id_range(*key, name);
+                                self.replace(name, range, format!("{name}:{new_name}"));
+                            }
+                        }
+                        (Node::Id(_) | Node::Meta(..), _) => {}
+                        (Node::Str(s), _) => self.visit_string(s, next_name),
+                        // e.g. spread entries
+                        _ => self.visit(*key, next_name),
+                    }
+                    if let Some(value) = value {
+                        self.visit(*value, next_name);
+                    }
+                }
+            }
+            Node::Chain((chain_node, next)) => {
+                match chain_node {
+                    ChainNode::Root(root) => self.visit(*root, next_name),
+                    ChainNode::Str(s) => self.visit_string(s, next_name),
+                    ChainNode::Index(index) => self.visit(*index, next_name),
+                    ChainNode::Call { args, .. } => {
+                        for arg in args.iter() {
+                            self.visit(*arg, next_name);
+                        }
+                    }
+                    ChainNode::Id(_) | ChainNode::Optional => {}
+                }
+                if let Some(next) = next {
+                    self.visit(*next, next_name);
+                }
+            }
+            Node::Match { expression, arms } => {
+                self.visit(*expression, next_name);
+                for arm in arms.iter() {
+                    // Strings in patterns need to remain as literals
+                    self.in_pattern = true;
+                    for pattern in arm.patterns.iter() {
+                        self.visit(*pattern, next_name);
+                    }
+                    self.in_pattern = false;
+                    if let Some(condition) = arm.condition {
+                        self.visit(condition, next_name);
+                    }
+                    self.visit(arm.expression, next_name);
+                }
+            }
+            // Imported names are never renamed, and debug expressions are left unchanged so that
+            // the debug output is preserved.
+            Node::Import { .. } | Node::Debug { .. } => {}
+            _ => {
+                for child in children(ast, node_index) {
+                    self.visit(child, next_name);
+                }
+            }
+        }
+    }
+
+    fn visit_string(&mut self, s: &AstString, next_name: usize) {
+        if let StringContents::Interpolated(nodes) = &s.contents {
+            for node in nodes.iter() {
+                if let StringNode::Expression { expression, .. } = node {
+                    self.visit(*expression, next_name);
+                }
+            }
+        }
+    }
+
+    fn add_string_literal(&mut self, node_index: AstIndex, s: &AstString) {
+        if self.table_name.is_none() || self.in_pattern {
+            return;
+        }
+        let StringContents::Literal(constant) = s.contents else {
+            return;
+        };
+        let span = self.ast.span(self.ast.node(node_index).span);
+        let Some(&start) = self.token_positions.get(&span.start) else {
+            return;
+        };
+        if !matches!(self.tokens[start].token, Token::StringStart(_)) {
+            return;
+        }
+        let Some(end) = self.tokens[start..]
+            .iter()
+            .position(|token| token.token == Token::StringEnd)
+        else {
+            return;
+        };
+
+        let source_bytes =
+            self.tokens[start].source_bytes.start..self.tokens[start + end].source_bytes.end;
+
+        // Skip strings that directly follow an id, e.g. `f'x'`,
+        // which would otherwise be merged with the table name
+        if self.source[..source_bytes.start]
+            .chars()
+            .next_back()
+            .is_some_and(is_id_continue)
+        {
+            return;
+        }
+
+        self.strings.push(StringLiteral {
+            source_bytes,
+            contents: self.ast.constants().get_str(constant).to_string(),
+        });
+    }
+
+    fn rename_id(&mut self, node_index: AstIndex, id: ConstantIndex) {
+        if let Some(new_name) = self.new_name(id) {
+            let name = self.ast.constants().get_str(id);
+            let range = self.id_range(node_index, name);
+            self.replace(name, range, new_name);
+        }
+    }
+
+    fn replace(&mut self, name: &str, range: Option<Range<usize>>, replacement: String) {
+        match range {
+            Some(range) => self.replacements.push((range, replacement)),
+            None => {
+                self.unlocated_names.insert(name.to_string());
+            }
+        }
+    }
+
+    fn new_name(&self, id: ConstantIndex) -> Option<String> {
+        self.scope
+            .get(self.ast.constants().get_str(id))
+            .cloned()
+            .flatten()
+    }
+
+    // Returns the byte range of the node's id
+    //
+    // The id is either at the start of the node, or directly precedes it (e.g. `foo...`).
+    fn id_range(&self, node_index: AstIndex, name: &str) -> Option<Range<usize>> {
+        let span = self.ast.span(self.ast.node(node_index).span);
+        let start = *self.token_positions.get(&span.start)?;
+        [Some(start), start.checked_sub(1)]
+            .into_iter()
+            .flatten()
+            .map(|i| &self.tokens[i])
+            .find(|token| token.token == Token::Id && token.slice(self.source) == name)
+            .map(|token| token.source_bytes.clone())
+    }
+
+    // Returns the byte range of a default argument's id
+    //
+    // The node's span is at the end of the default value, so the id is found by searching
+    // backwards for the `=` that follows the id.
+    fn default_arg_range(&self, node_index: AstIndex, name: &str) -> Option<Range<usize>> {
+        let span = self.ast.span(self.ast.node(node_index).span);
+        let end = *self.token_positions.get(&span.start)?;
+        let mut significant_tokens = self.tokens[..end]
+            .iter()
+            .rev()
+            .filter(|token| !token.token.is_whitespace());
+        let mut next_token = significant_tokens.next()?;
+        for token in significant_tokens {
+            if token.token == Token::Id
+                && token.slice(self.source) == name
+                && next_token.token == Token::Assign
+            {
+                return Some(token.source_bytes.clone());
+            }
+            next_token = token;
+        }
+        None
+    }
+
+    // Returns the index of the first id token matching the name, starting from the node's span
+    fn first_position(&self, node_index: AstIndex, name: &str) -> usize {
+        let span = self.ast.span(self.ast.node(node_index).span);
+        let start = self.token_positions.get(&span.start).copied().unwrap_or(0);
+        self.tokens[start..]
+            .iter()
+            .position(|token| token.token == Token::Id && token.slice(self.source) == name)
+            .map_or(usize::MAX, |position| start + position)
+    }
+
+    // Returns the n-th short name that isn't already used in the script
+    fn fresh_name(&mut self, n: usize) -> String {
+        while self.fresh_names.len() <= n {
+            let name = short_name(self.next_short_name);
+            self.next_short_name += 1;
+            if is_id(&name) && !self.used_names.contains(name.as_str()) {
+                self.fresh_names.push(name);
+            }
+        }
+        self.fresh_names[n].clone()
+    }
+
+    // Finds names that are assigned in the node, excluding names assigned in nested functions
+    fn find_assigned_names(&self, node_index: AstIndex, names: &mut HashSet<String>) {
+        let ast = self.ast;
+        match &ast.node(node_index).node {
+            Node::Function(_) => {}
+            Node::Assign { target, .. } => self.find_pattern_names(*target, names),
+            Node::MultiAssign { targets, .. } => {
+                for target in targets.iter() {
+                    self.find_pattern_names(*target, names);
+                }
+            }
+            Node::For(for_loop) => {
+                for arg in for_loop.args.iter() {
+                    self.find_pattern_names(*arg, names);
+                }
+            }
+            Node::Try(try_expression) => self.find_pattern_names(try_expression.catch_arg, names),
+            Node::Match { arms, .. } => {
+                for arm in arms.iter() {
+                    for pattern in arm.patterns.iter() {
+                        self.find_pattern_names(*pattern, names);
+                    }
+                }
+            }
+            Node::Import { items, .. } => {
+                for item in items.iter() {
+                    if let Some(name) = import_item_name(ast, item.name.unwrap_or(item.item)) {
+                        names.insert(name);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        if !matches!(ast.node(node_index).node, Node::Function(_)) {
+            for child in children(ast, node_index) {
+                self.find_assigned_names(child, names);
+            }
+        }
+    }
+
+    fn find_pattern_names(&self, node_index: AstIndex, names: &mut HashSet<String>) {
+        let ast = self.ast;
+        match &ast.node(node_index).node {
+            Node::Id(id) | Node::DefaultArg { id, .. } | Node::Ellipsis(Some(id)) => {
+                names.insert(ast.constants().get_str(*id).to_string());
+            }
+            Node::Tuple(elements) | Node::List(elements) | Node::TempTuple(elements) => {
+                for element in elements.iter() {
+                    self.find_pattern_names(*element, names);
+                }
+            }
+            Node::Nested(nested)
+            | Node::MatchType {
+                pattern: nested, ..
+            } => self.find_pattern_names(*nested, names),
+            _ => {}
+        }
+    }
+
+    // Finds names that are read in a function before they're assigned
+    //
+    // These names refer to values from outside of the function (e.g. captured or exported
+    // values), and so can't be renamed.
+    //
+    // Nodes are visited in the order that they're compiled, e.g. assignment expressions are
+    // visited before their targets.
+    fn find_reads_before_assignment(
+        &self,
+        node_index: AstIndex,
+        assigned: &mut HashSet<String>,
+        result: &mut HashSet<String>,
+    ) {
+        let ast = self.ast;
+        match &ast.node(node_index).node {
+            Node::Id(id) => {
+                let name = ast.constants().get_str(*id);
+                if !assigned.contains(name) {
+                    result.insert(name.to_string());
+                }
+            }
+            Node::Function(f) => {
+                let mut function_assigned = assigned.clone();
+                for arg in f.args.iter() {
+                    if let Node::DefaultArg { default, .. } = &ast.node(*arg).node {
+                        self.find_reads_before_assignment(*default, &mut function_assigned, result);
+                    }
+                    self.find_pattern_names(*arg, &mut function_assigned);
+                }
+                self.find_reads_before_assignment(f.body, &mut function_assigned, result);
+            }
+            Node::Assign { target, expression } => {
+                self.find_reads_before_assignment(*expression, assigned, result);
+                self.find_target_reads(*target, assigned, result);
+            }
+            Node::MultiAssign {
+                targets,
+                expression,
+            } => {
+                self.find_reads_before_assignment(*expression, assigned, result);
+                for target in targets.iter() {
+                    self.find_target_reads(*target, assigned, result);
+                }
+            }
+            Node::For(for_loop) => {
+                self.find_reads_before_assignment(for_loop.iterable, assigned, result);
+                for arg in for_loop.args.iter() {
+                    self.find_pattern_names(*arg, assigned);
+                }
+                self.find_reads_before_assignment(for_loop.body, assigned, result);
+            }
+            Node::Try(try_expression) => {
+                self.find_reads_before_assignment(try_expression.try_block, assigned, result);
+                self.find_pattern_names(try_expression.catch_arg, assigned);
+                self.find_reads_before_assignment(try_expression.catch_block, assigned, result);
+                if let Some(finally_block) = try_expression.finally_block {
+                    self.find_reads_before_assignment(finally_block, assigned, result);
+                }
+            }
+            Node::Match { expression, arms } => {
+                self.find_reads_before_assignment(*expression, assigned, result);
+                for arm in arms.iter() {
+                    for pattern in arm.patterns.iter() {
+                        self.find_pattern_names(*pattern, assigned);
+                    }
+                    if let Some(condition) = arm.condition {
+                        self.find_reads_before_assignment(condition, assigned, result);
+                    }
+                    self.find_reads_before_assignment(arm.expression, assigned, result);
+                }
+            }
+            Node::Map(entries) => {
+                for (key, value) in entries.iter() {
+                    match value {
+                        Some(value) => {
+                            if let Node::Str(_) = &ast.node(*key).node {
+                                self.find_reads_before_assignment(*key, assigned, result);
+                            }
+                            self.find_reads_before_assignment(*value, assigned, result);
+                        }
+                        None => self.find_reads_before_assignment(*key, assigned, result),
+                    }
+                }
+            }
+            Node::Import { .. } => self.find_assigned_names(node_index, assigned),
+            _ => {
+                for child in children(ast, node_index) {
+                    self.find_reads_before_assignment(child, assigned, result);
+                }
+            }
+        }
+    }
+
+    // Assignments to chains (e.g. `x.foo = 1`) read the chain's root,
+    // other assignment targets are assigned names
+    fn find_target_reads(
+        &self,
+        target: AstIndex,
+        assigned: &mut HashSet<String>,
+        result: &mut HashSet<String>,
+    ) {
+        match &self.ast.node(target).node {
+            Node::Chain(_) => self.find_reads_before_assignment(target, assigned, result),
+            _ => self.find_pattern_names(target, assigned),
+        }
+    }
+
+    // Finds names that can be accessed from outside of the function that assigns them
+    //
+    // This includes exported and imported names, the names of named arguments,
+    // and names used in debug expressions.
+    fn find_pinned_names(&self, node_index: AstIndex, exported: bool, names: &mut HashSet<String>) {
+        let ast = self.ast;
+        let node = &ast.node(node_index).node;
+        match node {
+            Node::Id(id) if exported => {
+                names.insert(ast.constants().get_str(*id).to_string());
+            }
+            Node::NamedArg { id, .. } => {
+                names.insert(ast.constants().get_str(*id).to_string());
+            }
+            Node::Debug { expression, .. } => {
+                self.find_all_names(*expression, names);
+                return;
+            }
+            _ => {}
+        }
+
+        let exported = match node {
+            Node::Export(_) | Node::Import { .. } => true,
+            Node::Function(_) => false,
+            _ => exported,
+        };
+        for child in children(ast, node_index) {
+            self.find_pinned_names(child, exported, names);
+        }
+    }
+
+    fn find_all_names(&self, node_index: AstIndex, names: &mut HashSet<String>) {
+        let ast = self.ast;
+        match &ast.node(node_index).node {
+            Node::Id(id) | Node::DefaultArg { id, .. } | Node::Ellipsis(Some(id)) => {
+                names.insert(ast.constants().get_str(*id).to_string());
+            }
+            _ => {}
+        }
+        for child in children(ast, node_index) {
+            self.find_all_names(child, names);
+        }
+    }
+}
+
+// Produces the string table's entries, along with replacements for the moved strings
+//
+// Strings are only moved into the table when doing so makes the script smaller.
+fn string_table(
+    source: &str,
+    table_name: &str,
+    strings: &[StringLiteral],
+) -> (Vec<String>, Vec<(Range<usize>, String)>) {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for string in strings.iter() {
+        *counts.entry(&string.contents).or_default() += 1;
+    }
+
+    let mut entries = Vec::new();
+    let mut table_indices: HashMap<&str, Option<usize>> = HashMap::new();
+    let mut replacements = Vec::new();
+
+    for string in strings.iter() {
+        let index = *table_indices.entry(&string.contents).or_insert_with(|| {
+            let index = entries.len();
+            let literal_len = string.source_bytes.len();
+            let reference_len = format!("{table_name}[{index}]").len();
+            let saved =
+                counts[string.contents.as_str()] * literal_len.saturating_sub(reference_len);
+            // The entry's size in the table includes a separating comma
+            if saved > literal_len + 1 {
+                entries.push(source[string.source_bytes.clone()].to_string());
+                Some(index)
+            } else {
+                None
+            }
+        });
+
+        if let Some(index) = index {
+            replacements.push((
+                string.source_bytes.clone(),
+                format!("{table_name}[{index}]"),
+            ));
+        }
+    }
+
+    (entries, replacements)
+}
+
+// Writes the tokens to the output, with comments and redundant whitespace removed
+fn write_tokens(
+    source: &str,
+    tokens: &[LexedToken],
+    replacements: &[(Range<usize>, String)],
+    output: &mut String,
+) {
+    // Indentation is reduced to the rank of each line's indentation
+    let mut indents: Vec<usize> = tokens
+        .iter()
+        .filter(|token| !token.token.is_whitespace_including_newline())
+        .map(|token| token.indent)
+        .chain(Some(0))
+        .collect();
+    indents.sort();
+    indents.dedup();
+
+    let mut replacements = replacements.iter().peekable();
+    let mut line_start = true;
+    let mut pending_space = false;
+    let mut skip_until = 0;
+
+    for token in tokens.iter() {
+        if token.source_bytes.start < skip_until {
+            continue;
+        }
+
+        match token.token {
+            Token::NewLine => {
+                if !line_start {
+                    output.push('\n');
+                }
+                line_start = true;
+                pending_space = false;
+            }
+            Token::Whitespace | Token::CommentSingle | Token::CommentMulti => {
+                pending_space = !line_start;
+            }
+            _ => {
+                if line_start {
+                    let indent = indents.binary_search(&token.indent).unwrap_or(0);
+                    output.push_str(&" ".repeat(indent));
+                    line_start = false;
+                } else if pending_space {
+                    output.push(' ');
+                }
+                pending_space = false;
+
+                match replacements.peek() {
+                    Some((range, replacement)) if range.start == token.source_bytes.start => {
+                        output.push_str(replacement);
+                        skip_until = range.end;
+                        replacements.next();
+                    }
+                    _ => output.push_str(token.slice(source)),
+                }
+            }
+        }
+    }
+
+    if !line_start {
+        output.push('\n');
+    }
+}
+
+// Returns the n-th name in the sequence a, b, ..., z, aa, ab, ...
+fn short_name(mut n: usize) -> String {
+    let mut result = Vec::new();
+    loop {
+        result.push(b'a' + (n % 26) as u8);
+        if n < 26 {
+            break;
+        }
+        n = n / 26 - 1;
+    }
+    result.reverse();
+    String::from_utf8(result).unwrap()
+}
+
+// Returns true if the name is lexed as a single identifier
+fn is_id(name: &str) -> bool {
+    let mut lexer = Lexer::new(name);
+    matches!(
+        (lexer.next(), lexer.next()),
+        (Some(token), None) if token.token == Token::Id
+    )
+}
+
+fn import_item_name(ast: &Ast, node_index: AstIndex) -> Option<String> {
+    match &ast.node(node_index).node {
+        Node::Id(id) => Some(ast.constants().get_str(*id).to_string()),
+        _ => None,
+    }
+}
+
+// Returns true if the script's leading comments contain a `# koto: strict` directive
+fn has_strict_directive(source: &str) -> bool {
+    source
+        .lines()
+        .map(str::trim)
+        .take_while(|line| line.is_empty() || line.starts_with('#'))
+        .any(|line| line == "# koto: strict")
+}
+
+// Returns the indices of the node's child nodes
+pub(crate) fn children(ast: &Ast, node_index: AstIndex) -> Vec<AstIndex> {
+    let mut result = Vec::new();
+
+    match &ast.node(node_index).node {
+        Node::Nested(child)
+        | Node::RangeFrom { start: child }
+        | Node::RangeTo { end: child, .. }
+        | Node::MatchType { pattern: child, .. }
+        | Node::Deprecated {
+            function: child, ..
+        }
+        | Node::Cfg { body: child, .. }
+        | Node::Export(child)
+        | Node::UnaryOp { value: child, .. }
+        | Node::DefaultArg { default: child, .. }
+        | Node::Spread(child)
+        | Node::NamedArg { value: child, .. }
+        | Node::Loop { body: child }
+        | Node::Throw(child)
+        | Node::Propagate(child)
+        | Node::Yield(child)
+        | Node::Debug {
+            expression: child, ..
+        } => result.push(*child),
+        Node::Break(child) | Node::Return(child) => result.extend(child),
+        Node::Chain((chain_node, next)) => {
+            match chain_node {
+                ChainNode::Root(child) | ChainNode::Index(child) => result.push(*child),
+                ChainNode::Call { args, .. } => result.extend_from_slice(args),
+                ChainNode::Str(s) => push_string_children(&s.contents, &mut result),
+                ChainNode::Id(_) | ChainNode::Optional => {}
+            }
+            result.extend(next);
+        }
+        Node::Str(s) => push_string_children(&s.contents, &mut result),
+        Node::List(children)
+        | Node::Tuple(children)
+        | Node::TempTuple(children)
+        | Node::Block(children)
+        | Node::MainBlock { body: children, .. } => result.extend_from_slice(children),
+        Node::Range {
+            start, end, step, ..
+        } => {
+            result.extend([*start, *end]);
+            result.extend(step);
+        }
+        Node::Map(entries) => {
+            for (key, value) in entries.iter() {
+                result.push(*key);
+                result.extend(value);
+            }
+        }
+        Node::Function(f) => {
+            result.extend_from_slice(&f.args);
+            result.push(f.body);
+        }
+        Node::Import { from, items } => {
+            result.extend_from_slice(from);
+            for item in items.iter() {
+                result.push(item.item);
+                result.extend(item.name);
+            }
+        }
+        Node::Assign { target, expression } => result.extend([*target, *expression]),
+        Node::MultiAssign {
+            targets,
+            expression,
+        } => {
+            result.extend_from_slice(targets);
+            result.push(*expression);
+        }
+        Node::BinaryOp { lhs, rhs, .. } => result.extend([*lhs, *rhs]),
+        Node::If(if_expression) => {
+            result.push(if_expression.condition);
+            result.push(if_expression.then_node);
+            for (condition, block) in if_expression.else_if_blocks.iter() {
+                result.extend([*condition, *block]);
+            }
+            result.extend(if_expression.else_node);
+        }
+        Node::Match { expression, arms } => {
+            result.push(*expression);
+            for arm in arms.iter() {
+                result.extend_from_slice(&arm.patterns);
+                result.extend(arm.condition);
+                result.push(arm.expression);
+            }
+        }
+        Node::Switch(arms) => {
+            for arm in arms.iter() {
+                result.extend(arm.condition);
+                result.push(arm.expression);
+            }
+        }
+        Node::For(for_loop) => {
+            result.extend_from_slice(&for_loop.args);
+            result.extend([for_loop.iterable, for_loop.body]);
+        }
+        Node::While { condition, body } | Node::Until { condition, body } => {
+            result.extend([*condition, *body])
+        }
+        Node::Try(try_expression) => {
+            result.extend([
+                try_expression.try_block,
+                try_expression.catch_arg,
+                try_expression.catch_block,
+            ]);
+            result.extend(try_expression.finally_block);
+        }
+        Node::Null
+        | Node::Id(_)
+        | Node::Meta(..)
+        | Node::BoolTrue
+        | Node::BoolFalse
+        | Node::SmallInt(_)
+        | Node::Int(_)
+        | Node::Float(_)
+        | Node::RangeFull
+        | Node::Self_
+        | Node::Wildcard(_)
+        | Node::Ellipsis(_)
+        | Node::Embed { .. }
+        | Node::Continue => {}
+    }
+
+    result
+}
+
+fn push_string_children(contents: &StringContents, result: &mut Vec<AstIndex>) {
+    if let StringContents::Interpolated(nodes) = contents {
+        for node in nodes.iter() {
+            if let StringNode::Expression { expression, .. } = node {
+                result.push(*expression);
+            }
+        }
+    }
+}
@@ -71,6 +71,10 @@ pub enum Node {
         /// e.g. `1..10` - a range from 1 up to but not including 10
         /// e.g. `1..=10` - a range from 1 up to and including 10
         inclusive: bool,
+        /// The range's optional step
+        ///
+        /// e.g. `0..10 step 2`
+        step: Option<AstIndex>,
     },
 
     /// A range without a defined end
@@ -124,6 +128,47 @@ pub enum Node {
     /// A function node
     Function(Function),
 
+    /// A function that has been annotated with `@deprecated`
+    ///
+    /// e.g.
+    /// ```koto
+    /// @deprecated 'use new_fn'
+    /// old_fn = |x| new_fn x
+    /// ```
+    Deprecated {
+        /// The message that's included when calls to the function are reported
+        message: ConstantIndex,
+        /// The function node
+        function: AstIndex,
+    },
+
+    /// A block that's only compiled when a flag has been enabled in the compiler's settings
+    ///
+    /// e.g.
+    /// ```koto
+    /// @cfg 'debug'
+    ///   print 'x: {x}'
+    /// ```
+    Cfg {
+        /// The name of the flag that enables the block
+        flag: ConstantIndex,
+        /// The block's body
+        body: AstIndex,
+    },
+
+    /// The contents of a file, embedded in the script when it's compiled
+    ///
+    /// e.g. `table = @embed 'data/table.csv'`
+    Embed {
+        /// The file's path, as written in the script
+        path: ConstantIndex,
+        /// The file's contents
+        ///
+        /// The contents are only available when the script was parsed with an
+        /// [EmbedLoader](crate::EmbedLoader).
+        contents: Option<ConstantIndex>,
+    },
+
     /// An import expression
     ///
     /// e.g. `from foo.bar import baz, 'qux'
@@ -190,6 +235,17 @@ pub enum Node {
         arms: Vec<MatchArm>,
     },
 
+    /// A type pattern in a match arm, e.g. `n: Number`
+    ///
+    /// The pattern matches values with the given type, as returned by `koto.type`,
+    /// with `Number` matching both `Int` and `Float` values.
+    MatchType {
+        /// The id or wildcard that the matched value is assigned to
+        pattern: AstIndex,
+        /// The name of the expected type
+        type_name: ConstantIndex,
+    },
+
     /// A switch expression
     Switch(Vec<SwitchArm>),
 
@@ -206,6 +262,33 @@ pub enum Node {
     /// Used when capturing variadic arguments, and when unpacking list or tuple values.
     Ellipsis(Option<ConstantIndex>),
 
+    /// A function argument with a default value, e.g. `|x, y = 2|`
+    ///
+    /// The default value is used when the argument is missing or `null`.
+    DefaultArg {
+        /// The argument's id
+        id: ConstantIndex,
+        /// The default value's expression
+        default: AstIndex,
+    },
+
+    /// A spread value, e.g. `f args...`, `[1, xs...]`, or `{defaults..., x: 1}`
+    ///
+    /// In function calls and lists, the values contained in the iterable expression are
+    /// inserted as separate values. In maps, the entries of the map are inserted, with the spread
+    /// node used as an entry's key.
+    Spread(AstIndex),
+
+    /// A named argument in a function call, e.g. `f x: 1, y: 2`
+    ///
+    /// Named arguments are matched against the called function's argument names.
+    NamedArg {
+        /// The name of the argument
+        id: ConstantIndex,
+        /// The argument's value
+        value: AstIndex,
+    },
+
     /// A `for` loop
     For(AstFor),
 
@@ -246,6 +329,12 @@ pub enum Node {
     /// A throw expression
     Throw(AstIndex),
 
+    /// An error propagation expression, e.g. `x?`
+    ///
+    /// If the expression evaluates to an `err` result, then the result is returned from the
+    /// enclosing function, otherwise the `ok` result's value is unwrapped.
+    Propagate(AstIndex),
+
     /// A yield expression
     Yield(AstIndex),
 
@@ -355,6 +444,7 @@ pub struct AstIf {
 pub enum AstUnaryOp {
     Negate,
     Not,
+    BitwiseNot,
 }
 
 /// An operation used in BinaryOp expressions
@@ -377,8 +467,14 @@ pub enum AstBinaryOp {
     LessOrEqual,
     Greater,
     GreaterOrEqual,
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    ShiftLeft,
+    ShiftRight,
     And,
     Or,
+    NullCoalesce,
     Pipe,
 }
 
@@ -418,6 +514,11 @@ pub enum ChainNode {
     Str(AstString),
     /// An index operation using square `[]` brackets.
     Index(AstIndex),
+    /// A `?` that makes the following `.` access optional, e.g. `x?.foo`
+    ///
+    /// The access evaluates to null if the accessed value is null or if the key isn't found.
+    /// `ok` results are unwrapped before the access, and `err` results are propagated as with `?`.
+    Optional,
     /// A function call
     Call {
         /// The arguments used in the function call
@@ -427,9 +528,9 @@ pub enum ChainNode {
         /// This is not cosmetic, as parentheses represent a 'closed call', which has an impact on
         /// function piping:
         /// e.g.
-        ///   `99 >> foo.bar 42` is equivalent to `foo.bar(42, 99)`
+        ///   `99 -> foo.bar 42` is equivalent to `foo.bar(42, 99)`
         /// but:
-        ///   `99 >> foo.bar(42)` is equivalent to `foo.bar(42)(99)`.
+        ///   `99 -> foo.bar(42)` is equivalent to `foo.bar(42)(99)`.
         with_parens: bool,
     },
 }
@@ -512,6 +613,16 @@ pub enum MetaKeyId {
     Equal,
     /// @!=
     NotEqual,
+    /// @&
+    BitwiseAnd,
+    /// @|
+    BitwiseOr,
+    /// @^
+    BitwiseXor,
+    /// @<<
+    ShiftLeft,
+    /// @>>
+    ShiftRight,
     /// @[]
     Index,
 
@@ -547,6 +658,12 @@ pub enum MetaKeyId {
     /// @main
     Main,
 
+    /// @command command_name
+    Command,
+
+    /// @repl_display
+    ReplDisplay,
+
     /// @meta name
     Named,
 
@@ -594,6 +711,11 @@ impl fmt::Display for MetaKeyId {
                 GreaterOrEqual => ">=",
                 Equal => "==",
                 NotEqual => "!=",
+                BitwiseAnd => "&",
+                BitwiseOr => "|",
+                BitwiseXor => "^",
+                ShiftLeft => "<<",
+                ShiftRight => ">>",
                 Index => "[]",
                 Display => "display",
                 Iterator => "iterator",
@@ -609,6 +731,8 @@ impl fmt::Display for MetaKeyId {
                 PreTest => "pre_test",
                 PostTest => "post_test",
                 Main => "main",
+                Command => "command",
+                ReplDisplay => "repl_display",
                 Named => "meta",
                 Invalid => unreachable!(),
             }
@@ -3,13 +3,17 @@
 use crate::{
     ast::{Ast, AstIndex},
     constant_pool::{ConstantIndex, ConstantPoolBuilder},
-    error::{Error, ErrorKind, ExpectedIndentation, InternalError, Result, SyntaxError},
+    error::{
+        Error, ErrorKind, ExpectedIndentation, InternalError, Result, SyntaxError, Warning,
+        WarningKind,
+    },
     node::*,
     StringFormatOptions,
 };
 use koto_lexer::{LexedToken, Lexer, Span, StringType, Token};
 use std::{
     collections::HashSet,
+    fmt,
     iter::Peekable,
     str::{Chars, FromStr},
 };
@@ -61,6 +65,11 @@ impl Frame {
         self.pending_accesses.remove(&id);
     }
 
+    // Returns true if the id has been assigned (or is being assigned) within the frame
+    fn is_declared(&self, id: ConstantIndex) -> bool {
+        self.ids_assigned_in_frame.contains(&id) || self.pending_assignments.contains(&id)
+    }
+
     // At the end of an expression, determine which RHS accesses are non-local
     fn finalize_id_accesses(&mut self) {
         for id in self.pending_accesses.drain() {
@@ -114,6 +123,28 @@ struct ExpressionContext {
     //      ^~~ The first line in an indented block will have the flag set to true to allow the
     //          block to be parsed as a map, see parse_indented_block().
     allow_map_block: bool,
+    // When true, the expression is an item in a comma separated sequence (e.g. a list entry or a
+    // call arg), so a comma ends the expression rather than continuing it.
+    // e.g.
+    //
+    // x = [if a then b else c, d]
+    //                        ^~~ The inline if's else branch ends at the comma rather than
+    //                            being parsed as a tuple.
+    comma_separated_item: bool,
+    // When true, the expression is a function's default argument value, so a `|` ends the
+    // function's args rather than being parsed as the bitwise or operator.
+    // e.g.
+    //
+    // f = |x = 1| x
+    //           ^~~ The end of the args, parentheses are needed for a bitwise or in the value
+    default_arg_value: bool,
+    // When true, the expression is the end of a range, so a following `step` isn't treated as
+    // an argument in a space-separated call.
+    // e.g.
+    //
+    // 0..size x step 2
+    //           ^~~~ The start of the range's step, rather than an argument for `size`
+    range_end: bool,
     // The indentation rules for the current context
     expected_indentation: Indentation,
 }
@@ -141,6 +172,9 @@ impl ExpressionContext {
             allow_space_separated_call: true,
             allow_linebreaks: true,
             allow_map_block: false,
+            comma_separated_item: false,
+            default_arg_value: false,
+            range_end: false,
             expected_indentation: Indentation::Greater,
         }
     }
@@ -150,6 +184,9 @@ impl ExpressionContext {
             allow_space_separated_call: false,
             allow_linebreaks: false,
             allow_map_block: false,
+            comma_separated_item: false,
+            default_arg_value: false,
+            range_end: false,
             expected_indentation: Indentation::Greater,
         }
     }
@@ -159,6 +196,9 @@ impl ExpressionContext {
             allow_space_separated_call: true,
             allow_linebreaks: false,
             allow_map_block: false,
+            comma_separated_item: false,
+            default_arg_value: false,
+            range_end: false,
             expected_indentation: Indentation::Greater,
         }
     }
@@ -170,6 +210,9 @@ impl ExpressionContext {
             allow_space_separated_call: true,
             allow_linebreaks: self.allow_linebreaks,
             allow_map_block: false,
+            comma_separated_item: false,
+            default_arg_value: false,
+            range_end: false,
             expected_indentation: Indentation::Greater,
         }
     }
@@ -182,6 +225,9 @@ impl ExpressionContext {
             allow_space_separated_call: true,
             allow_linebreaks: true,
             allow_map_block: false,
+            comma_separated_item: true,
+            default_arg_value: false,
+            range_end: false,
             expected_indentation: Indentation::Flexible,
         }
     }
@@ -196,6 +242,9 @@ impl ExpressionContext {
             allow_space_separated_call: false,
             allow_linebreaks: true,
             allow_map_block: false,
+            comma_separated_item: true,
+            default_arg_value: false,
+            range_end: false,
             expected_indentation: Indentation::Flexible,
         }
     }
@@ -218,6 +267,9 @@ impl ExpressionContext {
             allow_space_separated_call: self.allow_space_separated_call,
             allow_linebreaks: self.allow_linebreaks,
             allow_map_block: false,
+            comma_separated_item: self.comma_separated_item,
+            default_arg_value: self.default_arg_value,
+            range_end: self.range_end,
             expected_indentation,
         }
     }
@@ -230,6 +282,40 @@ impl ExpressionContext {
     }
 }
 
+/// Loads the contents of a file that's embedded in a script with `@embed`
+///
+/// The file's path is provided as it was written in the script, and a message describing the
+/// failure should be returned if the file can't be loaded.
+pub type EmbedLoader<'a> = &'a dyn Fn(&str) -> std::result::Result<String, String>;
+
+/// Settings that modify how a script is parsed
+#[derive(Clone, Copy, Default)]
+pub struct ParserSettings<'a> {
+    /// Requires new bindings to be explicitly declared with `let`
+    ///
+    /// When enabled, assigning to an identifier that hasn't been declared in the current scope
+    /// (e.g. with `let`, as a function argument, or as a loop argument) results in a syntax error.
+    ///
+    /// Strict mode can also be enabled for an individual script with a `# koto: strict`
+    /// directive in the script's leading comments.
+    pub strict: bool,
+    /// Loads the contents of files that are embedded with `@embed`
+    ///
+    /// When no loader is provided, `@embed` expressions are parsed without their contents, which
+    /// is useful for tools that work with a script's source. The contents need to be loaded for
+    /// the script to be compiled.
+    pub embed_loader: Option<EmbedLoader<'a>>,
+}
+
+impl fmt::Debug for ParserSettings<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ParserSettings")
+            .field("strict", &self.strict)
+            .field("embed_loader", &self.embed_loader.is_some())
+            .finish()
+    }
+}
+
 /// Koto's parser
 pub struct Parser<'source> {
     source: &'source str,
@@ -239,11 +325,25 @@ pub struct Parser<'source> {
     current_token: LexedToken,
     current_line: u32,
     frame_stack: Vec<Frame>,
+    // True when undeclared assignments should be rejected, see [ParserSettings::strict]
+    strict: bool,
+    // The message from a `@deprecated` annotation, taken by the next parsed function
+    pending_deprecation: Option<ConstantIndex>,
+    // Loads the contents of embedded files, see [ParserSettings::embed_loader]
+    embed_loader: Option<EmbedLoader<'source>>,
 }
 
 impl<'source> Parser<'source> {
     /// Takes in a source script, and produces an Ast
     pub fn parse(source: &'source str) -> Result<Ast> {
+        Self::parse_with_settings(source, ParserSettings::default())
+    }
+
+    /// Takes in a source script, and produces an Ast using the provided settings
+    pub fn parse_with_settings(
+        source: &'source str,
+        settings: ParserSettings<'source>,
+    ) -> Result<Ast> {
         let capacity_guess = source.len() / 4;
         let mut parser = Parser {
             source,
@@ -253,6 +353,9 @@ impl<'source> Parser<'source> {
             current_token: LexedToken::default(),
             current_line: 0,
             frame_stack: Vec::new(),
+            strict: settings.strict || has_strict_directive(source),
+            pending_deprecation: None,
+            embed_loader: settings.embed_loader,
         };
 
         parser.consume_main_block()?;
@@ -491,7 +594,7 @@ impl<'source> Parser<'source> {
         };
 
         let expression_start = match self.parse_term(context)? {
-            Some(term) => term,
+            Some(term) => self.check_for_propagation_after_node(term, context)?,
             None => return Ok(None),
         };
 
@@ -539,26 +642,43 @@ impl<'source> Parser<'source> {
         let start_indent = self.current_indent();
 
         if let Some(assignment_expression) =
-            self.parse_assign_expression(expression_start, previous_expressions, context)?
+            self.parse_assign_expression(expression_start, previous_expressions, false, context)?
         {
             return Ok(Some(assignment_expression));
         } else if let Some(next) = self.peek_token_with_context(context) {
-            let maybe_pipe = match next.token {
-                Token::Greater => {
-                    if matches!(self.peek_token_n(next.peek_count + 1), Some(Token::Greater)) {
-                        Some(Token::Pipe)
-                    } else {
-                        None
+            // Some operators are made up of multiple tokens, or share their token with other
+            // syntax, so they're detected here rather than in the lexer.
+            let (op, extra_op_tokens) = match next.token {
+                Token::Greater => match self.peek_token_n(next.peek_count + 1) {
+                    Some(Token::Greater) => (Token::ShiftRight, 1),
+                    _ => (Token::Greater, 0),
+                },
+                Token::Less => match self.peek_token_n(next.peek_count + 1) {
+                    Some(Token::Less) => (Token::ShiftLeft, 1),
+                    _ => (Token::Less, 0),
+                },
+                // A `|` in operator position is the bitwise or operator, unless a default
+                // argument value is being parsed, in which case it's the end of the args.
+                Token::Function if context.default_arg_value => {
+                    // `|x = 1 | 2| x` would be surprising if the ` | ` ended the args,
+                    // so a `|` with whitespace on both sides is rejected.
+                    if next.peek_count > 0
+                        && self
+                            .peek_token_n(next.peek_count + 1)
+                            .is_some_and(|token| token.is_whitespace_including_newline())
+                    {
+                        return self
+                            .consume_token_and_error(SyntaxError::AmbiguousBitwiseOrInDefaultArg);
                     }
+                    (Token::Function, 0)
                 }
-                _ => None,
+                Token::Function => (Token::BitwiseOr, 0),
+                token => (token, 0),
             };
-            if let Some((left_priority, right_priority)) =
-                operator_precedence(maybe_pipe.unwrap_or(next.token))
-            {
+            if let Some((left_priority, right_priority)) = operator_precedence(op) {
                 if left_priority >= min_precedence {
-                    let (op, _) = self.consume_token_with_context(context).unwrap();
-                    if maybe_pipe.is_some() {
+                    self.consume_token_with_context(context);
+                    for _ in 0..extra_op_tokens {
                         self.consume_token();
                     }
                     let op_span = self.current_span();
@@ -617,15 +737,22 @@ impl<'source> Parser<'source> {
                         Equal => AstBinaryOp::Equal,
                         NotEqual => AstBinaryOp::NotEqual,
 
-                        Greater if maybe_pipe.is_none() => AstBinaryOp::Greater,
+                        Greater => AstBinaryOp::Greater,
                         GreaterOrEqual => AstBinaryOp::GreaterOrEqual,
                         Less => AstBinaryOp::Less,
                         LessOrEqual => AstBinaryOp::LessOrEqual,
 
+                        BitwiseAnd => AstBinaryOp::BitwiseAnd,
+                        BitwiseOr => AstBinaryOp::BitwiseOr,
+                        BitwiseXor => AstBinaryOp::BitwiseXor,
+                        ShiftLeft => AstBinaryOp::ShiftLeft,
+                        ShiftRight => AstBinaryOp::ShiftRight,
+
                         And => AstBinaryOp::And,
                         Or => AstBinaryOp::Or,
+                        NullCoalesce => AstBinaryOp::NullCoalesce,
 
-                        Greater if maybe_pipe.is_some() => AstBinaryOp::Pipe,
+                        Pipe => AstBinaryOp::Pipe,
 
                         _ => unreachable!(), // The list of tokens here matches the operators in
                                              // operator_precedence()
@@ -659,10 +786,14 @@ impl<'source> Parser<'source> {
     // series will be passed in as `lhs`, with the previous targets passed in as `previous_lhs`.
     //
     // If the assignment is an export then operators other than `=` will be rejected.
+    //
+    // `declaring` is true when the targets are being declared with `let` or `export`, in which
+    // case they're allowed to be undeclared in strict mode.
     fn parse_assign_expression(
         &mut self,
         lhs: AstIndex,
         previous_lhs: &[AstIndex],
+        declaring: bool,
         context: &ExpressionContext,
     ) -> Result<Option<AstIndex>> {
         match self
@@ -678,8 +809,9 @@ impl<'source> Parser<'source> {
         for lhs_expression in previous_lhs.iter().chain(std::iter::once(&lhs)) {
             // Note which identifiers are being assigned to
             match self.ast.node(*lhs_expression).node.clone() {
-                Node::Id(id_index) => {
-                    self.frame_mut()?.add_local_id_assignment(id_index);
+                Node::Id(id_index) => self.add_assigned_id(id_index, *lhs_expression, declaring)?,
+                Node::Tuple(nested_targets) | Node::List(nested_targets) => {
+                    self.add_nested_assign_targets(&nested_targets, declaring)?
                 }
                 Node::Meta { .. } | Node::Chain(_) | Node::Wildcard(_) => {}
                 _ => return self.error(SyntaxError::ExpectedAssignmentTarget),
@@ -722,6 +854,77 @@ impl<'source> Parser<'source> {
         }
     }
 
+    fn add_assigned_id(
+        &mut self,
+        id_index: ConstantIndex,
+        target: AstIndex,
+        declaring: bool,
+    ) -> Result<()> {
+        if !declaring && !self.frame()?.is_declared(id_index) {
+            self.check_undeclared_assignment(id_index, target)?;
+        }
+        self.frame_mut()?.add_local_id_assignment(id_index);
+        Ok(())
+    }
+
+    // Notes the identifiers that are assigned in a nested assignment target
+    //
+    // e.g. `(a, (b, _)), c = x`
+    fn add_nested_assign_targets(&mut self, targets: &[AstIndex], declaring: bool) -> Result<()> {
+        for target in targets {
+            match self.ast.node(*target).node.clone() {
+                Node::Id(id_index) => self.add_assigned_id(id_index, *target, declaring)?,
+                Node::Tuple(nested_targets) | Node::List(nested_targets) => {
+                    self.add_nested_assign_targets(&nested_targets, declaring)?
+                }
+                Node::Wildcard(_) => {}
+                _ => return self.error(SyntaxError::ExpectedAssignmentTarget),
+            }
+        }
+
+        Ok(())
+    }
+
+    // Checks an assignment to an ID that hasn't been declared in the current frame
+    //
+    // In strict mode the assignment is an error, otherwise a warning is produced when the
+    // assignment would shadow a value that's declared at module level.
+    fn check_undeclared_assignment(
+        &mut self,
+        id_index: ConstantIndex,
+        target: AstIndex,
+    ) -> Result<()> {
+        let outer_frames = &self.frame_stack[..self.frame_stack.len().saturating_sub(1)];
+        let declared_in_outer_frame = outer_frames.iter().any(|frame| frame.is_declared(id_index));
+        // Values from enclosing functions aren't warned about, only module-level values
+        let declared_at_module_level = match outer_frames.split_first() {
+            Some((module_frame, function_frames)) => {
+                module_frame.is_declared(id_index)
+                    && !function_frames
+                        .iter()
+                        .any(|frame| frame.is_declared(id_index))
+            }
+            None => false,
+        };
+        let id = self.constants.get_str(id_index).to_string();
+
+        if self.strict {
+            if declared_in_outer_frame {
+                self.error(SyntaxError::AssignmentToOuterValue(id))
+            } else {
+                self.error(SyntaxError::UndeclaredAssignment(id))
+            }
+        } else {
+            if declared_at_module_level {
+                self.ast.add_warning(Warning {
+                    warning: WarningKind::ModuleValueShadowed(id),
+                    span: *self.ast.span(self.ast.node(target).span),
+                });
+            }
+            Ok(())
+        }
+    }
+
     // Peeks the next token and dispatches to the relevant parsing functions
     fn parse_term(&mut self, context: &ExpressionContext) -> Result<Option<AstIndex>> {
         use Node::*;
@@ -760,6 +963,15 @@ impl<'source> Parser<'source> {
             }
             Token::Id => self.consume_id_expression(context),
             Token::Self_ => self.consume_self_expression(context),
+            Token::At if self.peeked_deprecated_annotation(peeked.peek_count) => {
+                self.consume_deprecated_annotation(context)
+            }
+            Token::At if self.peeked_cfg_annotation(peeked.peek_count) => {
+                self.consume_cfg_annotation(context)
+            }
+            Token::At if self.peeked_embed_expression(peeked.peek_count) => {
+                self.consume_embed_expression(context)
+            }
             Token::At => {
                 let map_block_allowed =
                     context.allow_map_block || peeked.info.indent > start_indent;
@@ -779,7 +991,7 @@ impl<'source> Parser<'source> {
                 {
                     self.consume_map_block(meta_key, start_span, &meta_context)
                 } else {
-                    match self.parse_assign_expression(meta_key, &[], &meta_context)? {
+                    match self.parse_assign_expression(meta_key, &[], false, &meta_context)? {
                         Some(result) => self.push_node(Node::Export(result)),
                         None => self
                             .consume_token_and_error(SyntaxError::ExpectedAssignmentAfterMetaKey),
@@ -812,6 +1024,17 @@ impl<'source> Parser<'source> {
                 }
                 None => return Ok(None),
             },
+            Token::BitwiseNot => {
+                self.consume_token_with_context(context); // Token::BitwiseNot
+                if let Some(term) = self.parse_term(&ExpressionContext::restricted())? {
+                    self.push_node(Node::UnaryOp {
+                        op: AstUnaryOp::BitwiseNot,
+                        value: term,
+                    })
+                } else {
+                    self.consume_token_and_error(SyntaxError::ExpectedExpression)
+                }
+            }
             Token::Not => {
                 self.consume_token_with_context(context);
                 if let Some(expression) = self.parse_expression(&ExpressionContext {
@@ -863,10 +1086,10 @@ impl<'source> Parser<'source> {
             Token::From | Token::Import => self.consume_import(context),
             Token::Export => self.consume_export(context),
             Token::Try => self.consume_try_expression(context),
+            Token::Let => self.consume_let_expression(context),
             // Reserved keywords
             Token::Await => self.consume_token_and_error(SyntaxError::ReservedKeyword),
             Token::Const => self.consume_token_and_error(SyntaxError::ReservedKeyword),
-            Token::Let => self.consume_token_and_error(SyntaxError::ReservedKeyword),
             // An error occurred in the lexer
             Token::Error => self.consume_token_and_error(SyntaxError::LexerError),
             _ => return Ok(None),
@@ -882,11 +1105,16 @@ impl<'source> Parser<'source> {
     //   #   ^ You are here
     fn consume_function(&mut self, context: &ExpressionContext) -> Result<AstIndex> {
         let start_indent = self.current_indent();
+        let deprecation = self.pending_deprecation.take();
 
         self.consume_token_with_context(context); // Token::Function
 
         let span_start = self.current_span().start;
 
+        // The function's frame is pushed before parsing the args so that the accesses made in
+        // default argument values are made within the function's scope.
+        self.frame_stack.push(Frame::default());
+
         // Parse function's args
         let mut arg_nodes = Vec::new();
         let mut arg_ids = Vec::new();
@@ -899,13 +1127,38 @@ impl<'source> Parser<'source> {
                 .unwrap();
             match self.parse_id_or_wildcard(context)? {
                 Some(IdOrWildcard::Id(constant_index)) => {
-                    arg_ids.push(constant_index);
-                    arg_nodes.push(self.push_node(Node::Id(constant_index))?);
+                    if self.peek_next_token_on_same_line() == Some(Token::Assign) {
+                        self.consume_next_token_on_same_line();
 
-                    if self.peek_token() == Some(Token::Ellipsis) {
-                        self.consume_token();
-                        is_variadic = true;
-                        break;
+                        // Earlier args are available when evaluating the default value
+                        let frame = self.frame_mut()?;
+                        frame.ids_assigned_in_frame.extend(arg_ids.iter());
+
+                        let Some(default) = self.parse_expression(&ExpressionContext {
+                            default_arg_value: true,
+                            ..ExpressionContext::restricted()
+                        })?
+                        else {
+                            return self
+                                .consume_token_and_error(SyntaxError::ExpectedDefaultArgValue);
+                        };
+
+                        self.frame_mut()?.finalize_id_accesses();
+
+                        arg_ids.push(constant_index);
+                        arg_nodes.push(self.push_node(Node::DefaultArg {
+                            id: constant_index,
+                            default,
+                        })?);
+                    } else {
+                        arg_ids.push(constant_index);
+                        arg_nodes.push(self.push_node(Node::Id(constant_index))?);
+
+                        if self.peek_token() == Some(Token::Ellipsis) {
+                            self.consume_token();
+                            is_variadic = true;
+                            break;
+                        }
                     }
                 }
                 Some(IdOrWildcard::Wildcard(maybe_id)) => {
@@ -954,9 +1207,9 @@ impl<'source> Parser<'source> {
         }
 
         // body
-        let mut function_frame = Frame::default();
-        function_frame.ids_assigned_in_frame.extend(arg_ids.iter());
-        self.frame_stack.push(function_frame);
+        self.frame_mut()?
+            .ids_assigned_in_frame
+            .extend(arg_ids.iter());
 
         let body = if let Some(block) = self.parse_indented_block()? {
             block
@@ -980,8 +1233,12 @@ impl<'source> Parser<'source> {
         let local_count = function_frame.local_count();
 
         let span_end = self.current_span().end;
+        let span = Span {
+            start: span_start,
+            end: span_end,
+        };
 
-        self.ast.push(
+        let function = self.ast.push(
             Node::Function(Function {
                 args: arg_nodes,
                 local_count,
@@ -990,13 +1247,159 @@ impl<'source> Parser<'source> {
                 is_variadic,
                 is_generator: function_frame.contains_yield,
             }),
-            Span {
-                start: span_start,
-                end: span_end,
-            },
+            span,
+        )?;
+
+        match deprecation {
+            Some(message) => self.ast.push(Node::Deprecated { message, function }, span),
+            None => Ok(function),
+        }
+    }
+
+    // Returns true if the `@` token at the given peek position starts a `@deprecated` annotation
+    fn peeked_deprecated_annotation(&mut self, at_peek_count: usize) -> bool {
+        matches!(
+            self.lexer.peek(at_peek_count + 1),
+            Some(peeked) if peeked.token == Token::Id && peeked.slice(self.source) == "deprecated"
         )
     }
 
+    // Parses a `@deprecated` annotation, along with the function assignment that follows it
+    //
+    // e.g.
+    //   @deprecated 'use new_fn'
+    //   # ^ You are here
+    //   old_fn = |x| new_fn x
+    fn consume_deprecated_annotation(&mut self, context: &ExpressionContext) -> Result<AstIndex> {
+        self.consume_until_token_with_context(context);
+        let start_indent = self.current_indent();
+
+        self.consume_token(); // Token::At
+        self.consume_token(); // deprecated
+
+        let message =
+            self.parse_literal_string_on_same_line(SyntaxError::ExpectedDeprecationMessage)?;
+
+        // The annotated expression is expected on the following line
+        let line_context = ExpressionContext::permissive()
+            .with_expected_indentation(Indentation::Equal(start_indent));
+        if self.peek_next_token_on_same_line() != Some(Token::NewLine)
+            || self.peek_token_with_context(&line_context).is_none()
+        {
+            return self.consume_token_and_error(SyntaxError::ExpectedDeprecatedFunction);
+        }
+        self.consume_until_token_with_context(&line_context);
+
+        self.pending_deprecation = Some(message);
+        let expression = self.parse_line(&ExpressionContext::permissive())?;
+
+        let (Some(expression), None) = (expression, self.pending_deprecation.take()) else {
+            return self.error(SyntaxError::ExpectedDeprecatedFunction);
+        };
+
+        // Only assigned (or exported) functions can be annotated
+        let mut node = expression;
+        loop {
+            node = match &self.ast.node(node).node {
+                Node::Export(exported) => *exported,
+                Node::Assign { expression, .. } => *expression,
+                Node::Deprecated { .. } if node != expression => return Ok(expression),
+                _ => return self.error(SyntaxError::ExpectedDeprecatedFunction),
+            };
+        }
+    }
+
+    // Returns true if the `@` token at the given peek position starts a `@cfg` block
+    fn peeked_cfg_annotation(&mut self, at_peek_count: usize) -> bool {
+        matches!(
+            self.lexer.peek(at_peek_count + 1),
+            Some(peeked) if peeked.token == Token::Id && peeked.slice(self.source) == "cfg"
+        )
+    }
+
+    // Parses a `@cfg` block
+    //
+    // e.g.
+    //   @cfg 'debug'
+    //   # ^ You are here
+    //     print 'x: {x}'
+    fn consume_cfg_annotation(&mut self, context: &ExpressionContext) -> Result<AstIndex> {
+        self.consume_until_token_with_context(context);
+
+        self.consume_token(); // Token::At
+        let start_span = self.current_span();
+        self.consume_token(); // cfg
+
+        let flag = self.parse_literal_string_on_same_line(SyntaxError::ExpectedCfgFlag)?;
+
+        let Some(body) = self.parse_indented_block()? else {
+            return self.consume_token_on_same_line_and_error(ExpectedIndentation::CfgBlock);
+        };
+
+        self.push_node_with_start_span(Node::Cfg { flag, body }, start_span)
+    }
+
+    // Returns true if the `@` token at the given peek position starts an `@embed` expression
+    fn peeked_embed_expression(&mut self, at_peek_count: usize) -> bool {
+        matches!(
+            self.lexer.peek(at_peek_count + 1),
+            Some(peeked) if peeked.token == Token::Id && peeked.slice(self.source) == "embed"
+        )
+    }
+
+    // Parses an `@embed` expression, loading the embedded file's contents
+    //
+    // e.g.
+    //   table = @embed 'data/table.csv'
+    //   #       ^ You are here
+    fn consume_embed_expression(&mut self, context: &ExpressionContext) -> Result<AstIndex> {
+        self.consume_until_token_with_context(context);
+
+        self.consume_token(); // Token::At
+        let start_span = self.current_span();
+        self.consume_token(); // embed
+
+        let path = self.parse_literal_string_on_same_line(SyntaxError::ExpectedEmbedPath)?;
+
+        let contents = match self.embed_loader {
+            Some(load_file) => {
+                let path = self.constants.get_str(path).to_string();
+                match load_file(&path) {
+                    Ok(contents) => Some(self.add_string_constant(&contents)?),
+                    Err(error) => {
+                        return self.error(SyntaxError::FailedToEmbedFile { path, error })
+                    }
+                }
+            }
+            None => None,
+        };
+
+        self.push_node_with_start_span(Node::Embed { path, contents }, start_span)
+    }
+
+    // Parses a string without interpolation that's expected on the current line
+    //
+    // Used for the arguments of annotations like `@deprecated` and `@cfg`.
+    fn parse_literal_string_on_same_line(&mut self, error: SyntaxError) -> Result<ConstantIndex> {
+        match self.peek_next_token_on_same_line() {
+            Some(Token::StringStart(_)) => {
+                self.consume_until_next_token_on_same_line();
+                match self.parse_string(&ExpressionContext::inline())? {
+                    Some(ParseStringOutput {
+                        string:
+                            AstString {
+                                contents: StringContents::Literal(result),
+                                ..
+                            },
+                        ..
+                    }) => Ok(result),
+                    _ => self.error(error),
+                }
+            }
+            _ => self.consume_token_and_error(error),
+        }
+    }
+
     // Helper for parse_function() that recursively parses nested function arguments
     // e.g.
     //   f = |(foo, bar, (x, y))|
@@ -1076,6 +1479,7 @@ impl<'source> Parser<'source> {
 
         if context.allow_space_separated_call {
             let mut arg_context = ExpressionContext {
+                comma_separated_item: true,
                 expected_indentation: Indentation::Greater,
                 ..*context
             };
@@ -1088,14 +1492,50 @@ impl<'source> Parser<'source> {
 
                 if new_line {
                     arg_context.expected_indentation = Indentation::Equal(peeked.info.indent);
-                } else if args.is_empty() && self.peek_token() != Some(Token::Whitespace) {
-                    break;
+                } else if args.is_empty() {
+                    if self.peek_token() != Some(Token::Whitespace) {
+                        break;
+                    }
+                    // `step` following a range's end is the start of the range's step
+                    if context.range_end && self.peek_step() {
+                        break;
+                    }
+                    // A `|` followed by whitespace is the bitwise or operator rather than the
+                    // start of a function arg, e.g. `x | y`.
+                    if peeked.token == Token::Function
+                        && self
+                            .peek_token_n(peeked.peek_count + 1)
+                            .is_some_and(|token| token.is_whitespace_including_newline())
+                    {
+                        break;
+                    }
                 }
 
-                if let Some(expression) = self
+                // An indented first arg that starts with `id:` is a map block rather than a
+                // named arg, e.g.
+                //   x.takes_a_map
+                //     foo: 42
+                let named_arg = if new_line && args.is_empty() {
+                    None
+                } else {
+                    self.parse_named_arg_id(&arg_context)?
+                };
+
+                if let Some(id) = named_arg {
+                    let Some(value) = self.parse_expression_with_min_precedence(
+                        MIN_PRECEDENCE_AFTER_PIPE,
+                        &arg_context,
+                    )?
+                    else {
+                        return self.consume_token_and_error(SyntaxError::ExpectedNamedArgValue);
+                    };
+                    args.push(self.push_node(Node::NamedArg { id, value })?);
+                } else if let Some(expression) = self
                     .parse_expression_with_min_precedence(MIN_PRECEDENCE_AFTER_PIPE, &arg_context)?
                 {
-                    args.push(expression);
+                    self.check_for_positional_arg_after_named_arg(&args)?;
+                    let arg = self.check_for_spread(expression)?;
+                    args.push(arg);
                 } else {
                     break;
                 }
@@ -1111,6 +1551,52 @@ impl<'source> Parser<'source> {
         Ok(args)
     }
 
+    // Parses the id of a named call arg, along with its following `:`
+    //
+    // e.g.
+    //   f x: 1, y: 2
+    //   # ^ You are here
+    //
+    // None is returned if the next token isn't the start of a named arg.
+    fn parse_named_arg_id(&mut self, context: &ExpressionContext) -> Result<Option<ConstantIndex>> {
+        match self.peek_token_with_context(context) {
+            Some(PeekInfo {
+                token: Token::Id,
+                peek_count,
+                ..
+            }) if self.peek_token_n(peek_count + 1) == Some(Token::Colon) => {
+                self.consume_token_with_context(context);
+                let id = self.add_current_slice_as_string_constant()?;
+                self.consume_token(); // Token::Colon
+                Ok(Some(id))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    // Named args need to follow all of the positional args in a call
+    fn check_for_positional_arg_after_named_arg(&mut self, args: &[AstIndex]) -> Result<()> {
+        if args
+            .iter()
+            .any(|arg| matches!(self.ast.node(*arg).node, Node::NamedArg { .. }))
+        {
+            self.error(SyntaxError::PositionalArgAfterNamedArg)
+        } else {
+            Ok(())
+        }
+    }
+
+    // Spread values are marked with a trailing `...`, e.g. `f args...` or `[1, xs...]`
+    fn check_for_spread(&mut self, value: AstIndex) -> Result<AstIndex> {
+        if self.peek_token() == Some(Token::Ellipsis) {
+            self.consume_token();
+            let value_span = *self.ast.span(self.ast.node(value).span);
+            self.push_node_with_start_span(Node::Spread(value), value_span)
+        } else {
+            Ok(value)
+        }
+    }
+
     // Parses a single id
     //
     // See also: parse_id_or_wildcard(), consume_id_expression()
@@ -1262,6 +1748,28 @@ impl<'source> Parser<'source> {
         }
     }
 
+    // Checks to see if the parsed node is followed by `?` operators,
+    // and wraps the node in propagation nodes if so.
+    //
+    // Chains can continue after a `?`, e.g. `foo()?[0]?`
+    // Note that `?.` is parsed as an optional access by consume_chain.
+    fn check_for_propagation_after_node(
+        &mut self,
+        node: AstIndex,
+        context: &ExpressionContext,
+    ) -> Result<AstIndex> {
+        let start_span = *self.ast.span(self.ast.node(node).span);
+        let mut result = node;
+
+        while self.peek_token() == Some(Token::QuestionMark) {
+            self.consume_token();
+            result = self.push_node_with_start_span(Node::Propagate(result), start_span)?;
+            result = self.check_for_chain_after_node(result, context)?;
+        }
+
+        Ok(result)
+    }
+
     // Returns true if the following token is the start of a chain
     //
     // If the following token is on the same line, then it must be the _next_ token,
@@ -1269,7 +1777,9 @@ impl<'source> Parser<'source> {
     fn next_token_is_chain_start(&mut self, context: &ExpressionContext) -> bool {
         use Token::*;
 
-        if matches!(self.peek_token(), Some(Dot | SquareOpen | RoundOpen)) {
+        if matches!(self.peek_token(), Some(Dot | SquareOpen | RoundOpen))
+            || self.next_tokens_are_optional_access()
+        {
             true
         } else if context.allow_linebreaks {
             matches!(
@@ -1281,6 +1791,11 @@ impl<'source> Parser<'source> {
         }
     }
 
+    // Returns true if the following tokens are a `?.` optional access
+    fn next_tokens_are_optional_access(&mut self) -> bool {
+        self.peek_token() == Some(Token::QuestionMark) && self.peek_token_n(1) == Some(Token::Dot)
+    }
+
     // Parses an expression chain
     //
     // Expression chains represent a series of map accesses, index operations, and function calls.
@@ -1333,6 +1848,11 @@ impl<'source> Parser<'source> {
                         return self.error(SyntaxError::ExpectedIndexEnd);
                     }
                 }
+                // Optional map access, e.g. `x?.foo`
+                Token::QuestionMark if self.next_tokens_are_optional_access() => {
+                    self.consume_token();
+                    chain.push((ChainNode::Optional, self.current_span()));
+                }
                 // Map access
                 Token::Dot => {
                     self.consume_token();
@@ -1450,6 +1970,7 @@ impl<'source> Parser<'source> {
                             start: index_expression,
                             end: end_expression,
                             inclusive: false,
+                            step: None,
                         })?
                     } else {
                         self.push_node(Node::RangeFrom {
@@ -1465,6 +1986,7 @@ impl<'source> Parser<'source> {
                             start: index_expression,
                             end: end_expression,
                             inclusive: true,
+                            step: None,
                         })?
                     } else {
                         self.push_node(Node::RangeFrom {
@@ -1514,14 +2036,25 @@ impl<'source> Parser<'source> {
         let start_indent = self.current_indent();
         let mut args = Vec::new();
         let mut args_context = ExpressionContext::permissive();
+        let arg_context = ExpressionContext {
+            comma_separated_item: true,
+            ..ExpressionContext::inline()
+        };
 
         while self.peek_token_with_context(&args_context).is_some() {
             args_context = self
                 .consume_until_token_with_context(&args_context)
                 .unwrap();
 
-            if let Some(expression) = self.parse_expression(&ExpressionContext::inline())? {
-                args.push(expression);
+            if let Some(id) = self.parse_named_arg_id(&args_context)? {
+                let Some(value) = self.parse_expression(&arg_context)? else {
+                    return self.consume_token_and_error(SyntaxError::ExpectedNamedArgValue);
+                };
+                args.push(self.push_node(Node::NamedArg { id, value })?);
+            } else if let Some(expression) = self.parse_expression(&arg_context)? {
+                self.check_for_positional_arg_after_named_arg(&args)?;
+                let arg = self.check_for_spread(expression)?;
+                args.push(arg);
             } else {
                 break;
             }
@@ -1567,14 +2100,35 @@ impl<'source> Parser<'source> {
             start_span = self.current_span();
         }
 
-        let rhs = self.parse_expression(&ExpressionContext::inline())?;
+        let rhs = self.parse_expression(&ExpressionContext {
+            range_end: true,
+            ..ExpressionContext::inline()
+        })?;
 
         let range_node = match (lhs, rhs) {
-            (Some(start), Some(end)) => Range {
-                start,
-                end,
-                inclusive,
-            },
+            (Some(start), Some(end)) => {
+                // e.g.
+                // for x in 0..10 step 2
+                //                ^^^^ An optional step can follow the range's end
+                let step = if self.peek_step() {
+                    self.consume_next_token_on_same_line(); // step
+                    match self.parse_expression(&ExpressionContext::inline())? {
+                        Some(step) => Some(step),
+                        None => {
+                            return self.consume_token_and_error(SyntaxError::ExpectedRangeStep)
+                        }
+                    }
+                } else {
+                    None
+                };
+
+                Range {
+                    start,
+                    end,
+                    inclusive,
+                    step,
+                }
+            }
             (Some(start), None) => RangeFrom { start },
             (None, Some(end)) => RangeTo { end, inclusive },
             (None, None) => RangeFull,
@@ -1588,14 +2142,78 @@ impl<'source> Parser<'source> {
         self.consume_token_with_context(context); // Token::Export
 
         let start_span = self.current_span();
+        let export_context = ExpressionContext::permissive();
 
-        let Some(expression) = self.parse_expression(&ExpressionContext::permissive())? else {
+        let Some(term) = self.parse_term(&export_context)? else {
             return self.consume_token_and_error(SyntaxError::ExpectedExpression);
         };
 
+        // Exported assignments count as declarations
+        let expression = match self.parse_assign_expression(term, &[], true, &export_context)? {
+            Some(assignment) => assignment,
+            None => match self.parse_expression_continued(term, &[], 0, &export_context)? {
+                Some(expression) => expression,
+                None => return self.consume_token_and_error(SyntaxError::ExpectedExpression),
+            },
+        };
+
         self.push_node_with_start_span(Node::Export(expression), start_span)
     }
 
+    // Parses a `let` declaration
+    //
+    // e.g.
+    //   let x, y = foo()
+    //   #^ You are here
+    fn consume_let_expression(&mut self, context: &ExpressionContext) -> Result<AstIndex> {
+        self.consume_token_with_context(context); // Token::Let
+
+        if !matches!(
+            self.peek_next_token_on_same_line(),
+            Some(Token::Id | Token::Wildcard | Token::RoundOpen | Token::SquareOpen)
+        ) {
+            return self.consume_token_and_error(SyntaxError::ExpectedIdInLetDeclaration);
+        }
+
+        let target_context = context.start_new_expression();
+        let mut targets = Vec::new();
+
+        loop {
+            let Some(target) = self.parse_term(&target_context)? else {
+                return self.consume_token_and_error(SyntaxError::ExpectedIdInLetDeclaration);
+            };
+
+            if !self.is_valid_let_target(target) {
+                return self.error(SyntaxError::ExpectedIdInLetDeclaration);
+            }
+
+            targets.push(target);
+
+            match self.peek_next_token_on_same_line() {
+                Some(Token::Comma) => self.consume_next_token_on_same_line(),
+                _ => break,
+            };
+        }
+
+        // The targets are passed directly to the assignment so that only they are declared,
+        // and not any assignments that might be found in the assigned expression.
+        let (lhs, previous_lhs) = targets.split_last().unwrap(); // At least one target was parsed
+        match self.parse_assign_expression(*lhs, previous_lhs, true, &target_context)? {
+            Some(expression) => Ok(expression),
+            None => self.error(SyntaxError::ExpectedAssignmentAfterLet),
+        }
+    }
+
+    fn is_valid_let_target(&self, target: AstIndex) -> bool {
+        match &self.ast.node(target).node {
+            Node::Id(_) | Node::Wildcard(_) => true,
+            Node::Tuple(nested_targets) | Node::List(nested_targets) => nested_targets
+                .iter()
+                .all(|target| self.is_valid_let_target(*target)),
+            _ => false,
+        }
+    }
+
     fn consume_throw_expression(&mut self) -> Result<AstIndex> {
         self.consume_next_token_on_same_line(); // Token::Throw
 
@@ -1760,6 +2378,11 @@ impl<'source> Parser<'source> {
             self.consume_until_token_with_context(&entry_context);
 
             if let Some(entry) = self.parse_expression(&entry_context)? {
+                let entry = if end_token == Token::SquareClose {
+                    self.check_for_spread(entry)?
+                } else {
+                    entry
+                };
                 entries.push(entry);
                 last_token_was_a_comma = false;
             }
@@ -1873,10 +2496,20 @@ impl<'source> Parser<'source> {
                 break;
             };
 
-            if self.peek_token() == Some(Token::Colon) {
+            if self.peek_token() == Some(Token::Ellipsis) {
+                // Spread entries insert the entries of another map, e.g. `{defaults..., x: 1}`
+                match self.ast.node(key).node {
+                    Node::Id(id) => self.frame_mut()?.add_id_access(id),
+                    _ => return self.consume_token_and_error(SyntaxError::ExpectedMapColon),
+                }
+                entries.push((self.check_for_spread(key)?, None));
+            } else if self.peek_token() == Some(Token::Colon) {
                 self.consume_token();
 
-                let value_context = ExpressionContext::permissive();
+                let value_context = ExpressionContext {
+                    comma_separated_item: true,
+                    ..ExpressionContext::permissive()
+                };
                 if self.peek_token_with_context(&value_context).is_none() {
                     return self.error(SyntaxError::ExpectedMapValue);
                 }
@@ -1957,12 +2590,22 @@ impl<'source> Parser<'source> {
             Some(Token::MultiplyAssign) => MetaKeyId::MultiplyAssign,
             Some(Token::DivideAssign) => MetaKeyId::DivideAssign,
             Some(Token::RemainderAssign) => MetaKeyId::RemainderAssign,
+            Some(Token::Less) if self.peek_token() == Some(Token::Less) => {
+                self.consume_token();
+                MetaKeyId::ShiftLeft
+            }
             Some(Token::Less) => MetaKeyId::Less,
             Some(Token::LessOrEqual) => MetaKeyId::LessOrEqual,
+            Some(Token::Greater) if self.peek_token() == Some(Token::Greater) => {
+                self.consume_token();
+                MetaKeyId::ShiftRight
+            }
             Some(Token::Greater) => MetaKeyId::Greater,
             Some(Token::GreaterOrEqual) => MetaKeyId::GreaterOrEqual,
             Some(Token::Equal) => MetaKeyId::Equal,
             Some(Token::NotEqual) => MetaKeyId::NotEqual,
+            Some(Token::BitwiseAnd) => MetaKeyId::BitwiseAnd,
+            Some(Token::BitwiseXor) => MetaKeyId::BitwiseXor,
             Some(Token::Id) => match self.current_token.slice(self.source) {
                 "display" => MetaKeyId::Display,
                 "iterator" => MetaKeyId::Iterator,
@@ -1973,6 +2616,7 @@ impl<'source> Parser<'source> {
                 "type" => MetaKeyId::Type,
                 "base" => MetaKeyId::Base,
                 "main" => MetaKeyId::Main,
+                "repl_display" => MetaKeyId::ReplDisplay,
                 "tests" => MetaKeyId::Tests,
                 "pre_test" => MetaKeyId::PreTest,
                 "post_test" => MetaKeyId::PostTest,
@@ -1984,6 +2628,10 @@ impl<'source> Parser<'source> {
                     }
                     _ => return self.error(SyntaxError::ExpectedTestName),
                 },
+                "command" => {
+                    meta_name = Some(self.consume_command_name()?);
+                    MetaKeyId::Command
+                }
                 "meta" => match self.consume_next_token_on_same_line() {
                     Some(Token::Id) => {
                         let id = self.add_current_slice_as_string_constant()?;
@@ -1998,10 +2646,11 @@ impl<'source> Parser<'source> {
                 Some(Token::SquareClose) => MetaKeyId::Index,
                 _ => return self.error(SyntaxError::UnexpectedMetaKey),
             },
-            Some(Token::Function) => match self.consume_token() {
-                Some(Token::Function) => MetaKeyId::Call,
-                _ => return self.error(SyntaxError::UnexpectedMetaKey),
-            },
+            Some(Token::Function) if self.peek_token() == Some(Token::Function) => {
+                self.consume_token();
+                MetaKeyId::Call
+            }
+            Some(Token::Function) => MetaKeyId::BitwiseOr,
             _ => return self.error(SyntaxError::UnexpectedMetaKey),
         };
 
@@ -2011,6 +2660,31 @@ impl<'source> Parser<'source> {
         Ok(Some(result))
     }
 
+    // Parses the name that follows `@command`, either an id or a string without interpolation
+    fn consume_command_name(&mut self) -> Result<ConstantIndex> {
+        match self.peek_next_token_on_same_line() {
+            Some(Token::Id) => {
+                self.consume_next_token_on_same_line();
+                self.add_current_slice_as_string_constant()
+            }
+            Some(Token::StringStart(_)) => {
+                self.consume_until_next_token_on_same_line();
+                match self.parse_string(&ExpressionContext::inline())? {
+                    Some(ParseStringOutput {
+                        string:
+                            AstString {
+                                contents: StringContents::Literal(name),
+                                ..
+                            },
+                        ..
+                    }) => Ok(name),
+                    _ => self.error(SyntaxError::ExpectedCommandName),
+                }
+            }
+            _ => self.consume_token_and_error(SyntaxError::ExpectedCommandName),
+        }
+    }
+
     fn consume_for_loop(&mut self, context: &ExpressionContext) -> Result<AstIndex> {
         self.consume_token_with_context(context); // Token::For
 
@@ -2119,15 +2793,13 @@ impl<'source> Parser<'source> {
 
         if self.peek_next_token_on_same_line() == Some(Token::Then) {
             self.consume_next_token_on_same_line();
-            let Some(then_node) =
-                self.parse_expressions(&ExpressionContext::inline(), TempResult::No)?
-            else {
+            let Some(then_node) = self.parse_inline_if_branch(context)? else {
                 return self.error(ExpectedThenExpression);
             };
 
             let else_node = if self.peek_next_token_on_same_line() == Some(Token::Else) {
                 self.consume_next_token_on_same_line();
-                match self.parse_expressions(&ExpressionContext::inline(), TempResult::No)? {
+                match self.parse_inline_if_branch(context)? {
                     Some(else_node) => Some(else_node),
                     None => return self.error(ExpectedElseExpression),
                 }
@@ -2209,6 +2881,25 @@ impl<'source> Parser<'source> {
         }
     }
 
+    // Parses the `then` or `else` branch of an inline if expression
+    //
+    // A branch can be a comma separated series of expressions, unless the if expression is itself
+    // an item in a comma separated sequence, e.g.
+    //   x = if a then 1, 2 else 3, 4 # The branches are tuples
+    //   y = [if a then 1 else 2, 3]  # The list has two entries
+    fn parse_inline_if_branch(&mut self, context: &ExpressionContext) -> Result<Option<AstIndex>> {
+        let branch_context = ExpressionContext {
+            comma_separated_item: context.comma_separated_item,
+            ..ExpressionContext::inline()
+        };
+
+        if context.comma_separated_item {
+            self.parse_expression(&branch_context)
+        } else {
+            self.parse_expressions(&branch_context, TempResult::No)
+        }
+    }
+
     fn consume_switch_expression(
         &mut self,
         switch_context: &ExpressionContext,
@@ -2285,6 +2976,11 @@ impl<'source> Parser<'source> {
             }
         }
 
+        // Switch expressions are required to end with an else arm
+        if arms.last().is_some_and(|arm| arm.condition.is_some()) {
+            return Err(Error::new(ExpectedSwitchElse.into(), switch_span));
+        }
+
         self.push_node_with_span(Node::Switch(arms), switch_span)
     }
 
@@ -2436,7 +3132,17 @@ impl<'source> Parser<'source> {
 
         let result = match self.peek_token_with_context(&pattern_context) {
             Some(peeked) => match peeked.token {
-                True | False | Null | Number | StringStart { .. } | Subtract => {
+                Number | Subtract => {
+                    let term = self.parse_term(&pattern_context)?;
+                    match self.peek_next_token_on_same_line() {
+                        Some(Range | RangeInclusive) => {
+                            Some(self.consume_match_range_pattern(term)?)
+                        }
+                        _ => term,
+                    }
+                }
+                Range | RangeInclusive => Some(self.consume_match_range_pattern(None)?),
+                True | False | Null | StringStart { .. } => {
                     return self.parse_term(&pattern_context)
                 }
                 Id => match self.parse_id(&pattern_context)? {
@@ -2452,7 +3158,10 @@ impl<'source> Parser<'source> {
                             }
                         } else {
                             let id_node = self.push_node(Node::Id(id))?;
-                            if self.next_token_is_chain_start(&pattern_context) {
+                            if self.peek_token() == Some(Colon) {
+                                self.frame_mut()?.ids_assigned_in_frame.insert(id);
+                                self.consume_match_type_pattern(id_node)?
+                            } else if self.next_token_is_chain_start(&pattern_context) {
                                 self.frame_mut()?.add_id_access(id);
                                 self.consume_chain(id_node, &pattern_context)?
                             } else {
@@ -2464,7 +3173,14 @@ impl<'source> Parser<'source> {
                     }
                     None => return self.error(InternalError::IdParseFailure),
                 },
-                Wildcard => self.consume_wildcard(&pattern_context).map(Some)?,
+                Wildcard => {
+                    let wildcard = self.consume_wildcard(&pattern_context)?;
+                    if self.peek_token() == Some(Colon) {
+                        Some(self.consume_match_type_pattern(wildcard)?)
+                    } else {
+                        Some(wildcard)
+                    }
+                }
                 RoundOpen => {
                     self.consume_token_with_context(&pattern_context);
 
@@ -2493,6 +3209,64 @@ impl<'source> Parser<'source> {
         Ok(result)
     }
 
+    // Parses a range match pattern, with optional numeric start and end values
+    //
+    // e.g.
+    //   match x
+    //     0..10 then ...
+    //     10..=20 then ...
+    //     ..0 or 100.. then ...
+    fn consume_match_range_pattern(&mut self, start: Option<AstIndex>) -> Result<AstIndex> {
+        use Node::{Range, RangeFrom, RangeTo};
+
+        let inclusive = match self.consume_next_token_on_same_line() {
+            Some(Token::Range) => false,
+            Some(Token::RangeInclusive) => true,
+            _ => return self.error(InternalError::UnexpectedToken),
+        };
+
+        let start_span = self.current_span();
+
+        let end = match self.peek_next_token_on_same_line() {
+            Some(Token::Number | Token::Subtract) => {
+                self.parse_term(&ExpressionContext::restricted())?
+            }
+            _ => None,
+        };
+
+        let range_node = match (start, end) {
+            (Some(start), Some(end)) => Range {
+                start,
+                end,
+                inclusive,
+                step: None,
+            },
+            (Some(start), None) if !inclusive => RangeFrom { start },
+            (None, Some(end)) => RangeTo { end, inclusive },
+            _ => return self.error(SyntaxError::ExpectedMatchRangeEnd),
+        };
+
+        self.push_node_with_start_span(range_node, start_span)
+    }
+
+    // Parses a type pattern, following an id or wildcard
+    //
+    // e.g.
+    //   match x
+    //     n: Number then ...
+    //     _: String then ...
+    fn consume_match_type_pattern(&mut self, pattern: AstIndex) -> Result<AstIndex> {
+        self.consume_token(); // Token::Colon
+
+        if self.consume_next_token_on_same_line() != Some(Token::Id) {
+            return self.error(SyntaxError::ExpectedMatchTypeName);
+        }
+
+        let type_name = self.add_current_slice_as_string_constant()?;
+
+        self.push_node(Node::MatchType { pattern, type_name })
+    }
+
     // Recursively parses nested match patterns
     //
     // e.g.
@@ -2839,10 +3613,16 @@ impl<'source> Parser<'source> {
             'u' => match chars.next() {
                 Some('{') => {
                     let mut code = 0;
+                    let mut digits = 0;
 
                     while let Some(c) = chars.peek().cloned() {
                         if c.is_ascii_hexdigit() {
                             chars.next();
+                            digits += 1;
+                            // Values with more than 6 digits are out of range, and could overflow
+                            if digits > 6 {
+                                return self.error(UnicodeEscapeCodeOutOfRange);
+                            }
                             code *= 16;
                             code += c.to_digit(16).unwrap();
                         } else {
@@ -2851,6 +3631,7 @@ impl<'source> Parser<'source> {
                     }
 
                     match chars.next() {
+                        Some('}') if digits == 0 => self.error(ExpectedUnicodeEscapeValue),
                         Some('}') => match char::from_u32(code) {
                             Some(c) => Ok(c),
                             None => self.error(UnicodeEscapeCodeOutOfRange),
@@ -2859,10 +3640,10 @@ impl<'source> Parser<'source> {
                         None => self.error(UnterminatedNumericEscapeCode),
                     }
                 }
-                Some(_) => self.error(UnexpectedCharInNumericEscapeCode),
+                Some(_) => self.error(ExpectedUnicodeEscapeBrace),
                 None => self.error(UnterminatedNumericEscapeCode),
             },
-            _ => self.error(UnexpectedEscapeInString),
+            _ => self.error(UnknownEscapeCode(next)),
         };
 
         result.map(Some)
@@ -3140,6 +3921,16 @@ impl<'source> Parser<'source> {
     }
 
     // Peeks past whitespace on the same line until the next token is found
+    // Returns true if the next token on the same line is the `step` of a range, e.g. `0..10 step 2`
+    //
+    // `step` isn't a reserved keyword, so it's detected by its slice rather than as a token.
+    fn peek_step(&mut self) -> bool {
+        self.peek_token_with_context(&ExpressionContext::restricted())
+            .is_some_and(|peeked| {
+                peeked.token == Token::Id && peeked.info.slice(self.source) == "step"
+            })
+    }
+
     fn peek_next_token_on_same_line(&mut self) -> Option<Token> {
         let mut peek_count = 0;
 
@@ -3202,6 +3993,15 @@ impl<'source> Parser<'source> {
     }
 }
 
+// Returns true if the script's leading comments contain a `# koto: strict` directive
+fn has_strict_directive(source: &str) -> bool {
+    source
+        .lines()
+        .map(str::trim)
+        .take_while(|line| line.is_empty() || line.starts_with('#'))
+        .any(|line| line == "# koto: strict")
+}
+
 // Used by Parser::parse_expressions() to determine if comma-separated values should be stored in a
 // Tuple or a TempTuple.
 enum TempResult {
@@ -3209,13 +4009,13 @@ enum TempResult {
     Yes,
 }
 
-// The first operator that's above the pipe operator >> in precedence.
+// The first operator that's above the pipe operator -> in precedence.
 // Q: Why is this needed?
 // A: Function calls without parentheses aren't currently treated as operators (a Call operator
 //    with higher precedence than Pipe would allow this to go away, but would likely take quite a
 //    bit of reworking. All calls to parse_call_args will need to reworked).
 //    parse_call_args needs to parse arguments as expressions with a minimum precedence that
-//    excludes piping, otherwise `f g >> x` would be parsed as `f (g >> x)` instead of `(f g) >> x`.
+//    excludes piping, otherwise `f g -> x` would be parsed as `f (g -> x)` instead of `(f g) -> x`.
 const MIN_PRECEDENCE_AFTER_PIPE: u8 = 3;
 
 fn operator_precedence(op: Token) -> Option<(u8, u8)> {
@@ -3225,13 +4025,18 @@ fn operator_precedence(op: Token) -> Option<(u8, u8)> {
         AddAssign | SubtractAssign | MultiplyAssign | DivideAssign | RemainderAssign => {
             (4, MIN_PRECEDENCE_AFTER_PIPE)
         }
+        NullCoalesce => (5, 6),
         Or => (7, 8),
         And => (9, 10),
         // Chained comparisons require right-associativity
         Equal | NotEqual => (12, 11),
         Greater | GreaterOrEqual | Less | LessOrEqual => (14, 13),
-        Add | Subtract => (15, 16),
-        Multiply | Divide | Remainder => (17, 18),
+        BitwiseOr => (15, 16),
+        BitwiseXor => (17, 18),
+        BitwiseAnd => (19, 20),
+        ShiftLeft | ShiftRight => (21, 22),
+        Add | Subtract => (23, 24),
+        Multiply | Divide | Remainder => (25, 26),
         _ => return None,
     };
     Some(priority)
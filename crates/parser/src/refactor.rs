@@ -0,0 +1,791 @@
+//! Refactoring support for Koto scripts
+
+use crate::{
+    minify::children, Ast, AstIndex, AstString, ChainNode, ConstantIndex, Error, ImportItem, Node,
+    Parser, StringContents, StringNode,
+};
+use koto_lexer::{is_id_continue, LexedToken, Lexer, Position, Span, Token};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+};
+use thiserror::Error;
+use unicode_width::UnicodeWidthChar;
+
+/// Settings for [rename_symbol]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RenameSettings {
+    /// Whether or not matching words in string literals should be renamed
+    pub include_strings: bool,
+    /// Whether or not matching words in comments should be renamed
+    pub include_comments: bool,
+}
+
+/// The kind of source that contained a renamed symbol, see [RenamedSymbol]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenamedSymbolKind {
+    /// An identifier that refers to the renamed value, e.g. a variable, or an imported or
+    /// exported name
+    Id,
+    /// A word in a string literal, see [RenameSettings::include_strings]
+    String,
+    /// A word in a comment, see [RenameSettings::include_comments]
+    Comment,
+}
+
+/// A renamed occurrence of a symbol, see [Renamed]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenamedSymbol {
+    /// The kind of source that contained the symbol
+    pub kind: RenamedSymbolKind,
+    /// The symbol's byte positions in the original source
+    pub source_bytes: Range<usize>,
+    /// The symbol's span in the original source
+    pub span: Span,
+}
+
+/// The output of a successful call to [rename_symbol]
+#[derive(Clone, Debug)]
+pub struct Renamed {
+    /// The source with the symbol renamed
+    pub source: String,
+    /// The occurrences of the symbol that were renamed, in the order they appear in the source
+    pub renamed: Vec<RenamedSymbol>,
+}
+
+/// An error that can be produced by [rename_symbol]
+#[derive(Error, Clone, Debug)]
+pub enum RenameError {
+    /// One of the provided names isn't a valid identifier
+    #[error("'{0}' isn't a valid identifier")]
+    InvalidName(String),
+    /// The new name would clash with a value that's already in use
+    #[error("'{0}' is already in use")]
+    NameInUse(String),
+    /// The source couldn't be parsed
+    #[error("Failed to parse the source: {0}")]
+    InvalidSource(Error),
+    /// The source couldn't be parsed after renaming
+    #[error("The renamed source failed to parse: {0}")]
+    InvalidResult(Error),
+}
+
+/// Renames a top-level value in a script, along with the references to it
+///
+/// Identifiers are resolved in the scopes of the script's functions, so that only references to
+/// the top-level value are renamed, with local values that share its name (e.g. function
+/// arguments) left unchanged.
+///
+/// Map keys and lookups like `foo.old_name` are left unchanged unless they refer to an exported
+/// value, i.e. the keys of exported maps, and lookups on imported modules. Imported names are
+/// renamed along with their references, with the expectation that the imported module is also
+/// renamed. Matching words in string literals and comments are left unchanged unless enabled in
+/// the settings.
+///
+/// Renaming is rejected if the new name would clash with a value that's already in use, e.g. a
+/// top-level value, a local value in a function that refers to the renamed value, or a core
+/// library module. The source is parsed before and after renaming to ensure that the result is
+/// valid.
+pub fn rename_symbol(
+    source: &str,
+    old_name: &str,
+    new_name: &str,
+    settings: RenameSettings,
+) -> Result<Renamed, RenameError> {
+    for name in [old_name, new_name] {
+        if !is_id(name) {
+            return Err(RenameError::InvalidName(name.into()));
+        }
+    }
+
+    let ast = Parser::parse(source).map_err(RenameError::InvalidSource)?;
+    let tokens: Vec<LexedToken> = Lexer::new(source).collect();
+
+    let mut renamer = Renamer::new(source, &ast, &tokens, old_name, new_name);
+    if let Some(entry_point) = ast.entry_point() {
+        renamer.visit(entry_point);
+    }
+    let (mut renamed, in_use) = renamer.finish();
+    if in_use {
+        return Err(RenameError::NameInUse(new_name.into()));
+    }
+
+    for token in tokens.iter() {
+        let kind = match token.token {
+            Token::StringLiteral if settings.include_strings => RenamedSymbolKind::String,
+            Token::CommentSingle | Token::CommentMulti if settings.include_comments => {
+                RenamedSymbolKind::Comment
+            }
+            _ => continue,
+        };
+        for symbol in find_words(token.slice(source), old_name, token, kind) {
+            renamed.push((symbol, new_name.to_string()));
+        }
+    }
+    renamed.sort_by_key(|(symbol, _)| symbol.source_bytes.start);
+    renamed.dedup_by_key(|(symbol, _)| symbol.source_bytes.start);
+
+    let mut result = String::with_capacity(source.len());
+    let mut position = 0;
+    for (symbol, replacement) in renamed.iter() {
+        result.push_str(&source[position..symbol.source_bytes.start]);
+        result.push_str(replacement);
+        position = symbol.source_bytes.end;
+    }
+    result.push_str(&source[position..]);
+
+    Parser::parse(&result).map_err(RenameError::InvalidResult)?;
+
+    Ok(Renamed {
+        source: result,
+        renamed: renamed.into_iter().map(|(symbol, _)| symbol).collect(),
+    })
+}
+
+// A value that has been assigned in a scope
+#[derive(Clone, Copy)]
+struct Binding {
+    // True if the binding is being renamed
+    renamed: bool,
+    // True if the binding was imported, lookups on imported values can refer to a module's exports
+    imported: bool,
+}
+
+// The scope of a function, or of the script's top level
+#[derive(Default)]
+struct Frame<'a> {
+    // The names that are assigned anywhere in the frame, excluding nested functions
+    assigned: HashSet<&'a str>,
+    // The bindings in the frame that have been visited so far
+    bindings: HashMap<&'a str, Binding>,
+}
+
+// A reference to the old name that was visited before a matching binding was found,
+// e.g. a function that refers to an exported value that's assigned later in the script
+struct UnresolvedReference {
+    token: usize,
+    replacement: String,
+    // True if the new name would be captured by a local value if the reference is renamed
+    captured: bool,
+}
+
+// Finds the occurrences of a top-level value's name that need to be renamed
+//
+// Nodes are visited in the order that they're compiled, e.g. assignment expressions are visited
+// before their targets, so that references are resolved to the bindings that are visible at
+// that point in the script.
+struct Renamer<'a> {
+    source: &'a str,
+    ast: &'a Ast,
+    tokens: &'a [LexedToken],
+    // The index of each token, keyed by the token's starting position
+    token_positions: HashMap<Position, usize>,
+    old_name: &'a str,
+    new_name: &'a str,
+    // The stack of scopes, with the top-level scope first
+    frames: Vec<Frame<'a>>,
+    // The tokens that will be renamed, along with their replacements
+    renamed: Vec<(usize, String)>,
+    unresolved: Vec<UnresolvedReference>,
+    // True if a renamed token refers to a top-level value
+    renames_top_level: bool,
+    // True if the new name refers to a value that isn't assigned in the script
+    new_name_is_external: bool,
+    // True if a renamed reference would be captured by a local value with the new name
+    captured: bool,
+}
+
+impl<'a> Renamer<'a> {
+    fn new(
+        source: &'a str,
+        ast: &'a Ast,
+        tokens: &'a [LexedToken],
+        old_name: &'a str,
+        new_name: &'a str,
+    ) -> Self {
+        let mut token_positions = HashMap::new();
+        for (i, token) in tokens.iter().enumerate() {
+            if !token.token.is_whitespace_including_newline() {
+                token_positions.entry(token.span.start).or_insert(i);
+            }
+        }
+
+        Self {
+            source,
+            ast,
+            tokens,
+            token_positions,
+            old_name,
+            new_name,
+            frames: vec![Frame::default()],
+            renamed: Vec::new(),
+            unresolved: Vec::new(),
+            renames_top_level: false,
+            new_name_is_external: false,
+            captured: false,
+        }
+    }
+
+    // Returns the renamed symbols along with their replacements,
+    // and whether or not the new name is already in use
+    fn finish(mut self) -> (Vec<(RenamedSymbol, String)>, bool) {
+        // Unresolved references refer to top-level values that are assigned later in the script
+        if self
+            .top_level_binding(self.old_name)
+            .is_some_and(|b| b.renamed)
+        {
+            for reference in std::mem::take(&mut self.unresolved) {
+                self.renamed.push((reference.token, reference.replacement));
+                self.renames_top_level = true;
+                self.captured |= reference.captured;
+            }
+        }
+
+        let in_use = self.captured
+            || (self.renames_top_level
+                && (self.new_name_is_external || self.top_level_binding(self.new_name).is_some()));
+
+        let renamed = self
+            .renamed
+            .into_iter()
+            .map(|(token, replacement)| {
+                let token = &self.tokens[token];
+                let symbol = RenamedSymbol {
+                    kind: RenamedSymbolKind::Id,
+                    source_bytes: token.source_bytes.clone(),
+                    span: token.span,
+                };
+                (symbol, replacement)
+            })
+            .collect();
+
+        (renamed, in_use)
+    }
+
+    fn visit(&mut self, node_index: AstIndex) {
+        let ast = self.ast;
+        match &ast.node(node_index).node {
+            Node::Id(id) => self.visit_reference(node_index, *id),
+            Node::Function(f) => {
+                let mut frame = Frame::default();
+                for arg in f.args.iter() {
+                    find_pattern_names(ast, *arg, &mut frame.assigned);
+                }
+                find_assigned_names(ast, f.body, &mut frame.assigned);
+
+                self.frames.push(frame);
+                for arg in f.args.iter() {
+                    self.visit_target(*arg, false);
+                }
+                self.visit(f.body);
+                self.frames.pop();
+            }
+            Node::Assign { target, expression } => {
+                self.visit(*expression);
+                self.visit_target(*target, false);
+            }
+            Node::MultiAssign {
+                targets,
+                expression,
+            } => {
+                self.visit(*expression);
+                for target in targets.iter() {
+                    self.visit_target(*target, false);
+                }
+            }
+            Node::Export(exported) => match &ast.node(*exported).node {
+                Node::Assign { target, expression } => {
+                    self.visit(*expression);
+                    self.visit_target(*target, true);
+                }
+                Node::MultiAssign {
+                    targets,
+                    expression,
+                } => {
+                    self.visit(*expression);
+                    for target in targets.iter() {
+                        self.visit_target(*target, true);
+                    }
+                }
+                Node::Map(entries) => self.visit_map(entries, true),
+                _ => self.visit(*exported),
+            },
+            Node::Map(entries) => self.visit_map(entries, false),
+            Node::Chain(_) => self.visit_chain(node_index),
+            Node::For(for_loop) => {
+                self.visit(for_loop.iterable);
+                for arg in for_loop.args.iter() {
+                    self.visit_target(*arg, false);
+                }
+                self.visit(for_loop.body);
+            }
+            Node::Match { expression, arms } => {
+                self.visit(*expression);
+                for arm in arms.iter() {
+                    for pattern in arm.patterns.iter() {
+                        self.visit_target(*pattern, false);
+                    }
+                    if let Some(condition) = arm.condition {
+                        self.visit(condition);
+                    }
+                    self.visit(arm.expression);
+                }
+            }
+            Node::Try(try_expression) => {
+                self.visit(try_expression.try_block);
+                self.visit_target(try_expression.catch_arg, false);
+                self.visit(try_expression.catch_block);
+                if let Some(finally_block) = try_expression.finally_block {
+                    self.visit(finally_block);
+                }
+            }
+            Node::Import { from, items } => {
+                for item in items.iter() {
+                    self.visit_import_item(item, !from.is_empty());
+                }
+            }
+            _ => {
+                for child in children(ast, node_index) {
+                    self.visit(child);
+                }
+            }
+        }
+    }
+
+    // Visits an assignment target, or a pattern that assigns values
+    fn visit_target(&mut self, node_index: AstIndex, exported: bool) {
+        let ast = self.ast;
+        match &ast.node(node_index).node {
+            Node::Id(id) => {
+                let name = ast.constants().get_str(*id);
+                let token = self.id_token(node_index, name);
+                self.bind(name, token, exported);
+            }
+            Node::DefaultArg { id, default } => {
+                self.visit(*default);
+                let name = ast.constants().get_str(*id);
+                let token = self.default_arg_token(node_index, name);
+                self.bind(name, token, exported);
+            }
+            Node::Ellipsis(Some(id)) => {
+                let name = ast.constants().get_str(*id);
+                let token = self.id_token(node_index, name);
+                self.bind(name, token, exported);
+            }
+            Node::Tuple(elements) | Node::List(elements) | Node::TempTuple(elements) => {
+                for element in elements.iter() {
+                    self.visit_target(*element, exported);
+                }
+            }
+            Node::Nested(nested)
+            | Node::MatchType {
+                pattern: nested, ..
+            } => self.visit_target(*nested, exported),
+            // e.g. `x.foo = 1`, or literals in match patterns
+            _ => self.visit(node_index),
+        }
+    }
+
+    fn visit_reference(&mut self, node_index: AstIndex, id: ConstantIndex) {
+        let name = self.ast.constants().get_str(id);
+        match self.resolve(name) {
+            Some((frame, binding)) if binding.renamed => {
+                let token = self.id_token(node_index, name);
+                self.rename(token, self.new_name.to_string(), frame);
+            }
+            Some(_) => {}
+            None if name == self.old_name => {
+                if let Some(token) = self.id_token(node_index, name) {
+                    self.unresolved.push(UnresolvedReference {
+                        token,
+                        replacement: self.new_name.to_string(),
+                        captured: self.is_captured(0),
+                    });
+                }
+            }
+            None if name == self.new_name => self.new_name_is_external = true,
+            None => {}
+        }
+    }
+
+    fn visit_map(&mut self, entries: &[(AstIndex, Option<AstIndex>)], exported: bool) {
+        let ast = self.ast;
+        for (key, value) in entries.iter() {
+            match (&ast.node(*key).node, value) {
+                (Node::Id(id), None) => {
+                    let name = ast.constants().get_str(*id);
+                    if exported {
+                        // Exported shorthand entries refer to the value that's being exported
+                        self.bind_export(name, self.id_token(*key, name));
+                    } else if name == self.old_name {
+                        // The entry's value refers to the renamed value, so the entry is expanded
+                        // to keep the key unchanged.
+                        let replacement = format!("{name}: {}", self.new_name);
+                        match self.resolve(name) {
+                            Some((frame, binding)) if binding.renamed => {
+                                let token = self.id_token(*key, name);
+                                self.rename(token, replacement, frame);
+                            }
+                            Some(_) => {}
+                            None => {
+                                if let Some(token) = self.id_token(*key, name) {
+                                    self.unresolved.push(UnresolvedReference {
+                                        token,
+                                        replacement,
+                                        captured: self.is_captured(0),
+                                    });
+                                }
+                            }
+                        }
+                    } else {
+                        self.visit_reference(*key, *id);
+                    }
+                }
+                (Node::Id(id), Some(value)) => {
+                    self.visit(*value);
+                    if exported {
+                        let name = ast.constants().get_str(*id);
+                        self.bind_export(name, self.id_token(*key, name));
+                    }
+                }
+                (Node::Meta(..), Some(value)) => self.visit(*value),
+                _ => {
+                    self.visit(*key);
+                    if let Some(value) = value {
+                        self.visit(*value);
+                    }
+                }
+            }
+        }
+    }
+
+    fn visit_chain(&mut self, node_index: AstIndex) {
+        let ast = self.ast;
+        // True when the next lookup is on an imported value, e.g. `foo` in `module.foo`
+        let mut exports_lookup = false;
+        let mut next = Some(node_index);
+
+        while let Some(node_index) = next {
+            let Node::Chain((chain_node, next_node)) = &ast.node(node_index).node else {
+                self.visit(node_index);
+                break;
+            };
+
+            match chain_node {
+                ChainNode::Root(root) => {
+                    self.visit(*root);
+                    exports_lookup = match &ast.node(*root).node {
+                        Node::Id(id) => self
+                            .resolve(ast.constants().get_str(*id))
+                            .is_some_and(|(_, binding)| binding.imported),
+                        _ => false,
+                    };
+                }
+                ChainNode::Id(id) => {
+                    let name = ast.constants().get_str(*id);
+                    if exports_lookup && name == self.old_name {
+                        if let Some(token) = self.id_token(node_index, name) {
+                            self.renamed.push((token, self.new_name.to_string()));
+                        }
+                    }
+                    exports_lookup = false;
+                }
+                ChainNode::Str(s) => {
+                    self.visit_string(s);
+                    exports_lookup = false;
+                }
+                ChainNode::Index(index) => {
+                    self.visit(*index);
+                    exports_lookup = false;
+                }
+                ChainNode::Call { args, .. } => {
+                    for arg in args.iter() {
+                        self.visit(*arg);
+                    }
+                    exports_lookup = false;
+                }
+                ChainNode::Optional => {}
+            }
+
+            next = *next_node;
+        }
+    }
+
+    fn visit_string(&mut self, s: &AstString) {
+        if let StringContents::Interpolated(nodes) = &s.contents {
+            for node in nodes.iter() {
+                if let StringNode::Expression { expression, .. } = node {
+                    self.visit(*expression);
+                }
+            }
+        }
+    }
+
+    fn visit_import_item(&mut self, item: &ImportItem, has_from: bool) {
+        let ast = self.ast;
+        let item_name = match &ast.node(item.item).node {
+            Node::Id(id) => Some(ast.constants().get_str(*id)),
+            _ => None,
+        };
+        let frame = self.frames.len() - 1;
+
+        match item.name {
+            Some(alias) => {
+                // The item refers to one of the module's exports
+                if has_from && item_name == Some(self.old_name) {
+                    if let Some(token) = self.id_token(item.item, self.old_name) {
+                        self.renamed.push((token, self.new_name.to_string()));
+                    }
+                }
+
+                if let Node::Id(id) = &ast.node(alias).node {
+                    let name = ast.constants().get_str(*id);
+                    let renamed = name == self.old_name && frame == 0;
+                    self.frames[frame].bindings.insert(
+                        name,
+                        Binding {
+                            renamed,
+                            imported: true,
+                        },
+                    );
+                    if renamed {
+                        let token = self.id_token(alias, name);
+                        self.rename(token, self.new_name.to_string(), frame);
+                    }
+                }
+            }
+            None => {
+                if let Some(name) = item_name {
+                    // Items that are imported without `from` are module names,
+                    // which aren't renamed.
+                    let renamed = name == self.old_name && has_from;
+                    self.frames[frame].bindings.insert(
+                        name,
+                        Binding {
+                            renamed,
+                            imported: true,
+                        },
+                    );
+                    if renamed {
+                        let token = self.id_token(item.item, name);
+                        self.rename(token, self.new_name.to_string(), frame);
+                    }
+                }
+            }
+        }
+    }
+
+    // Adds a binding to the current frame, renaming it if it's a binding of the old name
+    //
+    // Exported values are also added to the top-level frame.
+    fn bind(&mut self, name: &'a str, token: Option<usize>, exported: bool) {
+        let frame = self.frames.len() - 1;
+        if exported && frame > 0 {
+            self.bind_export(name, None);
+        }
+
+        let renamed = name == self.old_name && (frame == 0 || exported);
+        self.frames[frame].bindings.insert(
+            name,
+            Binding {
+                renamed,
+                imported: false,
+            },
+        );
+        if renamed {
+            self.rename(
+                token,
+                self.new_name.to_string(),
+                if exported { 0 } else { frame },
+            );
+        }
+    }
+
+    fn bind_export(&mut self, name: &'a str, token: Option<usize>) {
+        let renamed = name == self.old_name;
+        self.frames[0].bindings.insert(
+            name,
+            Binding {
+                renamed,
+                imported: false,
+            },
+        );
+        if renamed {
+            self.rename(token, self.new_name.to_string(), 0);
+        }
+    }
+
+    fn rename(&mut self, token: Option<usize>, replacement: String, binding_frame: usize) {
+        if let Some(token) = token {
+            self.renamed.push((token, replacement));
+            self.captured |= self.is_captured(binding_frame);
+            if binding_frame == 0 {
+                self.renames_top_level = true;
+            }
+        }
+    }
+
+    // Returns true if a local value with the new name would hide a binding in the given frame
+    fn is_captured(&self, binding_frame: usize) -> bool {
+        self.frames[binding_frame.max(1)..]
+            .iter()
+            .any(|frame| frame.assigned.contains(self.new_name))
+    }
+
+    // Finds the binding that a name refers to, along with the index of its frame
+    fn resolve(&self, name: &str) -> Option<(usize, Binding)> {
+        self.frames
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(i, frame)| frame.bindings.get(name).map(|binding| (i, *binding)))
+    }
+
+    fn top_level_binding(&self, name: &str) -> Option<Binding> {
+        self.frames[0].bindings.get(name).copied()
+    }
+
+    // Returns the index of the node's id token
+    //
+    // The id is either at the start of the node, or directly precedes it (e.g. `foo...`).
+    fn id_token(&self, node_index: AstIndex, name: &str) -> Option<usize> {
+        let span = self.ast.span(self.ast.node(node_index).span);
+        let start = *self.token_positions.get(&span.start)?;
+        [Some(start), start.checked_sub(1)]
+            .into_iter()
+            .flatten()
+            .find(|i| {
+                let token = &self.tokens[*i];
+                token.token == Token::Id && token.slice(self.source) == name
+            })
+    }
+
+    // Returns the index of a default argument's id token
+    //
+    // The node's span is at the end of the default value, so the id is found by searching
+    // backwards for the `=` that follows the id.
+    fn default_arg_token(&self, node_index: AstIndex, name: &str) -> Option<usize> {
+        let span = self.ast.span(self.ast.node(node_index).span);
+        let end = *self.token_positions.get(&span.start)?;
+        let mut significant_tokens = self.tokens[..end]
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, token)| !token.token.is_whitespace());
+        let (_, mut next_token) = significant_tokens.next()?;
+        for (i, token) in significant_tokens {
+            if token.token == Token::Id
+                && token.slice(self.source) == name
+                && next_token.token == Token::Assign
+            {
+                return Some(i);
+            }
+            next_token = token;
+        }
+        None
+    }
+}
+
+// Finds names that are assigned in the node, excluding names assigned in nested functions
+fn find_assigned_names<'a>(ast: &'a Ast, node_index: AstIndex, names: &mut HashSet<&'a str>) {
+    match &ast.node(node_index).node {
+        Node::Function(_) => return,
+        Node::Assign { target, .. } => find_pattern_names(ast, *target, names),
+        Node::MultiAssign { targets, .. } => {
+            for target in targets.iter() {
+                find_pattern_names(ast, *target, names);
+            }
+        }
+        Node::For(for_loop) => {
+            for arg in for_loop.args.iter() {
+                find_pattern_names(ast, *arg, names);
+            }
+        }
+        Node::Try(try_expression) => find_pattern_names(ast, try_expression.catch_arg, names),
+        Node::Match { arms, .. } => {
+            for arm in arms.iter() {
+                for pattern in arm.patterns.iter() {
+                    find_pattern_names(ast, *pattern, names);
+                }
+            }
+        }
+        Node::Import { items, .. } => {
+            for item in items.iter() {
+                find_pattern_names(ast, item.name.unwrap_or(item.item), names);
+            }
+        }
+        _ => {}
+    }
+
+    for child in children(ast, node_index) {
+        find_assigned_names(ast, child, names);
+    }
+}
+
+fn find_pattern_names<'a>(ast: &'a Ast, node_index: AstIndex, names: &mut HashSet<&'a str>) {
+    match &ast.node(node_index).node {
+        Node::Id(id) | Node::DefaultArg { id, .. } | Node::Ellipsis(Some(id)) => {
+            names.insert(ast.constants().get_str(*id));
+        }
+        Node::Tuple(elements) | Node::List(elements) | Node::TempTuple(elements) => {
+            for element in elements.iter() {
+                find_pattern_names(ast, *element, names);
+            }
+        }
+        Node::Nested(nested)
+        | Node::MatchType {
+            pattern: nested, ..
+        } => find_pattern_names(ast, *nested, names),
+        _ => {}
+    }
+}
+
+// Returns true if the name is lexed as a single identifier
+fn is_id(name: &str) -> bool {
+    let mut lexer = Lexer::new(name);
+    matches!(
+        (lexer.next(), lexer.next()),
+        (Some(token), None) if token.token == Token::Id
+    )
+}
+
+// Finds whole-word matches of the name in a string literal or comment
+fn find_words(
+    text: &str,
+    name: &str,
+    token: &LexedToken,
+    kind: RenamedSymbolKind,
+) -> Vec<RenamedSymbol> {
+    let mut result = Vec::new();
+    for (offset, _) in text.match_indices(name) {
+        let before = text[..offset].chars().next_back();
+        let after = text[offset + name.len()..].chars().next();
+        if before.is_some_and(is_id_continue) || after.is_some_and(is_id_continue) {
+            continue;
+        }
+
+        let start = advance_position(token.span.start, &text[..offset]);
+        let end = advance_position(start, name);
+        let start_byte = token.source_bytes.start + offset;
+
+        result.push(RenamedSymbol {
+            kind,
+            source_bytes: start_byte..start_byte + name.len(),
+            span: Span { start, end },
+        });
+    }
+    result
+}
+
+// Advances the position past the text, matching the lexer's treatment of columns
+fn advance_position(mut position: Position, text: &str) -> Position {
+    for c in text.chars() {
+        if c == '\n' {
+            position.line += 1;
+            position.column = 0;
+        } else {
+            position.column += c.width().unwrap_or(0) as u32;
+        }
+    }
+    position
+}
@@ -0,0 +1,172 @@
+mod minify {
+    use koto_parser::{minify, MinifyError, MinifySettings};
+
+    fn check_minify(source: &str, settings: MinifySettings, expected: &str) {
+        match minify(source, settings) {
+            Ok(result) => assert_eq!(result, expected),
+            Err(error) => panic!("Minify failed: {error}"),
+        }
+    }
+
+    #[test]
+    fn comments_and_whitespace_are_removed() {
+        let source = "\
+# A comment
+x   =   1 # Another comment
+
+if x > 0
+    #- A multi-line
+       comment -#
+    print    'positive'
+else
+        print 'not positive'
+";
+        let expected = "\
+x = 1
+if x > 0
+ print 'positive'
+else
+  print 'not positive'
+";
+        check_minify(source, MinifySettings::default(), expected);
+    }
+
+    #[test]
+    fn locals_are_renamed() {
+        let source = "\
+total = 0
+add_squares = |first, second|
+  first_squared = first * first
+  second_squared = second * second
+  first_squared + second_squared
+total = add_squares 1, 2
+";
+        let expected = "\
+total = 0
+add_squares = |a, b|
+ c = a * a
+ d = b * b
+ c + d
+total = add_squares 1, 2
+";
+        check_minify(source, MinifySettings::default(), expected);
+    }
+
+    #[test]
+    fn captured_values_are_renamed_consistently() {
+        let source = "\
+make_counter = ||
+  count = 0
+  increment = ||
+    count += 1
+    {count}
+  increment
+";
+        let expected = "\
+make_counter = ||
+ a = 0
+ b = ||
+  a += 1
+  {count:a}
+ b
+";
+        check_minify(source, MinifySettings::default(), expected);
+    }
+
+    #[test]
+    fn names_accessible_from_outside_of_functions_are_unchanged() {
+        let source = "\
+export f = |x, value|
+  export exported = value
+  debug x
+  print_value value: x
+f 1, 2
+";
+        let expected = "\
+export f = |x, value|
+ export exported = value
+ debug x
+ print_value value: x
+f 1, 2
+";
+        check_minify(source, MinifySettings::default(), expected);
+    }
+
+    #[test]
+    fn values_read_before_assignment_are_unchanged() {
+        let source = "\
+f = ||
+  total = total + 1
+  local = 1
+  local
+";
+        let expected = "\
+f = ||
+ total = total + 1
+ a = 1
+ a
+";
+        check_minify(source, MinifySettings::default(), expected);
+    }
+
+    #[test]
+    fn fresh_names_avoid_existing_names() {
+        let source = "\
+a = 1
+f = |value| value + a
+";
+        let expected = "\
+a = 1
+f = |b| b + a
+";
+        check_minify(source, MinifySettings::default(), expected);
+    }
+
+    #[test]
+    fn string_table() {
+        let source = "\
+print 'a repeated string'
+f = ||
+  x = 'a repeated string'
+  match x
+    'a repeated string' then 'short'
+    else 'short'
+";
+        let expected = "\
+a=('a repeated string',)
+print a[0]
+f = ||
+ b = a[0]
+ match b
+  'a repeated string' then 'short'
+  else 'short'
+";
+        let settings = MinifySettings { string_table: true };
+        check_minify(source, settings, expected);
+    }
+
+    #[test]
+    fn strict_directive_is_preserved() {
+        let source = "\
+# koto: strict
+let x = 'a repeated string'
+let y = 'a repeated string'
+";
+        let expected = "\
+# koto: strict
+let a=('a repeated string',)
+let x = a[0]
+let y = a[0]
+";
+        let settings = MinifySettings { string_table: true };
+        check_minify(source, settings, expected);
+    }
+
+    #[test]
+    fn invalid_source() {
+        assert!(matches!(
+            minify("x = (", MinifySettings::default()),
+            Err(MinifyError::InvalidSource(_))
+        ));
+    }
+}
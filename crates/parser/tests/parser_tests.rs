@@ -116,6 +116,16 @@ mod parser {
             start: start.into(),
             end: end.into(),
             inclusive,
+            step: None,
+        }
+    }
+
+    fn stepped_range(start: u32, end: u32, inclusive: bool, step: u32) -> Node {
+        Node::Range {
+            start: start.into(),
+            end: end.into(),
+            inclusive,
+            step: Some(step.into()),
         }
     }
 
@@ -137,6 +147,10 @@ mod parser {
         Node::Chain((ChainNode::Index(index.into()), next.map(AstIndex::from)))
     }
 
+    fn chain_optional(next: Option<u32>) -> Node {
+        Node::Chain((ChainNode::Optional, next.map(AstIndex::from)))
+    }
+
     fn chain_root(index: u32, next: Option<u32>) -> Node {
         Node::Chain((ChainNode::Root(index.into()), next.map(AstIndex::from)))
     }
@@ -578,6 +592,27 @@ x = [
                 Some(&[Constant::Str("x")]),
             )
         }
+
+        #[test]
+        fn list_with_spread() {
+            let source = "[1, xs..., ys...]";
+            check_ast(
+                source,
+                &[
+                    SmallInt(1),
+                    id(0), // xs
+                    Spread(1.into()),
+                    id(1), // ys
+                    Spread(3.into()),
+                    List(expressions(&[0, 2, 4])), // 5
+                    MainBlock {
+                        body: expressions(&[5]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[Constant::Str("xs"), Constant::Str("ys")]),
+            )
+        }
     }
 
     mod maps {
@@ -636,6 +671,30 @@ x =
             )
         }
 
+        #[test]
+        fn map_inline_with_spread() {
+            let source = "{defaults..., color: 'red'}";
+            check_ast(
+                source,
+                &[
+                    id(0), // defaults
+                    Spread(0.into()),
+                    id(1), // color
+                    string_literal(2, StringQuote::Single),
+                    map_inline(&[(1, None), (2, Some(3))]),
+                    MainBlock {
+                        body: expressions(&[4]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[
+                    Constant::Str("defaults"),
+                    Constant::Str("color"),
+                    Constant::Str("red"),
+                ]),
+            )
+        }
+
         #[test]
         fn map_block_syntax() {
             let source = r#"
@@ -842,6 +901,44 @@ x =
             )
         }
 
+        #[test]
+        fn map_block_bitwise_meta() {
+            let source = r#"
+x =
+  @&: 0
+  @|: 1
+  @^: 2
+  @<<: 3
+  @>>: 4
+  @||: 5
+"#;
+            check_ast(
+                source,
+                &[
+                    id(0), // x
+                    Meta(MetaKeyId::BitwiseAnd, None),
+                    SmallInt(0),
+                    Meta(MetaKeyId::BitwiseOr, None),
+                    SmallInt(1),
+                    Meta(MetaKeyId::BitwiseXor, None), // 5
+                    SmallInt(2),
+                    Meta(MetaKeyId::ShiftLeft, None),
+                    SmallInt(3),
+                    Meta(MetaKeyId::ShiftRight, None),
+                    SmallInt(4), // 10
+                    Meta(MetaKeyId::Call, None),
+                    SmallInt(5),
+                    map_block(&[(1, 2), (3, 4), (5, 6), (7, 8), (9, 10), (11, 12)]),
+                    assign(0, 13),
+                    MainBlock {
+                        body: expressions(&[14]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[Constant::Str("x")]),
+            )
+        }
+
         #[test]
         fn assigning_map_to_meta_key() {
             let source = r#"
@@ -871,6 +968,52 @@ x =
                 Some(&[Constant::Str("foo")]),
             )
         }
+
+        #[test]
+        fn command_meta_keys() {
+            let source = r#"
+@command build = 0
+@command 'deploy-prod' = 1
+"#;
+            check_ast(
+                source,
+                &[
+                    Meta(MetaKeyId::Command, Some(0.into())), // build
+                    SmallInt(0),
+                    assign(0, 1),
+                    Export(2.into()),
+                    Meta(MetaKeyId::Command, Some(1.into())), // deploy-prod
+                    SmallInt(1),                              // 5
+                    assign(4, 5),
+                    Export(6.into()),
+                    MainBlock {
+                        body: expressions(&[3, 7]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[Constant::Str("build"), Constant::Str("deploy-prod")]),
+            )
+        }
+
+        #[test]
+        fn repl_display_meta_key() {
+            let source = "{@display: 0, @repl_display: 1}";
+            check_ast(
+                source,
+                &[
+                    Meta(MetaKeyId::Display, None),
+                    SmallInt(0),
+                    Meta(MetaKeyId::ReplDisplay, None),
+                    SmallInt(1),
+                    map_inline(&[(0, Some(1)), (2, Some(3))]),
+                    MainBlock {
+                        body: expressions(&[4]),
+                        local_count: 0,
+                    },
+                ],
+                None,
+            )
+        }
     }
 
     mod ranges {
@@ -975,6 +1118,51 @@ min..max
             )
         }
 
+        #[test]
+        fn range_with_step() {
+            let source = "0..10 step 2";
+            check_ast(
+                source,
+                &[
+                    SmallInt(0),
+                    SmallInt(10),
+                    SmallInt(2),
+                    stepped_range(0, 1, false, 2),
+                    MainBlock {
+                        body: expressions(&[3]),
+                        local_count: 0,
+                    },
+                ],
+                None,
+            )
+        }
+
+        #[test]
+        fn range_with_step_following_call() {
+            let source = "0..=size x step n";
+            check_ast(
+                source,
+                &[
+                    SmallInt(0),
+                    id(0),
+                    id(1),
+                    chain_call(&[2], false, None),
+                    chain_root(1, Some(3)),
+                    id(2), // 5
+                    stepped_range(0, 4, true, 5),
+                    MainBlock {
+                        body: expressions(&[6]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[
+                    Constant::Str("size"),
+                    Constant::Str("x"),
+                    Constant::Str("n"),
+                ]),
+            )
+        }
+
         #[test]
         fn ranges_in_lists() {
             let source = "\
@@ -1202,6 +1390,37 @@ min..max
             )
         }
 
+        #[test]
+        fn unpack_nested_targets() {
+            let source = "(a, [b, _]), c = x";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    id(1),
+                    Wildcard(None),
+                    List(expressions(&[1, 2])),
+                    Tuple(expressions(&[0, 3])),
+                    id(2), // 5
+                    id(3),
+                    MultiAssign {
+                        targets: expressions(&[4, 5]),
+                        expression: 6.into(),
+                    },
+                    MainBlock {
+                        body: expressions(&[7]),
+                        local_count: 3,
+                    },
+                ],
+                Some(&[
+                    Constant::Str("a"),
+                    Constant::Str("b"),
+                    Constant::Str("c"),
+                    Constant::Str("x"),
+                ]),
+            )
+        }
+
         #[test]
         fn tuple_with_linebreaks() {
             let source = "\
@@ -1324,6 +1543,85 @@ x %= 4";
                 Some(&[Constant::Str("foo"), Constant::Str("bar")]),
             )
         }
+
+        #[test]
+        fn let_declaration() {
+            check_ast_for_equivalent_sources(
+                &["let a = 1", "a = 1"],
+                &[
+                    id(0),
+                    SmallInt(1),
+                    assign(0, 1),
+                    MainBlock {
+                        body: expressions(&[2]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[Constant::Str("a")]),
+            )
+        }
+
+        #[test]
+        fn let_declaration_with_multiple_targets() {
+            let source = "let x, _ = f()";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    Wildcard(None),
+                    id(1),
+                    Chain((
+                        ChainNode::Call {
+                            args: expressions(&[]),
+                            with_parens: true,
+                        },
+                        None,
+                    )),
+                    chain_root(2, Some(3)),
+                    MultiAssign {
+                        targets: expressions(&[0, 1]),
+                        expression: 4.into(),
+                    }, // 5
+                    MainBlock {
+                        body: expressions(&[5]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[Constant::Str("x"), Constant::Str("f")]),
+            )
+        }
+
+        #[test]
+        fn declared_assignments_in_strict_mode() {
+            let source = "
+let x = 1
+x = x + 1
+export y = x
+y = 0
+let f = |a, (b, c)|
+  a = b
+  let x = c
+  x = a
+for i in 0..10
+  i = 42
+match x
+  (n, m) then n = m
+let g = ||
+  let z = 1
+  z = 2
+export
+  h: |a|
+    let w = a
+    w
+";
+            let settings = ParserSettings {
+                strict: true,
+                ..Default::default()
+            };
+            if let Err(error) = Parser::parse_with_settings(source, settings) {
+                panic!("{error} - {:?}", error.span.start);
+            }
+        }
     }
 
     mod export {
@@ -1664,6 +1962,97 @@ a = (1
                 None,
             )
         }
+
+        #[test]
+        fn null_coalescing() {
+            let source = "x ?? y or z";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    id(1),
+                    id(2),
+                    binary_op(AstBinaryOp::Or, 1, 2),
+                    binary_op(AstBinaryOp::NullCoalesce, 0, 3),
+                    MainBlock {
+                        body: expressions(&[4]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[Constant::Str("x"), Constant::Str("y"), Constant::Str("z")]),
+            )
+        }
+
+        #[test]
+        fn bitwise_ops() {
+            let source = "a|b ^ c & d << e";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    id(1),
+                    id(2),
+                    id(3),
+                    id(4),
+                    binary_op(AstBinaryOp::ShiftLeft, 3, 4), // 5
+                    binary_op(AstBinaryOp::BitwiseAnd, 2, 5),
+                    binary_op(AstBinaryOp::BitwiseXor, 1, 6),
+                    binary_op(AstBinaryOp::BitwiseOr, 0, 7),
+                    MainBlock {
+                        body: expressions(&[8]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[
+                    Constant::Str("a"),
+                    Constant::Str("b"),
+                    Constant::Str("c"),
+                    Constant::Str("d"),
+                    Constant::Str("e"),
+                ]),
+            )
+        }
+
+        #[test]
+        fn bitwise_not_and_shift_right() {
+            let source = "~x >> 1 == y";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    unary_op(AstUnaryOp::BitwiseNot, 0),
+                    SmallInt(1),
+                    binary_op(AstBinaryOp::ShiftRight, 1, 2),
+                    id(1),
+                    binary_op(AstBinaryOp::Equal, 3, 4), // 5
+                    MainBlock {
+                        body: expressions(&[5]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[Constant::Str("x"), Constant::Str("y")]),
+            )
+        }
+
+        #[test]
+        fn shifts_and_pipes() {
+            let source = "x << 1 -> f";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    SmallInt(1),
+                    binary_op(AstBinaryOp::ShiftLeft, 0, 1),
+                    id(1),
+                    binary_op(AstBinaryOp::Pipe, 2, 3),
+                    MainBlock {
+                        body: expressions(&[4]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[Constant::Str("x"), Constant::Str("f")]),
+            )
+        }
     }
 
     mod control_flow {
@@ -1792,6 +2181,62 @@ a",
             )
         }
 
+        #[test]
+        fn if_inline_in_list() {
+            let source = "[if true then 0 else 1, 2]";
+            check_ast(
+                source,
+                &[
+                    BoolTrue,
+                    SmallInt(0),
+                    SmallInt(1),
+                    If(AstIf {
+                        condition: 0.into(),
+                        then_node: 1.into(),
+                        else_if_blocks: vec![],
+                        else_node: Some(2.into()),
+                    }),
+                    SmallInt(2),
+                    List(expressions(&[3, 4])), // 5
+                    MainBlock {
+                        body: expressions(&[5]),
+                        local_count: 0,
+                    },
+                ],
+                None,
+            )
+        }
+
+        #[test]
+        fn if_inline_in_call_args() {
+            let sources = ["f(if true then 0 else 1, 2)", "f if true then 0 else 1, 2"];
+            for (source, with_parens) in sources.iter().zip([true, false]) {
+                check_ast(
+                    source,
+                    &[
+                        id(0),
+                        BoolTrue,
+                        SmallInt(0),
+                        SmallInt(1),
+                        If(AstIf {
+                            condition: 1.into(),
+                            then_node: 2.into(),
+                            else_if_blocks: vec![],
+                            else_node: Some(3.into()),
+                        }),
+                        SmallInt(2), // 5
+                        chain_call(&[4, 5], with_parens, None),
+                        chain_root(0, Some(6)),
+                        MainBlock {
+                            body: expressions(&[7]),
+                            local_count: 0,
+                        },
+                    ],
+                    Some(&[Constant::Str("f")]),
+                )
+            }
+        }
+
         #[test]
         fn if_block_in_function_followed_by_id() {
             let source = "
@@ -1830,6 +2275,35 @@ a",
                 Some(&[Constant::Str("x")]),
             )
         }
+
+        #[test]
+        fn cfg_block() {
+            let source = "
+@cfg 'debug'
+  x = 1
+  x
+";
+
+            check_ast(
+                source,
+                &[
+                    id(1),
+                    SmallInt(1),
+                    assign(0, 1),
+                    id(1),
+                    Block(expressions(&[2, 3])),
+                    Cfg {
+                        flag: 0.into(),
+                        body: 4.into(),
+                    }, // 5
+                    MainBlock {
+                        body: expressions(&[5]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[Constant::Str("debug"), Constant::Str("x")]),
+            )
+        }
     }
 
     mod loops {
@@ -2108,6 +2582,131 @@ a()";
             )
         }
 
+        #[test]
+        fn inline_default_args() {
+            let source = "|x, y = x + z| y";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    id(0),
+                    id(2),
+                    binary_op(AstBinaryOp::Add, 1, 2),
+                    DefaultArg {
+                        id: 1.into(),
+                        default: 3.into(),
+                    },
+                    id(1), // 5
+                    Function(koto_parser::Function {
+                        args: expressions(&[0, 4]),
+                        local_count: 2,
+                        accessed_non_locals: vec![2.into()],
+                        body: 5.into(),
+                        is_variadic: false,
+                        is_generator: false,
+                    }),
+                    MainBlock {
+                        body: expressions(&[6]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[Constant::Str("x"), Constant::Str("y"), Constant::Str("z")]),
+            )
+        }
+
+        #[test]
+        fn bitwise_or_in_body_with_default_arg() {
+            let source = "|x = 1| x | 2";
+            check_ast(
+                source,
+                &[
+                    SmallInt(1),
+                    DefaultArg {
+                        id: 0.into(),
+                        default: 0.into(),
+                    },
+                    id(0),
+                    SmallInt(2),
+                    binary_op(AstBinaryOp::BitwiseOr, 2, 3),
+                    Function(koto_parser::Function {
+                        args: expressions(&[1]),
+                        local_count: 1,
+                        accessed_non_locals: vec![],
+                        body: 4.into(),
+                        is_variadic: false,
+                        is_generator: false,
+                    }), // 5
+                    MainBlock {
+                        body: expressions(&[5]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[Constant::Str("x")]),
+            )
+        }
+
+        #[test]
+        fn bitwise_or_in_default_arg() {
+            let source = "|x = (1 | 2)| x";
+            check_ast(
+                source,
+                &[
+                    SmallInt(1),
+                    SmallInt(2),
+                    binary_op(AstBinaryOp::BitwiseOr, 0, 1),
+                    Nested(2.into()),
+                    DefaultArg {
+                        id: 0.into(),
+                        default: 3.into(),
+                    },
+                    id(0), // 5
+                    Function(koto_parser::Function {
+                        args: expressions(&[4]),
+                        local_count: 1,
+                        accessed_non_locals: vec![],
+                        body: 5.into(),
+                        is_variadic: false,
+                        is_generator: false,
+                    }),
+                    MainBlock {
+                        body: expressions(&[6]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[Constant::Str("x")]),
+            )
+        }
+
+        #[test]
+        fn function_arg_with_bitwise_or_in_body() {
+            let source = "f |x| x | y";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    id(1),
+                    id(1),
+                    id(2),
+                    binary_op(AstBinaryOp::BitwiseOr, 2, 3),
+                    Function(koto_parser::Function {
+                        args: expressions(&[1]),
+                        local_count: 1,
+                        accessed_non_locals: vec![2.into()],
+                        body: 4.into(),
+                        is_variadic: false,
+                        is_generator: false,
+                    }), // 5
+                    chain_call(&[5], false, None),
+                    chain_root(0, Some(6)),
+                    MainBlock {
+                        body: expressions(&[7]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[Constant::Str("f"), Constant::Str("x"), Constant::Str("y")]),
+            )
+        }
+
         #[test]
         fn with_body() {
             let source = "\
@@ -2323,6 +2922,89 @@ foo x,
             )
         }
 
+        #[test]
+        fn call_with_named_args() {
+            let sources = [
+                "
+foo x, y: 1
+",
+                "
+foo x,
+    y: 1
+",
+            ];
+
+            check_ast_for_equivalent_sources(
+                &sources,
+                &[
+                    id(0), //foo
+                    id(1), // x
+                    SmallInt(1),
+                    NamedArg {
+                        id: 2.into(),
+                        value: 2.into(),
+                    },
+                    chain_call(&[1, 3], false, None),
+                    chain_root(0, Some(4)), // 5
+                    MainBlock {
+                        body: expressions(&[5]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[Constant::Str("foo"), Constant::Str("x"), Constant::Str("y")]),
+            )
+        }
+
+        #[test]
+        fn call_with_named_args_in_parentheses() {
+            let source = "foo(x, y: 1)";
+
+            check_ast(
+                source,
+                &[
+                    id(0), //foo
+                    id(1), // x
+                    SmallInt(1),
+                    NamedArg {
+                        id: 2.into(),
+                        value: 2.into(),
+                    },
+                    chain_call(&[1, 3], true, None),
+                    chain_root(0, Some(4)), // 5
+                    MainBlock {
+                        body: expressions(&[5]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[Constant::Str("foo"), Constant::Str("x"), Constant::Str("y")]),
+            )
+        }
+
+        #[test]
+        fn call_with_spread_arg() {
+            let sources = ["foo x, y...", "foo(x, y...)"];
+            let with_parens = [false, true];
+
+            for (source, with_parens) in sources.iter().zip(with_parens) {
+                check_ast(
+                    source,
+                    &[
+                        id(0), //foo
+                        id(1), // x
+                        id(2), // y
+                        Spread(2.into()),
+                        chain_call(&[1, 3], with_parens, None),
+                        chain_root(0, Some(4)), // 5
+                        MainBlock {
+                            body: expressions(&[5]),
+                            local_count: 0,
+                        },
+                    ],
+                    Some(&[Constant::Str("foo"), Constant::Str("x"), Constant::Str("y")]),
+                )
+            }
+        }
+
         #[test]
         fn call_with_indented_function_arg() {
             let source = "
@@ -2463,7 +3145,7 @@ f x";
 
         #[test]
         fn call_with_pipe() {
-            let source = "f x >> g >> h";
+            let source = "f x -> g -> h";
             check_ast(
                 source,
                 &[
@@ -2493,8 +3175,8 @@ f x";
         fn indented_piped_calls_after_chain() {
             let source = "
 foo.bar x
-  >> y
-  >> z
+  -> y
+  -> z
 ";
             check_ast(
                 source,
@@ -3027,24 +3709,63 @@ z = y [0..20], |x| x > 1
                     Wildcard(Some(4.into())), // e
                     id(0),
                     Function(koto_parser::Function {
-                        args: expressions(&[0, 6, 7]),
-                        local_count: 3,
+                        args: expressions(&[0, 6, 7]),
+                        local_count: 3,
+                        accessed_non_locals: vec![],
+                        body: 8.into(),
+                        is_variadic: false,
+                        is_generator: false,
+                    }),
+                    MainBlock {
+                        body: expressions(&[9]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[
+                    Constant::Str("a"),
+                    Constant::Str("others"),
+                    Constant::Str("c"),
+                    Constant::Str("d"),
+                    Constant::Str("e"),
+                ]),
+            )
+        }
+
+        #[test]
+        fn deprecated_function() {
+            let source = "
+@deprecated 'use g'
+export f = |x| x
+";
+            check_ast(
+                source,
+                &[
+                    id(1),
+                    id(2),
+                    id(2),
+                    Function(koto_parser::Function {
+                        args: expressions(&[1]),
+                        local_count: 1,
                         accessed_non_locals: vec![],
-                        body: 8.into(),
+                        body: 2.into(),
                         is_variadic: false,
                         is_generator: false,
                     }),
+                    Deprecated {
+                        message: 0.into(),
+                        function: 3.into(),
+                    },
+                    assign(0, 4), // 5
+                    Export(5.into()),
                     MainBlock {
-                        body: expressions(&[9]),
-                        local_count: 0,
+                        body: expressions(&[6]),
+                        local_count: 1,
                     },
                 ],
                 Some(&[
-                    Constant::Str("a"),
-                    Constant::Str("others"),
-                    Constant::Str("c"),
-                    Constant::Str("d"),
-                    Constant::Str("e"),
+                    Constant::Str("use g"),
+                    Constant::Str("f"),
+                    Constant::Str("x"),
                 ]),
             )
         }
@@ -4089,6 +4810,49 @@ from foo import bar,
                 ]),
             )
         }
+
+        #[test]
+        fn embed_without_loader() {
+            let source = "x = @embed 'data.txt'";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    Embed {
+                        path: 1.into(),
+                        contents: None,
+                    },
+                    assign(0, 1),
+                    MainBlock {
+                        body: expressions(&[2]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[Constant::Str("x"), Constant::Str("data.txt")]),
+            )
+        }
+
+        #[test]
+        fn embed_with_loader() {
+            let load_file = |path: &str| {
+                assert_eq!(path, "data.txt");
+                Ok("file contents".to_string())
+            };
+            let settings = ParserSettings {
+                embed_loader: Some(&load_file),
+                ..Default::default()
+            };
+
+            let ast = Parser::parse_with_settings("@embed 'data.txt'", settings).unwrap();
+            assert_eq!(
+                ast.nodes()[0].node,
+                Embed {
+                    path: 0.into(),
+                    contents: Some(1.into()),
+                }
+            );
+            assert_eq!(ast.constants().get_str(1.into()), "file contents");
+        }
     }
 
     mod error_handling {
@@ -4304,6 +5068,116 @@ throw
                 ]),
             )
         }
+
+        #[test]
+        fn propagate_id() {
+            let source = "x = y?";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    id(1),
+                    Propagate(1.into()),
+                    Assign {
+                        target: 0.into(),
+                        expression: 2.into(),
+                    },
+                    MainBlock {
+                        body: expressions(&[3]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[Constant::Str("x"), Constant::Str("y")]),
+            )
+        }
+
+        #[test]
+        fn propagate_in_chain() {
+            let source = "foo()?[0]?";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    chain_call(&[], true, None),
+                    chain_root(0, Some(1)),
+                    Propagate(2.into()),
+                    SmallInt(0),
+                    chain_index(4, None), // 5
+                    chain_root(3, Some(5)),
+                    Propagate(6.into()),
+                    MainBlock {
+                        body: expressions(&[7]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[Constant::Str("foo")]),
+            )
+        }
+
+        #[test]
+        fn optional_access_in_chain() {
+            let source = "foo()?.bar()?";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    chain_call(&[], true, None),
+                    chain_id(1, Some(1)),
+                    chain_optional(Some(2)),
+                    chain_call(&[], true, Some(3)),
+                    chain_root(0, Some(4)), // 5
+                    Propagate(5.into()),
+                    MainBlock {
+                        body: expressions(&[6]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[Constant::Str("foo"), Constant::Str("bar")]),
+            )
+        }
+
+        #[test]
+        fn optional_access() {
+            let source = "x?.y?.'z'";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    Node::Chain((ChainNode::Str(simple_string(2, StringQuote::Single)), None)),
+                    chain_optional(Some(1)),
+                    chain_id(1, Some(2)),
+                    chain_optional(Some(3)),
+                    chain_root(0, Some(4)), // 5
+                    MainBlock {
+                        body: expressions(&[5]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[Constant::Str("x"), Constant::Str("y"), Constant::Str("z")]),
+            )
+        }
+
+        #[test]
+        fn propagate_call_args() {
+            let source = "f x?, y?";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    id(1),
+                    Propagate(1.into()),
+                    id(2),
+                    Propagate(3.into()),
+                    chain_call(&[2, 4], false, None), // 5
+                    chain_root(0, Some(5)),
+                    MainBlock {
+                        body: expressions(&[6]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[Constant::Str("f"), Constant::Str("x"), Constant::Str("y")]),
+            )
+        }
     }
 
     mod match_and_switch {
@@ -4396,6 +5270,110 @@ match x
             )
         }
 
+        #[test]
+        fn match_range_patterns() {
+            let source = "
+match x
+  0..10 then 1
+  ..=5 or 100.. then 2
+";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    SmallInt(0),
+                    SmallInt(10),
+                    Range {
+                        start: 1.into(),
+                        end: 2.into(),
+                        inclusive: false,
+                        step: None,
+                    },
+                    SmallInt(1),
+                    SmallInt(5), // 5
+                    RangeTo {
+                        end: 5.into(),
+                        inclusive: true,
+                    },
+                    SmallInt(100),
+                    RangeFrom { start: 7.into() },
+                    SmallInt(2),
+                    Match {
+                        expression: 0.into(),
+                        arms: vec![
+                            MatchArm {
+                                patterns: expressions(&[3]),
+                                condition: None,
+                                expression: 4.into(),
+                            },
+                            MatchArm {
+                                patterns: expressions(&[6, 8]),
+                                condition: None,
+                                expression: 9.into(),
+                            },
+                        ],
+                    }, // 10
+                    MainBlock {
+                        body: expressions(&[10]),
+                        local_count: 0,
+                    },
+                ],
+                Some(&[Constant::Str("x")]),
+            )
+        }
+
+        #[test]
+        fn match_type_patterns() {
+            let source = "
+match x
+  n: Number then n
+  _: String then 0
+";
+            check_ast(
+                source,
+                &[
+                    id(0),
+                    id(1),
+                    MatchType {
+                        pattern: 1.into(),
+                        type_name: 2.into(),
+                    },
+                    id(1),
+                    Wildcard(None),
+                    MatchType {
+                        pattern: 4.into(),
+                        type_name: 3.into(),
+                    }, // 5
+                    SmallInt(0),
+                    Match {
+                        expression: 0.into(),
+                        arms: vec![
+                            MatchArm {
+                                patterns: expressions(&[2]),
+                                condition: None,
+                                expression: 3.into(),
+                            },
+                            MatchArm {
+                                patterns: expressions(&[5]),
+                                condition: None,
+                                expression: 6.into(),
+                            },
+                        ],
+                    },
+                    MainBlock {
+                        body: expressions(&[7]),
+                        local_count: 1,
+                    },
+                ],
+                Some(&[
+                    Constant::Str("x"),
+                    Constant::Str("n"),
+                    Constant::Str("Number"),
+                    Constant::Str("String"),
+                ]),
+            )
+        }
+
         #[test]
         fn match_tuple() {
             let source = r#"
@@ -4859,4 +5837,103 @@ switch
             )
         }
     }
+
+    mod warnings {
+        use super::*;
+
+        fn check_warnings(source: &str, expected_shadowed_ids: &[&str]) {
+            let ast = match Parser::parse(source) {
+                Ok(ast) => ast,
+                Err(error) => panic!("{error} - {:?}", error.span.start),
+            };
+
+            let shadowed_ids = ast
+                .warnings()
+                .iter()
+                .map(|warning| match &warning.warning {
+                    WarningKind::ModuleValueShadowed(id) => id.as_str(),
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(shadowed_ids, expected_shadowed_ids);
+        }
+
+        #[test]
+        fn assignment_to_module_value_in_function() {
+            let source = "
+x = 1
+f = ||
+  x = 2
+";
+            check_warnings(source, &["x"]);
+        }
+
+        #[test]
+        fn assignment_to_module_value_in_nested_function() {
+            let source = "
+x = 1
+f = ||
+  g = ||
+    x = 2
+";
+            check_warnings(source, &["x"]);
+        }
+
+        #[test]
+        fn assignment_to_value_from_enclosing_function() {
+            let source = "
+f = |x|
+  g = ||
+    x = 2
+";
+            check_warnings(source, &[]);
+        }
+
+        #[test]
+        fn assignment_to_module_value_shadowed_by_enclosing_function() {
+            let source = "
+x = 1
+f = |x|
+  g = ||
+    x = 2
+";
+            check_warnings(source, &[]);
+        }
+
+        #[test]
+        fn warning_span_refers_to_the_assignment_target() {
+            let source = "
+x = 1
+f = ||
+  y, x = 2, 3
+";
+            let ast = Parser::parse(source).unwrap();
+            let warning = &ast.warnings()[0];
+            assert_eq!(warning.span.start, Position { line: 3, column: 5 });
+            assert_eq!(warning.span.end, Position { line: 3, column: 6 });
+        }
+
+        #[test]
+        fn explicit_declarations_and_exports() {
+            let source = "
+x, y = 1, 2
+f = |y|
+  let x = 2
+  x = 3
+  y = 4
+  export z = 5
+";
+            check_warnings(source, &[]);
+        }
+
+        #[test]
+        fn assignment_before_module_value_is_declared() {
+            let source = "
+f = ||
+  x = 2
+x = 1
+";
+            check_warnings(source, &[]);
+        }
+    }
 }
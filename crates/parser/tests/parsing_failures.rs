@@ -41,6 +41,16 @@ mod parser {
             check_parsing_fails("import foo bar");
         }
 
+        #[test]
+        fn non_id_in_nested_assignment_target() {
+            check_parsing_fails("(a, 1), b = x");
+        }
+
+        #[test]
+        fn missing_range_step() {
+            check_parsing_fails("0..10 step");
+        }
+
         mod indentation {
             use super::*;
 
@@ -112,6 +122,21 @@ for x in y if f x
                 check_parsing_fails("f = |x, _...| x");
             }
 
+            #[test]
+            fn missing_default_arg_value() {
+                check_parsing_fails("f = |x, y = | x");
+            }
+
+            #[test]
+            fn varargs_with_default_value() {
+                check_parsing_fails("f = |x, y = 1...| x");
+            }
+
+            #[test]
+            fn ambiguous_bitwise_or_in_default_arg_value() {
+                check_parsing_fails("f = |x = 1 | 2| x");
+            }
+
             #[test]
             fn missing_terminator_for_unpacked_arg() {
                 check_parsing_fails("f = |a, (b, c, d| a");
@@ -122,6 +147,21 @@ for x in y if f x
                 check_parsing_fails("f = |a, [b, c]| a");
             }
 
+            #[test]
+            fn positional_arg_after_named_arg() {
+                check_parsing_fails("f x: 1, 2");
+            }
+
+            #[test]
+            fn positional_arg_after_named_arg_in_parentheses() {
+                check_parsing_fails("f(x: 1, 2)");
+            }
+
+            #[test]
+            fn missing_named_arg_value() {
+                check_parsing_fails("f x: 1, y:");
+            }
+
             #[test]
             fn missing_commas_in_call() {
                 check_parsing_fails("f 1 2 3");
@@ -140,6 +180,79 @@ f = ||
             fn missing_commas_in_chained_call() {
                 check_parsing_fails("f.bar 1 2 3");
             }
+
+            #[test]
+            fn deprecated_without_message() {
+                let source = "
+@deprecated
+f = |x| x
+";
+                check_parsing_fails(source);
+            }
+
+            #[test]
+            fn deprecated_with_interpolated_message() {
+                let source = "
+@deprecated 'use {g}'
+f = |x| x
+";
+                check_parsing_fails(source);
+            }
+
+            #[test]
+            fn deprecated_non_function() {
+                let source = "
+@deprecated 'use y'
+x = 42
+";
+                check_parsing_fails(source);
+            }
+
+            #[test]
+            fn deprecated_function_not_on_following_line() {
+                check_parsing_fails("@deprecated 'use g' f = |x| x");
+            }
+        }
+
+        mod cfg {
+            use super::*;
+
+            #[test]
+            fn cfg_without_flag() {
+                let source = "
+@cfg
+  print 'debug'
+";
+                check_parsing_fails(source);
+            }
+
+            #[test]
+            fn cfg_with_interpolated_flag() {
+                let source = "
+@cfg '{mode}'
+  print 'debug'
+";
+                check_parsing_fails(source);
+            }
+
+            #[test]
+            fn cfg_without_block() {
+                check_parsing_fails("@cfg 'debug' print 'debug'");
+            }
+        }
+
+        mod embed {
+            use super::*;
+
+            #[test]
+            fn embed_without_path() {
+                check_parsing_fails("x = @embed");
+            }
+
+            #[test]
+            fn embed_with_interpolated_path() {
+                check_parsing_fails("x = @embed '{name}.txt'");
+            }
         }
 
         mod chains {
@@ -206,6 +319,14 @@ x = foo: 42, bar: 99,
             fn string_used_as_valueless_key() {
                 let source = "
 x = {'y'}
+";
+                check_parsing_fails(source);
+            }
+
+            #[test]
+            fn spread_string_key() {
+                let source = "
+x = {'y'...}
 ";
                 check_parsing_fails(source);
             }
@@ -293,6 +414,33 @@ match
                 check_parsing_fails(source);
             }
 
+            #[test]
+            fn range_pattern_without_bounds() {
+                let source = "
+match x
+  .. then 1
+";
+                check_parsing_fails(source);
+            }
+
+            #[test]
+            fn inclusive_range_pattern_without_end() {
+                let source = "
+match x
+  0..= then 1
+";
+                check_parsing_fails(source);
+            }
+
+            #[test]
+            fn type_pattern_without_type_name() {
+                let source = "
+match x
+  n: then 1
+";
+                check_parsing_fails(source);
+            }
+
             #[test]
             fn square_brackets_used_for_unpacking() {
                 let source = "
@@ -313,6 +461,16 @@ match [1, 2, 3]
 switch
   true
     1
+";
+                check_parsing_fails(source);
+            }
+
+            #[test]
+            fn missing_else_arm() {
+                let source = "
+switch
+  x < 0 then -1
+  x > 0 then 1
 ";
                 check_parsing_fails(source);
             }
@@ -355,6 +513,48 @@ switch
 ";
                 check_parsing_fails(source);
             }
+
+            #[test]
+            fn unknown_escape_code() {
+                check_parsing_fails(r"'\q'");
+            }
+
+            #[test]
+            fn ascii_escape_code_out_of_range() {
+                check_parsing_fails(r"'\x80'");
+            }
+
+            #[test]
+            fn ascii_escape_code_with_one_digit() {
+                check_parsing_fails(r"'\x4'");
+            }
+
+            #[test]
+            fn unicode_escape_code_without_braces() {
+                check_parsing_fails(r"'\u1F600'");
+            }
+
+            #[test]
+            fn empty_unicode_escape_code() {
+                check_parsing_fails(r"'\u{}'");
+            }
+
+            #[test]
+            fn unterminated_unicode_escape_code() {
+                check_parsing_fails(r"'\u{1F600'");
+            }
+
+            #[test]
+            fn unicode_escape_code_out_of_range() {
+                check_parsing_fails(r"'\u{110000}'");
+                check_parsing_fails(r"'\u{d800}'");
+            }
+
+            #[test]
+            fn unicode_escape_code_with_too_many_digits() {
+                check_parsing_fails(r"'\u{0000001}'");
+                check_parsing_fails(r"'\u{fffffffffff}'");
+            }
         }
 
         mod import {
@@ -381,6 +581,106 @@ switch
             }
         }
 
+        mod let_declarations {
+            use super::*;
+
+            #[test]
+            fn let_without_assignment() {
+                check_parsing_fails("let x");
+            }
+
+            #[test]
+            fn let_with_chain_target() {
+                check_parsing_fails("let x.y = 1");
+            }
+
+            #[test]
+            fn let_with_nested_chain_target() {
+                check_parsing_fails("let x, y[0] = 1, 2");
+            }
+
+            #[test]
+            fn undeclared_assignment_in_strict_mode() {
+                let source = "
+# koto: strict
+let value = 1
+valeu = 2
+";
+                check_parsing_fails(source);
+            }
+
+            #[test]
+            fn undeclared_multi_assignment_in_strict_mode() {
+                let source = "
+# koto: strict
+let a = 1
+a, b = 2, 3
+";
+                check_parsing_fails(source);
+            }
+
+            #[test]
+            fn assignment_to_module_value_in_strict_mode() {
+                let source = "
+# koto: strict
+let total = 0
+let add = |n|
+  total = total + n
+";
+                check_parsing_fails(source);
+            }
+
+            #[test]
+            fn shadowing_captured_value_in_strict_mode() {
+                let source = "
+# koto: strict
+let count = 0
+let f = ||
+  count = 1
+";
+                check_parsing_fails(source);
+            }
+
+            #[test]
+            fn undeclared_assignment_in_let_expression_in_strict_mode() {
+                let source = "
+# koto: strict
+let x = (y = 1)
+";
+                check_parsing_fails(source);
+            }
+
+            #[test]
+            fn undeclared_assignment_in_let_function_in_strict_mode() {
+                let source = "
+# koto: strict
+let a, f = 1, ||
+  b = 2
+";
+                check_parsing_fails(source);
+            }
+
+            #[test]
+            fn undeclared_assignment_in_exported_map_in_strict_mode() {
+                let source = "
+# koto: strict
+export
+  f: ||
+    x = 1
+";
+                check_parsing_fails(source);
+            }
+
+            #[test]
+            fn undeclared_assignment_in_export_expression_in_strict_mode() {
+                let source = "
+# koto: strict
+export {foo: (x = 1)}
+";
+                check_parsing_fails(source);
+            }
+        }
+
         mod reserved_keywords {
             use super::*;
 
@@ -0,0 +1,273 @@
+mod refactor {
+    use koto_parser::{
+        rename_symbol, Position, RenameError, RenameSettings, RenamedSymbolKind, Span,
+    };
+
+    fn check_rename(source: &str, old: &str, new: &str, settings: RenameSettings, expected: &str) {
+        match rename_symbol(source, old, new, settings) {
+            Ok(result) => assert_eq!(result.source, expected),
+            Err(error) => panic!("Rename failed: {error}"),
+        }
+    }
+
+    #[test]
+    fn rename_variable() {
+        let source = "\
+foo = 42
+bar = foo + 1
+f = |x| x + foo
+'{foo}'
+";
+        let expected = "\
+baz = 42
+bar = baz + 1
+f = |x| x + baz
+'{baz}'
+";
+        check_rename(source, "foo", "baz", RenameSettings::default(), expected);
+    }
+
+    #[test]
+    fn map_keys_and_lookups_are_unchanged() {
+        let source = "\
+first = |xs| xs.first()
+m =
+  first: 1
+  last: || self.first
+m.first
+x = {first}
+";
+        let expected = "\
+head = |xs| xs.first()
+m =
+  first: 1
+  last: || self.first
+m.first
+x = {first: head}
+";
+        check_rename(source, "first", "head", RenameSettings::default(), expected);
+    }
+
+    #[test]
+    fn local_values_are_unchanged() {
+        let source = "\
+f = |n| n + 1
+g = |f| f * 2
+h = ||
+  x = f 1
+  f = 2
+  f + x
+for x in f 0..3
+  f x
+";
+        let expected = "\
+inc = |n| n + 1
+g = |f| f * 2
+h = ||
+  x = inc 1
+  f = 2
+  f + x
+for x in inc 0..3
+  inc x
+";
+        check_rename(source, "f", "inc", RenameSettings::default(), expected);
+    }
+
+    #[test]
+    fn rename_exported_values() {
+        let source = "\
+export
+  foo: 1
+  bar: || foo + 1
+export baz = || foo
+";
+        let expected = "\
+export
+  qux: 1
+  bar: || qux + 1
+export baz = || qux
+";
+        check_rename(source, "foo", "qux", RenameSettings::default(), expected);
+    }
+
+    #[test]
+    fn rename_imported_values() {
+        let source = "\
+from utils import foo
+import utils
+from other import foo as bar
+x = foo utils.foo(), utils.bar.foo
+";
+        let expected = "\
+from utils import qux
+import utils
+from other import qux as bar
+x = qux utils.qux(), utils.bar.foo
+";
+        check_rename(source, "foo", "qux", RenameSettings::default(), expected);
+    }
+
+    #[test]
+    fn module_imports_are_unchanged() {
+        let source = "\
+import foo
+foo.bar()
+";
+        check_rename(source, "foo", "qux", RenameSettings::default(), source);
+    }
+
+    #[test]
+    fn strings_and_comments_are_skipped_by_default() {
+        let source = "\
+# foo
+x = 'foo foobar'
+foo = x #- foo -#
+";
+        let expected = "\
+# foo
+x = 'foo foobar'
+baz = x #- foo -#
+";
+        check_rename(source, "foo", "baz", RenameSettings::default(), expected);
+    }
+
+    #[test]
+    fn rename_in_strings_and_comments() {
+        let source = "\
+# foo
+x = 'foo foobar'
+foo = x #- foo -#
+";
+        let expected = "\
+# baz
+x = 'baz foobar'
+baz = x #- baz -#
+";
+        let settings = RenameSettings {
+            include_strings: true,
+            include_comments: true,
+        };
+        check_rename(source, "foo", "baz", settings, expected);
+    }
+
+    #[test]
+    fn meta_key_names_are_unchanged() {
+        let source = "\
+export
+  @test foo: || foo()
+  @main: || foo()
+foo = || null
+";
+        let expected = "\
+export
+  @test foo: || bar()
+  @main: || bar()
+bar = || null
+";
+        check_rename(source, "foo", "bar", RenameSettings::default(), expected);
+    }
+
+    #[test]
+    fn renamed_spans() {
+        let source = "\
+foo = 1
+x = 'é foo' # foo
+";
+        let settings = RenameSettings {
+            include_strings: true,
+            include_comments: false,
+        };
+        let result = rename_symbol(source, "foo", "bar", settings).unwrap();
+
+        let renamed: Vec<_> = result
+            .renamed
+            .iter()
+            .map(|symbol| (symbol.kind, symbol.source_bytes.clone(), symbol.span))
+            .collect();
+        let span = |line, start, end| Span {
+            start: Position {
+                line,
+                column: start,
+            },
+            end: Position { line, column: end },
+        };
+
+        assert_eq!(
+            renamed,
+            [
+                (RenamedSymbolKind::Id, 0..3, span(0, 0, 3)),
+                (RenamedSymbolKind::String, 16..19, span(1, 7, 10)),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_names() {
+        let source = "foo = 1";
+        for (old, new) in [("foo", "if"), ("foo", "1x"), ("foo", "a b"), ("", "foo")] {
+            assert!(matches!(
+                rename_symbol(source, old, new, RenameSettings::default()),
+                Err(RenameError::InvalidName(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn new_name_in_use() {
+        let check_in_use = |source: &str| {
+            assert!(
+                matches!(
+                    rename_symbol(source, "foo", "bar", RenameSettings::default()),
+                    Err(RenameError::NameInUse(_))
+                ),
+                "Expected 'bar' to be in use in:\n{source}"
+            );
+        };
+
+        // Top-level values
+        check_in_use("foo = 1\nbar = 2\n");
+        check_in_use("foo = 1\nexport\n  bar: 2\n");
+        check_in_use("import bar\nfoo = 1\n");
+        // A local value that would capture the renamed value
+        check_in_use("foo = 1\nf = |bar| bar + foo\n");
+        check_in_use("foo = 1\nf = ||\n  x = foo\n  bar = x\n");
+        // A value that isn't defined in the script, e.g. a core library module
+        check_in_use("foo = 1\nbar.baz()\n");
+    }
+
+    #[test]
+    fn new_name_in_unrelated_scope() {
+        let source = "\
+foo = 1
+f = |bar| bar * 2
+g = ||
+  bar = 3
+  bar + foo
+h = |x| x.bar + foo
+";
+        let expected = "\
+baz = 1
+f = |bar| bar * 2
+g = ||
+  bar = 3
+  bar + baz
+h = |x| x.bar + baz
+";
+        check_rename(source, "foo", "baz", RenameSettings::default(), expected);
+        check_rename(
+            "foo = 1\nf = |bar| bar * 2\nfoo\n",
+            "foo",
+            "bar",
+            RenameSettings::default(),
+            "bar = 1\nf = |bar| bar * 2\nbar\n",
+        );
+    }
+
+    #[test]
+    fn invalid_source() {
+        assert!(matches!(
+            rename_symbol("foo = (", "foo", "bar", RenameSettings::default()),
+            Err(RenameError::InvalidSource(_))
+        ));
+    }
+}
@@ -0,0 +1,268 @@
+//! Resource-limited execution of Koto scripts, intended for use in online playgrounds
+//!
+//! [run] compiles and runs a script with the given [Limits], capturing the script's output and
+//! returning errors in a structured form that can be presented alongside the script.
+//!
+//! Scripts only have access to a restricted prelude, with modules that provide access to the host
+//! system (e.g. `os`, and file access in `io`) being unavailable.
+//!
+//! When built for wasm, a `run` function is also exported via `wasm-bindgen`. The
+//! [memory limit](Limits::memory) is only enforced in wasm builds.
+//!
+//! ## Example
+//!
+//! ```
+//! use koto_playground::{run, Limits};
+//!
+//! let result = run("print 'Hello!'\n1 + 2", Limits::default());
+//! assert_eq!(result.output, "Hello!\n");
+//! assert_eq!(result.result.unwrap(), "3");
+//! ```
+
+#![warn(missing_docs)]
+
+mod output;
+#[cfg(target_arch = "wasm32")]
+mod wasm;
+
+use crate::output::{BlockedInput, CapturedOutput, OutputCapture};
+use koto::{parser::Span, prelude::*, ErrorKind};
+use std::time::Duration;
+
+/// Resource limits that are applied when running a script with [run]
+///
+/// Each limit is optional, with `None` disabling the limit.
+#[derive(Clone, Debug)]
+pub struct Limits {
+    /// The maximum number of instructions that the script is allowed to execute
+    pub instructions: Option<u64>,
+    /// The maximum number of bytes of memory that the script is allowed to use
+    ///
+    /// Memory usage is measured as the growth of the wasm module's linear memory, so the limit
+    /// only takes effect in wasm builds, and is ignored on other targets.
+    pub memory: Option<usize>,
+    /// The maximum amount of time that the script is allowed to run for
+    pub execution_time: Option<Duration>,
+    /// The maximum number of bytes of output that the script is allowed to produce
+    pub output: Option<usize>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            instructions: Some(10_000_000),
+            memory: Some(64 * 1024 * 1024),
+            execution_time: Some(Duration::from_secs(5)),
+            output: Some(1024 * 1024),
+        }
+    }
+}
+
+impl Limits {
+    /// Limits with each limit disabled
+    pub fn none() -> Self {
+        Self {
+            instructions: None,
+            memory: None,
+            execution_time: None,
+            output: None,
+        }
+    }
+}
+
+/// The result of running a script with [run]
+#[derive(Clone, Debug)]
+pub struct RunResult {
+    /// The output that was written by the script to stdout and stderr
+    pub output: String,
+    /// The script's return value rendered as a string, or the error that stopped the script
+    pub result: Result<String, PlaygroundError>,
+}
+
+/// An error that stopped a script from running to completion
+#[derive(Clone, Debug)]
+pub struct PlaygroundError {
+    /// The kind of error that occurred
+    pub kind: PlaygroundErrorKind,
+    /// The error's message
+    pub message: String,
+    /// The span in the script where the error occurred, if available
+    pub span: Option<Span>,
+}
+
+/// The different kinds of [PlaygroundError]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlaygroundErrorKind {
+    /// The script failed to compile
+    Compile,
+    /// An error was thrown while the script was running
+    Runtime,
+    /// The script reached the instruction limit
+    InstructionLimit,
+    /// The script exceeded the memory limit
+    MemoryLimit,
+    /// The script reached the execution time limit
+    Timeout,
+    /// The script reached the output limit
+    OutputLimit,
+}
+
+impl PlaygroundErrorKind {
+    /// The kind's name, e.g. for use by a playground's frontend
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Compile => "compile",
+            Self::Runtime => "runtime",
+            Self::InstructionLimit => "instruction_limit",
+            Self::MemoryLimit => "memory_limit",
+            Self::Timeout => "timeout",
+            Self::OutputLimit => "output_limit",
+        }
+    }
+}
+
+/// Compiles and runs a script with the given limits
+///
+/// Output from the script is captured rather than being written to the process's stdout, and
+/// reading from stdin is unsupported.
+///
+/// The script's prelude only contains items from an allow-list of core library modules, with `os`
+/// being unavailable, and `io` being limited to `print` and the standard streams.
+pub fn run(source: &str, limits: Limits) -> RunResult {
+    let output = CapturedOutput::new(limits.output);
+
+    let mut settings = KotoSettings {
+        run_tests: false,
+        ..Default::default()
+    }
+    .with_stdin(BlockedInput)
+    .with_stdout(OutputCapture::new("_stdout_", output.clone()))
+    .with_stderr(OutputCapture::new("_stderr_", output.clone()));
+
+    if let Some(limit) = limits.instructions {
+        settings = settings.with_instruction_limit(limit);
+    }
+    if let Some(limit) = limits.execution_time {
+        settings = settings.with_execution_limit(limit);
+    }
+    if let (Some(limit), Some(baseline)) = (limits.memory, memory_size()) {
+        settings = settings.with_memory_limit(limit, move || {
+            memory_size().unwrap_or(0).saturating_sub(baseline)
+        });
+    }
+
+    let mut koto = Koto::with_settings(settings);
+    restrict_prelude(koto.prelude());
+
+    let result = match koto.compile(source) {
+        Ok(_) => match koto.run() {
+            Ok(value) => koto.value_to_string(value).map_err(PlaygroundError::from),
+            Err(error) => Err(PlaygroundError::from(error)),
+        },
+        Err(error) => Err(PlaygroundError::from(error)),
+    };
+
+    // Reaching the output limit takes precedence over other results,
+    // the script may have caught the error that was thrown when writing.
+    let result = if output.limit_reached() {
+        Err(PlaygroundError {
+            kind: PlaygroundErrorKind::OutputLimit,
+            message: output::OUTPUT_LIMIT_MESSAGE.into(),
+            span: None,
+        })
+    } else {
+        result
+    };
+
+    RunResult {
+        output: output.take(),
+        result,
+    }
+}
+
+// The prelude items that are available to scripts run in the playground
+//
+// Modules that provide access to the host system (e.g. `os`) aren't included, and the `io`
+// module is replaced with a module containing only the items in IO_ITEMS.
+const PRELUDE_ITEMS: &[&str] = &[
+    "assert",
+    "assert_eq",
+    "assert_ne",
+    "assert_near",
+    "copy",
+    "err",
+    "iterator",
+    "koto",
+    "list",
+    "map",
+    "number",
+    "ok",
+    "print",
+    "range",
+    "size",
+    "string",
+    "test",
+    "tuple",
+    "type",
+];
+
+// The items from the `io` module that are available to scripts run in the playground
+//
+// The standard streams are redirected by `run`, so they can be used safely.
+const IO_ITEMS: &[&str] = &["print", "stderr", "stdin", "stdout"];
+
+// Removes the prelude items that aren't in the allow-lists
+fn restrict_prelude(prelude: &KMap) {
+    let io = KMap::default();
+    if let Some(KValue::Map(full_io)) = prelude.get("io") {
+        for &item in IO_ITEMS {
+            if let Some(value) = full_io.get(item) {
+                io.insert(item, value);
+            }
+        }
+    }
+
+    prelude.data_mut().retain(|key, _| match key.value() {
+        KValue::Str(name) => PRELUDE_ITEMS.contains(&name.as_str()),
+        _ => false,
+    });
+    prelude.insert("io", io);
+}
+
+impl From<koto::Error> for PlaygroundError {
+    fn from(error: koto::Error) -> Self {
+        let kind = match &error.error {
+            ErrorKind::CompileError(_) => PlaygroundErrorKind::Compile,
+            ErrorKind::InstructionLimit(_) => PlaygroundErrorKind::InstructionLimit,
+            ErrorKind::MemoryLimit(_) => PlaygroundErrorKind::MemoryLimit,
+            ErrorKind::Timeout(_) => PlaygroundErrorKind::Timeout,
+            _ => PlaygroundErrorKind::Runtime,
+        };
+
+        // Compilation errors include an excerpt from the source when displayed,
+        // so only the underlying error's message is used here.
+        let message = match &error.error {
+            ErrorKind::CompileError(compile_error) => compile_error.error.to_string(),
+            other => other.to_string(),
+        };
+
+        Self {
+            kind,
+            message,
+            span: error.source_span(),
+        }
+    }
+}
+
+// Returns the size of the wasm module's linear memory in bytes
+#[cfg(target_arch = "wasm32")]
+fn memory_size() -> Option<usize> {
+    const PAGE_SIZE: usize = 64 * 1024;
+    Some(core::arch::wasm32::memory_size(0) * PAGE_SIZE)
+}
+
+// Memory usage isn't measured on other platforms
+#[cfg(not(target_arch = "wasm32"))]
+fn memory_size() -> Option<usize> {
+    None
+}
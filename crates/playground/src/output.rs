@@ -0,0 +1,111 @@
+use koto::{prelude::*, runtime::Result, PtrMut};
+
+pub const OUTPUT_LIMIT_MESSAGE: &str = "The output limit was reached";
+
+// Output that's shared by the captured stdout and stderr
+#[derive(Clone)]
+pub struct CapturedOutput(PtrMut<OutputState>);
+
+struct OutputState {
+    output: String,
+    limit: Option<usize>,
+    limit_reached: bool,
+}
+
+impl CapturedOutput {
+    pub fn new(limit: Option<usize>) -> Self {
+        Self(
+            OutputState {
+                output: String::new(),
+                limit,
+                limit_reached: false,
+            }
+            .into(),
+        )
+    }
+
+    pub fn limit_reached(&self) -> bool {
+        self.0.borrow().limit_reached
+    }
+
+    pub fn take(&self) -> String {
+        std::mem::take(&mut self.0.borrow_mut().output)
+    }
+
+    // Appends text to the output, returning an error if the output limit is reached
+    //
+    // Output that would exceed the limit is discarded.
+    fn push(&self, text: &str) -> Result<()> {
+        let mut state = self.0.borrow_mut();
+
+        if let Some(limit) = state.limit {
+            if state.limit_reached || state.output.len() + text.len() > limit {
+                state.limit_reached = true;
+                return runtime_error!(OUTPUT_LIMIT_MESSAGE);
+            }
+        }
+
+        state.output.push_str(text);
+        Ok(())
+    }
+}
+
+// Captures output from Koto in the shared output
+pub struct OutputCapture {
+    id: KString,
+    output: CapturedOutput,
+}
+
+impl OutputCapture {
+    pub fn new(id: &str, output: CapturedOutput) -> Self {
+        Self {
+            id: id.into(),
+            output,
+        }
+    }
+}
+
+impl KotoFile for OutputCapture {
+    fn id(&self) -> KString {
+        self.id.clone()
+    }
+}
+
+impl KotoRead for OutputCapture {}
+impl KotoWrite for OutputCapture {
+    fn write(&self, bytes: &[u8]) -> Result<()> {
+        match std::str::from_utf8(bytes) {
+            Ok(s) => self.output.push(s),
+            Err(e) => Err(e.to_string().into()),
+        }
+    }
+
+    fn write_line(&self, output: &str) -> Result<()> {
+        self.output.push(output)?;
+        self.output.push("\n")
+    }
+
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+// Input isn't available in the playground
+pub struct BlockedInput;
+
+impl KotoFile for BlockedInput {
+    fn id(&self) -> KString {
+        "_stdin_".into()
+    }
+}
+
+impl KotoWrite for BlockedInput {}
+impl KotoRead for BlockedInput {
+    fn read_line(&self) -> Result<Option<String>> {
+        runtime_error!("Reading input is unsupported in the playground")
+    }
+
+    fn read_to_string(&self) -> Result<String> {
+        runtime_error!("Reading input is unsupported in the playground")
+    }
+}
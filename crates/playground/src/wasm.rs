@@ -0,0 +1,90 @@
+use crate::{Limits, PlaygroundError};
+use std::time::Duration;
+use wasm_bindgen::prelude::*;
+
+/// The result of running a script, see [crate::RunResult]
+#[wasm_bindgen]
+pub struct PlaygroundResult {
+    output: String,
+    return_value: Option<String>,
+    error: Option<PlaygroundError>,
+}
+
+#[wasm_bindgen]
+impl PlaygroundResult {
+    /// The output that was written by the script
+    #[wasm_bindgen(getter)]
+    pub fn output(&self) -> String {
+        self.output.clone()
+    }
+
+    /// The script's return value rendered as a string, if the script ran successfully
+    #[wasm_bindgen(getter, js_name = returnValue)]
+    pub fn return_value(&self) -> Option<String> {
+        self.return_value.clone()
+    }
+
+    /// The kind of error that stopped the script, see [crate::PlaygroundErrorKind::name]
+    #[wasm_bindgen(getter, js_name = errorKind)]
+    pub fn error_kind(&self) -> Option<String> {
+        self.error.as_ref().map(|error| error.kind.name().into())
+    }
+
+    /// The error's message
+    #[wasm_bindgen(getter, js_name = errorMessage)]
+    pub fn error_message(&self) -> Option<String> {
+        self.error.as_ref().map(|error| error.message.clone())
+    }
+
+    /// The line where the error occurred, counting from 1
+    #[wasm_bindgen(getter, js_name = errorLine)]
+    pub fn error_line(&self) -> Option<u32> {
+        self.error_span().map(|span| span.start.line + 1)
+    }
+
+    /// The column where the error occurred, counting from 1
+    #[wasm_bindgen(getter, js_name = errorColumn)]
+    pub fn error_column(&self) -> Option<u32> {
+        self.error_span().map(|span| span.start.column + 1)
+    }
+}
+
+impl PlaygroundResult {
+    fn error_span(&self) -> Option<koto::parser::Span> {
+        self.error.as_ref().and_then(|error| error.span)
+    }
+}
+
+/// Runs a script with the given limits, see [crate::run]
+///
+/// Limits that aren't provided fall back to the defaults defined in [Limits].
+#[wasm_bindgen(js_name = run)]
+pub fn run_script(
+    source: &str,
+    instruction_limit: Option<u32>,
+    memory_limit: Option<u32>,
+    time_limit_ms: Option<u32>,
+    output_limit: Option<u32>,
+) -> PlaygroundResult {
+    let defaults = Limits::default();
+    let limits = Limits {
+        instructions: instruction_limit.map(u64::from).or(defaults.instructions),
+        memory: memory_limit.map(|limit| limit as usize).or(defaults.memory),
+        execution_time: time_limit_ms
+            .map(|ms| Duration::from_millis(ms.into()))
+            .or(defaults.execution_time),
+        output: output_limit.map(|limit| limit as usize).or(defaults.output),
+    };
+
+    let result = crate::run(source, limits);
+    let (return_value, error) = match result.result {
+        Ok(value) => (Some(value), None),
+        Err(error) => (None, Some(error)),
+    };
+
+    PlaygroundResult {
+        output: result.output,
+        return_value,
+        error,
+    }
+}
@@ -0,0 +1,132 @@
+mod playground {
+    use koto_playground::{run, Limits, PlaygroundErrorKind};
+
+    fn check_error(script: &str, limits: Limits, expected_kind: PlaygroundErrorKind) -> String {
+        let result = run(script, limits);
+        match result.result {
+            Err(error) => {
+                assert_eq!(error.kind, expected_kind, "{}", error.message);
+                result.output
+            }
+            Ok(value) => panic!("Expected an error, but the script returned '{value}'"),
+        }
+    }
+
+    #[test]
+    fn captured_output_and_return_value() {
+        let result = run(
+            "print 'hello'\nio.stderr().write_line 'oops'\n[1, 2]",
+            Limits::default(),
+        );
+        assert_eq!(result.output, "hello\noops\n");
+        assert_eq!(result.result.unwrap(), "[1, 2]");
+    }
+
+    #[test]
+    fn compile_error() {
+        let result = run("x = 1\ny = (", Limits::default());
+        let error = result.result.unwrap_err();
+        assert_eq!(error.kind, PlaygroundErrorKind::Compile);
+        assert!(!error.message.contains('\n'));
+        assert_eq!(error.span.unwrap().start.line, 1);
+    }
+
+    #[test]
+    fn runtime_error() {
+        let result = run("print 'before'\nx = 1\nx.foo()", Limits::default());
+        assert_eq!(result.output, "before\n");
+        let error = result.result.unwrap_err();
+        assert_eq!(error.kind, PlaygroundErrorKind::Runtime);
+        assert_eq!(error.span.unwrap().start.line, 2);
+    }
+
+    #[test]
+    fn instruction_limit() {
+        let limits = Limits {
+            instructions: Some(1000),
+            ..Limits::none()
+        };
+        check_error(
+            "while true\n  ()",
+            limits,
+            PlaygroundErrorKind::InstructionLimit,
+        );
+    }
+
+    #[test]
+    fn instruction_limit_in_iterator_adaptor() {
+        let limits = Limits {
+            instructions: Some(1000),
+            ..Limits::none()
+        };
+        check_error(
+            "(0..1000000000).each(|x| x * 2).consume()",
+            limits,
+            PlaygroundErrorKind::InstructionLimit,
+        );
+    }
+
+    #[test]
+    fn output_limit() {
+        let limits = Limits {
+            output: Some(10),
+            ..Limits::default()
+        };
+        let output = check_error(
+            "for i in 0..100\n  print i",
+            limits,
+            PlaygroundErrorKind::OutputLimit,
+        );
+        assert_eq!(output, "0\n1\n2\n3\n4\n");
+    }
+
+    #[test]
+    fn output_limit_error_cant_be_hidden() {
+        let limits = Limits {
+            output: Some(10),
+            ..Limits::default()
+        };
+        check_error(
+            "
+for i in 0..100
+  try
+    print i
+  catch _
+    ()
+",
+            limits,
+            PlaygroundErrorKind::OutputLimit,
+        );
+    }
+
+    #[test]
+    fn host_modules_are_unavailable() {
+        for script in [
+            "os.command 'ls'",
+            "import os",
+            "io.open 'data.txt'",
+            "io.remove_file 'x'",
+        ] {
+            check_error(script, Limits::default(), PlaygroundErrorKind::Runtime);
+        }
+    }
+
+    #[test]
+    fn core_lib_modules_are_available() {
+        let result = run(
+            "print string.to_uppercase 'x'\n(list.last [1, 2]), number.abs(-3)",
+            Limits::default(),
+        );
+        assert_eq!(result.output, "X\n");
+        assert_eq!(result.result.unwrap(), "(2, 3)");
+    }
+
+    #[test]
+    fn reading_input_is_unsupported() {
+        check_error(
+            "io.stdin().read_line()",
+            Limits::default(),
+            PlaygroundErrorKind::Runtime,
+        );
+    }
+}
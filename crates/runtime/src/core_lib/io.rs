@@ -1,5 +1,9 @@
 //! The `io` core library module
 
+mod hash;
+mod prompt;
+
+use super::string::encoding;
 use crate::{derive::*, prelude::*, BufferedFile, Error, Ptr, Result};
 use std::{
     fmt, fs,
@@ -13,6 +17,12 @@ pub fn make_module() -> KMap {
 
     let result = KMap::with_type("core.io");
 
+    result.add_fn("confirm", |ctx| match ctx.args() {
+        [Str(message)] => prompt::confirm(ctx.vm, message, None),
+        [Str(message), Bool(default)] => prompt::confirm(ctx.vm, message, Some(*default)),
+        unexpected => type_error_with_slice("a message String, and an optional Bool", unexpected),
+    });
+
     result.add_fn("create", {
         move |ctx| match ctx.args() {
             [Str(path)] => {
@@ -61,6 +71,23 @@ pub fn make_module() -> KMap {
         ),
     });
 
+    result.add_fn("hash_file", |ctx| {
+        let (path, algorithm) = match ctx.args() {
+            [Str(path)] => (path, hash::DEFAULT_ALGORITHM),
+            [Str(path), Str(algorithm)] => (path, algorithm.as_str()),
+            unexpected => {
+                return type_error_with_slice(
+                    "a path String, and an optional algorithm name",
+                    unexpected,
+                )
+            }
+        };
+
+        hash::hash_file(Path::new(path.as_str()), algorithm)
+            .map(KValue::from)
+            .map_err(|error| error.with_prefix("io.hash_file"))
+    });
+
     result.add_fn("open", {
         |ctx| match ctx.args() {
             [Str(path)] => match fs::canonicalize(path.as_str()) {
@@ -105,14 +132,51 @@ pub fn make_module() -> KMap {
         result.map(|_| Null)
     });
 
-    result.add_fn("read_to_string", |ctx| match ctx.args() {
-        [Str(path)] => match fs::read_to_string(Path::new(path.as_str())) {
-            Ok(result) => Ok(result.into()),
-            Err(error) => {
-                runtime_error!("io.read_to_string: Unable to read file '{path}': {error}")
+    result.add_fn("prompt", |ctx| match ctx.args() {
+        [Str(message)] => prompt::prompt(ctx.vm, message, None),
+        [Str(message), Str(default)] => prompt::prompt(ctx.vm, message, Some(default)),
+        unexpected => type_error_with_slice(
+            "a message String, and an optional default String",
+            unexpected,
+        ),
+    });
+
+    result.add_fn("prompt_password", |ctx| match ctx.args() {
+        [Str(message)] => prompt::prompt_password(ctx.vm, message),
+        unexpected => type_error_with_slice("a message String", unexpected),
+    });
+
+    result.add_fn("read_to_string", |ctx| {
+        let (path, encoding) = match ctx.args() {
+            [Str(path)] => (path, None),
+            [Str(path), Str(encoding)] => (path, Some((encoding, None))),
+            [Str(path), Str(encoding), Str(mode)] => (path, Some((encoding, Some(mode)))),
+            unexpected => {
+                return type_error_with_slice(
+                    "a path String, and an optional encoding name and mode",
+                    unexpected,
+                )
             }
-        },
-        unexpected => type_error_with_slice("a path String as argument", unexpected),
+        };
+
+        let path_str = path.as_str();
+        match encoding {
+            None => match fs::read_to_string(Path::new(path_str)) {
+                Ok(result) => Ok(result.into()),
+                Err(error) => {
+                    runtime_error!("io.read_to_string: Unable to read file '{path}': {error}")
+                }
+            },
+            Some((encoding, mode)) => {
+                let (encoding, mode) = encoding::encoding_args(encoding, mode)?;
+                match fs::read(Path::new(path_str)) {
+                    Ok(bytes) => encoding::decode_bytes(&bytes, encoding, mode),
+                    Err(error) => {
+                        runtime_error!("io.read_to_string: Unable to read file '{path}': {error}")
+                    }
+                }
+            }
+        }
     });
 
     result.add_fn("remove_file", {
@@ -131,6 +195,24 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("select", |ctx| {
+        let (message, options) = match ctx.args() {
+            [KValue::List(options)] => (None, options.data().to_vec()),
+            [KValue::Tuple(options)] => (None, options.to_vec()),
+            [Str(message), KValue::List(options)] => {
+                (Some(message.clone()), options.data().to_vec())
+            }
+            [Str(message), KValue::Tuple(options)] => (Some(message.clone()), options.to_vec()),
+            unexpected => {
+                return type_error_with_slice(
+                    "an optional message String, and a List or Tuple of options",
+                    unexpected,
+                )
+            }
+        };
+        prompt::select(ctx.vm, message.as_deref(), &options)
+    });
+
     result.add_fn("stderr", |ctx| Ok(File::stderr(ctx.vm)));
     result.add_fn("stdin", |ctx| Ok(File::stdin(ctx.vm)));
     result.add_fn("stdout", |ctx| Ok(File::stdout(ctx.vm)));
@@ -139,6 +221,23 @@ pub fn make_module() -> KMap {
         |_| Ok(std::env::temp_dir().to_string_lossy().as_ref().into())
     });
 
+    result.add_fn("verify_checksums", |ctx| {
+        let (manifest, algorithm) = match ctx.args() {
+            [KValue::Map(manifest)] => (manifest, hash::DEFAULT_ALGORITHM),
+            [KValue::Map(manifest), Str(algorithm)] => (manifest, algorithm.as_str()),
+            unexpected => {
+                return type_error_with_slice(
+                    "a manifest Map, and an optional algorithm name",
+                    unexpected,
+                )
+            }
+        };
+
+        hash::verify_checksums(manifest, algorithm)
+            .map(KValue::from)
+            .map_err(|error| error.with_prefix("io.verify_checksums"))
+    });
+
     result
 }
 
@@ -156,6 +255,13 @@ impl File {
         Self(make_ptr!(BufferedSystemFile::new(file, path))).into()
     }
 
+    /// Returns the file that's wrapped by the File object
+    ///
+    /// This allows libraries to write to files that have been opened by a script.
+    pub fn inner(&self) -> &Ptr<dyn KotoFile> {
+        &self.0
+    }
+
     fn stderr(vm: &KotoVm) -> KValue {
         Self(vm.stderr().clone()).into()
     }
@@ -228,6 +334,18 @@ impl File {
         }
     }
 
+    #[koto_method]
+    fn write_bytes(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [iterable] if iterable.is_iterable() => {
+                let bytes =
+                    encoding::bytes_from_iterable(&mut ctx.vm.spawn_shared_vm(), iterable.clone())?;
+                ctx.instance_mut()?.0.write(&bytes).map(|_| KValue::Null)
+            }
+            unexpected => type_error_with_slice("an iterable of bytes", unexpected),
+        }
+    }
+
     #[koto_method]
     fn write_line(ctx: MethodContext<Self>) -> Result<KValue> {
         let mut display_context = DisplayContext::with_vm(ctx.vm);
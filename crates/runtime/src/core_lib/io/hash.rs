@@ -0,0 +1,90 @@
+//! File hashing helpers for the `io` module
+
+use crate::{prelude::*, Result};
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use std::{fs, io::Read, path::Path};
+
+/// The hashing algorithm that's used when none is specified
+pub const DEFAULT_ALGORITHM: &str = "sha256";
+
+// The size of the buffer that's used when streaming file data through a hasher
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// Returns the hex-encoded digest of the file's contents
+///
+/// The file is read in chunks, so large files don't need to be loaded into memory.
+pub fn hash_file(path: &Path, algorithm: &str) -> Result<String> {
+    match algorithm {
+        "md5" => hash_with::<Md5>(path),
+        "sha1" => hash_with::<Sha1>(path),
+        "sha256" => hash_with::<Sha256>(path),
+        "sha512" => hash_with::<Sha512>(path),
+        _ => runtime_error!(
+            "Unsupported hashing algorithm '{algorithm}', \
+             expected 'md5', 'sha1', 'sha256', or 'sha512'"
+        ),
+    }
+}
+
+/// Checks the files in the manifest against their expected digests
+///
+/// The result contains an entry for each file that failed verification, mapping the file's path to
+/// its actual digest, or to `null` if the file couldn't be read.
+pub fn verify_checksums(manifest: &KMap, algorithm: &str) -> Result<KMap> {
+    let result = KMap::new();
+
+    for (path, expected) in manifest.data().iter() {
+        let (KValue::Str(path_string), KValue::Str(expected)) = (path.value(), expected) else {
+            return type_error(
+                "a map of path Strings to digest Strings",
+                &KValue::Tuple(vec![path.value().clone(), expected.clone()].into()),
+            );
+        };
+
+        let path = Path::new(path_string.as_str());
+        if !path.is_file() {
+            result.insert(path_string.clone(), KValue::Null);
+            continue;
+        }
+
+        let digest = hash_file(path, algorithm)?;
+        if !digest.eq_ignore_ascii_case(expected.trim()) {
+            result.insert(path_string.clone(), digest);
+        }
+    }
+
+    Ok(result)
+}
+
+fn hash_with<D: Digest>(path: &Path) -> Result<String> {
+    let mut file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(error) => {
+            return runtime_error!("Unable to open file '{}': {error}", path.to_string_lossy())
+        }
+    };
+
+    let mut hasher = D::new();
+    let mut buffer = vec![0; BUFFER_SIZE];
+    loop {
+        match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => hasher.update(&buffer[..n]),
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(error) => {
+                return runtime_error!(
+                    "Error while reading file '{}': {error}",
+                    path.to_string_lossy()
+                )
+            }
+        }
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
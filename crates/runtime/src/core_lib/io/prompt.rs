@@ -0,0 +1,162 @@
+//! Interactive input helpers for the `io` module
+
+use crate::{prelude::*, KeyInput, Result};
+
+/// Shows a prompt and returns the line of input that was entered
+///
+/// The default is returned if the input is empty, and `null` is returned if the end of the input
+/// has been reached.
+pub fn prompt(vm: &KotoVm, message: &str, default: Option<&KString>) -> Result<KValue> {
+    write_prompt(vm, message)?;
+    let result = match trim_line(vm.stdin().read_line()?) {
+        Some(line) if line.is_empty() => default.cloned().map(KValue::from),
+        Some(line) => Some(line.into()),
+        None => default.cloned().map(KValue::from),
+    };
+    Ok(result.unwrap_or_default())
+}
+
+/// Shows a prompt and returns the line of input that was entered, without echoing the input
+pub fn prompt_password(vm: &KotoVm, message: &str) -> Result<KValue> {
+    write_prompt(vm, message)?;
+    let stdin = vm.stdin();
+    let line = if stdin.is_terminal() {
+        let line = stdin.read_line_hidden()?;
+        // The hidden input doesn't include the user's newline
+        vm.stdout().write_line("")?;
+        line
+    } else {
+        stdin.read_line()?
+    };
+    Ok(trim_line(line).map(KValue::from).unwrap_or_default())
+}
+
+/// Asks a yes/no question, repeating the question until a valid answer is given
+///
+/// If the input is empty, or if the end of the input has been reached, then the default is
+/// returned, or `false` if no default was provided.
+pub fn confirm(vm: &KotoVm, message: &str, default: Option<bool>) -> Result<KValue> {
+    let options = match default {
+        Some(true) => "[Y/n]",
+        Some(false) => "[y/N]",
+        None => "[y/n]",
+    };
+
+    loop {
+        write_prompt(vm, &format!("{message} {options} "))?;
+        let Some(line) = trim_line(vm.stdin().read_line()?) else {
+            return Ok(default.unwrap_or(false).into());
+        };
+        match line.trim().to_lowercase().as_str() {
+            "y" | "yes" => return Ok(true.into()),
+            "n" | "no" => return Ok(false.into()),
+            "" if default.is_some() => return Ok(default.unwrap_or(false).into()),
+            _ => vm.stdout().write_line("Please answer 'y' or 'n'.")?,
+        }
+    }
+}
+
+/// Asks the user to select one of the given options, returning the selected option
+///
+/// When stdin is a terminal then the selection is made with the arrow keys, otherwise the options
+/// are numbered and the selection is made by entering a number.
+///
+/// `null` is returned if the selection is cancelled, or if the end of the input has been reached.
+pub fn select(vm: &mut KotoVm, message: Option<&str>, options: &[KValue]) -> Result<KValue> {
+    if options.is_empty() {
+        return runtime_error!("at least one option is required");
+    }
+
+    let mut labels = Vec::with_capacity(options.len());
+    for option in options {
+        match vm.run_unary_op(UnaryOp::Display, option.clone())? {
+            KValue::Str(label) => labels.push(label),
+            unexpected => return type_error("a String from @display", &unexpected),
+        }
+    }
+
+    if let Some(message) = message {
+        vm.stdout().write_line(message)?;
+    }
+
+    let selected = if vm.stdin().is_terminal() {
+        select_with_keys(vm, &labels)?
+    } else {
+        select_with_numbers(vm, &labels)?
+    };
+
+    Ok(selected
+        .map(|index| options[index].clone())
+        .unwrap_or_default())
+}
+
+fn select_with_keys(vm: &KotoVm, labels: &[KString]) -> Result<Option<usize>> {
+    let stdout = vm.stdout();
+    let mut selected = 0;
+
+    let render = |selected: usize| -> Result<()> {
+        for (i, label) in labels.iter().enumerate() {
+            let marker = if i == selected { '>' } else { ' ' };
+            // Clear the line before writing the option
+            stdout.write_line(&format!("\r\x1b[2K{marker} {label}"))?;
+        }
+        stdout.flush()
+    };
+
+    render(selected)?;
+    loop {
+        match vm.stdin().read_key()? {
+            KeyInput::Up => selected = (selected + labels.len() - 1) % labels.len(),
+            KeyInput::Down => selected = (selected + 1) % labels.len(),
+            KeyInput::Enter => return Ok(Some(selected)),
+            KeyInput::Cancel => return Ok(None),
+            KeyInput::Other => continue,
+        }
+        // Move the cursor back to the first option and redraw
+        stdout.write(format!("\x1b[{}A", labels.len()).as_bytes())?;
+        render(selected)?;
+    }
+}
+
+fn select_with_numbers(vm: &KotoVm, labels: &[KString]) -> Result<Option<usize>> {
+    for (i, label) in labels.iter().enumerate() {
+        vm.stdout().write_line(&format!("  {}) {label}", i + 1))?;
+    }
+
+    loop {
+        write_prompt(vm, &format!("Select an option (1-{}): ", labels.len()))?;
+        let Some(line) = trim_line(vm.stdin().read_line()?) else {
+            return Ok(None);
+        };
+        match line.trim().parse::<usize>() {
+            Ok(n) if (1..=labels.len()).contains(&n) => return Ok(Some(n - 1)),
+            _ => vm.stdout().write_line(&format!(
+                "Please enter a number between 1 and {}.",
+                labels.len()
+            ))?,
+        }
+    }
+}
+
+fn write_prompt(vm: &KotoVm, message: &str) -> Result<()> {
+    let stdout = vm.stdout();
+    stdout.write(message.as_bytes())?;
+    stdout.flush()
+}
+
+// Removes the line's newline, with `None` being returned when the end of the input was reached
+fn trim_line(line: Option<String>) -> Option<String> {
+    match line {
+        Some(line) if line.is_empty() => None,
+        Some(mut line) => {
+            if line.ends_with('\n') {
+                line.pop();
+                if line.ends_with('\r') {
+                    line.pop();
+                }
+            }
+            Some(line)
+        }
+        None => None,
+    }
+}
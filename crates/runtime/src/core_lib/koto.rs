@@ -1,5 +1,12 @@
 //! The `koto` core library module
 
+pub mod host;
+mod rate_limiter;
+mod result;
+
+pub use rate_limiter::RateLimiter;
+pub use result::KResult;
+
 use crate::prelude::*;
 use crate::Result;
 use koto_bytecode::CompilerSettings;
@@ -33,8 +40,21 @@ pub fn make_module() -> KMap {
         unexpected => type_error_with_slice("a single argument", unexpected),
     });
 
+    result.add_fn("err", |ctx| match ctx.args() {
+        [error] => Ok(KResult::err(error.clone()).into()),
+        unexpected => type_error_with_slice("a single argument", unexpected),
+    });
+
+    result.add_fn("expect", |ctx| match ctx.args() {
+        [KValue::Null, KValue::Str(message)] => runtime_error!("{message}"),
+        [value, KValue::Str(_)] => Ok(value.clone()),
+        unexpected => type_error_with_slice("a value and a message String", unexpected),
+    });
+
     result.add_fn("exports", |ctx| Ok(KValue::Map(ctx.vm.exports().clone())));
 
+    result.insert("host", host::make_module());
+
     result.add_fn("hash", |ctx| match ctx.args() {
         [value] => match ValueKey::try_from(value.clone()) {
             Ok(key) => {
@@ -47,6 +67,25 @@ pub fn make_module() -> KMap {
         unexpected => type_error_with_slice("a single argument", unexpected),
     });
 
+    result.add_fn("map_null", |ctx| match ctx.args() {
+        [KValue::Null, f] if f.is_callable() => Ok(KValue::Null),
+        [value, f] if f.is_callable() => {
+            let (value, f) = (value.clone(), f.clone());
+            ctx.vm.call_function(f, value)
+        }
+        unexpected => type_error_with_slice("a value and a callable function", unexpected),
+    });
+
+    result.add_fn("ok", |ctx| match ctx.args() {
+        [] => Ok(KResult::ok(KValue::Null).into()),
+        [value] => Ok(KResult::ok(value.clone()).into()),
+        unexpected => type_error_with_slice("an optional value", unexpected),
+    });
+
+    result.add_fn("rate_limiter", |ctx| {
+        RateLimiter::from_args(ctx.args()).map(KValue::from)
+    });
+
     result.insert("script_dir", KValue::Null);
     result.insert("script_path", KValue::Null);
 
@@ -60,6 +99,12 @@ pub fn make_module() -> KMap {
         unexpected => type_error_with_slice("a single argument", unexpected),
     });
 
+    result.add_fn("unwrap_or", |ctx| match ctx.args() {
+        [KValue::Null, default] => Ok(default.clone()),
+        [value, _] => Ok(value.clone()),
+        unexpected => type_error_with_slice("a value and a default value", unexpected),
+    });
+
     result.add_fn("load", |ctx| match ctx.args() {
         [KValue::Str(s)] => Ok(try_load_koto_script(ctx, s)?.into()),
         unexpected => type_error_with_slice("a single String", unexpected),
@@ -0,0 +1,55 @@
+//! The `koto.host` module, used by scripts to exchange events with the host application
+
+use crate::{prelude::*, Result};
+
+/// Initializes the `koto.host` module
+///
+/// See [crate::HostEvents].
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("core.koto.host");
+
+    result.add_fn("emit", |ctx| {
+        let (name, data) = match ctx.args() {
+            [KValue::Str(name)] => (name.clone(), KValue::Null),
+            [KValue::Str(name), data] => (name.clone(), data.clone()),
+            unexpected => {
+                return type_error_with_slice("an event name, with optional data", unexpected)
+            }
+        };
+        let host_events = ctx.vm.host_events().clone();
+        host_events.emit(name, data)
+    });
+
+    result.add_fn("has_callback", |ctx| match ctx.args() {
+        [KValue::Str(name)] => Ok(ctx.vm.host_events().has_callback(name).into()),
+        unexpected => type_error_with_slice("an event name", unexpected),
+    });
+
+    result.add_fn("off", |ctx| match ctx.args() {
+        [KValue::Str(name)] => Ok(ctx.vm.host_events().remove_handlers(name).into()),
+        unexpected => type_error_with_slice("an event name", unexpected),
+    });
+
+    result.add_fn("on", |ctx| match ctx.args() {
+        [KValue::Str(name), handler] if handler.is_callable() => {
+            ctx.vm
+                .host_events()
+                .add_handler(name.clone(), handler.clone());
+            Ok(KValue::Null)
+        }
+        unexpected => type_error_with_slice("an event name and a handler Function", unexpected),
+    });
+
+    result.add_fn("process_events", |ctx| match ctx.args() {
+        [] => process_events(ctx),
+        unexpected => type_error_with_slice("no arguments", unexpected),
+    });
+
+    result
+}
+
+fn process_events(ctx: &mut CallContext) -> Result<KValue> {
+    // A shared VM is used so that the handlers don't interfere with the calling VM's state
+    let count = ctx.vm.spawn_shared_vm().process_host_events()?;
+    Ok(count.into())
+}
@@ -0,0 +1,193 @@
+//! A token bucket rate limiter, used by `koto.rate_limiter`
+
+use crate::{derive::*, prelude::*, PtrMut, Result};
+use instant::Instant;
+
+/// A rate limiter that uses a token bucket
+///
+/// The bucket starts full, and is refilled at a constant rate up to its capacity.
+/// Each call to `acquire` takes tokens from the bucket, waiting for the bucket to refill when
+/// there aren't enough tokens available.
+#[derive(Clone, KotoType)]
+pub struct RateLimiter(PtrMut<TokenBucket>);
+
+#[derive(Clone)]
+struct TokenBucket {
+    // The number of tokens added per second
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    last_refill: Instant,
+    blocking: bool,
+}
+
+impl TokenBucket {
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    // Returns the number of seconds until the requested number of tokens are available
+    fn wait_time(&self, tokens: f64) -> f64 {
+        ((tokens - self.tokens) / self.rate).max(0.0)
+    }
+}
+
+#[koto_impl(runtime = crate)]
+impl RateLimiter {
+    /// Makes a new rate limiter
+    ///
+    /// `per_second` is the rate at which tokens are added to the bucket, and `burst` is the
+    /// bucket's capacity. In non-blocking mode, `acquire` returns the time to wait rather than
+    /// waiting for tokens to become available.
+    pub fn new(per_second: f64, burst: f64, blocking: bool) -> Result<Self> {
+        if !(per_second > 0.0 && per_second.is_finite()) {
+            return runtime_error!("expected a positive rate, found {per_second}");
+        }
+        if !(burst >= 1.0 && burst.is_finite()) {
+            return runtime_error!("expected a burst size of at least 1, found {burst}");
+        }
+
+        Ok(Self(PtrMut::from(TokenBucket {
+            rate: per_second,
+            capacity: burst,
+            tokens: burst,
+            last_refill: Instant::now(),
+            blocking,
+        })))
+    }
+
+    /// Makes a new rate limiter from the arguments passed to `koto.rate_limiter`
+    pub fn from_args(args: &[KValue]) -> Result<Self> {
+        use KValue::*;
+
+        match args {
+            [Number(per_second)] => {
+                let per_second = f64::from(per_second);
+                Self::new(per_second, per_second.max(1.0), true)
+            }
+            [Map(options)] => {
+                let per_second = match options.get("per_second") {
+                    Some(Number(n)) => f64::from(n),
+                    Some(unexpected) => return type_error("a Number as per_second", &unexpected),
+                    None => return runtime_error!("missing 'per_second' option"),
+                };
+                let burst = match options.get("burst") {
+                    Some(Number(n)) => f64::from(n),
+                    Some(Null) | None => per_second.max(1.0),
+                    Some(unexpected) => return type_error("a Number as burst", &unexpected),
+                };
+                let blocking = match options.get("blocking") {
+                    Some(Bool(b)) => b,
+                    Some(Null) | None => true,
+                    Some(unexpected) => return type_error("a Bool as blocking", &unexpected),
+                };
+                Self::new(per_second, burst, blocking)
+            }
+            unexpected => {
+                type_error_with_slice("a Number of requests per second, or a Map", unexpected)
+            }
+        }
+    }
+
+    /// Takes tokens from the bucket, returning the number of seconds to wait
+    ///
+    /// In blocking mode, the rate limiter waits until the tokens are available, and the time
+    /// spent waiting is returned. In non-blocking mode, the tokens are only taken if they're
+    /// available, and the time to wait until they become available is returned.
+    pub fn acquire_tokens(&self, tokens: f64) -> Result<f64> {
+        let mut bucket = self.0.borrow_mut();
+        if tokens > bucket.capacity {
+            return runtime_error!(
+                "unable to acquire {tokens} tokens, the burst size is {}",
+                bucket.capacity
+            );
+        }
+
+        bucket.refill();
+        let wait = bucket.wait_time(tokens);
+
+        if wait > 0.0 {
+            if !bucket.blocking {
+                return Ok(wait);
+            }
+            sleep(wait)?;
+            bucket.refill();
+        }
+
+        bucket.tokens = (bucket.tokens - tokens).max(0.0);
+        Ok(wait)
+    }
+
+    /// Takes tokens from the bucket if they're available, returning true if they were taken
+    pub fn try_acquire_tokens(&self, tokens: f64) -> bool {
+        let mut bucket = self.0.borrow_mut();
+        bucket.refill();
+        if bucket.tokens >= tokens {
+            bucket.tokens -= tokens;
+            true
+        } else {
+            false
+        }
+    }
+
+    #[koto_method]
+    fn acquire(&self, args: &[KValue]) -> Result<KValue> {
+        let wait = self.acquire_tokens(tokens_from_args(args)?)?;
+        Ok(wait.into())
+    }
+
+    #[koto_method]
+    fn available(&self) -> KValue {
+        let mut bucket = self.0.borrow_mut();
+        bucket.refill();
+        bucket.tokens.into()
+    }
+
+    #[koto_method]
+    fn try_acquire(&self, args: &[KValue]) -> Result<KValue> {
+        Ok(self.try_acquire_tokens(tokens_from_args(args)?).into())
+    }
+}
+
+impl KotoCopy for RateLimiter {
+    fn copy(&self) -> KObject {
+        // Copies get their own bucket
+        Self(PtrMut::from(self.0.borrow().clone())).into()
+    }
+}
+
+impl KotoObject for RateLimiter {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        let rate = KNumber::from(self.0.borrow().rate);
+        ctx.append(format!("{}({rate}/s)", Self::type_static()));
+        Ok(())
+    }
+}
+
+impl From<RateLimiter> for KValue {
+    fn from(limiter: RateLimiter) -> Self {
+        KObject::from(limiter).into()
+    }
+}
+
+fn tokens_from_args(args: &[KValue]) -> Result<f64> {
+    match args {
+        [] => Ok(1.0),
+        [KValue::Number(n)] if f64::from(n) > 0.0 => Ok(n.into()),
+        unexpected => type_error_with_slice("an optional positive Number of tokens", unexpected),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn sleep(seconds: f64) -> Result<()> {
+    std::thread::sleep(std::time::Duration::from_secs_f64(seconds));
+    Ok(())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn sleep(_seconds: f64) -> Result<()> {
+    runtime_error!("blocking rate limiters aren't supported on this platform")
+}
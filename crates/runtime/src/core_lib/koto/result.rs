@@ -0,0 +1,184 @@
+//! The result type returned by `koto.ok` and `koto.err`
+
+use crate::{derive::*, prelude::*, Error, Ptr, Result};
+
+/// A result value, representing either success (`ok`) or failure (`err`)
+///
+/// Results are created in scripts with `ok(value)` and `err(error)`, and the `?` operator can
+/// be used to unwrap an `ok` value, or to return an `err` from the enclosing function.
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "Result")]
+pub struct KResult(Ptr<ResultInner>);
+
+enum ResultInner {
+    Ok(KValue),
+    Err(KValue),
+}
+
+#[koto_impl(runtime = crate)]
+impl KResult {
+    /// Makes a successful result containing the given value
+    pub fn ok(value: KValue) -> Self {
+        Self(ResultInner::Ok(value).into())
+    }
+
+    /// Makes a failed result containing the given error
+    pub fn err(error: KValue) -> Self {
+        Self(ResultInner::Err(error).into())
+    }
+
+    /// Returns the result's contents as a Rust [Result](std::result::Result)
+    pub fn as_result(&self) -> std::result::Result<&KValue, &KValue> {
+        match &*self.0 {
+            ResultInner::Ok(value) => Ok(value),
+            ResultInner::Err(error) => Err(error),
+        }
+    }
+
+    #[koto_method]
+    fn is_ok(&self) -> KValue {
+        self.as_result().is_ok().into()
+    }
+
+    #[koto_method]
+    fn is_err(&self) -> KValue {
+        self.as_result().is_err().into()
+    }
+
+    #[koto_method]
+    fn and_then(ctx: MethodContext<Self>) -> Result<KValue> {
+        let f = single_function_arg(ctx.args)?;
+        let this = ctx.instance()?;
+        match &*this.0 {
+            ResultInner::Ok(value) => {
+                match ctx.vm.spawn_shared_vm().call_function(f, value.clone())? {
+                    KValue::Object(o) if o.is_a::<Self>() => Ok(o.into()),
+                    unexpected => type_error("a Result from the function", &unexpected),
+                }
+            }
+            ResultInner::Err(_) => ctx.instance_result(),
+        }
+    }
+
+    #[koto_method]
+    fn map(ctx: MethodContext<Self>) -> Result<KValue> {
+        let f = single_function_arg(ctx.args)?;
+        let this = ctx.instance()?;
+        match &*this.0 {
+            ResultInner::Ok(value) => {
+                let mapped = ctx.vm.spawn_shared_vm().call_function(f, value.clone())?;
+                Ok(Self::ok(mapped).into())
+            }
+            ResultInner::Err(_) => ctx.instance_result(),
+        }
+    }
+
+    #[koto_method]
+    fn map_err(ctx: MethodContext<Self>) -> Result<KValue> {
+        let f = single_function_arg(ctx.args)?;
+        let this = ctx.instance()?;
+        match &*this.0 {
+            ResultInner::Ok(_) => ctx.instance_result(),
+            ResultInner::Err(error) => {
+                let mapped = ctx.vm.spawn_shared_vm().call_function(f, error.clone())?;
+                Ok(Self::err(mapped).into())
+            }
+        }
+    }
+
+    #[koto_method]
+    fn unwrap(ctx: MethodContext<Self>) -> Result<KValue> {
+        let this = ctx.instance()?;
+        match &*this.0 {
+            ResultInner::Ok(value) => Ok(value.clone()),
+            ResultInner::Err(error) => match error {
+                // Errors that can be thrown are rethrown as they are
+                KValue::Str(_) | KValue::Object(_) | KValue::Map(_) => Err(Error::from_koto_value(
+                    error.clone(),
+                    ctx.vm.spawn_shared_vm(),
+                )),
+                _ => {
+                    let error = ctx.vm.spawn_shared_vm().value_to_string(error)?;
+                    runtime_error!("unwrap called on err({error})")
+                }
+            },
+        }
+    }
+
+    #[koto_method]
+    fn unwrap_err(ctx: MethodContext<Self>) -> Result<KValue> {
+        let this = ctx.instance()?;
+        match &*this.0 {
+            ResultInner::Ok(value) => {
+                let value = ctx.vm.spawn_shared_vm().value_to_string(value)?;
+                runtime_error!("unwrap_err called on ok({value})")
+            }
+            ResultInner::Err(error) => Ok(error.clone()),
+        }
+    }
+
+    #[koto_method]
+    fn unwrap_or(ctx: MethodContext<Self>) -> Result<KValue> {
+        let default = match ctx.args {
+            [default] => default.clone(),
+            unexpected => return type_error_with_slice("a default value", unexpected),
+        };
+        match &*ctx.instance()?.0 {
+            ResultInner::Ok(value) => Ok(value.clone()),
+            ResultInner::Err(_) => Ok(default),
+        }
+    }
+
+    #[koto_method]
+    fn unwrap_or_else(ctx: MethodContext<Self>) -> Result<KValue> {
+        let f = single_function_arg(ctx.args)?;
+        let this = ctx.instance()?;
+        match &*this.0 {
+            ResultInner::Ok(value) => Ok(value.clone()),
+            ResultInner::Err(error) => ctx.vm.spawn_shared_vm().call_function(f, error.clone()),
+        }
+    }
+}
+
+impl KotoObject for KResult {
+    // Comparisons between results are performed by the runtime,
+    // so here the result is being compared with a value that isn't a result.
+    fn equal(&self, _rhs: &KValue) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn not_equal(&self, _rhs: &KValue) -> Result<bool> {
+        Ok(true)
+    }
+
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        let value = match self.as_result() {
+            Ok(value) => {
+                ctx.append("ok(");
+                value
+            }
+            Err(error) => {
+                ctx.append("err(");
+                error
+            }
+        };
+        ctx.push_container(Ptr::address(&self.0));
+        value.display(ctx)?;
+        ctx.pop_container();
+        ctx.append(')');
+        Ok(())
+    }
+}
+
+impl From<KResult> for KValue {
+    fn from(result: KResult) -> Self {
+        KObject::from(result).into()
+    }
+}
+
+fn single_function_arg(args: &[KValue]) -> Result<KValue> {
+    match args {
+        [f] if f.is_callable() => Ok(f.clone()),
+        unexpected => type_error_with_slice("a callable function", unexpected),
+    }
+}
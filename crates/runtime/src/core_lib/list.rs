@@ -2,15 +2,53 @@
 
 use super::{
     iterator::collect_pair,
-    value_sort::{compare_values, sort_values},
+    value_sort::{compare_values, sort_values, sort_values_by_key},
 };
-use crate::prelude::*;
+use crate::{prelude::*, Result};
 use std::{cmp::Ordering, ops::DerefMut};
 
 /// Initializes the `list` core library module
 pub fn make_module() -> KMap {
     let result = KMap::with_type("core.list");
 
+    result.add_fn("binary_search", |ctx| {
+        let expected_error = "a List, and either a Value or a comparison function";
+
+        // The search is performed on a copy of the list's data,
+        // so that comparisons that modify the list don't affect the search.
+        let (values, target) = match ctx.instance_and_args(is_list, expected_error)? {
+            (KValue::List(l), [target]) => (l.data().clone(), target.clone()),
+            (_, unexpected) => return type_error_with_slice(expected_error, unexpected),
+        };
+
+        let mut low = 0;
+        let mut high = values.len();
+
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let value = values[mid].clone();
+
+            let ordering = if target.is_callable() {
+                match ctx.vm.call_function(target.clone(), value)? {
+                    KValue::Number(n) => n.partial_cmp(&0.0).unwrap_or(Ordering::Equal),
+                    unexpected => {
+                        return type_error("a Number from the comparison function", &unexpected)
+                    }
+                }
+            } else {
+                compare_values(ctx.vm, &value, &target)?
+            };
+
+            match ordering {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return Ok(mid.into()),
+            }
+        }
+
+        Ok(KValue::Null)
+    });
+
     result.add_fn("clear", |ctx| {
         let expected_error = "a List";
 
@@ -52,6 +90,48 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("dedup", |ctx| {
+        let expected_error = "a List";
+
+        match ctx.instance_and_args(is_list, expected_error)? {
+            (KValue::List(l), []) => {
+                let l = l.clone();
+
+                // The comparisons are made using a copy of the list's data,
+                // so that equality ops that modify the list don't affect the result.
+                let values = l.data().clone();
+                let mut result = ValueVec::with_capacity(values.len());
+                for value in values {
+                    let is_duplicate = if let Some(previous) = result.last() {
+                        match ctx.vm.run_binary_op(
+                            BinaryOp::Equal,
+                            previous.clone(),
+                            value.clone(),
+                        )? {
+                            KValue::Bool(result) => result,
+                            unexpected => {
+                                return type_error(
+                                    "a Bool from the equality comparison",
+                                    &unexpected,
+                                )
+                            }
+                        }
+                    } else {
+                        false
+                    };
+
+                    if !is_duplicate {
+                        result.push(value);
+                    }
+                }
+                *l.data_mut() = result;
+
+                Ok(KValue::List(l))
+            }
+            (_, unexpected) => type_error_with_slice(expected_error, unexpected),
+        }
+    });
+
     result.add_fn("extend", |ctx| {
         let expected_error = "a List and iterable";
 
@@ -214,6 +294,18 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("reserve", |ctx| {
+        let expected_error = "a List and a non-negative Number";
+
+        match ctx.instance_and_args(is_list, expected_error)? {
+            (KValue::List(l), [KValue::Number(n)]) if *n >= 0.0 => {
+                reserve_capacity(l.data_mut().deref_mut(), n.into())?;
+                Ok(KValue::List(l.clone()))
+            }
+            (_, unexpected) => type_error_with_slice(expected_error, unexpected),
+        }
+    });
+
     result.add_fn("resize", |ctx| {
         let expected_error = "a List, a non-negative Number, and an optional Value";
 
@@ -355,44 +447,9 @@ pub fn make_module() -> KMap {
                 let l = l.clone();
                 let f = f.clone();
 
-                // apply function and construct a vec of (key, value)
-                let mut pairs = l
-                    .data()
-                    .iter()
-                    .map(
-                        |value| match ctx.vm.call_function(f.clone(), value.clone()) {
-                            Ok(key) => Ok((key, value.clone())),
-                            Err(e) => Err(e),
-                        },
-                    )
-                    .collect::<Result<Vec<_>, _>>()?;
-
-                let mut error = None;
-
-                // sort array by key (i.e. from [key, value])
-                pairs.sort_by(|a, b| {
-                    if error.is_some() {
-                        return Ordering::Equal;
-                    }
-
-                    match compare_values(ctx.vm, &a.0, &b.0) {
-                        Ok(ordering) => ordering,
-                        Err(e) => {
-                            error.get_or_insert(e);
-                            Ordering::Equal
-                        }
-                    }
-                });
-
-                if let Some(error) = error {
-                    return Err(error);
-                }
-
-                // collect values
-                *l.data_mut() = pairs
-                    .iter()
-                    .map(|(_key, value)| value.clone())
-                    .collect::<_>();
+                // Clone the data so that the list is free to be accessed by the key function
+                let data = l.data().to_vec();
+                *l.data_mut() = sort_values_by_key(ctx.vm, &data, &f)?.into();
 
                 Ok(KValue::List(l))
             }
@@ -401,13 +458,26 @@ pub fn make_module() -> KMap {
     });
 
     result.add_fn("swap", |ctx| {
-        let expected_error = "two Lists";
+        let expected_error = "two Lists, or a List and two indices";
 
         match ctx.instance_and_args(is_list, expected_error)? {
             (KValue::List(a), [KValue::List(b)]) => {
                 std::mem::swap(a.data_mut().deref_mut(), b.data_mut().deref_mut());
                 Ok(KValue::Null)
             }
+            (KValue::List(l), [KValue::Number(i), KValue::Number(j)]) if *i >= 0.0 && *j >= 0.0 => {
+                let (i, j) = (usize::from(i), usize::from(j));
+                let len = l.len();
+                if i >= len || j >= len {
+                    return runtime_error!(
+                        "list.swap: Index out of bounds - \
+                         the indices are {i} and {j} but the List only has {len} elements"
+                    );
+                }
+
+                l.data_mut().swap(i, j);
+                Ok(KValue::Null)
+            }
             (_, unexpected) => type_error_with_slice(expected_error, unexpected),
         }
     });
@@ -442,9 +512,43 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("with_size", |ctx| match ctx.args() {
+        [KValue::Number(n), f] if *n >= 0.0 && f.is_callable() => {
+            let size = usize::from(n);
+            let f = f.clone();
+
+            let mut data = ValueVec::new();
+            reserve_capacity(&mut data, size)?;
+            for _ in 0..size {
+                data.push(ctx.vm.call_function(f.clone(), &[])?);
+            }
+
+            Ok(KValue::List(KList::with_data(data)))
+        }
+        [KValue::Number(n), value] if *n >= 0.0 => {
+            let size = usize::from(n);
+            let mut data = ValueVec::new();
+            reserve_capacity(&mut data, size)?;
+            data.resize(size, value.clone());
+            Ok(KValue::List(KList::with_data(data)))
+        }
+        unexpected => type_error_with_slice(
+            "a non-negative Number, and either a Value or a function",
+            unexpected,
+        ),
+    });
+
     result
 }
 
+// Reserves capacity for additional values, with an error being returned if the allocation fails
+fn reserve_capacity(data: &mut ValueVec, additional: usize) -> Result<()> {
+    if data.try_reserve(additional).is_err() {
+        return runtime_error!("unable to allocate memory for {additional} values");
+    }
+    Ok(())
+}
+
 fn is_list(value: &KValue) -> bool {
     matches!(value, KValue::List(_))
 }
@@ -13,6 +13,7 @@ pub fn make_module() -> KMap {
 
         match map_instance_and_args(ctx, expected_error)? {
             (KValue::Map(m), []) => {
+                ctx.vm.check_map_is_mutable(m)?;
                 m.data_mut().clear();
                 Ok(KValue::Map(m.clone()))
             }
@@ -37,6 +38,9 @@ pub fn make_module() -> KMap {
 
         match map_instance_and_args(ctx, expected_error)? {
             (KValue::Map(m), [KValue::Map(other)]) => {
+                for key in other.data().keys() {
+                    ctx.vm.check_map_insert(m, key)?;
+                }
                 m.data_mut().extend(
                     other
                         .data()
@@ -50,26 +54,25 @@ pub fn make_module() -> KMap {
                 let iterable = iterable.clone();
                 let iterator = ctx.vm.make_iterator(iterable)?;
 
-                {
-                    let mut map_data = m.data_mut();
-                    let (size_hint, _) = iterator.size_hint();
-                    map_data.reserve(size_hint);
-
-                    for output in iterator {
-                        use KIteratorOutput as Output;
-                        let (key, value) = match output {
-                            Output::ValuePair(key, value) => (key, value),
-                            Output::Value(KValue::Tuple(t)) if t.len() == 2 => {
-                                let key = t[0].clone();
-                                let value = t[1].clone();
-                                (key, value)
-                            }
-                            Output::Value(value) => (value, KValue::Null),
-                            Output::Error(error) => return Err(error),
-                        };
+                let (size_hint, _) = iterator.size_hint();
+                m.data_mut().reserve(size_hint);
+
+                for output in iterator {
+                    use KIteratorOutput as Output;
+                    let (key, value) = match output {
+                        Output::ValuePair(key, value) => (key, value),
+                        Output::Value(KValue::Tuple(t)) if t.len() == 2 => {
+                            let key = t[0].clone();
+                            let value = t[1].clone();
+                            (key, value)
+                        }
+                        Output::Value(value) => (value, KValue::Null),
+                        Output::Error(error) => return Err(error),
+                    };
 
-                        map_data.insert(ValueKey::try_from(key.clone())?, value);
-                    }
+                    let key = ValueKey::try_from(key.clone())?;
+                    ctx.vm.check_map_insert(&m, &key)?;
+                    m.data_mut().insert(key, value);
                 }
 
                 Ok(KValue::Map(m))
@@ -137,18 +140,18 @@ pub fn make_module() -> KMap {
         let expected_error = "a Map and key (with optional Value to insert)";
 
         match map_instance_and_args(ctx, expected_error)? {
-            (KValue::Map(m), [key]) => match m
-                .data_mut()
-                .insert(ValueKey::try_from(key.clone())?, KValue::Null)
-            {
-                Some(old_value) => Ok(old_value),
-                None => Ok(KValue::Null),
-            },
+            (KValue::Map(m), [key]) => {
+                let key = ValueKey::try_from(key.clone())?;
+                ctx.vm.check_map_insert(m, &key)?;
+                match m.data_mut().insert(key, KValue::Null) {
+                    Some(old_value) => Ok(old_value),
+                    None => Ok(KValue::Null),
+                }
+            }
             (KValue::Map(m), [key, value]) => {
-                match m
-                    .data_mut()
-                    .insert(ValueKey::try_from(key.clone())?, value.clone())
-                {
+                let key = ValueKey::try_from(key.clone())?;
+                ctx.vm.check_map_insert(m, &key)?;
+                match m.data_mut().insert(key, value.clone()) {
                     Some(old_value) => Ok(old_value),
                     None => Ok(KValue::Null),
                 }
@@ -183,6 +186,7 @@ pub fn make_module() -> KMap {
 
         match map_instance_and_args(ctx, expected_error)? {
             (KValue::Map(m), [key]) => {
+                ctx.vm.check_map_is_mutable(m)?;
                 match m.data_mut().shift_remove(&ValueKey::try_from(key.clone())?) {
                     Some(old_value) => Ok(old_value),
                     None => Ok(KValue::Null),
@@ -197,6 +201,7 @@ pub fn make_module() -> KMap {
 
         match map_instance_and_args(ctx, expected_error)? {
             (KValue::Map(m), []) => {
+                ctx.vm.check_map_is_mutable(m)?;
                 let mut error = None;
                 m.data_mut().sort_by(|key_a, _, key_b, _| {
                     if error.is_some() {
@@ -220,6 +225,7 @@ pub fn make_module() -> KMap {
                 }
             }
             (KValue::Map(m), [f]) if f.is_callable() => {
+                ctx.vm.check_map_is_mutable(m)?;
                 let m = m.clone();
                 let f = f.clone();
                 let mut error = None;
@@ -338,6 +344,7 @@ fn do_map_update(
     f: KValue,
     vm: &mut KotoVm,
 ) -> Result<KValue> {
+    vm.check_map_insert(&map, &key)?;
     if !map.data().contains_key(&key) {
         map.data_mut().insert(key.clone(), default);
     }
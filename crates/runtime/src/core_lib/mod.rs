@@ -11,7 +11,7 @@ pub mod range;
 pub mod string;
 pub mod test;
 pub mod tuple;
-mod value_sort;
+pub mod value_sort;
 
 use crate::KMap;
 
@@ -59,6 +59,8 @@ impl CoreLib {
         default_import!("assert_near", test);
         default_import!("print", io);
         default_import!("copy", koto);
+        default_import!("err", koto);
+        default_import!("ok", koto);
         default_import!("size", koto);
         default_import!("type", koto);
 
@@ -1,6 +1,6 @@
 //! The `number` core library module
 
-use crate::prelude::*;
+use crate::{prelude::*, KIteratorOutput};
 
 /// Initializes the `number` core library module
 pub fn make_module() -> KMap {
@@ -43,26 +43,26 @@ pub fn make_module() -> KMap {
     macro_rules! bitwise_fn {
         ($name:ident, $op:tt) => {
             result.add_fn(stringify!($name), |ctx| {
-                use KNumber::I64;
-                let expected_error = "two Integers";
+                let expected_error = "two Numbers";
 
-                match ctx.instance_and_args(is_integer, expected_error)? {
-                    (Number(I64(a)), [Number(I64(b))]) => Ok((a $op b).into()),
+                match ctx.instance_and_args(is_number, expected_error)? {
+                    (Number(a), [Number(b)]) => Ok(Number(a $op b)),
                     (_, unexpected) => type_error_with_slice(expected_error, unexpected),
                 }
             })
         };
     }
 
-    macro_rules! bitwise_fn_positive_arg {
-        ($name:ident, $op:tt) => {
+    macro_rules! shift_fn {
+        ($name:ident) => {
             result.add_fn(stringify!($name), |ctx| {
-                use KNumber::I64;
+                let expected_error = "two Numbers (with non-negative second Number)";
 
-                let expected_error = "two Integers (with non-negative second Integer)";
-
-                match ctx.instance_and_args(is_integer, expected_error)? {
-                    (Number(I64(a)), [Number(I64(b))]) if *b >= 0 => Ok((a $op b).into()),
+                match ctx.instance_and_args(is_number, expected_error)? {
+                    (Number(a), args @ [Number(b)]) => match a.$name(*b) {
+                        Some(result) => Ok(Number(result)),
+                        None => type_error_with_slice(expected_error, args),
+                    },
                     (_, unexpected) => type_error_with_slice(expected_error, unexpected),
                 }
             })
@@ -108,10 +108,10 @@ pub fn make_module() -> KMap {
     number_f64_fn!(exp2);
 
     result.add_fn("flip_bits", |ctx| {
-        let expected_error = "an Integer";
+        let expected_error = "a Number";
 
-        match ctx.instance_and_args(is_integer, expected_error)? {
-            (Number(KNumber::I64(n)), []) => Ok((!n).into()),
+        match ctx.instance_and_args(is_number, expected_error)? {
+            (Number(n), []) => Ok(Number(!n)),
             (_, unexpected) => type_error_with_slice(expected_error, unexpected),
         }
     });
@@ -141,6 +141,36 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("linspace", |ctx| {
+        let expected_error = "two Numbers, and a non-negative Integer";
+
+        match ctx.instance_and_args(is_number, expected_error)? {
+            (Number(start), [Number(end), Number(count @ KNumber::I64(_))]) if *count >= 0 => {
+                let start = f64::from(start);
+                let end = f64::from(end);
+                let count = i64::from(count);
+                let step = if count > 1 {
+                    (end - start) / (count - 1) as f64
+                } else {
+                    0.0
+                };
+
+                let iter = (0..count).map(move |i| {
+                    // Ensure that the last value matches the end of the range exactly
+                    let n = if i == count - 1 && count > 1 {
+                        end
+                    } else {
+                        start + step * i as f64
+                    };
+                    KIteratorOutput::Value(n.into())
+                });
+
+                Ok(KIterator::with_std_iter(iter).into())
+            }
+            (_, unexpected) => type_error_with_slice(expected_error, unexpected),
+        }
+    });
+
     number_f64_fn!(ln);
     number_f64_fn!(log2);
     number_f64_fn!(log10);
@@ -168,6 +198,11 @@ pub fn make_module() -> KMap {
 
     bitwise_fn!(or, |);
 
+    result.add_fn("parse_human_size", |ctx| match ctx.args() {
+        [KValue::Str(s)] => Ok(parse_human_size(s).map_or(KValue::Null, KValue::from)),
+        unexpected => type_error_with_slice("a String", unexpected),
+    });
+
     result.insert("pi", std::f64::consts::PI);
     result.insert("pi_2", std::f64::consts::FRAC_PI_2);
     result.insert("pi_4", std::f64::consts::FRAC_PI_4);
@@ -185,8 +220,8 @@ pub fn make_module() -> KMap {
     number_f64_fn!(recip);
     number_fn!(round);
 
-    bitwise_fn_positive_arg!(shift_left, <<);
-    bitwise_fn_positive_arg!(shift_right, >>);
+    shift_fn!(shift_left);
+    shift_fn!(shift_right);
 
     number_f64_fn!(sin);
     number_f64_fn!(sinh);
@@ -205,6 +240,26 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("to_human_size", |ctx| {
+        let expected_error = "a Number, and an optional 'binary' or 'decimal' units String";
+
+        let (n, binary) = match ctx.instance_and_args(is_number, expected_error)? {
+            (Number(n), []) => (n, true),
+            (Number(n), [KValue::Str(units)]) => match units.as_str() {
+                "binary" => (n, true),
+                "decimal" => (n, false),
+                other => {
+                    return runtime_error!(
+                        "Expected 'binary' or 'decimal' as the size units, found '{other}'"
+                    )
+                }
+            },
+            (_, unexpected) => return type_error_with_slice(expected_error, unexpected),
+        };
+
+        Ok(format_human_size(n.into(), binary).into())
+    });
+
     result.add_fn("to_int", |ctx| {
         let expected_error = "a Number";
 
@@ -219,10 +274,63 @@ pub fn make_module() -> KMap {
     result
 }
 
-fn is_number(value: &KValue) -> bool {
-    matches!(value, KValue::Number(_))
+const DECIMAL_SIZE_UNITS: [&str; 7] = ["B", "KB", "MB", "GB", "TB", "PB", "EB"];
+const BINARY_SIZE_UNITS: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+// Formats a number of bytes as a size string like '1.5KiB'
+fn format_human_size(bytes: f64, binary: bool) -> String {
+    let (base, units) = if binary {
+        (1024.0, BINARY_SIZE_UNITS)
+    } else {
+        (1000.0, DECIMAL_SIZE_UNITS)
+    };
+
+    let mut size = bytes;
+    let mut unit_index = 0;
+    while size.abs() >= base && unit_index < units.len() - 1 {
+        size /= base;
+        unit_index += 1;
+    }
+
+    let unit = units[unit_index];
+    let rounded = (size * 10.0).round() / 10.0;
+    if unit_index == 0 || rounded.fract() == 0.0 {
+        format!("{rounded:.0}{unit}")
+    } else {
+        format!("{rounded:.1}{unit}")
+    }
+}
+
+// Parses a size string like '4.5GiB' or '100 MB' into a number of bytes
+fn parse_human_size(input: &str) -> Option<i64> {
+    let input = input.trim();
+    let number_end = input
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(input.len());
+    let amount = input[..number_end].parse::<f64>().ok()?;
+    let unit = input[number_end..].trim_start();
+
+    let multiplier = if unit.is_empty() {
+        1.0
+    } else {
+        let find_unit = |units: [&str; 7]| {
+            units
+                .iter()
+                .position(|u| u.eq_ignore_ascii_case(unit))
+                .map(|i| i as i32)
+        };
+        if let Some(exponent) = find_unit(DECIMAL_SIZE_UNITS) {
+            1000f64.powi(exponent)
+        } else if let Some(exponent) = find_unit(BINARY_SIZE_UNITS) {
+            1024f64.powi(exponent)
+        } else {
+            return None;
+        }
+    };
+
+    Some((amount * multiplier).round() as i64)
 }
 
-fn is_integer(value: &KValue) -> bool {
-    matches!(value, KValue::Number(KNumber::I64(_)))
+fn is_number(value: &KValue) -> bool {
+    matches!(value, KValue::Number(_))
 }
@@ -1,6 +1,12 @@
 //! The `os` core library module
 
-use crate::{derive::*, prelude::*, Result};
+#[cfg(all(feature = "desktop", not(target_arch = "wasm32")))]
+mod desktop;
+mod dotenv;
+mod process;
+mod system;
+
+use crate::{derive::*, prelude::*, Result, Signal};
 use chrono::prelude::*;
 use instant::Instant;
 
@@ -10,10 +16,155 @@ pub fn make_module() -> KMap {
 
     let result = KMap::with_type("core.os");
 
+    process::init_start_time();
+
+    #[cfg(all(feature = "desktop", not(target_arch = "wasm32")))]
+    {
+        result.add_fn("clipboard_get", |ctx| match ctx.args() {
+            [] => desktop::clipboard_get(),
+            unexpected => type_error_with_slice("no arguments", unexpected),
+        });
+
+        result.add_fn("clipboard_set", |ctx| match ctx.args() {
+            [KValue::Str(text)] => desktop::clipboard_set(text),
+            unexpected => type_error_with_slice("a String", unexpected),
+        });
+    }
+
+    result.add_fn("duration", |ctx| match ctx.args() {
+        [Number(seconds)] => Ok(Duration::from_seconds(seconds.into())),
+        [KValue::Str(s)] => Ok(Duration::from_seconds(parse_duration(s)?)),
+        unexpected => {
+            type_error_with_slice("a Number of seconds, or a duration String", unexpected)
+        }
+    });
+
+    result.add_fn("env", |ctx| match ctx.args() {
+        [] => {
+            let result = KMap::new();
+            for (key, value) in std::env::vars() {
+                result.insert(key.as_str(), value);
+            }
+            Ok(result.into())
+        }
+        [KValue::Str(name)] => match std::env::var(name.as_str()) {
+            Ok(value) => Ok(value.into()),
+            Err(_) => Ok(KValue::Null),
+        },
+        unexpected => type_error_with_slice("an optional variable name String", unexpected),
+    });
+
+    result.add_fn("load_dotenv", |ctx| {
+        let (path, inject) = match ctx.args() {
+            [KValue::Str(path)] => (path, false),
+            [KValue::Str(path), KValue::Bool(inject)] => (path, *inject),
+            unexpected => {
+                return type_error_with_slice("a path String, and an optional Bool", unexpected)
+            }
+        };
+
+        let contents = match std::fs::read_to_string(path.as_str()) {
+            Ok(contents) => contents,
+            Err(e) => return runtime_error!("Failed to read '{path}': {e}"),
+        };
+
+        let variables = match dotenv::parse(&contents) {
+            Ok(variables) => variables,
+            Err(e) => return runtime_error!("Failed to parse '{path}': {e}"),
+        };
+
+        let result = KMap::with_capacity(variables.len());
+        for (key, value) in variables {
+            // Variables that are already defined in the environment aren't overridden
+            if inject && std::env::var_os(&key).is_none() {
+                std::env::set_var(&key, &value);
+            }
+            result.insert(key.as_str(), value);
+        }
+
+        Ok(result.into())
+    });
+
     result.add_fn("name", |_| Ok(std::env::consts::OS.into()));
 
+    #[cfg(all(feature = "desktop", not(target_arch = "wasm32")))]
+    result.add_fn("notify", |ctx| match ctx.args() {
+        [KValue::Str(title), KValue::Str(body)] => desktop::notify(title, body),
+        unexpected => type_error_with_slice("a title String and a body String", unexpected),
+    });
+
+    result.add_fn("on_signal", |ctx| match ctx.args() {
+        [KValue::Str(name), handler] if handler.is_callable() => match Signal::from_name(name) {
+            Some(signal) => {
+                ctx.vm.add_signal_handler(signal, handler.clone());
+                Ok(KValue::Null)
+            }
+            None => runtime_error!("unknown signal '{name}'"),
+        },
+        unexpected => type_error_with_slice("a signal name and a handler Function", unexpected),
+    });
+
+    result.add_fn("pid", |_| pid());
+
+    result.add_fn("process_info", |ctx| match ctx.args() {
+        [] => {
+            let info = process::process_info();
+            let result = KMap::with_capacity(4);
+            result.insert("rss", info.rss.map_or(KValue::Null, KValue::from));
+            result.insert(
+                "cpu_time",
+                info.cpu_time.map_or(KValue::Null, Duration::from_seconds),
+            );
+            result.insert("fd_count", info.fd_count.map_or(KValue::Null, KValue::from));
+            result.insert(
+                "uptime",
+                info.uptime.map_or(KValue::Null, Duration::from_seconds),
+            );
+            Ok(result.into())
+        }
+        unexpected => type_error_with_slice("no arguments", unexpected),
+    });
+
     result.add_fn("start_timer", |_| Ok(Timer::now()));
 
+    result.add_fn("system_info", |ctx| {
+        let path = match ctx.args() {
+            [] => ".",
+            [KValue::Str(path)] => path.as_str(),
+            unexpected => return type_error_with_slice("an optional path String", unexpected),
+        };
+
+        let info = system::system_info(std::path::Path::new(path));
+
+        let disk = info.disk.map_or(KValue::Null, |disk| {
+            let result = KMap::with_capacity(2);
+            result.insert("total", disk.total);
+            result.insert("available", disk.available);
+            result.into()
+        });
+
+        let result = KMap::with_capacity(6);
+        result.insert(
+            "cpu_count",
+            info.cpu_count.map_or(KValue::Null, KValue::from),
+        );
+        result.insert(
+            "memory_total",
+            info.memory_total.map_or(KValue::Null, KValue::from),
+        );
+        result.insert(
+            "memory_available",
+            info.memory_available.map_or(KValue::Null, KValue::from),
+        );
+        result.insert("hostname", info.hostname.map_or(KValue::Null, KValue::from));
+        result.insert(
+            "os_version",
+            info.os_version.map_or(KValue::Null, KValue::from),
+        );
+        result.insert("disk", disk);
+        Ok(result.into())
+    });
+
     result.add_fn("time", |ctx| match ctx.args() {
         [] => Ok(DateTime::now()),
         [Number(seconds)] => DateTime::from_seconds(seconds.into(), None),
@@ -29,12 +180,32 @@ pub fn make_module() -> KMap {
     result
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+fn pid() -> Result<KValue> {
+    Ok(std::process::id().into())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn pid() -> Result<KValue> {
+    runtime_error!("os.pid is unsupported on this platform")
+}
+
 /// The underlying data type returned by `os.time()`
 #[derive(Clone, Debug, KotoCopy, KotoType)]
 pub struct DateTime(chrono::DateTime<FixedOffset>);
 
 #[koto_impl(runtime = crate)]
 impl DateTime {
+    /// Makes a DateTime from a chrono DateTime
+    pub fn from_chrono(time: chrono::DateTime<FixedOffset>) -> Self {
+        Self(time)
+    }
+
+    /// Returns the underlying chrono DateTime
+    pub fn inner(&self) -> chrono::DateTime<FixedOffset> {
+        self.0
+    }
+
     fn with_chrono_datetime(time: chrono::DateTime<FixedOffset>) -> KValue {
         KObject::from(Self(time)).into()
     }
@@ -122,6 +293,12 @@ impl KotoObject for DateTime {
     }
 }
 
+impl From<DateTime> for KValue {
+    fn from(time: DateTime) -> Self {
+        KObject::from(time).into()
+    }
+}
+
 /// The underlying data type returned by `os.start_timer()`
 #[derive(Clone, Debug, KotoCopy, KotoType)]
 pub struct Timer(Instant);
@@ -166,3 +343,193 @@ impl KotoObject for Timer {
         }
     }
 }
+
+/// The underlying data type returned by `os.duration()`
+#[derive(Clone, Copy, Debug, KotoCopy, KotoType)]
+#[koto(use_copy)]
+pub struct Duration(f64);
+
+#[koto_impl(runtime = crate)]
+impl Duration {
+    fn from_seconds(seconds: f64) -> KValue {
+        KObject::from(Self(seconds)).into()
+    }
+
+    fn seconds_from_value(value: &KValue) -> Result<f64> {
+        match value {
+            KValue::Object(o) if o.is_a::<Self>() => Ok(o.cast::<Self>()?.0),
+            unexpected => type_error(Self::type_static(), unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn days(&self) -> KValue {
+        (self.0 / SECONDS_PER_DAY).into()
+    }
+
+    #[koto_method]
+    fn hours(&self) -> KValue {
+        (self.0 / SECONDS_PER_HOUR).into()
+    }
+
+    #[koto_method]
+    fn minutes(&self) -> KValue {
+        (self.0 / SECONDS_PER_MINUTE).into()
+    }
+
+    #[koto_method]
+    fn seconds(&self) -> KValue {
+        self.0.into()
+    }
+
+    #[koto_method]
+    fn milliseconds(&self) -> KValue {
+        (self.0 * 1000.0).into()
+    }
+
+    #[koto_method]
+    fn format(&self) -> KValue {
+        format_duration(self.0).into()
+    }
+}
+
+impl KotoObject for Duration {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!("Duration({})", format_duration(self.0)));
+        Ok(())
+    }
+
+    fn negate(&self, _vm: &mut KotoVm) -> Result<KValue> {
+        Ok(Self::from_seconds(-self.0))
+    }
+
+    fn add(&self, rhs: &KValue) -> Result<KValue> {
+        Ok(Self::from_seconds(self.0 + Self::seconds_from_value(rhs)?))
+    }
+
+    fn subtract(&self, rhs: &KValue) -> Result<KValue> {
+        Ok(Self::from_seconds(self.0 - Self::seconds_from_value(rhs)?))
+    }
+
+    fn multiply(&self, rhs: &KValue) -> Result<KValue> {
+        match rhs {
+            KValue::Number(n) => Ok(Self::from_seconds(self.0 * f64::from(n))),
+            unexpected => type_error("a Number", unexpected),
+        }
+    }
+
+    fn divide(&self, rhs: &KValue) -> Result<KValue> {
+        match rhs {
+            KValue::Number(n) => Ok(Self::from_seconds(self.0 / f64::from(n))),
+            KValue::Object(o) if o.is_a::<Self>() => Ok((self.0 / o.cast::<Self>()?.0).into()),
+            unexpected => type_error("a Number or a Duration", unexpected),
+        }
+    }
+
+    fn less(&self, rhs: &KValue) -> Result<bool> {
+        Ok(self.0 < Self::seconds_from_value(rhs)?)
+    }
+
+    fn less_or_equal(&self, rhs: &KValue) -> Result<bool> {
+        Ok(self.0 <= Self::seconds_from_value(rhs)?)
+    }
+
+    fn greater(&self, rhs: &KValue) -> Result<bool> {
+        Ok(self.0 > Self::seconds_from_value(rhs)?)
+    }
+
+    fn greater_or_equal(&self, rhs: &KValue) -> Result<bool> {
+        Ok(self.0 >= Self::seconds_from_value(rhs)?)
+    }
+
+    fn equal(&self, rhs: &KValue) -> Result<bool> {
+        Ok(self.0 == Self::seconds_from_value(rhs)?)
+    }
+
+    fn not_equal(&self, rhs: &KValue) -> Result<bool> {
+        Ok(self.0 != Self::seconds_from_value(rhs)?)
+    }
+}
+
+const SECONDS_PER_MINUTE: f64 = 60.0;
+const SECONDS_PER_HOUR: f64 = 60.0 * SECONDS_PER_MINUTE;
+const SECONDS_PER_DAY: f64 = 24.0 * SECONDS_PER_HOUR;
+const SECONDS_PER_WEEK: f64 = 7.0 * SECONDS_PER_DAY;
+
+// Parses a duration string like '1h 20m', '1.5s', or '250ms' into seconds
+fn parse_duration(input: &str) -> Result<f64> {
+    let mut result = 0.0;
+    let mut remaining = input.trim_start();
+
+    if remaining.is_empty() {
+        return runtime_error!("Expected a duration, found an empty string");
+    }
+
+    while !remaining.is_empty() {
+        let number_end = remaining
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(remaining.len());
+        let Ok(amount) = remaining[..number_end].parse::<f64>() else {
+            return runtime_error!("Invalid duration '{input}'");
+        };
+        remaining = &remaining[number_end..];
+
+        let unit_end = remaining
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(remaining.len());
+        let unit_seconds = match &remaining[..unit_end] {
+            "w" => SECONDS_PER_WEEK,
+            "d" => SECONDS_PER_DAY,
+            "h" => SECONDS_PER_HOUR,
+            "m" => SECONDS_PER_MINUTE,
+            "s" => 1.0,
+            "ms" => 0.001,
+            "" => return runtime_error!("Missing unit in duration '{input}'"),
+            unit => return runtime_error!("Unknown unit '{unit}' in duration '{input}'"),
+        };
+        remaining = remaining[unit_end..].trim_start();
+
+        result += amount * unit_seconds;
+    }
+
+    Ok(result)
+}
+
+// Formats a number of seconds as a duration string like '1h 20m'
+fn format_duration(seconds: f64) -> String {
+    // Round to the nearest millisecond to avoid floating point noise in the output
+    let total_ms = (seconds.abs() * 1000.0).round() as u64;
+    let (days, remainder) = (total_ms / 86_400_000, total_ms % 86_400_000);
+    let (hours, remainder) = (remainder / 3_600_000, remainder % 3_600_000);
+    let (minutes, remainder) = (remainder / 60_000, remainder % 60_000);
+    let (whole_seconds, ms) = (remainder / 1000, remainder % 1000);
+
+    let mut parts = Vec::new();
+    if days > 0 {
+        parts.push(format!("{days}d"));
+    }
+    if hours > 0 {
+        parts.push(format!("{hours}h"));
+    }
+    if minutes > 0 {
+        parts.push(format!("{minutes}m"));
+    }
+    match (whole_seconds, ms) {
+        (0, 0) => {}
+        (0, ms) => parts.push(format!("{ms}ms")),
+        (whole_seconds, 0) => parts.push(format!("{whole_seconds}s")),
+        (whole_seconds, ms) => {
+            let fraction = format!("{ms:03}");
+            parts.push(format!(
+                "{whole_seconds}.{}s",
+                fraction.trim_end_matches('0')
+            ));
+        }
+    }
+
+    match parts.as_slice() {
+        [] => "0s".into(),
+        _ if seconds < 0.0 => format!("-{}", parts.join(" ")),
+        _ => parts.join(" "),
+    }
+}
@@ -0,0 +1,212 @@
+//! Desktop helpers for the `os` module, implemented with the platform's command line tools
+
+use crate::{prelude::*, Result};
+use std::{
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+
+// A command line tool that can be used to perform a desktop operation
+struct Tool {
+    program: &'static str,
+    args: Vec<String>,
+    env: Vec<(&'static str, String)>,
+}
+
+impl Tool {
+    fn new(program: &'static str, args: &[&str]) -> Self {
+        Self {
+            program,
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            env: Vec::new(),
+        }
+    }
+
+    #[cfg(windows)]
+    fn with_env(mut self, name: &'static str, value: &str) -> Self {
+        self.env.push((name, value.into()));
+        self
+    }
+}
+
+/// Returns the contents of the system clipboard
+pub fn clipboard_get() -> Result<KValue> {
+    let output = run_first_available(clipboard_get_tools(), None, "read the clipboard")?;
+    Ok(output.into())
+}
+
+/// Replaces the contents of the system clipboard with the given text
+pub fn clipboard_set(text: &str) -> Result<KValue> {
+    run_first_available(clipboard_set_tools(), Some(text), "set the clipboard")?;
+    Ok(KValue::Null)
+}
+
+/// Shows a desktop notification
+pub fn notify(title: &str, body: &str) -> Result<KValue> {
+    run_first_available(notify_tools(title, body), None, "show a notification")?;
+    Ok(KValue::Null)
+}
+
+#[cfg(target_os = "macos")]
+fn clipboard_get_tools() -> Vec<Tool> {
+    vec![Tool::new("pbpaste", &[])]
+}
+
+#[cfg(target_os = "macos")]
+fn clipboard_set_tools() -> Vec<Tool> {
+    vec![Tool::new("pbcopy", &[])]
+}
+
+#[cfg(target_os = "macos")]
+fn notify_tools(title: &str, body: &str) -> Vec<Tool> {
+    // The title and body are passed as arguments to the script to avoid the need for escaping
+    vec![Tool::new(
+        "osascript",
+        &[
+            "-e",
+            "on run argv",
+            "-e",
+            "display notification (item 2 of argv) with title (item 1 of argv)",
+            "-e",
+            "end run",
+            title,
+            body,
+        ],
+    )]
+}
+
+#[cfg(windows)]
+fn clipboard_get_tools() -> Vec<Tool> {
+    vec![Tool::new(
+        "powershell",
+        &["-NoProfile", "-Command", "Get-Clipboard -Raw"],
+    )]
+}
+
+#[cfg(windows)]
+fn clipboard_set_tools() -> Vec<Tool> {
+    vec![Tool::new(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-Command",
+            "$input | Out-String | Set-Clipboard",
+        ],
+    )]
+}
+
+#[cfg(windows)]
+fn notify_tools(title: &str, body: &str) -> Vec<Tool> {
+    // The title and body are passed via environment variables to avoid the need for escaping
+    let tool = Tool::new(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-Command",
+            "Add-Type -AssemblyName System.Windows.Forms; \
+             $n = New-Object System.Windows.Forms.NotifyIcon; \
+             $n.Icon = [System.Drawing.SystemIcons]::Information; \
+             $n.Visible = $true; \
+             $n.ShowBalloonTip(5000, $env:KOTO_NOTIFY_TITLE, $env:KOTO_NOTIFY_BODY, 'None'); \
+             Start-Sleep -Seconds 5; \
+             $n.Dispose()",
+        ],
+    );
+    vec![tool
+        .with_env("KOTO_NOTIFY_TITLE", title)
+        .with_env("KOTO_NOTIFY_BODY", body)]
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn clipboard_get_tools() -> Vec<Tool> {
+    with_wayland_first(
+        Tool::new("wl-paste", &["--no-newline"]),
+        [
+            Tool::new("xclip", &["-selection", "clipboard", "-out"]),
+            Tool::new("xsel", &["--clipboard", "--output"]),
+        ],
+    )
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn clipboard_set_tools() -> Vec<Tool> {
+    with_wayland_first(
+        Tool::new("wl-copy", &[]),
+        [
+            Tool::new("xclip", &["-selection", "clipboard", "-in"]),
+            Tool::new("xsel", &["--clipboard", "--input"]),
+        ],
+    )
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn notify_tools(title: &str, body: &str) -> Vec<Tool> {
+    vec![Tool::new("notify-send", &["--", title, body])]
+}
+
+// The Wayland tool is preferred when running in a Wayland session
+#[cfg(not(any(target_os = "macos", windows)))]
+fn with_wayland_first(wayland: Tool, x11: [Tool; 2]) -> Vec<Tool> {
+    let mut result = Vec::from(x11);
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        result.insert(0, wayland);
+    } else {
+        result.push(wayland);
+    }
+    result
+}
+
+// Runs the first tool that's available, returning its output
+fn run_first_available(tools: Vec<Tool>, input: Option<&str>, description: &str) -> Result<String> {
+    for tool in tools.iter() {
+        match run_tool(tool, input) {
+            Ok(output) => return Ok(output),
+            // Try the next tool if this one isn't installed
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                return runtime_error!("failed to {description} with '{}': {e}", tool.program)
+            }
+        }
+    }
+
+    let names = tools.iter().map(|tool| tool.program).collect::<Vec<_>>();
+    runtime_error!(
+        "failed to {description}, one of the following tools needs to be installed: {}",
+        names.join(", ")
+    )
+}
+
+fn run_tool(tool: &Tool, input: Option<&str>) -> io::Result<String> {
+    let mut command = Command::new(tool.program);
+    command
+        .args(&tool.args)
+        .envs(tool.env.iter().map(|(name, value)| (name, value)));
+
+    let Some(input) = input else {
+        let output = command.stdin(Stdio::null()).output()?;
+        return if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        } else {
+            let error = String::from_utf8_lossy(&output.stderr);
+            Err(io::Error::other(error.trim().to_string()))
+        };
+    };
+
+    // Tools that set the clipboard (e.g. xclip) can leave a background process running that
+    // serves the clipboard's contents, so their output isn't captured to avoid waiting for the
+    // background process to exit.
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if status.success() {
+        Ok(String::new())
+    } else {
+        Err(io::Error::other(status.to_string()))
+    }
+}
@@ -0,0 +1,137 @@
+//! A parser for `.env` files, used by `os.load_dotenv`
+
+use std::collections::HashMap;
+
+/// Parses the contents of a `.env` file into a list of key/value pairs
+///
+/// - Lines can optionally start with `export`.
+/// - Lines starting with `#` are comments, and unquoted values can be followed by a comment.
+/// - Values in single quotes are used as-is.
+/// - Values in double quotes support `\n`, `\t`, `\"`, and `\\` escapes, and can span
+///   multiple lines.
+/// - `${NAME}` in unquoted or double-quoted values is replaced with the value of a previously
+///   defined variable, falling back to the process's environment.
+pub fn parse(input: &str) -> Result<Vec<(String, String)>, String> {
+    let mut result = Vec::new();
+    let mut defined = HashMap::new();
+    let mut lines = input.lines().enumerate();
+
+    while let Some((i, line)) = lines.next() {
+        let line_number = i + 1;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").unwrap_or(line).trim_start();
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("Expected KEY=value on line {line_number}"));
+        };
+
+        let key = key.trim();
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+        {
+            return Err(format!(
+                "Invalid variable name '{key}' on line {line_number}"
+            ));
+        }
+
+        let value = value.trim_start();
+        let value = if let Some(quoted) = value.strip_prefix('\'') {
+            match quoted.find('\'') {
+                Some(end) => quoted[..end].to_string(),
+                None => return Err(format!("Unterminated single quote on line {line_number}")),
+            }
+        } else if let Some(quoted) = value.strip_prefix('"') {
+            let mut raw = quoted.to_string();
+            // Double-quoted values can span multiple lines
+            let raw = loop {
+                if let Some(end) = find_closing_quote(&raw) {
+                    raw.truncate(end);
+                    break raw;
+                }
+                match lines.next() {
+                    Some((_, next_line)) => {
+                        raw.push('\n');
+                        raw.push_str(next_line);
+                    }
+                    None => return Err(format!("Unterminated double quote on line {line_number}")),
+                }
+            };
+            expand_variables(&unescape(&raw), &defined)
+        } else {
+            let value = match value.find(" #") {
+                Some(comment_start) => &value[..comment_start],
+                None => value,
+            };
+            expand_variables(value.trim_end(), &defined)
+        };
+
+        defined.insert(key.to_string(), value.clone());
+        result.push((key.to_string(), value));
+    }
+
+    Ok(result)
+}
+
+fn find_closing_quote(s: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => return Some(i),
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
+fn unescape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('t') => result.push('\t'),
+                Some('r') => result.push('\r'),
+                Some(other) => result.push(other),
+                None => result.push('\\'),
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn expand_variables(s: &str, defined: &HashMap<String, String>) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut remaining = s;
+
+    while let Some(start) = remaining.find("${") {
+        result.push_str(&remaining[..start]);
+        let after = &remaining[start + 2..];
+        match after.find('}') {
+            Some(end) => {
+                let name = &after[..end];
+                match defined.get(name) {
+                    Some(value) => result.push_str(value),
+                    None => result.push_str(&std::env::var(name).unwrap_or_default()),
+                }
+                remaining = &after[end + 1..];
+            }
+            None => {
+                result.push_str(&remaining[start..]);
+                remaining = "";
+            }
+        }
+    }
+
+    result.push_str(remaining);
+    result
+}
@@ -0,0 +1,121 @@
+//! Resource usage information for the current process, used by `os.process_info`
+
+use instant::Instant;
+use std::sync::OnceLock;
+
+// The time at which the core library was first initialized
+//
+// Used as an approximation of the process's start time on platforms where it isn't available.
+static START_TIME: OnceLock<Instant> = OnceLock::new();
+
+/// Resource usage information for the current process
+///
+/// Values that aren't available on the current platform are set to `None`.
+#[derive(Clone, Debug, Default)]
+pub struct ProcessInfo {
+    /// The resident set size of the process, in bytes
+    pub rss: Option<u64>,
+    /// The CPU time used by the process in seconds, combining user and system time
+    pub cpu_time: Option<f64>,
+    /// The number of file descriptors that are open in the process
+    pub fd_count: Option<usize>,
+    /// The time in seconds since the process was started
+    pub uptime: Option<f64>,
+}
+
+/// Records the time at which the `os` module was initialized
+///
+/// Only the first call has an effect.
+pub fn init_start_time() {
+    START_TIME.get_or_init(Instant::now);
+}
+
+/// Gathers resource usage information for the current process
+pub fn process_info() -> ProcessInfo {
+    ProcessInfo {
+        rss: rss(),
+        cpu_time: cpu_time(),
+        fd_count: fd_count(),
+        uptime: uptime().or_else(|| START_TIME.get().map(|start| start.elapsed().as_secs_f64())),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn rss() -> Option<u64> {
+    // The second field in statm is the number of resident pages
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let resident_pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    Some(resident_pages * sysconf(libc::_SC_PAGESIZE)?)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn rss() -> Option<u64> {
+    None
+}
+
+#[cfg(unix)]
+fn cpu_time() -> Option<f64> {
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::uninit();
+    // Safety: getrusage initializes the provided struct when it returns successfully
+    let usage = unsafe {
+        if libc::getrusage(libc::RUSAGE_SELF, usage.as_mut_ptr()) != 0 {
+            return None;
+        }
+        usage.assume_init()
+    };
+
+    let seconds = |time: libc::timeval| time.tv_sec as f64 + time.tv_usec as f64 / 1_000_000.0;
+    Some(seconds(usage.ru_utime) + seconds(usage.ru_stime))
+}
+
+#[cfg(not(unix))]
+fn cpu_time() -> Option<f64> {
+    None
+}
+
+#[cfg(unix)]
+fn fd_count() -> Option<usize> {
+    let fd_dir = if cfg!(target_os = "linux") {
+        "/proc/self/fd"
+    } else {
+        "/dev/fd"
+    };
+    // Reading the directory opens a file descriptor, which is excluded from the count
+    let count = std::fs::read_dir(fd_dir).ok()?.count();
+    Some(count.saturating_sub(1))
+}
+
+#[cfg(not(unix))]
+fn fd_count() -> Option<usize> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn uptime() -> Option<f64> {
+    // The process's start time is the 22nd field in stat, measured in clock ticks since boot.
+    // The second field is the executable's name in parentheses, which can contain spaces,
+    // so the fields are counted from the closing parenthesis.
+    let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+    let (_, fields) = stat.rsplit_once(')')?;
+    let start_ticks: u64 = fields.split_whitespace().nth(19)?.parse().ok()?;
+    let start = start_ticks as f64 / sysconf(libc::_SC_CLK_TCK)? as f64;
+
+    let system_uptime = std::fs::read_to_string("/proc/uptime").ok()?;
+    let system_uptime: f64 = system_uptime.split_whitespace().next()?.parse().ok()?;
+
+    Some((system_uptime - start).max(0.0))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn uptime() -> Option<f64> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn sysconf(name: libc::c_int) -> Option<u64> {
+    // Safety: sysconf has no preconditions, and returns -1 for unsupported names
+    match unsafe { libc::sysconf(name) } {
+        value if value > 0 => Some(value as u64),
+        _ => None,
+    }
+}
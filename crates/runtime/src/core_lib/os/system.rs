@@ -0,0 +1,123 @@
+//! Information about the system that the program is running on, used by `os.system_info`
+
+use std::path::Path;
+
+/// Information about the system
+///
+/// Values that aren't available on the current platform are set to `None`.
+#[derive(Clone, Debug, Default)]
+pub struct SystemInfo {
+    /// The number of logical CPUs that are available to the process
+    pub cpu_count: Option<usize>,
+    /// The total amount of memory in bytes
+    pub memory_total: Option<u64>,
+    /// The amount of memory in bytes that's available for starting new programs
+    pub memory_available: Option<u64>,
+    /// The system's hostname
+    pub hostname: Option<String>,
+    /// The version of the operating system's kernel
+    pub os_version: Option<String>,
+    /// The disk usage of the filesystem containing the provided path
+    pub disk: Option<DiskUsage>,
+}
+
+/// Disk usage information for a filesystem
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DiskUsage {
+    /// The total size of the filesystem in bytes
+    pub total: u64,
+    /// The number of bytes that are available to unprivileged users
+    pub available: u64,
+}
+
+/// Gathers information about the system, with disk usage for the filesystem containing `path`
+pub fn system_info(path: &Path) -> SystemInfo {
+    let (memory_total, memory_available) = memory();
+    let (hostname, os_version) = uname();
+
+    SystemInfo {
+        cpu_count: std::thread::available_parallelism()
+            .ok()
+            .map(|count| count.get()),
+        memory_total,
+        memory_available,
+        hostname,
+        os_version,
+        disk: disk_usage(path),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn memory() -> (Option<u64>, Option<u64>) {
+    let Ok(meminfo) = std::fs::read_to_string("/proc/meminfo") else {
+        return (None, None);
+    };
+
+    // Entries in meminfo are in the form `MemTotal:  16323412 kB`
+    let get_entry = |name: &str| {
+        meminfo.lines().find_map(|line| {
+            let value = line.strip_prefix(name)?.strip_prefix(':')?;
+            let kilobytes: u64 = value.trim().strip_suffix("kB")?.trim().parse().ok()?;
+            Some(kilobytes * 1024)
+        })
+    };
+
+    (get_entry("MemTotal"), get_entry("MemAvailable"))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn memory() -> (Option<u64>, Option<u64>) {
+    (None, None)
+}
+
+#[cfg(unix)]
+fn uname() -> (Option<String>, Option<String>) {
+    let mut info = std::mem::MaybeUninit::<libc::utsname>::uninit();
+    // Safety: uname initializes the provided struct when it returns successfully
+    let info = unsafe {
+        if libc::uname(info.as_mut_ptr()) != 0 {
+            return (None, None);
+        }
+        info.assume_init()
+    };
+
+    let to_string = |field: &[libc::c_char]| {
+        // Safety: The fields in utsname are null-terminated
+        let field = unsafe { std::ffi::CStr::from_ptr(field.as_ptr()) };
+        Some(field.to_string_lossy().into_owned())
+    };
+
+    (to_string(&info.nodename), to_string(&info.release))
+}
+
+#[cfg(not(unix))]
+fn uname() -> (Option<String>, Option<String>) {
+    (std::env::var("COMPUTERNAME").ok(), None)
+}
+
+#[cfg(unix)]
+fn disk_usage(path: &Path) -> Option<DiskUsage> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stats = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // Safety: statvfs initializes the provided struct when it returns successfully
+    let stats = unsafe {
+        if libc::statvfs(path.as_ptr(), stats.as_mut_ptr()) != 0 {
+            return None;
+        }
+        stats.assume_init()
+    };
+
+    // The sizes of the statvfs fields vary between platforms
+    #[allow(clippy::unnecessary_cast)]
+    Some(DiskUsage {
+        total: stats.f_blocks as u64 * stats.f_frsize as u64,
+        available: stats.f_bavail as u64 * stats.f_frsize as u64,
+    })
+}
+
+#[cfg(not(unix))]
+fn disk_usage(_path: &Path) -> Option<DiskUsage> {
+    None
+}
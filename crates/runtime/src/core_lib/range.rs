@@ -1,6 +1,6 @@
 //! The `range` core library module
 
-use crate::prelude::*;
+use crate::{prelude::*, Result};
 
 /// Initializes the `range` core library module
 pub fn make_module() -> KMap {
@@ -11,6 +11,9 @@ pub fn make_module() -> KMap {
 
         match ctx.instance_and_args(is_range, expected_error)? {
             (KValue::Range(r), [KValue::Number(n)]) => Ok(r.contains(*n).into()),
+            (KValue::Range(a), [KValue::Range(b)]) if a.step().is_some() || b.step().is_some() => {
+                stepped_range_error("contains", a, Some(b))
+            }
             (KValue::Range(a), [KValue::Range(b)]) => {
                 let r_a = a.as_sorted_range();
                 let r_b = b.as_sorted_range();
@@ -36,6 +39,9 @@ pub fn make_module() -> KMap {
         let expected_error = "a Range and Number";
 
         match ctx.instance_and_args(is_range, expected_error)? {
+            (KValue::Range(r), [KValue::Number(_)]) if r.step().is_some() => {
+                stepped_range_error("expanded", r, None)
+            }
             (KValue::Range(r), [KValue::Number(n)]) => match (r.start(), r.end()) {
                 (Some(start), Some((end, inclusive))) => {
                     let n = i64::from(n);
@@ -56,6 +62,9 @@ pub fn make_module() -> KMap {
         let expected_error = "two Ranges";
 
         match ctx.instance_and_args(is_range, expected_error)? {
+            (KValue::Range(a), [KValue::Range(b)]) if a.step().is_some() || b.step().is_some() => {
+                stepped_range_error("intersection", a, Some(b))
+            }
             (KValue::Range(a), [KValue::Range(b)]) => Ok(a
                 .intersection(b)
                 .map_or(KValue::Null, |result| result.into())),
@@ -87,6 +96,12 @@ pub fn make_module() -> KMap {
         let expected_error = "a Range, and a Number or another Range";
 
         match ctx.instance_and_args(is_range, expected_error)? {
+            (KValue::Range(a), [KValue::Range(b)]) if a.step().is_some() || b.step().is_some() => {
+                stepped_range_error("union", a, Some(b))
+            }
+            (KValue::Range(r), [KValue::Number(_)]) if r.step().is_some() => {
+                stepped_range_error("union", r, None)
+            }
             (KValue::Range(r), [KValue::Number(n)]) => {
                 let n = i64::from(n);
                 match (r.start(), r.end()) {
@@ -129,3 +144,13 @@ pub fn make_module() -> KMap {
 fn is_range(value: &KValue) -> bool {
     matches!(value, KValue::Range(_))
 }
+
+// The range operations are performed on the range's bounds, which would ignore the step
+fn stepped_range_error(fn_name: &str, a: &KRange, b: Option<&KRange>) -> Result<KValue> {
+    match b {
+        Some(b) => {
+            runtime_error!("range.{fn_name} can't be used with stepped ranges ('{a}' and '{b}')")
+        }
+        None => runtime_error!("range.{fn_name} can't be used with the stepped range '{a}'"),
+    }
+}
@@ -1,9 +1,10 @@
 //! The `string` core library module
 
+pub mod encoding;
 pub mod iterators;
 
-use super::iterator::collect_pair;
-use crate::prelude::*;
+use crate::{prelude::*, Result};
+use unicode_segmentation::UnicodeSegmentation;
 
 /// Initializes the `string` core library module
 pub fn make_module() -> KMap {
@@ -21,6 +22,15 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("byte_len", |ctx| {
+        let expected_error = "a String";
+
+        match ctx.instance_and_args(is_string, expected_error)? {
+            (KValue::Str(s), []) => Ok(s.len().into()),
+            (_, unexpected) => type_error_with_slice(expected_error, unexpected),
+        }
+    });
+
     result.add_fn("chars", |ctx| {
         let expected_error = "a String";
 
@@ -42,6 +52,15 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("char_len", |ctx| {
+        let expected_error = "a String";
+
+        match ctx.instance_and_args(is_string, expected_error)? {
+            (KValue::Str(s), []) => Ok(s.grapheme_count().into()),
+            (_, unexpected) => type_error_with_slice(expected_error, unexpected),
+        }
+    });
+
     result.add_fn("contains", |ctx| {
         let expected_error = "a String";
 
@@ -51,6 +70,51 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("decode", |ctx| {
+        let (iterable, encoding, mode) = match ctx.args() {
+            [iterable, KValue::Str(encoding)] if iterable.is_iterable() => {
+                (iterable, encoding, None)
+            }
+            [iterable, KValue::Str(encoding), KValue::Str(mode)] if iterable.is_iterable() => {
+                (iterable, encoding, Some(mode))
+            }
+            unexpected => {
+                return type_error_with_slice(
+                    "an iterable of bytes, an encoding name, and an optional mode",
+                    unexpected,
+                )
+            }
+        };
+
+        let (encoding, mode) = encoding::encoding_args(encoding, mode)?;
+        let bytes = encoding::bytes_from_iterable(ctx.vm, iterable.clone())?;
+        encoding::decode_bytes(&bytes, encoding, mode)
+    });
+
+    result.add_fn("encode", |ctx| {
+        let expected_error = "a String, an encoding name, and an optional mode";
+
+        let (s, encoding, mode) = match ctx.instance_and_args(is_string, expected_error)? {
+            (KValue::Str(s), [KValue::Str(encoding)]) => (s, encoding, None),
+            (KValue::Str(s), [KValue::Str(encoding), KValue::Str(mode)]) => {
+                (s, encoding, Some(mode))
+            }
+            (_, unexpected) => return type_error_with_slice(expected_error, unexpected),
+        };
+
+        let (encoding, mode) = encoding::encoding_args(encoding, mode)?;
+        match encoding.encode(s, mode) {
+            Ok(bytes) => {
+                Ok(KTuple::from(bytes.into_iter().map(KValue::from).collect::<Vec<_>>()).into())
+            }
+            Err(c) => runtime_error!(
+                "'{}' can't be encoded as {}",
+                c.escape_debug(),
+                encoding.name()
+            ),
+        }
+    });
+
     result.add_fn("ends_with", |ctx| {
         let expected_error = "a String";
 
@@ -66,31 +130,43 @@ pub fn make_module() -> KMap {
         let expected_error = "a String";
 
         match ctx.instance_and_args(is_string, expected_error)? {
-            (KValue::Str(s), []) => Ok(s.escape_default().to_string().into()),
+            (KValue::Str(s), []) => {
+                let mut result = String::with_capacity(s.len());
+                for c in s.chars() {
+                    match c {
+                        // Escaped so that the result doesn't contain interpolated expressions
+                        '{' => result.push_str("\\{"),
+                        _ => result.extend(c.escape_default()),
+                    }
+                }
+                Ok(result.into())
+            }
             (_, unexpected) => type_error_with_slice(expected_error, unexpected),
         }
     });
 
-    result.add_fn("from_bytes", |ctx| match ctx.args() {
-        [iterable] if iterable.is_iterable() => {
-            let iterable = iterable.clone();
-            let iterator = ctx.vm.make_iterator(iterable)?;
-            let (size_hint, _) = iterator.size_hint();
-            let mut bytes = Vec::<u8>::with_capacity(size_hint);
-
-            for output in iterator.map(collect_pair) {
-                use KIteratorOutput as Output;
-                match output {
-                    Output::Value(KValue::Number(n)) => match u8::try_from(n.as_i64()) {
-                        Ok(byte) => bytes.push(byte),
-                        Err(_) => return runtime_error!("'{n}' is out of the valid byte range"),
-                    },
-                    Output::Value(unexpected) => return type_error("a number", &unexpected),
-                    Output::Error(error) => return Err(error),
-                    _ => unreachable!(),
+    result.add_fn("find", |ctx| {
+        let expected_error = "a String, and either a String or a predicate function";
+
+        match ctx.instance_and_args(is_string, expected_error)? {
+            (KValue::Str(s), [KValue::Str(pattern)]) => match s.find(pattern.as_str()) {
+                Some(index) => Ok(index.into()),
+                None => Ok(KValue::Null),
+            },
+            (KValue::Str(s), [predicate]) if predicate.is_callable() => {
+                let (s, predicate) = (s.clone(), predicate.clone());
+                match find_char(ctx.vm, &s, predicate)? {
+                    Some((byte_index, _)) => Ok(byte_index.into()),
+                    None => Ok(KValue::Null),
                 }
             }
+            (_, unexpected) => type_error_with_slice(expected_error, unexpected),
+        }
+    });
 
+    result.add_fn("from_bytes", |ctx| match ctx.args() {
+        [iterable] if iterable.is_iterable() => {
+            let bytes = encoding::bytes_from_iterable(ctx.vm, iterable.clone())?;
             match String::from_utf8(bytes) {
                 Ok(result) => Ok(result.into()),
                 Err(_) => runtime_error!("Input failed UTF-8 validation"),
@@ -99,6 +175,25 @@ pub fn make_module() -> KMap {
         unexpected => type_error_with_slice("an iterable", unexpected),
     });
 
+    result.add_fn("index_of", |ctx| {
+        let expected_error = "a String, and either a String or a predicate function";
+
+        match ctx.instance_and_args(is_string, expected_error)? {
+            (KValue::Str(s), [KValue::Str(pattern)]) => match s.find(pattern.as_str()) {
+                Some(byte_index) => Ok(s[..byte_index].graphemes(true).count().into()),
+                None => Ok(KValue::Null),
+            },
+            (KValue::Str(s), [predicate]) if predicate.is_callable() => {
+                let (s, predicate) = (s.clone(), predicate.clone());
+                match find_char(ctx.vm, &s, predicate)? {
+                    Some((_, char_index)) => Ok(char_index.into()),
+                    None => Ok(KValue::Null),
+                }
+            }
+            (_, unexpected) => type_error_with_slice(expected_error, unexpected),
+        }
+    });
+
     result.add_fn("is_empty", |ctx| {
         let expected_error = "a String";
 
@@ -120,6 +215,71 @@ pub fn make_module() -> KMap {
         }
     });
 
+    for (name, pad_at_start) in [("pad_end", false), ("pad_start", true)] {
+        result.add_fn(name, move |ctx| {
+            let expected_error = "a String, a width Number, and an optional padding String";
+
+            let (s, width, padding) = match ctx.instance_and_args(is_string, expected_error)? {
+                (KValue::Str(s), [KValue::Number(width)]) => (s, width, " "),
+                (KValue::Str(s), [KValue::Number(width), KValue::Str(padding)]) => {
+                    (s, width, padding.as_str())
+                }
+                (_, unexpected) => return type_error_with_slice(expected_error, unexpected),
+            };
+
+            if *width < 0.0 {
+                return runtime_error!("Expected a non-negative width, found '{width}'");
+            }
+
+            let pad_count = usize::from(width).saturating_sub(s.grapheme_count());
+            if pad_count == 0 {
+                return Ok(s.clone().into());
+            }
+            if padding.is_empty() {
+                return runtime_error!("Expected a non-empty padding String");
+            }
+
+            // The padding's size is checked before it's generated, so that very large widths
+            // produce an error rather than exhausting memory
+            let graphemes: Vec<&str> = padding.graphemes(true).collect();
+            let remainder_size: usize = graphemes[..pad_count % graphemes.len()]
+                .iter()
+                .map(|grapheme| grapheme.len())
+                .sum();
+            let result_size = (pad_count / graphemes.len())
+                .checked_mul(padding.len())
+                .and_then(|size| size.checked_add(remainder_size + s.len()));
+            let mut result = String::new();
+            if result_size.is_none_or(|size| result.try_reserve_exact(size).is_err()) {
+                return runtime_error!("unable to allocate memory for a width of {width}");
+            }
+
+            let pad = graphemes.iter().copied().cycle().take(pad_count);
+            if pad_at_start {
+                result.extend(pad);
+                result.push_str(s);
+            } else {
+                result.push_str(s);
+                result.extend(pad);
+            }
+
+            Ok(result.into())
+        });
+    }
+
+    result.add_fn("parse_bool", |ctx| {
+        let expected_error = "a String";
+
+        match ctx.instance_and_args(is_string, expected_error)? {
+            (KValue::Str(s), []) => match s.as_str() {
+                "true" => Ok(true.into()),
+                "false" => Ok(false.into()),
+                _ => Ok(KValue::Null),
+            },
+            (_, unexpected) => type_error_with_slice(expected_error, unexpected),
+        }
+    });
+
     result.add_fn("replace", |ctx| {
         let expected_error = "a String, followed by pattern and replacement Strings";
 
@@ -131,15 +291,46 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("rfind", |ctx| {
+        let expected_error = "two Strings";
+
+        match ctx.instance_and_args(is_string, expected_error)? {
+            (KValue::Str(s), [KValue::Str(pattern)]) => match s.rfind(pattern.as_str()) {
+                Some(index) => Ok(index.into()),
+                None => Ok(KValue::Null),
+            },
+            (_, unexpected) => type_error_with_slice(expected_error, unexpected),
+        }
+    });
+
     result.add_fn("split", |ctx| {
         let iterator = {
-            let expected_error = "a String, and either a String or a predicate function";
+            let expected_error = "a String, and either a String (or container of Strings) \
+                                  with optional split options, or a predicate function";
 
             match ctx.instance_and_args(is_string, expected_error)? {
                 (KValue::Str(input), [KValue::Str(pattern)]) => {
                     let result = iterators::Split::new(input.clone(), pattern.clone());
                     KIterator::new(result)
                 }
+                (KValue::Str(input), [patterns @ (KValue::List(_) | KValue::Tuple(_))]) => {
+                    let patterns = split_patterns(patterns)?;
+                    let options = iterators::SplitOptions::default();
+                    KIterator::new(iterators::Split::with_options(
+                        input.clone(),
+                        patterns,
+                        options,
+                    ))
+                }
+                (KValue::Str(input), [patterns, KValue::Map(options)]) => {
+                    let patterns = split_patterns(patterns)?;
+                    let options = split_options(options)?;
+                    KIterator::new(iterators::Split::with_options(
+                        input.clone(),
+                        patterns,
+                        options,
+                    ))
+                }
                 (KValue::Str(input), [predicate]) if predicate.is_callable() => {
                     let result = iterators::SplitWith::new(
                         input.clone(),
@@ -166,6 +357,81 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("strip_prefix", |ctx| {
+        let expected_error = "two Strings";
+
+        match ctx.instance_and_args(is_string, expected_error)? {
+            (KValue::Str(s), [KValue::Str(prefix)]) => match s.strip_prefix(prefix.as_str()) {
+                Some(_) => Ok(s.with_bounds(prefix.len()..s.len()).unwrap().into()),
+                None => Ok(KValue::Null),
+            },
+            (_, unexpected) => type_error_with_slice(expected_error, unexpected),
+        }
+    });
+
+    result.add_fn("strip_suffix", |ctx| {
+        let expected_error = "two Strings";
+
+        match ctx.instance_and_args(is_string, expected_error)? {
+            (KValue::Str(s), [KValue::Str(suffix)]) => match s.strip_suffix(suffix.as_str()) {
+                Some(stripped) => Ok(s.with_bounds(0..stripped.len()).unwrap().into()),
+                None => Ok(KValue::Null),
+            },
+            (_, unexpected) => type_error_with_slice(expected_error, unexpected),
+        }
+    });
+
+    result.add_fn("substring", |ctx| {
+        let expected_error = "a String, a non-negative start index, and an optional end index";
+
+        match ctx.instance_and_args(is_string, expected_error)? {
+            (KValue::Str(s), [KValue::Number(start)]) if *start >= 0.0 => {
+                let end = s.grapheme_count();
+                Ok(s.with_grapheme_indices(start.into()..end).into())
+            }
+            (KValue::Str(s), [KValue::Number(start), KValue::Number(end)])
+                if *start >= 0.0 && *end >= 0.0 =>
+            {
+                let start = usize::from(start);
+                let end = usize::from(end).max(start);
+                Ok(s.with_grapheme_indices(start..end).into())
+            }
+            (_, unexpected) => type_error_with_slice(expected_error, unexpected),
+        }
+    });
+
+    result.add_fn("to_float", |ctx| {
+        let expected_error = "a String";
+
+        match ctx.instance_and_args(is_string, expected_error)? {
+            (KValue::Str(s), []) => match s.parse::<f64>() {
+                Ok(float) => Ok(float.into()),
+                Err(_) => Ok(KValue::Null),
+            },
+            (_, unexpected) => type_error_with_slice(expected_error, unexpected),
+        }
+    });
+
+    result.add_fn("to_int", |ctx| {
+        let expected_error = "a String, with an optional Number base";
+
+        match ctx.instance_and_args(is_string, expected_error)? {
+            (KValue::Str(s), []) => Ok(parse_integer(s).map_or(KValue::Null, KValue::from)),
+            (KValue::Str(s), [KValue::Number(n)]) => {
+                let base = n.into();
+                if !(2..=36).contains(&base) {
+                    return runtime_error!("Number base must be within 2..=36");
+                }
+
+                match i64::from_str_radix(s, base) {
+                    Ok(result) => Ok(result.into()),
+                    Err(_) => Ok(KValue::Null),
+                }
+            }
+            (_, unexpected) => type_error_with_slice(expected_error, unexpected),
+        }
+    });
+
     result.add_fn("to_lowercase", |ctx| {
         let expected_error = "a String";
 
@@ -183,17 +449,7 @@ pub fn make_module() -> KMap {
 
         match ctx.instance_and_args(is_string, expected_error)? {
             (KValue::Str(s), []) => {
-                let maybe_integer = if let Some(hex) = s.strip_prefix("0x") {
-                    i64::from_str_radix(hex, 16)
-                } else if let Some(octal) = s.strip_prefix("0o") {
-                    i64::from_str_radix(octal, 8)
-                } else if let Some(binary) = s.strip_prefix("0b") {
-                    i64::from_str_radix(binary, 2)
-                } else {
-                    s.parse::<i64>()
-                };
-
-                if let Ok(integer) = maybe_integer {
+                if let Some(integer) = parse_integer(s) {
                     Ok(integer.into())
                 } else if let Ok(float) = s.parse::<f64>() {
                     Ok(float.into())
@@ -251,6 +507,79 @@ pub fn make_module() -> KMap {
     result
 }
 
+// Parses an integer, with support for hex, octal, and binary prefixes
+fn parse_integer(s: &str) -> Option<i64> {
+    if let Some(hex) = s.strip_prefix("0x") {
+        i64::from_str_radix(hex, 16).ok()
+    } else if let Some(octal) = s.strip_prefix("0o") {
+        i64::from_str_radix(octal, 8).ok()
+    } else if let Some(binary) = s.strip_prefix("0b") {
+        i64::from_str_radix(binary, 2).ok()
+    } else {
+        s.parse::<i64>().ok()
+    }
+}
+
+// Gets the patterns used by string.split, either a single string, or a container of strings
+// Finds the first character that causes the predicate to return true
+//
+// The character's byte index is returned along with its character index.
+fn find_char(vm: &mut KotoVm, s: &KString, predicate: KValue) -> Result<Option<(usize, usize)>> {
+    for (char_index, (byte_index, grapheme)) in s.grapheme_indices(true).enumerate() {
+        let c = s
+            .with_bounds(byte_index..byte_index + grapheme.len())
+            .unwrap();
+        match vm.call_function(predicate.clone(), c)? {
+            KValue::Bool(true) => return Ok(Some((byte_index, char_index))),
+            KValue::Bool(false) => {}
+            unexpected => {
+                return type_error("a Bool to be returned from the predicate", &unexpected)
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+fn split_patterns(patterns: &KValue) -> Result<Vec<KString>> {
+    let strings_from_slice = |values: &[KValue]| {
+        values
+            .iter()
+            .map(|value| match value {
+                KValue::Str(s) => Ok(s.clone()),
+                unexpected => type_error("a String as split pattern", unexpected),
+            })
+            .collect()
+    };
+
+    match patterns {
+        KValue::Str(pattern) => Ok(vec![pattern.clone()]),
+        KValue::List(patterns) => strings_from_slice(&patterns.data()),
+        KValue::Tuple(patterns) => strings_from_slice(patterns),
+        unexpected => type_error("a String or a container of Strings", unexpected),
+    }
+}
+
+// Gets the options used by string.split from a map
+fn split_options(options: &KMap) -> Result<iterators::SplitOptions> {
+    let mut result = iterators::SplitOptions::default();
+
+    for (key, value) in options.data().iter() {
+        match (key.to_string().as_str(), value) {
+            ("max_splits", KValue::Number(n)) if *n >= 0.0 => result.max_splits = Some(n.into()),
+            ("max_splits", KValue::Null) => result.max_splits = None,
+            ("keep_empty", KValue::Bool(keep_empty)) => result.keep_empty = *keep_empty,
+            ("max_splits", unexpected) => {
+                return type_error("a non-negative Number as 'max_splits'", unexpected)
+            }
+            ("keep_empty", unexpected) => return type_error("a Bool as 'keep_empty'", unexpected),
+            (unexpected, _) => return runtime_error!("Unexpected split option '{unexpected}'"),
+        }
+    }
+
+    Ok(result)
+}
+
 fn is_string(value: &KValue) -> bool {
     matches!(value, KValue::Str(_))
 }
@@ -0,0 +1,236 @@
+//! Conversions between strings and text encodings other than UTF-8
+
+use crate::{core_lib::iterator::collect_pair, prelude::*, Result};
+
+/// A text encoding supported by `string.decode` and `string.encode`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// 7-bit ASCII
+    Ascii,
+    /// ISO-8859-1, where each byte maps to the Unicode code point with the same value
+    Latin1,
+    /// UTF-8
+    Utf8,
+    /// UTF-16, little-endian
+    Utf16Le,
+    /// UTF-16, big-endian
+    Utf16Be,
+    /// Windows-1252, a superset of Latin-1 that's commonly used on Windows
+    Windows1252,
+}
+
+/// How errors are handled when converting between encodings
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ErrorMode {
+    /// Invalid data causes the conversion to fail
+    #[default]
+    Strict,
+    /// Invalid data is replaced, with `�` when decoding, and `?` when encoding
+    Lossy,
+}
+
+// The characters that Windows-1252 maps to the bytes 0x80..=0x9F
+//
+// Bytes that are undefined in Windows-1252 map to the C1 control characters with the same value,
+// matching the WHATWG encoding standard.
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+impl Encoding {
+    /// Returns the encoding matching the given name, e.g. `utf-16le`, `latin-1`, or `cp1252`
+    ///
+    /// Names are matched without taking case, `-`, or `_` into account.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let normalized: String = name
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_'))
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+
+        let result = match normalized.as_str() {
+            "ascii" | "usascii" => Self::Ascii,
+            "latin1" | "iso88591" => Self::Latin1,
+            "utf8" => Self::Utf8,
+            "utf16le" => Self::Utf16Le,
+            "utf16be" => Self::Utf16Be,
+            "windows1252" | "cp1252" => Self::Windows1252,
+            _ => return None,
+        };
+
+        Some(result)
+    }
+
+    /// Returns the encoding's canonical name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ascii => "ascii",
+            Self::Latin1 => "latin-1",
+            Self::Utf8 => "utf-8",
+            Self::Utf16Le => "utf-16le",
+            Self::Utf16Be => "utf-16be",
+            Self::Windows1252 => "windows-1252",
+        }
+    }
+
+    /// Decodes the bytes into a string
+    ///
+    /// In strict mode, the offset of the first invalid byte is returned as the error.
+    pub fn decode(&self, bytes: &[u8], mode: ErrorMode) -> std::result::Result<String, usize> {
+        let lossy = mode == ErrorMode::Lossy;
+
+        match self {
+            Self::Ascii => {
+                let mut result = String::with_capacity(bytes.len());
+                for (i, byte) in bytes.iter().enumerate() {
+                    if byte.is_ascii() {
+                        result.push(*byte as char);
+                    } else if lossy {
+                        result.push(char::REPLACEMENT_CHARACTER);
+                    } else {
+                        return Err(i);
+                    }
+                }
+                Ok(result)
+            }
+            Self::Latin1 => Ok(bytes.iter().map(|byte| *byte as char).collect()),
+            Self::Utf8 => match std::str::from_utf8(bytes) {
+                Ok(s) => Ok(s.to_string()),
+                Err(_) if lossy => Ok(String::from_utf8_lossy(bytes).into_owned()),
+                Err(error) => Err(error.valid_up_to()),
+            },
+            Self::Utf16Le | Self::Utf16Be => {
+                let units = bytes.chunks_exact(2).map(|pair| {
+                    let pair = [pair[0], pair[1]];
+                    if *self == Self::Utf16Le {
+                        u16::from_le_bytes(pair)
+                    } else {
+                        u16::from_be_bytes(pair)
+                    }
+                });
+
+                let mut result = String::with_capacity(bytes.len() / 2);
+                let mut offset = 0;
+                for c in char::decode_utf16(units) {
+                    match c {
+                        Ok(c) => {
+                            result.push(c);
+                            offset += c.len_utf16() * 2;
+                        }
+                        Err(_) if lossy => {
+                            result.push(char::REPLACEMENT_CHARACTER);
+                            offset += 2;
+                        }
+                        Err(_) => return Err(offset),
+                    }
+                }
+
+                // A trailing odd byte can't be part of a valid code unit
+                if !bytes.len().is_multiple_of(2) {
+                    if lossy {
+                        result.push(char::REPLACEMENT_CHARACTER);
+                    } else {
+                        return Err(bytes.len() - 1);
+                    }
+                }
+
+                Ok(result)
+            }
+            Self::Windows1252 => Ok(bytes
+                .iter()
+                .map(|byte| match byte {
+                    0x80..=0x9F => WINDOWS_1252_HIGH[(byte - 0x80) as usize],
+                    _ => *byte as char,
+                })
+                .collect()),
+        }
+    }
+
+    /// Encodes the string into bytes
+    ///
+    /// In strict mode, the first character that can't be encoded is returned as the error.
+    pub fn encode(&self, s: &str, mode: ErrorMode) -> std::result::Result<Vec<u8>, char> {
+        let lossy = mode == ErrorMode::Lossy;
+
+        // Encodes single-byte encodings, using the provided function to map chars to bytes
+        let encode_bytes = |to_byte: fn(char) -> Option<u8>| {
+            let mut result = Vec::with_capacity(s.len());
+            for c in s.chars() {
+                match to_byte(c) {
+                    Some(byte) => result.push(byte),
+                    None if lossy => result.push(b'?'),
+                    None => return Err(c),
+                }
+            }
+            Ok(result)
+        };
+
+        match self {
+            Self::Ascii => encode_bytes(|c| c.is_ascii().then_some(c as u8)),
+            Self::Latin1 => encode_bytes(|c| u8::try_from(c).ok()),
+            Self::Utf8 => Ok(s.as_bytes().to_vec()),
+            Self::Utf16Le => Ok(s.encode_utf16().flat_map(u16::to_le_bytes).collect()),
+            Self::Utf16Be => Ok(s.encode_utf16().flat_map(u16::to_be_bytes).collect()),
+            Self::Windows1252 => encode_bytes(|c| match u8::try_from(c) {
+                Ok(byte) if !(0x80..=0x9F).contains(&byte) => Some(byte),
+                _ => WINDOWS_1252_HIGH
+                    .iter()
+                    .position(|high| *high == c)
+                    .map(|i| 0x80 + i as u8),
+            }),
+        }
+    }
+}
+
+/// Parses the encoding and optional error mode arguments used by the core library
+pub(crate) fn encoding_args(
+    encoding: &str,
+    mode: Option<&KString>,
+) -> Result<(Encoding, ErrorMode)> {
+    let Some(encoding) = Encoding::from_name(encoding) else {
+        return runtime_error!("Unsupported encoding '{encoding}'");
+    };
+
+    let mode = match mode.map(|mode| mode.as_str()) {
+        None | Some("strict") => ErrorMode::Strict,
+        Some("lossy") => ErrorMode::Lossy,
+        Some(other) => {
+            return runtime_error!("Unexpected mode '{other}', expected 'strict' or 'lossy'")
+        }
+    };
+
+    Ok((encoding, mode))
+}
+
+/// Decodes bytes with the given encoding, producing an error that describes invalid data
+pub(crate) fn decode_bytes(bytes: &[u8], encoding: Encoding, mode: ErrorMode) -> Result<KValue> {
+    match encoding.decode(bytes, mode) {
+        Ok(result) => Ok(result.into()),
+        Err(offset) => runtime_error!("Invalid {} data at byte {offset}", encoding.name()),
+    }
+}
+
+/// Collects bytes from an iterable value, e.g. a Tuple of Numbers
+pub(crate) fn bytes_from_iterable(vm: &mut KotoVm, iterable: KValue) -> Result<Vec<u8>> {
+    let iterator = vm.make_iterator(iterable)?;
+    let (size_hint, _) = iterator.size_hint();
+    let mut bytes = Vec::<u8>::with_capacity(size_hint);
+
+    for output in iterator.map(collect_pair) {
+        use KIteratorOutput as Output;
+        match output {
+            Output::Value(KValue::Number(n)) => match u8::try_from(n.as_i64()) {
+                Ok(byte) => bytes.push(byte),
+                Err(_) => return runtime_error!("'{n}' is out of the valid byte range"),
+            },
+            Output::Value(unexpected) => return type_error("a number", &unexpected),
+            Output::Error(error) => return Err(error),
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(bytes)
+}
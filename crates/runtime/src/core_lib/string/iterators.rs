@@ -1,6 +1,6 @@
 //! A collection of string iterators
 
-use crate::{prelude::*, KIteratorOutput as Output, Result};
+use crate::{prelude::*, KIteratorOutput as Output, Ptr, Result};
 use unicode_segmentation::UnicodeSegmentation;
 
 /// An iterator that outputs the individual bytes contained in a string
@@ -143,21 +143,93 @@ impl Iterator for Lines {
     }
 }
 
-/// An iterator that splits up a string into parts, separated by a provided pattern
+/// An iterator that splits up a string into parts, separated by any of the provided patterns
 #[derive(Clone)]
 pub struct Split {
     input: KString,
-    pattern: KString,
+    patterns: Ptr<[KString]>,
+    // The position of each pattern's next match, with `None` when there are no more matches
+    next_matches: Vec<Option<usize>>,
     start: usize,
+    remaining_splits: Option<usize>,
+    keep_empty: bool,
 }
 
 impl Split {
     /// Creates a new [Split] iterator
     pub fn new(input: KString, pattern: KString) -> Self {
+        Self::with_options(input, vec![pattern], SplitOptions::default())
+    }
+
+    /// Creates a new [Split] iterator that splits on any of the provided patterns
+    ///
+    /// Empty patterns are ignored.
+    pub fn with_options(input: KString, patterns: Vec<KString>, options: SplitOptions) -> Self {
+        let patterns = patterns
+            .into_iter()
+            .filter(|pattern| !pattern.is_empty())
+            .collect::<Vec<_>>();
+        let next_matches = patterns
+            .iter()
+            .map(|pattern| input.find(pattern.as_str()))
+            .collect();
+
         Self {
             input,
-            pattern,
+            patterns: patterns.into(),
+            next_matches,
             start: 0,
+            remaining_splits: options.max_splits,
+            keep_empty: options.keep_empty,
+        }
+    }
+
+    // Finds the next match in the remaining input, returning the match's position and length
+    //
+    // When more than one pattern matches at the same position, the longest pattern is used.
+    //
+    // Each pattern's next match is cached, and a pattern is only searched for again once the
+    // start of the remaining input has moved past its cached match, so the input is scanned
+    // once per pattern rather than once per match.
+    fn find_next_match(&mut self) -> Option<(usize, usize)> {
+        let mut result: Option<(usize, usize)> = None;
+
+        for (pattern, next_match) in self.patterns.iter().zip(self.next_matches.iter_mut()) {
+            if next_match.is_some_and(|position| position < self.start) {
+                *next_match = self.input[self.start..]
+                    .find(pattern.as_str())
+                    .map(|position| self.start + position);
+            }
+
+            if let Some(position) = *next_match {
+                let is_better_match = result.is_none_or(|(best_position, best_len)| {
+                    position < best_position
+                        || (position == best_position && pattern.len() > best_len)
+                });
+                if is_better_match {
+                    result = Some((position, pattern.len()));
+                }
+            }
+        }
+
+        result
+    }
+}
+
+/// Options that modify the behaviour of the [Split] iterator
+#[derive(Clone, Copy, Debug)]
+pub struct SplitOptions {
+    /// The maximum number of splits to perform, with `None` meaning unlimited splits
+    pub max_splits: Option<usize>,
+    /// Whether or not empty parts should be included in the output
+    pub keep_empty: bool,
+}
+
+impl Default for SplitOptions {
+    fn default() -> Self {
+        Self {
+            max_splits: None,
+            keep_empty: true,
         }
     }
 }
@@ -172,24 +244,49 @@ impl Iterator for Split {
     type Item = Output;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let start = self.start;
-        if start <= self.input.len() {
-            let end = match self.input[start..].find(self.pattern.as_str()) {
-                Some(end) => start + end,
-                None => self.input.len(),
+        while self.start <= self.input.len() {
+            let start = self.start;
+
+            let next_match = if self.remaining_splits == Some(0) {
+                None
+            } else {
+                self.find_next_match()
             };
 
+            let end = match next_match {
+                Some((end, pattern_len)) => {
+                    if let Some(remaining_splits) = self.remaining_splits.as_mut() {
+                        *remaining_splits -= 1;
+                    }
+                    self.start = end + pattern_len;
+                    end
+                }
+                None => {
+                    // Move past the end of the input so that iteration stops after this output
+                    self.start = self.input.len() + 1;
+                    self.input.len()
+                }
+            };
+
+            if start == end && !self.keep_empty {
+                continue;
+            }
+
             let output = KValue::Str(self.input.with_bounds(start..end).unwrap());
-            self.start = end + self.pattern.len();
-            Some(Output::Value(output))
-        } else {
-            None
+            return Some(Output::Value(output));
         }
+
+        None
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining_bytes = self.input.len() - self.start;
-        (1.min(remaining_bytes), Some(remaining_bytes))
+        match self.input.len().checked_sub(self.start) {
+            Some(remaining_bytes) => {
+                let lower = if self.keep_empty { 1 } else { 0 };
+                (lower, Some(remaining_bytes + 1))
+            }
+            None => (0, Some(0)),
+        }
     }
 }
 
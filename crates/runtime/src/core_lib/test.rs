@@ -1,5 +1,7 @@
 //! The `test` core library module
 
+pub mod diff;
+
 use crate::{prelude::*, Result};
 
 /// Initializes the `test` core library module
@@ -28,6 +30,13 @@ pub fn make_module() -> KMap {
             match result {
                 Ok(KValue::Bool(true)) => Ok(KValue::Null),
                 Ok(KValue::Bool(false)) => {
+                    // Multi-line strings are easier to compare with a diff
+                    if let Some(diff) = multiline_diff(&a, &b) {
+                        return runtime_error!(
+                            "Assertion failed, the strings are not equal\n{}",
+                            diff.trim_end()
+                        );
+                    }
                     runtime_error!(
                         "Assertion failed, '{}' is not equal to '{}'",
                         ctx.vm.value_to_string(&a)?,
@@ -87,6 +96,16 @@ pub fn make_module() -> KMap {
     result
 }
 
+fn multiline_diff(a: &KValue, b: &KValue) -> Option<String> {
+    match (a, b) {
+        (KValue::Str(a), KValue::Str(b)) if a.contains('\n') || b.contains('\n') => {
+            // Strings that only differ in their line endings produce an empty diff
+            Some(diff::format_unified(a, b, "left", "right", 3)).filter(|diff| !diff.is_empty())
+        }
+        _ => None,
+    }
+}
+
 fn f64_near(a: f64, b: f64, allowed_diff: f64) -> bool {
     (a - b).abs() <= allowed_diff
 }
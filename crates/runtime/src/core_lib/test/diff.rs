@@ -0,0 +1,237 @@
+//! Diffing of sequences, used by `assert_eq` to display the differences between strings
+//!
+//! The diff is produced with Myers' algorithm, which finds a minimal set of deletions and
+//! insertions that turn the old sequence into the new sequence.
+
+use std::{fmt::Write, ops::Range};
+
+/// The kind of change that was made to an element of a sequence
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// The element is in both sequences
+    Equal,
+    /// The element was removed from the old sequence
+    Delete,
+    /// The element was added to the new sequence
+    Insert,
+}
+
+impl ChangeKind {
+    /// Returns the name of the change kind, as used in scripts
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Equal => "equal",
+            Self::Delete => "delete",
+            Self::Insert => "insert",
+        }
+    }
+}
+
+/// A change to a single element of a sequence
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Change {
+    /// The kind of change
+    pub kind: ChangeKind,
+    /// The position of the change in the old sequence
+    ///
+    /// For insertions, this is the position in the old sequence where the element is inserted.
+    pub old: usize,
+    /// The position of the change in the new sequence
+    ///
+    /// For deletions, this is the position in the new sequence where the element was removed.
+    pub new: usize,
+}
+
+/// A group of nearby changes, along with surrounding unchanged elements
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hunk {
+    /// The range of elements in the old sequence that are covered by the hunk
+    pub old: Range<usize>,
+    /// The range of elements in the new sequence that are covered by the hunk
+    pub new: Range<usize>,
+    /// The hunk's changes, in order
+    pub changes: Vec<Change>,
+}
+
+/// Returns the changes that turn the old sequence into the new sequence
+///
+/// Every element of both sequences is included in the result, with unchanged elements
+/// having the [ChangeKind::Equal] kind.
+pub fn diff<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Change> {
+    let n = old.len() as isize;
+    let m = new.len() as isize;
+    let max = n + m;
+    let offset = max + 1;
+
+    // The furthest reaching x position for each diagonal k, indexed by k + offset
+    let mut v = vec![0isize; 2 * offset as usize + 1];
+    // Snapshots of v for diagonals -d..=d, taken at the start of each step
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    'search: for d in 0..=max {
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+
+        for k in (-d..=d).step_by(2) {
+            let index = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[index - 1] < v[index + 1]) {
+                v[index + 1]
+            } else {
+                v[index - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[index] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    // Walk back through the trace to find the path that was taken
+    let mut result = Vec::with_capacity(old.len().max(new.len()));
+    let (mut x, mut y) = (n, m);
+    let mut push = |kind, x: isize, y: isize| {
+        result.push(Change {
+            kind,
+            old: x as usize,
+            new: y as usize,
+        })
+    };
+
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        // The snapshot for step d covers diagonals -d..=d
+        let get = |k: isize| v[(k + d) as usize];
+        let k = x - y;
+
+        let (prev_x, prev_y) = if d == 0 {
+            (0, 0)
+        } else {
+            let prev_k = if k == -d || (k != d && get(k - 1) < get(k + 1)) {
+                k + 1
+            } else {
+                k - 1
+            };
+            let prev_x = get(prev_k);
+            (prev_x, prev_x - prev_k)
+        };
+
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            push(ChangeKind::Equal, x, y);
+        }
+
+        if d > 0 {
+            if x == prev_x {
+                y -= 1;
+                push(ChangeKind::Insert, x, y);
+            } else {
+                x -= 1;
+                push(ChangeKind::Delete, x, y);
+            }
+        }
+    }
+
+    result.reverse();
+    result
+}
+
+/// Groups changes into hunks, with up to `context` unchanged elements around each change
+///
+/// Changes that are close enough for their context to overlap are placed in the same hunk.
+pub fn hunks(changes: &[Change], context: usize) -> Vec<Hunk> {
+    let changed: Vec<usize> = changes
+        .iter()
+        .enumerate()
+        .filter(|(_, change)| change.kind != ChangeKind::Equal)
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut result = Vec::new();
+    let mut i = 0;
+    while i < changed.len() {
+        let first = changed[i];
+        let mut last = first;
+        i += 1;
+        while i < changed.len() && changed[i] - last <= 2 * context + 1 {
+            last = changed[i];
+            i += 1;
+        }
+
+        let start = first.saturating_sub(context);
+        let end = (last + context + 1).min(changes.len());
+        let hunk_changes = &changes[start..end];
+
+        let old_start = hunk_changes[0].old;
+        let new_start = hunk_changes[0].new;
+        let old_len = hunk_changes
+            .iter()
+            .filter(|change| change.kind != ChangeKind::Insert)
+            .count();
+        let new_len = hunk_changes
+            .iter()
+            .filter(|change| change.kind != ChangeKind::Delete)
+            .count();
+
+        result.push(Hunk {
+            old: old_start..old_start + old_len,
+            new: new_start..new_start + new_len,
+            changes: hunk_changes.to_vec(),
+        });
+    }
+
+    result
+}
+
+/// Formats the differences between the lines of two strings as a unified diff
+///
+/// An empty string is returned if there are no differences.
+pub fn format_unified(
+    old: &str,
+    new: &str,
+    old_name: &str,
+    new_name: &str,
+    context: usize,
+) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let hunks = hunks(&diff(&old_lines, &new_lines), context);
+
+    let mut result = String::new();
+    if hunks.is_empty() {
+        return result;
+    }
+
+    // Writing to a String can't fail, so the results of write! are ignored
+    let _ = writeln!(result, "--- {old_name}\n+++ {new_name}");
+    for hunk in hunks {
+        let _ = writeln!(
+            result,
+            "@@ -{} +{} @@",
+            unified_range(&hunk.old),
+            unified_range(&hunk.new)
+        );
+        for change in hunk.changes {
+            let _ = match change.kind {
+                ChangeKind::Equal => writeln!(result, " {}", old_lines[change.old]),
+                ChangeKind::Delete => writeln!(result, "-{}", old_lines[change.old]),
+                ChangeKind::Insert => writeln!(result, "+{}", new_lines[change.new]),
+            };
+        }
+    }
+
+    result
+}
+
+// Line numbers in unified diffs start at 1, with empty ranges referring to the preceding line
+fn unified_range(range: &Range<usize>) -> String {
+    match range.len() {
+        0 => format!("{},0", range.start),
+        1 => format!("{}", range.start + 1),
+        len => format!("{},{len}", range.start + 1),
+    }
+}
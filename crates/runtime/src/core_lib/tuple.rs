@@ -1,6 +1,6 @@
 //! The `tuple` core library module
 
-use super::value_sort::sort_values;
+use super::value_sort::{sort_values, sort_values_by_key};
 use crate::prelude::*;
 
 /// Initializes the `tuple` core library module
@@ -65,6 +65,15 @@ pub fn make_module() -> KMap {
         }
     });
 
+    result.add_fn("is_empty", |ctx| {
+        let expected_error = "a Tuple";
+
+        match ctx.instance_and_args(is_tuple, expected_error)? {
+            (KValue::Tuple(t), []) => Ok(t.is_empty().into()),
+            (_, unexpected) => type_error_with_slice(expected_error, unexpected),
+        }
+    });
+
     result.add_fn("last", |ctx| {
         let expected_error = "a Tuple";
 
@@ -78,7 +87,7 @@ pub fn make_module() -> KMap {
     });
 
     result.add_fn("sort_copy", |ctx| {
-        let expected_error = "a Tuple";
+        let expected_error = "a Tuple, and an optional key function";
 
         match ctx.instance_and_args(is_tuple, expected_error)? {
             (KValue::Tuple(t), []) => {
@@ -88,6 +97,14 @@ pub fn make_module() -> KMap {
 
                 Ok(KValue::Tuple(result.into()))
             }
+            (KValue::Tuple(t), [f]) if f.is_callable() => {
+                let t = t.clone();
+                let f = f.clone();
+
+                let result = sort_values_by_key(ctx.vm, &t, &f)?;
+
+                Ok(KValue::Tuple(result.into()))
+            }
             (_, unexpected) => type_error_with_slice(expected_error, unexpected),
         }
     });
@@ -31,6 +31,50 @@ pub fn sort_values(vm: &mut KotoVm, arr: &mut [KValue]) -> Result<(), Error> {
     Ok(())
 }
 
+/// Sorts values using keys produced by calling a function with each value.
+///
+/// The function is called once per value, and then the keys are compared using Koto operators.
+pub fn sort_values_by_key(
+    vm: &mut KotoVm,
+    values: &[KValue],
+    key_fn: &KValue,
+) -> Result<Vec<KValue>, Error> {
+    // apply function and construct a vec of (key, value)
+    let mut pairs = values
+        .iter()
+        .map(
+            |value| match vm.call_function(key_fn.clone(), value.clone()) {
+                Ok(key) => Ok((key, value.clone())),
+                Err(e) => Err(e),
+            },
+        )
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut error = None;
+
+    // sort array by key (i.e. from [key, value])
+    pairs.sort_by(|a, b| {
+        if error.is_some() {
+            return Ordering::Equal;
+        }
+
+        match compare_values(vm, &a.0, &b.0) {
+            Ok(ordering) => ordering,
+            Err(e) => {
+                error.get_or_insert(e);
+                Ordering::Equal
+            }
+        }
+    });
+
+    if let Some(error) = error {
+        return Err(error);
+    }
+
+    // collect values
+    Ok(pairs.into_iter().map(|(_key, value)| value).collect())
+}
+
 /// Compares values using Koto operators.
 pub fn compare_values(vm: &mut KotoVm, a: &KValue, b: &KValue) -> Result<Ordering, Error> {
     use KValue::Bool;
@@ -0,0 +1,139 @@
+use crate::{make_ptr, KotoSend, KotoSync, Ptr, PtrMut};
+use koto_parser::Span;
+use rustc_hash::FxHashSet;
+use std::{fmt, path::PathBuf};
+
+/// The trait used by callbacks registered with [Diagnostics::set_callback]
+pub trait DiagnosticCallback: Fn(&Diagnostic) + KotoSend + KotoSync {}
+
+// Implement the trait for any matching function
+impl<T> DiagnosticCallback for T where T: Fn(&Diagnostic) + KotoSend + KotoSync {}
+
+/// The kind of a [Diagnostic]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DiagnosticKind {
+    /// A function annotated with `@deprecated` was called
+    Deprecated,
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagnosticKind::Deprecated => f.write_str("Deprecated"),
+        }
+    }
+}
+
+/// A location in a script's source
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SourceLocation {
+    /// The path of the script, if it was loaded from a file
+    pub path: Option<PathBuf>,
+    /// The location's span in the script
+    pub span: Span,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let line = self.span.start.line + 1;
+        match &self.path {
+            Some(path) => write!(f, "{}: {line}", path.display()),
+            None => write!(f, "{line}"),
+        }
+    }
+}
+
+/// A report made by the runtime while running a script
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    /// The diagnostic's kind
+    pub kind: DiagnosticKind,
+    /// The diagnostic's message, e.g. the message provided with a `@deprecated` annotation
+    pub message: String,
+    /// The location in the script that caused the report, or `None` if it was caused by the host
+    pub location: Option<SourceLocation>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(location) = &self.location {
+            write!(f, "[{location}] ")?;
+        }
+        write!(f, "{}: {}", self.kind, self.message)
+    }
+}
+
+/// Diagnostics reported by the runtime while running scripts
+///
+/// Calls to functions annotated with `@deprecated` are reported once per call site, so the host
+/// can keep track of the remaining callers of a deprecated function without the script's output
+/// being affected.
+///
+/// Reports are passed to the callback registered with [Diagnostics::set_callback], or if no
+/// callback has been registered they're queued until they're taken with [Diagnostics::take].
+///
+/// The diagnostics are shared by all VMs that share the runtime's context, and clones of
+/// `Diagnostics` refer to the same underlying state.
+#[derive(Clone, Default)]
+pub struct Diagnostics(PtrMut<DiagnosticsState>);
+
+#[derive(Default)]
+struct DiagnosticsState {
+    callback: Option<Ptr<dyn DiagnosticCallback>>,
+    // Reports that were made while no callback was registered
+    queued: Vec<Diagnostic>,
+    // The reports that have already been made, used to only report each call site once
+    reported: FxHashSet<Diagnostic>,
+}
+
+impl Diagnostics {
+    /// Sets the callback that's called with each report
+    ///
+    /// Any previously registered callback is replaced.
+    pub fn set_callback(&self, callback: impl DiagnosticCallback + 'static) {
+        self.0.borrow_mut().callback = Some(make_ptr!(callback));
+    }
+
+    /// Removes the registered callback, returning true if a callback was removed
+    pub fn remove_callback(&self) -> bool {
+        self.0.borrow_mut().callback.take().is_some()
+    }
+
+    /// Takes the queued reports, in the order that they were made
+    pub fn take(&self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.0.borrow_mut().queued)
+    }
+
+    /// Makes a report, unless a matching report has already been made
+    ///
+    /// Returns true if the report was made.
+    pub fn report(&self, diagnostic: Diagnostic) -> bool {
+        let callback = {
+            let mut state = self.0.borrow_mut();
+            if !state.reported.insert(diagnostic.clone()) {
+                return false;
+            }
+            match &state.callback {
+                Some(callback) => callback.clone(),
+                None => {
+                    state.queued.push(diagnostic);
+                    return true;
+                }
+            }
+        };
+
+        // The state's borrow is released before calling the callback,
+        // allowing the callback to make use of the diagnostics.
+        callback(&diagnostic);
+        true
+    }
+
+    /// Clears the record of reports that have been made, along with any queued reports
+    ///
+    /// Following a reset, each call site will be reported again when it's next encountered.
+    pub fn clear_reported(&self) {
+        let mut state = self.0.borrow_mut();
+        state.queued.clear();
+        state.reported.clear();
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{prelude::*, Ptr};
 use koto_bytecode::{Chunk, LoaderError};
-use koto_parser::format_source_excerpt;
+use koto_parser::{format_source_excerpt, Span};
 use std::{error, fmt, time::Duration};
 use thiserror::Error;
 
@@ -23,6 +23,10 @@ pub enum ErrorKind {
     },
     #[error("Execution timed out (the limit of {} seconds was reached)", .0.as_secs_f64())]
     Timeout(Duration),
+    #[error("Execution stopped (the limit of {0} instructions was reached)")]
+    InstructionLimit(u64),
+    #[error("Execution stopped (the memory limit of {0} bytes was exceeded)")]
+    MemoryLimit(usize),
     #[error("Expected {expected}, but found '{}'", get_value_types(unexpected))]
     UnexpectedType {
         expected: String,
@@ -101,6 +105,22 @@ impl Error {
         self
     }
 
+    /// Returns the span in the source where the error occurred, if available
+    ///
+    /// For compilation errors the span of the error in the compiled script is returned,
+    /// otherwise the span of the instruction that threw the error is returned.
+    pub fn source_span(&self) -> Option<Span> {
+        match &self.error {
+            ErrorKind::CompileError(error) => error.source.as_ref().map(|source| source.span),
+            _ => self
+                .trace
+                .first()
+                .and_then(|ErrorFrame { chunk, instruction }| {
+                    chunk.debug_info.get_source_span(*instruction)
+                }),
+        }
+    }
+
     /// Returns true if the error was caused by the parser expecting indentation
     pub fn is_indentation_error(&self) -> bool {
         match &self.error {
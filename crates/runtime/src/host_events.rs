@@ -0,0 +1,173 @@
+use crate::{prelude::*, KotoSend, KotoSync, Ptr, PtrMut, Result};
+use indexmap::IndexMap;
+use rustc_hash::FxHasher;
+use std::{collections::VecDeque, hash::BuildHasherDefault};
+
+type FxIndexMap<K, V> = IndexMap<K, V, BuildHasherDefault<FxHasher>>;
+
+/// The trait used by callbacks registered with [HostEvents::register_callback]
+pub trait HostCallback: Fn(&KValue) -> Result<KValue> + KotoSend + KotoSync {}
+
+// Implement the trait for any matching function
+impl<T> HostCallback for T where T: Fn(&KValue) -> Result<KValue> + KotoSend + KotoSync {}
+
+/// A named event, along with its data
+#[derive(Clone)]
+pub struct HostEvent {
+    /// The event's name
+    pub name: KString,
+    /// The data that was provided with the event, or `Null` if no data was provided
+    pub data: KValue,
+}
+
+/// Named events passed between the host application and its scripts
+///
+/// Events flow in both directions:
+///   - Scripts emit events to the host with `koto.host.emit`. When the host has registered a
+///     callback for the event with [HostEvents::register_callback], then the callback is called
+///     immediately, and its result is returned to the script. Events without a registered callback
+///     can optionally be queued (see [HostEvents::set_queue_unhandled]), and then taken by the host
+///     with [HostEvents::take_emitted].
+///   - Scripts register handlers for host events with `koto.host.on`. The host can either
+///     dispatch events to the handlers immediately with [KotoVm::dispatch_host_event], or post
+///     events with [HostEvents::post] to be dispatched later, either by the host with
+///     [KotoVm::process_host_events] or by the script with `koto.host.process_events`.
+///
+/// The host events are shared by all VMs that share the runtime's context, and clones of
+/// `HostEvents` refer to the same underlying state, so a clone can be kept by the host,
+/// e.g. to post events from elsewhere in the application.
+#[derive(Clone, Default)]
+pub struct HostEvents(PtrMut<HostEventsState>);
+
+#[derive(Default)]
+struct HostEventsState {
+    // Callbacks registered by the host, called when the script emits an event
+    callbacks: FxIndexMap<String, Ptr<dyn HostCallback>>,
+    // Handlers registered by scripts, called when the host dispatches an event
+    handlers: FxIndexMap<String, Vec<KValue>>,
+    // Events posted by the host that haven't yet been dispatched to the script
+    posted: VecDeque<HostEvent>,
+    // Events emitted by the script that didn't have a registered callback
+    emitted: VecDeque<HostEvent>,
+    // Whether or not emitted events without a callback should be queued
+    queue_unhandled: bool,
+}
+
+impl HostEvents {
+    /// Registers a callback that's called when a script emits the named event
+    ///
+    /// Any previously registered callback for the event is replaced.
+    pub fn register_callback(&self, name: &str, callback: impl HostCallback + 'static) {
+        self.0
+            .borrow_mut()
+            .callbacks
+            .insert(name.into(), make_ptr!(callback));
+    }
+
+    /// Removes the callback for the named event, returning true if a callback was removed
+    pub fn remove_callback(&self, name: &str) -> bool {
+        self.0.borrow_mut().callbacks.shift_remove(name).is_some()
+    }
+
+    /// Returns true if a callback has been registered for the named event
+    pub fn has_callback(&self, name: &str) -> bool {
+        self.0.borrow().callbacks.contains_key(name)
+    }
+
+    /// Sets whether or not emitted events without a registered callback should be queued
+    ///
+    /// When queueing is disabled (the default), emitting an event without a callback results in
+    /// an error being thrown in the script.
+    pub fn set_queue_unhandled(&self, enabled: bool) {
+        self.0.borrow_mut().queue_unhandled = enabled;
+    }
+
+    /// Takes the queued events that were emitted by scripts, in the order they were emitted
+    pub fn take_emitted(&self) -> Vec<HostEvent> {
+        self.0.borrow_mut().emitted.drain(..).collect()
+    }
+
+    /// Posts an event to be dispatched to the script's handlers later
+    ///
+    /// See [KotoVm::process_host_events].
+    pub fn post(&self, name: &str, data: impl Into<KValue>) {
+        self.0.borrow_mut().posted.push_back(HostEvent {
+            name: name.into(),
+            data: data.into(),
+        });
+    }
+
+    /// Returns the number of posted events that haven't yet been dispatched
+    pub fn pending(&self) -> usize {
+        self.0.borrow().posted.len()
+    }
+
+    /// Returns true if the script has registered a handler for the named event
+    pub fn has_handler(&self, name: &str) -> bool {
+        self.0
+            .borrow()
+            .handlers
+            .get(name)
+            .is_some_and(|handlers| !handlers.is_empty())
+    }
+
+    /// Removes all registered handlers, callbacks, and queued events
+    pub fn clear(&self) {
+        let mut state = self.0.borrow_mut();
+        state.callbacks.clear();
+        state.handlers.clear();
+        state.posted.clear();
+        state.emitted.clear();
+    }
+
+    // Called when the runtime is reset, removing the handlers that were registered by scripts
+    pub(crate) fn clear_handlers(&self) {
+        self.0.borrow_mut().handlers.clear();
+    }
+
+    // Called by `koto.host.emit`
+    pub(crate) fn emit(&self, name: KString, data: KValue) -> Result<KValue> {
+        let callback = {
+            let mut state = self.0.borrow_mut();
+            match state.callbacks.get(name.as_str()) {
+                Some(callback) => callback.clone(),
+                None if state.queue_unhandled => {
+                    state.emitted.push_back(HostEvent { name, data });
+                    return Ok(KValue::Null);
+                }
+                None => return runtime_error!("no host callback has been registered for '{name}'"),
+            }
+        };
+        // The callback is called without the state being borrowed,
+        // allowing the callback to make use of the host events.
+        callback(&data)
+    }
+
+    // Called by `koto.host.on`
+    pub(crate) fn add_handler(&self, name: KString, handler: KValue) {
+        self.0
+            .borrow_mut()
+            .handlers
+            .entry(name.to_string())
+            .or_default()
+            .push(handler);
+    }
+
+    // Called by `koto.host.off`
+    pub(crate) fn remove_handlers(&self, name: &str) -> bool {
+        self.0.borrow_mut().handlers.shift_remove(name).is_some()
+    }
+
+    pub(crate) fn handlers(&self, name: &str) -> Vec<KValue> {
+        self.0
+            .borrow()
+            .handlers
+            .get(name)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn next_posted(&self) -> Option<HostEvent> {
+        self.0.borrow_mut().posted.pop_front()
+    }
+}
@@ -14,6 +14,29 @@ pub trait KotoFile: KotoRead + KotoWrite + KotoSend + KotoSync {
     fn seek(&self, _position: u64) -> Result<()> {
         runtime_error!("unsupported for this file type")
     }
+
+    /// Returns true if the file is an interactive terminal
+    ///
+    /// Terminals are expected to support [KotoRead::read_key] and [KotoRead::read_line_hidden],
+    /// which are used by interactive functions like `io.select` and `io.prompt_password`.
+    fn is_terminal(&self) -> bool {
+        false
+    }
+}
+
+/// A key press that was read from an interactive terminal, see [KotoRead::read_key]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyInput {
+    /// The up arrow key
+    Up,
+    /// The down arrow key
+    Down,
+    /// The enter key
+    Enter,
+    /// A key that cancels the current input, e.g. the escape key
+    Cancel,
+    /// Any other key
+    Other,
 }
 
 /// A trait that defines the read operations of a [KotoFile]
@@ -29,6 +52,22 @@ pub trait KotoRead {
     fn read_to_string(&self) -> Result<String> {
         runtime_error!("unsupported for this file type")
     }
+
+    /// Returns the next line without echoing the input, e.g. when reading a password
+    ///
+    /// As with [KotoRead::read_line], the line should include the newline characters.
+    ///
+    /// The default implementation falls back to [KotoRead::read_line].
+    fn read_line_hidden(&self) -> Result<Option<String>> {
+        self.read_line()
+    }
+
+    /// Waits for the next key press
+    ///
+    /// This only needs to be supported by files that are terminals, see [KotoFile::is_terminal].
+    fn read_key(&self) -> Result<KeyInput> {
+        runtime_error!("unsupported for this file type")
+    }
 }
 
 /// A trait that defines the write operations of a [KotoFile]
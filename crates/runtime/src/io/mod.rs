@@ -4,6 +4,6 @@ mod stdio;
 
 pub use self::{
     buffered_file::BufferedFile,
-    file::{KotoFile, KotoRead, KotoWrite},
+    file::{KeyInput, KotoFile, KotoRead, KotoWrite},
     stdio::{DefaultStderr, DefaultStdin, DefaultStdout},
 };
@@ -2,9 +2,13 @@
 
 #![warn(missing_docs)]
 
+mod diagnostics;
 mod display_context;
 mod error;
+mod host_events;
 mod io;
+mod pinned_values;
+mod signals;
 mod types;
 mod vm;
 
@@ -13,17 +17,27 @@ pub mod prelude;
 mod send_sync;
 
 pub use crate::{
+    diagnostics::{Diagnostic, DiagnosticCallback, DiagnosticKind, Diagnostics, SourceLocation},
     display_context::DisplayContext,
     error::{type_error, type_error_with_slice, Error, ErrorKind, Result},
-    io::{BufferedFile, DefaultStderr, DefaultStdin, DefaultStdout, KotoFile, KotoRead, KotoWrite},
+    host_events::{HostCallback, HostEvent, HostEvents},
+    io::{
+        BufferedFile, DefaultStderr, DefaultStdin, DefaultStdout, KeyInput, KotoFile, KotoRead,
+        KotoWrite,
+    },
+    pinned_values::{PinnedValues, ValueHandle},
     send_sync::{KotoSend, KotoSync},
+    signals::{Signal, Signals},
     types::{
         BinaryOp, CallContext, IsIterable, KCaptureFunction, KFunction, KIterator, KIteratorOutput,
         KList, KMap, KNativeFunction, KNumber, KObject, KRange, KString, KTuple, KValue, KotoCopy,
         KotoEntries, KotoFunction, KotoHasher, KotoIterator, KotoObject, KotoType, MetaKey,
         MetaMap, MethodContext, UnaryOp, ValueKey, ValueMap, ValueVec,
     },
-    vm::{CallArgs, KotoVm, KotoVmSettings, ModuleImportedCallback, ReturnOrYield},
+    vm::{
+        CallArgs, Finalizer, KotoVm, KotoVmSettings, MemoryLimit, MemoryUsageCallback,
+        ModuleImportedCallback, ReturnOrYield, UnloadedModule,
+    },
 };
 pub use koto_derive as derive;
 pub use koto_memory::{make_ptr, make_ptr_mut, Borrow, BorrowMut, KCell, Ptr, PtrMut};
@@ -0,0 +1,101 @@
+use crate::{prelude::*, PtrMut};
+use rustc_hash::FxHashMap;
+
+/// A handle to a value that has been pinned with [PinnedValues::pin]
+///
+/// Handles are small `Copy` values that can be freely passed around by the host, e.g. stored in
+/// host callbacks, or converted to an integer with [ValueHandle::id] to be passed across an FFI
+/// boundary.
+///
+/// Handles aren't reused after their values are unpinned, so a stale handle won't refer to a
+/// different value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ValueHandle(u64);
+
+impl ValueHandle {
+    /// Returns the handle's id
+    pub fn id(&self) -> u64 {
+        self.0
+    }
+
+    /// Makes a handle from an id that was previously returned by [ValueHandle::id]
+    pub fn from_id(id: u64) -> Self {
+        Self(id)
+    }
+}
+
+/// Values that have been pinned by the host, giving the host stable handles to the values
+///
+/// Pinned values are kept alive by the runtime until they're unpinned, or until the runtime is
+/// dropped, so the host doesn't need to manage the lifetimes of the values itself. The pinned
+/// values are held by the runtime's context rather than by a module, so they remain available
+/// when a module is unloaded, or when the runtime is reset with [KotoVm::reset].
+///
+/// The pinned values are shared by all VMs that share the runtime's context, and clones of
+/// `PinnedValues` refer to the same underlying state, so a clone can be kept by the host,
+/// e.g. to access pinned values from within host callbacks.
+#[derive(Clone, Default)]
+pub struct PinnedValues(PtrMut<PinnedValuesState>);
+
+#[derive(Default)]
+struct PinnedValuesState {
+    values: FxHashMap<u64, KValue>,
+    // The id that will be used for the next pinned value
+    next_id: u64,
+}
+
+impl PinnedValues {
+    /// Pins a value, returning a handle that can be used to access the value
+    pub fn pin(&self, value: impl Into<KValue>) -> ValueHandle {
+        let mut state = self.0.borrow_mut();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.values.insert(id, value.into());
+        ValueHandle(id)
+    }
+
+    /// Returns the pinned value for the handle, or `None` if the value has been unpinned
+    pub fn get(&self, handle: ValueHandle) -> Option<KValue> {
+        self.0.borrow().values.get(&handle.0).cloned()
+    }
+
+    /// Replaces the pinned value for the handle, returning the previous value
+    ///
+    /// `None` is returned if the value has been unpinned, in which case the handle isn't
+    /// reinstated.
+    pub fn replace(&self, handle: ValueHandle, value: impl Into<KValue>) -> Option<KValue> {
+        self.0
+            .borrow_mut()
+            .values
+            .get_mut(&handle.0)
+            .map(|pinned| std::mem::replace(pinned, value.into()))
+    }
+
+    /// Unpins the value for the handle, returning the value if it was pinned
+    pub fn unpin(&self, handle: ValueHandle) -> Option<KValue> {
+        self.0.borrow_mut().values.remove(&handle.0)
+    }
+
+    /// Returns true if the handle refers to a pinned value
+    pub fn is_pinned(&self, handle: ValueHandle) -> bool {
+        self.0.borrow().values.contains_key(&handle.0)
+    }
+
+    /// Returns the number of pinned values
+    pub fn len(&self) -> usize {
+        self.0.borrow().values.len()
+    }
+
+    /// Returns true if there are no pinned values
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().values.is_empty()
+    }
+
+    /// Unpins all values
+    ///
+    /// Handles that were previously returned by [PinnedValues::pin] will no longer refer to
+    /// values.
+    pub fn clear(&self) {
+        self.0.borrow_mut().values.clear();
+    }
+}
@@ -0,0 +1,114 @@
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+/// Process signals that can be handled by scripts with `os.on_signal`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum Signal {
+    /// `SIGINT`, e.g. sent when Ctrl-C is pressed in a terminal, named `int` in scripts
+    Interrupt,
+    /// `SIGTERM`, a request for the process to shut down, named `term` in scripts
+    Terminate,
+    /// `SIGHUP`, e.g. sent when the process's terminal is closed, named `hup` in scripts
+    Hangup,
+    /// `SIGUSR1`, named `usr1` in scripts
+    User1,
+    /// `SIGUSR2`, named `usr2` in scripts
+    User2,
+}
+
+impl Signal {
+    /// All signals that can be handled by scripts
+    pub const ALL: [Signal; 5] = [
+        Signal::Interrupt,
+        Signal::Terminate,
+        Signal::Hangup,
+        Signal::User1,
+        Signal::User2,
+    ];
+
+    /// The signal's name, as used by scripts
+    pub fn name(&self) -> &'static str {
+        match self {
+            Signal::Interrupt => "int",
+            Signal::Terminate => "term",
+            Signal::Hangup => "hup",
+            Signal::User1 => "usr1",
+            Signal::User2 => "usr2",
+        }
+    }
+
+    /// Returns the signal that matches the given name, or `None` if the name isn't recognized
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|signal| signal.name() == name)
+    }
+
+    fn mask(&self) -> u32 {
+        1 << (*self as u32)
+    }
+}
+
+/// Signals that have been raised by the host, waiting to be passed to the script's handlers
+///
+/// The host is responsible for installing the process's signal handlers, which should then call
+/// [Signals::raise]. The VM checks for raised signals between instructions, and calls the handlers
+/// that were registered by the script with `os.on_signal`.
+///
+/// `Signals` only makes use of atomic operations, so [Signals::raise] can be safely called from
+/// other threads, or from within a process signal handler.
+///
+/// The signals are shared by all VMs that share the runtime's context, and clones of `Signals`
+/// refer to the same underlying state.
+#[derive(Clone, Default)]
+pub struct Signals(Arc<SignalsState>);
+
+#[derive(Default)]
+struct SignalsState {
+    // The signals that have been raised and not yet handled
+    pending: AtomicU32,
+    // The signals that have at least one handler registered by the script
+    handled: AtomicU32,
+}
+
+impl Signals {
+    /// Raises a signal, which will be passed to the script's handlers
+    ///
+    /// Raising a signal that's already pending has no additional effect.
+    pub fn raise(&self, signal: Signal) {
+        self.0.pending.fetch_or(signal.mask(), Ordering::SeqCst);
+    }
+
+    /// Returns true if the script has registered a handler for the signal
+    ///
+    /// Hosts can use this to decide whether or not a process signal should be passed to the script,
+    /// e.g. falling back to the signal's default behaviour when the script has no handler.
+    pub fn is_handled(&self, signal: Signal) -> bool {
+        self.0.handled.load(Ordering::SeqCst) & signal.mask() != 0
+    }
+
+    /// Returns true if any signals have been raised that haven't yet been handled
+    pub fn is_pending(&self) -> bool {
+        self.0.pending.load(Ordering::Relaxed) != 0
+    }
+
+    // Called when the script registers a handler
+    pub(crate) fn set_handled(&self, signal: Signal) {
+        self.0.handled.fetch_or(signal.mask(), Ordering::SeqCst);
+    }
+
+    // Called when the runtime is reset, and the script's handlers have been removed
+    pub(crate) fn clear_handled(&self) {
+        self.0.handled.store(0, Ordering::SeqCst);
+    }
+
+    // Takes the pending signals, in the order that they're defined in `Signal`
+    pub(crate) fn take_pending(&self) -> Vec<Signal> {
+        let pending = self.0.pending.swap(0, Ordering::SeqCst);
+        Signal::ALL
+            .into_iter()
+            .filter(|signal| pending & signal.mask() != 0)
+            .collect()
+    }
+}
@@ -28,6 +28,9 @@ pub struct KFunction {
     /// executes the function's body for each iteration step, pausing when a yield instruction is
     /// encountered. See Vm::call_generator and Iterable::Generator.
     pub generator: bool,
+    /// If the function has been annotated with `@deprecated`, then calls to the function will be
+    /// reported to the runtime's [Diagnostics](crate::Diagnostics).
+    pub deprecated: bool,
 }
 
 /// A Koto function with captured values
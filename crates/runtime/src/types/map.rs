@@ -14,18 +14,34 @@ type ValueMapType = IndexMap<ValueKey, KValue, BuildHasherDefault<KotoHasher>>;
 /// The (ValueKey -> Value) 'data' hashmap used by the Koto runtime
 ///
 /// See also: [KMap]
-#[derive(Clone, Default)]
-pub struct ValueMap(ValueMapType);
+#[derive(Default)]
+pub struct ValueMap {
+    map: ValueMapType,
+    // True if the map has been sealed against modification by scripts, see [KotoVm::seal_map]
+    sealed: bool,
+}
 
 impl ValueMap {
     /// Creates a new DataMap with the given capacity
     pub fn with_capacity(capacity: usize) -> Self {
-        Self(ValueMapType::with_capacity_and_hasher(
+        Self::from(ValueMapType::with_capacity_and_hasher(
             capacity,
             Default::default(),
         ))
     }
 
+    /// Returns true if the map has been sealed
+    ///
+    /// See [KotoVm::seal_map].
+    pub fn is_sealed(&self) -> bool {
+        self.sealed
+    }
+
+    /// Seals the map, see [KotoVm::seal_map]
+    pub(crate) fn seal(&mut self) {
+        self.sealed = true;
+    }
+
     /// Makes a new ValueMap containing a slice of the map's elements
     pub fn make_data_slice(&self, range: impl RangeBounds<usize>) -> Option<Self> {
         self.get_range(range).map(|entries| {
@@ -38,23 +54,36 @@ impl ValueMap {
     }
 }
 
+// Copies of a sealed map aren't sealed
+impl Clone for ValueMap {
+    fn clone(&self) -> Self {
+        Self::from(self.map.clone())
+    }
+}
+
+impl From<ValueMapType> for ValueMap {
+    fn from(map: ValueMapType) -> Self {
+        Self { map, sealed: false }
+    }
+}
+
 impl Deref for ValueMap {
     type Target = ValueMapType;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.map
     }
 }
 
 impl DerefMut for ValueMap {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.map
     }
 }
 
 impl FromIterator<(ValueKey, KValue)> for ValueMap {
     fn from_iter<T: IntoIterator<Item = (ValueKey, KValue)>>(iter: T) -> ValueMap {
-        Self(ValueMapType::from_iter(iter))
+        Self::from(ValueMapType::from_iter(iter))
     }
 }
 
@@ -188,6 +217,11 @@ impl KMap {
         self.meta = None;
     }
 
+    /// Returns the number of references currently held to the map's data
+    pub fn ref_count(&self) -> usize {
+        PtrMut::ref_count(&self.data)
+    }
+
     /// Returns true if the provided KMap occupies the same memory address
     pub fn is_same_instance(&self, other: &Self) -> bool {
         PtrMut::ptr_eq(&self.data, &other.data)
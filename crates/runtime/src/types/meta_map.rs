@@ -85,6 +85,18 @@ pub enum MetaKey {
     ///
     /// Used to define a function that will be run when a module is first imported.
     Main,
+    /// A command function
+    ///
+    /// e.g. `@command build`
+    ///
+    /// Commands are entry points that can be selected when running a script,
+    /// see `Koto::run_command`.
+    Command(KString),
+    /// `@repl_display`
+    ///
+    /// Used to define a function that provides a rich representation of the value when it's
+    /// displayed in a REPL, see `Koto::value_to_repl_string`.
+    ReplDisplay,
     /// `@type`
     ///
     /// Provides a [KString](crate::KString) that declares the value's type.
@@ -156,6 +168,16 @@ pub enum BinaryOp {
     Equal,
     /// `@!=`
     NotEqual,
+    /// `@&`
+    BitwiseAnd,
+    /// `@|`
+    BitwiseOr,
+    /// `@^`
+    BitwiseXor,
+    /// `@<<`
+    ShiftLeft,
+    /// `@>>`
+    ShiftRight,
     /// `@[]`
     Index,
 }
@@ -184,6 +206,11 @@ impl fmt::Display for BinaryOp {
                 GreaterOrEqual => ">=",
                 Equal => "==",
                 NotEqual => "!=",
+                BitwiseAnd => "&",
+                BitwiseOr => "|",
+                BitwiseXor => "^",
+                ShiftLeft => "<<",
+                ShiftRight => ">>",
                 Index => "[]",
             }
         )
@@ -231,6 +258,11 @@ pub fn meta_id_to_key(id: MetaKeyId, name: Option<KString>) -> Result<MetaKey> {
         MetaKeyId::GreaterOrEqual => MetaKey::BinaryOp(GreaterOrEqual),
         MetaKeyId::Equal => MetaKey::BinaryOp(Equal),
         MetaKeyId::NotEqual => MetaKey::BinaryOp(NotEqual),
+        MetaKeyId::BitwiseAnd => MetaKey::BinaryOp(BitwiseAnd),
+        MetaKeyId::BitwiseOr => MetaKey::BinaryOp(BitwiseOr),
+        MetaKeyId::BitwiseXor => MetaKey::BinaryOp(BitwiseXor),
+        MetaKeyId::ShiftLeft => MetaKey::BinaryOp(ShiftLeft),
+        MetaKeyId::ShiftRight => MetaKey::BinaryOp(ShiftRight),
         MetaKeyId::Index => MetaKey::BinaryOp(Index),
         MetaKeyId::Iterator => MetaKey::UnaryOp(Iterator),
         MetaKeyId::Next => MetaKey::UnaryOp(Next),
@@ -247,6 +279,10 @@ pub fn meta_id_to_key(id: MetaKeyId, name: Option<KString>) -> Result<MetaKey> {
         MetaKeyId::PreTest => MetaKey::PreTest,
         MetaKeyId::PostTest => MetaKey::PostTest,
         MetaKeyId::Main => MetaKey::Main,
+        MetaKeyId::Command => {
+            MetaKey::Command(name.ok_or_else(|| Error::from("Missing name for command"))?)
+        }
+        MetaKeyId::ReplDisplay => MetaKey::ReplDisplay,
         MetaKeyId::Type => MetaKey::Type,
         MetaKeyId::Base => MetaKey::Base,
         MetaKeyId::Invalid => return runtime_error!("Invalid MetaKeyId"),
@@ -118,6 +118,31 @@ impl KNumber {
             Self::I64(n) => n,
         }
     }
+
+    /// Shifts the number's bits to the left, returning `None` if `bits` is negative
+    ///
+    /// Floats are truncated to integers before shifting, and shifting by 64 or more bits results
+    /// in zero.
+    pub fn shift_left(self, bits: KNumber) -> Option<KNumber> {
+        let n = i64::from(self);
+        let bits = shift_amount(bits)?;
+        Some(n.checked_shl(bits).unwrap_or(0).into())
+    }
+
+    /// Performs an arithmetic shift of the number's bits to the right, returning `None` if `bits`
+    /// is negative
+    ///
+    /// Floats are truncated to integers before shifting, and shifting by 64 or more bits results
+    /// in either zero or -1, depending on the number's sign.
+    pub fn shift_right(self, bits: KNumber) -> Option<KNumber> {
+        let n = i64::from(self);
+        let bits = shift_amount(bits)?;
+        Some(
+            n.checked_shr(bits)
+                .unwrap_or(if n < 0 { -1 } else { 0 })
+                .into(),
+        )
+    }
 }
 
 impl fmt::Debug for KNumber {
@@ -429,3 +454,53 @@ impl ops::Div for &KNumber {
         }
     }
 }
+
+// The bitwise operators work with integers, with floats truncated towards zero before the
+// operation is performed (saturating at the bounds of i64, with NaN converted to zero).
+macro_rules! bitwise_op {
+    ($trait:ident, $fn:ident, $op:tt) => {
+        impl ops::$trait for KNumber {
+            type Output = KNumber;
+
+            fn $fn(self, other: KNumber) -> KNumber {
+                KNumber::I64(i64::from(self) $op i64::from(other))
+            }
+        }
+
+        impl ops::$trait for &KNumber {
+            type Output = KNumber;
+
+            fn $fn(self, other: &KNumber) -> KNumber {
+                KNumber::I64(i64::from(self) $op i64::from(other))
+            }
+        }
+    };
+}
+
+bitwise_op!(BitAnd, bitand, &);
+bitwise_op!(BitOr, bitor, |);
+bitwise_op!(BitXor, bitxor, ^);
+
+impl ops::Not for KNumber {
+    type Output = KNumber;
+
+    fn not(self) -> KNumber {
+        KNumber::I64(!i64::from(self))
+    }
+}
+
+impl ops::Not for &KNumber {
+    type Output = KNumber;
+
+    fn not(self) -> KNumber {
+        KNumber::I64(!i64::from(self))
+    }
+}
+
+// Converts a shift amount into a u32, capped at 64
+fn shift_amount(bits: KNumber) -> Option<u32> {
+    match i64::from(bits) {
+        n if n < 0 => None,
+        n => Some(n.min(64) as u32),
+    }
+}
@@ -177,6 +177,31 @@ pub trait KotoObject: KotoType + KotoCopy + KotoEntries + KotoSend + KotoSync +
         unimplemented_error("@%", self.type_string())
     }
 
+    /// The `&` bitwise and operator
+    fn bitwise_and(&self, _rhs: &KValue) -> Result<KValue> {
+        unimplemented_error("@&", self.type_string())
+    }
+
+    /// The `|` bitwise or operator
+    fn bitwise_or(&self, _rhs: &KValue) -> Result<KValue> {
+        unimplemented_error("@|", self.type_string())
+    }
+
+    /// The `^` bitwise xor operator
+    fn bitwise_xor(&self, _rhs: &KValue) -> Result<KValue> {
+        unimplemented_error("@^", self.type_string())
+    }
+
+    /// The `<<` left shift operator
+    fn shift_left(&self, _rhs: &KValue) -> Result<KValue> {
+        unimplemented_error("@<<", self.type_string())
+    }
+
+    /// The `>>` right shift operator
+    fn shift_right(&self, _rhs: &KValue) -> Result<KValue> {
+        unimplemented_error("@>>", self.type_string())
+    }
+
     /// The `+=` in-place addition operator
     fn add_assign(&mut self, _rhs: &KValue) -> Result<()> {
         unimplemented_error("@+=", self.type_string())
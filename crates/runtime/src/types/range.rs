@@ -29,6 +29,8 @@ enum Inner {
     },
     // Placing ranges with i64 bounds to the heap allows the size of KRange to be 16 bytes
     BoundedLarge(Ptr<Bounded64>),
+    // Stepped ranges are also placed on the heap, see KRange::with_step
+    Stepped(Ptr<Stepped>),
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
@@ -44,6 +46,32 @@ impl From<Bounded64> for Inner {
     }
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+struct Stepped {
+    start: i64,
+    end: i64,
+    inclusive: bool,
+    // The step is negative for descending ranges
+    step: i64,
+}
+
+impl Stepped {
+    // Returns the last value that will be produced by the range, if any
+    fn last(&self) -> Option<i64> {
+        let direction = self.step.signum();
+        let limit = if self.inclusive {
+            self.end
+        } else {
+            self.end - direction
+        };
+        if (limit - self.start) * direction < 0 {
+            None
+        } else {
+            Some(self.start + (limit - self.start) / self.step * self.step)
+        }
+    }
+}
+
 impl KRange {
     /// Initializes a range with the given start and end bounds
     ///
@@ -77,6 +105,29 @@ impl KRange {
         }
     }
 
+    /// Returns a copy of the range that steps through its values by the given amount
+    ///
+    /// The range needs to be bounded, and the step needs to be greater than zero.
+    /// Descending ranges step downwards from the start of the range.
+    pub fn with_step(&self, step: i64) -> Result<Self, Error> {
+        if step <= 0 {
+            return runtime_error!("Expected a step greater than zero, found '{step}'");
+        }
+
+        match (self.start(), self.end()) {
+            (Some(start), Some((end, inclusive))) => Ok(Self(Inner::Stepped(
+                Stepped {
+                    start,
+                    end,
+                    inclusive,
+                    step: if start <= end { step } else { -step },
+                }
+                .into(),
+            ))),
+            _ => runtime_error!("A step can only be used with bounded ranges (range: {self})"),
+        }
+    }
+
     /// Returns the start of the range
     pub fn start(&self) -> Option<i64> {
         use Inner::*;
@@ -84,6 +135,7 @@ impl KRange {
             From { start } => Some(*start),
             Bounded { start, .. } => Some(*start as i64),
             BoundedLarge(r) => Some(r.start),
+            Stepped(r) => Some(r.start),
             _ => None,
         }
     }
@@ -97,6 +149,15 @@ impl KRange {
             To { end, inclusive } => Some((*end, *inclusive)),
             Bounded { end, inclusive, .. } => Some((*end as i64, *inclusive)),
             BoundedLarge(r) => Some((r.end, r.inclusive)),
+            Stepped(r) => Some((r.end, r.inclusive)),
+            _ => None,
+        }
+    }
+
+    /// Returns the range's step if one has been set with [KRange::with_step]
+    pub fn step(&self) -> Option<i64> {
+        match &self.0 {
+            Inner::Stepped(r) => Some(r.step.abs()),
             _ => None,
         }
     }
@@ -126,6 +187,7 @@ impl KRange {
                     inclusive,
                 } => sort_bounded(*start as i64, *end as i64, *inclusive),
                 BoundedLarge(r) => sort_bounded(r.start, r.end, r.inclusive),
+                Stepped(r) => sort_bounded(r.start, r.end, r.inclusive),
                 Unbounded => (i64::MIN, i64::MAX),
             }
         };
@@ -134,9 +196,15 @@ impl KRange {
     }
 
     /// Returns true if the provided number is within the range
+    ///
+    /// For stepped ranges, the number also needs to be one of the range's steps.
     pub fn contains(&self, n: KNumber) -> bool {
         let n: i64 = if n < 0.0 { n.floor() } else { n.ceil() }.into();
-        self.as_sorted_range().contains(&n)
+        let in_bounds = self.as_sorted_range().contains(&n);
+        match &self.0 {
+            Inner::Stepped(r) => in_bounds && (n - r.start) % r.step == 0,
+            _ => in_bounds,
+        }
     }
 
     /// Returns the range translated into non-negative indices, suitable for container access
@@ -176,6 +244,7 @@ impl KRange {
             To { end, .. } => *end > 0,
             Bounded { start, end, .. } => *start <= *end,
             BoundedLarge(r) => r.start <= r.end,
+            Stepped(r) => r.step > 0,
             _ => true,
         }
     }
@@ -183,8 +252,12 @@ impl KRange {
     /// Returns the size of the range if both start and end boundaries are specified
     ///
     /// Descending ranges have a non-negative size, i.e. the size is equal to `start - end`.
+    /// The size of a stepped range is the number of steps that it contains.
     pub fn size(&self) -> Option<usize> {
-        if self.is_bounded() {
+        if let Inner::Stepped(r) = &self.0 {
+            let size = r.last().map_or(0, |last| (last - r.start) / r.step + 1);
+            Some(size as usize)
+        } else if self.is_bounded() {
             let range = self.as_sorted_range();
             Some((range.end - range.start) as usize)
         } else {
@@ -195,7 +268,10 @@ impl KRange {
     /// Returns true if the range has defined start and end boundaries
     pub fn is_bounded(&self) -> bool {
         use Inner::*;
-        matches!(self.0, Bounded { .. } | BoundedLarge { .. })
+        matches!(
+            self.0,
+            Bounded { .. } | BoundedLarge { .. } | Stepped { .. }
+        )
     }
 
     /// Removes and returns the first element in the range.
@@ -257,6 +333,21 @@ impl KRange {
                     }
                 }
             }
+            Stepped(r) => {
+                let r = Ptr::make_mut(r);
+                let result = r.last().map(|_| r.start);
+                if result.is_some() {
+                    match r.start.checked_add(r.step) {
+                        Some(next) => r.start = next,
+                        None => {
+                            // Allow iteration to stop
+                            r.start = r.end;
+                            r.inclusive = false;
+                        }
+                    }
+                }
+                result
+            }
             _ => return runtime_error!("KRange::pop_front can only be used with bounded ranges"),
         };
 
@@ -322,6 +413,16 @@ impl KRange {
                     }
                 }
             }
+            Stepped(r) => {
+                let r = Ptr::make_mut(r);
+                let result = r.last();
+                if let Some(last) = result {
+                    // The next value from the back will be the step before the last value
+                    r.end = last;
+                    r.inclusive = false;
+                }
+                result
+            }
             _ => return runtime_error!("KRange::pop_back can only be used with bounded ranges"),
         };
 
@@ -365,6 +466,10 @@ impl fmt::Display for KRange {
             write!(f, "{end}")?;
         }
 
+        if let Some(step) = self.step() {
+            write!(f, " step {step}")?;
+        }
+
         Ok(())
     }
 }
@@ -414,6 +519,26 @@ mod tests {
         assert!(KRange::from(20..).is_ascending());
     }
 
+    #[test]
+    fn stepped() {
+        let range = KRange::from(0..10).with_step(3).unwrap();
+        assert_eq!(range.size(), Some(4));
+        assert!(range.contains(6.into()));
+        assert!(!range.contains(7.into()));
+        assert_eq!(range.to_string(), "0..10 step 3");
+
+        let mut range = KRange::from(10..=0).with_step(5).unwrap();
+        assert!(!range.is_ascending());
+        assert_eq!(range.size(), Some(3));
+        assert_eq!(range.pop_back().unwrap(), Some(0));
+        assert_eq!(range.pop_front().unwrap(), Some(10));
+        assert_eq!(range.pop_front().unwrap(), Some(5));
+        assert_eq!(range.pop_front().unwrap(), None);
+
+        assert!(KRange::from(0..10).with_step(0).is_err());
+        assert!(KRange::from(0..).with_step(1).is_err());
+    }
+
     #[test]
     fn bounded_large() {
         let start_big = 2_i64.pow(42);
@@ -1,9 +1,11 @@
 use crate::{
-    core_lib::CoreLib,
+    core_lib::{koto::KResult, CoreLib},
     error::{Error, ErrorKind},
     prelude::*,
     types::{meta_id_to_key, value::RegisterSlice},
-    DefaultStderr, DefaultStdin, DefaultStdout, KCaptureFunction, KFunction, Ptr, Result,
+    DefaultStderr, DefaultStdin, DefaultStdout, Diagnostic, DiagnosticKind, Diagnostics,
+    HostEvents, KCaptureFunction, KFunction, PinnedValues, Ptr, Result, Signal, Signals,
+    SourceLocation, ValueHandle,
 };
 use instant::Instant;
 use koto_bytecode::{Chunk, Instruction, InstructionReader, Loader};
@@ -14,6 +16,8 @@ use std::{
     fmt,
     hash::BuildHasherDefault,
     path::{Path, PathBuf},
+    slice,
+    sync::atomic::{AtomicU64, Ordering},
     time::Duration,
 };
 use unicode_segmentation::UnicodeSegmentation;
@@ -55,6 +59,20 @@ struct VmContext {
     loader: KCell<Loader>,
     // The cached export maps of imported modules
     imported_modules: KCell<ModuleCache>,
+    // Finalizers that should be run when the context is dropped, in reverse order of registration
+    finalizers: KCell<Vec<Box<dyn Finalizer>>>,
+    // Events passed between the host and scripts
+    host_events: HostEvents,
+    // Process signals raised by the host
+    signals: Signals,
+    // Values pinned by the host
+    pinned_values: PinnedValues,
+    // Diagnostics reported while running scripts, e.g. calls to deprecated functions
+    diagnostics: Diagnostics,
+    // Handlers registered by scripts with `os.on_signal`, in order of registration
+    signal_handlers: KCell<Vec<(Signal, KValue)>>,
+    // The number of instructions that have been executed, counted when resource limits are set
+    instruction_count: AtomicU64,
 }
 
 impl Default for VmContext {
@@ -66,28 +84,119 @@ impl Default for VmContext {
 impl VmContext {
     fn with_settings(settings: KotoVmSettings) -> Self {
         let core_lib = CoreLib::default();
+        let prelude = core_lib.prelude();
+
+        if settings.seal_core_lib {
+            for value in prelude.data().values() {
+                if let KValue::Map(module) = value {
+                    module.data_mut().seal();
+                }
+            }
+        }
 
         Self {
             settings,
-            prelude: core_lib.prelude(),
+            prelude,
             core_lib,
             loader: Loader::default().into(),
             imported_modules: ModuleCache::default().into(),
+            finalizers: Vec::new().into(),
+            host_events: HostEvents::default(),
+            signals: Signals::default(),
+            pinned_values: PinnedValues::default(),
+            diagnostics: Diagnostics::default(),
+            signal_handlers: Vec::new().into(),
+            instruction_count: AtomicU64::new(0),
+        }
+    }
+
+    fn run_finalizers(&self) {
+        // The finalizers are popped one at a time so that the borrow is released before each
+        // finalizer is called, allowing finalizers to register further finalizers.
+        loop {
+            let finalizer = self.finalizers.borrow_mut().pop();
+            match finalizer {
+                Some(finalizer) => finalizer(),
+                None => break,
+            }
         }
     }
 }
 
+impl Drop for VmContext {
+    fn drop(&mut self) {
+        self.run_finalizers();
+    }
+}
+
 /// The trait used by the 'module imported' callback mechanism
 pub trait ModuleImportedCallback: Fn(&Path) + KotoSend + KotoSync {}
 
 // Implement the trait for any matching function
 impl<T> ModuleImportedCallback for T where T: Fn(&Path) + KotoSend + KotoSync {}
 
+/// The trait used by the callback that reports memory usage for a [MemoryLimit]
+pub trait MemoryUsageCallback: Fn() -> usize + KotoSend + KotoSync {}
+
+// Implement the trait for any matching function
+impl<T> MemoryUsageCallback for T where T: Fn() -> usize + KotoSend + KotoSync {}
+
+/// A limit on the amount of memory that the runtime is allowed to use
+///
+/// The runtime doesn't track its own allocations, so the current memory usage is provided by the
+/// embedder via a callback, e.g. by querying a tracking allocator, or the size of a wasm module's
+/// linear memory.
+pub struct MemoryLimit {
+    /// The maximum number of bytes that are allowed to be in use
+    pub max_bytes: usize,
+    /// A callback that returns the number of bytes that are currently in use
+    pub usage: Box<dyn MemoryUsageCallback>,
+}
+
+/// Information about a module that was unloaded with [KotoVm::unload_module]
+#[derive(Clone, Debug)]
+pub struct UnloadedModule {
+    /// The module's path
+    pub path: PathBuf,
+    /// The number of references to the module's exports map that are still live
+    ///
+    /// e.g. the exports map may be held by a module that imported it, or by the host.
+    pub export_references: usize,
+    /// The number of references to the module's compiled bytecode that are still live
+    ///
+    /// Functions defined in the module refer to the module's bytecode, so a non-zero count
+    /// indicates that some of the module's functions are still reachable.
+    pub chunk_references: usize,
+}
+
+impl UnloadedModule {
+    /// Returns true if no references to the module's exports or bytecode remain
+    pub fn is_released(&self) -> bool {
+        self.export_references == 0 && self.chunk_references == 0
+    }
+}
+
+/// The trait used by finalizers registered with [KotoVm::add_finalizer]
+pub trait Finalizer: FnOnce() + KotoSend + KotoSync {}
+
+// Implement the trait for any matching function
+impl<T> Finalizer for T where T: FnOnce() + KotoSend + KotoSync {}
+
 /// The configurable settings that should be used by the Koto runtime
 pub struct KotoVmSettings {
     /// Whether or not tests should be run when importing modules
     pub run_import_tests: bool,
 
+    /// Whether or not the core library's modules should be sealed
+    ///
+    /// When enabled, scripts are prevented from modifying the contents of core library modules,
+    /// e.g. `list.push = ...` will result in an error being thrown.
+    ///
+    /// Sealed modules can still be extended by the embedder via the Rust API.
+    ///
+    /// See [KotoVm::seal_map].
+    pub seal_core_lib: bool,
+
     /// An optional duration that limits how long execution is allowed to take.
     ///
     /// If the limit is reached without execution ending,
@@ -100,6 +209,25 @@ pub struct KotoVmSettings {
     /// block execution.
     pub execution_limit: Option<Duration>,
 
+    /// An optional limit on the number of instructions that the runtime is allowed to execute
+    ///
+    /// If the limit is reached then an [InstructionLimit](ErrorKind::InstructionLimit) error
+    /// will be returned.
+    ///
+    /// The count is shared by all VMs that share the runtime's context, and accumulates until the
+    /// runtime is reset with [KotoVm::reset]. Unlike the execution limit, the instruction limit is deterministic,
+    /// which makes it useful in environments like wasm where timing may be unreliable.
+    pub instruction_limit: Option<u64>,
+
+    /// An optional limit on the amount of memory that the runtime is allowed to use
+    ///
+    /// If the limit is exceeded then a [MemoryLimit](ErrorKind::MemoryLimit) error will be
+    /// returned.
+    ///
+    /// The memory usage is checked periodically between VM instructions, so a single external
+    /// function call can allocate beyond the limit before execution is stopped.
+    pub memory_limit: Option<MemoryLimit>,
+
     /// An optional callback that is called whenever a module is imported by the runtime
     ///
     /// This allows you to track the runtime's dependencies, which might be useful if you want to
@@ -120,7 +248,10 @@ impl Default for KotoVmSettings {
     fn default() -> Self {
         Self {
             run_import_tests: true,
+            seal_core_lib: true,
             execution_limit: None,
+            instruction_limit: None,
+            memory_limit: None,
             module_imported_callback: None,
             stdin: make_ptr!(DefaultStdin::default()),
             stdout: make_ptr!(DefaultStdout::default()),
@@ -211,6 +342,83 @@ impl KotoVm {
         &self.context.loader
     }
 
+    /// Unloads a previously imported module, removing it from the runtime's module caches
+    ///
+    /// The module is found in the same way as an `import` expression, searching relative to
+    /// `load_from_path`, or the current working directory if no path is provided.
+    ///
+    /// Unloading the module allows it to be released, while also causing the module to be
+    /// reloaded the next time that it's imported. References to the module's exports or functions
+    /// that are still held elsewhere will keep the module alive, and are counted in the
+    /// returned [UnloadedModule].
+    ///
+    /// `None` is returned if the module hadn't been loaded.
+    pub fn unload_module(
+        &self,
+        name: &str,
+        load_from_path: Option<&Path>,
+    ) -> Result<Option<UnloadedModule>> {
+        let path = self
+            .context
+            .loader
+            .borrow()
+            .find_module(name, load_from_path)?;
+
+        let exports = self
+            .context
+            .imported_modules
+            .borrow_mut()
+            .remove(&path)
+            .flatten();
+        let chunk = self.context.loader.borrow_mut().remove_module(&path);
+
+        if exports.is_none() && chunk.is_none() {
+            return Ok(None);
+        }
+
+        // The exports are dropped before the chunk's references are counted,
+        // so that the module's exported functions are only counted when they're held elsewhere.
+        let export_references = exports.map_or(0, |exports| exports.ref_count() - 1);
+        let chunk_references = chunk.map_or(0, |chunk| Ptr::ref_count(&chunk) - 1);
+
+        Ok(Some(UnloadedModule {
+            path,
+            export_references,
+            chunk_references,
+        }))
+    }
+
+    /// Resets the runtime, clearing its exports and any compiled or loaded modules
+    ///
+    /// Any registered finalizers are called (see [KotoVm::add_finalizer]), and handlers that were
+    /// registered by scripts for host events and signals are removed, while state that's owned by
+    /// the host (e.g. the prelude, host callbacks, and pinned values) is retained. The instruction
+    /// count used by the [instruction limit](KotoVmSettings::instruction_limit) is also reset,
+    /// along with the record of call sites that have been reported to the runtime's
+    /// [Diagnostics].
+    ///
+    /// The runtime's existing allocations are reused where possible.
+    pub fn reset(&mut self) {
+        self.context.run_finalizers();
+
+        self.exports.clear();
+        self.reader = InstructionReader::default();
+        self.registers.clear();
+        self.call_stack.clear();
+        self.sequence_builders.clear();
+        self.string_builders.clear();
+        self.instruction_ip = 0;
+        self.execution_state = ExecutionState::Inactive;
+
+        self.context.imported_modules.borrow_mut().clear();
+        self.context.loader.borrow_mut().clear_cache();
+        self.context.host_events.clear_handlers();
+        self.context.signal_handlers.borrow_mut().clear();
+        self.context.signals.clear_handled();
+        self.context.instruction_count.store(0, Ordering::Relaxed);
+        self.context.diagnostics.clear_reported();
+    }
+
     /// The prelude, containing items that can be imported within all modules
     pub fn prelude(&self) -> &KMap {
         &self.context.prelude
@@ -229,6 +437,194 @@ impl KotoVm {
         &mut self.exports
     }
 
+    /// Registers a finalizer that will be called when the runtime is dropped or reset
+    ///
+    /// Finalizers are shared by all VMs that share the runtime's context
+    /// (see [KotoVm::spawn_shared_vm]), and are called when the last VM referring to the context
+    /// is dropped, when the runtime is [reset](KotoVm::reset), or when [KotoVm::run_finalizers]
+    /// is called.
+    ///
+    /// Finalizers are called in the reverse order of their registration, so that resources are
+    /// released in the opposite order to which they were acquired. This makes them useful for
+    /// ensuring that resources opened by a script (e.g. files or sockets) are closed, even when the
+    /// script fails with an error.
+    pub fn add_finalizer(&self, finalizer: impl Finalizer + 'static) {
+        self.context
+            .finalizers
+            .borrow_mut()
+            .push(Box::new(finalizer));
+    }
+
+    /// Calls any registered finalizers, leaving the list of finalizers empty
+    ///
+    /// This is called automatically when the runtime is dropped or reset, and can also be called
+    /// explicitly.
+    ///
+    /// See [KotoVm::add_finalizer].
+    pub fn run_finalizers(&self) {
+        self.context.run_finalizers();
+    }
+
+    /// The events passed between the host application and the runtime's scripts
+    ///
+    /// See [HostEvents].
+    pub fn host_events(&self) -> &HostEvents {
+        &self.context.host_events
+    }
+
+    /// The diagnostics that are reported while running scripts
+    ///
+    /// See [Diagnostics].
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.context.diagnostics
+    }
+
+    /// The values that have been pinned by the host
+    ///
+    /// See [PinnedValues].
+    pub fn pinned_values(&self) -> &PinnedValues {
+        &self.context.pinned_values
+    }
+
+    /// Pins a value, returning a handle that can be used to access the value
+    ///
+    /// See [PinnedValues::pin].
+    pub fn pin(&self, value: impl Into<KValue>) -> ValueHandle {
+        self.context.pinned_values.pin(value)
+    }
+
+    /// Returns the pinned value for the handle, or `None` if the value has been unpinned
+    pub fn pinned(&self, handle: ValueHandle) -> Option<KValue> {
+        self.context.pinned_values.get(handle)
+    }
+
+    /// Unpins the value for the handle, returning the value if it was pinned
+    pub fn unpin(&self, handle: ValueHandle) -> Option<KValue> {
+        self.context.pinned_values.unpin(handle)
+    }
+
+    /// Calls the script's handlers for the named event, returning the number of handlers called
+    ///
+    /// Handlers are registered by scripts with `koto.host.on`, and are called in the order of
+    /// their registration, with the event's data as argument.
+    pub fn dispatch_host_event(&mut self, name: &str, data: impl Into<KValue>) -> Result<usize> {
+        let handlers = self.context.host_events.handlers(name);
+        let data = data.into();
+        for handler in handlers.iter() {
+            self.call_function(handler.clone(), data.clone())?;
+        }
+        Ok(handlers.len())
+    }
+
+    /// Dispatches the events that were posted with [HostEvents::post] to the script's handlers
+    ///
+    /// Events are dispatched in the order that they were posted, and the number of dispatched
+    /// events is returned. If a handler throws an error, then the remaining events stay queued.
+    pub fn process_host_events(&mut self) -> Result<usize> {
+        let mut count = 0;
+        while let Some(event) = self.context.host_events.next_posted() {
+            self.dispatch_host_event(&event.name, event.data)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// The process signals that are passed to the script's handlers
+    ///
+    /// See [Signals].
+    pub fn signals(&self) -> &Signals {
+        &self.context.signals
+    }
+
+    /// Calls the script's handlers for any signals that have been raised with [Signals::raise]
+    ///
+    /// Handlers are registered by scripts with `os.on_signal`, and are called in the order of
+    /// their registration, with the signal's name as argument. The number of handled signals
+    /// is returned.
+    ///
+    /// Pending signals are processed automatically between instructions while the VM is executing,
+    /// so this only needs to be called when the host wants signals to be handled while the script
+    /// is idle.
+    pub fn process_signals(&mut self) -> Result<usize> {
+        let mut count = 0;
+        for signal in self.context.signals.take_pending() {
+            let handlers: Vec<KValue> = self
+                .context
+                .signal_handlers
+                .borrow()
+                .iter()
+                .filter(|(handler_signal, _)| *handler_signal == signal)
+                .map(|(_, handler)| handler.clone())
+                .collect();
+            for handler in handlers {
+                self.call_function(handler, signal.name())?;
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    // Called by `os.on_signal`
+    pub(crate) fn add_signal_handler(&self, signal: Signal, handler: KValue) {
+        self.context
+            .signal_handlers
+            .borrow_mut()
+            .push((signal, handler));
+        self.context.signals.set_handled(signal);
+    }
+
+    /// Seals a map, preventing its entries from being modified by scripts
+    ///
+    /// Attempting to add, replace, or remove an entry in a sealed map from a script
+    /// (e.g. by assigning to it, or by calling a function like `map.remove`) will result in an
+    /// error.
+    ///
+    /// Sealing only applies to scripts, the map can still be modified via the Rust API
+    /// (e.g. with [KMap::insert]), which allows embedders to extend or override sealed modules.
+    ///
+    /// The seal is stored with the map's data, so it applies to all maps that share the data,
+    /// while copies of the map (e.g. made with `koto.copy`) aren't sealed.
+    pub fn seal_map(&self, map: &KMap) {
+        map.data_mut().seal();
+    }
+
+    /// Returns true if the map has been sealed
+    ///
+    /// See [KotoVm::seal_map].
+    pub fn is_sealed(&self, map: &KMap) -> bool {
+        map.data().is_sealed()
+    }
+
+    /// Returns an error if the map is sealed
+    ///
+    /// This should be called before an operation that could modify existing entries in the map.
+    pub(crate) fn check_map_is_mutable(&self, map: &KMap) -> Result<()> {
+        if self.is_sealed(map) {
+            runtime_error!("Unable to modify {} as it is sealed", sealed_map_name(map))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns an error if the map is sealed
+    ///
+    /// This should be called before inserting an entry into the map.
+    pub(crate) fn check_map_insert(&self, map: &KMap, key: &ValueKey) -> Result<()> {
+        if self.is_sealed(map) {
+            let action = if map.data().contains_key(key) {
+                "replace"
+            } else {
+                "add"
+            };
+            runtime_error!(
+                "Unable to {action} '{key}' in {} as it is sealed",
+                sealed_map_name(map)
+            )
+        } else {
+            Ok(())
+        }
+    }
+
     /// The stdin wrapper used by the VM
     pub fn stdin(&self) -> &Ptr<dyn KotoFile> {
         &self.context.settings.stdin
@@ -507,6 +903,13 @@ impl KotoVm {
             BinaryOp::NotEqual => {
                 self.run_not_equal(result_register, lhs_register, rhs_register)?
             }
+            BinaryOp::BitwiseAnd
+            | BinaryOp::BitwiseOr
+            | BinaryOp::BitwiseXor
+            | BinaryOp::ShiftLeft
+            | BinaryOp::ShiftRight => {
+                self.run_bitwise_op(result_register, lhs_register, rhs_register, op)?
+            }
             BinaryOp::Index => self.run_index(result_register, lhs_register, rhs_register)?,
         }
 
@@ -647,6 +1050,22 @@ impl KotoVm {
     }
 
     fn execute_instructions(&mut self) -> Result<KValue> {
+        let mut interrupt_check = InterruptCheck::default();
+
+        let result = self.execute_instructions_with_interrupt_check(&mut interrupt_check);
+
+        // Instructions that were executed since the last check still count towards the limit
+        self.context
+            .instruction_count
+            .fetch_add(interrupt_check.elapsed(), Ordering::Relaxed);
+
+        result
+    }
+
+    fn execute_instructions_with_interrupt_check(
+        &mut self,
+        interrupt_check: &mut InterruptCheck,
+    ) -> Result<KValue> {
         let mut timeout = self
             .context
             .settings
@@ -668,7 +1087,33 @@ impl KotoVm {
                 }
             }
 
-            match self.execute_instruction(instruction) {
+            // Resource limits and raised signals are checked periodically rather than before
+            // every instruction, keeping the overhead of the checks low.
+            let signals_pending = if interrupt_check.remaining == 0 {
+                if let Some(error) = self.check_resource_limits(interrupt_check) {
+                    return self
+                        .pop_call_stack_on_error(error.into(), false)
+                        .map(|_| KValue::Null);
+                }
+
+                self.context.signals.is_pending()
+            } else {
+                false
+            };
+
+            interrupt_check.remaining -= 1;
+
+            // Raised signals are handled in a shared VM before the next instruction is executed,
+            // with errors thrown by handlers being treated as if thrown by the instruction.
+            let result = if signals_pending {
+                self.spawn_shared_vm()
+                    .process_signals()
+                    .and_then(|_| self.execute_instruction(instruction))
+            } else {
+                self.execute_instruction(instruction)
+            };
+
+            match result {
                 Ok(ControlFlow::Continue) => {}
                 Ok(ControlFlow::Return(value)) => {
                     self.execution_state = ExecutionState::Inactive;
@@ -701,6 +1146,49 @@ impl KotoVm {
         Ok(KValue::Null)
     }
 
+    // Counts the instructions executed since the last check against the runtime's resource limits,
+    // and then prepares the next check interval
+    //
+    // An error is returned if one of the limits has been exceeded.
+    fn check_resource_limits(&self, interrupt_check: &mut InterruptCheck) -> Option<ErrorKind> {
+        let elapsed = interrupt_check.elapsed();
+        let count = self
+            .context
+            .instruction_count
+            .fetch_add(elapsed, Ordering::Relaxed)
+            + elapsed;
+        interrupt_check.interval = 0;
+
+        let settings = &self.context.settings;
+
+        // The next instruction would take the count beyond the limit
+        if let Some(limit) = settings.instruction_limit {
+            if count >= limit {
+                return Some(ErrorKind::InstructionLimit(limit));
+            }
+        }
+
+        // Memory usage is checked each time the count passes a multiple of the check interval
+        let interval_passed =
+            (count - elapsed) / INTERRUPT_CHECK_INTERVAL != count / INTERRUPT_CHECK_INTERVAL;
+        if let Some(limit) = &settings.memory_limit {
+            if interval_passed && (limit.usage)() > limit.max_bytes {
+                return Some(ErrorKind::MemoryLimit(limit.max_bytes));
+            }
+        }
+
+        // The interval is shortened when the instruction limit is close, so that the limit is
+        // enforced exactly.
+        let interval = match settings.instruction_limit {
+            Some(limit) => INTERRUPT_CHECK_INTERVAL.min(limit - count),
+            None => INTERRUPT_CHECK_INTERVAL,
+        };
+        interrupt_check.interval = interval;
+        interrupt_check.remaining = interval;
+
+        None
+    }
+
     fn execute_instruction(&mut self, instruction: Instruction) -> Result<ControlFlow> {
         use Instruction::*;
 
@@ -751,6 +1239,7 @@ impl KotoVm {
                     self.run_sequence_push(value_register)?;
                 }
             }
+            SequenceExtend { iterable } => self.run_sequence_extend(iterable)?,
             SequenceToList { register } => self.run_sequence_to_list(register)?,
             SequenceToTuple { register } => self.run_sequence_to_tuple(register)?,
             StringStart { size_hint } => self
@@ -779,6 +1268,7 @@ impl KotoVm {
                 self.run_make_range(register, Some(start), None, false)?
             }
             RangeFull { register } => self.run_make_range(register, None, None, false)?,
+            RangeStep { register, step } => self.run_range_step(register, step)?,
             MakeIterator { register, iterable } => {
                 self.run_make_iterator(register, iterable, true)?
             }
@@ -795,6 +1285,22 @@ impl KotoVm {
             Multiply { register, lhs, rhs } => self.run_multiply(register, lhs, rhs)?,
             Divide { register, lhs, rhs } => self.run_divide(register, lhs, rhs)?,
             Remainder { register, lhs, rhs } => self.run_remainder(register, lhs, rhs)?,
+            BitwiseNot { register, value } => self.run_bitwise_not(register, value)?,
+            BitwiseAnd { register, lhs, rhs } => {
+                self.run_bitwise_op(register, lhs, rhs, BinaryOp::BitwiseAnd)?
+            }
+            BitwiseOr { register, lhs, rhs } => {
+                self.run_bitwise_op(register, lhs, rhs, BinaryOp::BitwiseOr)?
+            }
+            BitwiseXor { register, lhs, rhs } => {
+                self.run_bitwise_op(register, lhs, rhs, BinaryOp::BitwiseXor)?
+            }
+            ShiftLeft { register, lhs, rhs } => {
+                self.run_bitwise_op(register, lhs, rhs, BinaryOp::ShiftLeft)?
+            }
+            ShiftRight { register, lhs, rhs } => {
+                self.run_bitwise_op(register, lhs, rhs, BinaryOp::ShiftRight)?
+            }
             AddAssign { lhs, rhs } => self.run_add_assign(lhs, rhs)?,
             SubtractAssign { lhs, rhs } => self.run_subtract_assign(lhs, rhs)?,
             MultiplyAssign { lhs, rhs } => self.run_multiply_assign(lhs, rhs)?,
@@ -812,6 +1318,11 @@ impl KotoVm {
             JumpBack { offset } => self.jump_ip_back(offset as u32),
             JumpIfTrue { register, offset } => self.run_jump_if_true(register, offset as u32)?,
             JumpIfFalse { register, offset } => self.run_jump_if_false(register, offset as u32)?,
+            JumpIfNotNull { register, offset } => {
+                if !matches!(self.get_register(register), KValue::Null) {
+                    self.jump_ip(offset as u32);
+                }
+            }
             Call {
                 result,
                 function,
@@ -826,12 +1337,56 @@ impl KotoVm {
                 self.clone_register(function),
                 None,
             )?,
+            CallNamed {
+                result,
+                function,
+                frame_base,
+                arg_count,
+            } => self.call_with_named_args(
+                &CallInfo {
+                    result_register: result,
+                    frame_base,
+                    arg_count,
+                },
+                self.clone_register(function),
+            )?,
+            CallSpread {
+                result,
+                function,
+                frame_base,
+                arg_count,
+            } => self.call_with_spread_args(
+                &CallInfo {
+                    result_register: result,
+                    frame_base,
+                    arg_count,
+                },
+                self.clone_register(function),
+            )?,
             Return { register } => {
                 if let Some(return_value) = self.pop_frame(self.clone_register(register))? {
                     // If pop_frame returns a new return_value, then execution should stop.
                     control_flow = ControlFlow::Return(return_value);
                 }
             }
+            Propagate {
+                register,
+                value,
+                offset,
+            } => {
+                let result = match self.get_register(value) {
+                    KValue::Object(o) if o.is_a::<KResult>() => o.cast::<KResult>()?.clone(),
+                    unexpected => return type_error("a Result when using '?'", unexpected),
+                };
+                match result.as_result().ok().cloned() {
+                    Some(value) => {
+                        self.set_register(register, value);
+                        self.jump_ip(offset as u32);
+                    }
+                    // The following instructions return the err result from the current frame
+                    None => self.set_register(register, result.into()),
+                }
+            }
             Yield { register } => control_flow = ControlFlow::Yield(self.clone_register(register)),
             Throw { register } => {
                 let thrown_value = self.clone_register(register);
@@ -886,6 +1441,7 @@ impl KotoVm {
                 key,
                 value,
             } => self.run_map_insert(register, key, value)?,
+            MapExtend { register, source } => self.run_map_extend(register, source)?,
             MetaInsert {
                 register,
                 value,
@@ -915,6 +1471,12 @@ impl KotoVm {
                 };
                 self.run_access(register, value, key_string)?;
             }
+            AccessOptional {
+                register,
+                value,
+                key,
+                offset,
+            } => self.run_access_optional(register, value, key, offset as u32)?,
             TryStart {
                 arg_register,
                 catch_offset,
@@ -928,6 +1490,16 @@ impl KotoVm {
             Debug { register, constant } => self.run_debug(register, constant)?,
             CheckSizeEqual { register, size } => self.run_check_size_equal(register, size)?,
             CheckSizeMin { register, size } => self.run_check_size_min(register, size)?,
+            MatchRange {
+                register,
+                value,
+                range,
+            } => self.run_match_range(register, value, range)?,
+            MatchType {
+                register,
+                value,
+                type_name,
+            } => self.run_match_type(register, value, type_name),
         }
 
         Ok(control_flow)
@@ -999,6 +1571,22 @@ impl KotoVm {
         Ok(())
     }
 
+    fn run_range_step(&mut self, register: u8, step_register: u8) -> Result<()> {
+        let result = match (
+            self.get_register(register),
+            self.get_register(step_register),
+        ) {
+            (KValue::Range(range), KValue::Number(step)) => range.with_step(step.into())?,
+            (KValue::Range(_), unexpected) => {
+                return type_error("a Number for the range's step", unexpected)
+            }
+            (unexpected, _) => return type_error("Range", unexpected),
+        };
+
+        self.set_register(register, result.into());
+        Ok(())
+    }
+
     // Runs the MakeIterator instruction
     //
     // This function is distinct from the public `make_iterator`, which will defer to this function
@@ -1326,6 +1914,7 @@ impl KotoVm {
                 variadic,
                 generator,
                 arg_is_unpacked_tuple,
+                deprecated,
                 size,
             } => {
                 let info = KFunction {
@@ -1335,6 +1924,7 @@ impl KotoVm {
                     variadic,
                     arg_is_unpacked_tuple,
                     generator,
+                    deprecated,
                 };
 
                 let value = if capture_count > 0 {
@@ -1567,6 +2157,64 @@ impl KotoVm {
         Ok(())
     }
 
+    fn run_bitwise_not(&mut self, result: u8, value: u8) -> Result<()> {
+        match self.get_register(value) {
+            KValue::Number(n) => {
+                let result_value = KValue::Number(!n);
+                self.set_register(result, result_value);
+                Ok(())
+            }
+            unexpected => type_error("Number", unexpected),
+        }
+    }
+
+    // Runs one of the bitwise binary operations (`&`, `|`, `^`, `<<`, and `>>`)
+    fn run_bitwise_op(&mut self, result: u8, lhs: u8, rhs: u8, op: BinaryOp) -> Result<()> {
+        use KValue::*;
+
+        let lhs_value = self.get_register(lhs);
+        let rhs_value = self.get_register(rhs);
+        let result_value = match (lhs_value, rhs_value) {
+            (Number(a), Number(b)) => {
+                let number_result = match op {
+                    BinaryOp::BitwiseAnd => Some(a & b),
+                    BinaryOp::BitwiseOr => Some(a | b),
+                    BinaryOp::BitwiseXor => Some(a ^ b),
+                    BinaryOp::ShiftLeft => a.shift_left(*b),
+                    BinaryOp::ShiftRight => a.shift_right(*b),
+                    _ => unreachable!(),
+                };
+                match number_result {
+                    Some(n) => Number(n),
+                    // The shift ops return None when the shift amount is negative
+                    None => {
+                        return runtime_error!("Expected a non-negative shift amount, found '{b}'")
+                    }
+                }
+            }
+            (Map(m), _) if m.contains_meta_key(&op.into()) => {
+                let op = m.get_meta_value(&op.into()).unwrap();
+                let rhs_value = rhs_value.clone();
+                return self.call_overridden_binary_op(result, lhs, rhs_value, op);
+            }
+            (Object(o), _) => {
+                let o = o.try_borrow()?;
+                match op {
+                    BinaryOp::BitwiseAnd => o.bitwise_and(rhs_value)?,
+                    BinaryOp::BitwiseOr => o.bitwise_or(rhs_value)?,
+                    BinaryOp::BitwiseXor => o.bitwise_xor(rhs_value)?,
+                    BinaryOp::ShiftLeft => o.shift_left(rhs_value)?,
+                    BinaryOp::ShiftRight => o.shift_right(rhs_value)?,
+                    _ => unreachable!(),
+                }
+            }
+            _ => return binary_op_error(lhs_value, rhs_value, op),
+        };
+        self.set_register(result, result_value);
+
+        Ok(())
+    }
+
     fn run_add_assign(&mut self, lhs: u8, rhs: u8) -> Result<()> {
         use BinaryOp::AddAssign;
         use KValue::*;
@@ -1829,6 +2477,11 @@ impl KotoVm {
                     false
                 }
             }
+            (Object(a), Object(b)) if a.is_a::<KResult>() && b.is_a::<KResult>() => {
+                let a = a.cast::<KResult>()?.clone();
+                let b = b.cast::<KResult>()?.clone();
+                self.compare_results(&a, &b)?
+            }
             (Object(o), _) => o.try_borrow()?.equal(rhs_value)?,
             (CaptureFunction(a), CaptureFunction(b)) => {
                 if a.info == b.info {
@@ -1889,6 +2542,11 @@ impl KotoVm {
                     true
                 }
             }
+            (Object(a), Object(b)) if a.is_a::<KResult>() && b.is_a::<KResult>() => {
+                let a = a.cast::<KResult>()?.clone();
+                let b = b.cast::<KResult>()?.clone();
+                !self.compare_results(&a, &b)?
+            }
             (Object(o), _) => o.try_borrow()?.not_equal(rhs_value)?,
             (CaptureFunction(a), CaptureFunction(b)) => {
                 if a.info == b.info {
@@ -1908,6 +2566,18 @@ impl KotoVm {
         Ok(())
     }
 
+    // Called from run_equal / run_not_equal to compare results
+    //
+    // Results are equal if they're both `ok` or both `err`, with equal contents.
+    fn compare_results(&mut self, a: &KResult, b: &KResult) -> Result<bool> {
+        match (a.as_result(), b.as_result()) {
+            (Ok(a), Ok(b)) | (Err(a), Err(b)) => {
+                self.compare_value_ranges(slice::from_ref(a), slice::from_ref(b))
+            }
+            _ => Ok(false),
+        }
+    }
+
     // Called from run_equal / run_not_equal to compare the contents of lists and tuples
     fn compare_value_ranges(&mut self, range_a: &[KValue], range_b: &[KValue]) -> Result<bool> {
         if range_a.len() != range_b.len() {
@@ -2223,6 +2893,9 @@ impl KotoVm {
                             return runtime_error!("Index '{index}' not in List");
                         }
                     }
+                    Range(range) if range.step().is_some() => {
+                        return runtime_error!("Stepped ranges can't be used as indices ({range})")
+                    }
                     Range(range) => {
                         for i in range.indices(list_len) {
                             list_data[i] = value.clone();
@@ -2251,6 +2924,23 @@ impl KotoVm {
         Ok(index)
     }
 
+    // Strings are indexed by byte, so the errors make it clear that a byte index was expected
+    fn validate_string_index(&self, n: KNumber, s: &str) -> Result<usize> {
+        if n < 0.0 {
+            return runtime_error!("Negative byte indices aren't allowed ('{n}')");
+        }
+
+        let index = usize::from(n);
+        if index >= s.len() {
+            return runtime_error!(
+                "Byte index out of bounds - index: {n}, byte length: {}",
+                s.len()
+            );
+        }
+
+        Ok(index)
+    }
+
     fn run_index(
         &mut self,
         result_register: u8,
@@ -2268,6 +2958,9 @@ impl KotoVm {
                 let index = self.validate_index(n, Some(l.len()))?;
                 self.set_register(result_register, l.data()[index].clone());
             }
+            (List(_) | Tuple(_) | Str(_), Range(range)) if range.step().is_some() => {
+                return runtime_error!("Stepped ranges can't be used as indices ({range})");
+            }
             (List(l), Range(range)) => self.set_register(
                 result_register,
                 List(KList::from_slice(&l.data()[range.indices(l.len())])),
@@ -2282,18 +2975,27 @@ impl KotoVm {
                 self.set_register(result_register, Tuple(result))
             }
             (Str(s), Number(n)) => {
-                let index = self.validate_index(n, Some(s.len()))?;
+                let index = self.validate_string_index(n, s)?;
                 let Some(result) = s.with_bounds(index..index + 1) else {
-                    return runtime_error!(
-                        "indexing with ({index}) would result in invalid UTF-8 data"
-                    );
+                    if s.is_char_boundary(index) {
+                        return runtime_error!(
+                            "the character at byte index {index} is longer than a single byte, \
+                             use a range to index the character (see `string.char_indices`)"
+                        );
+                    } else {
+                        return runtime_error!(
+                            "byte index {index} isn't at the start of a character \
+                             (see `string.char_indices`)"
+                        );
+                    }
                 };
                 self.set_register(result_register, Str(result));
             }
             (Str(s), Range(range)) => {
                 let Some(result) = s.with_bounds(range.indices(s.len())) else {
                     return runtime_error!(
-                        "indexing with ({range}) would result in invalid UTF-8 data"
+                        "the byte range {range} doesn't start and end on character boundaries \
+                         (see `string.char_indices`)"
                     );
                 };
                 self.set_register(result_register, Str(result));
@@ -2319,6 +3021,28 @@ impl KotoVm {
         Ok(())
     }
 
+    fn run_map_extend(&mut self, map_register: u8, source_register: u8) -> Result<()> {
+        let entries = match self.get_register(source_register) {
+            KValue::Map(source) => source
+                .data()
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect::<Vec<_>>(),
+            unexpected => return type_error("a Map to spread", unexpected),
+        };
+
+        match self.get_register(map_register) {
+            KValue::Map(map) => {
+                for (key, _) in entries.iter() {
+                    self.check_map_insert(map, key)?;
+                }
+                map.data_mut().extend(entries);
+                Ok(())
+            }
+            unexpected => type_error("a Map", unexpected),
+        }
+    }
+
     fn run_map_insert(
         &mut self,
         map_register: u8,
@@ -2328,8 +3052,9 @@ impl KotoVm {
         let key = ValueKey::try_from(self.clone_register(key_register))?;
         let value = self.clone_register(value_register);
 
-        match self.get_register_mut(map_register) {
+        match self.get_register(map_register) {
             KValue::Map(map) => {
+                self.check_map_insert(map, &key)?;
                 map.data_mut().insert(key, value);
                 Ok(())
             }
@@ -2425,24 +3150,79 @@ impl KotoVm {
         value_register: u8,
         key_string: KString,
     ) -> Result<()> {
+        let accessed_value = self.clone_register(value_register);
+
+        match self.lookup_value(&accessed_value, key_string)? {
+            Access::Found(value) => {
+                self.set_register(result_register, value);
+                Ok(())
+            }
+            Access::NotFound { key, container } => {
+                runtime_error!("'{key}' not found in '{container}'")
+            }
+        }
+    }
+
+    fn run_access_optional(
+        &mut self,
+        result_register: u8,
+        value_register: u8,
+        key_register: u8,
+        offset: u32,
+    ) -> Result<()> {
+        let key_string = match self.clone_register(key_register) {
+            KValue::Str(s) => s,
+            other => return type_error("a String", &other),
+        };
+
+        let mut accessed_value = self.clone_register(value_register);
+
+        // Results are handled as they would be with `?`, so that `?.` can be used in the same way
+        if let KValue::Object(o) = &accessed_value {
+            if o.is_a::<KResult>() {
+                let result = o.cast::<KResult>()?.clone();
+                match result.as_result() {
+                    Ok(value) => accessed_value = value.clone(),
+                    Err(_) => {
+                        // The following instructions return the err result from the current frame
+                        self.set_register(result_register, result.into());
+                        return Ok(());
+                    }
+                }
+            }
+        }
+
+        let result = match accessed_value {
+            KValue::Null => KValue::Null,
+            _ => match self.lookup_value(&accessed_value, key_string)? {
+                Access::Found(value) => value,
+                Access::NotFound { .. } => KValue::Null,
+            },
+        };
+
+        self.set_register(result_register, result);
+        self.jump_ip(offset);
+        Ok(())
+    }
+
+    // Looks up the value for a '.' access, see run_access and run_access_optional
+    fn lookup_value(&self, accessed_value: &KValue, key_string: KString) -> Result<Access> {
         use KValue::*;
 
-        let accessed_value = self.clone_register(value_register);
         let key = ValueKey::from(key_string.clone());
 
         macro_rules! core_op {
             ($module:ident, $iterator_fallback:expr) => {{
-                let op = self.get_core_op(
-                    &key,
+                return Ok(self.get_core_op(
+                    key,
                     &self.context.core_lib.$module,
                     $iterator_fallback,
                     stringify!($module),
-                )?;
-                self.set_register(result_register, op);
+                ));
             }};
         }
 
-        match &accessed_value {
+        match accessed_value {
             List(_) => core_op!(list, true),
             Number(_) => core_op!(number, false),
             Range(_) => core_op!(range, true),
@@ -2459,7 +3239,6 @@ impl KotoVm {
                         // Fallback to the map module when there's no metamap
                         None if access_map.meta_map().is_none() => {
                             core_op!(map, true);
-                            return Ok(());
                         }
                         _ => match access_map.get_meta_value(&MetaKey::Named(key_string.clone())) {
                             Some(value) => access_result = Some(value),
@@ -2477,74 +3256,69 @@ impl KotoVm {
                     }
                 }
 
-                // Iterator fallback?
-                if access_result.is_none()
-                    && (map.contains_meta_key(&UnaryOp::Iterator.into())
-                        || map.contains_meta_key(&UnaryOp::Next.into()))
-                {
-                    access_result = Some(self.get_core_op(
-                        &key,
-                        &self.context.core_lib.iterator,
-                        false,
-                        &accessed_value.type_as_string(),
-                    )?);
+                match access_result {
+                    Some(value) => Ok(Access::Found(value)),
+                    // Iterator fallback?
+                    None if map.contains_meta_key(&UnaryOp::Iterator.into())
+                        || map.contains_meta_key(&UnaryOp::Next.into()) =>
+                    {
+                        Ok(self.get_core_op(
+                            key,
+                            &self.context.core_lib.iterator,
+                            false,
+                            &accessed_value.type_as_string(),
+                        ))
+                    }
+                    None => Ok(Access::NotFound {
+                        key,
+                        container: accessed_value.type_as_string(),
+                    }),
                 }
-
-                let Some(value) = access_result else {
-                    return runtime_error!(
-                        "'{key}' not found in '{}'",
-                        accessed_value.type_as_string()
-                    );
-                };
-
-                self.set_register(result_register, value);
             }
             Object(o) => {
                 let o = o.try_borrow()?;
 
-                let mut result = None;
-                if let Some(entries) = o.entries() {
-                    result = entries.get(&key);
+                if let Some(value) = o.entries().and_then(|entries| entries.get(&key)) {
+                    return Ok(Access::Found(value));
                 }
 
                 // Iterator fallback?
-                if result.is_none() && !matches!(o.is_iterable(), IsIterable::NotIterable) {
-                    result = Some(self.get_core_op(
-                        &key,
+                if !matches!(o.is_iterable(), IsIterable::NotIterable) {
+                    Ok(self.get_core_op(
+                        key,
                         &self.context.core_lib.iterator,
                         false,
                         &o.type_string(),
-                    )?);
-                }
-
-                if let Some(result) = result {
-                    self.set_register(result_register, result);
+                    ))
                 } else {
-                    return runtime_error!("'{key}' not found in '{}'", o.type_string());
+                    Ok(Access::NotFound {
+                        key,
+                        container: o.type_string(),
+                    })
                 }
             }
-            unexpected => return type_error("Value that supports '.' access", unexpected),
+            unexpected => type_error("Value that supports '.' access", unexpected),
         }
-
-        Ok(())
     }
 
     fn get_core_op(
         &self,
-        key: &ValueKey,
+        key: ValueKey,
         module: &KMap,
         iterator_fallback: bool,
         module_name: &str,
-    ) -> Result<KValue> {
-        let maybe_op = match module.get(key) {
-            None if iterator_fallback => self.context.core_lib.iterator.get(key),
+    ) -> Access {
+        let maybe_op = match module.get(&key) {
+            None if iterator_fallback => self.context.core_lib.iterator.get(&key),
             maybe_op => maybe_op,
         };
 
-        if let Some(result) = maybe_op {
-            Ok(result)
-        } else {
-            runtime_error!("'{key}' not found in '{module_name}'")
+        match maybe_op {
+            Some(op) => Access::Found(op),
+            None => Access::NotFound {
+                key,
+                container: module_name.into(),
+            },
         }
     }
 
@@ -2564,6 +3338,36 @@ impl KotoVm {
         Ok(())
     }
 
+    // Reports a call to a function that was annotated with `@deprecated`
+    //
+    // The call site is the instruction that's currently being executed, or if the call stack is
+    // empty then the function has been called directly by the host.
+    fn report_deprecated_call(&self, f: &KFunction) {
+        let message = match f.chunk.debug_info.get_function_deprecation(f.ip) {
+            Some(message) => f.chunk.constants.get_str(message).to_string(),
+            None => return,
+        };
+
+        let location = if self.call_stack.is_empty() {
+            None
+        } else {
+            let chunk = &self.reader.chunk;
+            chunk
+                .debug_info
+                .get_source_span(self.instruction_ip)
+                .map(|span| SourceLocation {
+                    path: chunk.source_path.clone(),
+                    span,
+                })
+        };
+
+        self.context.diagnostics.report(Diagnostic {
+            kind: DiagnosticKind::Deprecated,
+            message,
+            location,
+        });
+    }
+
     fn call_generator(
         &mut self,
         call_info: &CallInfo,
@@ -2664,6 +3468,10 @@ impl KotoVm {
         captures: Option<&KList>,
         temp_tuple_values: Option<&[KValue]>,
     ) -> Result<()> {
+        if f.deprecated {
+            self.report_deprecated_call(f);
+        }
+
         if f.generator {
             return self.call_generator(call_info, f, captures, temp_tuple_values);
         }
@@ -2720,6 +3528,115 @@ impl KotoVm {
         Ok(())
     }
 
+    // Makes a call with spread args, which are provided as a tuple in the first arg register
+    //
+    // The tuple's values are moved into the arg registers, followed by the optional map of named
+    // args, and then the call is made as normal.
+    fn call_with_spread_args(&mut self, info: &CallInfo, callable: KValue) -> Result<()> {
+        let args = match self.clone_register(info.frame_base + 1) {
+            KValue::Tuple(args) => args,
+            unexpected => return type_error("a Tuple of spread arguments", &unexpected),
+        };
+        let named_args = (info.arg_count == 2).then(|| self.clone_register(info.frame_base + 2));
+
+        let arg_count = args.len() + named_args.is_some() as usize;
+        let Ok(arg_count) = u8::try_from(arg_count) else {
+            return runtime_error!("Too many arguments in call ({arg_count})");
+        };
+
+        let arg_base = self.register_index(info.frame_base) + 1;
+        self.registers.truncate(arg_base);
+        self.registers.extend(args.iter().cloned());
+        self.registers.extend(named_args.clone());
+
+        let info = CallInfo {
+            result_register: info.result_register,
+            frame_base: info.frame_base,
+            arg_count,
+        };
+
+        if named_args.is_some() {
+            self.call_with_named_args(&info, callable)
+        } else {
+            self.call_callable(&info, callable, None)
+        }
+    }
+
+    // Makes a call with named args, which are provided in a map following the positional args
+    //
+    // The named args are moved into the registers that match the function's arg names, with Null
+    // being used for any skipped args, and then the function is called as normal.
+    //
+    // Native functions don't have arg names, so the map is passed to them as their last arg.
+    fn call_with_named_args(&mut self, info: &CallInfo, callable: KValue) -> Result<()> {
+        let f = match &callable {
+            KValue::Function(f) => f.clone(),
+            KValue::CaptureFunction(f) => f.info.clone(),
+            KValue::NativeFunction(_) => return self.call_callable(info, callable, None),
+            unexpected => {
+                return type_error("a Koto function when using named arguments", unexpected)
+            }
+        };
+
+        let named_args = match self.clone_register(info.frame_base + info.arg_count) {
+            KValue::Map(named_args) => named_args,
+            unexpected => return type_error("a Map of named arguments", &unexpected),
+        };
+
+        let Some(arg_names) = f.chunk.debug_info.get_function_args(f.ip) else {
+            return runtime_error!("Missing argument names for the called function");
+        };
+
+        // Variadic args can't be provided by name
+        let named_arg_limit = if f.variadic {
+            f.arg_count - 1
+        } else {
+            f.arg_count
+        } as usize;
+
+        // Remove the named args map from the value stack
+        let positional_count = info.arg_count - 1;
+        let arg_base = self.register_index(info.frame_base) + 1;
+        self.registers
+            .truncate(arg_base + positional_count as usize);
+
+        let mut arg_count = positional_count;
+        for (name, value) in named_args.data().iter() {
+            let name = match name.value() {
+                KValue::Str(name) => name,
+                unexpected => return type_error("a String as argument name", unexpected),
+            };
+
+            let Some(index) = arg_names[..named_arg_limit].iter().position(|arg_name| {
+                arg_name.is_some_and(|id| f.chunk.constants.get_str(id) == name.as_str())
+            }) else {
+                return runtime_error!(
+                    "The called function doesn't have an argument named '{name}'"
+                );
+            };
+
+            if index < positional_count as usize {
+                return runtime_error!("The '{name}' argument was provided more than once");
+            }
+
+            if self.registers.len() <= arg_base + index {
+                self.registers.resize(arg_base + index + 1, KValue::Null);
+            }
+            self.registers[arg_base + index] = value.clone();
+            arg_count = arg_count.max(index as u8 + 1);
+        }
+
+        self.call_callable(
+            &CallInfo {
+                result_register: info.result_register,
+                frame_base: info.frame_base,
+                arg_count,
+            },
+            callable,
+            None,
+        )
+    }
+
     fn call_callable(
         &mut self,
         info: &CallInfo,
@@ -2792,6 +3709,58 @@ impl KotoVm {
         }
     }
 
+    fn run_match_range(
+        &mut self,
+        result_register: u8,
+        value_register: u8,
+        range_register: u8,
+    ) -> Result<()> {
+        let range = match self.get_register(range_register) {
+            KValue::Range(range) => range,
+            unexpected => return type_error("a Range", unexpected),
+        };
+
+        // Non-numeric values don't match, and numbers are compared directly against the
+        // range's bounds so that floats in between the range's integers are also matched.
+        let result = match self.get_register(value_register) {
+            KValue::Number(n) => {
+                let after_start = match range.start() {
+                    Some(start) => *n >= KNumber::from(start),
+                    None => true,
+                };
+                let before_end = match range.end() {
+                    Some((end, true)) => *n <= KNumber::from(end),
+                    Some((end, false)) => *n < KNumber::from(end),
+                    None => true,
+                };
+                after_start && before_end
+            }
+            _ => false,
+        };
+
+        self.set_register(result_register, result.into());
+        Ok(())
+    }
+
+    fn run_match_type(
+        &mut self,
+        result_register: u8,
+        value_register: u8,
+        type_name: ConstantIndex,
+    ) {
+        let value = self.get_register(value_register);
+        let expected = self.get_constant_str(type_name);
+
+        // Numbers are reported as either Int or Float by koto.type,
+        // `Number` is also accepted to match both.
+        let result = match (value, expected) {
+            (KValue::Number(_), "Number") => true,
+            _ => value.type_as_string().as_str() == expected,
+        };
+
+        self.set_register(result_register, result.into());
+    }
+
     fn get_value_size(&mut self, value_register: u8) -> Result<usize> {
         match self.run_unary_op(UnaryOp::Size, self.clone_register(value_register))? {
             KValue::Number(n) => Ok(n.into()),
@@ -2809,6 +3778,24 @@ impl KotoVm {
         }
     }
 
+    fn run_sequence_extend(&mut self, iterable_register: u8) -> Result<()> {
+        let values = match self.clone_register(iterable_register) {
+            KValue::List(l) => l.data().to_vec(),
+            KValue::Tuple(t) => t.to_vec(),
+            iterable => self
+                .make_iterator(iterable)?
+                .map(KValue::try_from)
+                .collect::<Result<Vec<_>>>()?,
+        };
+
+        if let Some(builder) = self.sequence_builders.last_mut() {
+            builder.extend(values);
+            Ok(())
+        } else {
+            runtime_error!(ErrorKind::MissingSequenceBuilder)
+        }
+    }
+
     fn run_sequence_to_list(&mut self, register: u8) -> Result<()> {
         if let Some(result) = self.sequence_builders.pop() {
             let list = KList::with_data(ValueVec::from_vec(result));
@@ -3125,6 +4112,13 @@ impl fmt::Debug for KotoVm {
     }
 }
 
+fn sealed_map_name(map: &KMap) -> String {
+    match map.get_meta_value(&MetaKey::Type) {
+        Some(KValue::Str(type_name)) => format!("'{type_name}'"),
+        _ => "the map".into(),
+    }
+}
+
 fn binary_op_error(lhs: &KValue, rhs: &KValue, op: BinaryOp) -> Result<()> {
     runtime_error!(ErrorKind::InvalidBinaryOp {
         lhs: lhs.clone(),
@@ -3240,6 +4234,12 @@ impl Frame {
     }
 }
 
+// See Vm::lookup_value
+enum Access {
+    Found(KValue),
+    NotFound { key: ValueKey, container: KString },
+}
+
 // See Vm::call_external
 enum ExternalCallable {
     Function(KNativeFunction),
@@ -3254,6 +4254,26 @@ struct CallInfo {
     arg_count: u8,
 }
 
+// The number of instructions that should elapse between checks for exceeded resource limits and
+// raised signals
+const INTERRUPT_CHECK_INTERVAL: u64 = 1024;
+
+// Tracks when the execution loop should next check resource limits and raised signals
+#[derive(Default)]
+struct InterruptCheck {
+    // The number of instructions in the current interval
+    interval: u64,
+    // The number of instructions that remain to be executed before the next check
+    remaining: u64,
+}
+
+impl InterruptCheck {
+    // The number of instructions that have been executed in the current interval
+    fn elapsed(&self) -> u64 {
+        self.interval - self.remaining
+    }
+}
+
 struct ExecutionTimeout {
     // The instant at which the deadline was last checked
     last_check: Instant,
@@ -0,0 +1,67 @@
+mod cfg {
+    use koto_bytecode::{CompilerSettings, Loader};
+    use koto_runtime::prelude::*;
+    use koto_test_utils::OutputCapture;
+
+    fn run_script(script: &str, cfg_flags: &[&str]) -> (KValue, String) {
+        let (mut vm, output) = OutputCapture::make_vm_with_output_capture();
+
+        let mut loader = Loader::default();
+        let settings = CompilerSettings {
+            cfg_flags: cfg_flags.iter().map(|flag| flag.to_string()).collect(),
+            ..Default::default()
+        };
+        let chunk = match loader.compile_script(script, None, settings) {
+            Ok(chunk) => chunk,
+            Err(error) => {
+                panic!("Error while compiling script: {error}");
+            }
+        };
+
+        match vm.run(chunk) {
+            Ok(result) => (result, output.captured_output().clone()),
+            Err(error) => panic!("Error while running script: {error}"),
+        }
+    }
+
+    const SCRIPT: &str = "
+x = 1
+@cfg 'debug'
+  print 'x: {x}'
+@cfg 'extra'
+  x += 1
+x
+";
+
+    #[test]
+    fn blocks_are_compiled_when_their_flag_is_enabled() {
+        let (result, output) = run_script(SCRIPT, &["debug", "extra"]);
+        assert!(matches!(result, KValue::Number(n) if n == 2));
+        assert_eq!(output, "x: 1\n");
+    }
+
+    #[test]
+    fn blocks_are_removed_when_their_flag_is_disabled() {
+        let (result, output) = run_script(SCRIPT, &["extra"]);
+        assert!(matches!(result, KValue::Number(n) if n == 2));
+        assert!(output.is_empty());
+
+        let (result, output) = run_script(SCRIPT, &[]);
+        assert!(matches!(result, KValue::Number(n) if n == 1));
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn disabled_blocks_evaluate_to_null() {
+        let script = "
+x = @cfg 'debug'
+  42
+x
+";
+        let (result, _) = run_script(script, &[]);
+        assert!(matches!(result, KValue::Null));
+
+        let (result, _) = run_script(script, &["debug"]);
+        assert!(matches!(result, KValue::Number(n) if n == 42));
+    }
+}
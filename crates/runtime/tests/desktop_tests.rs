@@ -0,0 +1,74 @@
+#![cfg(all(feature = "desktop", unix, not(target_os = "macos")))]
+
+mod desktop {
+    use koto_bytecode::{CompilerSettings, Loader};
+    use koto_runtime::prelude::*;
+    use std::{env, fs, os::unix::fs::PermissionsExt, path::Path};
+
+    fn run_script(script: &str) -> koto_runtime::Result<KValue> {
+        let mut loader = Loader::default();
+        let chunk = loader
+            .compile_script(script, None, CompilerSettings::default())
+            .expect("failed to compile script");
+        KotoVm::default().run(chunk)
+    }
+
+    fn write_tool(dir: &Path, name: &str, script: &str) {
+        let path = dir.join(name);
+        fs::write(&path, format!("#!/bin/sh\n{script}\n")).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    // The tests share a single function as they modify the process's environment
+    #[test]
+    fn clipboard_and_notify_use_the_platform_tools() {
+        let dir = env::temp_dir().join(format!("koto_desktop_tests_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let clipboard = dir.join("clipboard");
+        let notifications = dir.join("notifications");
+
+        // Fake tools that store the clipboard and notifications in files
+        write_tool(
+            &dir,
+            "xclip",
+            &format!(
+                r#"if [ "$3" = "-in" ]; then /bin/cat > {0}; else /bin/cat {0}; fi"#,
+                clipboard.display()
+            ),
+        );
+        write_tool(
+            &dir,
+            "notify-send",
+            &format!(r#"echo "$2|$3" >> {}"#, notifications.display()),
+        );
+
+        env::set_var("PATH", &dir);
+        env::remove_var("WAYLAND_DISPLAY");
+
+        let result = run_script(
+            "
+os.clipboard_set 'Hello, clipboard!'
+os.notify 'Title', 'Body with \"quotes\"'
+os.clipboard_get()
+",
+        )
+        .unwrap();
+        assert!(matches!(result, KValue::Str(s) if s == "Hello, clipboard!"));
+        assert_eq!(
+            fs::read_to_string(&notifications).unwrap(),
+            "Title|Body with \"quotes\"\n"
+        );
+
+        // Missing tools result in an error that lists the supported tools
+        fs::remove_file(dir.join("notify-send")).unwrap();
+        let error = run_script("os.notify 'Title', 'Body'").unwrap_err();
+        assert!(error.to_string().contains("notify-send"));
+
+        // Errors from tools are passed on to the script
+        write_tool(&dir, "xclip", "echo 'clipboard unavailable' >&2; exit 1");
+        let error = run_script("os.clipboard_get()").unwrap_err();
+        assert!(error.to_string().contains("clipboard unavailable"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
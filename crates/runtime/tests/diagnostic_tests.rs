@@ -0,0 +1,125 @@
+mod diagnostics {
+    use koto_bytecode::{CompilerSettings, Loader};
+    use koto_runtime::{prelude::*, Diagnostic, DiagnosticKind, PtrMut};
+
+    fn run_script(vm: &mut KotoVm, script: &str) -> koto_runtime::Result<KValue> {
+        let mut loader = Loader::default();
+        let chunk = match loader.compile_script(script, None, CompilerSettings::default()) {
+            Ok(chunk) => chunk,
+            Err(error) => {
+                panic!("Error while compiling script: {error}");
+            }
+        };
+
+        vm.run(chunk)
+    }
+
+    fn reported_lines(reports: &[Diagnostic]) -> Vec<u32> {
+        reports
+            .iter()
+            .map(|report| report.location.as_ref().unwrap().span.start.line)
+            .collect()
+    }
+
+    #[test]
+    fn deprecated_calls_are_reported_once_per_call_site() {
+        let mut vm = KotoVm::default();
+
+        let script = "
+@deprecated 'use new_fn'
+old_fn = |x| x
+
+for i in 0..10
+  old_fn i
+old_fn 42
+";
+        run_script(&mut vm, script).unwrap();
+
+        let reports = vm.diagnostics().take();
+        assert_eq!(reported_lines(&reports), [5, 6]);
+        for report in reports.iter() {
+            assert_eq!(report.kind, DiagnosticKind::Deprecated);
+            assert_eq!(report.message, "use new_fn");
+        }
+
+        assert!(vm.diagnostics().take().is_empty());
+    }
+
+    #[test]
+    fn deprecated_generator_and_capture_function() {
+        let mut vm = KotoVm::default();
+
+        let script = "
+y = 1
+@deprecated 'captures'
+f = |x| x + y
+@deprecated 'generator'
+g = ||
+  yield 1
+f 1
+g().to_tuple()
+";
+        run_script(&mut vm, script).unwrap();
+
+        let messages: Vec<_> = vm
+            .diagnostics()
+            .take()
+            .into_iter()
+            .map(|report| report.message)
+            .collect();
+        assert_eq!(messages, ["captures", "generator"]);
+    }
+
+    #[test]
+    fn callback_is_called_for_reports() {
+        let mut vm = KotoVm::default();
+
+        let reported = PtrMut::from(Vec::new());
+        vm.diagnostics().set_callback({
+            let reported = reported.clone();
+            move |report: &Diagnostic| reported.borrow_mut().push(report.to_string())
+        });
+
+        let script = "
+@deprecated 'use g'
+f = || null
+for _ in 0..3
+  f()
+";
+        run_script(&mut vm, script).unwrap();
+
+        assert_eq!(*reported.borrow(), ["[5] Deprecated: use g"]);
+        assert!(vm.diagnostics().take().is_empty());
+    }
+
+    #[test]
+    fn calls_made_by_the_host_have_no_location() {
+        let mut vm = KotoVm::default();
+
+        let f = run_script(&mut vm, "@deprecated 'use g'\nexport f = |x| x\nf").unwrap();
+        vm.call_function(f.clone(), &[1.into()]).unwrap();
+        vm.call_function(f, &[2.into()]).unwrap();
+
+        let reports = vm.diagnostics().take();
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].location.is_none());
+    }
+
+    #[test]
+    fn call_sites_are_reported_again_after_reset() {
+        let mut vm = KotoVm::default();
+        let script = "
+@deprecated 'use g'
+f = || null
+f()
+";
+
+        run_script(&mut vm, script).unwrap();
+        run_script(&mut vm, script).unwrap();
+        assert_eq!(vm.diagnostics().take().len(), 1);
+
+        vm.reset();
+        run_script(&mut vm, script).unwrap();
+        assert_eq!(vm.diagnostics().take().len(), 1);
+    }
+}
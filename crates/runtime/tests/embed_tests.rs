@@ -0,0 +1,62 @@
+mod embed {
+    use koto_bytecode::{CompilerSettings, Loader};
+    use koto_runtime::prelude::*;
+    use std::{env, fs, path::PathBuf};
+
+    // Creates an empty temporary directory for a test
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("koto_embed_tests_{}_{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("data")).expect("Failed to create test dir");
+        fs::write(dir.join("script.koto"), "").expect("Failed to create script");
+        dir
+    }
+
+    #[test]
+    fn embedded_files_are_loaded_relative_to_the_script() {
+        let dir = test_dir("relative");
+        fs::write(dir.join("data").join("table.csv"), "a,b\n1,2\n").unwrap();
+        let script_path = dir.join("script.koto");
+
+        let mut loader = Loader::default();
+        let chunk = loader
+            .compile_script(
+                "@embed 'data/table.csv'",
+                Some(&script_path),
+                CompilerSettings::default(),
+            )
+            .unwrap();
+
+        // The file isn't needed once the script has been compiled
+        fs::remove_dir_all(&dir).unwrap();
+
+        match KotoVm::default().run(chunk).unwrap() {
+            KValue::Str(contents) => assert_eq!(contents.as_str(), "a,b\n1,2\n"),
+            unexpected => panic!("Unexpected result: {unexpected:?}"),
+        }
+    }
+
+    #[test]
+    fn missing_embedded_files_are_reported() {
+        let dir = test_dir("missing");
+        let script_path = dir.join("script.koto");
+
+        let mut loader = Loader::default();
+        let error = loader
+            .compile_script(
+                "x = @embed 'data/missing.csv'",
+                Some(&script_path),
+                CompilerSettings::default(),
+            )
+            .unwrap_err();
+        assert!(
+            error
+                .error
+                .to_string()
+                .starts_with("Failed to embed 'data/missing.csv': "),
+            "{error}"
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
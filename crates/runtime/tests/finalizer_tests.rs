@@ -0,0 +1,127 @@
+mod finalizers {
+    use koto_bytecode::{CompilerSettings, Loader};
+    use koto_runtime::{prelude::*, PtrMut};
+
+    fn run_script(vm: &mut KotoVm, script: &str) -> koto_runtime::Result<KValue> {
+        let mut loader = Loader::default();
+        let chunk = match loader.compile_script(script, None, CompilerSettings::default()) {
+            Ok(chunk) => chunk,
+            Err(error) => {
+                panic!("Error while compiling script: {error}");
+            }
+        };
+
+        vm.run(chunk)
+    }
+
+    fn make_log() -> PtrMut<Vec<String>> {
+        PtrMut::from(Vec::new())
+    }
+
+    fn add_logging_finalizer(vm: &KotoVm, log: &PtrMut<Vec<String>>, id: &str) {
+        let log = log.clone();
+        let id = id.to_string();
+        vm.add_finalizer(move || log.borrow_mut().push(id));
+    }
+
+    #[test]
+    fn finalizers_run_in_reverse_order_on_drop() {
+        let log = make_log();
+
+        {
+            let vm = KotoVm::default();
+            add_logging_finalizer(&vm, &log, "a");
+            add_logging_finalizer(&vm, &log, "b");
+            add_logging_finalizer(&vm, &log, "c");
+            assert!(log.borrow().is_empty());
+        }
+
+        assert_eq!(*log.borrow(), ["c", "b", "a"]);
+    }
+
+    #[test]
+    fn finalizers_run_after_shared_vms_are_dropped() {
+        let log = make_log();
+
+        let vm = KotoVm::default();
+        let shared_vm = vm.spawn_shared_vm();
+        add_logging_finalizer(&shared_vm, &log, "shared");
+
+        drop(vm);
+        assert!(log.borrow().is_empty());
+
+        drop(shared_vm);
+        assert_eq!(*log.borrow(), ["shared"]);
+    }
+
+    #[test]
+    fn run_finalizers_explicitly() {
+        let log = make_log();
+
+        let vm = KotoVm::default();
+        add_logging_finalizer(&vm, &log, "a");
+        add_logging_finalizer(&vm, &log, "b");
+
+        vm.run_finalizers();
+        assert_eq!(*log.borrow(), ["b", "a"]);
+
+        // Finalizers are only called once
+        drop(vm);
+        assert_eq!(*log.borrow(), ["b", "a"]);
+    }
+
+    #[test]
+    fn finalizers_run_on_reset() {
+        let log = make_log();
+
+        let mut vm = KotoVm::default();
+        add_logging_finalizer(&vm, &log, "a");
+        add_logging_finalizer(&vm, &log, "b");
+
+        vm.reset();
+        assert_eq!(*log.borrow(), ["b", "a"]);
+
+        // Finalizers registered after the reset are called on the next reset
+        add_logging_finalizer(&vm, &log, "c");
+        vm.reset();
+        assert_eq!(*log.borrow(), ["b", "a", "c"]);
+
+        // Finalizers are only called once
+        drop(vm);
+        assert_eq!(*log.borrow(), ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn finalizer_registered_by_native_function_after_script_error() {
+        let log = make_log();
+
+        {
+            let mut vm = KotoVm::default();
+
+            vm.prelude().add_fn("open_resource", {
+                let log = log.clone();
+                move |ctx| {
+                    let id = match ctx.args() {
+                        [KValue::Str(id)] => id.to_string(),
+                        unexpected => return type_error_with_slice("a String", unexpected),
+                    };
+                    log.borrow_mut().push(format!("open {id}"));
+                    let log = log.clone();
+                    ctx.vm
+                        .add_finalizer(move || log.borrow_mut().push(format!("close {id}")));
+                    Ok(KValue::Null)
+                }
+            });
+
+            let script = "
+open_resource 'x'
+open_resource 'y'
+throw 'oops'
+";
+            assert!(run_script(&mut vm, script).is_err());
+            assert_eq!(*log.borrow(), ["open x", "open y"]);
+        }
+
+        assert_eq!(*log.borrow(), ["open x", "open y", "close y", "close x"]);
+    }
+}
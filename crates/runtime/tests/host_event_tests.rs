@@ -0,0 +1,165 @@
+mod host_events {
+    use koto_bytecode::{CompilerSettings, Loader};
+    use koto_runtime::{prelude::*, PtrMut};
+
+    fn run_script(vm: &mut KotoVm, script: &str) -> koto_runtime::Result<KValue> {
+        let mut loader = Loader::default();
+        let chunk = match loader.compile_script(script, None, CompilerSettings::default()) {
+            Ok(chunk) => chunk,
+            Err(error) => {
+                panic!("Error while compiling script: {error}");
+            }
+        };
+
+        vm.run(chunk)
+    }
+
+    #[test]
+    fn emit_calls_the_registered_callback() {
+        let mut vm = KotoVm::default();
+        let saved = PtrMut::from(Vec::new());
+
+        vm.host_events().register_callback("save_requested", {
+            let saved = saved.clone();
+            move |data| {
+                saved.borrow_mut().push(data.clone());
+                Ok(true.into())
+            }
+        });
+
+        let result = run_script(
+            &mut vm,
+            "
+assert koto.host.has_callback 'save_requested'
+assert not koto.host.has_callback 'other'
+koto.host.emit 'save_requested', 42
+",
+        )
+        .unwrap();
+
+        assert!(matches!(result, KValue::Bool(true)));
+        assert!(matches!(saved.borrow().as_slice(), [KValue::Number(n)] if *n == 42));
+    }
+
+    #[test]
+    fn emitting_without_a_callback_throws_an_error() {
+        let mut vm = KotoVm::default();
+
+        let result = run_script(&mut vm, "koto.host.emit 'unknown'");
+
+        assert!(result.is_err());
+        assert!(vm.host_events().take_emitted().is_empty());
+    }
+
+    #[test]
+    fn unhandled_events_can_be_queued() {
+        let mut vm = KotoVm::default();
+        vm.host_events().set_queue_unhandled(true);
+
+        let result = run_script(
+            &mut vm,
+            "
+koto.host.emit 'first'
+koto.host.emit 'second', 'data'
+",
+        )
+        .unwrap();
+
+        assert!(matches!(result, KValue::Null));
+        let emitted = vm.host_events().take_emitted();
+        assert_eq!(emitted.len(), 2);
+        assert_eq!(emitted[0].name, "first");
+        assert!(matches!(emitted[0].data, KValue::Null));
+        assert_eq!(emitted[1].name, "second");
+        assert!(matches!(&emitted[1].data, KValue::Str(s) if s.as_str() == "data"));
+        assert!(vm.host_events().take_emitted().is_empty());
+    }
+
+    #[test]
+    fn dispatch_calls_script_handlers() {
+        let mut vm = KotoVm::default();
+
+        run_script(
+            &mut vm,
+            "
+export received = []
+koto.host.on 'tick', |n| received.push n
+koto.host.on 'tick', |n| received.push n * 10
+",
+        )
+        .unwrap();
+
+        assert!(vm.host_events().has_handler("tick"));
+        assert_eq!(vm.dispatch_host_event("tick", 1).unwrap(), 2);
+        assert_eq!(vm.dispatch_host_event("unhandled", 1).unwrap(), 0);
+
+        let result = run_script(&mut vm, "received").unwrap();
+        let KValue::List(received) = result else {
+            panic!("Expected a list");
+        };
+        assert_eq!(received.len(), 2);
+    }
+
+    #[test]
+    fn posted_events_are_processed_in_order() {
+        let mut vm = KotoVm::default();
+
+        run_script(
+            &mut vm,
+            "
+export received = []
+koto.host.on 'message', |text| received.push text
+",
+        )
+        .unwrap();
+
+        let host_events = vm.host_events().clone();
+        host_events.post("message", "a");
+        host_events.post("message", "b");
+        assert_eq!(host_events.pending(), 2);
+
+        assert_eq!(vm.process_host_events().unwrap(), 2);
+        assert_eq!(host_events.pending(), 0);
+
+        // Scripts can also process posted events
+        host_events.post("message", "c");
+        let result = run_script(
+            &mut vm,
+            "
+assert_eq koto.host.process_events(), 1
+received
+",
+        )
+        .unwrap();
+        let KValue::List(received) = result else {
+            panic!("Expected a list");
+        };
+        let received: Vec<String> = received
+            .data()
+            .iter()
+            .map(|value| match value {
+                KValue::Str(s) => s.to_string(),
+                _ => panic!("Expected a string"),
+            })
+            .collect();
+        assert_eq!(received, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn handlers_can_be_removed() {
+        let mut vm = KotoVm::default();
+
+        run_script(
+            &mut vm,
+            "
+koto.host.on 'tick', |_| throw 'unexpected'
+assert koto.host.off 'tick'
+assert not koto.host.off 'tick'
+",
+        )
+        .unwrap();
+
+        assert!(!vm.host_events().has_handler("tick"));
+        assert_eq!(vm.dispatch_host_event("tick", KValue::Null).unwrap(), 0);
+    }
+}
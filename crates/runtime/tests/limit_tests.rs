@@ -0,0 +1,167 @@
+mod limits {
+    use koto_bytecode::{CompilerSettings, Loader};
+    use koto_runtime::{prelude::*, Error, ErrorKind, MemoryLimit, Result};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    fn run_script(vm: &mut KotoVm, script: &str) -> Result<KValue> {
+        let mut loader = Loader::default();
+        let chunk = match loader.compile_script(script, None, CompilerSettings::default()) {
+            Ok(chunk) => chunk,
+            Err(error) => {
+                panic!("Error while compiling script: {error}");
+            }
+        };
+
+        vm.run(chunk)
+    }
+
+    fn vm_with_instruction_limit(limit: u64) -> KotoVm {
+        KotoVm::with_settings(KotoVmSettings {
+            instruction_limit: Some(limit),
+            ..Default::default()
+        })
+    }
+
+    mod instruction_limit {
+        use super::*;
+
+        #[test]
+        fn within_limit() {
+            let script = "
+n = 0
+while n < 100
+  n += 1
+n
+";
+            let mut vm = vm_with_instruction_limit(10_000);
+            assert!(matches!(
+                run_script(&mut vm, script),
+                Ok(KValue::Number(n)) if n == 100
+            ));
+        }
+
+        #[test]
+        fn infinite_loop() {
+            let script = "
+while true
+  ()
+";
+            let mut vm = vm_with_instruction_limit(10_000);
+            match run_script(&mut vm, script) {
+                Err(Error {
+                    error: ErrorKind::InstructionLimit(10_000),
+                    ..
+                }) => {}
+                other => panic!("Unexpected result: {other:?}"),
+            }
+        }
+
+        #[test]
+        fn limit_below_check_interval() {
+            let script = "
+n = 0
+while n < 100
+  n += 1
+n
+";
+            let mut vm = vm_with_instruction_limit(100);
+            assert!(matches!(
+                run_script(&mut vm, script),
+                Err(Error {
+                    error: ErrorKind::InstructionLimit(100),
+                    ..
+                })
+            ));
+        }
+
+        #[test]
+        fn limit_cant_be_caught() {
+            let script = "
+try
+  while true
+    ()
+catch _
+  42
+";
+            let mut vm = vm_with_instruction_limit(10_000);
+            assert!(matches!(
+                run_script(&mut vm, script),
+                Err(Error {
+                    error: ErrorKind::InstructionLimit(_),
+                    ..
+                })
+            ));
+        }
+
+        #[test]
+        fn count_is_shared_across_calls() {
+            let script = "
+f = ||
+  n = 0
+  while n < 100
+    n += 1
+f
+";
+            let mut vm = vm_with_instruction_limit(10_000);
+            let f = run_script(&mut vm, script).unwrap();
+
+            let result = (0..1000).try_for_each(|_| vm.call_function(f.clone(), &[]).map(|_| ()));
+
+            assert!(matches!(
+                result,
+                Err(Error {
+                    error: ErrorKind::InstructionLimit(_),
+                    ..
+                })
+            ));
+        }
+    }
+
+    mod memory_limit {
+        use super::*;
+
+        fn vm_with_memory_usage(usage: Arc<AtomicUsize>) -> KotoVm {
+            KotoVm::with_settings(KotoVmSettings {
+                memory_limit: Some(MemoryLimit {
+                    max_bytes: 1000,
+                    usage: Box::new(move || usage.load(Ordering::Relaxed)),
+                }),
+                ..Default::default()
+            })
+        }
+
+        #[test]
+        fn within_limit() {
+            let script = "
+n = 0
+while n < 10000
+  n += 1
+n
+";
+            let mut vm = vm_with_memory_usage(Arc::new(AtomicUsize::new(1000)));
+            assert!(matches!(
+                run_script(&mut vm, script),
+                Ok(KValue::Number(n)) if n == 10_000
+            ));
+        }
+
+        #[test]
+        fn limit_exceeded() {
+            let script = "
+while true
+  ()
+";
+            let mut vm = vm_with_memory_usage(Arc::new(AtomicUsize::new(1001)));
+            match run_script(&mut vm, script) {
+                Err(Error {
+                    error: ErrorKind::MemoryLimit(1000),
+                    ..
+                }) => {}
+                other => panic!("Unexpected result: {other:?}"),
+            }
+        }
+    }
+}
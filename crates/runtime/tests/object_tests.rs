@@ -172,6 +172,26 @@ mod objects {
             arithmetic_op!(self, rhs, %)
         }
 
+        fn bitwise_and(&self, rhs: &KValue) -> Result<KValue> {
+            arithmetic_op!(self, rhs, &)
+        }
+
+        fn bitwise_or(&self, rhs: &KValue) -> Result<KValue> {
+            arithmetic_op!(self, rhs, |)
+        }
+
+        fn bitwise_xor(&self, rhs: &KValue) -> Result<KValue> {
+            arithmetic_op!(self, rhs, ^)
+        }
+
+        fn shift_left(&self, rhs: &KValue) -> Result<KValue> {
+            arithmetic_op!(self, rhs, <<)
+        }
+
+        fn shift_right(&self, rhs: &KValue) -> Result<KValue> {
+            arithmetic_op!(self, rhs, >>)
+        }
+
         fn add_assign(&mut self, rhs: &KValue) -> Result<()> {
             assignment_op!(self, rhs, +=)
         }
@@ -426,6 +446,27 @@ x.as_number()
             test_object_script(script, 5);
         }
 
+        #[test]
+        fn bitwise_ops() {
+            let script = "
+x = (make_object 0b1100) & (make_object 0b1010)
+x = x | 0b0001
+x = x ^ (make_object 0b1111)
+x.as_number()
+";
+            test_object_script(script, 0b0110);
+        }
+
+        #[test]
+        fn shifts() {
+            let script = "
+x = (make_object 1) << (make_object 4)
+x = x >> 2
+x.as_number()
+";
+            test_object_script(script, 4);
+        }
+
         #[test]
         fn add_assign() {
             let script = "
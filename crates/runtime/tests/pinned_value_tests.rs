@@ -0,0 +1,97 @@
+mod pinned_values {
+    use koto_bytecode::{CompilerSettings, Loader};
+    use koto_runtime::{prelude::*, ValueHandle};
+
+    fn run_script(vm: &mut KotoVm, script: &str) -> koto_runtime::Result<KValue> {
+        let mut loader = Loader::default();
+        let chunk = match loader.compile_script(script, None, CompilerSettings::default()) {
+            Ok(chunk) => chunk,
+            Err(error) => {
+                panic!("Error while compiling script: {error}");
+            }
+        };
+
+        vm.run(chunk)
+    }
+
+    #[test]
+    fn pin_and_get() {
+        let vm = KotoVm::default();
+
+        let a = vm.pin(42);
+        let b = vm.pin("hello");
+
+        assert_ne!(a, b);
+        assert!(matches!(vm.pinned(a), Some(KValue::Number(n)) if n == 42));
+        assert!(matches!(vm.pinned(b), Some(KValue::Str(s)) if s == "hello"));
+        assert_eq!(vm.pinned_values().len(), 2);
+    }
+
+    #[test]
+    fn unpinned_handles_are_not_reused() {
+        let vm = KotoVm::default();
+
+        let a = vm.pin(1);
+        assert!(vm.unpin(a).is_some());
+        assert!(vm.unpin(a).is_none());
+
+        let b = vm.pin(2);
+        assert_ne!(a, b);
+        assert!(vm.pinned(a).is_none());
+        assert!(!vm.pinned_values().is_pinned(a));
+    }
+
+    #[test]
+    fn replace_pinned_value() {
+        let vm = KotoVm::default();
+
+        let handle = vm.pin(1);
+        let previous = vm.pinned_values().replace(handle, 2);
+
+        assert!(matches!(previous, Some(KValue::Number(n)) if n == 1));
+        assert!(matches!(vm.pinned(handle), Some(KValue::Number(n)) if n == 2));
+
+        vm.unpin(handle);
+        assert!(vm.pinned_values().replace(handle, 3).is_none());
+        assert!(vm.pinned_values().is_empty());
+    }
+
+    #[test]
+    fn pinned_script_function_outlives_its_module() {
+        let mut vm = KotoVm::default();
+
+        let f = run_script(&mut vm, "|x| x * 2").unwrap();
+        let handle = vm.pin(f);
+
+        // Running another script replaces the VM's exports and registers
+        run_script(&mut vm, "export x = 99").unwrap();
+
+        let f = vm.pinned(handle).unwrap();
+        let result = vm.call_function(f, &[21.into()]).unwrap();
+        assert!(matches!(result, KValue::Number(n) if n == 42));
+    }
+
+    #[test]
+    fn handles_can_be_passed_via_ids() {
+        let vm = KotoVm::default();
+        let pinned_values = vm.pinned_values().clone();
+
+        let id = vm.pin(KMap::new()).id();
+
+        // Clones of the pinned values refer to the same state
+        let handle = ValueHandle::from_id(id);
+        assert!(matches!(pinned_values.get(handle), Some(KValue::Map(_))));
+
+        pinned_values.clear();
+        assert!(vm.pinned(handle).is_none());
+    }
+
+    #[test]
+    fn pinned_values_are_shared_with_spawned_vms() {
+        let vm = KotoVm::default();
+        let handle = vm.pin(123);
+
+        let shared = vm.spawn_shared_vm();
+        assert!(matches!(shared.pinned(handle), Some(KValue::Number(n)) if n == 123));
+    }
+}
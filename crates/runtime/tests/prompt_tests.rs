@@ -0,0 +1,218 @@
+mod prompt {
+    use koto_bytecode::{CompilerSettings, Loader};
+    use koto_runtime::{prelude::*, KeyInput, Ptr, PtrMut, Result};
+    use koto_test_utils::OutputCapture;
+    use std::collections::VecDeque;
+
+    // A stdin that provides predefined input, optionally acting as a terminal
+    #[derive(Clone, Default)]
+    struct ScriptedInput {
+        lines: PtrMut<VecDeque<String>>,
+        keys: PtrMut<VecDeque<KeyInput>>,
+        terminal: bool,
+    }
+
+    impl ScriptedInput {
+        fn lines(lines: &[&str]) -> Self {
+            Self {
+                lines: make_ptr_mut!(lines
+                    .iter()
+                    .map(|line| format!("{line}\n"))
+                    .collect::<VecDeque<_>>()),
+                ..Default::default()
+            }
+        }
+
+        fn terminal(lines: &[&str], keys: &[KeyInput]) -> Self {
+            Self {
+                keys: make_ptr_mut!(keys.iter().copied().collect::<VecDeque<_>>()),
+                terminal: true,
+                ..Self::lines(lines)
+            }
+        }
+    }
+
+    impl KotoFile for ScriptedInput {
+        fn id(&self) -> KString {
+            "_scripted_input_".into()
+        }
+
+        fn is_terminal(&self) -> bool {
+            self.terminal
+        }
+    }
+
+    impl KotoWrite for ScriptedInput {}
+    impl KotoRead for ScriptedInput {
+        fn read_line(&self) -> Result<Option<String>> {
+            Ok(self.lines.borrow_mut().pop_front())
+        }
+
+        fn read_line_hidden(&self) -> Result<Option<String>> {
+            assert!(self.terminal);
+            self.read_line()
+        }
+
+        fn read_key(&self) -> Result<KeyInput> {
+            match self.keys.borrow_mut().pop_front() {
+                Some(key) => Ok(key),
+                None => runtime_error!("no more keys"),
+            }
+        }
+    }
+
+    fn run_script(input: ScriptedInput, script: &str) -> (KValue, String) {
+        let output = OutputCapture::default();
+        let mut vm = KotoVm::with_settings(KotoVmSettings {
+            stdin: make_ptr!(input),
+            stdout: make_ptr!(output.clone()),
+            ..Default::default()
+        });
+
+        let mut loader = Loader::default();
+        let chunk = loader
+            .compile_script(script, None, CompilerSettings::default())
+            .expect("failed to compile script");
+        let result = vm.run(chunk).expect("failed to run script");
+        let output = output.captured_output().clone();
+        (result, output)
+    }
+
+    fn string(s: &str) -> KValue {
+        s.into()
+    }
+
+    fn assert_result(result: KValue, expected: KValue) {
+        let mut vm = KotoVm::default();
+        let equal = vm
+            .run_binary_op(BinaryOp::Equal, result.clone(), expected.clone())
+            .unwrap();
+        assert!(
+            matches!(equal, KValue::Bool(true)),
+            "expected '{}', found '{}'",
+            vm.value_to_string(&expected).unwrap(),
+            vm.value_to_string(&result).unwrap(),
+        );
+    }
+
+    #[test]
+    fn prompt() {
+        let (result, output) = run_script(
+            ScriptedInput::lines(&["Koto", ""]),
+            "
+name = io.prompt 'Name: '
+greeting = io.prompt 'Greeting: ', 'Hello'
+missing = io.prompt 'Anything else? '
+name, greeting, missing
+",
+        );
+        assert_result(
+            result,
+            KValue::Tuple(vec![string("Koto"), string("Hello"), KValue::Null].into()),
+        );
+        assert_eq!(output, "Name: Greeting: Anything else? ");
+    }
+
+    #[test]
+    fn prompt_password_without_a_terminal() {
+        let (result, output) = run_script(
+            ScriptedInput::lines(&["secret"]),
+            "io.prompt_password 'Password: '",
+        );
+        assert_result(result, string("secret"));
+        assert_eq!(output, "Password: ");
+    }
+
+    #[test]
+    fn prompt_password_with_a_terminal() {
+        let (result, output) = run_script(
+            ScriptedInput::terminal(&["secret"], &[]),
+            "io.prompt_password 'Password: '",
+        );
+        assert_result(result, string("secret"));
+        assert_eq!(output, "Password: \n");
+    }
+
+    #[test]
+    fn confirm() {
+        let (result, output) = run_script(
+            ScriptedInput::lines(&["maybe", "Y", "no", "", ""]),
+            "
+a = io.confirm 'Proceed?'
+b = io.confirm 'Really?'
+c = io.confirm 'Default yes?', true
+d = io.confirm 'Default no?', false
+e = io.confirm 'Out of input?'
+a, b, c, d, e
+",
+        );
+        assert_result(
+            result,
+            KValue::Tuple(
+                vec![
+                    true.into(),
+                    false.into(),
+                    true.into(),
+                    false.into(),
+                    false.into(),
+                ]
+                .into(),
+            ),
+        );
+        assert_eq!(
+            output,
+            "\
+Proceed? [y/n] Please answer 'y' or 'n'.
+Proceed? [y/n] Really? [y/n] Default yes? [Y/n] Default no? [y/N] Out of input? [y/n] "
+        );
+    }
+
+    #[test]
+    fn select_with_numbers() {
+        let (result, output) = run_script(
+            ScriptedInput::lines(&["0", "x", "2"]),
+            "io.select 'Pick a color:', ['red', 'green', 'blue']",
+        );
+        assert_result(result, string("green"));
+        assert_eq!(
+            output,
+            "\
+Pick a color:
+  1) red
+  2) green
+  3) blue
+Select an option (1-3): Please enter a number between 1 and 3.
+Select an option (1-3): Please enter a number between 1 and 3.
+Select an option (1-3): "
+        );
+    }
+
+    #[test]
+    fn select_with_keys() {
+        let (result, output) = run_script(
+            ScriptedInput::terminal(&[], &[KeyInput::Up, KeyInput::Other, KeyInput::Enter]),
+            "io.select (1, 2, 3)",
+        );
+        assert_result(result, 3.into());
+        assert_eq!(
+            output,
+            "\
+\r\x1b[2K> 1
+\r\x1b[2K  2
+\r\x1b[2K  3
+\x1b[3A\r\x1b[2K  1
+\r\x1b[2K  2
+\r\x1b[2K> 3
+"
+        );
+    }
+
+    #[test]
+    fn cancelled_select() {
+        let (result, _) = run_script(
+            ScriptedInput::terminal(&[], &[KeyInput::Down, KeyInput::Cancel]),
+            "io.select ['a', 'b']",
+        );
+        assert_result(result, KValue::Null);
+    }
+}
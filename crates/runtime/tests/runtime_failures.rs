@@ -101,6 +101,23 @@ x
                 let script = "
 for i in 0..
   print i
+";
+                check_script_fails(script);
+            }
+
+            #[test]
+            fn negative_range_step() {
+                let script = "
+x = 0..10 step -1
+";
+                check_script_fails(script);
+            }
+
+            #[test]
+            fn stepped_range_used_as_index() {
+                let script = "
+x = [1, 2, 3, 4]
+x[0..4 step 2]
 ";
                 check_script_fails(script);
             }
@@ -214,6 +231,74 @@ x.reversed().next()
             }
         }
 
+        mod sealed_maps {
+            use super::*;
+
+            #[test]
+            fn add_entry_to_core_lib_module() {
+                let script = "
+list.answer = || 42
+";
+                check_script_fails(script);
+            }
+
+            #[test]
+            fn add_entry_to_core_lib_module_with_insert() {
+                let script = "
+map.insert string, 'answer', 42
+";
+                check_script_fails(script);
+            }
+
+            #[test]
+            fn extend_core_lib_module() {
+                let script = "
+map.extend number, {answer: 42}
+";
+                check_script_fails(script);
+            }
+
+            #[test]
+            fn assign_to_core_lib_entry() {
+                let script = "
+list.push = |x| x
+";
+                check_script_fails(script);
+            }
+
+            #[test]
+            fn replace_core_lib_entry_with_insert() {
+                let script = "
+map.insert string, 'to_uppercase', 42
+";
+                check_script_fails(script);
+            }
+
+            #[test]
+            fn remove_from_core_lib_module() {
+                let script = "
+map.remove list, 'push'
+";
+                check_script_fails(script);
+            }
+
+            #[test]
+            fn update_core_lib_entry() {
+                let script = "
+map.update tuple, 'first', |_| 42
+";
+                check_script_fails(script);
+            }
+
+            #[test]
+            fn clear_core_lib_module() {
+                let script = "
+map.clear koto
+";
+                check_script_fails(script);
+            }
+        }
+
         mod strings {
             use super::*;
 
@@ -0,0 +1,116 @@
+mod signals {
+    use koto_bytecode::{CompilerSettings, Loader};
+    use koto_runtime::{prelude::*, Signal};
+    use std::{thread, time::Duration};
+
+    fn run_script(vm: &mut KotoVm, script: &str) -> koto_runtime::Result<KValue> {
+        let mut loader = Loader::default();
+        let chunk = match loader.compile_script(script, None, CompilerSettings::default()) {
+            Ok(chunk) => chunk,
+            Err(error) => {
+                panic!("Error while compiling script: {error}");
+            }
+        };
+
+        vm.run(chunk)
+    }
+
+    #[test]
+    fn raised_signals_are_handled_during_execution() {
+        let mut vm = KotoVm::default();
+        let signals = vm.signals().clone();
+
+        let raiser = thread::spawn(move || {
+            while !signals.is_handled(Signal::Terminate) {
+                thread::sleep(Duration::from_millis(1));
+            }
+            signals.raise(Signal::Terminate);
+        });
+
+        let result = run_script(
+            &mut vm,
+            "
+state = {running: true, received: null}
+os.on_signal 'term', |name|
+  state.running = false
+  state.received = name
+while state.running
+  x = 1
+state.received
+",
+        )
+        .unwrap();
+
+        raiser.join().unwrap();
+        assert!(matches!(result, KValue::Str(s) if s == "term"));
+    }
+
+    #[test]
+    fn handlers_are_called_in_order_of_registration() {
+        let mut vm = KotoVm::default();
+
+        run_script(
+            &mut vm,
+            "
+export calls = []
+os.on_signal 'int', |_| calls.push 1
+os.on_signal 'hup', |_| calls.push 'hup'
+os.on_signal 'int', |_| calls.push 2
+",
+        )
+        .unwrap();
+
+        assert!(vm.signals().is_handled(Signal::Interrupt));
+        assert!(vm.signals().is_handled(Signal::Hangup));
+        assert!(!vm.signals().is_handled(Signal::Terminate));
+
+        vm.signals().raise(Signal::Interrupt);
+        vm.signals().raise(Signal::Interrupt);
+        vm.signals().raise(Signal::Terminate);
+        assert!(vm.signals().is_pending());
+        assert_eq!(vm.process_signals().unwrap(), 2);
+        assert!(!vm.signals().is_pending());
+
+        let calls = vm.exports().get("calls").unwrap();
+        let KValue::List(calls) = calls else {
+            panic!("expected a list")
+        };
+        assert_eq!(calls.len(), 2);
+        assert_eq!(vm.process_signals().unwrap(), 0);
+    }
+
+    #[test]
+    fn errors_thrown_by_handlers_can_be_caught() {
+        let mut vm = KotoVm::default();
+        let signals = vm.signals().clone();
+
+        let raiser = thread::spawn(move || {
+            while !signals.is_handled(Signal::User1) {
+                thread::sleep(Duration::from_millis(1));
+            }
+            signals.raise(Signal::User1);
+        });
+
+        let result = run_script(
+            &mut vm,
+            "
+os.on_signal 'usr1', |_| throw 'stopping'
+try
+  while true
+    x = 1
+catch error
+  error
+",
+        )
+        .unwrap();
+
+        raiser.join().unwrap();
+        assert!(matches!(result, KValue::Str(s) if s == "stopping"));
+    }
+
+    #[test]
+    fn unknown_signal_names_throw_an_error() {
+        let mut vm = KotoVm::default();
+        assert!(run_script(&mut vm, "os.on_signal 'kill', |_| null").is_err());
+    }
+}
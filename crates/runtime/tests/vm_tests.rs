@@ -1,4 +1,5 @@
 mod vm {
+    use koto_bytecode::{CompilerSettings, Loader};
     use koto_runtime::prelude::*;
     use koto_test_utils::*;
 
@@ -69,6 +70,94 @@ a = 99
         }
     }
 
+    mod bitwise {
+        use super::*;
+
+        #[test]
+        fn and_or_xor() {
+            check_script_output("0b1100 | 0b0011 ^ 0b0110 & 0b0101", 0b1111);
+        }
+
+        #[test]
+        fn shifts() {
+            check_script_output("(1 << 10) + (1024 >> 3)", 1152);
+        }
+
+        #[test]
+        fn shift_right_negative() {
+            check_script_output("-16 >> 2", -4);
+        }
+
+        #[test]
+        fn shift_by_64_or_more() {
+            check_script_output("(1 << 64) + (-1 >> 100)", -1);
+        }
+
+        #[test]
+        fn bitwise_not() {
+            let script = "
+x = 5
+~x";
+            check_script_output(script, -6);
+        }
+
+        #[test]
+        fn floats_are_truncated() {
+            check_script_output("(7.9 & 3) + (-1.5 | 0) + (2.5 << 1.9)", 6);
+        }
+
+        #[test]
+        fn bitwise_or_with_ids() {
+            let script = "
+f = |x| x << 1
+x = 1
+y = x | 4
+f y | 2";
+            check_script_output(script, 14);
+        }
+
+        #[test]
+        fn bitwise_or_without_whitespace() {
+            let script = "
+x = 1
+y = 4
+x|y|2";
+            check_script_output(script, 7);
+        }
+
+        #[test]
+        fn bitwise_or_in_function_with_default_arg() {
+            let script = "
+f = |x = 1| x | 2
+f()";
+            check_script_output(script, 3);
+        }
+
+        #[test]
+        fn bitwise_or_in_default_arg() {
+            let script = "
+f = |x = (1 | 2)| x
+f()";
+            check_script_output(script, 3);
+        }
+
+        #[test]
+        fn overloaded_ops() {
+            let script = "
+foo = |x|
+  x: x
+  @&: |other| foo self.x & other.x
+  @|: |other| foo self.x | other.x
+  @^: |other| foo self.x ^ other.x
+  @<<: |n| foo self.x << n
+  @>>: |n| foo self.x >> n
+x = ((foo 0b1100) & (foo 0b1010)) | (foo 0b0001)
+x = (x ^ (foo 0b1111)) << 4
+(x >> 1).x";
+            check_script_output(script, 0b0110 << 3);
+        }
+    }
+
     mod logic {
         use super::*;
 
@@ -196,6 +285,16 @@ a, b, c
             check_script_output("4..=0", KRange::from(4..=0));
             check_script_output("2 * 2..=3 * 3", KRange::from(4..=9));
         }
+
+        #[test]
+        fn range_with_step() {
+            check_script_output("0..10 step 2", KRange::from(0..10).with_step(2).unwrap());
+            check_script_output("10..=0 step 5", KRange::from(10..=0).with_step(5).unwrap());
+            check_script_output(
+                "x = 3\n1..x * 3 step x - 1",
+                KRange::from(1..9).with_step(2).unwrap(),
+            );
+        }
     }
 
     mod tuples {
@@ -608,6 +707,35 @@ x
 ";
             check_script_output(script, number_tuple(&[1, 2, 3]));
         }
+
+        #[test]
+        fn inline_if_in_list() {
+            let script = "
+x = false
+[1, if x then 2 else 3, 4]
+";
+            check_script_output(script, number_list(&[1, 3, 4]));
+        }
+
+        #[test]
+        fn inline_if_in_call_args() {
+            let script = "
+f = |a, b| a + b
+x = true
+f(if x then 10 else 20, 1) + f if x then 100 else 200, 2
+";
+            check_script_output(script, 113);
+        }
+
+        #[test]
+        fn inline_if_in_map() {
+            let script = "
+x = true
+m = {foo: if x then 'yes' else 'no', bar: 42}
+m.bar
+";
+            check_script_output(script, 42);
+        }
     }
 
     mod match_expressions {
@@ -665,6 +793,22 @@ match 0
             check_script_output(script, 42);
         }
 
+        #[test]
+        fn match_with_condition_using_nested_bindings() {
+            let script = "
+f = |point|
+  match point
+    (x, y) if x == y then 'diagonal {x}'
+    (x, (y, z)) if x == y + z then 'sum'
+    (x, _) then 'other {x}'
+(f (2, 2)), (f (3, (1, 2))), (f (4, 5))
+";
+            check_script_output(
+                script,
+                tuple(&["diagonal 2".into(), "sum".into(), "other 4".into()]),
+            );
+        }
+
         #[test]
         fn match_on_alternative() {
             let script = "
@@ -904,24 +1048,12 @@ switch
 x = switch
   false then 1, 2
   true then 3, 4
+  else 5, 6
 x
 "#;
             check_script_output(script, number_tuple(&[3, 4]));
         }
 
-        #[test]
-        fn missing_else_branch() {
-            // A bug meant that a missing else branch would leak previously assigned values
-            let script = r#"
-a, b = 42, 43
-x = switch
-  1 == 2 then 1, 2
-  3 == 4 then 3, 4
-x
-"#;
-            check_script_output(script, KValue::Null);
-        }
-
         #[test]
         fn assignment_target_used_in_switch_arm() {
             let script = r#"
@@ -929,6 +1061,7 @@ x = 10
 x = switch
   1 == 2 then 99
   3 <= 4 then x * x
+  else x
 x
 "#;
             check_script_output(script, 100);
@@ -986,6 +1119,93 @@ x
         }
     }
 
+    mod sealed_maps {
+        use super::*;
+
+        #[test]
+        fn copied_core_lib_module_can_be_modified() {
+            let script = "
+x = koto.copy list
+x.push = 42
+x.push
+";
+            check_script_output(script, 42);
+        }
+
+        #[test]
+        fn sealed_module_extended_by_embedder() {
+            let vm = KotoVm::default();
+            vm.prelude()
+                .data()
+                .get("list")
+                .and_then(|list| match list {
+                    KValue::Map(list) => Some(list.clone()),
+                    _ => None,
+                })
+                .unwrap()
+                .insert("answer", 42);
+
+            let script = "list.answer";
+            if let Err(e) = check_script_output_with_vm(vm, script, 42) {
+                panic!("{e}");
+            }
+        }
+
+        #[test]
+        fn unsealed_core_lib() {
+            let vm = KotoVm::with_settings(KotoVmSettings {
+                seal_core_lib: false,
+                ..Default::default()
+            });
+
+            let script = "
+list.first = || 42
+[].first()
+";
+            if let Err(e) = check_script_output_with_vm(vm, script, 42) {
+                panic!("{e}");
+            }
+        }
+
+        #[test]
+        fn sealed_map_is_released_after_reset() {
+            let mut vm = KotoVm::default();
+            let sealed = KMap::default();
+            vm.seal_map(&sealed);
+            vm.exports().insert("sealed", sealed.clone());
+            assert_eq!(sealed.ref_count(), 2);
+
+            vm.reset();
+
+            // The runtime doesn't hold on to sealed maps, the seal is stored with the map
+            assert_eq!(sealed.ref_count(), 1);
+            assert!(vm.is_sealed(&sealed));
+        }
+
+        #[test]
+        fn map_sealed_by_embedder() {
+            let vm = KotoVm::default();
+            let sealed = KMap::default();
+            sealed.insert("x", 1);
+            vm.seal_map(&sealed);
+            vm.prelude().insert("sealed", sealed);
+
+            let script = "
+result = try
+  sealed.x = 2
+  'not sealed'
+catch _
+  'sealed'
+result, sealed.x
+";
+            if let Err(e) =
+                check_script_output_with_vm(vm, script, tuple(&["sealed".into(), 1.into()]))
+            {
+                panic!("{e}");
+            }
+        }
+    }
+
     mod functions {
         use super::*;
 
@@ -1515,8 +1735,8 @@ add = |a, b| a + b
 multiply = |a, b| a * b
 square = |x| x * x
 add 1, 2
-  >> square
-  >> multiply 10
+  -> square
+  -> multiply 10
 ";
                 check_script_output(script, 90);
             }
@@ -1530,9 +1750,9 @@ ops =
   square: |x| x * x
 
 2
-  >> ops.add 1
-  >> ops.square
-  >> ops.multiply 2
+  -> ops.add 1
+  -> ops.square
+  -> ops.multiply 2
 ";
                 check_script_output(script, 18);
             }
@@ -1547,10 +1767,10 @@ ops = [inc, dec]
 get_op = |i| ops[i]
 
 0
-  >> ops[0]     # 1
-  >> get_op(0)  # 2
-  >> (get_op 0) # 3
-  >> get_op(1)  # 2
+  -> ops[0]     # 1
+  -> get_op(0)  # 2
+  -> (get_op 0) # 3
+  -> get_op(1)  # 2
 ";
                 check_script_output(script, 2);
             }
@@ -1567,7 +1787,7 @@ g = |x|
   calls.push x
   f
 
-g(1)(100) >> g(2) >> g(3) >> g(4)
+g(1)(100) -> g(2) -> g(3) -> g(4)
 
 calls
 ";
@@ -2373,6 +2593,73 @@ f()(8)
 ";
             check_script_output(script, 64);
         }
+
+        #[test]
+        fn optional_access() {
+            let script = "
+config = {server: {port: 8080}}
+config?.server?.port
+";
+            check_script_output(script, 8080);
+        }
+
+        #[test]
+        fn optional_access_with_missing_key() {
+            let script = "
+config = {}
+config?.server?.port
+";
+            check_script_output(script, KValue::Null);
+        }
+
+        #[test]
+        fn optional_access_on_null() {
+            let script = "
+x = null
+x?.foo
+";
+            check_script_output(script, KValue::Null);
+        }
+
+        #[test]
+        fn optional_access_with_quoted_key() {
+            let script = "
+m = {'key 1': 42}
+m?.'key 1'
+";
+            check_script_output(script, 42);
+        }
+
+        #[test]
+        fn optional_access_in_chain_with_call() {
+            let script = "
+m = {foo: || {bar: || 99}}
+m?.foo()?.bar()
+";
+            check_script_output(script, 99);
+        }
+
+        #[test]
+        fn optional_access_unwraps_ok_result() {
+            let script = "
+f = || ok {foo: 42}
+g = || ok f()?.foo
+g()?
+";
+            check_script_output(script, 42);
+        }
+
+        #[test]
+        fn optional_access_propagates_err_result() {
+            let script = "
+f = || err 'oops'
+g = ||
+  x = f()?.foo
+  ok x
+g() == err 'oops'
+";
+            check_script_output(script, true);
+        }
     }
 
     mod placeholders {
@@ -2494,15 +2781,24 @@ gen().to_tuple()
 
         #[test]
         fn generator_as_iterator_adaptor() {
+            // Extending the iterator module requires the core library to be unsealed
+            let vm = KotoVm::with_settings(KotoVmSettings {
+                seal_core_lib: false,
+                ..Default::default()
+            });
+
             let script = "
 iterator.every_other = ||
   n = 0
   for output in self
     if n % 2 == 0 then yield output
     n += 1
-(1..=5).every_other().to_tuple()
+(1..=5).every_other().to_tuple(), (10..15).each(|x| x * 2).every_other().to_tuple()
 ";
-            check_script_output(script, number_tuple(&[1, 3, 5]));
+            let expected = tuple(&[number_tuple(&[1, 3, 5]), number_tuple(&[20, 24, 28])]);
+            if let Err(e) = check_script_output_with_vm(vm, script, expected) {
+                panic!("{e}");
+            }
         }
 
         #[test]
@@ -2597,6 +2893,40 @@ gen().to_tuple()
         fn escaped_backslash() {
             check_script_output(r#""\\""#, "\\");
         }
+
+        #[test]
+        fn escape_codes() {
+            check_script_output(r"'\x41\u{1F44B}\u{0}\t'", "A👋\0\t");
+        }
+
+        #[test]
+        fn escape_round_trip() {
+            let compile = |script: &str| {
+                Loader::default()
+                    .compile_script(script, None, CompilerSettings::default())
+                    .unwrap()
+            };
+
+            for original in [
+                "{1 + 1}",
+                "'single' \"double\"",
+                "back\\slash",
+                "tab\tnewline\r\n",
+                "👋 ñ \u{0} \u{7f} \u{10ffff}",
+            ] {
+                let mut vm = KotoVm::default();
+                vm.exports_mut().insert("x", original);
+                let escaped = match vm.run(compile("x.escape()")).unwrap() {
+                    KValue::Str(escaped) => escaped,
+                    unexpected => panic!("Unexpected escape result: {unexpected:?}"),
+                };
+
+                match vm.run(compile(&format!("'{escaped}'"))).unwrap() {
+                    KValue::Str(result) => assert_eq!(result.as_str(), original),
+                    unexpected => panic!("Unexpected round trip result: {unexpected:?}"),
+                }
+            }
+        }
     }
 
     mod string_interpolation {
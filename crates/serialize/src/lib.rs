@@ -1,5 +1,11 @@
 //! Serde serialization support for Koto value types
 
+mod options;
+
+pub use options::{
+    NonFiniteMode, NullMode, RangeMode, SerializableValueWithOptions, SerializeOptions, TupleMode,
+};
+
 use koto_runtime::KValue;
 use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
 
@@ -0,0 +1,318 @@
+use crate::SerializableValue;
+use koto_runtime::{prelude::*, Result};
+use serde::ser::{Error, Serialize, SerializeMap, SerializeSeq, Serializer};
+use std::fmt;
+
+/// How null values are serialized
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NullMode {
+    /// Nulls are serialized as the format's null value
+    Keep,
+    /// Nulls are left out of the output
+    Skip,
+    /// An error is produced when a null value is found
+    Error,
+}
+
+/// How non-finite numbers (NaN and infinity) are serialized
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NonFiniteMode {
+    /// Non-finite numbers are passed to the serializer as they are
+    Keep,
+    /// Non-finite numbers are serialized as null
+    Null,
+    /// Non-finite numbers are serialized as strings, e.g. `"NaN"`, `"inf"`, or `"-inf"`
+    String,
+    /// An error is produced when a non-finite number is found
+    Error,
+}
+
+/// How tuples are serialized
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TupleMode {
+    /// Tuples are serialized as arrays
+    Array,
+    /// An error is produced when a tuple is found
+    Error,
+}
+
+/// How ranges are serialized
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RangeMode {
+    /// Ranges are serialized as strings, e.g. `"1..10"`
+    String,
+    /// Ranges are serialized as maps, with `start`, `end`, and `inclusive` entries
+    ///
+    /// Stepped ranges also have a `step` entry.
+    Map,
+    /// An error is produced when a range is found
+    Error,
+}
+
+/// Options that control how special values are serialized
+///
+/// Values that don't have a representation in the output format (e.g. functions and objects)
+/// always produce an error. Errors include the path of the value that caused the error.
+#[derive(Clone, Debug)]
+pub struct SerializeOptions {
+    /// How null values are serialized
+    pub nulls: NullMode,
+    /// How NaN and infinite numbers are serialized
+    pub nan: NonFiniteMode,
+    /// How tuples are serialized
+    pub tuples: TupleMode,
+    /// How ranges are serialized
+    pub ranges: RangeMode,
+    /// When true, the output is made stable so that it can be hashed or compared
+    ///
+    /// Map entries are sorted by key, and negative zero is serialized as zero.
+    pub canonical: bool,
+}
+
+impl Default for SerializeOptions {
+    fn default() -> Self {
+        Self {
+            nulls: NullMode::Keep,
+            nan: NonFiniteMode::Error,
+            tuples: TupleMode::Array,
+            ranges: RangeMode::Error,
+            canonical: false,
+        }
+    }
+}
+
+impl SerializeOptions {
+    /// Updates the options with the entries in a Koto map
+    ///
+    /// e.g. `{nulls: 'skip', nan: 'string'}`
+    pub fn update_from_map(&mut self, options: &KMap) -> Result<()> {
+        for (key, value) in options.data().iter() {
+            let name = match key.value() {
+                KValue::Str(name) => name.as_str(),
+                unexpected => return type_error("a String as option name", unexpected),
+            };
+            let value = match value {
+                KValue::Str(value) => value.as_str(),
+                unexpected => return type_error(&format!("a String for '{name}'"), unexpected),
+            };
+
+            match name {
+                "nulls" => {
+                    self.nulls = match value {
+                        "keep" => NullMode::Keep,
+                        "skip" => NullMode::Skip,
+                        "error" => NullMode::Error,
+                        _ => {
+                            return unexpected_option_value(name, value, "'keep', 'skip', 'error'")
+                        }
+                    }
+                }
+                "nan" => {
+                    self.nan = match value {
+                        "keep" => NonFiniteMode::Keep,
+                        "null" => NonFiniteMode::Null,
+                        "string" => NonFiniteMode::String,
+                        "error" => NonFiniteMode::Error,
+                        _ => {
+                            return unexpected_option_value(
+                                name,
+                                value,
+                                "'keep', 'null', 'string', 'error'",
+                            )
+                        }
+                    }
+                }
+                "tuples" => {
+                    self.tuples = match value {
+                        "array" => TupleMode::Array,
+                        "error" => TupleMode::Error,
+                        _ => return unexpected_option_value(name, value, "'array', 'error'"),
+                    }
+                }
+                "ranges" => {
+                    self.ranges = match value {
+                        "string" => RangeMode::String,
+                        "map" => RangeMode::Map,
+                        "error" => RangeMode::Error,
+                        _ => {
+                            return unexpected_option_value(name, value, "'string', 'map', 'error'")
+                        }
+                    }
+                }
+                _ => return runtime_error!("unknown option '{name}'"),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Wraps a value for serialization with the options
+    pub fn serializable<'a>(&'a self, value: &'a KValue) -> SerializableValueWithOptions<'a, 'a> {
+        SerializableValueWithOptions {
+            value,
+            options: self,
+            path: &ValuePath::Root,
+        }
+    }
+}
+
+fn unexpected_option_value(name: &str, value: &str, expected: &str) -> Result<()> {
+    runtime_error!("unexpected value '{value}' for '{name}' (expected one of {expected})")
+}
+
+/// A value that's serialized with [SerializeOptions], see [SerializeOptions::serializable]
+pub struct SerializableValueWithOptions<'a, 'p> {
+    value: &'a KValue,
+    options: &'a SerializeOptions,
+    path: &'p ValuePath<'p>,
+}
+
+impl SerializableValueWithOptions<'_, '_> {
+    fn error<E: Error>(&self, message: &str, option: Option<&str>) -> E {
+        let mut result = message.to_string();
+        if !matches!(self.path, ValuePath::Root) {
+            result.push_str(&format!(" at '{}'", self.path));
+        }
+        if let Some(option) = option {
+            result.push_str(&format!(" (see the '{option}' option)"));
+        }
+        E::custom(result)
+    }
+}
+
+impl Serialize for SerializableValueWithOptions<'_, '_> {
+    fn serialize<S>(&self, s: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let options = self.options;
+
+        match self.value {
+            KValue::Null => match options.nulls {
+                NullMode::Error => Err(self.error("unsupported null value", Some("nulls"))),
+                // Skipped nulls are removed by their containers, a null at the top level is kept
+                NullMode::Keep | NullMode::Skip => s.serialize_unit(),
+            },
+            KValue::Number(n) if n.is_f64() && !f64::from(n).is_finite() => match options.nan {
+                NonFiniteMode::Keep => s.serialize_f64(f64::from(n)),
+                NonFiniteMode::Null => s.serialize_unit(),
+                NonFiniteMode::String => {
+                    let n = f64::from(n);
+                    let string = if n.is_nan() {
+                        "NaN"
+                    } else if n.is_sign_positive() {
+                        "inf"
+                    } else {
+                        "-inf"
+                    };
+                    s.serialize_str(string)
+                }
+                NonFiniteMode::Error => {
+                    Err(self.error(&format!("unsupported number '{n}'"), Some("nan")))
+                }
+            },
+            KValue::Number(n) if options.canonical && n.is_f64() && f64::from(n) == 0.0 => {
+                s.serialize_f64(0.0)
+            }
+            KValue::Bool(_) | KValue::Number(_) | KValue::Str(_) => {
+                SerializableValue(self.value).serialize(s)
+            }
+            KValue::List(l) => self.serialize_sequence(s, &l.data()),
+            KValue::Tuple(t) => match options.tuples {
+                TupleMode::Array => self.serialize_sequence(s, t),
+                TupleMode::Error => Err(self.error("unsupported tuple", Some("tuples"))),
+            },
+            KValue::Map(m) => {
+                let data = m.data();
+                let skip_nulls = options.nulls == NullMode::Skip;
+                let mut entries: Vec<_> = data
+                    .iter()
+                    .filter(|(_, value)| !(skip_nulls && matches!(value, KValue::Null)))
+                    .map(|(key, value)| (key.to_string(), value))
+                    .collect();
+                if options.canonical {
+                    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+                }
+                let mut map = s.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries.iter() {
+                    let path = ValuePath::Key(self.path, key);
+                    map.serialize_entry(key, &self.nested(value, &path))?;
+                }
+                map.end()
+            }
+            KValue::Range(r) => match options.ranges {
+                RangeMode::String => s.serialize_str(&r.to_string()),
+                RangeMode::Map => {
+                    let mut map = s.serialize_map(None)?;
+                    if let Some(start) = r.start() {
+                        map.serialize_entry("start", &start)?;
+                    }
+                    if let Some((end, inclusive)) = r.end() {
+                        map.serialize_entry("end", &end)?;
+                        map.serialize_entry("inclusive", &inclusive)?;
+                    }
+                    if let Some(step) = r.step() {
+                        map.serialize_entry("step", &step)?;
+                    }
+                    map.end()
+                }
+                RangeMode::Error => Err(self.error("unsupported range", Some("ranges"))),
+            },
+            unexpected => Err(self.error(
+                &format!(
+                    "unsupported value of type '{}'",
+                    unexpected.type_as_string()
+                ),
+                None,
+            )),
+        }
+    }
+}
+
+impl<'a> SerializableValueWithOptions<'a, '_> {
+    fn nested<'p>(
+        &self,
+        value: &'a KValue,
+        path: &'p ValuePath<'p>,
+    ) -> SerializableValueWithOptions<'a, 'p> {
+        SerializableValueWithOptions {
+            value,
+            options: self.options,
+            path,
+        }
+    }
+
+    fn serialize_sequence<S>(&self, s: S, values: &[KValue]) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let skip_nulls = self.options.nulls == NullMode::Skip;
+        let mut seq = s.serialize_seq(if skip_nulls { None } else { Some(values.len()) })?;
+        for (index, value) in values.iter().enumerate() {
+            if skip_nulls && matches!(value, KValue::Null) {
+                continue;
+            }
+            let path = ValuePath::Index(self.path, index);
+            seq.serialize_element(&self.nested(value, &path))?;
+        }
+        seq.end()
+    }
+}
+
+// The location of a value that's being serialized, used in error messages
+enum ValuePath<'a> {
+    Root,
+    Key(&'a ValuePath<'a>, &'a str),
+    Index(&'a ValuePath<'a>, usize),
+}
+
+impl fmt::Display for ValuePath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Root => Ok(()),
+            Self::Key(Self::Root, key) => write!(f, "{key}"),
+            Self::Key(parent, key) => write!(f, "{parent}.{key}"),
+            Self::Index(parent, index) => write!(f, "{parent}[{index}]"),
+        }
+    }
+}
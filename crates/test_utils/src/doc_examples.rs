@@ -99,6 +99,8 @@ Actual:
 /// The following additional code block tags can be used to control how an example is tested.
 /// - `skip_check`: the example will be compiled and run, but the output won't be checked.
 /// - `skip_run`: the example will be compiled but not run.
+/// - `skip_compile`: the example won't be compiled, e.g. when it depends on files that aren't
+///   available to the test runner.
 pub fn run_koto_examples_in_markdown(markdown: &str, prelude_entries: ValueMap) -> Result<()> {
     use pulldown_cmark::{CodeBlockKind, Event::*, Parser, Tag::*};
 
@@ -111,6 +113,7 @@ pub fn run_koto_examples_in_markdown(markdown: &str, prelude_entries: ValueMap)
     let mut expected_output = String::with_capacity(128);
     let mut skip_check = false;
     let mut skip_run = false;
+    let mut skip_compile = false;
     let mut headings: Vec<String> = Vec::with_capacity(4);
     let mut current_level = None;
 
@@ -140,8 +143,12 @@ pub fn run_koto_examples_in_markdown(markdown: &str, prelude_entries: ValueMap)
                     let modifier = lang_info.next();
                     skip_check = matches!(modifier, Some("skip_check"));
                     skip_run = matches!(modifier, Some("skip_run"));
+                    skip_compile = matches!(modifier, Some("skip_compile"));
                 }
             }
+            End(CodeBlock(_)) if in_koto_code && skip_compile => {
+                in_koto_code = false;
+            }
             End(CodeBlock(_)) if in_koto_code => {
                 in_koto_code = false;
 
@@ -0,0 +1,425 @@
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use koto_runtime::{derive::*, prelude::*, Ptr, Result};
+use std::{
+    fs,
+    io::{self, Read, Write},
+    path::{Component, Path, PathBuf},
+};
+
+/// The archive formats supported by the `archive` module
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// A zip archive, with entries compressed using deflate
+    Zip,
+    /// A tar archive
+    Tar,
+    /// A tar archive compressed with gzip
+    TarGz,
+}
+
+impl ArchiveFormat {
+    /// Returns the tar format that matches the path's extension, e.g. `.tar.gz` or `.tgz`
+    pub fn tar_from_path(path: &Path) -> Self {
+        let path = path.to_string_lossy();
+        if path.ends_with(".gz") || path.ends_with(".tgz") {
+            Self::TarGz
+        } else {
+            Self::Tar
+        }
+    }
+}
+
+/// An entry in an archive
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    /// The entry's path in the archive
+    pub name: String,
+    /// The uncompressed size of the entry in bytes
+    pub size: u64,
+    /// True if the entry is a directory
+    pub is_dir: bool,
+}
+
+/// An archive that was opened with `archive.zip_read` or `archive.tar_read`
+///
+/// The archive's file is opened each time it's accessed, so no file handles are kept open.
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "Archive")]
+pub struct Archive(Ptr<(PathBuf, ArchiveFormat)>);
+
+#[koto_impl(runtime = koto_runtime)]
+impl Archive {
+    /// Opens the archive at the given path, checking that it can be read
+    pub fn open(path: PathBuf, format: ArchiveFormat) -> Result<Self> {
+        let result = Self((path, format).into());
+        result.read_entries()?;
+        Ok(result)
+    }
+
+    /// Returns the path of the archive's file
+    pub fn file_path(&self) -> &Path {
+        &self.0 .0
+    }
+
+    /// Returns the archive's format
+    pub fn format(&self) -> ArchiveFormat {
+        self.0 .1
+    }
+
+    /// Returns the entries contained in the archive
+    pub fn read_entries(&self) -> Result<Vec<Entry>> {
+        let mut result = Vec::new();
+
+        match self.format() {
+            ArchiveFormat::Zip => {
+                let mut archive = self.open_zip()?;
+                for i in 0..archive.len() {
+                    let file = archive.by_index_raw(i).map_err(zip_error)?;
+                    result.push(Entry {
+                        name: file.name().trim_end_matches('/').to_string(),
+                        size: file.size(),
+                        is_dir: file.is_dir(),
+                    });
+                }
+            }
+            ArchiveFormat::Tar | ArchiveFormat::TarGz => {
+                self.for_each_tar_entry(|entry, _| {
+                    result.push(entry);
+                    Ok(false)
+                })?;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the contents of the file entry with the given name
+    pub fn read_entry(&self, name: &str) -> Result<Vec<u8>> {
+        let name = name.trim_end_matches('/');
+        let mut result = None;
+
+        match self.format() {
+            ArchiveFormat::Zip => {
+                let mut archive = self.open_zip()?;
+                match archive.by_name(name) {
+                    Ok(mut file) if !file.is_dir() => {
+                        let mut bytes = Vec::with_capacity(file.size() as usize);
+                        file.read_to_end(&mut bytes).map_err(io_error)?;
+                        result = Some(bytes);
+                    }
+                    Ok(_) | Err(zip::result::ZipError::FileNotFound) => {}
+                    Err(error) => return Err(zip_error(error)),
+                };
+            }
+            ArchiveFormat::Tar | ArchiveFormat::TarGz => {
+                self.for_each_tar_entry(|entry, reader| {
+                    if entry.is_dir || entry.name != name {
+                        return Ok(false);
+                    }
+                    let mut bytes = Vec::with_capacity(entry.size as usize);
+                    reader.read_to_end(&mut bytes).map_err(io_error)?;
+                    result = Some(bytes);
+                    Ok(true)
+                })?;
+            }
+        }
+
+        match result {
+            Some(bytes) => Ok(bytes),
+            None => runtime_error!("'{name}' wasn't found in the archive"),
+        }
+    }
+
+    /// Extracts the archive's entries into the destination directory, returning the entry names
+    ///
+    /// Entries with names that would be extracted outside of the destination (e.g. names that
+    /// contain `..`, or absolute paths) cause an error to be returned before anything is written.
+    pub fn extract_to(&self, destination: &Path) -> Result<Vec<String>> {
+        let entries = self.read_entries()?;
+        for entry in entries.iter() {
+            if safe_entry_path(&entry.name).is_none() {
+                return runtime_error!("Unsafe path in archive: '{}'", entry.name);
+            }
+        }
+
+        let mut extracted = Vec::with_capacity(entries.len());
+        let mut write_entry = |entry: Entry, reader: &mut dyn Read| -> Result<()> {
+            // The entry names have already been checked
+            let target = destination.join(safe_entry_path(&entry.name).unwrap());
+            if entry.is_dir {
+                fs::create_dir_all(&target).map_err(io_error)?;
+            } else {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent).map_err(io_error)?;
+                }
+                let mut file = fs::File::create(&target).map_err(io_error)?;
+                io::copy(reader, &mut file).map_err(io_error)?;
+            }
+            extracted.push(entry.name);
+            Ok(())
+        };
+
+        match self.format() {
+            ArchiveFormat::Zip => {
+                let mut archive = self.open_zip()?;
+                for i in 0..archive.len() {
+                    let mut file = archive.by_index(i).map_err(zip_error)?;
+                    let entry = Entry {
+                        name: file.name().trim_end_matches('/').to_string(),
+                        size: file.size(),
+                        is_dir: file.is_dir(),
+                    };
+                    write_entry(entry, &mut file)?;
+                }
+            }
+            ArchiveFormat::Tar | ArchiveFormat::TarGz => {
+                self.for_each_tar_entry(|entry, reader| {
+                    write_entry(entry, reader)?;
+                    Ok(false)
+                })?;
+            }
+        }
+
+        Ok(extracted)
+    }
+
+    fn open_zip(&self) -> Result<zip::ZipArchive<fs::File>> {
+        zip::ZipArchive::new(open_file(self.file_path())?).map_err(zip_error)
+    }
+
+    // Calls the function with each of the archive's file and directory entries,
+    // stopping early if the function returns true
+    //
+    // Other kinds of entries, like symbolic links, are skipped.
+    fn for_each_tar_entry(
+        &self,
+        mut f: impl FnMut(Entry, &mut dyn Read) -> Result<bool>,
+    ) -> Result<()> {
+        let file = open_file(self.file_path())?;
+        let reader: Box<dyn Read> = match self.format() {
+            ArchiveFormat::TarGz => Box::new(GzDecoder::new(file)),
+            _ => Box::new(file),
+        };
+
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive.entries().map_err(io_error)? {
+            let mut entry = entry.map_err(io_error)?;
+            let entry_type = entry.header().entry_type();
+            if !(entry_type.is_file() || entry_type.is_dir()) {
+                continue;
+            }
+            let info = Entry {
+                name: entry
+                    .path()
+                    .map_err(io_error)?
+                    .to_string_lossy()
+                    .trim_end_matches('/')
+                    .to_string(),
+                size: entry.size(),
+                is_dir: entry_type.is_dir(),
+            };
+            if f(info, &mut entry)? {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[koto_method]
+    fn entries(&self) -> Result<KValue> {
+        let entries = self
+            .read_entries()?
+            .into_iter()
+            .map(|entry| {
+                let result = KMap::with_capacity(3);
+                result.insert("name", entry.name);
+                result.insert("size", entry.size as i64);
+                result.insert("is_dir", entry.is_dir);
+                result.into()
+            })
+            .collect();
+        Ok(KList::with_data(entries).into())
+    }
+
+    #[koto_method]
+    fn extract(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Str(destination)] => {
+                let extracted = self
+                    .extract_to(Path::new(destination.as_str()))?
+                    .into_iter()
+                    .map(KValue::from)
+                    .collect();
+                Ok(KList::with_data(extracted).into())
+            }
+            unexpected => type_error_with_slice("a destination path String", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn path(&self) -> Result<KValue> {
+        Ok(self.file_path().to_string_lossy().to_string().into())
+    }
+
+    #[koto_method]
+    fn read(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Str(name)] => match String::from_utf8(self.read_entry(name)?) {
+                Ok(contents) => Ok(contents.into()),
+                Err(_) => runtime_error!("'{name}' doesn't contain valid UTF-8 data"),
+            },
+            unexpected => type_error_with_slice("an entry name String", unexpected),
+        }
+    }
+}
+
+impl KotoObject for Archive {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!(
+            "{}({})",
+            Self::type_static(),
+            self.file_path().to_string_lossy()
+        ));
+        Ok(())
+    }
+}
+
+impl From<Archive> for KValue {
+    fn from(archive: Archive) -> Self {
+        KObject::from(archive).into()
+    }
+}
+
+/// Creates an archive containing the provided sources
+///
+/// Each source is a pair of a name for the entry in the archive, and the path of the file or
+/// directory that should be added. Directories are added recursively.
+pub fn create(path: &Path, format: ArchiveFormat, sources: &[(String, PathBuf)]) -> Result<()> {
+    let mut files = Vec::new();
+    for (name, source) in sources {
+        let Some(name) = safe_entry_path(name) else {
+            return runtime_error!("Unsafe archive entry name: '{name}'");
+        };
+        collect_files(&name, source, &mut files)?;
+    }
+
+    let file = fs::File::create(path).map_err(io_error)?;
+
+    match format {
+        ArchiveFormat::Zip => {
+            let mut writer = zip::ZipWriter::new(file);
+            let options = zip::write::FileOptions::default()
+                .compression_method(zip::CompressionMethod::Deflated);
+            for (name, source) in files {
+                let name = entry_name(&name);
+                match source {
+                    Some(source) => {
+                        writer.start_file(name, options).map_err(zip_error)?;
+                        let mut source = open_file(&source)?;
+                        io::copy(&mut source, &mut writer).map_err(io_error)?;
+                    }
+                    None => writer.add_directory(name, options).map_err(zip_error)?,
+                }
+            }
+            writer.finish().map_err(zip_error)?;
+        }
+        ArchiveFormat::Tar => {
+            write_tar(file, files)?.flush().map_err(io_error)?;
+        }
+        ArchiveFormat::TarGz => {
+            let encoder = write_tar(GzEncoder::new(file, Compression::default()), files)?;
+            encoder.finish().map_err(io_error)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_tar<W: Write>(writer: W, files: Vec<(PathBuf, Option<PathBuf>)>) -> Result<W> {
+    let mut builder = tar::Builder::new(writer);
+    for (name, source) in files {
+        let name = entry_name(&name);
+        match source {
+            Some(source) => builder
+                .append_path_with_name(&source, &name)
+                .map_err(|error| io_error_with_path(error, &source))?,
+            None => {
+                let mut header = tar::Header::new_gnu();
+                header.set_entry_type(tar::EntryType::Directory);
+                header.set_mode(0o755);
+                header.set_size(0);
+                builder
+                    .append_data(&mut header, &name, io::empty())
+                    .map_err(io_error)?;
+            }
+        }
+    }
+    builder.into_inner().map_err(io_error)
+}
+
+// Collects the files that should be added to an archive,
+// with directories included as entries without a source file
+fn collect_files(
+    name: &Path,
+    source: &Path,
+    files: &mut Vec<(PathBuf, Option<PathBuf>)>,
+) -> Result<()> {
+    let metadata = fs::metadata(source).map_err(|error| io_error_with_path(error, source))?;
+    if metadata.is_dir() {
+        files.push((name.to_path_buf(), None));
+        let mut children = fs::read_dir(source)
+            .and_then(|entries| entries.map(|entry| entry.map(|e| e.path())).collect())
+            .map_err(|error| io_error_with_path(error, source))
+            .map(|children: Vec<PathBuf>| children)?;
+        // Sorting the directory's contents keeps the archive's layout consistent
+        children.sort();
+        for child in children {
+            if let Some(child_name) = child.file_name() {
+                collect_files(&name.join(child_name), &child, files)?;
+            }
+        }
+    } else {
+        files.push((name.to_path_buf(), Some(source.to_path_buf())));
+    }
+    Ok(())
+}
+
+// Converts a relative path into an archive entry name, using `/` as the separator
+fn entry_name(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Returns the name as a relative path, or `None` if the path would escape the archive's root
+///
+/// Names containing `..`, or that are absolute paths, are considered to be unsafe.
+pub fn safe_entry_path(name: &str) -> Option<PathBuf> {
+    let mut result = PathBuf::new();
+    for component in Path::new(name).components() {
+        match component {
+            Component::Normal(part) => result.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!result.as_os_str().is_empty()).then_some(result)
+}
+
+fn open_file(path: &Path) -> Result<fs::File> {
+    fs::File::open(path).map_err(|error| io_error_with_path(error, path))
+}
+
+fn io_error(error: io::Error) -> koto_runtime::Error {
+    error.to_string().into()
+}
+
+fn io_error_with_path(error: io::Error, path: &Path) -> koto_runtime::Error {
+    format!("{} ('{}')", error, path.to_string_lossy()).into()
+}
+
+fn zip_error(error: zip::result::ZipError) -> koto_runtime::Error {
+    error.to_string().into()
+}
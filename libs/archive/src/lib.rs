@@ -0,0 +1,68 @@
+//! A Koto language module for reading and writing zip and tar archives
+//!
+//! Archives are created from a map of entry names to files or directories on disk, and can be read
+//! back as `Archive` objects that list, read, and extract their entries. Extraction refuses entries
+//! that would be written outside of the destination directory.
+
+mod archive;
+
+pub use archive::{create, safe_entry_path, Archive, ArchiveFormat, Entry};
+
+use koto_runtime::{prelude::*, Result};
+use std::path::{Path, PathBuf};
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("archive");
+
+    result.add_fn("tar_create", |ctx| match ctx.args() {
+        [KValue::Str(path), KValue::Map(entries)] => {
+            let path = Path::new(path.as_str());
+            create(path, ArchiveFormat::tar_from_path(path), &sources(entries)?)
+                .map(|_| KValue::Null)
+        }
+        unexpected => type_error_with_slice("a path String and a Map of entries", unexpected),
+    });
+
+    result.add_fn("tar_read", |ctx| match ctx.args() {
+        [KValue::Str(path)] => {
+            let path = PathBuf::from(path.as_str());
+            let format = ArchiveFormat::tar_from_path(&path);
+            Archive::open(path, format).map(KValue::from)
+        }
+        unexpected => type_error_with_slice("a path String", unexpected),
+    });
+
+    result.add_fn("zip_create", |ctx| match ctx.args() {
+        [KValue::Str(path), KValue::Map(entries)] => create(
+            Path::new(path.as_str()),
+            ArchiveFormat::Zip,
+            &sources(entries)?,
+        )
+        .map(|_| KValue::Null),
+        unexpected => type_error_with_slice("a path String and a Map of entries", unexpected),
+    });
+
+    result.add_fn("zip_read", |ctx| match ctx.args() {
+        [KValue::Str(path)] => {
+            Archive::open(PathBuf::from(path.as_str()), ArchiveFormat::Zip).map(KValue::from)
+        }
+        unexpected => type_error_with_slice("a path String", unexpected),
+    });
+
+    result
+}
+
+// Converts a map of entry names to source paths into a list of sources for `create`
+fn sources(entries: &KMap) -> Result<Vec<(String, PathBuf)>> {
+    entries
+        .data()
+        .iter()
+        .map(|(name, source)| match (name.value(), source) {
+            (KValue::Str(name), KValue::Str(source)) => {
+                Ok((name.to_string(), PathBuf::from(source.as_str())))
+            }
+            (KValue::Str(_), unexpected) => type_error("a source path String", unexpected),
+            (unexpected, _) => type_error("an entry name String", unexpected),
+        })
+        .collect()
+}
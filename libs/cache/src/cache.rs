@@ -0,0 +1,390 @@
+use indexmap::IndexMap;
+use koto_json::json_value_to_koto_value;
+use koto_runtime::{derive::*, prelude::*, PtrMut, Result};
+use koto_serialize::SerializableValue;
+use serde_json::{json, Value as JsonValue};
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// A key/value cache with optional size and time limits
+///
+/// When the cache is full, the least recently used entry is evicted to make room for new entries.
+/// Entries older than the cache's TTL (time to live) are treated as missing.
+#[derive(Clone, KotoType)]
+#[koto(type_name = "Cache")]
+pub struct Cache(PtrMut<CacheState>);
+
+#[derive(Clone, Default)]
+struct CacheState {
+    // Entries are kept in order of use, with the least recently used entry first
+    entries: IndexMap<ValueKey, Entry>,
+    max_size: Option<usize>,
+    ttl: Option<Duration>,
+    path: Option<PathBuf>,
+    hits: usize,
+    misses: usize,
+    evictions: usize,
+}
+
+#[derive(Clone)]
+struct Entry {
+    value: KValue,
+    expires: Option<SystemTime>,
+}
+
+impl Entry {
+    fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+}
+
+/// The settings used when making a [Cache]
+#[derive(Clone, Default)]
+pub struct CacheSettings {
+    /// The maximum number of entries, or `None` if the cache is unbounded
+    pub max_size: Option<usize>,
+    /// The time to live for entries, or `None` if entries don't expire
+    pub ttl: Option<Duration>,
+    /// The path of the file used to persist the cache's entries
+    pub path: Option<PathBuf>,
+}
+
+impl CacheSettings {
+    /// Makes settings from a Koto options map
+    pub fn from_map(options: &KMap) -> Result<Self> {
+        let max_size = match options.get("max_size") {
+            Some(KValue::Number(n)) if n >= 1 => Some(usize::from(n)),
+            Some(KValue::Null) | None => None,
+            Some(unexpected) => return type_error("a positive Number as max_size", &unexpected),
+        };
+        let ttl = match options.get("ttl") {
+            Some(KValue::Number(n)) if f64::from(n) > 0.0 && f64::from(n).is_finite() => {
+                Some(Duration::from_secs_f64(n.into()))
+            }
+            Some(KValue::Null) | None => None,
+            Some(unexpected) => return type_error("a positive Number as ttl", &unexpected),
+        };
+        let path = match options.get("path") {
+            Some(KValue::Str(path)) => Some(PathBuf::from(path.as_str())),
+            Some(KValue::Null) | None => None,
+            Some(unexpected) => return type_error("a String as path", &unexpected),
+        };
+
+        Ok(Self {
+            max_size,
+            ttl,
+            path,
+        })
+    }
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Cache {
+    /// Makes a new cache
+    ///
+    /// If a path is provided in the settings and the file exists,
+    /// then the cache is loaded with the file's unexpired entries.
+    pub fn new(settings: CacheSettings) -> Result<Self> {
+        let result = Self(PtrMut::from(CacheState {
+            max_size: settings.max_size,
+            ttl: settings.ttl,
+            path: settings.path.clone(),
+            ..Default::default()
+        }));
+
+        if let Some(path) = settings.path {
+            if path.exists() {
+                result.load_file(&path)?;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the cached value for the key, updating the hit and miss statistics
+    pub fn get_value(&self, key: &ValueKey) -> Option<KValue> {
+        let mut state = self.0.borrow_mut();
+        state.remove_expired(key);
+        match state.entries.get_index_of(key) {
+            Some(index) => {
+                state.hits += 1;
+                let last = state.entries.len() - 1;
+                state.entries.move_index(index, last);
+                Some(state.entries[last].value.clone())
+            }
+            None => {
+                state.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Inserts a value into the cache, evicting the least recently used entry if necessary
+    pub fn put_value(&self, key: ValueKey, value: KValue) {
+        let mut state = self.0.borrow_mut();
+        let expires = state.ttl.map(|ttl| SystemTime::now() + ttl);
+        state.entries.shift_remove(&key);
+        state.entries.insert(key, Entry { value, expires });
+        state.evict();
+    }
+
+    /// Returns the number of unexpired entries in the cache
+    pub fn len(&self) -> usize {
+        let mut state = self.0.borrow_mut();
+        state.remove_all_expired();
+        state.entries.len()
+    }
+
+    /// Returns true if the cache has no unexpired entries
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes the cache's unexpired entries to a file as JSON
+    pub fn save_file(&self, path: &Path) -> Result<()> {
+        let mut state = self.0.borrow_mut();
+        state.remove_all_expired();
+
+        let mut entries = Vec::with_capacity(state.entries.len());
+        for (key, entry) in state.entries.iter() {
+            let expires = match entry.expires {
+                Some(expires) => json!(expires
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs_f64()),
+                None => JsonValue::Null,
+            };
+            let key = match serde_json::to_value(SerializableValue(key.value())) {
+                Ok(key) => key,
+                Err(e) => return runtime_error!("failed to save cache key '{key}': {e}"),
+            };
+            let value = match serde_json::to_value(SerializableValue(&entry.value)) {
+                Ok(value) => value,
+                Err(e) => return runtime_error!("failed to save cache value for '{key}': {e}"),
+            };
+            entries.push(json!({"key": key, "value": value, "expires": expires}));
+        }
+
+        let contents = json!({ "entries": entries }).to_string();
+        match std::fs::write(path, contents) {
+            Ok(()) => Ok(()),
+            Err(e) => runtime_error!("failed to write '{}': {e}", path.display()),
+        }
+    }
+
+    fn load_file(&self, path: &Path) -> Result<()> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => return runtime_error!("failed to read '{}': {e}", path.display()),
+        };
+        if contents.trim().is_empty() {
+            return Ok(());
+        }
+        let json: JsonValue = match serde_json::from_str(&contents) {
+            Ok(json) => json,
+            Err(e) => return runtime_error!("failed to parse '{}': {e}", path.display()),
+        };
+        let Some(entries) = json.get("entries").and_then(JsonValue::as_array) else {
+            return runtime_error!("missing 'entries' in '{}'", path.display());
+        };
+
+        let now = SystemTime::now();
+        let mut state = self.0.borrow_mut();
+        for entry in entries {
+            let (Some(key), Some(value)) = (entry.get("key"), entry.get("value")) else {
+                return runtime_error!("invalid cache entry in '{}'", path.display());
+            };
+            let expires = entry
+                .get("expires")
+                .and_then(JsonValue::as_f64)
+                .map(|seconds| UNIX_EPOCH + Duration::from_secs_f64(seconds));
+            let entry = Entry {
+                value: json_value_to_koto_value(value)?,
+                expires,
+            };
+            if !entry.is_expired(now) {
+                let key = ValueKey::try_from(json_value_to_koto_value(key)?)?;
+                state.entries.insert(key, entry);
+            }
+        }
+        state.evict();
+
+        Ok(())
+    }
+
+    #[koto_method]
+    fn clear(ctx: MethodContext<Self>) -> Result<KValue> {
+        ctx.instance()?.0.borrow_mut().entries.clear();
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn contains(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [key] => {
+                let key = ValueKey::try_from(key.clone())?;
+                let mut state = self.0.borrow_mut();
+                state.remove_expired(&key);
+                Ok(state.entries.contains_key(&key).into())
+            }
+            unexpected => type_error_with_slice("a key", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn get(&self, args: &[KValue]) -> Result<KValue> {
+        let (key, default) = match args {
+            [key] => (key, &KValue::Null),
+            [key, default] => (key, default),
+            unexpected => return type_error_with_slice("a key, with optional default", unexpected),
+        };
+        let key = ValueKey::try_from(key.clone())?;
+        Ok(self.get_value(&key).unwrap_or_else(|| default.clone()))
+    }
+
+    #[koto_method]
+    fn get_or_compute(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (key, f) = match ctx.args {
+            [key, f] if f.is_callable() => (key, f),
+            unexpected => return type_error_with_slice("a key and a Function", unexpected),
+        };
+        let value_key = ValueKey::try_from(key.clone())?;
+
+        // The instance is cloned so that it isn't borrowed while the function is running
+        let this = ctx.instance()?.clone();
+        if let Some(value) = this.get_value(&value_key) {
+            return Ok(value);
+        }
+
+        let value = ctx
+            .vm
+            .spawn_shared_vm()
+            .call_function(f.clone(), key.clone())?;
+        this.put_value(value_key, value.clone());
+        Ok(value)
+    }
+
+    #[koto_method]
+    fn keys(&self) -> KValue {
+        let mut state = self.0.borrow_mut();
+        state.remove_all_expired();
+        let keys: Vec<KValue> = state
+            .entries
+            .keys()
+            .map(|key| key.value().clone())
+            .collect();
+        KList::with_data(keys.into()).into()
+    }
+
+    #[koto_method]
+    fn put(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [key, value] => {
+                let key = ValueKey::try_from(key.clone())?;
+                ctx.instance()?.put_value(key, value.clone());
+            }
+            unexpected => return type_error_with_slice("a key and a value", unexpected),
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn remove(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [key] => {
+                let key = ValueKey::try_from(key.clone())?;
+                let mut state = self.0.borrow_mut();
+                state.remove_expired(&key);
+                Ok(state
+                    .entries
+                    .shift_remove(&key)
+                    .map_or(KValue::Null, |entry| entry.value))
+            }
+            unexpected => type_error_with_slice("a key", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn save(&self, args: &[KValue]) -> Result<KValue> {
+        let path = match args {
+            [] => match self.0.borrow().path.clone() {
+                Some(path) => path,
+                None => return runtime_error!("the cache doesn't have a path, and none was given"),
+            },
+            [KValue::Str(path)] => PathBuf::from(path.as_str()),
+            unexpected => return type_error_with_slice("an optional path String", unexpected),
+        };
+        self.save_file(&path)?;
+        Ok(KValue::Null)
+    }
+
+    #[koto_method]
+    fn stats(&self) -> KValue {
+        let size = self.len();
+        let state = self.0.borrow();
+        let result = KMap::with_capacity(4);
+        result.insert("hits", state.hits);
+        result.insert("misses", state.misses);
+        result.insert("evictions", state.evictions);
+        result.insert("size", size);
+        result.into()
+    }
+}
+
+impl CacheState {
+    fn remove_expired(&mut self, key: &ValueKey) {
+        let now = SystemTime::now();
+        if self
+            .entries
+            .get(key)
+            .is_some_and(|entry| entry.is_expired(now))
+        {
+            self.entries.shift_remove(key);
+        }
+    }
+
+    fn remove_all_expired(&mut self) {
+        let now = SystemTime::now();
+        self.entries.retain(|_, entry| !entry.is_expired(now));
+    }
+
+    // Removes the least recently used entries until the cache is within its size limit
+    fn evict(&mut self) {
+        let Some(max_size) = self.max_size else {
+            return;
+        };
+        if self.entries.len() > max_size {
+            self.remove_all_expired();
+        }
+        while self.entries.len() > max_size {
+            self.entries.shift_remove_index(0);
+            self.evictions += 1;
+        }
+    }
+}
+
+impl KotoCopy for Cache {
+    fn copy(&self) -> KObject {
+        // Copies get their own entries
+        Self(PtrMut::from(self.0.borrow().clone())).into()
+    }
+}
+
+impl KotoObject for Cache {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!("Cache({} entries)", self.len()));
+        Ok(())
+    }
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl From<Cache> for KValue {
+    fn from(cache: Cache) -> Self {
+        KObject::from(cache).into()
+    }
+}
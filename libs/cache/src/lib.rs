@@ -0,0 +1,19 @@
+//! A Koto language module for caching values
+
+mod cache;
+
+pub use cache::{Cache, CacheSettings};
+
+use koto_runtime::prelude::*;
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("cache");
+
+    result.add_fn("new", |ctx| match ctx.args() {
+        [] => Ok(Cache::new(CacheSettings::default())?.into()),
+        [KValue::Map(options)] => Ok(Cache::new(CacheSettings::from_map(options)?)?.into()),
+        unexpected => type_error_with_slice("an optional options Map", unexpected),
+    });
+
+    result
+}
@@ -0,0 +1,395 @@
+use crate::{
+    color_from_value,
+    command::{Color, Command},
+};
+use koto_runtime::{derive::*, prelude::*, PtrMut, Result};
+
+/// A canvas that records drawing commands into a display list
+///
+/// Canvases share their display list when cloned, so a host application can keep a clone of a
+/// canvas that's been made available to a script, and then consume the script's drawing commands
+/// with [Canvas::take_commands].
+#[derive(Clone, KotoType)]
+pub struct Canvas(PtrMut<CanvasState>);
+
+#[derive(Clone)]
+struct CanvasState {
+    width: f64,
+    height: f64,
+    commands: Vec<Command>,
+    style: Style,
+    // Styles that have been saved with `save`
+    saved_styles: Vec<Style>,
+}
+
+#[derive(Clone, Copy)]
+struct Style {
+    fill: Color,
+    stroke: Color,
+    line_width: f64,
+    font_size: f64,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            fill: Color::BLACK,
+            stroke: Color::BLACK,
+            line_width: 1.0,
+            font_size: 16.0,
+        }
+    }
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Canvas {
+    pub fn new(width: f64, height: f64) -> Self {
+        Self(PtrMut::from(CanvasState {
+            width,
+            height,
+            commands: Vec::new(),
+            style: Style::default(),
+            saved_styles: Vec::new(),
+        }))
+    }
+
+    /// The canvas's width and height
+    pub fn size(&self) -> (f64, f64) {
+        let state = self.0.borrow();
+        (state.width, state.height)
+    }
+
+    /// Returns a copy of the commands that have been recorded
+    pub fn recorded_commands(&self) -> Vec<Command> {
+        self.0.borrow().commands.clone()
+    }
+
+    /// Removes the recorded commands from the canvas and returns them
+    ///
+    /// This is typically called by the host once per frame, after the script has finished
+    /// drawing. Styles and saved states are reset so that each frame starts from a clean state.
+    pub fn take_commands(&self) -> Vec<Command> {
+        let mut state = self.0.borrow_mut();
+        state.style = Style::default();
+        state.saved_styles.clear();
+        std::mem::take(&mut state.commands)
+    }
+
+    fn push(&self, command: Command) {
+        self.0.borrow_mut().commands.push(command);
+    }
+
+    // Records a command built from numeric arguments, returning the canvas
+    fn record<const N: usize>(
+        ctx: &MethodContext<Self>,
+        expected: &str,
+        make_command: impl FnOnce([f64; N]) -> Command,
+    ) -> Result<KValue> {
+        let command = make_command(numbers(ctx.args, expected)?);
+        ctx.instance()?.push(command);
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn arc(ctx: MethodContext<Self>) -> Result<KValue> {
+        Self::record(
+            &ctx,
+            "a position, a radius, and start and end angles",
+            |[x, y, radius, start, end]| Command::Arc {
+                x,
+                y,
+                radius,
+                start,
+                end,
+            },
+        )
+    }
+
+    #[koto_method]
+    fn begin_path(ctx: MethodContext<Self>) -> Result<KValue> {
+        Self::record(&ctx, "no arguments", |[]| Command::BeginPath)
+    }
+
+    #[koto_method]
+    fn bezier_to(ctx: MethodContext<Self>) -> Result<KValue> {
+        Self::record(
+            &ctx,
+            "two control points and an end point",
+            |[c1x, c1y, c2x, c2y, x, y]| Command::BezierTo {
+                c1x,
+                c1y,
+                c2x,
+                c2y,
+                x,
+                y,
+            },
+        )
+    }
+
+    #[koto_method]
+    fn circle(ctx: MethodContext<Self>) -> Result<KValue> {
+        Self::record(&ctx, "a position and a radius", |[x, y, radius]| {
+            Command::Circle { x, y, radius }
+        })
+    }
+
+    #[koto_method]
+    fn clear(ctx: MethodContext<Self>) -> Result<KValue> {
+        let color = match ctx.args {
+            [] => Color::rgba(0, 0, 0, 0),
+            [color] => color_from_value(color)?,
+            unexpected => return type_error_with_slice("an optional color", unexpected),
+        };
+        ctx.instance()?.push(Command::Clear(color));
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn close_path(ctx: MethodContext<Self>) -> Result<KValue> {
+        Self::record(&ctx, "no arguments", |[]| Command::ClosePath)
+    }
+
+    #[koto_method]
+    fn commands(&self) -> KValue {
+        let commands = self
+            .0
+            .borrow()
+            .commands
+            .iter()
+            .map(|command| command.to_map().into())
+            .collect();
+        KList::with_data(commands).into()
+    }
+
+    #[koto_method]
+    fn fill(ctx: MethodContext<Self>) -> Result<KValue> {
+        let color = match ctx.args {
+            [] => None,
+            [color] => Some(color_from_value(color)?),
+            unexpected => return type_error_with_slice("an optional color", unexpected),
+        };
+        {
+            let this = ctx.instance()?;
+            let color = color.unwrap_or(this.0.borrow().style.fill);
+            this.push(Command::Fill { color });
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn fill_style(ctx: MethodContext<Self>) -> Result<KValue> {
+        let color = match ctx.args {
+            [color] => color_from_value(color)?,
+            unexpected => return type_error_with_slice("a color", unexpected),
+        };
+        ctx.instance()?.0.borrow_mut().style.fill = color;
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn font_size(ctx: MethodContext<Self>) -> Result<KValue> {
+        let [size] = numbers(ctx.args, "a font size")?;
+        ctx.instance()?.0.borrow_mut().style.font_size = size;
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn height(&self) -> KValue {
+        self.0.borrow().height.into()
+    }
+
+    #[koto_method]
+    fn line_to(ctx: MethodContext<Self>) -> Result<KValue> {
+        Self::record(&ctx, "a position", |[x, y]| Command::LineTo { x, y })
+    }
+
+    #[koto_method]
+    fn line_width(ctx: MethodContext<Self>) -> Result<KValue> {
+        let [width] = numbers(ctx.args, "a line width")?;
+        ctx.instance()?.0.borrow_mut().style.line_width = width;
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn move_to(ctx: MethodContext<Self>) -> Result<KValue> {
+        Self::record(&ctx, "a position", |[x, y]| Command::MoveTo { x, y })
+    }
+
+    #[koto_method]
+    fn quad_to(ctx: MethodContext<Self>) -> Result<KValue> {
+        Self::record(&ctx, "a control point and an end point", |[cx, cy, x, y]| {
+            Command::QuadTo { cx, cy, x, y }
+        })
+    }
+
+    #[koto_method]
+    fn rect(ctx: MethodContext<Self>) -> Result<KValue> {
+        Self::record(
+            &ctx,
+            "a position, followed by a width and height",
+            |[x, y, width, height]| Command::Rect {
+                x,
+                y,
+                width,
+                height,
+            },
+        )
+    }
+
+    #[koto_method]
+    fn reset(ctx: MethodContext<Self>) -> Result<KValue> {
+        ctx.instance()?.take_commands();
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn restore(ctx: MethodContext<Self>) -> Result<KValue> {
+        {
+            let this = ctx.instance()?;
+            let mut state = this.0.borrow_mut();
+            let Some(style) = state.saved_styles.pop() else {
+                return runtime_error!("restore was called without a matching call to save");
+            };
+            state.style = style;
+            state.commands.push(Command::Restore);
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn rotate(ctx: MethodContext<Self>) -> Result<KValue> {
+        Self::record(&ctx, "an angle in radians", |[angle]| Command::Rotate {
+            angle,
+        })
+    }
+
+    #[koto_method]
+    fn save(ctx: MethodContext<Self>) -> Result<KValue> {
+        {
+            let this = ctx.instance()?;
+            let mut state = this.0.borrow_mut();
+            let style = state.style;
+            state.saved_styles.push(style);
+            state.commands.push(Command::Save);
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn scale(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (x, y) = match ctx.args {
+            [KValue::Number(scale)] => (scale.into(), scale.into()),
+            [KValue::Number(x), KValue::Number(y)] => (x.into(), y.into()),
+            unexpected => {
+                return type_error_with_slice("a scale, or separate x and y scales", unexpected)
+            }
+        };
+        ctx.instance()?.push(Command::Scale { x, y });
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn stroke(ctx: MethodContext<Self>) -> Result<KValue> {
+        let color = match ctx.args {
+            [] => None,
+            [color] => Some(color_from_value(color)?),
+            unexpected => return type_error_with_slice("an optional color", unexpected),
+        };
+        {
+            let this = ctx.instance()?;
+            let style = this.0.borrow().style;
+            this.push(Command::Stroke {
+                color: color.unwrap_or(style.stroke),
+                width: style.line_width,
+            });
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn stroke_style(ctx: MethodContext<Self>) -> Result<KValue> {
+        let color = match ctx.args {
+            [color] => color_from_value(color)?,
+            unexpected => return type_error_with_slice("a color", unexpected),
+        };
+        ctx.instance()?.0.borrow_mut().style.stroke = color;
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn text(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (x, y, text) = match ctx.args {
+            [KValue::Number(x), KValue::Number(y), KValue::Str(text)] => {
+                (x.into(), y.into(), text.to_string())
+            }
+            unexpected => return type_error_with_slice("a position and a String", unexpected),
+        };
+        {
+            let this = ctx.instance()?;
+            let style = this.0.borrow().style;
+            this.push(Command::Text {
+                x,
+                y,
+                text,
+                size: style.font_size,
+                color: style.fill,
+            });
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn translate(ctx: MethodContext<Self>) -> Result<KValue> {
+        Self::record(&ctx, "x and y offsets", |[x, y]| Command::Translate {
+            x,
+            y,
+        })
+    }
+
+    #[koto_method]
+    fn width(&self) -> KValue {
+        self.0.borrow().width.into()
+    }
+}
+
+impl KotoCopy for Canvas {
+    fn copy(&self) -> KObject {
+        // Copies get their own display list
+        Self(PtrMut::from(self.0.borrow().clone())).into()
+    }
+}
+
+impl KotoObject for Canvas {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        let state = self.0.borrow();
+        ctx.append(format!(
+            "Canvas({}x{}, {} commands)",
+            state.width,
+            state.height,
+            state.commands.len()
+        ));
+        Ok(())
+    }
+}
+
+impl From<Canvas> for KValue {
+    fn from(canvas: Canvas) -> Self {
+        KObject::from(canvas).into()
+    }
+}
+
+// Gets a fixed number of Numbers from the arguments
+fn numbers<const N: usize>(args: &[KValue], expected: &str) -> Result<[f64; N]> {
+    let mut result = [0.0; N];
+    if args.len() == N {
+        for (value, arg) in result.iter_mut().zip(args) {
+            match arg {
+                KValue::Number(n) => *value = n.into(),
+                _ => return type_error_with_slice(expected, args),
+            }
+        }
+        Ok(result)
+    } else {
+        type_error_with_slice(expected, args)
+    }
+}
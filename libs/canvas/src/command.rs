@@ -0,0 +1,209 @@
+use koto_runtime::prelude::*;
+
+/// An RGBA color, with 8 bits per channel
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const BLACK: Self = Self::rgba(0, 0, 0, 255);
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+}
+
+impl From<Color> for KValue {
+    fn from(color: Color) -> Self {
+        KValue::Tuple(
+            vec![
+                color.r.into(),
+                color.g.into(),
+                color.b.into(),
+                color.a.into(),
+            ]
+            .into(),
+        )
+    }
+}
+
+/// A drawing command recorded by a canvas
+///
+/// Paths are built up with the path commands, and then drawn with [Command::Fill] or
+/// [Command::Stroke]. Transforms apply to the commands that follow them, until the transform
+/// state is restored with [Command::Restore].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    /// Fills the whole canvas with a color, ignoring the current transform
+    Clear(Color),
+    /// Starts a new path, discarding the current path
+    BeginPath,
+    /// Starts a new sub-path at the given point
+    MoveTo { x: f64, y: f64 },
+    /// Adds a straight line to the current path
+    LineTo { x: f64, y: f64 },
+    /// Adds a quadratic bézier curve to the current path
+    QuadTo { cx: f64, cy: f64, x: f64, y: f64 },
+    /// Adds a cubic bézier curve to the current path
+    BezierTo {
+        c1x: f64,
+        c1y: f64,
+        c2x: f64,
+        c2y: f64,
+        x: f64,
+        y: f64,
+    },
+    /// Adds a clockwise circular arc to the current path, with angles in radians
+    Arc {
+        x: f64,
+        y: f64,
+        radius: f64,
+        start: f64,
+        end: f64,
+    },
+    /// Adds a closed rectangle to the current path
+    Rect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    },
+    /// Adds a closed circle to the current path
+    Circle { x: f64, y: f64, radius: f64 },
+    /// Closes the current sub-path
+    ClosePath,
+    /// Fills the current path
+    Fill { color: Color },
+    /// Draws the outline of the current path
+    Stroke { color: Color, width: f64 },
+    /// Draws text, with the given position as the text's top-left corner
+    Text {
+        x: f64,
+        y: f64,
+        text: String,
+        size: f64,
+        color: Color,
+    },
+    /// Translates the current transform
+    Translate { x: f64, y: f64 },
+    /// Rotates the current transform clockwise, with the angle in radians
+    Rotate { angle: f64 },
+    /// Scales the current transform
+    Scale { x: f64, y: f64 },
+    /// Saves the current transform
+    Save,
+    /// Restores the most recently saved transform
+    Restore,
+}
+
+impl Command {
+    /// The name of the command, as used in the `type` entry of [Command::to_map]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Clear(_) => "clear",
+            Self::BeginPath => "begin_path",
+            Self::MoveTo { .. } => "move_to",
+            Self::LineTo { .. } => "line_to",
+            Self::QuadTo { .. } => "quad_to",
+            Self::BezierTo { .. } => "bezier_to",
+            Self::Arc { .. } => "arc",
+            Self::Rect { .. } => "rect",
+            Self::Circle { .. } => "circle",
+            Self::ClosePath => "close_path",
+            Self::Fill { .. } => "fill",
+            Self::Stroke { .. } => "stroke",
+            Self::Text { .. } => "text",
+            Self::Translate { .. } => "translate",
+            Self::Rotate { .. } => "rotate",
+            Self::Scale { .. } => "scale",
+            Self::Save => "save",
+            Self::Restore => "restore",
+        }
+    }
+
+    /// Returns the command as a map, with the command's name in a `type` entry
+    pub fn to_map(&self) -> KMap {
+        let result = KMap::with_capacity(7);
+        result.insert("type", self.name());
+
+        let insert_numbers = |entries: &[(&str, f64)]| {
+            for (key, value) in entries {
+                result.insert(*key, *value);
+            }
+        };
+
+        match self {
+            Self::Clear(color) => result.insert("color", *color),
+            Self::BeginPath | Self::ClosePath | Self::Save | Self::Restore => {}
+            Self::MoveTo { x, y } | Self::LineTo { x, y } => {
+                insert_numbers(&[("x", *x), ("y", *y)])
+            }
+            Self::QuadTo { cx, cy, x, y } => {
+                insert_numbers(&[("cx", *cx), ("cy", *cy), ("x", *x), ("y", *y)])
+            }
+            Self::BezierTo {
+                c1x,
+                c1y,
+                c2x,
+                c2y,
+                x,
+                y,
+            } => insert_numbers(&[
+                ("c1x", *c1x),
+                ("c1y", *c1y),
+                ("c2x", *c2x),
+                ("c2y", *c2y),
+                ("x", *x),
+                ("y", *y),
+            ]),
+            Self::Arc {
+                x,
+                y,
+                radius,
+                start,
+                end,
+            } => insert_numbers(&[
+                ("x", *x),
+                ("y", *y),
+                ("radius", *radius),
+                ("start", *start),
+                ("end", *end),
+            ]),
+            Self::Rect {
+                x,
+                y,
+                width,
+                height,
+            } => insert_numbers(&[("x", *x), ("y", *y), ("width", *width), ("height", *height)]),
+            Self::Circle { x, y, radius } => {
+                insert_numbers(&[("x", *x), ("y", *y), ("radius", *radius)])
+            }
+            Self::Fill { color } => result.insert("color", *color),
+            Self::Stroke { color, width } => {
+                result.insert("color", *color);
+                result.insert("width", *width);
+            }
+            Self::Text {
+                x,
+                y,
+                text,
+                size,
+                color,
+            } => {
+                insert_numbers(&[("x", *x), ("y", *y), ("size", *size)]);
+                result.insert("text", text.as_str());
+                result.insert("color", *color);
+            }
+            Self::Translate { x, y } | Self::Scale { x, y } => {
+                insert_numbers(&[("x", *x), ("y", *y)])
+            }
+            Self::Rotate { angle } => insert_numbers(&[("angle", *angle)]),
+        }
+
+        result
+    }
+}
@@ -0,0 +1,89 @@
+//! A Koto language module for recording 2D drawing commands
+//!
+//! Scripts draw on a [Canvas] with path, fill, transform, and text calls, which are recorded as
+//! [Command]s in a display list rather than being rendered directly. The host application is
+//! responsible for rendering the display list, typically by taking the canvas's commands once
+//! per frame with [Canvas::take_commands].
+
+mod canvas;
+mod command;
+
+pub use crate::{
+    canvas::Canvas,
+    command::{Color, Command},
+};
+
+use koto_runtime::{prelude::*, Result};
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("canvas");
+
+    result.add_fn("new", |ctx| match ctx.args() {
+        [KValue::Number(width), KValue::Number(height)] => {
+            if *width <= 0 || *height <= 0 {
+                return runtime_error!("Expected a positive width and height");
+            }
+            Ok(Canvas::new(width.into(), height.into()).into())
+        }
+        unexpected => type_error_with_slice("a width and height", unexpected),
+    });
+
+    result
+}
+
+/// Gets a [Color] from a tuple or list of 3 or 4 channels, or from a hex string
+pub fn color_from_value(value: &KValue) -> Result<Color> {
+    let channels = match value {
+        KValue::Tuple(channels) => channels.to_vec(),
+        KValue::List(channels) => channels.data().to_vec(),
+        KValue::Str(hex) => return color_from_hex(hex),
+        unexpected => return type_error("a color", unexpected),
+    };
+
+    let channel = |value: &KValue| match value {
+        KValue::Number(n) => Ok(f64::from(n).round().clamp(0.0, 255.0) as u8),
+        unexpected => type_error("a Number as color channel", unexpected),
+    };
+
+    match channels.as_slice() {
+        [r, g, b] => Ok(Color::rgba(channel(r)?, channel(g)?, channel(b)?, 255)),
+        [r, g, b, a] => Ok(Color::rgba(
+            channel(r)?,
+            channel(g)?,
+            channel(b)?,
+            channel(a)?,
+        )),
+        unexpected => type_error_with_slice("3 or 4 color channels", unexpected),
+    }
+}
+
+fn color_from_hex(hex: &str) -> Result<Color> {
+    let digits = hex.strip_prefix('#').unwrap_or(hex);
+    let invalid = || runtime_error!("Invalid hex color '{hex}'");
+
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return invalid();
+    }
+
+    let channels: Vec<u8> = match digits.len() {
+        // Short hex colors have each digit repeated, e.g. #f80 is #ff8800
+        3 => digits
+            .chars()
+            .map(|c| {
+                let n = c.to_digit(16).unwrap_or_default() as u8;
+                n * 16 + n
+            })
+            .collect(),
+        6 | 8 => (0..digits.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap_or_default())
+            .collect(),
+        _ => return invalid(),
+    };
+
+    match channels.as_slice() {
+        [r, g, b] => Ok(Color::rgba(*r, *g, *b, 255)),
+        [r, g, b, a] => Ok(Color::rgba(*r, *g, *b, *a)),
+        _ => invalid(),
+    }
+}
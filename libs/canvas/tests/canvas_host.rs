@@ -0,0 +1,88 @@
+use koto_canvas::{make_module, Canvas, Color, Command};
+use koto_runtime::{prelude::*, Result};
+use koto_test_utils::check_script_output_with_vm;
+
+fn make_vm(canvas: &Canvas) -> KotoVm {
+    let vm = KotoVm::default();
+    vm.prelude().insert("canvas", make_module());
+    vm.prelude().insert("screen", canvas.clone());
+    vm
+}
+
+#[test]
+fn take_commands_from_script() -> Result<()> {
+    let canvas = Canvas::new(100.0, 50.0);
+    let vm = make_vm(&canvas);
+
+    let script = "
+screen
+  .clear '#fff'
+  .fill_style '#f00'
+  .translate 10, 20
+  .circle 0, 0, 5
+  .fill()
+null
+";
+    check_script_output_with_vm(vm, script, KValue::Null)?;
+
+    assert_eq!(canvas.size(), (100.0, 50.0));
+    assert_eq!(
+        canvas.take_commands(),
+        [
+            Command::Clear(Color::rgba(255, 255, 255, 255)),
+            Command::Translate { x: 10.0, y: 20.0 },
+            Command::Circle {
+                x: 0.0,
+                y: 0.0,
+                radius: 5.0
+            },
+            Command::Fill {
+                color: Color::rgba(255, 0, 0, 255)
+            },
+        ]
+    );
+    assert!(canvas.recorded_commands().is_empty());
+    Ok(())
+}
+
+#[test]
+fn take_commands_each_frame() -> Result<()> {
+    let canvas = Canvas::new(10.0, 10.0);
+    let mut vm = make_vm(&canvas);
+
+    let script = "
+export draw = ||
+  screen.line_width((size screen.commands()) + 2).stroke()
+null
+";
+    check_script_output_with_vm(vm.spawn_shared_vm(), script, KValue::Null)?;
+    let draw = vm.exports().get("draw").unwrap();
+
+    for _ in 0..2 {
+        vm.call_function(draw.clone(), &[])?;
+        assert_eq!(
+            canvas.take_commands(),
+            [Command::Stroke {
+                color: Color::BLACK,
+                width: 2.0
+            }]
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn copies_have_separate_commands() -> Result<()> {
+    let canvas = Canvas::new(10.0, 10.0);
+    let vm = make_vm(&canvas);
+
+    let script = "
+c = copy screen
+c.begin_path()
+assert_eq (size c.commands()), 1
+size screen.commands()
+";
+    check_script_output_with_vm(vm, script, KValue::Number(0.into()))?;
+    assert!(canvas.recorded_commands().is_empty());
+    Ok(())
+}
@@ -1,7 +1,15 @@
 use koto_runtime::{derive::*, prelude::*, Result};
 use std::{fmt, ops};
 
-use palette::{rgb::LinSrgba as Inner, FromColor, Mix};
+use palette::{
+    encoding::{Linear, Srgb},
+    rgb::LinSrgba as Inner,
+    FromColor, Hsl, Hsv, Mix,
+};
+
+// The color spaces used for conversions, matching the spaces used by `color.hsl` and `color.hsv`
+type HslColor = Hsl<Linear<Srgb>, f32>;
+type HsvColor = Hsv<Linear<Srgb>, f32>;
 
 macro_rules! impl_arithmetic_op {
     ($trait:ident, $trait_fn:ident, $op:tt) => {
@@ -139,10 +147,111 @@ impl Color {
         })
     }
 
+    /// Makes a color from a hex string like `#f80`, `#ff8800`, or `#ff880080`
+    ///
+    /// The leading `#` is optional.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let channels: Vec<f32> = match digits.len() {
+            // Short hex colors have each digit repeated, e.g. #f80 is #ff8800
+            3 | 4 => digits
+                .chars()
+                .map(|c| c.to_digit(16).unwrap_or_default() as f32 * 17.0 / 255.0)
+                .collect(),
+            6 | 8 => (0..digits.len())
+                .step_by(2)
+                .map(|i| {
+                    u8::from_str_radix(&digits[i..i + 2], 16).unwrap_or_default() as f32 / 255.0
+                })
+                .collect(),
+            _ => return None,
+        };
+
+        match channels.as_slice() {
+            [r, g, b] => Some(Self::rgb(*r, *g, *b)),
+            [r, g, b, a] => Some(Self::rgba(*r, *g, *b, *a)),
+            _ => None,
+        }
+    }
+
+    /// Returns the color as a hex string
+    ///
+    /// The alpha component is only included if the color isn't fully opaque.
+    pub fn to_hex_string(&self) -> String {
+        let to_byte = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let c = self.0.color;
+        let rgb = format!(
+            "#{:02x}{:02x}{:02x}",
+            to_byte(c.red),
+            to_byte(c.green),
+            to_byte(c.blue)
+        );
+        if to_byte(self.0.alpha) == 255 {
+            rgb
+        } else {
+            format!("{rgb}{:02x}", to_byte(self.0.alpha))
+        }
+    }
+
+    /// Returns the color's relative luminance, as defined by WCAG 2
+    ///
+    /// The color's components are treated as gamma-encoded sRGB values, as used in hex colors.
+    pub fn relative_luminance(&self) -> f64 {
+        let linear = |c: f32| {
+            let c = f64::from(c);
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        let c = self.0.color;
+        0.2126 * linear(c.red) + 0.7152 * linear(c.green) + 0.0722 * linear(c.blue)
+    }
+
+    /// Returns the contrast ratio between two colors, as defined by WCAG 2
+    ///
+    /// The result is in the range 1 to 21.
+    pub fn contrast_ratio(&self, other: &Self) -> f64 {
+        let a = self.relative_luminance();
+        let b = other.relative_luminance();
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
     pub fn inner(&self) -> &Inner {
         &self.0
     }
 
+    // Returns a copy of the color with a modified lightness or saturation
+    fn adjust_hsl(&self, adjust: impl FnOnce(&mut HslColor)) -> Self {
+        let mut hsl = HslColor::from_color(self.0.color);
+        adjust(&mut hsl);
+        hsl.saturation = hsl.saturation.clamp(0.0, 1.0);
+        hsl.lightness = hsl.lightness.clamp(0.0, 1.0);
+        let mut result = Self::from(hsl);
+        result.0.alpha = self.0.alpha;
+        result
+    }
+
+    // Runs an adjustment method that takes an amount
+    fn adjust_method(
+        &self,
+        args: &[KValue],
+        adjust: impl FnOnce(&mut HslColor, f32),
+    ) -> Result<KValue> {
+        match args {
+            [KValue::Number(amount)] => {
+                let amount = f32::from(amount);
+                Ok(self.adjust_hsl(|hsl| adjust(hsl, amount)).into())
+            }
+            unexpected => type_error_with_slice("a Number", unexpected),
+        }
+    }
+
     #[koto_method(alias = "r")]
     pub fn red(&self) -> KValue {
         self.0.color.red.into()
@@ -226,6 +335,72 @@ impl Color {
             unexpected => type_error_with_slice("2 Colors and an optional mix amount", unexpected),
         }
     }
+
+    #[koto_method]
+    pub fn contrast(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Object(other)] if other.is_a::<Color>() => {
+                Ok(self.contrast_ratio(&*other.cast::<Color>()?).into())
+            }
+            unexpected => type_error_with_slice("a Color", unexpected),
+        }
+    }
+
+    #[koto_method]
+    pub fn darken(&self, args: &[KValue]) -> Result<KValue> {
+        self.adjust_method(args, |hsl, amount| hsl.lightness -= amount)
+    }
+
+    #[koto_method]
+    pub fn desaturate(&self, args: &[KValue]) -> Result<KValue> {
+        self.adjust_method(args, |hsl, amount| hsl.saturation -= amount)
+    }
+
+    #[koto_method]
+    pub fn lighten(&self, args: &[KValue]) -> Result<KValue> {
+        self.adjust_method(args, |hsl, amount| hsl.lightness += amount)
+    }
+
+    #[koto_method]
+    pub fn luminance(&self) -> KValue {
+        self.relative_luminance().into()
+    }
+
+    #[koto_method]
+    pub fn saturate(&self, args: &[KValue]) -> Result<KValue> {
+        self.adjust_method(args, |hsl, amount| hsl.saturation += amount)
+    }
+
+    #[koto_method]
+    pub fn to_hex(&self) -> KValue {
+        self.to_hex_string().into()
+    }
+
+    #[koto_method]
+    pub fn to_hsl(&self) -> KValue {
+        let hsl = HslColor::from_color(self.0.color);
+        KValue::Tuple(
+            vec![
+                hsl.hue.into_positive_degrees().into(),
+                hsl.saturation.into(),
+                hsl.lightness.into(),
+            ]
+            .into(),
+        )
+    }
+
+    #[koto_method]
+    pub fn to_hsv(&self) -> KValue {
+        let hsv = HsvColor::from_color(self.0.color);
+        KValue::Tuple(
+            vec![
+                hsv.hue.into_positive_degrees().into(),
+                hsv.saturation.into(),
+                hsv.value.into(),
+            ]
+            .into(),
+        )
+    }
 }
 
 impl KotoObject for Color {
@@ -5,12 +5,62 @@ mod color;
 pub use color::Color;
 
 use koto_runtime::{prelude::*, Result};
-use palette::{Hsl, Hsv};
+use palette::{Hsl, Hsv, Mix};
 
 pub fn make_module() -> KMap {
     use KValue::{Number, Str};
     let mut result = KMap::default();
 
+    result.add_fn("gradient", |ctx| {
+        let (colors, steps) = match ctx.args() {
+            [colors, Number(steps)] if colors.is_iterable() => (colors.clone(), steps),
+            unexpected => {
+                return type_error_with_slice(
+                    "an iterable of Colors and a number of steps",
+                    unexpected,
+                )
+            }
+        };
+        let steps = match usize::try_from(i64::from(steps)) {
+            Ok(steps) if steps >= 2 => steps,
+            _ => return runtime_error!("Expected at least 2 steps, found {steps}"),
+        };
+
+        let mut stops = Vec::new();
+        for output in ctx.vm.make_iterator(colors)? {
+            match output {
+                KIteratorOutput::Value(KValue::Object(o)) if o.is_a::<Color>() => {
+                    stops.push(*o.cast::<Color>()?.inner())
+                }
+                KIteratorOutput::Value(unexpected) => return type_error("a Color", &unexpected),
+                KIteratorOutput::ValuePair(..) => return runtime_error!("Expected a Color"),
+                KIteratorOutput::Error(error) => return Err(error),
+            }
+        }
+        if stops.len() < 2 {
+            return runtime_error!("Expected at least 2 colors");
+        }
+
+        // The steps are spread evenly across the gradient, with the first and last steps
+        // matching the first and last colors
+        let segments = (stops.len() - 1) as f32;
+        let result = (0..steps)
+            .map(|step| {
+                let position = step as f32 / (steps - 1) as f32 * segments;
+                let segment = (position.floor() as usize).min(stops.len() - 2);
+                let amount = position - segment as f32;
+                Color::from(stops[segment].mix(stops[segment + 1], amount)).into()
+            })
+            .collect::<Vec<KValue>>();
+
+        Ok(KList::with_data(result.into()).into())
+    });
+
+    result.add_fn("hex", |ctx| match ctx.args() {
+        [Str(s)] => hex(s),
+        unexpected => type_error_with_slice("a String", unexpected),
+    });
+
     result.add_fn("hsl", |ctx| match ctx.args() {
         [Number(h), Number(s), Number(l)] => {
             let hsv = Hsl::new(f32::from(h), f32::from(s), f32::from(l));
@@ -46,16 +96,27 @@ pub fn make_module() -> KMap {
 
     meta.insert(MetaKey::Type, "color".into());
     meta.add_fn(MetaKey::Call, |ctx| match ctx.args() {
+        [Str(s)] if s.starts_with('#') => hex(s),
         [Str(s)] => named(s),
         [Number(r), Number(g), Number(b)] => rgb(r, g, b),
         [Number(r), Number(g), Number(b), Number(a)] => rgba(r, g, b, a),
-        unexpected => type_error_with_slice("a color name, rgb, or rgba values", unexpected),
+        unexpected => type_error_with_slice(
+            "a color name, a hex string, rgb, or rgba values",
+            unexpected,
+        ),
     });
 
     result.set_meta_map(Some(meta.into()));
     result
 }
 
+fn hex(s: &str) -> Result<KValue> {
+    match Color::from_hex(s) {
+        Some(c) => Ok(c.into()),
+        None => runtime_error!("Invalid hex color '{s}'"),
+    }
+}
+
 fn named(name: &str) -> Result<KValue> {
     match Color::named(name) {
         Some(c) => Ok(c.into()),
@@ -0,0 +1,173 @@
+use crate::hash::hash_value;
+use koto_runtime::{derive::*, prelude::*, Result};
+
+/// A Bloom filter, created with `containers.bloom`
+///
+/// Values are hashed using double hashing to derive the filter's bit positions.
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "Bloom")]
+pub struct Bloom {
+    bits: Vec<u64>,
+    bit_count: u64,
+    hash_count: u32,
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Bloom {
+    /// Makes a filter sized for the expected number of values and the false positive rate
+    ///
+    /// An error is returned if memory for the filter's bits can't be allocated.
+    pub fn new(expected: u64, false_positive_rate: f64) -> Result<Self> {
+        let ln2 = std::f64::consts::LN_2;
+        let n = expected.max(1) as f64;
+        let bit_count = (-n * false_positive_rate.ln() / (ln2 * ln2))
+            .ceil()
+            .max(64.0) as u64;
+        let hash_count = ((bit_count as f64 / n) * ln2).round().clamp(1.0, 32.0) as u32;
+        Self::with_size(bit_count, hash_count)
+    }
+
+    /// Makes a filter with the given number of bits and hash functions
+    ///
+    /// An error is returned if memory for the filter's bits can't be allocated.
+    pub fn with_size(bit_count: u64, hash_count: u32) -> Result<Self> {
+        let bit_count = bit_count.max(1);
+        let word_count = usize::try_from(bit_count.div_ceil(64)).unwrap_or(usize::MAX);
+        let mut bits = Vec::new();
+        if bits.try_reserve_exact(word_count).is_err() {
+            return runtime_error!("unable to allocate a Bloom filter with {bit_count} bits");
+        }
+        bits.resize(word_count, 0);
+
+        Ok(Self {
+            bits,
+            bit_count,
+            hash_count: hash_count.max(1),
+        })
+    }
+
+    /// Inserts a value into the filter
+    ///
+    /// Returns true if the value wasn't already present in the filter.
+    pub fn insert(&mut self, value: &KValue) -> Result<bool> {
+        let hash = hash_value(value)?;
+        let mut inserted = false;
+        for bit in self.bit_positions(hash) {
+            let (word, mask) = (bit / 64, 1 << (bit % 64));
+            if self.bits[word as usize] & mask == 0 {
+                self.bits[word as usize] |= mask;
+                inserted = true;
+            }
+        }
+        Ok(inserted)
+    }
+
+    /// Returns true if the value might have been inserted into the filter
+    ///
+    /// False positives are possible, but false negatives aren't.
+    pub fn contains(&self, value: &KValue) -> Result<bool> {
+        let hash = hash_value(value)?;
+        Ok(self
+            .bit_positions(hash)
+            .all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0))
+    }
+
+    /// Returns an estimate of the number of distinct values that have been inserted
+    pub fn estimate(&self) -> f64 {
+        let m = self.bit_count as f64;
+        let k = self.hash_count as f64;
+        let set_bits = self
+            .bits
+            .iter()
+            .map(|word| word.count_ones() as f64)
+            .sum::<f64>();
+        if set_bits >= m {
+            f64::INFINITY
+        } else {
+            -(m / k) * (1.0 - set_bits / m).ln()
+        }
+    }
+
+    /// Merges another filter's values into this filter
+    ///
+    /// The filters must have been created with the same size parameters.
+    pub fn merge(&mut self, other: &Bloom) -> Result<()> {
+        if self.bit_count != other.bit_count || self.hash_count != other.hash_count {
+            return runtime_error!("only Bloom filters with matching sizes can be merged");
+        }
+        for (a, b) in self.bits.iter_mut().zip(other.bits.iter()) {
+            *a |= b;
+        }
+        Ok(())
+    }
+
+    fn bit_positions(&self, hash: u64) -> impl Iterator<Item = u64> {
+        let (h1, h2) = (hash, (hash >> 32) | 1);
+        let bit_count = self.bit_count;
+        (0..self.hash_count as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % bit_count)
+    }
+
+    #[koto_method]
+    fn bit_count(&self) -> KValue {
+        self.bit_count.into()
+    }
+
+    #[koto_method]
+    fn clear(ctx: MethodContext<Self>) -> Result<KValue> {
+        ctx.instance_mut()?.bits.fill(0);
+        ctx.instance_result()
+    }
+
+    #[koto_method(alias = "contains")]
+    fn contains_koto(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [value] => Ok(self.contains(value)?.into()),
+            unexpected => type_error_with_slice("a value", unexpected),
+        }
+    }
+
+    #[koto_method(alias = "estimate")]
+    fn estimate_koto(&self) -> KValue {
+        self.estimate().into()
+    }
+
+    #[koto_method]
+    fn hash_count(&self) -> KValue {
+        self.hash_count.into()
+    }
+
+    #[koto_method(alias = "insert")]
+    fn insert_koto(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [value] => Ok(ctx.instance_mut()?.insert(value)?.into()),
+            unexpected => type_error_with_slice("a value", unexpected),
+        }
+    }
+
+    #[koto_method(alias = "merge")]
+    fn merge_koto(ctx: MethodContext<Self>) -> Result<KValue> {
+        let other = match ctx.args {
+            // The other filter is cloned in case it's the same instance as this filter
+            [KValue::Object(o)] if o.is_a::<Self>() => o.cast::<Self>()?.clone(),
+            unexpected => return type_error_with_slice("a Bloom filter", unexpected),
+        };
+        ctx.instance_mut()?.merge(&other)?;
+        ctx.instance_result()
+    }
+}
+
+impl KotoObject for Bloom {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!(
+            "Bloom({} bits, {} hashes)",
+            self.bit_count, self.hash_count
+        ));
+        Ok(())
+    }
+}
+
+impl From<Bloom> for KValue {
+    fn from(bloom: Bloom) -> Self {
+        KObject::from(bloom).into()
+    }
+}
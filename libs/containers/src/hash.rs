@@ -0,0 +1,26 @@
+use koto_runtime::{prelude::*, Result};
+use rustc_hash::FxHasher;
+use std::hash::{Hash, Hasher};
+
+/// Returns a 64 bit hash of a hashable Koto value
+///
+/// The hash is deterministic, so values inserted into separate containers can be compared when
+/// the containers are merged.
+pub fn hash_value(value: &KValue) -> Result<u64> {
+    let key =
+        ValueKey::try_from(value.clone()).or_else(|_| type_error("a hashable value", value))?;
+    let mut hasher = FxHasher::default();
+    key.hash(&mut hasher);
+    Ok(mix(hasher.finish()))
+}
+
+// FxHash is fast but doesn't distribute its input well across all bits of the output,
+// so the hash is passed through the MurmurHash3 finalizer.
+fn mix(mut h: u64) -> u64 {
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    h ^= h >> 33;
+    h
+}
@@ -0,0 +1,141 @@
+use crate::hash::hash_value;
+use koto_runtime::{derive::*, prelude::*, Result};
+
+/// A HyperLogLog cardinality estimator, created with `containers.hll`
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "HyperLogLog")]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+    precision: u8,
+}
+
+/// The range of precisions supported by [HyperLogLog]
+pub const HLL_PRECISION_RANGE: std::ops::RangeInclusive<u8> = 4..=18;
+
+/// The default precision used by [HyperLogLog], with a standard error of around 0.8%
+pub const HLL_DEFAULT_PRECISION: u8 = 14;
+
+#[koto_impl(runtime = koto_runtime)]
+impl HyperLogLog {
+    /// Makes an estimator with `2^precision` registers
+    ///
+    /// Higher precisions give more accurate estimates at the cost of using more memory.
+    pub fn new(precision: u8) -> Result<Self> {
+        if !HLL_PRECISION_RANGE.contains(&precision) {
+            return runtime_error!(
+                "the precision must be in the range {}..={}",
+                HLL_PRECISION_RANGE.start(),
+                HLL_PRECISION_RANGE.end()
+            );
+        }
+        Ok(Self {
+            registers: vec![0; 1 << precision],
+            precision,
+        })
+    }
+
+    /// Inserts a value into the estimator
+    pub fn insert(&mut self, value: &KValue) -> Result<()> {
+        let hash = hash_value(value)?;
+        let index = (hash >> (64 - self.precision)) as usize;
+        // The rank is the position of the first set bit in the hash's remaining bits
+        let remaining = hash << self.precision;
+        let max_rank = 64 - self.precision + 1;
+        let rank = (remaining.leading_zeros() as u8 + 1).min(max_rank);
+        let register = &mut self.registers[index];
+        *register = (*register).max(rank);
+        Ok(())
+    }
+
+    /// Returns an estimate of the number of distinct values that have been inserted
+    pub fn estimate(&self) -> f64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|register| 2f64.powi(-i32::from(*register)))
+            .sum();
+        let estimate = alpha * m * m / sum;
+
+        // Linear counting gives better estimates for small cardinalities
+        let zeros = self
+            .registers
+            .iter()
+            .filter(|register| **register == 0)
+            .count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            estimate
+        }
+    }
+
+    /// Merges another estimator's values into this estimator
+    ///
+    /// The estimators must have the same precision.
+    pub fn merge(&mut self, other: &HyperLogLog) -> Result<()> {
+        if self.precision != other.precision {
+            return runtime_error!("only HyperLogLogs with matching precisions can be merged");
+        }
+        for (a, b) in self.registers.iter_mut().zip(other.registers.iter()) {
+            *a = (*a).max(*b);
+        }
+        Ok(())
+    }
+
+    #[koto_method]
+    fn clear(ctx: MethodContext<Self>) -> Result<KValue> {
+        ctx.instance_mut()?.registers.fill(0);
+        ctx.instance_result()
+    }
+
+    #[koto_method(alias = "estimate")]
+    fn estimate_koto(&self) -> KValue {
+        (self.estimate().round() as i64).into()
+    }
+
+    #[koto_method(alias = "insert")]
+    fn insert_koto(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [value] => ctx.instance_mut()?.insert(value)?,
+            unexpected => return type_error_with_slice("a value", unexpected),
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method(alias = "merge")]
+    fn merge_koto(ctx: MethodContext<Self>) -> Result<KValue> {
+        let other = match ctx.args {
+            // The other estimator is cloned in case it's the same instance as this estimator
+            [KValue::Object(o)] if o.is_a::<Self>() => o.cast::<Self>()?.clone(),
+            unexpected => return type_error_with_slice("a HyperLogLog", unexpected),
+        };
+        ctx.instance_mut()?.merge(&other)?;
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn precision(&self) -> KValue {
+        self.precision.into()
+    }
+}
+
+impl KotoObject for HyperLogLog {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!("HyperLogLog(precision {})", self.precision));
+        Ok(())
+    }
+}
+
+impl From<HyperLogLog> for KValue {
+    fn from(hll: HyperLogLog) -> Self {
+        KObject::from(hll).into()
+    }
+}
@@ -0,0 +1,294 @@
+use koto_runtime::{derive::*, prelude::*, Result};
+use std::cmp::Ordering;
+
+/// A map from half-open intervals to values, created with `containers.interval_map`
+///
+/// Intervals are kept sorted by their start, and the sorted intervals are treated as an implicit
+/// balanced binary tree, with each node storing the maximum end of the intervals in its subtree.
+/// This allows queries to skip subtrees that can't contain matching intervals.
+#[derive(Clone, Default, KotoType, KotoCopy)]
+#[koto(type_name = "IntervalMap")]
+pub struct IntervalMap {
+    entries: Vec<Entry>,
+    // The maximum end of each subtree, rebuilt when needed after modifications
+    max_ends: Vec<KNumber>,
+    dirty: bool,
+}
+
+#[derive(Clone)]
+struct Entry {
+    start: KNumber,
+    end: KNumber,
+    value: KValue,
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl IntervalMap {
+    /// Makes an empty map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of intervals in the map
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the map contains no intervals
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Inserts an interval `[start, end)` with an associated value
+    ///
+    /// Overlapping intervals are allowed, including multiple intervals with the same bounds.
+    pub fn insert(&mut self, start: KNumber, end: KNumber, value: KValue) -> Result<()> {
+        check_interval(start, end)?;
+        // Insert after any intervals with the same start, so that insertion order is preserved
+        let index = self
+            .entries
+            .partition_point(|entry| compare(entry.start, start) != Ordering::Greater);
+        self.entries.insert(index, Entry { start, end, value });
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Removes all intervals with the given bounds, returning the number of removed intervals
+    pub fn remove(&mut self, start: KNumber, end: KNumber) -> usize {
+        let before = self.entries.len();
+        self.entries
+            .retain(|entry| !(entry.start == start && entry.end == end));
+        let removed = before - self.entries.len();
+        if removed > 0 {
+            self.dirty = true;
+        }
+        removed
+    }
+
+    /// Returns the indices of the intervals that contain the given point, sorted by start
+    fn find_containing(&mut self, point: KNumber) -> Vec<usize> {
+        self.update_max_ends();
+        let mut result = Vec::new();
+        self.visit(
+            0,
+            self.entries.len(),
+            point,
+            &mut result,
+            &|entry| match compare(entry.start, point) {
+                Ordering::Greater => Visit::Stop,
+                _ if compare(entry.end, point) == Ordering::Greater => Visit::Match,
+                _ => Visit::Continue,
+            },
+        );
+        result
+    }
+
+    /// Returns the indices of the intervals that overlap `[start, end)`, sorted by start
+    fn find_overlapping(&mut self, start: KNumber, end: KNumber) -> Vec<usize> {
+        self.update_max_ends();
+        let mut result = Vec::new();
+        self.visit(0, self.entries.len(), start, &mut result, &|entry| {
+            if compare(entry.start, end) != Ordering::Less {
+                Visit::Stop
+            } else if compare(entry.end, start) == Ordering::Greater {
+                Visit::Match
+            } else {
+                Visit::Continue
+            }
+        });
+        result
+    }
+
+    // Visits the subtree in `lo..hi`, skipping subtrees where no interval ends after `min_end`
+    fn visit(
+        &self,
+        lo: usize,
+        hi: usize,
+        min_end: KNumber,
+        result: &mut Vec<usize>,
+        check: &dyn Fn(&Entry) -> Visit,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        if compare(self.max_ends[mid], min_end) != Ordering::Greater {
+            return;
+        }
+        self.visit(lo, mid, min_end, result, check);
+        match check(&self.entries[mid]) {
+            Visit::Stop => return,
+            Visit::Match => result.push(mid),
+            Visit::Continue => {}
+        }
+        self.visit(mid + 1, hi, min_end, result, check);
+    }
+
+    fn update_max_ends(&mut self) {
+        if self.dirty || self.max_ends.len() != self.entries.len() {
+            self.max_ends = self.entries.iter().map(|entry| entry.end).collect();
+            build_max_ends(&self.entries, &mut self.max_ends, 0, self.entries.len());
+            self.dirty = false;
+        }
+    }
+
+    fn entry_tuple(&self, index: usize) -> KValue {
+        let entry = &self.entries[index];
+        KValue::Tuple(vec![entry.start.into(), entry.end.into(), entry.value.clone()].into())
+    }
+
+    #[koto_method]
+    fn clear(ctx: MethodContext<Self>) -> Result<KValue> {
+        *ctx.instance_mut()? = Self::new();
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn contains(ctx: MethodContext<Self>) -> Result<KValue> {
+        let point = point_arg(ctx.args)?;
+        Ok((!ctx.instance_mut()?.find_containing(point).is_empty()).into())
+    }
+
+    #[koto_method]
+    fn entries(&self) -> KValue {
+        let entries = (0..self.entries.len())
+            .map(|i| self.entry_tuple(i))
+            .collect::<ValueVec>();
+        KList::with_data(entries).into()
+    }
+
+    #[koto_method]
+    fn get(ctx: MethodContext<Self>) -> Result<KValue> {
+        let point = point_arg(ctx.args)?;
+        let mut this = ctx.instance_mut()?;
+        let values = this
+            .find_containing(point)
+            .into_iter()
+            .map(|i| this.entries[i].value.clone())
+            .collect::<ValueVec>();
+        Ok(KList::with_data(values).into())
+    }
+
+    #[koto_method(alias = "insert")]
+    fn insert_koto(ctx: MethodContext<Self>) -> Result<KValue> {
+        let ((start, end), value) = match ctx.args {
+            [KValue::Range(r), value] => (range_bounds(r)?, value),
+            [KValue::Number(start), KValue::Number(end), value] => ((*start, *end), value),
+            unexpected => {
+                return type_error_with_slice(
+                    "a Range or a start and end Number, followed by a value",
+                    unexpected,
+                )
+            }
+        };
+        ctx.instance_mut()?.insert(start, end, value.clone())?;
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn overlapping(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (start, end) = interval_args(ctx.args)?;
+        check_interval(start, end)?;
+        let mut this = ctx.instance_mut()?;
+        let entries = this
+            .find_overlapping(start, end)
+            .into_iter()
+            .map(|i| this.entry_tuple(i))
+            .collect::<ValueVec>();
+        Ok(KList::with_data(entries).into())
+    }
+
+    #[koto_method(alias = "remove")]
+    fn remove_koto(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (start, end) = interval_args(ctx.args)?;
+        Ok(ctx.instance_mut()?.remove(start, end).into())
+    }
+
+    #[koto_method(alias = "size")]
+    fn size_koto(&self) -> KValue {
+        self.len().into()
+    }
+}
+
+impl KotoObject for IntervalMap {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!("IntervalMap({})", self.entries.len()));
+        Ok(())
+    }
+}
+
+impl From<IntervalMap> for KValue {
+    fn from(map: IntervalMap) -> Self {
+        KObject::from(map).into()
+    }
+}
+
+enum Visit {
+    // The entry matches the query
+    Match,
+    // The entry doesn't match, but later entries might
+    Continue,
+    // The entry and all following entries can't match the query
+    Stop,
+}
+
+fn build_max_ends(entries: &[Entry], max_ends: &mut [KNumber], lo: usize, hi: usize) -> KNumber {
+    let mid = lo + (hi - lo) / 2;
+    let mut max_end = entries[mid].end;
+    if lo < mid {
+        max_end = max(max_end, build_max_ends(entries, max_ends, lo, mid));
+    }
+    if mid + 1 < hi {
+        max_end = max(max_end, build_max_ends(entries, max_ends, mid + 1, hi));
+    }
+    max_ends[mid] = max_end;
+    max_end
+}
+
+// Interval bounds are checked to not be NaN when inserted, so the comparison always succeeds
+fn compare(a: KNumber, b: KNumber) -> Ordering {
+    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
+fn max(a: KNumber, b: KNumber) -> KNumber {
+    if compare(a, b) == Ordering::Less {
+        b
+    } else {
+        a
+    }
+}
+
+fn check_interval(start: KNumber, end: KNumber) -> Result<()> {
+    if f64::from(start).is_nan() || f64::from(end).is_nan() {
+        runtime_error!("interval bounds can't be NaN")
+    } else if compare(start, end) != Ordering::Less {
+        runtime_error!("the interval's start ({start}) must be less than its end ({end})")
+    } else {
+        Ok(())
+    }
+}
+
+fn range_bounds(range: &KRange) -> Result<(KNumber, KNumber)> {
+    match (range.start(), range.end()) {
+        (Some(start), Some((end, inclusive))) => {
+            let end = if inclusive { end + 1 } else { end };
+            Ok((start.into(), end.into()))
+        }
+        _ => runtime_error!("expected a bounded range, found '{range}'"),
+    }
+}
+
+fn interval_args(args: &[KValue]) -> Result<(KNumber, KNumber)> {
+    match args {
+        [KValue::Range(r)] => range_bounds(r),
+        [KValue::Number(start), KValue::Number(end)] => Ok((*start, *end)),
+        unexpected => type_error_with_slice("a Range, or a start and end Number", unexpected),
+    }
+}
+
+fn point_arg(args: &[KValue]) -> Result<KNumber> {
+    match args {
+        [KValue::Number(n)] => Ok(*n),
+        unexpected => type_error_with_slice("a Number", unexpected),
+    }
+}
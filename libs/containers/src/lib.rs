@@ -0,0 +1,63 @@
+//! A Koto language module containing specialized container types
+//!
+//! The containers are implemented natively, for use when the core container types would be too
+//! slow or would use too much memory.
+
+mod bloom;
+mod hash;
+mod hyperloglog;
+mod interval_map;
+mod trie;
+
+pub use crate::{
+    bloom::Bloom,
+    hyperloglog::{HyperLogLog, HLL_DEFAULT_PRECISION, HLL_PRECISION_RANGE},
+    interval_map::IntervalMap,
+    trie::Trie,
+};
+
+use koto_runtime::prelude::*;
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("containers");
+
+    result.add_fn("bloom", |ctx| {
+        let (expected, false_positive_rate) = match ctx.args() {
+            [KValue::Number(expected)] if *expected > 0 => (expected, 0.01),
+            [KValue::Number(expected), KValue::Number(rate)]
+                if *expected > 0 && *rate > 0.0 && *rate < 1.0 =>
+            {
+                (expected, rate.into())
+            }
+            unexpected => {
+                return type_error_with_slice(
+                    "the expected number of values, and an optional false positive rate \
+                     between 0 and 1",
+                    unexpected,
+                )
+            }
+        };
+        Ok(Bloom::new(expected.into(), false_positive_rate)?.into())
+    });
+
+    result.add_fn("hll", |ctx| {
+        let precision = match ctx.args() {
+            [] => HLL_DEFAULT_PRECISION,
+            [KValue::Number(n)] => u8::try_from(i64::from(n)).unwrap_or(0),
+            unexpected => return type_error_with_slice("an optional precision", unexpected),
+        };
+        Ok(HyperLogLog::new(precision)?.into())
+    });
+
+    result.add_fn("interval_map", |ctx| match ctx.args() {
+        [] => Ok(IntervalMap::new().into()),
+        unexpected => type_error_with_slice("no arguments", unexpected),
+    });
+
+    result.add_fn("trie", |ctx| match ctx.args() {
+        [] => Ok(Trie::new().into()),
+        unexpected => type_error_with_slice("no arguments", unexpected),
+    });
+
+    result
+}
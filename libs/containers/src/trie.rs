@@ -0,0 +1,219 @@
+use koto_runtime::{derive::*, prelude::*, Result};
+use std::collections::BTreeMap;
+
+/// A map from strings to values that supports prefix queries, created with `containers.trie`
+///
+/// Keys are stored character by character, with the children of each node kept in sorted order
+/// so that entries are always visited in the same order as sorted keys.
+#[derive(Clone, Default, KotoType, KotoCopy)]
+#[koto(type_name = "Trie")]
+pub struct Trie {
+    root: TrieNode,
+    len: usize,
+}
+
+#[derive(Clone, Default)]
+struct TrieNode {
+    children: BTreeMap<char, TrieNode>,
+    value: Option<KValue>,
+}
+
+impl TrieNode {
+    fn find(&self, key: &str) -> Option<&TrieNode> {
+        key.chars().try_fold(self, |node, c| node.children.get(&c))
+    }
+
+    // Removes the value for the key, pruning any nodes that are left empty
+    fn remove(&mut self, mut chars: std::str::Chars) -> Option<KValue> {
+        match chars.next() {
+            None => self.value.take(),
+            Some(c) => {
+                let child = self.children.get_mut(&c)?;
+                let result = child.remove(chars);
+                if child.value.is_none() && child.children.is_empty() {
+                    self.children.remove(&c);
+                }
+                result
+            }
+        }
+    }
+
+    fn visit_entries(&self, key: &mut String, f: &mut dyn FnMut(&str, &KValue)) {
+        if let Some(value) = &self.value {
+            f(key, value);
+        }
+        for (c, child) in self.children.iter() {
+            key.push(*c);
+            child.visit_entries(key, f);
+            key.pop();
+        }
+    }
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Trie {
+    /// Makes an empty trie
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of keys in the trie
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the trie contains no keys
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts a value for the key, returning the key's previous value
+    pub fn insert(&mut self, key: &str, value: KValue) -> Option<KValue> {
+        let node = key.chars().fold(&mut self.root, |node, c| {
+            node.children.entry(c).or_default()
+        });
+        let previous = node.value.replace(value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    /// Returns the value for the key
+    pub fn get(&self, key: &str) -> Option<&KValue> {
+        self.root.find(key).and_then(|node| node.value.as_ref())
+    }
+
+    /// Removes the key from the trie, returning its value
+    pub fn remove(&mut self, key: &str) -> Option<KValue> {
+        let result = self.root.remove(key.chars());
+        if result.is_some() {
+            self.len -= 1;
+        }
+        result
+    }
+
+    /// Returns the longest key in the trie that's a prefix of the string, along with its value
+    pub fn longest_prefix_match<'a>(&self, s: &'a str) -> Option<(&'a str, &KValue)> {
+        let mut node = &self.root;
+        let mut result = node.value.as_ref().map(|value| (&s[..0], value));
+        for (i, c) in s.char_indices() {
+            match node.children.get(&c) {
+                Some(child) => {
+                    node = child;
+                    if let Some(value) = &node.value {
+                        result = Some((&s[..i + c.len_utf8()], value));
+                    }
+                }
+                None => break,
+            }
+        }
+        result
+    }
+
+    /// Calls the function with each entry that has a key starting with the prefix
+    ///
+    /// The entries are visited in the order of their sorted keys.
+    pub fn visit_entries_with_prefix(&self, prefix: &str, mut f: impl FnMut(&str, &KValue)) {
+        if let Some(node) = self.root.find(prefix) {
+            node.visit_entries(&mut prefix.to_string(), &mut f);
+        }
+    }
+
+    #[koto_method]
+    fn clear(ctx: MethodContext<Self>) -> Result<KValue> {
+        *ctx.instance_mut()? = Self::new();
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn contains(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Str(key)] => Ok(self.get(key).is_some().into()),
+            unexpected => type_error_with_slice("a String", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn entries(&self, args: &[KValue]) -> Result<KValue> {
+        let mut result = ValueVec::new();
+        self.visit_entries_with_prefix(prefix_arg(args)?, |key, value| {
+            result.push(KValue::Tuple(vec![key.into(), value.clone()].into()))
+        });
+        Ok(KList::with_data(result).into())
+    }
+
+    #[koto_method(alias = "get")]
+    fn get_koto(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Str(key)] => Ok(self.get(key).cloned().unwrap_or_default()),
+            [KValue::Str(key), default] => Ok(self.get(key).unwrap_or(default).clone()),
+            unexpected => {
+                type_error_with_slice("a String, and an optional default value", unexpected)
+            }
+        }
+    }
+
+    #[koto_method(alias = "insert")]
+    fn insert_koto(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [KValue::Str(key), value] => {
+                ctx.instance_mut()?.insert(key, value.clone());
+                ctx.instance_result()
+            }
+            unexpected => type_error_with_slice("a String and a value", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn keys(&self, args: &[KValue]) -> Result<KValue> {
+        let mut result = ValueVec::new();
+        self.visit_entries_with_prefix(prefix_arg(args)?, |key, _| result.push(key.into()));
+        Ok(KList::with_data(result).into())
+    }
+
+    #[koto_method(alias = "longest_prefix_match")]
+    fn longest_prefix_match_koto(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Str(s)] => Ok(match self.longest_prefix_match(s) {
+                Some((prefix, value)) => KValue::Tuple(vec![prefix.into(), value.clone()].into()),
+                None => KValue::Null,
+            }),
+            unexpected => type_error_with_slice("a String", unexpected),
+        }
+    }
+
+    #[koto_method(alias = "remove")]
+    fn remove_koto(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [KValue::Str(key)] => Ok(ctx.instance_mut()?.remove(key).unwrap_or_default()),
+            unexpected => type_error_with_slice("a String", unexpected),
+        }
+    }
+
+    #[koto_method(alias = "size")]
+    fn size_koto(&self) -> KValue {
+        self.len.into()
+    }
+}
+
+impl KotoObject for Trie {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!("Trie({})", self.len));
+        Ok(())
+    }
+}
+
+impl From<Trie> for KValue {
+    fn from(trie: Trie) -> Self {
+        KObject::from(trie).into()
+    }
+}
+
+fn prefix_arg(args: &[KValue]) -> Result<&str> {
+    match args {
+        [] => Ok(""),
+        [KValue::Str(prefix)] => Ok(prefix.as_str()),
+        unexpected => type_error_with_slice("an optional prefix String", unexpected),
+    }
+}
@@ -0,0 +1,209 @@
+//! A Koto language module for comparing text
+//!
+//! The diffing algorithm is shared with the core library's `assert_eq`, and is available in Rust
+//! via [koto_runtime::core_lib::test::diff].
+
+use koto_runtime::{
+    core_lib::test::diff::{self, ChangeKind, Hunk},
+    prelude::*,
+    Result,
+};
+use std::ops::Range;
+
+const DEFAULT_CONTEXT: usize = 3;
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("diff");
+
+    result.add_fn("format_unified", |ctx| {
+        let (old, new, options) = match ctx.args() {
+            [KValue::Str(old), KValue::Str(new)] => (old, new, None),
+            [KValue::Str(old), KValue::Str(new), KValue::Map(options)] => (old, new, Some(options)),
+            unexpected => {
+                return type_error_with_slice(
+                    "two Strings, and an optional Map of options",
+                    unexpected,
+                )
+            }
+        };
+
+        let mut context = DEFAULT_CONTEXT;
+        let mut old_name = KString::from("old");
+        let mut new_name = KString::from("new");
+        if let Some(options) = options {
+            for (key, value) in options.data().iter() {
+                match (key.to_string().as_str(), value) {
+                    ("context", KValue::Number(n)) if *n >= 0 => context = n.into(),
+                    ("old_name", KValue::Str(name)) => old_name = name.clone(),
+                    ("new_name", KValue::Str(name)) => new_name = name.clone(),
+                    ("context", unexpected) => {
+                        return type_error("a non-negative Number as 'context'", unexpected)
+                    }
+                    ("old_name", unexpected) => {
+                        return type_error("a String as 'old_name'", unexpected)
+                    }
+                    ("new_name", unexpected) => {
+                        return type_error("a String as 'new_name'", unexpected)
+                    }
+                    (unexpected, _) => return runtime_error!("Unexpected option '{unexpected}'"),
+                }
+            }
+        }
+
+        Ok(diff::format_unified(old, new, &old_name, &new_name, context).into())
+    });
+
+    result.add_fn("lines", |ctx| {
+        let (old, new, context) = diff_args(ctx.args())?;
+        let old_lines: Vec<&str> = old.lines().collect();
+        let new_lines: Vec<&str> = new.lines().collect();
+
+        let hunks = diff::hunks(&diff::diff(&old_lines, &new_lines), context)
+            .into_iter()
+            .map(|hunk| {
+                let changes = hunk
+                    .changes
+                    .iter()
+                    .map(|change| {
+                        let line = match change.kind {
+                            ChangeKind::Equal | ChangeKind::Delete => old_lines[change.old],
+                            ChangeKind::Insert => new_lines[change.new],
+                        };
+                        change_tuple(change.kind, line)
+                    })
+                    .collect();
+                hunk_map(hunk.old, hunk.new, changes)
+            })
+            .collect();
+
+        Ok(KList::with_data(hunks).into())
+    });
+
+    result.add_fn("words", |ctx| {
+        let (old, new, context) = diff_args(ctx.args())?;
+        let old_tokens = tokenize(old);
+        let new_tokens = tokenize(new);
+        let old_words: Vec<&str> = old_tokens.iter().map(|t| &old[t.clone()]).collect();
+        let new_words: Vec<&str> = new_tokens.iter().map(|t| &new[t.clone()]).collect();
+
+        let hunks = diff::hunks(&diff::diff(&old_words, &new_words), context)
+            .into_iter()
+            .map(|hunk| {
+                let changes = merge_word_changes(&hunk, &old_words, &new_words);
+                hunk_map(
+                    byte_range(&old_tokens, hunk.old, old.len()),
+                    byte_range(&new_tokens, hunk.new, new.len()),
+                    changes,
+                )
+            })
+            .collect();
+
+        Ok(KList::with_data(hunks).into())
+    });
+
+    result
+}
+
+fn diff_args(args: &[KValue]) -> Result<(&KString, &KString, usize)> {
+    match args {
+        [KValue::Str(old), KValue::Str(new)] => Ok((old, new, DEFAULT_CONTEXT)),
+        [KValue::Str(old), KValue::Str(new), KValue::Number(context)] if *context >= 0 => {
+            Ok((old, new, context.into()))
+        }
+        unexpected => type_error_with_slice(
+            "two Strings, and an optional non-negative Number of context lines",
+            unexpected,
+        ),
+    }
+}
+
+fn hunk_map(old: Range<usize>, new: Range<usize>, changes: ValueVec) -> KValue {
+    let result = KMap::with_capacity(3);
+    result.insert("old", to_krange(old));
+    result.insert("new", to_krange(new));
+    result.insert("changes", KList::with_data(changes));
+    result.into()
+}
+
+fn to_krange(range: Range<usize>) -> KRange {
+    KRange::from(range.start as i64..range.end as i64)
+}
+
+fn change_tuple(kind: ChangeKind, text: &str) -> KValue {
+    KValue::Tuple(vec![kind.as_str().into(), text.into()].into())
+}
+
+// Adjacent word changes of the same kind are joined together to make the result easier to read
+fn merge_word_changes(hunk: &Hunk, old_words: &[&str], new_words: &[&str]) -> ValueVec {
+    let mut result = ValueVec::new();
+    let mut current: Option<(ChangeKind, String)> = None;
+
+    for change in hunk.changes.iter() {
+        let word = match change.kind {
+            ChangeKind::Equal | ChangeKind::Delete => old_words[change.old],
+            ChangeKind::Insert => new_words[change.new],
+        };
+        match &mut current {
+            Some((kind, text)) if *kind == change.kind => text.push_str(word),
+            _ => {
+                if let Some((kind, text)) = current.take() {
+                    result.push(change_tuple(kind, &text));
+                }
+                current = Some((change.kind, word.to_string()));
+            }
+        }
+    }
+
+    if let Some((kind, text)) = current {
+        result.push(change_tuple(kind, &text));
+    }
+
+    result
+}
+
+// Splits a string into words, runs of whitespace, and individual punctuation characters,
+// returning the byte ranges of the tokens
+fn tokenize(s: &str) -> Vec<Range<usize>> {
+    #[derive(PartialEq)]
+    enum TokenKind {
+        Word,
+        Whitespace,
+        Other,
+    }
+
+    let kind = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            TokenKind::Word
+        } else if c.is_whitespace() {
+            TokenKind::Whitespace
+        } else {
+            TokenKind::Other
+        }
+    };
+
+    let mut result: Vec<Range<usize>> = Vec::new();
+    let mut previous_kind = None;
+    for (i, c) in s.char_indices() {
+        let c_kind = kind(c);
+        let end = i + c.len_utf8();
+        match result.last_mut() {
+            Some(last) if c_kind != TokenKind::Other && previous_kind.as_ref() == Some(&c_kind) => {
+                last.end = end
+            }
+            _ => result.push(i..end),
+        }
+        previous_kind = Some(c_kind);
+    }
+    result
+}
+
+// Converts a range of tokens into a range of bytes in the tokenized string
+fn byte_range(tokens: &[Range<usize>], range: Range<usize>, len: usize) -> Range<usize> {
+    let start = tokens.get(range.start).map_or(len, |token| token.start);
+    let end = if range.is_empty() {
+        start
+    } else {
+        tokens[range.end - 1].end
+    };
+    start..end
+}
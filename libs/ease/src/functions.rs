@@ -0,0 +1,205 @@
+//! Easing functions that map progress in the range 0..=1 to an eased value
+//!
+//! The functions follow the definitions used by [easings.net](https://easings.net).
+
+use std::f64::consts::PI;
+
+const BACK_C1: f64 = 1.70158;
+const BACK_C2: f64 = BACK_C1 * 1.525;
+const BACK_C3: f64 = BACK_C1 + 1.0;
+const ELASTIC_C4: f64 = (2.0 * PI) / 3.0;
+const ELASTIC_C5: f64 = (2.0 * PI) / 4.5;
+
+pub fn linear(t: f64) -> f64 {
+    t
+}
+
+pub fn in_sine(t: f64) -> f64 {
+    1.0 - (t * PI / 2.0).cos()
+}
+
+pub fn out_sine(t: f64) -> f64 {
+    (t * PI / 2.0).sin()
+}
+
+pub fn in_out_sine(t: f64) -> f64 {
+    -((PI * t).cos() - 1.0) / 2.0
+}
+
+pub fn in_quad(t: f64) -> f64 {
+    in_power(t, 2)
+}
+
+pub fn out_quad(t: f64) -> f64 {
+    out_power(t, 2)
+}
+
+pub fn in_out_quad(t: f64) -> f64 {
+    in_out_power(t, 2)
+}
+
+pub fn in_cubic(t: f64) -> f64 {
+    in_power(t, 3)
+}
+
+pub fn out_cubic(t: f64) -> f64 {
+    out_power(t, 3)
+}
+
+pub fn in_out_cubic(t: f64) -> f64 {
+    in_out_power(t, 3)
+}
+
+pub fn in_quart(t: f64) -> f64 {
+    in_power(t, 4)
+}
+
+pub fn out_quart(t: f64) -> f64 {
+    out_power(t, 4)
+}
+
+pub fn in_out_quart(t: f64) -> f64 {
+    in_out_power(t, 4)
+}
+
+pub fn in_quint(t: f64) -> f64 {
+    in_power(t, 5)
+}
+
+pub fn out_quint(t: f64) -> f64 {
+    out_power(t, 5)
+}
+
+pub fn in_out_quint(t: f64) -> f64 {
+    in_out_power(t, 5)
+}
+
+pub fn in_expo(t: f64) -> f64 {
+    if t == 0.0 {
+        0.0
+    } else {
+        2.0f64.powf(10.0 * t - 10.0)
+    }
+}
+
+pub fn out_expo(t: f64) -> f64 {
+    if t == 1.0 {
+        1.0
+    } else {
+        1.0 - 2.0f64.powf(-10.0 * t)
+    }
+}
+
+pub fn in_out_expo(t: f64) -> f64 {
+    if t == 0.0 || t == 1.0 {
+        t
+    } else if t < 0.5 {
+        2.0f64.powf(20.0 * t - 10.0) / 2.0
+    } else {
+        (2.0 - 2.0f64.powf(-20.0 * t + 10.0)) / 2.0
+    }
+}
+
+pub fn in_circ(t: f64) -> f64 {
+    1.0 - (1.0 - t * t).sqrt()
+}
+
+pub fn out_circ(t: f64) -> f64 {
+    (1.0 - (t - 1.0).powi(2)).sqrt()
+}
+
+pub fn in_out_circ(t: f64) -> f64 {
+    if t < 0.5 {
+        (1.0 - (1.0 - (2.0 * t).powi(2)).sqrt()) / 2.0
+    } else {
+        ((1.0 - (-2.0 * t + 2.0).powi(2)).sqrt() + 1.0) / 2.0
+    }
+}
+
+pub fn in_back(t: f64) -> f64 {
+    BACK_C3 * t * t * t - BACK_C1 * t * t
+}
+
+pub fn out_back(t: f64) -> f64 {
+    1.0 + BACK_C3 * (t - 1.0).powi(3) + BACK_C1 * (t - 1.0).powi(2)
+}
+
+pub fn in_out_back(t: f64) -> f64 {
+    if t < 0.5 {
+        ((2.0 * t).powi(2) * ((BACK_C2 + 1.0) * 2.0 * t - BACK_C2)) / 2.0
+    } else {
+        ((2.0 * t - 2.0).powi(2) * ((BACK_C2 + 1.0) * (t * 2.0 - 2.0) + BACK_C2) + 2.0) / 2.0
+    }
+}
+
+pub fn in_elastic(t: f64) -> f64 {
+    if t == 0.0 || t == 1.0 {
+        t
+    } else {
+        -(2.0f64.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * ELASTIC_C4).sin()
+    }
+}
+
+pub fn out_elastic(t: f64) -> f64 {
+    if t == 0.0 || t == 1.0 {
+        t
+    } else {
+        2.0f64.powf(-10.0 * t) * ((t * 10.0 - 0.75) * ELASTIC_C4).sin() + 1.0
+    }
+}
+
+pub fn in_out_elastic(t: f64) -> f64 {
+    if t == 0.0 || t == 1.0 {
+        t
+    } else if t < 0.5 {
+        -(2.0f64.powf(20.0 * t - 10.0) * ((20.0 * t - 11.125) * ELASTIC_C5).sin()) / 2.0
+    } else {
+        (2.0f64.powf(-20.0 * t + 10.0) * ((20.0 * t - 11.125) * ELASTIC_C5).sin()) / 2.0 + 1.0
+    }
+}
+
+pub fn in_bounce(t: f64) -> f64 {
+    1.0 - out_bounce(1.0 - t)
+}
+
+pub fn out_bounce(t: f64) -> f64 {
+    const N1: f64 = 7.5625;
+    const D1: f64 = 2.75;
+
+    if t < 1.0 / D1 {
+        N1 * t * t
+    } else if t < 2.0 / D1 {
+        let t = t - 1.5 / D1;
+        N1 * t * t + 0.75
+    } else if t < 2.5 / D1 {
+        let t = t - 2.25 / D1;
+        N1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D1;
+        N1 * t * t + 0.984375
+    }
+}
+
+pub fn in_out_bounce(t: f64) -> f64 {
+    if t < 0.5 {
+        (1.0 - out_bounce(1.0 - 2.0 * t)) / 2.0
+    } else {
+        (1.0 + out_bounce(2.0 * t - 1.0)) / 2.0
+    }
+}
+
+fn in_power(t: f64, n: i32) -> f64 {
+    t.powi(n)
+}
+
+fn out_power(t: f64, n: i32) -> f64 {
+    1.0 - (1.0 - t).powi(n)
+}
+
+fn in_out_power(t: f64, n: i32) -> f64 {
+    if t < 0.5 {
+        2.0f64.powi(n - 1) * t.powi(n)
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(n) / 2.0
+    }
+}
@@ -0,0 +1,58 @@
+//! A Koto language module containing easing functions for animation
+
+pub mod functions;
+
+use functions::*;
+use koto_runtime::prelude::*;
+
+/// A function that maps progress in the range 0..=1 to an eased value
+pub type EasingFunction = fn(f64) -> f64;
+
+/// The easing functions that are included in the module, along with their names
+pub const EASING_FUNCTIONS: &[(&str, EasingFunction)] = &[
+    ("linear", linear),
+    ("in_sine", in_sine),
+    ("out_sine", out_sine),
+    ("in_out_sine", in_out_sine),
+    ("in_quad", in_quad),
+    ("out_quad", out_quad),
+    ("in_out_quad", in_out_quad),
+    ("in_cubic", in_cubic),
+    ("out_cubic", out_cubic),
+    ("in_out_cubic", in_out_cubic),
+    ("in_quart", in_quart),
+    ("out_quart", out_quart),
+    ("in_out_quart", in_out_quart),
+    ("in_quint", in_quint),
+    ("out_quint", out_quint),
+    ("in_out_quint", in_out_quint),
+    ("in_expo", in_expo),
+    ("out_expo", out_expo),
+    ("in_out_expo", in_out_expo),
+    ("in_circ", in_circ),
+    ("out_circ", out_circ),
+    ("in_out_circ", in_out_circ),
+    ("in_back", in_back),
+    ("out_back", out_back),
+    ("in_out_back", in_out_back),
+    ("in_elastic", in_elastic),
+    ("out_elastic", out_elastic),
+    ("in_out_elastic", in_out_elastic),
+    ("in_bounce", in_bounce),
+    ("out_bounce", out_bounce),
+    ("in_out_bounce", in_out_bounce),
+];
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("ease");
+
+    for (name, f) in EASING_FUNCTIONS {
+        result.add_fn(name, |ctx| match ctx.args() {
+            // Progress values outside of the 0..=1 range are clamped
+            [KValue::Number(t)] => Ok(f(f64::from(t).clamp(0.0, 1.0)).into()),
+            unexpected => type_error_with_slice("a Number", unexpected),
+        });
+    }
+
+    result
+}
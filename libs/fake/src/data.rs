@@ -0,0 +1,143 @@
+// Source data for the generators
+
+pub const FIRST_NAMES: &[&str] = &[
+    "Ada", "Alan", "Alice", "Amara", "Ana", "Ben", "Carlos", "Chen", "Clara", "David", "Elena",
+    "Emma", "Farah", "Felix", "Grace", "Hana", "Ines", "Isaac", "Jack", "Jun", "Kai", "Laila",
+    "Leo", "Lina", "Marco", "Maya", "Mei", "Nia", "Noah", "Olga", "Omar", "Priya", "Ravi", "Rosa",
+    "Sam", "Sofia", "Tariq", "Theo", "Uma", "Yara", "Yusuf", "Zoe",
+];
+
+pub const LAST_NAMES: &[&str] = &[
+    "Adams", "Alvarez", "Bauer", "Brown", "Chen", "Costa", "Dubois", "Evans", "Fischer", "Garcia",
+    "Gupta", "Hansen", "Hughes", "Ibrahim", "Ito", "Jensen", "Kim", "Kowalski", "Lopez", "Martin",
+    "Meyer", "Moreau", "Nakamura", "Novak", "Okafor", "Olsen", "Patel", "Rossi", "Santos",
+    "Schmidt", "Silva", "Smith", "Tanaka", "Taylor", "Wang", "Wilson", "Yilmaz", "Zhang",
+];
+
+pub const EMAIL_DOMAINS: &[&str] = &["example.com", "example.net", "example.org"];
+
+pub const STREET_NAMES: &[&str] = &[
+    "Acacia", "Ash", "Birch", "Cedar", "Chestnut", "Elm", "Hawthorn", "Highland", "Juniper",
+    "Lake", "Maple", "Meadow", "Mill", "Oak", "Orchard", "Park", "Pine", "River", "Spruce",
+    "Station", "Sunset", "Willow",
+];
+
+pub const STREET_SUFFIXES: &[&str] = &[
+    "Avenue", "Close", "Court", "Drive", "Lane", "Road", "Street", "Way",
+];
+
+pub const CITIES: &[&str] = &[
+    "Ashford",
+    "Brookfield",
+    "Clearwater",
+    "Eastwick",
+    "Fairview",
+    "Glenwood",
+    "Greenville",
+    "Harborview",
+    "Kingsbridge",
+    "Lakeside",
+    "Maplewood",
+    "Millbrook",
+    "Northgate",
+    "Oakridge",
+    "Riverside",
+    "Springfield",
+    "Stonehaven",
+    "Westfield",
+    "Willowdale",
+    "Woodbury",
+];
+
+pub const COUNTRIES: &[&str] = &[
+    "Argentina",
+    "Australia",
+    "Brazil",
+    "Canada",
+    "Chile",
+    "Denmark",
+    "Egypt",
+    "France",
+    "Germany",
+    "India",
+    "Italy",
+    "Japan",
+    "Kenya",
+    "Mexico",
+    "Netherlands",
+    "New Zealand",
+    "Nigeria",
+    "Norway",
+    "Portugal",
+    "South Korea",
+    "Spain",
+    "Sweden",
+    "Turkey",
+    "Vietnam",
+];
+
+pub const LOREM_WORDS: &[&str] = &[
+    "lorem",
+    "ipsum",
+    "dolor",
+    "sit",
+    "amet",
+    "consectetur",
+    "adipiscing",
+    "elit",
+    "sed",
+    "do",
+    "eiusmod",
+    "tempor",
+    "incididunt",
+    "ut",
+    "labore",
+    "et",
+    "dolore",
+    "magna",
+    "aliqua",
+    "enim",
+    "ad",
+    "minim",
+    "veniam",
+    "quis",
+    "nostrud",
+    "exercitation",
+    "ullamco",
+    "laboris",
+    "nisi",
+    "aliquip",
+    "ex",
+    "ea",
+    "commodo",
+    "consequat",
+    "duis",
+    "aute",
+    "irure",
+    "in",
+    "reprehenderit",
+    "voluptate",
+    "velit",
+    "esse",
+    "cillum",
+    "eu",
+    "fugiat",
+    "nulla",
+    "pariatur",
+    "excepteur",
+    "sint",
+    "occaecat",
+    "cupidatat",
+    "non",
+    "proident",
+    "sunt",
+    "culpa",
+    "qui",
+    "officia",
+    "deserunt",
+    "mollit",
+    "anim",
+    "id",
+    "est",
+    "laborum",
+];
@@ -0,0 +1,189 @@
+use crate::data::*;
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// A generator of fake data
+///
+/// Generators created with the same seed produce the same sequence of values.
+pub struct Generator {
+    rng: ChaCha8Rng,
+}
+
+impl Generator {
+    /// Makes a generator that's seeded from entropy
+    pub fn from_entropy() -> Self {
+        Self {
+            rng: ChaCha8Rng::from_entropy(),
+        }
+    }
+
+    /// Makes a generator with the given seed
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// Reseeds the generator
+    pub fn seed(&mut self, seed: u64) {
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items.choose(&mut self.rng).unwrap()
+    }
+
+    /// Returns `true` with the given probability
+    pub fn bool(&mut self, probability: f64) -> bool {
+        self.rng.gen_bool(probability.clamp(0.0, 1.0))
+    }
+
+    /// Returns a random index for a container of the given size
+    pub fn index(&mut self, size: usize) -> usize {
+        self.rng.gen_range(0..size)
+    }
+
+    /// Returns an integer in the inclusive range `min..=max`
+    pub fn int(&mut self, min: i64, max: i64) -> i64 {
+        self.rng.gen_range(min..=max)
+    }
+
+    /// Returns a number in the range `min..max`
+    pub fn number(&mut self, min: f64, max: f64) -> f64 {
+        min + self.rng.gen::<f64>() * (max - min)
+    }
+
+    /// Returns a number from a normal distribution
+    pub fn normal(&mut self, mean: f64, std_dev: f64) -> f64 {
+        // Box-Muller transform, 1 - gen() avoids taking the log of 0
+        let u1 = 1.0 - self.rng.gen::<f64>();
+        let u2 = self.rng.gen::<f64>();
+        let z = (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+        mean + z * std_dev
+    }
+
+    /// Returns a number from an exponential distribution with the given rate
+    pub fn exponential(&mut self, rate: f64) -> f64 {
+        -(1.0 - self.rng.gen::<f64>()).ln() / rate
+    }
+
+    /// Returns a first name
+    pub fn first_name(&mut self) -> String {
+        self.pick(FIRST_NAMES).to_string()
+    }
+
+    /// Returns a last name
+    pub fn last_name(&mut self) -> String {
+        self.pick(LAST_NAMES).to_string()
+    }
+
+    /// Returns a full name
+    pub fn name(&mut self) -> String {
+        format!("{} {}", self.pick(FIRST_NAMES), self.pick(LAST_NAMES))
+    }
+
+    /// Returns a username
+    pub fn username(&mut self) -> String {
+        let first = self.pick(FIRST_NAMES).to_lowercase();
+        let last = self.pick(LAST_NAMES).to_lowercase();
+        match self.rng.gen_range(0..3) {
+            0 => format!("{first}{last}"),
+            1 => format!("{first}_{}", self.rng.gen_range(1..100)),
+            _ => format!("{}{last}", &first[..1]),
+        }
+    }
+
+    /// Returns an email address
+    ///
+    /// The addresses use domains that are reserved for examples, e.g. `example.com`.
+    pub fn email(&mut self) -> String {
+        let first = self.pick(FIRST_NAMES).to_lowercase();
+        let last = self.pick(LAST_NAMES).to_lowercase();
+        let domain = self.pick(EMAIL_DOMAINS);
+        if self.rng.gen_bool(0.5) {
+            format!("{first}.{last}@{domain}")
+        } else {
+            format!("{first}{}@{domain}", self.rng.gen_range(1..1000))
+        }
+    }
+
+    /// Returns a street address, e.g. `42 Maple Street`
+    pub fn street(&mut self) -> String {
+        format!(
+            "{} {} {}",
+            self.rng.gen_range(1..1000),
+            self.pick(STREET_NAMES),
+            self.pick(STREET_SUFFIXES)
+        )
+    }
+
+    /// Returns a city name
+    pub fn city(&mut self) -> String {
+        self.pick(CITIES).to_string()
+    }
+
+    /// Returns a country name
+    pub fn country(&mut self) -> String {
+        self.pick(COUNTRIES).to_string()
+    }
+
+    /// Returns a five digit postcode
+    pub fn postcode(&mut self) -> String {
+        format!("{:05}", self.rng.gen_range(1000..100000))
+    }
+
+    /// Returns a date and time in the range `from..to`
+    pub fn date(
+        &mut self,
+        from: DateTime<FixedOffset>,
+        to: DateTime<FixedOffset>,
+    ) -> DateTime<FixedOffset> {
+        let start = from.timestamp();
+        let end = to.timestamp().max(start + 1);
+        let timestamp = self.rng.gen_range(start..end);
+        from.timezone()
+            .timestamp_opt(timestamp, 0)
+            .single()
+            .unwrap_or(from)
+    }
+
+    /// Returns the default range used by [Self::date], from 2000 to 2030
+    pub fn default_date_range() -> (DateTime<FixedOffset>, DateTime<FixedOffset>) {
+        let from = Utc.with_ymd_and_hms(2000, 1, 1, 0, 0, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        (from.fixed_offset(), to.fixed_offset())
+    }
+
+    /// Returns a lorem ipsum word
+    pub fn word(&mut self) -> String {
+        self.pick(LOREM_WORDS).to_string()
+    }
+
+    /// Returns the given number of lorem ipsum words, separated by spaces
+    pub fn words(&mut self, count: usize) -> String {
+        (0..count)
+            .map(|_| self.pick(LOREM_WORDS))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Returns a lorem ipsum sentence with the given number of words
+    pub fn sentence(&mut self, word_count: usize) -> String {
+        let words = self.words(word_count.max(1));
+        let mut chars = words.chars();
+        let first = chars.next().unwrap().to_uppercase();
+        format!("{first}{}.", chars.as_str())
+    }
+
+    /// Returns a lorem ipsum paragraph with the given number of sentences
+    pub fn paragraph(&mut self, sentence_count: usize) -> String {
+        (0..sentence_count.max(1))
+            .map(|_| {
+                let word_count = self.rng.gen_range(4..12);
+                self.sentence(word_count)
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
@@ -0,0 +1,189 @@
+//! A Koto language module for generating fake data
+//!
+//! The module provides seeded generators for names, addresses, dates, lorem ipsum text, and
+//! numbers, along with `fake.from_schema`, which generates values that match a validator from
+//! the `validate` module.
+
+mod data;
+mod generator;
+mod schema;
+
+pub use crate::{generator::Generator, schema::generate_from_schema};
+
+use koto_runtime::{core_lib::os::DateTime, prelude::*, PtrMut, Result};
+use koto_validate::Validator;
+
+pub fn make_module() -> KMap {
+    let result = make_generator_module(Generator::from_entropy());
+
+    result.add_fn("generator", |ctx| {
+        let generator = match ctx.args() {
+            [] => Generator::from_entropy(),
+            [KValue::Number(seed)] => Generator::with_seed(seed.to_bits()),
+            unexpected => return type_error_with_slice("an optional seed Number", unexpected),
+        };
+        Ok(make_generator_module(generator).into())
+    });
+
+    result
+}
+
+// Makes a map containing the generator functions, sharing the provided generator
+fn make_generator_module(generator: Generator) -> KMap {
+    let result = KMap::with_type("fake");
+    let generator = PtrMut::from(generator);
+
+    macro_rules! add_fn {
+        ($name:expr, |$generator:ident, $ctx:ident| $body:expr) => {{
+            let generator = generator.clone();
+            result.add_fn($name, move |$ctx| {
+                let $generator = &generator;
+                $body
+            });
+        }};
+    }
+
+    macro_rules! add_string_fn {
+        ($name:ident) => {
+            add_fn!(stringify!($name), |generator, ctx| match ctx.args() {
+                [] => Ok(generator.borrow_mut().$name().into()),
+                unexpected => type_error_with_slice("no arguments", unexpected),
+            });
+        };
+    }
+
+    add_fn!("address", |generator, ctx| match ctx.args() {
+        [] => {
+            let mut generator = generator.borrow_mut();
+            let address = KMap::with_capacity(4);
+            address.insert("street", generator.street());
+            address.insert("city", generator.city());
+            address.insert("postcode", generator.postcode());
+            address.insert("country", generator.country());
+            Ok(address.into())
+        }
+        unexpected => type_error_with_slice("no arguments", unexpected),
+    });
+
+    add_string_fn!(city);
+    add_string_fn!(country);
+
+    add_fn!("date", |generator, ctx| {
+        let (from, to) = match ctx.args() {
+            [] => Generator::default_date_range(),
+            [KValue::Object(from), KValue::Object(to)]
+                if from.is_a::<DateTime>() && to.is_a::<DateTime>() =>
+            {
+                let from = from.cast::<DateTime>()?.inner();
+                let to = to.cast::<DateTime>()?.inner();
+                if to <= from {
+                    return runtime_error!("the end of the range must be after the start");
+                }
+                (from, to)
+            }
+            unexpected => {
+                return type_error_with_slice("an optional range of two DateTimes", unexpected)
+            }
+        };
+        let date = generator.borrow_mut().date(from, to);
+        Ok(KObject::from(DateTime::from_chrono(date)).into())
+    });
+
+    add_string_fn!(email);
+
+    add_fn!("exponential", |generator, ctx| {
+        let rate = match ctx.args() {
+            [] => 1.0,
+            [KValue::Number(rate)] if *rate > 0.0 => rate.into(),
+            unexpected => return type_error_with_slice("an optional positive rate", unexpected),
+        };
+        Ok(generator.borrow_mut().exponential(rate).into())
+    });
+
+    add_string_fn!(first_name);
+
+    add_fn!("from_schema", |generator, ctx| match ctx.args() {
+        [KValue::Object(o)] if o.is_a::<Validator>() => {
+            let validator = o.cast::<Validator>()?.clone();
+            generate_from_schema(generator, &validator, ctx.vm)
+        }
+        unexpected => type_error_with_slice("a Validator", unexpected),
+    });
+
+    add_fn!("int", |generator, ctx| match ctx.args() {
+        [KValue::Number(min), KValue::Number(max)] => {
+            let (min, max) = (i64::from(min), i64::from(max));
+            if max < min {
+                return runtime_error!("the maximum must be greater than or equal to the minimum");
+            }
+            Ok(generator.borrow_mut().int(min, max).into())
+        }
+        unexpected => type_error_with_slice("minimum and maximum Numbers", unexpected),
+    });
+
+    add_string_fn!(last_name);
+    add_string_fn!(name);
+
+    add_fn!("normal", |generator, ctx| {
+        let (mean, std_dev) = match ctx.args() {
+            [] => (0.0, 1.0),
+            [KValue::Number(mean), KValue::Number(std_dev)] if *std_dev >= 0.0 => {
+                (mean.into(), std_dev.into())
+            }
+            unexpected => {
+                return type_error_with_slice("an optional mean and standard deviation", unexpected)
+            }
+        };
+        Ok(generator.borrow_mut().normal(mean, std_dev).into())
+    });
+
+    add_fn!("number", |generator, ctx| {
+        let (min, max) = match ctx.args() {
+            [] => (0.0, 1.0),
+            [KValue::Number(min), KValue::Number(max)] if max >= min => (min.into(), max.into()),
+            unexpected => {
+                return type_error_with_slice("an optional minimum and maximum", unexpected)
+            }
+        };
+        Ok(generator.borrow_mut().number(min, max).into())
+    });
+
+    add_fn!("paragraph", |generator, ctx| {
+        let sentences = optional_count(ctx.args(), 4)?;
+        Ok(generator.borrow_mut().paragraph(sentences).into())
+    });
+
+    add_string_fn!(postcode);
+
+    add_fn!("seed", |generator, ctx| match ctx.args() {
+        [KValue::Number(seed)] => {
+            generator.borrow_mut().seed(seed.to_bits());
+            Ok(KValue::Null)
+        }
+        unexpected => type_error_with_slice("a seed Number", unexpected),
+    });
+
+    add_fn!("sentence", |generator, ctx| {
+        let words = optional_count(ctx.args(), 8)?;
+        Ok(generator.borrow_mut().sentence(words).into())
+    });
+
+    add_string_fn!(street);
+    add_string_fn!(username);
+    add_string_fn!(word);
+
+    add_fn!("words", |generator, ctx| {
+        let words = optional_count(ctx.args(), 3)?;
+        Ok(generator.borrow_mut().words(words).into())
+    });
+
+    result
+}
+
+fn optional_count(args: &[KValue], default: usize) -> Result<usize> {
+    match args {
+        [] => Ok(default),
+        [KValue::Number(n)] if *n > 0 => Ok(n.into()),
+        unexpected => type_error_with_slice("an optional positive count", unexpected),
+    }
+}
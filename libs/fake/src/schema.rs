@@ -0,0 +1,173 @@
+use crate::Generator;
+use koto_runtime::{prelude::*, PtrMut, Result};
+use koto_validate::{Constraint, Validator, ValidatorKind};
+
+// The number of attempts that are made to generate a value that passes validation
+const MAX_ATTEMPTS: usize = 100;
+
+/// Generates a random value that's accepted by the validator
+///
+/// Values are generated from the validator's type and constraints, and then validated. Custom
+/// checks and patterns can't be taken into account during generation, so values are regenerated
+/// until one passes validation, with an error returned if no valid value could be found.
+pub fn generate_from_schema(
+    generator: &PtrMut<Generator>,
+    validator: &Validator,
+    vm: &mut KotoVm,
+) -> Result<KValue> {
+    generate_value(generator, validator, None, vm)
+}
+
+fn generate_value(
+    generator: &PtrMut<Generator>,
+    validator: &Validator,
+    name: Option<&str>,
+    vm: &mut KotoVm,
+) -> Result<KValue> {
+    for _ in 0..MAX_ATTEMPTS {
+        let candidate = generate_candidate(generator, validator, name, vm)?;
+        // The generator mustn't be borrowed during validation, custom checks could use it
+        if validator.violations(&candidate, vm)?.is_empty() {
+            return Ok(candidate);
+        }
+    }
+
+    match name {
+        Some(name) => runtime_error!("unable to generate a valid value for '{name}'"),
+        None => runtime_error!("unable to generate a valid value"),
+    }
+}
+
+fn generate_candidate(
+    generator: &PtrMut<Generator>,
+    validator: &Validator,
+    name: Option<&str>,
+    vm: &mut KotoVm,
+) -> Result<KValue> {
+    let limits = Limits::from_constraints(validator.constraints());
+
+    if let Some(values) = &limits.one_of {
+        if !values.is_empty() {
+            let index = generator.borrow_mut().index(values.len());
+            return Ok(values[index].clone());
+        }
+    }
+
+    let result = match validator.kind() {
+        ValidatorKind::Any => generator.borrow_mut().word().into(),
+        ValidatorKind::Bool => generator.borrow_mut().bool(0.5).into(),
+        ValidatorKind::Int => {
+            let (min, max) = limits.number_range();
+            let (min, max) = (min.ceil() as i64, max.floor() as i64);
+            generator.borrow_mut().int(min, max.max(min)).into()
+        }
+        ValidatorKind::Number => {
+            let (min, max) = limits.number_range();
+            generator.borrow_mut().number(min, max).into()
+        }
+        ValidatorKind::String => {
+            let (min_len, max_len) = limits.len_range(1, 20);
+            let mut generator = generator.borrow_mut();
+            let mut result = match name.map(|name| name.to_lowercase()) {
+                Some(name) if name.contains("email") => generator.email(),
+                Some(name) if name.contains("first_name") => generator.first_name(),
+                Some(name) if name.contains("last_name") => generator.last_name(),
+                Some(name) if name.contains("username") => generator.username(),
+                Some(name) if name.contains("name") => generator.name(),
+                Some(name) if name.contains("city") => generator.city(),
+                Some(name) if name.contains("country") => generator.country(),
+                Some(name) if name.contains("street") || name.contains("address") => {
+                    generator.street()
+                }
+                Some(name) if name.contains("postcode") || name.contains("zip") => {
+                    generator.postcode()
+                }
+                _ => generator.word(),
+            };
+            while result.chars().count() < min_len {
+                result.push(' ');
+                result.push_str(&generator.word());
+            }
+            if result.chars().count() > max_len {
+                result = result.chars().take(max_len).collect();
+            }
+            result.into()
+        }
+        ValidatorKind::List(item_validator) => {
+            let (min_len, max_len) = limits.len_range(1, 3);
+            let len = generator.borrow_mut().int(min_len as i64, max_len as i64) as usize;
+            let mut items = ValueVec::with_capacity(len);
+            for _ in 0..len {
+                let item = match item_validator {
+                    Some(item_validator) => generate_value(generator, item_validator, None, vm)?,
+                    None => generator.borrow_mut().word().into(),
+                };
+                items.push(item);
+            }
+            KList::with_data(items).into()
+        }
+        ValidatorKind::Map(schema) => {
+            let result = KMap::with_capacity(schema.len());
+            for (key, field_validator) in schema.iter() {
+                let value = generate_value(generator, field_validator, Some(key), vm)?;
+                result.insert(key.clone(), value);
+            }
+            result.into()
+        }
+    };
+
+    Ok(result)
+}
+
+// The limits that are taken into account while generating values
+#[derive(Default)]
+struct Limits {
+    min: Option<f64>,
+    max: Option<f64>,
+    min_len: Option<usize>,
+    max_len: Option<usize>,
+    one_of: Option<Vec<KValue>>,
+}
+
+impl Limits {
+    fn from_constraints(constraints: &[Constraint]) -> Self {
+        let mut result = Self::default();
+
+        for constraint in constraints {
+            match constraint {
+                Constraint::Min(n) => {
+                    result.min = Some(result.min.unwrap_or(f64::MIN).max(n.into()))
+                }
+                Constraint::Max(n) => {
+                    result.max = Some(result.max.unwrap_or(f64::MAX).min(n.into()))
+                }
+                Constraint::MinLen(n) => result.min_len = Some(result.min_len.unwrap_or(0).max(*n)),
+                Constraint::MaxLen(n) => {
+                    result.max_len = Some(result.max_len.unwrap_or(usize::MAX).min(*n))
+                }
+                Constraint::OneOf(values) => result.one_of = Some(values.to_vec()),
+                _ => {}
+            }
+        }
+
+        result
+    }
+
+    // Numbers default to the range 0..100, shifted to fit any provided limits
+    fn number_range(&self) -> (f64, f64) {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => (min, max.max(min)),
+            (Some(min), None) => (min, min + 100.0),
+            (None, Some(max)) => (if max >= 0.0 { 0.0 } else { max - 100.0 }, max),
+            (None, None) => (0.0, 100.0),
+        }
+    }
+
+    fn len_range(&self, default_min: usize, default_max: usize) -> (usize, usize) {
+        let min = self
+            .min_len
+            .unwrap_or(default_min.min(self.max_len.unwrap_or(usize::MAX)));
+        let max = self.max_len.unwrap_or(default_max.max(min));
+        (min, max.max(min))
+    }
+}
@@ -0,0 +1,14 @@
+use koto_runtime::{prelude::*, Result};
+use koto_test_utils::run_koto_examples_in_markdown;
+
+#[test]
+fn fake_docs() -> Result<()> {
+    let mut prelude_entries = ValueMap::default();
+    prelude_entries.insert("fake".into(), koto_fake::make_module().into());
+    prelude_entries.insert("validate".into(), koto_validate::make_module().into());
+    let markdown = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../docs/libs/fake.md"
+    ));
+    run_koto_examples_in_markdown(markdown, prelude_entries)
+}
@@ -0,0 +1,227 @@
+use koto_runtime::{prelude::*, Result};
+use std::fmt::Write;
+
+/// A state machine's states and transitions, parsed from a Koto map
+pub struct Definition {
+    pub initial: usize,
+    pub states: Vec<State>,
+    pub transitions: Vec<Transition>,
+}
+
+/// A state, with optional callbacks that are called when the state is entered or exited
+pub struct State {
+    pub name: KString,
+    pub on_enter: Option<KValue>,
+    pub on_exit: Option<KValue>,
+}
+
+/// A transition between states that's triggered by an event
+pub struct Transition {
+    /// The states that the transition can be taken from, or `None` if it applies to every state
+    pub source: Option<Vec<usize>>,
+    pub event: KString,
+    pub target: usize,
+    pub guard: Option<KValue>,
+}
+
+impl Definition {
+    /// Parses a definition from a map containing `initial`, `states`, and `transitions` entries
+    ///
+    /// The `states` entry is optional, with states that are only referred to by transitions being
+    /// added without callbacks.
+    pub fn from_map(map: &KMap) -> Result<Self> {
+        let mut result = Self {
+            initial: 0,
+            states: Vec::new(),
+            transitions: Vec::new(),
+        };
+
+        match map.get("states") {
+            Some(KValue::Map(states)) => {
+                for (name, state) in states.data().iter() {
+                    let KValue::Str(name) = name.value() else {
+                        return runtime_error!("Expected a String as state name, found '{name}'");
+                    };
+                    let (on_enter, on_exit) = match state {
+                        KValue::Map(callbacks) => (
+                            callback(callbacks, "on_enter")?,
+                            callback(callbacks, "on_exit")?,
+                        ),
+                        KValue::Null => (None, None),
+                        unexpected => return type_error("a Map of state callbacks", unexpected),
+                    };
+                    result.states.push(State {
+                        name: name.clone(),
+                        on_enter,
+                        on_exit,
+                    });
+                }
+            }
+            Some(unexpected) => return type_error("a Map of states", &unexpected),
+            None => {}
+        }
+
+        result.initial = match map.get("initial") {
+            Some(KValue::Str(initial)) => result.state_index(&initial),
+            Some(unexpected) => return type_error("a String as initial state", &unexpected),
+            None => return runtime_error!("Missing 'initial' state"),
+        };
+
+        match map.get("transitions") {
+            Some(KValue::List(transitions)) => {
+                for transition in transitions.data().iter() {
+                    let KValue::Map(transition) = transition else {
+                        return type_error("a Map as transition", transition);
+                    };
+                    let transition = result.parse_transition(transition)?;
+                    result.transitions.push(transition);
+                }
+            }
+            Some(KValue::Tuple(transitions)) => {
+                for transition in transitions.iter() {
+                    let KValue::Map(transition) = transition else {
+                        return type_error("a Map as transition", transition);
+                    };
+                    let transition = result.parse_transition(transition)?;
+                    result.transitions.push(transition);
+                }
+            }
+            Some(unexpected) => return type_error("a List of transitions", &unexpected),
+            None => {}
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the name of the state at the given index
+    pub fn state_name(&self, index: usize) -> &KString {
+        &self.states[index].name
+    }
+
+    /// Returns the transitions for the event that can be taken from the state, in declaration order
+    ///
+    /// Guards aren't checked here, they need to be called by the machine.
+    pub fn candidates<'a>(
+        &'a self,
+        state: usize,
+        event: &'a str,
+    ) -> impl Iterator<Item = &'a Transition> + 'a {
+        self.transitions.iter().filter(move |transition| {
+            transition.event.as_str() == event
+                && transition
+                    .source
+                    .as_ref()
+                    .is_none_or(|from| from.contains(&state))
+        })
+    }
+
+    /// Renders the state machine as a graph in the DOT language
+    pub fn to_dot(&self, current: usize) -> String {
+        let mut result = String::from("digraph {\n  rankdir=LR;\n  __start [shape=point];\n");
+
+        for (i, state) in self.states.iter().enumerate() {
+            let style = if i == current { ", style=bold" } else { "" };
+            writeln!(result, "  {} [shape=ellipse{style}];", quoted(&state.name)).ok();
+        }
+
+        writeln!(
+            result,
+            "  __start -> {};",
+            quoted(self.state_name(self.initial))
+        )
+        .ok();
+
+        for transition in self.transitions.iter() {
+            let from: Vec<usize> = match &transition.source {
+                Some(from) => from.clone(),
+                None => (0..self.states.len()).collect(),
+            };
+            let label = if transition.guard.is_some() {
+                format!("{} [guarded]", transition.event)
+            } else {
+                transition.event.to_string()
+            };
+            for state in from {
+                writeln!(
+                    result,
+                    "  {} -> {} [label={}];",
+                    quoted(self.state_name(state)),
+                    quoted(self.state_name(transition.target)),
+                    quoted(&label)
+                )
+                .ok();
+            }
+        }
+
+        result.push('}');
+        result
+    }
+
+    fn parse_transition(&mut self, transition: &KMap) -> Result<Transition> {
+        let source = match transition.get("source") {
+            Some(KValue::Str(from)) if from.as_str() == "*" => None,
+            Some(KValue::Str(from)) => Some(vec![self.state_index(&from)]),
+            Some(KValue::List(from)) => Some(self.state_indices(from.data().iter())?),
+            Some(KValue::Tuple(from)) => Some(self.state_indices(from.iter())?),
+            Some(unexpected) => {
+                return type_error("a state name, a List of state names, or '*'", &unexpected)
+            }
+            None => return runtime_error!("Missing 'source' in transition"),
+        };
+
+        let event = match transition.get("event") {
+            Some(KValue::Str(event)) => event,
+            Some(unexpected) => return type_error("a String as event", &unexpected),
+            None => return runtime_error!("Missing 'event' in transition"),
+        };
+
+        let target = match transition.get("target") {
+            Some(KValue::Str(to)) => self.state_index(&to),
+            Some(unexpected) => return type_error("a String as target state", &unexpected),
+            None => return runtime_error!("Missing 'target' in transition"),
+        };
+
+        Ok(Transition {
+            source,
+            event,
+            target,
+            guard: callback(transition, "guard")?,
+        })
+    }
+
+    fn state_indices<'a>(&mut self, names: impl Iterator<Item = &'a KValue>) -> Result<Vec<usize>> {
+        names
+            .map(|name| match name {
+                KValue::Str(name) => Ok(self.state_index(name)),
+                unexpected => type_error("a String as state name", unexpected),
+            })
+            .collect()
+    }
+
+    // Returns the index of the state with the given name, adding the state if necessary
+    fn state_index(&mut self, name: &KString) -> usize {
+        match self.states.iter().position(|state| state.name == *name) {
+            Some(index) => index,
+            None => {
+                self.states.push(State {
+                    name: name.clone(),
+                    on_enter: None,
+                    on_exit: None,
+                });
+                self.states.len() - 1
+            }
+        }
+    }
+}
+
+fn callback(map: &KMap, key: &str) -> Result<Option<KValue>> {
+    match map.get(key) {
+        Some(f) if f.is_callable() => Ok(Some(f)),
+        Some(KValue::Null) | None => Ok(None),
+        Some(unexpected) => type_error(&format!("a Function as '{key}'"), &unexpected),
+    }
+}
+
+fn quoted(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,19 @@
+//! A Koto language module for defining state machines
+
+mod definition;
+mod machine;
+
+pub use machine::Machine;
+
+use koto_runtime::prelude::*;
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("fsm");
+
+    result.add_fn("machine", |ctx| match ctx.args() {
+        [KValue::Map(definition)] => Ok(Machine::from_map(definition)?.into()),
+        unexpected => type_error_with_slice("a definition Map", unexpected),
+    });
+
+    result
+}
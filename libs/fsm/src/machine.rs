@@ -0,0 +1,193 @@
+use crate::definition::Definition;
+use koto_runtime::{derive::*, prelude::*, Ptr, PtrMut, Result};
+
+/// A state machine, created from a [Definition]
+///
+/// The definition is shared between copies of the machine, while each copy has its own current
+/// state.
+#[derive(Clone, KotoType)]
+#[koto(type_name = "Machine")]
+pub struct Machine {
+    definition: Ptr<Definition>,
+    current: PtrMut<usize>,
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Machine {
+    /// Makes a new machine from a definition map, starting in the definition's initial state
+    pub fn from_map(map: &KMap) -> Result<Self> {
+        let definition = Definition::from_map(map)?;
+        let initial = definition.initial;
+        Ok(Self {
+            definition: definition.into(),
+            current: initial.into(),
+        })
+    }
+
+    /// Returns the name of the current state
+    pub fn current_state(&self) -> KString {
+        self.definition.state_name(*self.current.borrow()).clone()
+    }
+
+    /// Sends an event to the machine, returning true if a transition was taken
+    ///
+    /// Transitions are checked in the order that they were declared, and the first transition
+    /// with a passing guard is taken. The current state's `on_exit` callback is called before the
+    /// state changes, and then the new state's `on_enter` callback is called.
+    pub fn send_event(&self, vm: &mut KotoVm, event: &str, data: &KValue) -> Result<bool> {
+        let from = *self.current.borrow();
+        let Some((to, info)) = self.find_transition(vm, from, event, data)? else {
+            return Ok(false);
+        };
+
+        // The current state isn't borrowed while the callbacks are running,
+        // allowing them to make use of the machine.
+        if let Some(on_exit) = self.definition.states[from].on_exit.clone() {
+            vm.call_function(on_exit, info.clone())?;
+        }
+        *self.current.borrow_mut() = to;
+        if let Some(on_enter) = self.definition.states[to].on_enter.clone() {
+            vm.call_function(on_enter, info)?;
+        }
+
+        Ok(true)
+    }
+
+    // Finds the transition that would be taken for the event,
+    // returning the target state and the info map that gets passed to callbacks.
+    fn find_transition(
+        &self,
+        vm: &mut KotoVm,
+        from: usize,
+        event: &str,
+        data: &KValue,
+    ) -> Result<Option<(usize, KValue)>> {
+        for transition in self.definition.candidates(from, event) {
+            let info = self.transition_info(from, transition.target, &transition.event, data);
+            match &transition.guard {
+                Some(guard) => match vm.call_function(guard.clone(), info.clone())? {
+                    KValue::Bool(true) => return Ok(Some((transition.target, info))),
+                    KValue::Bool(false) => {}
+                    unexpected => {
+                        return type_error("a Bool to be returned from the guard", &unexpected)
+                    }
+                },
+                None => return Ok(Some((transition.target, info))),
+            }
+        }
+        Ok(None)
+    }
+
+    fn transition_info(&self, from: usize, to: usize, event: &KString, data: &KValue) -> KValue {
+        let info = KMap::with_capacity(4);
+        info.insert("source", self.definition.state_name(from).clone());
+        info.insert("event", event.clone());
+        info.insert("target", self.definition.state_name(to).clone());
+        info.insert("data", data.clone());
+        info.into()
+    }
+
+    #[koto_method]
+    fn can(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (event, data) = event_args(ctx.args)?;
+        let this = ctx.instance()?.clone();
+        let from = *this.current.borrow();
+        let mut vm = ctx.vm.spawn_shared_vm();
+        Ok(this
+            .find_transition(&mut vm, from, event, data)?
+            .is_some()
+            .into())
+    }
+
+    #[koto_method]
+    fn events(&self) -> KValue {
+        let current = *self.current.borrow();
+        let mut events: Vec<&KString> = Vec::new();
+        for transition in self.definition.transitions.iter() {
+            let available = transition
+                .source
+                .as_ref()
+                .is_none_or(|from| from.contains(&current));
+            if available && !events.contains(&&transition.event) {
+                events.push(&transition.event);
+            }
+        }
+        let events: Vec<KValue> = events
+            .into_iter()
+            .map(|event| event.clone().into())
+            .collect();
+        KList::with_data(events.into()).into()
+    }
+
+    #[koto_method]
+    fn reset(ctx: MethodContext<Self>) -> Result<KValue> {
+        {
+            let this = ctx.instance()?;
+            *this.current.borrow_mut() = this.definition.initial;
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn send(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (event, data) = event_args(ctx.args)?;
+        // The instance is cloned so that it isn't borrowed while callbacks are running
+        let this = ctx.instance()?.clone();
+        let mut vm = ctx.vm.spawn_shared_vm();
+        Ok(this.send_event(&mut vm, event, data)?.into())
+    }
+
+    #[koto_method]
+    fn state(&self) -> KValue {
+        self.current_state().into()
+    }
+
+    #[koto_method]
+    fn states(&self) -> KValue {
+        let states: Vec<KValue> = self
+            .definition
+            .states
+            .iter()
+            .map(|state| state.name.clone().into())
+            .collect();
+        KList::with_data(states.into()).into()
+    }
+
+    #[koto_method]
+    fn to_dot(&self) -> KValue {
+        self.definition.to_dot(*self.current.borrow()).into()
+    }
+}
+
+impl KotoCopy for Machine {
+    fn copy(&self) -> KObject {
+        // Copies share the definition, but get their own current state
+        Self {
+            definition: self.definition.clone(),
+            current: (*self.current.borrow()).into(),
+        }
+        .into()
+    }
+}
+
+impl KotoObject for Machine {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!("Machine({})", self.current_state()));
+        Ok(())
+    }
+}
+
+impl From<Machine> for KValue {
+    fn from(machine: Machine) -> Self {
+        KObject::from(machine).into()
+    }
+}
+
+// Gets the event name and optional event data from a method's arguments
+fn event_args(args: &[KValue]) -> Result<(&str, &KValue)> {
+    match args {
+        [KValue::Str(event)] => Ok((event.as_str(), &KValue::Null)),
+        [KValue::Str(event), data] => Ok((event.as_str(), data)),
+        unexpected => type_error_with_slice("an event String, with optional data", unexpected),
+    }
+}
@@ -0,0 +1,112 @@
+//! A Koto language module for fuzzy string matching
+//!
+//! The matching functions are also available for use in Rust via the [matching] module,
+//! e.g. for suggesting corrections when a name isn't found.
+
+pub mod matching;
+
+use koto_runtime::{prelude::*, Result};
+use matching::{Algorithm, Scoring};
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("fuzzy");
+
+    result.add_fn("best_matches", |ctx| {
+        let (query, candidates, limit, options) = match ctx.args() {
+            [KValue::Str(query), candidates] => (query, candidates, None, None),
+            [KValue::Str(query), candidates, KValue::Number(limit)] if *limit >= 0 => {
+                (query, candidates, Some(limit), None)
+            }
+            [KValue::Str(query), candidates, KValue::Map(options)] => {
+                (query, candidates, None, Some(options))
+            }
+            [KValue::Str(query), candidates, KValue::Number(limit), KValue::Map(options)]
+                if *limit >= 0 =>
+            {
+                (query, candidates, Some(limit), Some(options))
+            }
+            unexpected => {
+                return type_error_with_slice(
+                    "a query String, a List or Tuple of candidates, an optional limit, \
+                     and an optional scoring Map",
+                    unexpected,
+                )
+            }
+        };
+
+        let candidates = match candidates {
+            KValue::List(list) => list.data().to_vec(),
+            KValue::Tuple(tuple) => tuple.to_vec(),
+            unexpected => return type_error("a List or Tuple of candidates", unexpected),
+        };
+        let candidate_strings = candidates
+            .iter()
+            .map(|candidate| match candidate {
+                KValue::Str(s) => Ok(s.as_str()),
+                unexpected => type_error("a String", unexpected),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let scoring = scoring_from_options(options)?;
+        let matches =
+            matching::best_matches(query, candidate_strings, limit.map(usize::from), scoring)
+                .into_iter()
+                .map(|(index, score)| {
+                    KValue::Tuple(vec![candidates[index].clone(), score.into()].into())
+                })
+                .collect::<ValueVec>();
+
+        Ok(KList::with_data(matches).into())
+    });
+
+    result.add_fn("levenshtein", |ctx| match ctx.args() {
+        [KValue::Str(a), KValue::Str(b)] => Ok(matching::levenshtein(a, b).into()),
+        unexpected => type_error_with_slice("two Strings", unexpected),
+    });
+
+    result.add_fn("similarity", |ctx| {
+        let (a, b, options) = match ctx.args() {
+            [KValue::Str(a), KValue::Str(b)] => (a, b, None),
+            [KValue::Str(a), KValue::Str(b), KValue::Map(options)] => (a, b, Some(options)),
+            unexpected => {
+                return type_error_with_slice(
+                    "two Strings, and an optional scoring Map",
+                    unexpected,
+                )
+            }
+        };
+        let scoring = scoring_from_options(options)?;
+        Ok(matching::similarity(a, b, scoring).into())
+    });
+
+    result
+}
+
+fn scoring_from_options(options: Option<&KMap>) -> Result<Scoring> {
+    let mut result = Scoring::default();
+    let Some(options) = options else {
+        return Ok(result);
+    };
+
+    for (key, value) in options.data().iter() {
+        match (key.to_string().as_str(), value) {
+            ("algorithm", KValue::Str(algorithm)) => {
+                result.algorithm = match algorithm.as_str() {
+                    "levenshtein" => Algorithm::Levenshtein,
+                    "jaro_winkler" => Algorithm::JaroWinkler,
+                    other => return runtime_error!("Unknown algorithm '{other}'"),
+                }
+            }
+            ("ignore_case", KValue::Bool(ignore_case)) => result.ignore_case = *ignore_case,
+            ("min_score", KValue::Number(min_score)) => result.min_score = min_score.into(),
+            ("algorithm", unexpected) => return type_error("a String as 'algorithm'", unexpected),
+            ("ignore_case", unexpected) => {
+                return type_error("a Bool as 'ignore_case'", unexpected)
+            }
+            ("min_score", unexpected) => return type_error("a Number as 'min_score'", unexpected),
+            (unexpected, _) => return runtime_error!("Unexpected scoring option '{unexpected}'"),
+        }
+    }
+
+    Ok(result)
+}
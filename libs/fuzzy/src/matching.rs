@@ -0,0 +1,196 @@
+//! Fuzzy string matching algorithms
+
+/// The algorithm used to score the similarity of two strings
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Algorithm {
+    /// The Levenshtein edit distance, normalized by the length of the longest string
+    #[default]
+    Levenshtein,
+    /// The Jaro-Winkler similarity, which favours strings that share a common prefix
+    JaroWinkler,
+}
+
+/// Options that control how strings are scored
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scoring {
+    /// The algorithm used to score strings
+    pub algorithm: Algorithm,
+    /// If true, then strings are compared without taking case into account
+    pub ignore_case: bool,
+    /// Matches with scores below the minimum score are discarded by [best_matches]
+    pub min_score: f64,
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self {
+            algorithm: Algorithm::default(),
+            ignore_case: false,
+            min_score: 0.0,
+        }
+    }
+}
+
+/// Returns the number of single character edits needed to turn one string into another
+///
+/// Insertions, deletions, and substitutions are counted as edits.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    levenshtein_chars(&a, &b, &mut Vec::new())
+}
+
+// The buffer is passed in so that it can be reused when scoring many strings
+fn levenshtein_chars(a: &[char], b: &[char], row: &mut Vec<usize>) -> usize {
+    // Only a single row of the distance matrix is needed at a time
+    row.clear();
+    row.extend(0..=b.len());
+
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+/// Returns the Jaro-Winkler similarity of two strings, in the range `0.0..=1.0`
+pub fn jaro_winkler(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    jaro_winkler_chars(&a, &b)
+}
+
+fn jaro_winkler_chars(a: &[char], b: &[char]) -> f64 {
+    let jaro = jaro_chars(a, b);
+    let prefix = a
+        .iter()
+        .zip(b.iter())
+        .take(4)
+        .take_while(|(a, b)| a == b)
+        .count();
+    jaro + prefix as f64 * 0.1 * (1.0 - jaro)
+}
+
+fn jaro_chars(a: &[char], b: &[char]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0;
+
+    for (i, ca) in a.iter().enumerate() {
+        let start = i.saturating_sub(window);
+        let end = (i + window + 1).min(b.len());
+        for j in start..end {
+            if !b_matched[j] && b[j] == *ca {
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+
+    if matches == 0 {
+        return 0.0;
+    }
+
+    // Count the matched characters that appear in a different order
+    let a_matches = a.iter().zip(a_matched).filter(|(_, m)| *m).map(|(c, _)| c);
+    let b_matches = b.iter().zip(b_matched).filter(|(_, m)| *m).map(|(c, _)| c);
+    let transpositions = a_matches.zip(b_matches).filter(|(a, b)| a != b).count() / 2;
+
+    let m = matches as f64;
+    (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0
+}
+
+/// Scores strings against a query, reusing allocations between calls
+pub struct Scorer {
+    scoring: Scoring,
+    query: Vec<char>,
+    candidate: Vec<char>,
+    row: Vec<usize>,
+}
+
+impl Scorer {
+    /// Makes a scorer for the given query
+    pub fn new(query: &str, scoring: Scoring) -> Self {
+        let mut result = Self {
+            scoring,
+            query: Vec::new(),
+            candidate: Vec::new(),
+            row: Vec::new(),
+        };
+        prepare(query, scoring.ignore_case, &mut result.query);
+        result
+    }
+
+    /// Returns the similarity of the candidate to the query, in the range `0.0..=1.0`
+    pub fn score(&mut self, candidate: &str) -> f64 {
+        prepare(candidate, self.scoring.ignore_case, &mut self.candidate);
+
+        match self.scoring.algorithm {
+            Algorithm::Levenshtein => {
+                let max_len = self.query.len().max(self.candidate.len());
+                if max_len == 0 {
+                    1.0
+                } else {
+                    let distance = levenshtein_chars(&self.query, &self.candidate, &mut self.row);
+                    1.0 - distance as f64 / max_len as f64
+                }
+            }
+            Algorithm::JaroWinkler => jaro_winkler_chars(&self.query, &self.candidate),
+        }
+    }
+}
+
+fn prepare(s: &str, ignore_case: bool, chars: &mut Vec<char>) {
+    chars.clear();
+    if ignore_case {
+        chars.extend(s.chars().flat_map(char::to_lowercase));
+    } else {
+        chars.extend(s.chars());
+    }
+}
+
+/// Returns the similarity of two strings, in the range `0.0..=1.0`
+pub fn similarity(a: &str, b: &str, scoring: Scoring) -> f64 {
+    Scorer::new(a, scoring).score(b)
+}
+
+/// Finds the candidates that best match the query
+///
+/// The result contains the indices of the best matching candidates along with their scores,
+/// sorted with the best matches first. Candidates with equal scores are kept in their original
+/// order.
+pub fn best_matches<'a>(
+    query: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+    limit: Option<usize>,
+    scoring: Scoring,
+) -> Vec<(usize, f64)> {
+    let mut scorer = Scorer::new(query, scoring);
+    let mut result: Vec<(usize, f64)> = candidates
+        .into_iter()
+        .enumerate()
+        .map(|(i, candidate)| (i, scorer.score(candidate)))
+        .filter(|(_, score)| *score >= scoring.min_score)
+        .collect();
+    result.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    if let Some(limit) = limit {
+        result.truncate(limit);
+    }
+    result
+}
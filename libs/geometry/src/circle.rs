@@ -0,0 +1,97 @@
+use crate::{
+    shape::{shape_from_args, Shape},
+    Vec2,
+};
+use koto_runtime::{derive::*, prelude::*, Result};
+use nannou_core::geom::DVec2;
+use std::{f64::consts::PI, fmt};
+
+#[derive(Copy, Clone, PartialEq, KotoCopy, KotoType)]
+#[koto(use_copy)]
+pub struct Circle {
+    pub center: DVec2,
+    pub radius: f64,
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Circle {
+    pub fn new(x: f64, y: f64, radius: f64) -> Self {
+        Self {
+            center: DVec2::new(x, y),
+            radius,
+        }
+    }
+
+    #[koto_method]
+    fn area(&self) -> KValue {
+        (PI * self.radius * self.radius).into()
+    }
+
+    #[koto_method]
+    fn bounds(&self) -> KValue {
+        Shape::Circle(*self).bounds().into()
+    }
+
+    #[koto_method]
+    fn center(&self) -> KValue {
+        Vec2::from(self.center).into()
+    }
+
+    #[koto_method]
+    fn circumference(&self) -> KValue {
+        (2.0 * PI * self.radius).into()
+    }
+
+    #[koto_method]
+    fn contains(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Object(p)] if p.is_a::<Vec2>() => {
+                let p = p.cast::<Vec2>().unwrap();
+                Ok((self.center.distance(p.inner()) <= self.radius).into())
+            }
+            unexpected => type_error_with_slice("a Vec2", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn intersects(&self, args: &[KValue]) -> Result<KValue> {
+        let other = shape_from_args(args)?;
+        Ok(Shape::Circle(*self).intersects(&other).into())
+    }
+
+    #[koto_method]
+    fn radius(&self) -> KValue {
+        self.radius.into()
+    }
+}
+
+impl KotoObject for Circle {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(self.to_string());
+        Ok(())
+    }
+
+    fn equal(&self, rhs: &KValue) -> Result<bool> {
+        geometry_comparison_op!(self, rhs, ==)
+    }
+
+    fn not_equal(&self, rhs: &KValue) -> Result<bool> {
+        geometry_comparison_op!(self, rhs, !=)
+    }
+}
+
+impl From<Circle> for KValue {
+    fn from(circle: Circle) -> Self {
+        KObject::from(circle).into()
+    }
+}
+
+impl fmt::Display for Circle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Circle{{x: {}, y: {}, radius: {}}}",
+            self.center.x, self.center.y, self.radius
+        )
+    }
+}
@@ -2,21 +2,96 @@
 
 #[macro_use]
 mod macros;
+mod circle;
+mod polygon;
 mod rect;
+mod segment;
+mod shape;
 mod vec2;
 mod vec3;
 
+pub use circle::Circle;
+pub use polygon::Polygon;
 pub use rect::Rect;
+pub use segment::Segment;
+pub use shape::Shape;
 pub use vec2::Vec2;
 pub use vec3::Vec3;
 
 use koto_runtime::prelude::*;
+use nannou_core::geom::DVec2;
+use shape::{bounds_of_points, SHAPE_TYPES};
 
 pub fn make_module() -> KMap {
     use KValue::{Number, Object};
 
     let result = KMap::with_type("geometry");
 
+    result.add_fn("bounds", |ctx| {
+        let shapes = match ctx.args() {
+            [shapes] if shapes.is_iterable() => shapes.clone(),
+            unexpected => return type_error_with_slice("an iterable of shapes", unexpected),
+        };
+
+        let mut corners = Vec::new();
+        for output in ctx.vm.make_iterator(shapes)? {
+            match output {
+                KIteratorOutput::Value(value) => match Shape::from_value(&value) {
+                    Some(shape) => {
+                        let bounds = shape.bounds().inner();
+                        corners.push(bounds.bottom_left());
+                        corners.push(bounds.top_right());
+                    }
+                    None => return type_error(SHAPE_TYPES, &value),
+                },
+                KIteratorOutput::ValuePair(..) => return runtime_error!("Expected a shape"),
+                KIteratorOutput::Error(error) => return Err(error),
+            }
+        }
+
+        if corners.is_empty() {
+            Ok(KValue::Null)
+        } else {
+            Ok(bounds_of_points(&corners).into())
+        }
+    });
+
+    result.add_fn("circle", |ctx| {
+        let (x, y, radius) = match ctx.args() {
+            [Number(x), Number(y), Number(radius)] => (x.into(), y.into(), radius.into()),
+            [Object(center), Number(radius)] if center.is_a::<Vec2>() => {
+                let center = center.cast::<Vec2>().unwrap().inner();
+                (center.x, center.y, radius.into())
+            }
+            unexpected => {
+                return type_error_with_slice("3 Numbers, or a Vec2 and a Number", unexpected)
+            }
+        };
+
+        Ok(Circle::new(x, y, radius).into())
+    });
+
+    result.add_fn("polygon", |ctx| {
+        let points = match ctx.args() {
+            [points] if points.is_iterable() => points.clone(),
+            unexpected => return type_error_with_slice("an iterable of Vec2s", unexpected),
+        };
+
+        let mut vertices = Vec::new();
+        for output in ctx.vm.make_iterator(points)? {
+            match output {
+                KIteratorOutput::Value(Object(p)) if p.is_a::<Vec2>() => {
+                    vertices.push(p.cast::<Vec2>().unwrap().inner())
+                }
+                KIteratorOutput::Value(unexpected) => return type_error("a Vec2", &unexpected),
+                KIteratorOutput::ValuePair(..) => return runtime_error!("Expected a Vec2"),
+                KIteratorOutput::Error(error) => return Err(error),
+            }
+        }
+
+        Ok(Polygon::new(vertices)?.into())
+    });
+
     result.add_fn("rect", |ctx| {
         let (x, y, width, height) = match ctx.args() {
             [] => (0.0, 0.0, 0.0, 0.0),
@@ -34,6 +109,22 @@ pub fn make_module() -> KMap {
         Ok(Rect::from_x_y_w_h(x, y, width, height).into())
     });
 
+    result.add_fn("segment", |ctx| {
+        let (start, end) = match ctx.args() {
+            [Number(x1), Number(y1), Number(x2), Number(y2)] => (
+                DVec2::new(x1.into(), y1.into()),
+                DVec2::new(x2.into(), y2.into()),
+            ),
+            [Object(start), Object(end)] if start.is_a::<Vec2>() && end.is_a::<Vec2>() => (
+                start.cast::<Vec2>().unwrap().inner(),
+                end.cast::<Vec2>().unwrap().inner(),
+            ),
+            unexpected => return type_error_with_slice("4 Numbers, or 2 Vec2s", unexpected),
+        };
+
+        Ok(Segment::new(start, end).into())
+    });
+
     result.add_fn("vec2", |ctx| {
         let (x, y) = match ctx.args() {
             [] => (0.0, 0.0),
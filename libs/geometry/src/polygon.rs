@@ -0,0 +1,94 @@
+use crate::{
+    shape::{polygon_area, shape_from_args, Shape},
+    Vec2,
+};
+use koto_runtime::{derive::*, prelude::*, Ptr, Result};
+use nannou_core::geom::DVec2;
+use std::fmt;
+
+#[derive(Clone, KotoCopy, KotoType)]
+pub struct Polygon(Ptr<Vec<DVec2>>);
+
+#[koto_impl(runtime = koto_runtime)]
+impl Polygon {
+    /// Makes a polygon from its vertices
+    ///
+    /// At least 3 vertices are required.
+    pub fn new(vertices: Vec<DVec2>) -> Result<Self> {
+        if vertices.len() < 3 {
+            return runtime_error!(
+                "A polygon needs at least 3 points, found {}",
+                vertices.len()
+            );
+        }
+        Ok(Self(vertices.into()))
+    }
+
+    pub fn vertices(&self) -> &[DVec2] {
+        &self.0
+    }
+
+    #[koto_method]
+    fn area(&self) -> KValue {
+        polygon_area(&self.0).into()
+    }
+
+    #[koto_method]
+    fn bounds(&self) -> KValue {
+        Shape::Polygon(self.clone()).bounds().into()
+    }
+
+    #[koto_method]
+    fn contains(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Object(p)] if p.is_a::<Vec2>() => {
+                let p = p.cast::<Vec2>().unwrap();
+                Ok(Shape::Polygon(self.clone())
+                    .contains_point(p.inner())
+                    .into())
+            }
+            unexpected => type_error_with_slice("a Vec2", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn intersects(&self, args: &[KValue]) -> Result<KValue> {
+        let other = shape_from_args(args)?;
+        Ok(Shape::Polygon(self.clone()).intersects(&other).into())
+    }
+
+    #[koto_method]
+    fn perimeter(&self) -> KValue {
+        let edges = self.0.iter().zip(self.0.iter().cycle().skip(1));
+        edges.map(|(a, b)| a.distance(*b)).sum::<f64>().into()
+    }
+
+    #[koto_method]
+    fn points(&self) -> KValue {
+        let points: Vec<KValue> = self.0.iter().map(|p| Vec2::from(*p).into()).collect();
+        KValue::Tuple(points.into())
+    }
+}
+
+impl KotoObject for Polygon {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(self.to_string());
+        Ok(())
+    }
+
+    fn size(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+impl From<Polygon> for KValue {
+    fn from(polygon: Polygon) -> Self {
+        KObject::from(polygon).into()
+    }
+}
+
+impl fmt::Display for Polygon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Polygon({} points)", self.0.len())
+    }
+}
@@ -1,5 +1,9 @@
-use crate::Vec2;
+use crate::{
+    shape::{shape_from_args, Shape},
+    Vec2,
+};
 use koto_runtime::{derive::*, prelude::*, Result};
+use nannou_core::geom::DVec2;
 use std::fmt;
 
 type Inner = nannou_core::geom::Rect<f64>;
@@ -14,6 +18,20 @@ impl Rect {
         Inner::from_x_y_w_h(x, y, width, height).into()
     }
 
+    /// Makes a rect with the given corners
+    pub fn from_corners(a: DVec2, b: DVec2) -> Self {
+        Inner::from_corners_f64(a, b).into()
+    }
+
+    pub fn inner(&self) -> Inner {
+        self.0
+    }
+
+    #[koto_method]
+    fn area(&self) -> KValue {
+        (self.0.w() * self.0.h()).into()
+    }
+
     #[koto_method]
     fn left(&self) -> KValue {
         self.0.left().into()
@@ -67,10 +85,38 @@ impl Rect {
                 let result = self.0.contains(p.inner());
                 Ok(result.into())
             }
-            unexpected => type_error_with_slice("Vec2", unexpected),
+            _ => {
+                // Shapes are contained in the rect if their bounds are within the rect
+                let bounds = shape_from_args(args)?.bounds().0;
+                let result = bounds.left() >= self.0.left()
+                    && bounds.right() <= self.0.right()
+                    && bounds.bottom() >= self.0.bottom()
+                    && bounds.top() <= self.0.top();
+                Ok(result.into())
+            }
         }
     }
 
+    #[koto_method]
+    fn intersection(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Object(other)] if other.is_a::<Rect>() => {
+                let other = other.cast::<Rect>().unwrap();
+                match self.0.overlap(other.0) {
+                    Some(overlap) => Ok(Rect::from(overlap).into()),
+                    None => Ok(KValue::Null),
+                }
+            }
+            unexpected => type_error_with_slice("a Rect", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn intersects(&self, args: &[KValue]) -> Result<KValue> {
+        let other = shape_from_args(args)?;
+        Ok(Shape::Rect(*self).intersects(&other).into())
+    }
+
     #[koto_method]
     fn set_center(ctx: MethodContext<Self>) -> Result<KValue> {
         use KValue::{Number, Object};
@@ -89,6 +135,12 @@ impl Rect {
         // Return a clone of the Rect instance
         ctx.instance_result()
     }
+
+    #[koto_method]
+    fn union(&self, args: &[KValue]) -> Result<KValue> {
+        let bounds = shape_from_args(args)?.bounds();
+        Ok(Rect::from(self.0.max(bounds.0)).into())
+    }
 }
 
 impl KotoObject for Rect {
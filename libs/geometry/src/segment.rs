@@ -0,0 +1,98 @@
+use crate::{
+    shape::{segment_intersection, shape_from_args, Shape},
+    Vec2,
+};
+use koto_runtime::{derive::*, prelude::*, Result};
+use nannou_core::geom::DVec2;
+use std::fmt;
+
+#[derive(Copy, Clone, PartialEq, KotoCopy, KotoType)]
+#[koto(use_copy)]
+pub struct Segment {
+    pub start: DVec2,
+    pub end: DVec2,
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Segment {
+    pub fn new(start: DVec2, end: DVec2) -> Self {
+        Self { start, end }
+    }
+
+    #[koto_method]
+    fn bounds(&self) -> KValue {
+        Shape::Segment(*self).bounds().into()
+    }
+
+    #[koto_method]
+    fn end(&self) -> KValue {
+        Vec2::from(self.end).into()
+    }
+
+    #[koto_method]
+    fn intersection(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Object(other)] if other.is_a::<Segment>() => {
+                let other = other.cast::<Segment>().unwrap();
+                match segment_intersection(self.start, self.end, other.start, other.end) {
+                    Some(p) => Ok(Vec2::from(p).into()),
+                    None => Ok(KValue::Null),
+                }
+            }
+            unexpected => type_error_with_slice("a Segment", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn intersects(&self, args: &[KValue]) -> Result<KValue> {
+        let other = shape_from_args(args)?;
+        Ok(Shape::Segment(*self).intersects(&other).into())
+    }
+
+    #[koto_method]
+    fn length(&self) -> KValue {
+        self.start.distance(self.end).into()
+    }
+
+    #[koto_method]
+    fn midpoint(&self) -> KValue {
+        Vec2::from(self.start.lerp(self.end, 0.5)).into()
+    }
+
+    #[koto_method]
+    fn start(&self) -> KValue {
+        Vec2::from(self.start).into()
+    }
+}
+
+impl KotoObject for Segment {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(self.to_string());
+        Ok(())
+    }
+
+    fn equal(&self, rhs: &KValue) -> Result<bool> {
+        geometry_comparison_op!(self, rhs, ==)
+    }
+
+    fn not_equal(&self, rhs: &KValue) -> Result<bool> {
+        geometry_comparison_op!(self, rhs, !=)
+    }
+}
+
+impl From<Segment> for KValue {
+    fn from(segment: Segment) -> Self {
+        KObject::from(segment).into()
+    }
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Segment{{start: {}, end: {}}}",
+            Vec2::from(self.start),
+            Vec2::from(self.end)
+        )
+    }
+}
@@ -0,0 +1,246 @@
+use crate::{Circle, Polygon, Rect, Segment, Vec2};
+use koto_runtime::{prelude::*, Result};
+use nannou_core::geom::DVec2;
+
+// The types accepted by functions that take a shape, used in error messages
+pub const SHAPE_TYPES: &str = "a Vec2, Rect, Circle, Segment, or Polygon";
+
+// The distance within which a point is considered to be on a segment
+const SEGMENT_TOLERANCE: f64 = 1.0e-9;
+
+/// One of the geometry module's shapes, used for intersection and bounds tests
+#[derive(Clone)]
+pub enum Shape {
+    Point(DVec2),
+    Rect(Rect),
+    Circle(Circle),
+    Segment(Segment),
+    Polygon(Polygon),
+}
+
+impl Shape {
+    /// Makes a shape from a Koto value, returning `None` if the value isn't a geometry type
+    pub fn from_value(value: &KValue) -> Option<Self> {
+        let KValue::Object(o) = value else {
+            return None;
+        };
+
+        if let Ok(p) = o.cast::<Vec2>() {
+            Some(Self::Point(p.inner()))
+        } else if let Ok(r) = o.cast::<Rect>() {
+            Some(Self::Rect(*r))
+        } else if let Ok(c) = o.cast::<Circle>() {
+            Some(Self::Circle(*c))
+        } else if let Ok(s) = o.cast::<Segment>() {
+            Some(Self::Segment(*s))
+        } else if let Ok(p) = o.cast::<Polygon>() {
+            Some(Self::Polygon(p.clone()))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the smallest rect that contains the shape
+    pub fn bounds(&self) -> Rect {
+        match self {
+            Self::Point(p) => Rect::from_corners(*p, *p),
+            Self::Rect(r) => *r,
+            Self::Circle(c) => {
+                let radius = DVec2::splat(c.radius);
+                Rect::from_corners(c.center - radius, c.center + radius)
+            }
+            Self::Segment(s) => Rect::from_corners(s.start.min(s.end), s.start.max(s.end)),
+            Self::Polygon(p) => bounds_of_points(p.vertices()),
+        }
+    }
+
+    /// Returns true if the point is inside the shape, or on its boundary
+    pub fn contains_point(&self, p: DVec2) -> bool {
+        match self {
+            Self::Point(point) => *point == p,
+            Self::Rect(r) => {
+                let r = r.inner();
+                (r.left()..=r.right()).contains(&p.x) && (r.bottom()..=r.top()).contains(&p.y)
+            }
+            Self::Circle(c) => c.center.distance(p) <= c.radius,
+            Self::Segment(s) => distance_to_segment(p, s.start, s.end) <= SEGMENT_TOLERANCE,
+            Self::Polygon(polygon) => polygon_contains(polygon.vertices(), p),
+        }
+    }
+
+    /// Returns true if the shapes overlap or touch
+    pub fn intersects(&self, other: &Shape) -> bool {
+        use Shape::*;
+
+        match (self, other) {
+            (Point(a), Point(b)) => a == b,
+            (Point(p), shape) | (shape, Point(p)) => shape.contains_point(*p),
+            (Rect(a), Rect(b)) => {
+                let (a, b) = (a.inner(), b.inner());
+                a.left() <= b.right()
+                    && b.left() <= a.right()
+                    && a.bottom() <= b.top()
+                    && b.bottom() <= a.top()
+            }
+            (Circle(a), Circle(b)) => a.center.distance(b.center) <= a.radius + b.radius,
+            (Circle(c), Segment(s)) | (Segment(s), Circle(c)) => {
+                distance_to_segment(c.center, s.start, s.end) <= c.radius
+            }
+            (Circle(c), shape) | (shape, Circle(c)) => {
+                let outline = shape.outline();
+                polygon_contains(&outline, c.center)
+                    || edges(&outline).any(|(a, b)| distance_to_segment(c.center, a, b) <= c.radius)
+            }
+            (Segment(a), Segment(b)) => segments_intersect(a.start, a.end, b.start, b.end),
+            (Segment(s), shape) | (shape, Segment(s)) => {
+                let outline = shape.outline();
+                polygon_contains(&outline, s.start)
+                    || edges(&outline).any(|(a, b)| segments_intersect(s.start, s.end, a, b))
+            }
+            (a, b) => {
+                let (a, b) = (a.outline(), b.outline());
+                edges(&a)
+                    .any(|(a1, a2)| edges(&b).any(|(b1, b2)| segments_intersect(a1, a2, b1, b2)))
+                    || polygon_contains(&b, a[0])
+                    || polygon_contains(&a, b[0])
+            }
+        }
+    }
+
+    // Returns the points that make up the outline of a rect or polygon
+    fn outline(&self) -> Vec<DVec2> {
+        match self {
+            Self::Rect(r) => {
+                let r = r.inner();
+                vec![
+                    r.bottom_left(),
+                    r.bottom_right(),
+                    r.top_right(),
+                    r.top_left(),
+                ]
+            }
+            Self::Polygon(p) => p.vertices().to_vec(),
+            Self::Point(p) => vec![*p],
+            Self::Circle(c) => vec![c.center],
+            Self::Segment(s) => vec![s.start, s.end],
+        }
+    }
+}
+
+/// Gets a shape from a method's arguments
+pub fn shape_from_args(args: &[KValue]) -> Result<Shape> {
+    match args {
+        [arg] => match Shape::from_value(arg) {
+            Some(shape) => Ok(shape),
+            None => type_error(SHAPE_TYPES, arg),
+        },
+        unexpected => type_error_with_slice(SHAPE_TYPES, unexpected),
+    }
+}
+
+/// Returns the smallest rect that contains all of the points
+pub fn bounds_of_points(points: &[DVec2]) -> Rect {
+    let (min, max) = points
+        .iter()
+        .skip(1)
+        .fold((points[0], points[0]), |(min, max), p| {
+            (min.min(*p), max.max(*p))
+        });
+    Rect::from_corners(min, max)
+}
+
+/// Returns the area of the polygon made from the points
+pub fn polygon_area(points: &[DVec2]) -> f64 {
+    (edges(points).map(|(a, b)| a.perp_dot(b)).sum::<f64>() / 2.0).abs()
+}
+
+/// Returns true if the point is inside the polygon made from the points, or on one of its edges
+pub fn polygon_contains(points: &[DVec2], p: DVec2) -> bool {
+    if points.len() < 3 {
+        return points
+            .iter()
+            .zip(points.iter().skip(1))
+            .any(|(a, b)| distance_to_segment(p, *a, *b) <= SEGMENT_TOLERANCE);
+    }
+
+    let mut inside = false;
+    for (a, b) in edges(points) {
+        if distance_to_segment(p, a, b) <= SEGMENT_TOLERANCE {
+            return true;
+        }
+        // Count the edges that are crossed by a ray cast from the point in the +x direction
+        if (a.y > p.y) != (b.y > p.y) && p.x < a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Returns the point where two segments intersect
+///
+/// `None` is returned if the segments don't intersect, or if they're parallel.
+pub fn segment_intersection(a1: DVec2, a2: DVec2, b1: DVec2, b2: DVec2) -> Option<DVec2> {
+    let r = a2 - a1;
+    let s = b2 - b1;
+    let denominator = r.perp_dot(s);
+    if denominator == 0.0 {
+        return None;
+    }
+
+    let t = (b1 - a1).perp_dot(s) / denominator;
+    let u = (b1 - a1).perp_dot(r) / denominator;
+    if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+        Some(a1 + r * t)
+    } else {
+        None
+    }
+}
+
+/// Returns the shortest distance from the point to the segment
+pub fn distance_to_segment(p: DVec2, a: DVec2, b: DVec2) -> f64 {
+    let ab = b - a;
+    let length_squared = ab.length_squared();
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        ((p - a).dot(ab) / length_squared).clamp(0.0, 1.0)
+    };
+    (a + ab * t).distance(p)
+}
+
+/// Returns true if the segments touch or cross
+pub fn segments_intersect(a1: DVec2, a2: DVec2, b1: DVec2, b2: DVec2) -> bool {
+    let d1 = orientation(b1, b2, a1);
+    let d2 = orientation(b1, b2, a2);
+    let d3 = orientation(a1, a2, b1);
+    let d4 = orientation(a1, a2, b2);
+
+    if d1 * d2 < 0.0 && d3 * d4 < 0.0 {
+        return true;
+    }
+
+    // Check for collinear points that lie on the other segment
+    (d1 == 0.0 && within_bounds(b1, b2, a1))
+        || (d2 == 0.0 && within_bounds(b1, b2, a2))
+        || (d3 == 0.0 && within_bounds(a1, a2, b1))
+        || (d4 == 0.0 && within_bounds(a1, a2, b2))
+}
+
+// Returns the edges of the closed polygon made from the points
+fn edges(points: &[DVec2]) -> impl Iterator<Item = (DVec2, DVec2)> + '_ {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| (*a, *b))
+}
+
+// Returns a positive number if c is to the left of the line from a to b,
+// negative if it's to the right, and zero if the points are collinear.
+fn orientation(a: DVec2, b: DVec2, c: DVec2) -> f64 {
+    (b - a).perp_dot(c - a)
+}
+
+// Returns true if p is within the bounding box of the segment from a to b
+fn within_bounds(a: DVec2, b: DVec2, p: DVec2) -> bool {
+    p.cmpge(a.min(b)).all() && p.cmple(a.max(b)).all()
+}
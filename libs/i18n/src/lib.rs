@@ -0,0 +1,273 @@
+//! A Koto language module for translating messages with message catalogs
+
+mod plurals;
+
+use koto_runtime::{prelude::*, PtrMut, Result};
+use std::{collections::HashMap, path::Path};
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("i18n");
+    let catalogs = PtrMut::from(Catalogs::default());
+
+    result.add_fn("add_messages", {
+        let catalogs = catalogs.clone();
+        move |ctx| match ctx.args() {
+            [KValue::Str(locale), KValue::Map(messages)] => {
+                catalogs.borrow_mut().add_messages(locale, messages);
+                Ok(KValue::Null)
+            }
+            unexpected => type_error_with_slice("a locale String and a messages Map", unexpected),
+        }
+    });
+
+    result.add_fn("fallback_locale", {
+        let catalogs = catalogs.clone();
+        move |_| match &catalogs.borrow().fallback {
+            Some(fallback) => Ok(fallback.as_str().into()),
+            None => Ok(KValue::Null),
+        }
+    });
+
+    result.add_fn("load", {
+        let catalogs = catalogs.clone();
+        move |ctx| match ctx.args() {
+            [KValue::Str(locale), KValue::Str(path)] => {
+                let messages = load_messages(Path::new(path.as_str()))?;
+                catalogs.borrow_mut().add_messages(locale, &messages);
+                Ok(KValue::Null)
+            }
+            unexpected => type_error_with_slice("a locale String and a path String", unexpected),
+        }
+    });
+
+    result.add_fn("locale", {
+        let catalogs = catalogs.clone();
+        move |_| Ok(catalogs.borrow().locale.as_str().into())
+    });
+
+    result.add_fn("set_fallback_locale", {
+        let catalogs = catalogs.clone();
+        move |ctx| match ctx.args() {
+            [KValue::Str(locale)] => {
+                catalogs.borrow_mut().fallback = Some(locale.to_string());
+                Ok(KValue::Null)
+            }
+            [KValue::Null] => {
+                catalogs.borrow_mut().fallback = None;
+                Ok(KValue::Null)
+            }
+            unexpected => type_error_with_slice("a locale String or null", unexpected),
+        }
+    });
+
+    result.add_fn("set_locale", {
+        let catalogs = catalogs.clone();
+        move |ctx| match ctx.args() {
+            [KValue::Str(locale)] => {
+                catalogs.borrow_mut().locale = locale.to_string();
+                Ok(KValue::Null)
+            }
+            unexpected => type_error_with_slice("a locale String", unexpected),
+        }
+    });
+
+    result.add_fn("t", move |ctx| {
+        let expected_error = "a message key String, and an optional arguments Map";
+
+        let (key, args) = match ctx.args() {
+            [KValue::Str(key)] => (key.clone(), None),
+            [KValue::Str(key), KValue::Map(args)] => (key.clone(), Some(args.clone())),
+            unexpected => return type_error_with_slice(expected_error, unexpected),
+        };
+
+        // Clone the message out of the catalog so that the catalogs aren't borrowed while
+        // the message is being formatted
+        let found = catalogs.borrow().find(&key);
+        match found {
+            Some((message, locale)) => translate(ctx.vm, &key, &message, &locale, args.as_ref()),
+            None => Ok(key.into()),
+        }
+    });
+
+    result
+}
+
+struct Catalogs {
+    messages: HashMap<String, KMap>,
+    locale: String,
+    fallback: Option<String>,
+}
+
+impl Default for Catalogs {
+    fn default() -> Self {
+        Self {
+            messages: HashMap::default(),
+            locale: "en".into(),
+            fallback: None,
+        }
+    }
+}
+
+impl Catalogs {
+    fn add_messages(&mut self, locale: &str, messages: &KMap) {
+        let catalog = self.messages.entry(locale.to_string()).or_default();
+        merge_maps(catalog, messages);
+    }
+
+    // Finds the message for a key, along with the locale of the catalog that contained it
+    //
+    // The message is looked up in the current locale, then in the current locale's language
+    // (e.g. 'de' for 'de-AT'), and then in the fallback locale.
+    fn find(&self, key: &str) -> Option<(KValue, String)> {
+        let language = self.locale.split(['-', '_']).next().unwrap_or_default();
+
+        [
+            Some(self.locale.as_str()),
+            Some(language),
+            self.fallback.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .find_map(|locale| {
+            let catalog = self.messages.get(locale)?;
+            let message = find_nested(catalog, key)?;
+            Some((message, locale.to_string()))
+        })
+    }
+}
+
+// Merges the entries from `source` into `target`, recursively merging nested maps
+fn merge_maps(target: &KMap, source: &KMap) {
+    for (key, value) in source.data().iter() {
+        let existing = target.data().get(key).cloned();
+        match (existing, value) {
+            (Some(KValue::Map(existing)), KValue::Map(nested)) => merge_maps(&existing, nested),
+            _ => {
+                target.data_mut().insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+// Looks up a message key, with dots in the key used to find messages in nested maps
+fn find_nested(catalog: &KMap, key: &str) -> Option<KValue> {
+    if let Some(message) = catalog.get(key) {
+        return Some(message);
+    }
+
+    let mut current = KValue::Map(catalog.clone());
+    for part in key.split('.') {
+        current = match current {
+            KValue::Map(map) => map.get(part)?,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+fn load_messages(path: &Path) -> Result<KMap> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => return runtime_error!("Failed to read '{}': {e}", path.display()),
+    };
+
+    let messages = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => match serde_json::from_str(&contents) {
+            Ok(value) => koto_json::json_value_to_koto_value(&value)?,
+            Err(e) => return runtime_error!("Failed to parse '{}': {e}", path.display()),
+        },
+        Some("toml") => match toml::from_str(&contents) {
+            Ok(value) => koto_toml::toml_to_koto_value(&value)?,
+            Err(e) => return runtime_error!("Failed to parse '{}': {e}", path.display()),
+        },
+        _ => {
+            return runtime_error!(
+                "Expected a message catalog with a .json or .toml extension, found '{}'",
+                path.display()
+            )
+        }
+    };
+
+    match messages {
+        KValue::Map(messages) => Ok(messages),
+        unexpected => type_error("a Map in the message catalog", &unexpected),
+    }
+}
+
+fn translate(
+    vm: &mut KotoVm,
+    key: &str,
+    message: &KValue,
+    locale: &str,
+    args: Option<&KMap>,
+) -> Result<KValue> {
+    let template = match message {
+        KValue::Str(template) => template.clone(),
+        // Maps contain plural forms, selected using the 'count' argument
+        KValue::Map(forms) => {
+            let count = match args.and_then(|args| args.get("count")) {
+                Some(KValue::Number(count)) => count,
+                Some(unexpected) => return type_error("a Number as 'count'", &unexpected),
+                None => {
+                    return runtime_error!(
+                        "The message '{key}' has plural forms, and requires a 'count' argument"
+                    )
+                }
+            };
+
+            let exact = if count == 0 { forms.get("zero") } else { None };
+            let form = exact
+                .or_else(|| forms.get(plurals::category(locale, f64::from(count))))
+                .or_else(|| forms.get("other"));
+
+            match form {
+                Some(KValue::Str(template)) => template,
+                Some(unexpected) => {
+                    return type_error("a String as plural form", &unexpected);
+                }
+                None => return runtime_error!("Missing plural form for message '{key}'"),
+            }
+        }
+        unexpected => return type_error("a String or Map as message", unexpected),
+    };
+
+    match args {
+        Some(args) => Ok(interpolate(vm, &template, args)?.into()),
+        None => Ok(template.into()),
+    }
+}
+
+// Replaces `{name}` placeholders in the template with matching values from the args map
+//
+// Placeholders without a matching argument are left unchanged.
+fn interpolate(vm: &mut KotoVm, template: &str, args: &KMap) -> Result<String> {
+    let mut result = String::with_capacity(template.len());
+    let mut remaining = template;
+
+    while let Some(start) = remaining.find('{') {
+        result.push_str(&remaining[..start]);
+        let after_brace = &remaining[start + 1..];
+
+        match after_brace.find('}') {
+            Some(end) => {
+                let name = &after_brace[..end];
+                match args.get(name) {
+                    Some(value) => result.push_str(&vm.value_to_string(&value)?),
+                    None => {
+                        result.push('{');
+                        result.push_str(name);
+                        result.push('}');
+                    }
+                }
+                remaining = &after_brace[end + 1..];
+            }
+            None => {
+                result.push_str(&remaining[start..]);
+                remaining = "";
+            }
+        }
+    }
+
+    result.push_str(remaining);
+    Ok(result)
+}
@@ -0,0 +1,47 @@
+//! Plural rules, simplified from the CLDR cardinal plural rules
+//!
+//! See https://www.unicode.org/cldr/charts/latest/supplemental/language_plural_rules.html
+
+/// Returns the plural category that should be used for the given count in a locale
+///
+/// The returned category is one of `zero`, `one`, `two`, `few`, `many`, or `other`.
+pub fn category(locale: &str, n: f64) -> &'static str {
+    let language = locale.split(['-', '_']).next().unwrap_or_default();
+    let is_integer = n.fract() == 0.0;
+    let i = n.abs().trunc() as u64;
+
+    match language {
+        "ja" | "ko" | "zh" | "th" | "vi" | "id" | "ms" => "other",
+        "fr" | "pt" if i <= 1 => "one",
+        "fr" | "pt" => "other",
+        "ru" | "uk" | "be" if !is_integer => "other",
+        "ru" | "uk" | "be" => match (i % 10, i % 100) {
+            (1, rem_100) if rem_100 != 11 => "one",
+            (2..=4, rem_100) if !(12..=14).contains(&rem_100) => "few",
+            _ => "many",
+        },
+        "pl" if !is_integer => "other",
+        "pl" => match (i, i % 10, i % 100) {
+            (1, _, _) => "one",
+            (_, 2..=4, rem_100) if !(12..=14).contains(&rem_100) => "few",
+            _ => "many",
+        },
+        "cs" | "sk" if !is_integer => "many",
+        "cs" | "sk" => match i {
+            1 => "one",
+            2..=4 => "few",
+            _ => "other",
+        },
+        "ar" if !is_integer => "other",
+        "ar" => match (i, i % 100) {
+            (0, _) => "zero",
+            (1, _) => "one",
+            (2, _) => "two",
+            (_, 3..=10) => "few",
+            (_, 11..=99) => "many",
+            _ => "other",
+        },
+        _ if is_integer && i == 1 => "one",
+        _ => "other",
+    }
+}
@@ -0,0 +1,157 @@
+//! Drawing primitives for RGBA images
+//!
+//! Drawing is clipped to the image's bounds, and colors with transparency are blended with the
+//! image's existing pixels.
+
+use font8x8::legacy::BASIC_LEGACY;
+use image::{Rgba, RgbaImage};
+
+// The size of a glyph in the built-in font, in pixels
+const GLYPH_SIZE: i64 = 8;
+// The distance between lines of text, in pixels
+const LINE_HEIGHT: i64 = 10;
+
+/// Blends a color onto the pixel at the given position, ignoring positions outside the image
+pub fn blend_pixel(image: &mut RgbaImage, x: i64, y: i64, color: Rgba<u8>) {
+    if x < 0 || y < 0 || x >= image.width() as i64 || y >= image.height() as i64 {
+        return;
+    }
+    let pixel = image.get_pixel_mut(x as u32, y as u32);
+    *pixel = blend(*pixel, color);
+}
+
+// Composites a color over a pixel, with the 'source over' operator
+fn blend(pixel: Rgba<u8>, color: Rgba<u8>) -> Rgba<u8> {
+    match color[3] {
+        255 => return color,
+        0 => return pixel,
+        _ => {}
+    }
+
+    let source_alpha = color[3] as f64 / 255.0;
+    let dest_alpha = pixel[3] as f64 / 255.0 * (1.0 - source_alpha);
+    let alpha = source_alpha + dest_alpha;
+
+    let channel = |i: usize| {
+        let value = (color[i] as f64 * source_alpha + pixel[i] as f64 * dest_alpha) / alpha;
+        value.round() as u8
+    };
+
+    Rgba([
+        channel(0),
+        channel(1),
+        channel(2),
+        (alpha * 255.0).round() as u8,
+    ])
+}
+
+/// Draws a line between two points, using Bresenham's line algorithm
+pub fn line(image: &mut RgbaImage, start: (i64, i64), end: (i64, i64), color: Rgba<u8>) {
+    let (mut x, mut y) = start;
+    let dx = (end.0 - x).abs();
+    let dy = -(end.1 - y).abs();
+    let step_x = if x < end.0 { 1 } else { -1 };
+    let step_y = if y < end.1 { 1 } else { -1 };
+    let mut error = dx + dy;
+
+    loop {
+        blend_pixel(image, x, y, color);
+        if (x, y) == end {
+            break;
+        }
+        let e2 = 2 * error;
+        if e2 >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if e2 <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+}
+
+/// Draws the outline of a rectangle
+pub fn rect(image: &mut RgbaImage, x: i64, y: i64, width: i64, height: i64, color: Rgba<u8>) {
+    if width <= 0 || height <= 0 {
+        return;
+    }
+    let right = x + width - 1;
+    let bottom = y + height - 1;
+
+    for column in x..=right {
+        blend_pixel(image, column, y, color);
+        if bottom != y {
+            blend_pixel(image, column, bottom, color);
+        }
+    }
+    // The corners have already been drawn
+    for row in y + 1..bottom {
+        blend_pixel(image, x, row, color);
+        if right != x {
+            blend_pixel(image, right, row, color);
+        }
+    }
+}
+
+/// Fills a rectangle with a color
+pub fn fill_rect(image: &mut RgbaImage, x: i64, y: i64, width: i64, height: i64, color: Rgba<u8>) {
+    let left = x.max(0);
+    let top = y.max(0);
+    let right = (x + width).min(image.width() as i64);
+    let bottom = (y + height).min(image.height() as i64);
+
+    for row in top..bottom {
+        for column in left..right {
+            blend_pixel(image, column, row, color);
+        }
+    }
+}
+
+/// Draws text with the built-in 8x8 pixel font, scaled by a whole number
+///
+/// Newlines start a new line of text, and characters outside of the ASCII range are left blank.
+pub fn text(image: &mut RgbaImage, x: i64, y: i64, text: &str, scale: i64, color: Rgba<u8>) {
+    let mut glyph_x = x;
+    let mut glyph_y = y;
+
+    for c in text.chars() {
+        if c == '\n' {
+            glyph_x = x;
+            glyph_y += LINE_HEIGHT * scale;
+            continue;
+        }
+
+        if let Some(glyph) = BASIC_LEGACY.get(c as usize) {
+            for (row, bits) in glyph.iter().enumerate() {
+                for column in 0..GLYPH_SIZE {
+                    // The lowest bit in each row is the leftmost pixel
+                    if bits & (1 << column) != 0 {
+                        fill_rect(
+                            image,
+                            glyph_x + column * scale,
+                            glyph_y + row as i64 * scale,
+                            scale,
+                            scale,
+                            color,
+                        );
+                    }
+                }
+            }
+        }
+
+        glyph_x += GLYPH_SIZE * scale;
+    }
+}
+
+/// Returns the size in pixels of the given text when drawn with [text]
+pub fn text_size(text: &str, scale: i64) -> (i64, i64) {
+    let mut width = 0;
+    let mut lines = 0;
+    for line in text.split('\n') {
+        width = width.max(line.chars().count() as i64 * GLYPH_SIZE * scale);
+        lines += 1;
+    }
+    let height = (lines - 1) * LINE_HEIGHT * scale + GLYPH_SIZE * scale;
+    (width, height)
+}
@@ -0,0 +1,447 @@
+//! A Koto language module for loading and manipulating images
+
+mod draw;
+
+use image::{
+    imageops, imageops::FilterType, DynamicImage, ImageFormat, ImageReader, Rgba, RgbaImage,
+};
+use koto_runtime::{derive::*, prelude::*, Result};
+use std::path::Path;
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("image");
+
+    result.add_fn("load", |ctx| match ctx.args() {
+        [KValue::Str(path)] => Image::load(Path::new(path.as_str())).map(KValue::from),
+        unexpected => type_error_with_slice("a path String", unexpected),
+    });
+
+    result.add_fn("new", |ctx| {
+        let (width, height, color) = match ctx.args() {
+            [KValue::Number(width), KValue::Number(height)] => (width, height, Rgba([0, 0, 0, 0])),
+            [KValue::Number(width), KValue::Number(height), color] => {
+                (width, height, color_from_value(color)?)
+            }
+            unexpected => {
+                return type_error_with_slice(
+                    "a width and height, and an optional color",
+                    unexpected,
+                )
+            }
+        };
+
+        if *width < 1 || *height < 1 {
+            return runtime_error!("Expected a width and height of at least 1");
+        }
+
+        Ok(Image::from(RgbaImage::from_pixel(width.into(), height.into(), color)).into())
+    });
+
+    result.add_fn("text_size", |ctx| {
+        let (text, scale) = match ctx.args() {
+            [KValue::Str(text)] => (text, 1),
+            [KValue::Str(text), KValue::Number(scale)] if *scale >= 1 => (text, scale.into()),
+            unexpected => {
+                return type_error_with_slice("a String, and an optional scale", unexpected)
+            }
+        };
+
+        let (width, height) = draw::text_size(text, scale);
+        Ok(KValue::Tuple(vec![width.into(), height.into()].into()))
+    });
+
+    result
+}
+
+/// An RGBA image with 8 bits per channel
+///
+/// Drawing operations modify the image in place, while transformations like resizing produce
+/// new images.
+#[derive(Clone, KotoType, KotoCopy)]
+pub struct Image(RgbaImage);
+
+#[koto_impl(runtime = koto_runtime)]
+impl Image {
+    /// Loads an image from a file, with the format detected from the file's contents
+    pub fn load(path: &Path) -> Result<Self> {
+        let image = ImageReader::open(path)
+            .and_then(|reader| reader.with_guessed_format())
+            .map_err(|e| format!("Failed to open '{}': {e}", path.display()))?
+            .decode()
+            .map_err(|e| format!("Failed to load '{}': {e}", path.display()))?;
+        Ok(Self(image.to_rgba8()))
+    }
+
+    /// Saves the image to a file, with the format chosen by the path's extension
+    ///
+    /// JPEG doesn't support transparency, so the alpha channel is dropped when saving JPEG files.
+    pub fn save_to_path(&self, path: &Path) -> Result<()> {
+        let result = match ImageFormat::from_path(path) {
+            Ok(ImageFormat::Png) => self.0.save_with_format(path, ImageFormat::Png),
+            Ok(ImageFormat::Jpeg) => DynamicImage::ImageRgba8(self.0.clone())
+                .to_rgb8()
+                .save_with_format(path, ImageFormat::Jpeg),
+            _ => {
+                return runtime_error!(
+                    "Expected a path with a .png, .jpg, or .jpeg extension, found '{}'",
+                    path.display()
+                )
+            }
+        };
+
+        result.map_err(|e| format!("Failed to save '{}': {e}", path.display()).into())
+    }
+
+    // Modifies the image in place, returning the image
+    fn modify(ctx: MethodContext<Self>, f: impl FnOnce(&mut RgbaImage)) -> Result<KValue> {
+        f(&mut ctx.instance_mut()?.0);
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn crop(&self, args: &[KValue]) -> Result<KValue> {
+        let [x, y, width, height] = match args {
+            [KValue::Number(x), KValue::Number(y), KValue::Number(w), KValue::Number(h)] => {
+                [x, y, w, h].map(i64::from)
+            }
+            unexpected => {
+                return type_error_with_slice(
+                    "a position, followed by a width and height",
+                    unexpected,
+                )
+            }
+        };
+
+        if x < 0
+            || y < 0
+            || width < 1
+            || height < 1
+            || x + width > self.0.width() as i64
+            || y + height > self.0.height() as i64
+        {
+            return runtime_error!(
+                "The crop area ({x}, {y}, {width}, {height}) is outside of the image's bounds ({}x{})",
+                self.0.width(),
+                self.0.height()
+            );
+        }
+
+        let cropped = imageops::crop_imm(&self.0, x as u32, y as u32, width as u32, height as u32);
+        Ok(Self(cropped.to_image()).into())
+    }
+
+    #[koto_method]
+    fn draw_line(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (start, end, color) = match ctx.args {
+            [KValue::Number(x1), KValue::Number(y1), KValue::Number(x2), KValue::Number(y2), color] => {
+                (
+                    (x1.into(), y1.into()),
+                    (x2.into(), y2.into()),
+                    color_from_value(color)?,
+                )
+            }
+            unexpected => {
+                return type_error_with_slice("start and end positions, and a color", unexpected)
+            }
+        };
+        Self::modify(ctx, |image| draw::line(image, start, end, color))
+    }
+
+    #[koto_method]
+    fn draw_rect(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (x, y, width, height, color) = rect_args(ctx.args)?;
+        Self::modify(ctx, |image| draw::rect(image, x, y, width, height, color))
+    }
+
+    #[koto_method]
+    fn draw_text(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (x, y, text, color, scale) = match ctx.args {
+            [KValue::Number(x), KValue::Number(y), KValue::Str(text), color] => {
+                (x, y, text.clone(), color_from_value(color)?, 1)
+            }
+            [KValue::Number(x), KValue::Number(y), KValue::Str(text), color, KValue::Number(scale)]
+                if *scale >= 1 =>
+            {
+                (x, y, text.clone(), color_from_value(color)?, scale.into())
+            }
+            unexpected => {
+                return type_error_with_slice(
+                    "a position, a String, a color, and an optional scale",
+                    unexpected,
+                )
+            }
+        };
+        let (x, y) = (x.into(), y.into());
+        Self::modify(ctx, |image| draw::text(image, x, y, &text, scale, color))
+    }
+
+    #[koto_method]
+    fn fill(ctx: MethodContext<Self>) -> Result<KValue> {
+        let color = match ctx.args {
+            [color] => color_from_value(color)?,
+            unexpected => return type_error_with_slice("a color", unexpected),
+        };
+        Self::modify(ctx, |image| {
+            for pixel in image.pixels_mut() {
+                *pixel = color;
+            }
+        })
+    }
+
+    #[koto_method]
+    fn fill_rect(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (x, y, width, height, color) = rect_args(ctx.args)?;
+        Self::modify(ctx, |image| {
+            draw::fill_rect(image, x, y, width, height, color)
+        })
+    }
+
+    #[koto_method]
+    fn flip_horizontal(&self) -> KValue {
+        Self(imageops::flip_horizontal(&self.0)).into()
+    }
+
+    #[koto_method]
+    fn flip_vertical(&self) -> KValue {
+        Self(imageops::flip_vertical(&self.0)).into()
+    }
+
+    #[koto_method]
+    fn get_pixel(&self, args: &[KValue]) -> Result<KValue> {
+        let (x, y) = self.pixel_position(args)?;
+        let Rgba(channels) = self.0.get_pixel(x, y);
+        Ok(KValue::Tuple(
+            channels
+                .iter()
+                .map(|c| KValue::from(*c))
+                .collect::<Vec<_>>()
+                .into(),
+        ))
+    }
+
+    #[koto_method]
+    fn height(&self) -> KValue {
+        self.0.height().into()
+    }
+
+    #[koto_method]
+    fn resize(&self, args: &[KValue]) -> Result<KValue> {
+        let (width, height, filter) = match args {
+            [KValue::Number(width), KValue::Number(height)] => {
+                (width, height, FilterType::Triangle)
+            }
+            [KValue::Number(width), KValue::Number(height), KValue::Str(filter)] => {
+                (width, height, filter_from_str(filter)?)
+            }
+            unexpected => {
+                return type_error_with_slice(
+                    "a width and height, and an optional filter",
+                    unexpected,
+                )
+            }
+        };
+
+        if *width < 1 || *height < 1 {
+            return runtime_error!("Expected a width and height of at least 1");
+        }
+
+        Ok(Self(imageops::resize(
+            &self.0,
+            width.into(),
+            height.into(),
+            filter,
+        ))
+        .into())
+    }
+
+    #[koto_method]
+    fn rotate(&self, args: &[KValue]) -> Result<KValue> {
+        let degrees = match args {
+            [KValue::Number(degrees)] if degrees.is_i64() => i64::from(degrees),
+            unexpected => return type_error_with_slice("a whole Number of degrees", unexpected),
+        };
+
+        let rotated = match degrees.rem_euclid(360) {
+            0 => self.0.clone(),
+            90 => imageops::rotate90(&self.0),
+            180 => imageops::rotate180(&self.0),
+            270 => imageops::rotate270(&self.0),
+            _ => return runtime_error!("Expected a multiple of 90 degrees, found {degrees}"),
+        };
+
+        Ok(Self(rotated).into())
+    }
+
+    #[koto_method]
+    fn save(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Str(path)] => self
+                .save_to_path(Path::new(path.as_str()))
+                .map(|_| KValue::Null),
+            unexpected => type_error_with_slice("a path String", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn set_pixel(ctx: MethodContext<Self>) -> Result<KValue> {
+        let ((x, y), color) = match ctx.args {
+            [x, y, color] => (
+                ctx.instance()?.pixel_position(&[x.clone(), y.clone()])?,
+                color_from_value(color)?,
+            ),
+            unexpected => return type_error_with_slice("a position and a color", unexpected),
+        };
+        Self::modify(ctx, |image| image.put_pixel(x, y, color))
+    }
+
+    #[koto_method]
+    fn size(&self) -> KValue {
+        KValue::Tuple(vec![self.0.width().into(), self.0.height().into()].into())
+    }
+
+    #[koto_method]
+    fn thumbnail(&self, args: &[KValue]) -> Result<KValue> {
+        let (max_width, max_height) = match args {
+            [KValue::Number(width), KValue::Number(height)] if *width >= 1 && *height >= 1 => {
+                (width.into(), height.into())
+            }
+            unexpected => return type_error_with_slice("a maximum width and height", unexpected),
+        };
+
+        let thumbnail = DynamicImage::ImageRgba8(self.0.clone()).thumbnail(max_width, max_height);
+        Ok(Self(thumbnail.to_rgba8()).into())
+    }
+
+    #[koto_method]
+    fn width(&self) -> KValue {
+        self.0.width().into()
+    }
+
+    // Gets a pixel position from the arguments, checking that it's within the image's bounds
+    fn pixel_position(&self, args: &[KValue]) -> Result<(u32, u32)> {
+        match args {
+            [KValue::Number(x), KValue::Number(y)] => {
+                let (x, y) = (i64::from(x), i64::from(y));
+                if x < 0 || y < 0 || x >= self.0.width() as i64 || y >= self.0.height() as i64 {
+                    runtime_error!(
+                        "The position ({x}, {y}) is outside of the image's bounds ({}x{})",
+                        self.0.width(),
+                        self.0.height()
+                    )
+                } else {
+                    Ok((x as u32, y as u32))
+                }
+            }
+            unexpected => type_error_with_slice("an x and y position", unexpected),
+        }
+    }
+}
+
+impl KotoObject for Image {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!("Image({}x{})", self.0.width(), self.0.height()));
+        Ok(())
+    }
+}
+
+impl From<RgbaImage> for Image {
+    fn from(image: RgbaImage) -> Self {
+        Self(image)
+    }
+}
+
+impl From<Image> for KValue {
+    fn from(image: Image) -> Self {
+        KObject::from(image).into()
+    }
+}
+
+/// Converts a Koto value into a color
+///
+/// Colors are either tuples or lists of 3 or 4 numbers in the range 0 to 255,
+/// or hex strings in the form `#rgb`, `#rrggbb`, or `#rrggbbaa`.
+pub fn color_from_value(value: &KValue) -> Result<Rgba<u8>> {
+    let channels = match value {
+        KValue::Tuple(channels) => channels.to_vec(),
+        KValue::List(channels) => channels.data().to_vec(),
+        KValue::Str(hex) => return color_from_hex(hex),
+        unexpected => return type_error("a color", unexpected),
+    };
+
+    let channel = |value: &KValue| match value {
+        KValue::Number(n) => Ok(f64::from(n).round().clamp(0.0, 255.0) as u8),
+        unexpected => type_error("a Number as color channel", unexpected),
+    };
+
+    match channels.as_slice() {
+        [r, g, b] => Ok(Rgba([channel(r)?, channel(g)?, channel(b)?, 255])),
+        [r, g, b, a] => Ok(Rgba([channel(r)?, channel(g)?, channel(b)?, channel(a)?])),
+        unexpected => type_error_with_slice("3 or 4 color channels", unexpected),
+    }
+}
+
+fn color_from_hex(hex: &str) -> Result<Rgba<u8>> {
+    let digits = hex.strip_prefix('#').unwrap_or(hex);
+    let invalid = || runtime_error!("Invalid hex color '{hex}'");
+
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return invalid();
+    }
+
+    let channels: Vec<u8> = match digits.len() {
+        // Short hex colors have each digit repeated, e.g. #f80 is #ff8800
+        3 => digits
+            .chars()
+            .map(|c| {
+                let n = c.to_digit(16).unwrap_or_default() as u8;
+                n * 16 + n
+            })
+            .collect(),
+        6 | 8 => (0..digits.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap_or_default())
+            .collect(),
+        _ => return invalid(),
+    };
+
+    match channels.as_slice() {
+        [r, g, b] => Ok(Rgba([*r, *g, *b, 255])),
+        [r, g, b, a] => Ok(Rgba([*r, *g, *b, *a])),
+        _ => invalid(),
+    }
+}
+
+fn filter_from_str(filter: &str) -> Result<FilterType> {
+    let result = match filter {
+        "nearest" => FilterType::Nearest,
+        "linear" => FilterType::Triangle,
+        "cubic" => FilterType::CatmullRom,
+        "gaussian" => FilterType::Gaussian,
+        "lanczos" => FilterType::Lanczos3,
+        other => {
+            return runtime_error!(
+                "Unknown filter '{other}', \
+                 expected one of 'nearest', 'linear', 'cubic', 'gaussian', or 'lanczos'"
+            )
+        }
+    };
+    Ok(result)
+}
+
+fn rect_args(args: &[KValue]) -> Result<(i64, i64, i64, i64, Rgba<u8>)> {
+    match args {
+        [KValue::Number(x), KValue::Number(y), KValue::Number(width), KValue::Number(height), color] => {
+            Ok((
+                x.into(),
+                y.into(),
+                width.into(),
+                height.into(),
+                color_from_value(color)?,
+            ))
+        }
+        unexpected => type_error_with_slice(
+            "a position, followed by a width and height, and a color",
+            unexpected,
+        ),
+    }
+}
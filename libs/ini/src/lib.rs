@@ -0,0 +1,132 @@
+//! A Koto language module for working with INI data
+
+use koto_runtime::{prelude::*, Result};
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("ini");
+
+    result.add_fn("from_string", |ctx| match ctx.args() {
+        [KValue::Str(s)] => match parse_ini(s) {
+            Ok(result) => Ok(result.into()),
+            Err(e) => runtime_error!("ini.from_string: {e}"),
+        },
+        unexpected => type_error_with_slice("a String as argument", unexpected),
+    });
+
+    result.add_fn("to_string", |ctx| match ctx.args() {
+        [KValue::Map(map)] => {
+            let map = map.clone();
+            Ok(write_ini(ctx.vm, &map)?.into())
+        }
+        unexpected => type_error_with_slice("a Map as argument", unexpected),
+    });
+
+    result
+}
+
+/// Parses INI data into a map
+///
+/// Entries that appear before the first section are added to the top level of the map,
+/// while entries in sections are added to nested maps.
+pub fn parse_ini(input: &str) -> std::result::Result<KMap, String> {
+    let result = KMap::new();
+    let mut section: Option<KMap> = None;
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        let line_number = i + 1;
+
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[') {
+            let Some(name) = name.strip_suffix(']') else {
+                return Err(format!("Unterminated section header on line {line_number}"));
+            };
+            let name = name.trim();
+
+            // Repeated sections are merged together
+            let section_map = match result.get(name) {
+                Some(KValue::Map(existing)) => existing,
+                _ => {
+                    let new_section = KMap::new();
+                    result.insert(name, new_section.clone());
+                    new_section
+                }
+            };
+            section = Some(section_map);
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(['=', ':']) else {
+            return Err(format!("Expected a key/value pair on line {line_number}"));
+        };
+
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(format!("Missing key on line {line_number}"));
+        }
+
+        let value = unquote(value.trim());
+        section.as_ref().unwrap_or(&result).insert(key, value);
+    }
+
+    Ok(result)
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(unquoted) = value
+            .strip_prefix(quote)
+            .and_then(|value| value.strip_suffix(quote))
+        {
+            return unquoted;
+        }
+    }
+    value
+}
+
+/// Writes a map as INI data
+///
+/// Nested maps are written as sections, which can't contain further nested maps.
+pub fn write_ini(vm: &mut KotoVm, map: &KMap) -> Result<String> {
+    let mut result = String::new();
+    let mut sections = Vec::new();
+
+    for (key, value) in map.data().iter() {
+        match value {
+            KValue::Map(section) => sections.push((key.clone(), section.clone())),
+            _ => write_entry(vm, &mut result, key, value)?,
+        }
+    }
+
+    for (name, section) in sections {
+        if !result.is_empty() {
+            result.push('\n');
+        }
+        result.push_str(&format!("[{name}]\n"));
+
+        for (key, value) in section.data().iter() {
+            if matches!(value, KValue::Map(_)) {
+                return runtime_error!(
+                    "ini.to_string: Sections can't contain nested maps ('{name}.{key}')"
+                );
+            }
+            write_entry(vm, &mut result, key, value)?;
+        }
+    }
+
+    Ok(result)
+}
+
+fn write_entry(vm: &mut KotoVm, output: &mut String, key: &ValueKey, value: &KValue) -> Result<()> {
+    let value = match value {
+        KValue::Str(s) => s.to_string(),
+        KValue::Null => String::new(),
+        KValue::Bool(_) | KValue::Number(_) => vm.value_to_string(value)?,
+        unexpected => return type_error("a String, Number, Bool, or Map as INI value", unexpected),
+    };
+    output.push_str(&format!("{key} = {value}\n"));
+    Ok(())
+}
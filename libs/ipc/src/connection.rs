@@ -0,0 +1,180 @@
+use koto_json::json_value_to_koto_value;
+use koto_runtime::{derive::*, prelude::*, PtrMut, Result};
+use koto_serialize::SerializableValue;
+use std::{
+    io::{self, Read, Write},
+    os::unix::net::UnixStream,
+    path::Path,
+    time::Duration,
+};
+
+// Messages larger than this are rejected, guarding against corrupted length prefixes
+const MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024;
+
+/// A connection to another Koto process
+///
+/// Messages are sent with a 4 byte big-endian length prefix, followed by the message's value
+/// serialized as JSON.
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "Connection")]
+pub struct Connection(PtrMut<Option<UnixStream>>);
+
+#[koto_impl(runtime = koto_runtime)]
+impl Connection {
+    /// Connects to the socket at the given path
+    pub fn connect(path: &Path) -> Result<Self> {
+        match UnixStream::connect(path) {
+            Ok(stream) => Ok(Self::from_stream(stream)),
+            Err(e) => runtime_error!("failed to connect to '{}': {e}", path.display()),
+        }
+    }
+
+    pub(crate) fn from_stream(stream: UnixStream) -> Self {
+        Self(PtrMut::from(Some(stream)))
+    }
+
+    /// Serializes and sends a value
+    pub fn send_value(&self, value: &KValue) -> Result<()> {
+        let message = match serde_json::to_vec(&SerializableValue(value)) {
+            Ok(message) => message,
+            Err(e) => return runtime_error!("failed to serialize message: {e}"),
+        };
+        if message.len() > MAX_MESSAGE_SIZE {
+            return runtime_error!("the message is too large ({} bytes)", message.len());
+        }
+
+        let mut stream = self.0.borrow_mut();
+        let Some(stream) = stream.as_mut() else {
+            return runtime_error!("the connection is closed");
+        };
+        let result = stream
+            .write_all(&(message.len() as u32).to_be_bytes())
+            .and_then(|_| stream.write_all(&message))
+            .and_then(|_| stream.flush());
+        match result {
+            Ok(()) => Ok(()),
+            Err(e) => runtime_error!("failed to send message: {e}"),
+        }
+    }
+
+    /// Waits for a value to be received
+    ///
+    /// `None` is returned if the other end of the connection has been closed.
+    pub fn receive_value(&self) -> Result<Option<KValue>> {
+        let mut stream = self.0.borrow_mut();
+        let Some(stream) = stream.as_mut() else {
+            return runtime_error!("the connection is closed");
+        };
+
+        let mut size = [0; 4];
+        match stream.read_exact(&mut size) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return receive_error(e),
+        }
+        let size = u32::from_be_bytes(size) as usize;
+        if size > MAX_MESSAGE_SIZE {
+            return runtime_error!("the received message is too large ({size} bytes)");
+        }
+
+        let mut message = vec![0; size];
+        if let Err(e) = stream.read_exact(&mut message) {
+            return receive_error(e);
+        }
+        match serde_json::from_slice(&message) {
+            Ok(value) => json_value_to_koto_value(&value).map(Some),
+            Err(e) => runtime_error!("failed to deserialize message: {e}"),
+        }
+    }
+
+    /// Closes the connection
+    pub fn close_connection(&self) {
+        if let Some(stream) = self.0.borrow_mut().take() {
+            let _ = stream.shutdown(std::net::Shutdown::Both);
+        }
+    }
+
+    #[koto_method]
+    fn close(&self) -> KValue {
+        self.close_connection();
+        KValue::Null
+    }
+
+    #[koto_method]
+    fn is_open(&self) -> KValue {
+        self.0.borrow().is_some().into()
+    }
+
+    #[koto_method]
+    fn receive(&self) -> Result<KValue> {
+        Ok(self.receive_value()?.unwrap_or_default())
+    }
+
+    #[koto_method]
+    fn request(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [value] => {
+                self.send_value(value)?;
+                match self.receive_value()? {
+                    Some(response) => Ok(response),
+                    None => runtime_error!("the connection was closed before a response arrived"),
+                }
+            }
+            unexpected => type_error_with_slice("a value to send", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn send(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [value] => {
+                ctx.instance()?.send_value(value)?;
+                ctx.instance_result()
+            }
+            unexpected => type_error_with_slice("a value to send", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn set_timeout(ctx: MethodContext<Self>) -> Result<KValue> {
+        let timeout = match ctx.args {
+            [KValue::Null] => None,
+            [KValue::Number(seconds)] if f64::from(seconds) > 0.0 => {
+                Some(Duration::from_secs_f64(seconds.into()))
+            }
+            unexpected => {
+                return type_error_with_slice("a positive Number of seconds, or null", unexpected)
+            }
+        };
+
+        let this = ctx.instance()?;
+        if let Some(stream) = this.0.borrow().as_ref() {
+            if let Err(e) = stream.set_read_timeout(timeout) {
+                return runtime_error!("failed to set the timeout: {e}");
+            }
+        }
+        ctx.instance_result()
+    }
+}
+
+impl KotoObject for Connection {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append("Connection");
+        Ok(())
+    }
+}
+
+impl From<Connection> for KValue {
+    fn from(connection: Connection) -> Self {
+        KObject::from(connection).into()
+    }
+}
+
+fn receive_error<T>(error: io::Error) -> Result<T> {
+    match error.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => {
+            runtime_error!("timed out while waiting for a message")
+        }
+        _ => runtime_error!("failed to receive message: {error}"),
+    }
+}
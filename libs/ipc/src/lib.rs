@@ -0,0 +1,79 @@
+//! A Koto language module for messaging between Koto processes
+//!
+//! Messages are exchanged via Unix domain sockets, which are currently only supported on Unix-like
+//! platforms.
+
+#[cfg(unix)]
+mod connection;
+#[cfg(unix)]
+mod listener;
+
+#[cfg(unix)]
+pub use connection::Connection;
+#[cfg(unix)]
+pub use listener::Listener;
+
+use koto_runtime::prelude::*;
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("ipc");
+
+    result.add_fn("connect", |ctx| match ctx.args() {
+        [KValue::Str(path)] => connect(path),
+        unexpected => type_error_with_slice("a socket path String", unexpected),
+    });
+
+    result.add_fn("listen", |ctx| match ctx.args() {
+        [KValue::Str(path)] => listen(path),
+        unexpected => type_error_with_slice("a socket path String", unexpected),
+    });
+
+    result.add_fn("request", |ctx| match ctx.args() {
+        [KValue::Str(path), value] => request(path, value),
+        unexpected => type_error_with_slice("a socket path String and a value", unexpected),
+    });
+
+    result
+}
+
+#[cfg(unix)]
+fn connect(path: &str) -> koto_runtime::Result<KValue> {
+    Connection::connect(path.as_ref()).map(KValue::from)
+}
+
+#[cfg(unix)]
+fn listen(path: &str) -> koto_runtime::Result<KValue> {
+    Listener::bind(path.as_ref()).map(KValue::from)
+}
+
+#[cfg(unix)]
+fn request(path: &str, value: &KValue) -> koto_runtime::Result<KValue> {
+    let connection = Connection::connect(path.as_ref())?;
+    connection.send_value(value)?;
+    let response = connection.receive_value()?;
+    connection.close_connection();
+    match response {
+        Some(response) => Ok(response),
+        None => runtime_error!("the connection was closed before a response arrived"),
+    }
+}
+
+#[cfg(not(unix))]
+fn connect(_path: &str) -> koto_runtime::Result<KValue> {
+    unsupported()
+}
+
+#[cfg(not(unix))]
+fn listen(_path: &str) -> koto_runtime::Result<KValue> {
+    unsupported()
+}
+
+#[cfg(not(unix))]
+fn request(_path: &str, _value: &KValue) -> koto_runtime::Result<KValue> {
+    unsupported()
+}
+
+#[cfg(not(unix))]
+fn unsupported() -> koto_runtime::Result<KValue> {
+    runtime_error!("ipc is only supported on Unix-like platforms")
+}
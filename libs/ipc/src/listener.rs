@@ -0,0 +1,143 @@
+use crate::Connection;
+use koto_runtime::{derive::*, prelude::*, PtrMut, Result};
+use std::{
+    fs,
+    os::unix::{
+        fs::FileTypeExt,
+        net::{UnixListener, UnixStream},
+    },
+    path::{Path, PathBuf},
+};
+
+/// A socket that accepts connections from other Koto processes
+///
+/// The socket's file is removed when the listener is closed or dropped.
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "Listener")]
+pub struct Listener(PtrMut<ListenerState>);
+
+struct ListenerState {
+    listener: Option<UnixListener>,
+    path: PathBuf,
+}
+
+impl ListenerState {
+    fn close(&mut self) {
+        if self.listener.take().is_some() {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+impl Drop for ListenerState {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Listener {
+    /// Creates a socket at the given path and starts listening for connections
+    ///
+    /// A socket that was left behind by a process that's no longer listening is replaced,
+    /// while an error is returned if another process is listening with the socket,
+    /// or if the path refers to something other than a socket.
+    pub fn bind(path: &Path) -> Result<Self> {
+        if let Ok(metadata) = fs::symlink_metadata(path) {
+            if !metadata.file_type().is_socket() {
+                return runtime_error!("'{}' already exists", path.display());
+            }
+            if UnixStream::connect(path).is_ok() {
+                return runtime_error!("'{}' is already in use", path.display());
+            }
+            let _ = fs::remove_file(path);
+        }
+
+        match UnixListener::bind(path) {
+            Ok(listener) => Ok(Self(PtrMut::from(ListenerState {
+                listener: Some(listener),
+                path: path.to_path_buf(),
+            }))),
+            Err(e) => runtime_error!("failed to listen on '{}': {e}", path.display()),
+        }
+    }
+
+    /// Waits for a connection
+    pub fn accept_connection(&self) -> Result<Connection> {
+        let state = self.0.borrow();
+        let Some(listener) = state.listener.as_ref() else {
+            return runtime_error!("the listener is closed");
+        };
+        match listener.accept() {
+            Ok((stream, _)) => Ok(Connection::from_stream(stream)),
+            Err(e) => runtime_error!("failed to accept a connection: {e}"),
+        }
+    }
+
+    /// Returns true if the listener hasn't been closed
+    pub fn is_listening(&self) -> bool {
+        self.0.borrow().listener.is_some()
+    }
+
+    #[koto_method]
+    fn accept(&self) -> Result<KValue> {
+        self.accept_connection().map(KValue::from)
+    }
+
+    #[koto_method]
+    fn close(&self) -> KValue {
+        self.0.borrow_mut().close();
+        KValue::Null
+    }
+
+    #[koto_method]
+    fn is_open(&self) -> KValue {
+        self.is_listening().into()
+    }
+
+    #[koto_method]
+    fn path(&self) -> KValue {
+        self.0.borrow().path.to_string_lossy().to_string().into()
+    }
+
+    #[koto_method]
+    fn serve(ctx: MethodContext<Self>) -> Result<KValue> {
+        let handler = match ctx.args {
+            [f] if f.is_callable() => f.clone(),
+            unexpected => return type_error_with_slice("a Function", unexpected),
+        };
+
+        let this = ctx.instance()?.clone();
+        let mut vm = ctx.vm.spawn_shared_vm();
+        let mut served = 0;
+
+        // Connections are handled one at a time until the listener is closed
+        while this.is_listening() {
+            let connection = this.accept_connection()?;
+            while let Some(request) = connection.receive_value()? {
+                let response = vm.call_function(handler.clone(), request)?;
+                connection.send_value(&response)?;
+                served += 1;
+                if !this.is_listening() {
+                    break;
+                }
+            }
+            connection.close_connection();
+        }
+
+        Ok(KValue::Number(served.into()))
+    }
+}
+
+impl KotoObject for Listener {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!("Listener({})", self.0.borrow().path.display()));
+        Ok(())
+    }
+}
+
+impl From<Listener> for KValue {
+    fn from(listener: Listener) -> Self {
+        KObject::from(listener).into()
+    }
+}
@@ -0,0 +1,116 @@
+#![cfg(unix)]
+
+use koto_runtime::{prelude::*, Result};
+use koto_test_utils::check_script_output_with_vm;
+use std::{
+    env,
+    os::unix::net::UnixListener,
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+fn socket_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("koto_ipc_{name}_{}.sock", std::process::id()))
+}
+
+fn run_script(path: &Path, script: &str, expected: KValue) -> Result<()> {
+    run_script_with_vm(KotoVm::default(), path, script, expected)
+}
+
+fn run_script_with_vm(vm: KotoVm, path: &Path, script: &str, expected: KValue) -> Result<()> {
+    let prelude = vm.prelude();
+    prelude.insert("ipc", koto_ipc::make_module());
+    prelude.insert("socket_path", path.to_string_lossy().as_ref());
+    check_script_output_with_vm(vm, script, expected)
+}
+
+#[test]
+fn serve_requests_from_another_vm() -> Result<()> {
+    let path = socket_path("serve");
+    let listener = koto_ipc::Listener::bind(&path)?;
+
+    let server = thread::spawn({
+        let path = path.clone();
+        move || {
+            let vm = KotoVm::default();
+            vm.prelude().insert("listener", listener);
+            let script = "
+listener.serve |request|
+  match request.command
+    'add' then request.a + request.b
+    'stop' then
+      listener.close()
+      'stopping'
+";
+            run_script_with_vm(vm, &path, script, 3.into())
+        }
+    });
+
+    let script = "
+x = ipc.request socket_path, {command: 'add', a: 1, b: 2}
+
+connection = ipc.connect socket_path
+y = connection.request {command: 'add', a: x, b: 10}
+z = connection.request {command: 'stop'}
+x, y, z
+";
+    let expected = KValue::Tuple(vec![3.into(), 13.into(), "stopping".into()].into());
+    let client = run_script(&path, script, expected);
+    server.join().expect("the server thread panicked")?;
+    client?;
+    assert!(!path.exists());
+    Ok(())
+}
+
+#[test]
+fn stale_socket_is_replaced() -> Result<()> {
+    let path = socket_path("stale");
+
+    // Dropping a std listener leaves its socket file behind
+    drop(UnixListener::bind(&path).unwrap());
+    assert!(path.exists());
+
+    let listener = koto_ipc::Listener::bind(&path)?;
+    assert!(listener.is_listening());
+    Ok(())
+}
+
+#[test]
+fn socket_in_use() -> Result<()> {
+    let path = socket_path("in_use");
+    let _listener = koto_ipc::Listener::bind(&path)?;
+    let script = "
+try
+  ipc.listen socket_path
+catch error
+  error.contains 'already in use'
+";
+    run_script(&path, script, true.into())
+}
+
+#[test]
+fn receive_timeout_from_silent_server() -> Result<()> {
+    let path = socket_path("timeout");
+    let listener = koto_ipc::Listener::bind(&path)?;
+
+    let script = "
+connection = ipc.connect(socket_path).set_timeout 0.05
+try
+  connection.receive()
+catch error
+  error
+";
+    let server = thread::spawn(move || {
+        let connection = listener.accept_connection().unwrap();
+        thread::sleep(Duration::from_millis(200));
+        drop(connection);
+    });
+    run_script(
+        &path,
+        script,
+        "timed out while waiting for a message".into(),
+    )?;
+    server.join().unwrap();
+    Ok(())
+}
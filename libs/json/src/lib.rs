@@ -1,7 +1,11 @@
 //! A Koto language module for working with JSON data
 
-use koto_runtime::{prelude::*, Result};
-use koto_serialize::SerializableValue;
+mod writer;
+
+pub use writer::JsonWriter;
+
+use koto_runtime::{core_lib::io::File, prelude::*, Result};
+use koto_serialize::{NonFiniteMode, SerializeOptions};
 use serde_json::Value as JsonValue;
 
 pub fn json_value_to_koto_value(value: &serde_json::Value) -> Result<KValue> {
@@ -55,13 +59,59 @@ pub fn make_module() -> KMap {
         unexpected => type_error_with_slice("a String as argument", unexpected),
     });
 
-    result.add_fn("to_string", |ctx| match ctx.args() {
-        [value] => match serde_json::to_string_pretty(&SerializableValue(value)) {
+    result.add_fn("to_string", |ctx| {
+        let (value, options) = serialize_args("json.to_string", ctx.args())?;
+        match serde_json::to_string_pretty(&options.serializable(value)) {
             Ok(result) => Ok(result.into()),
             Err(e) => runtime_error!("json.to_string: {e}"),
-        },
-        unexpected => type_error_with_slice("a Value as argument", unexpected),
+        }
+    });
+
+    result.add_fn("to_string_canonical", |ctx| {
+        let (value, mut options) = serialize_args("json.to_string_canonical", ctx.args())?;
+        options.canonical = true;
+        match serde_json::to_string(&options.serializable(value)) {
+            Ok(result) => Ok(result.into()),
+            Err(e) => runtime_error!("json.to_string_canonical: {e}"),
+        }
+    });
+
+    result.add_fn("writer", |ctx| match ctx.args() {
+        [KValue::Object(o)] if o.is_a::<File>() => {
+            Ok(JsonWriter::new(o.cast::<File>()?.inner().clone()).into())
+        }
+        unexpected => type_error_with_slice("a File", unexpected),
     });
 
     result
 }
+
+/// Makes the options used when serializing values to JSON
+///
+/// By default nulls and tuples are kept, while non-finite numbers and ranges produce an error.
+pub fn serialize_options(options: Option<&KMap>) -> Result<SerializeOptions> {
+    let mut result = SerializeOptions::default();
+    if let Some(options) = options {
+        result.update_from_map(options)?;
+    }
+    if result.nan == NonFiniteMode::Keep {
+        return runtime_error!("'keep' isn't supported for the 'nan' option");
+    }
+    Ok(result)
+}
+
+// Gets the value to serialize from a function's arguments, along with the serialization options
+fn serialize_args<'a>(fn_name: &str, args: &'a [KValue]) -> Result<(&'a KValue, SerializeOptions)> {
+    let (value, options) = match args {
+        [value] => (value, None),
+        [value, KValue::Map(options)] => (value, Some(options)),
+        unexpected => {
+            return type_error_with_slice(
+                "a Value as argument, with an optional Map of options",
+                unexpected,
+            )
+        }
+    };
+    let options = serialize_options(options).map_err(|e| format!("{fn_name}: {e}"))?;
+    Ok((value, options))
+}
@@ -0,0 +1,216 @@
+use koto_runtime::{derive::*, prelude::*, Ptr, PtrMut, Result};
+use koto_serialize::SerializableValue;
+
+/// A writer that serializes JSON data incrementally to a file
+///
+/// Values are written as they're provided, so large outputs can be produced without first
+/// building the complete structure in memory. The output matches the format used by
+/// `json.to_string`, followed by a newline.
+///
+/// The writer is shared between copies.
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "JsonWriter")]
+pub struct JsonWriter(PtrMut<WriterState>);
+
+#[koto_impl(runtime = koto_runtime)]
+impl JsonWriter {
+    /// Makes a new writer that writes to the provided file
+    pub fn new(file: Ptr<dyn KotoFile>) -> Self {
+        Self(
+            WriterState {
+                file,
+                containers: Vec::new(),
+                complete: false,
+            }
+            .into(),
+        )
+    }
+
+    /// Starts writing a map, which is written until a matching call to [Self::end]
+    pub fn begin_map(&self) -> Result<()> {
+        let mut state = self.0.borrow_mut();
+        state.start_value()?;
+        state.file.write(b"{")?;
+        state.containers.push(Container::Map {
+            empty: true,
+            has_key: false,
+        });
+        Ok(())
+    }
+
+    /// Starts writing a list, which is written until a matching call to [Self::end]
+    pub fn begin_list(&self) -> Result<()> {
+        let mut state = self.0.borrow_mut();
+        state.start_value()?;
+        state.file.write(b"[")?;
+        state.containers.push(Container::List { empty: true });
+        Ok(())
+    }
+
+    /// Writes the key for the next entry in the current map
+    pub fn key(&self, key: &str) -> Result<()> {
+        let mut state = self.0.borrow_mut();
+        let indent = state.indent();
+        let key = serde_json::to_string(key).map_err(|e| format!("json.writer: {e}"))?;
+        match state.containers.last_mut() {
+            Some(Container::Map { empty, has_key }) if !*has_key => {
+                let separator = if *empty { "" } else { "," };
+                *empty = false;
+                *has_key = true;
+                state
+                    .file
+                    .write(format!("{separator}\n{indent}{key}: ").as_bytes())
+            }
+            Some(Container::Map { .. }) => {
+                runtime_error!("json.writer: expected a value for the previous key")
+            }
+            _ => runtime_error!("json.writer: keys can only be written in maps"),
+        }
+    }
+
+    /// Writes a complete value
+    pub fn value(&self, value: &KValue) -> Result<()> {
+        let mut state = self.0.borrow_mut();
+        let json = serde_json::to_string_pretty(&SerializableValue(value))
+            .map_err(|e| format!("json.writer: {e}"))?;
+        // JSON strings can't contain newlines, so only the pretty-printer's line breaks are
+        // indented here.
+        let json = json.replace('\n', &format!("\n{}", state.indent()));
+        state.start_value()?;
+        state.file.write(json.as_bytes())?;
+        state.finish_value()
+    }
+
+    /// Finishes writing the current map or list
+    pub fn end(&self) -> Result<()> {
+        let mut state = self.0.borrow_mut();
+        let (empty, closing) = match state.containers.pop() {
+            Some(Container::Map { has_key: true, .. }) => {
+                return runtime_error!("json.writer: expected a value for the previous key")
+            }
+            Some(Container::Map { empty, .. }) => (empty, "}"),
+            Some(Container::List { empty }) => (empty, "]"),
+            None => return runtime_error!("json.writer: there's no map or list to end"),
+        };
+        if empty {
+            state.file.write(closing.as_bytes())?;
+        } else {
+            let indent = state.indent();
+            state
+                .file
+                .write(format!("\n{indent}{closing}").as_bytes())?;
+        }
+        state.finish_value()
+    }
+
+    #[koto_method(alias = "begin_list")]
+    fn begin_list_koto(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [] => ctx.instance()?.begin_list()?,
+            unexpected => return type_error_with_slice("no arguments", unexpected),
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method(alias = "begin_map")]
+    fn begin_map_koto(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [] => ctx.instance()?.begin_map()?,
+            unexpected => return type_error_with_slice("no arguments", unexpected),
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method(alias = "end")]
+    fn end_koto(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [] => ctx.instance()?.end()?,
+            unexpected => return type_error_with_slice("no arguments", unexpected),
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method(alias = "key")]
+    fn key_koto(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [KValue::Str(key)] => ctx.instance()?.key(key)?,
+            unexpected => return type_error_with_slice("a String", unexpected),
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method(alias = "value")]
+    fn value_koto(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [value] => ctx.instance()?.value(value)?,
+            unexpected => return type_error_with_slice("a single value", unexpected),
+        }
+        ctx.instance_result()
+    }
+}
+
+impl KotoObject for JsonWriter {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(Self::type_static());
+        Ok(())
+    }
+}
+
+impl From<JsonWriter> for KValue {
+    fn from(writer: JsonWriter) -> Self {
+        KObject::from(writer).into()
+    }
+}
+
+struct WriterState {
+    file: Ptr<dyn KotoFile>,
+    containers: Vec<Container>,
+    // Set to true once a complete top-level value has been written
+    complete: bool,
+}
+
+enum Container {
+    Map { empty: bool, has_key: bool },
+    List { empty: bool },
+}
+
+impl WriterState {
+    // Prepares the output for a value, which is either a map entry's value, a list entry,
+    // or the top-level value
+    fn start_value(&mut self) -> Result<()> {
+        let indent = self.indent();
+        match self.containers.last_mut() {
+            Some(Container::Map { has_key, .. }) => {
+                if *has_key {
+                    *has_key = false;
+                    Ok(())
+                } else {
+                    runtime_error!("json.writer: expected a key before the value")
+                }
+            }
+            Some(Container::List { empty }) => {
+                let separator = if *empty { "" } else { "," };
+                *empty = false;
+                self.file.write(format!("{separator}\n{indent}").as_bytes())
+            }
+            None if self.complete => {
+                runtime_error!("json.writer: a complete value has already been written")
+            }
+            None => Ok(()),
+        }
+    }
+
+    // The output is ended with a newline once the top-level value has been written
+    fn finish_value(&mut self) -> Result<()> {
+        if self.containers.is_empty() {
+            self.complete = true;
+            self.file.write(b"\n")
+        } else {
+            Ok(())
+        }
+    }
+
+    fn indent(&self) -> String {
+        "  ".repeat(self.containers.len())
+    }
+}
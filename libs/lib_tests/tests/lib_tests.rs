@@ -12,13 +12,48 @@ fn run_script(script: &str, path: &Path, should_fail_at_runtime: bool) {
     koto.set_script_path(Some(path)).unwrap();
 
     let prelude = koto.prelude();
+    prelude.insert("archive", koto_archive::make_module());
+    prelude.insert("cache", koto_cache::make_module());
+    prelude.insert("canvas", koto_canvas::make_module());
     prelude.insert("color", koto_color::make_module());
+    prelude.insert("containers", koto_containers::make_module());
+    prelude.insert("diff", koto_diff::make_module());
+    prelude.insert("ease", koto_ease::make_module());
+    prelude.insert("fake", koto_fake::make_module());
+    prelude.insert("fsm", koto_fsm::make_module());
+    prelude.insert("fuzzy", koto_fuzzy::make_module());
     prelude.insert("geometry", koto_geometry::make_module());
+    prelude.insert("i18n", koto_i18n::make_module());
+    prelude.insert("image", koto_image::make_module());
+    prelude.insert("ini", koto_ini::make_module());
+    prelude.insert("ipc", koto_ipc::make_module());
     prelude.insert("json", koto_json::make_module());
+    prelude.insert("locale", koto_locale::make_module());
+    prelude.insert("metrics", koto_metrics::make_module());
+    prelude.insert("midi", koto_midi::make_module());
+    prelude.insert("money", koto_money::make_module());
+    prelude.insert("noise", koto_noise::make_module());
+    prelude.insert("osc", koto_osc::make_module());
+    prelude.insert("parquet", koto_parquet::make_module());
+    prelude.insert("parse", koto_parse::make_module());
+    prelude.insert("plot", koto_plot::make_module());
+    prelude.insert("progress", koto_progress::make_module());
+    prelude.insert("protobuf", koto_protobuf::make_module());
     prelude.insert("random", koto_random::make_module());
+    prelude.insert("readline", koto_readline::make_module());
     prelude.insert("regex", koto_regex::make_module());
+    prelude.insert("rules", koto_rules::make_module());
+    prelude.insert("schedule", koto_schedule::make_module());
+    prelude.insert("semver", koto_semver::make_module());
+    prelude.insert("sound", koto_sound::make_module());
+    prelude.insert("spatial", koto_spatial::make_module());
+    prelude.insert("store", koto_store::make_module());
+    prelude.insert("table", koto_table::make_module());
     prelude.insert("tempfile", koto_tempfile::make_module());
     prelude.insert("toml", koto_toml::make_module());
+    prelude.insert("trace", koto_trace::make_module());
+    prelude.insert("units", koto_units::make_module());
+    prelude.insert("validate", koto_validate::make_module());
     prelude.insert("yaml", koto_yaml::make_module());
 
     match koto.compile(script) {
@@ -66,12 +101,47 @@ macro_rules! lib_test {
 mod lib_tests {
     use super::*;
 
+    lib_test!(archive);
+    lib_test!(cache);
+    lib_test!(canvas);
     lib_test!(color);
+    lib_test!(containers);
+    lib_test!(diff);
+    lib_test!(ease);
+    lib_test!(fake);
+    lib_test!(fsm);
+    lib_test!(fuzzy);
     lib_test!(geometry);
+    lib_test!(i18n);
+    lib_test!(image);
+    lib_test!(ini);
+    lib_test!(ipc);
     lib_test!(json);
+    lib_test!(locale);
+    lib_test!(metrics);
+    lib_test!(midi);
+    lib_test!(money);
+    lib_test!(noise);
+    lib_test!(osc);
+    lib_test!(parquet);
+    lib_test!(parse);
+    lib_test!(plot);
+    lib_test!(progress);
+    lib_test!(protobuf);
     lib_test!(random);
+    lib_test!(readline);
     lib_test!(regex);
+    lib_test!(rules);
+    lib_test!(schedule);
+    lib_test!(semver);
+    lib_test!(sound);
+    lib_test!(spatial);
+    lib_test!(store);
+    lib_test!(table);
     lib_test!(tempfile);
     lib_test!(toml);
+    lib_test!(trace);
+    lib_test!(units);
+    lib_test!(validate);
     lib_test!(yaml);
 }
@@ -0,0 +1,103 @@
+//! A Koto language module for locale-aware number parsing and formatting
+
+mod locales;
+
+use koto_runtime::{prelude::*, Result};
+use locales::Locale;
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("locale");
+
+    result.add_fn("format_number", |ctx| {
+        let expected_error = "a Number, a locale String, and an optional options Map";
+
+        match ctx.args() {
+            [KValue::Number(n), KValue::Str(locale)] => {
+                let locale = Locale::find(locale)?;
+                Ok(locale.format(n.into(), &FormatOptions::default())?.into())
+            }
+            [KValue::Number(n), KValue::Str(locale), KValue::Map(options)] => {
+                let locale = Locale::find(locale)?;
+                let options = FormatOptions::from_map(options)?;
+                Ok(locale.format(n.into(), &options)?.into())
+            }
+            unexpected => type_error_with_slice(expected_error, unexpected),
+        }
+    });
+
+    result.add_fn("parse_number", |ctx| match ctx.args() {
+        [KValue::Str(s), KValue::Str(locale)] => {
+            let locale = Locale::find(locale)?;
+            Ok(locale.parse(s).unwrap_or(KValue::Null))
+        }
+        unexpected => type_error_with_slice("a String and a locale String", unexpected),
+    });
+
+    result
+}
+
+/// The formatting styles supported by `locale.format_number`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Style {
+    #[default]
+    Decimal,
+    Currency,
+    Percent,
+}
+
+/// Options that affect the output of `locale.format_number`
+#[derive(Clone, Debug)]
+pub struct FormatOptions {
+    pub style: Style,
+    pub currency: Option<String>,
+    pub decimals: Option<usize>,
+    pub grouping: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            style: Style::default(),
+            currency: None,
+            decimals: None,
+            grouping: true,
+        }
+    }
+}
+
+impl FormatOptions {
+    fn from_map(options: &KMap) -> Result<Self> {
+        let mut result = Self::default();
+
+        for (key, value) in options.data().iter() {
+            match (key.to_string().as_str(), value) {
+                ("style", KValue::Str(style)) => {
+                    result.style = match style.as_str() {
+                        "decimal" => Style::Decimal,
+                        "currency" => Style::Currency,
+                        "percent" => Style::Percent,
+                        other => return runtime_error!("Unknown number style '{other}'"),
+                    }
+                }
+                ("currency", KValue::Str(currency)) => {
+                    result.currency = Some(currency.to_uppercase())
+                }
+                ("decimals", KValue::Number(n)) if *n >= 0.0 => result.decimals = Some(n.into()),
+                ("grouping", KValue::Bool(grouping)) => result.grouping = *grouping,
+                ("style", unexpected) => return type_error("a String as 'style'", unexpected),
+                ("currency", unexpected) => {
+                    return type_error("a String as 'currency'", unexpected)
+                }
+                ("decimals", unexpected) => {
+                    return type_error("a non-negative Number as 'decimals'", unexpected)
+                }
+                ("grouping", unexpected) => return type_error("a Bool as 'grouping'", unexpected),
+                (unexpected, _) => {
+                    return runtime_error!("Unexpected format option '{unexpected}'")
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,267 @@
+use crate::{FormatOptions, Style};
+use koto_runtime::{prelude::*, Result};
+
+/// Where a currency symbol is placed relative to the number
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CurrencyPosition {
+    // e.g. '$1.50'
+    Prefix,
+    // e.g. 'R$ 1,50'
+    PrefixSpaced,
+    // e.g. '1,50 €'
+    Suffix,
+}
+
+/// The number formatting conventions used by a locale
+#[derive(Clone, Copy, Debug)]
+pub struct Locale {
+    tag: &'static str,
+    decimal: char,
+    group: char,
+    currency_position: CurrencyPosition,
+    percent_spaced: bool,
+}
+
+impl Locale {
+    const fn new(
+        tag: &'static str,
+        decimal: char,
+        group: char,
+        currency_position: CurrencyPosition,
+        percent_spaced: bool,
+    ) -> Self {
+        Self {
+            tag,
+            decimal,
+            group,
+            currency_position,
+            percent_spaced,
+        }
+    }
+
+    /// Finds the locale matching the given tag
+    ///
+    /// If there's no exact match for the tag then the tag's language is used, e.g. 'de-AT' will
+    /// fall back to 'de'.
+    pub fn find(tag: &str) -> Result<Self> {
+        let tag = tag.replace('_', "-");
+        let language = tag.split('-').next().unwrap_or_default();
+
+        LOCALES
+            .iter()
+            .find(|locale| locale.tag.eq_ignore_ascii_case(&tag))
+            .or_else(|| {
+                LOCALES
+                    .iter()
+                    .find(|locale| locale.tag.eq_ignore_ascii_case(language))
+            })
+            .copied()
+            .map_or_else(|| runtime_error!("Unsupported locale '{tag}'"), Ok)
+    }
+
+    /// Formats a number following the locale's conventions
+    pub fn format(&self, n: f64, options: &FormatOptions) -> Result<String> {
+        if !n.is_finite() {
+            return Ok(n.to_string());
+        }
+
+        let currency = match (options.style, &options.currency) {
+            (Style::Currency, Some(currency)) => Some(currency.as_str()),
+            (Style::Currency, None) => {
+                return runtime_error!("A 'currency' option is required for the currency style")
+            }
+            _ => None,
+        };
+
+        let (value, default_decimals) = match options.style {
+            Style::Decimal => (n, None),
+            Style::Percent => (n * 100.0, None),
+            Style::Currency => (n, Some(currency_decimals(currency.unwrap()))),
+        };
+
+        let decimals = options.decimals.or(default_decimals);
+        let formatted = match decimals {
+            Some(decimals) => format!("{:.*}", decimals, value.abs()),
+            None => {
+                // Up to 3 decimal places are shown, without trailing zeros
+                let formatted = format!("{:.3}", value.abs());
+                formatted
+                    .trim_end_matches('0')
+                    .trim_end_matches('.')
+                    .to_string()
+            }
+        };
+
+        let (integer, fraction) = match formatted.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (formatted.as_str(), None),
+        };
+
+        let mut number = if options.grouping {
+            group_digits(integer, self.group)
+        } else {
+            integer.to_string()
+        };
+        if let Some(fraction) = fraction {
+            number.push(self.decimal);
+            number.push_str(fraction);
+        }
+
+        let is_negative = value < 0.0 && formatted.chars().any(|c| c.is_ascii_digit() && c != '0');
+        let sign = if is_negative { "-" } else { "" };
+
+        let result = match options.style {
+            Style::Decimal => format!("{sign}{number}"),
+            Style::Percent if self.percent_spaced => format!("{sign}{number} %"),
+            Style::Percent => format!("{sign}{number}%"),
+            Style::Currency => {
+                let symbol = currency_symbol(currency.unwrap());
+                let is_code = symbol.chars().all(|c| c.is_ascii_alphabetic());
+                match self.currency_position {
+                    CurrencyPosition::Prefix if !is_code => format!("{sign}{symbol}{number}"),
+                    CurrencyPosition::Prefix | CurrencyPosition::PrefixSpaced => {
+                        format!("{sign}{symbol} {number}")
+                    }
+                    CurrencyPosition::Suffix => format!("{sign}{number} {symbol}"),
+                }
+            }
+        };
+
+        Ok(result)
+    }
+
+    /// Parses a number following the locale's conventions
+    ///
+    /// Currency symbols and codes are ignored, and a trailing `%` causes the result to be divided
+    /// by 100. `None` is returned if the input doesn't contain a valid number.
+    pub fn parse(&self, input: &str) -> Option<KValue> {
+        let mut s = input.trim();
+
+        let is_percent = match s.strip_suffix('%') {
+            Some(stripped) => {
+                s = stripped.trim_end();
+                true
+            }
+            None => false,
+        };
+
+        let is_digit_or_decimal = |c: char| c.is_ascii_digit() || c == self.decimal;
+
+        let mut is_negative = false;
+        let mut strip_sign = |s: &str| -> String {
+            let s = s.trim();
+            match s.strip_prefix(['-', '\u{2212}']) {
+                Some(stripped) => {
+                    is_negative = true;
+                    stripped.trim_start().to_string()
+                }
+                None => s.to_string(),
+            }
+        };
+
+        // Remove currency symbols and codes, which can appear on either side of the sign
+        let s = strip_sign(s);
+        let s = s.trim_start_matches(|c: char| !is_digit_or_decimal(c) && c != '-');
+        let s = strip_sign(s);
+        let s = s.trim_end_matches(|c: char| !is_digit_or_decimal(c));
+
+        let mut number = String::with_capacity(s.len());
+        let mut has_decimal = false;
+        for c in s.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+            } else if c == self.decimal && !has_decimal {
+                number.push('.');
+                has_decimal = true;
+            } else if !self.is_group_separator(c) {
+                return None;
+            }
+        }
+
+        if !number.chars().any(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let sign = if is_negative { -1.0 } else { 1.0 };
+
+        if is_percent {
+            let n: f64 = number.parse().ok()?;
+            Some((sign * n / 100.0).into())
+        } else if !has_decimal {
+            match number.parse::<i64>() {
+                Ok(n) => Some((if is_negative { -n } else { n }).into()),
+                Err(_) => Some((sign * number.parse::<f64>().ok()?).into()),
+            }
+        } else {
+            Some((sign * number.parse::<f64>().ok()?).into())
+        }
+    }
+
+    fn is_group_separator(&self, c: char) -> bool {
+        if c == self.group {
+            return true;
+        }
+
+        match self.group {
+            // Spaces used as group separators are often interchangeable
+            ' ' | '\u{a0}' | '\u{202f}' => c.is_whitespace(),
+            // Apostrophe-style separators are also often interchangeable
+            '\'' | '\u{2019}' => c == '\'' || c == '\u{2019}',
+            _ => false,
+        }
+    }
+}
+
+// Inserts a group separator between every group of 3 digits
+fn group_digits(digits: &str, separator: char) -> String {
+    let mut result = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            result.push(separator);
+        }
+        result.push(c);
+    }
+    result
+}
+
+fn currency_symbol(code: &str) -> &str {
+    match code {
+        "USD" => "$",
+        "EUR" => "€",
+        "GBP" => "£",
+        "JPY" | "CNY" => "¥",
+        "INR" => "₹",
+        "KRW" => "₩",
+        "BRL" => "R$",
+        _ => code,
+    }
+}
+
+fn currency_decimals(code: &str) -> usize {
+    match code {
+        "JPY" | "KRW" => 0,
+        _ => 2,
+    }
+}
+
+const LOCALES: &[Locale] = {
+    use CurrencyPosition::*;
+
+    &[
+        Locale::new("en", '.', ',', Prefix, false),
+        Locale::new("de", ',', '.', Suffix, true),
+        Locale::new("de-CH", '.', '\u{2019}', PrefixSpaced, false),
+        Locale::new("es", ',', '.', Suffix, true),
+        Locale::new("fr", ',', ' ', Suffix, true),
+        Locale::new("fr-CH", ',', ' ', PrefixSpaced, true),
+        Locale::new("it", ',', '.', Suffix, false),
+        Locale::new("ja", '.', ',', Prefix, false),
+        Locale::new("nl", ',', '.', PrefixSpaced, false),
+        Locale::new("pl", ',', ' ', Suffix, false),
+        Locale::new("pt", ',', '.', PrefixSpaced, false),
+        Locale::new("pt-PT", ',', ' ', Suffix, false),
+        Locale::new("ru", ',', ' ', Suffix, true),
+        Locale::new("sv", ',', ' ', Suffix, true),
+        Locale::new("zh", '.', ',', Prefix, false),
+    ]
+};
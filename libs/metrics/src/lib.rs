@@ -0,0 +1,120 @@
+//! A Koto language module for recording metrics
+//!
+//! Scripts record [Counter]s, [Gauge]s, and [Histogram]s in a [Registry], which can be shared
+//! with the host application by making the module with [make_module_with_registry]. The host can
+//! then export the script's metrics, e.g. to be scraped by Prometheus, with
+//! [Registry::export_prometheus].
+
+mod metrics;
+mod registry;
+
+pub use crate::{
+    metrics::{Counter, Gauge, Histogram},
+    registry::{Labels, MetricKind, Registry, DEFAULT_BUCKETS},
+};
+
+use koto_runtime::{prelude::*, Result};
+
+pub fn make_module() -> KMap {
+    make_module_with_registry(Registry::new())
+}
+
+/// Makes the module with a registry that can be shared with the host application
+pub fn make_module_with_registry(registry: Registry) -> KMap {
+    let result = KMap::with_type("metrics");
+
+    result.add_fn("counter", {
+        let registry = registry.clone();
+        move |ctx| {
+            let (name, help, _) = metric_args(ctx.args(), false)?;
+            let data = registry.register(&name, MetricKind::Counter, help, None)?;
+            Ok(Counter::new(name, data).into())
+        }
+    });
+
+    result.add_fn("export", {
+        let registry = registry.clone();
+        move |ctx| match ctx.args() {
+            [] => Ok(registry.export_prometheus().into()),
+            unexpected => type_error_with_slice("no arguments", unexpected),
+        }
+    });
+
+    result.add_fn("gauge", {
+        let registry = registry.clone();
+        move |ctx| {
+            let (name, help, _) = metric_args(ctx.args(), false)?;
+            let data = registry.register(&name, MetricKind::Gauge, help, None)?;
+            Ok(Gauge::new(name, data).into())
+        }
+    });
+
+    result.add_fn("histogram", {
+        let registry = registry.clone();
+        move |ctx| {
+            let (name, help, buckets) = metric_args(ctx.args(), true)?;
+            let data = registry.register(&name, MetricKind::Histogram, help, buckets)?;
+            Ok(Histogram::new(name, data).into())
+        }
+    });
+
+    result.add_fn("reset", move |ctx| match ctx.args() {
+        [] => {
+            registry.reset();
+            Ok(KValue::Null)
+        }
+        unexpected => type_error_with_slice("no arguments", unexpected),
+    });
+
+    result
+}
+
+type MetricArgs = (KString, Option<String>, Option<Vec<f64>>);
+
+fn metric_args(args: &[KValue], allow_buckets: bool) -> Result<MetricArgs> {
+    let expected = if allow_buckets {
+        "a name, with optional help String or options Map"
+    } else {
+        "a name, with optional help String"
+    };
+
+    match args {
+        [KValue::Str(name)] => Ok((name.clone(), None, None)),
+        [KValue::Str(name), KValue::Str(help)] => Ok((name.clone(), Some(help.to_string()), None)),
+        [KValue::Str(name), KValue::Map(options)] if allow_buckets => {
+            let help = match options.get("help") {
+                Some(KValue::Str(help)) => Some(help.to_string()),
+                Some(KValue::Null) | None => None,
+                Some(unexpected) => return type_error("a String as help", &unexpected),
+            };
+            let buckets = match options.get("buckets") {
+                Some(KValue::List(buckets)) => Some(buckets_from_values(&buckets.data())?),
+                Some(KValue::Tuple(buckets)) => Some(buckets_from_values(&buckets)?),
+                Some(KValue::Null) | None => None,
+                Some(unexpected) => return type_error("a List of Numbers as buckets", &unexpected),
+            };
+            Ok((name.clone(), help, buckets))
+        }
+        unexpected => type_error_with_slice(expected, unexpected),
+    }
+}
+
+fn buckets_from_values(values: &[KValue]) -> Result<Vec<f64>> {
+    let mut result = Vec::with_capacity(values.len());
+
+    for value in values {
+        match value {
+            KValue::Number(n) if f64::from(n).is_finite() => result.push(f64::from(n)),
+            unexpected => return type_error("a finite Number as bucket bound", unexpected),
+        }
+    }
+
+    if result.is_empty() {
+        return runtime_error!("at least one bucket is required");
+    }
+    if result.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return runtime_error!("bucket bounds must be in increasing order");
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,283 @@
+use crate::registry::{is_valid_label_name, Labels, MetricData, Series};
+use instant::Instant;
+use koto_runtime::{derive::*, prelude::*, PtrMut, Result};
+
+/// A counter, a value that only increases
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "Counter")]
+pub struct Counter(Metric);
+
+#[koto_impl(runtime = koto_runtime)]
+impl Counter {
+    pub(crate) fn new(name: KString, data: PtrMut<MetricData>) -> Self {
+        Self(Metric { name, data })
+    }
+
+    #[koto_method]
+    fn get(&self, args: &[KValue]) -> Result<KValue> {
+        self.0.get(args)
+    }
+
+    #[koto_method]
+    fn increment(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (amount, labels) = amount_and_labels(ctx.args)?;
+        if amount < 0.0 {
+            return runtime_error!("counters can't be decreased (found {amount})");
+        }
+        ctx.instance()?.0.data.borrow_mut().add(labels, amount);
+        ctx.instance_result()
+    }
+}
+
+impl KotoObject for Counter {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        self.0.display(Self::type_static(), ctx)
+    }
+}
+
+impl From<Counter> for KValue {
+    fn from(counter: Counter) -> Self {
+        KObject::from(counter).into()
+    }
+}
+
+/// A gauge, a value that can increase and decrease
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "Gauge")]
+pub struct Gauge(Metric);
+
+#[koto_impl(runtime = koto_runtime)]
+impl Gauge {
+    pub(crate) fn new(name: KString, data: PtrMut<MetricData>) -> Self {
+        Self(Metric { name, data })
+    }
+
+    #[koto_method]
+    fn decrement(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (amount, labels) = amount_and_labels(ctx.args)?;
+        ctx.instance()?.0.data.borrow_mut().add(labels, -amount);
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn get(&self, args: &[KValue]) -> Result<KValue> {
+        self.0.get(args)
+    }
+
+    #[koto_method]
+    fn increment(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (amount, labels) = amount_and_labels(ctx.args)?;
+        ctx.instance()?.0.data.borrow_mut().add(labels, amount);
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn set(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (value, labels) = match ctx.args {
+            [KValue::Number(value)] => (value, Labels::new()),
+            [KValue::Number(value), KValue::Map(labels)] => (value, labels_from_map(labels)?),
+            unexpected => {
+                return type_error_with_slice("a Number, with optional labels Map", unexpected)
+            }
+        };
+        ctx.instance()?
+            .0
+            .data
+            .borrow_mut()
+            .set(labels, f64::from(value));
+        ctx.instance_result()
+    }
+}
+
+impl KotoObject for Gauge {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        self.0.display(Self::type_static(), ctx)
+    }
+}
+
+impl From<Gauge> for KValue {
+    fn from(gauge: Gauge) -> Self {
+        KObject::from(gauge).into()
+    }
+}
+
+/// A histogram, which counts observed values in buckets
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "Histogram")]
+pub struct Histogram(Metric);
+
+#[koto_impl(runtime = koto_runtime)]
+impl Histogram {
+    pub(crate) fn new(name: KString, data: PtrMut<MetricData>) -> Self {
+        Self(Metric { name, data })
+    }
+
+    #[koto_method]
+    fn buckets(&self, args: &[KValue]) -> Result<KValue> {
+        let labels = optional_labels(args)?;
+        let data = self.0.data.borrow();
+        let bucket_counts = match data.series.get(&labels) {
+            Some(Series::Histogram { bucket_counts, .. }) => bucket_counts.clone(),
+            _ => vec![0; data.buckets.len()],
+        };
+
+        // Bucket counts are cumulative, as in Prometheus exports
+        let mut cumulative = 0;
+        let result = KMap::with_capacity(data.buckets.len());
+        for (bound, count) in data.buckets.iter().zip(bucket_counts) {
+            cumulative += count;
+            result.insert(KNumber::from(*bound), number_value(cumulative as f64));
+        }
+        Ok(result.into())
+    }
+
+    #[koto_method]
+    fn count(&self, args: &[KValue]) -> Result<KValue> {
+        let labels = optional_labels(args)?;
+        let count = match self.0.data.borrow().series.get(&labels) {
+            Some(Series::Histogram { count, .. }) => *count,
+            _ => 0,
+        };
+        Ok(number_value(count as f64))
+    }
+
+    #[koto_method]
+    fn observe(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (value, labels) = match ctx.args {
+            [KValue::Number(value)] => (value, Labels::new()),
+            [KValue::Number(value), KValue::Map(labels)] => (value, labels_from_map(labels)?),
+            unexpected => {
+                return type_error_with_slice("a Number, with optional labels Map", unexpected)
+            }
+        };
+        ctx.instance()?
+            .0
+            .data
+            .borrow_mut()
+            .observe(labels, f64::from(value));
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn sum(&self, args: &[KValue]) -> Result<KValue> {
+        let labels = optional_labels(args)?;
+        let sum = match self.0.data.borrow().series.get(&labels) {
+            Some(Series::Histogram { sum, .. }) => *sum,
+            _ => 0.0,
+        };
+        Ok(number_value(sum))
+    }
+
+    #[koto_method]
+    fn time(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (f, labels) = match ctx.args {
+            [f] if f.is_callable() => (f, Labels::new()),
+            [f, KValue::Map(labels)] if f.is_callable() => (f, labels_from_map(labels)?),
+            unexpected => {
+                return type_error_with_slice("a Function, with optional labels Map", unexpected)
+            }
+        };
+
+        // The instance is cloned so that it isn't borrowed while the function is running
+        let this = ctx.instance()?.clone();
+        let start = Instant::now();
+        let result = ctx.vm.spawn_shared_vm().call_function(f.clone(), &[])?;
+        let elapsed = start.elapsed().as_secs_f64();
+        this.0.data.borrow_mut().observe(labels, elapsed);
+
+        Ok(result)
+    }
+}
+
+impl KotoObject for Histogram {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        self.0.display(Self::type_static(), ctx)
+    }
+}
+
+impl From<Histogram> for KValue {
+    fn from(histogram: Histogram) -> Self {
+        KObject::from(histogram).into()
+    }
+}
+
+// The data shared by each metric type
+//
+// Copies of a metric refer to the same registered data.
+#[derive(Clone)]
+struct Metric {
+    name: KString,
+    data: PtrMut<MetricData>,
+}
+
+impl Metric {
+    fn get(&self, args: &[KValue]) -> Result<KValue> {
+        let labels = optional_labels(args)?;
+        Ok(number_value(self.data.borrow().value(&labels)))
+    }
+
+    fn display(&self, type_name: &str, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!("{type_name}({})", self.name));
+        Ok(())
+    }
+}
+
+/// Converts a Koto map into a sorted list of labels
+///
+/// Label values can be Strings, Numbers, or Bools.
+fn labels_from_map(map: &KMap) -> Result<Labels> {
+    let mut result = Labels::with_capacity(map.len());
+
+    for (name, value) in map.data().iter() {
+        let name = match name.value() {
+            KValue::Str(name) if is_valid_label_name(name) && name.as_str() != "le" => {
+                name.to_string()
+            }
+            KValue::Str(name) => return runtime_error!("'{name}' isn't a valid label name"),
+            unexpected => return type_error("a String as label name", unexpected),
+        };
+        let value = match value {
+            KValue::Str(s) => s.to_string(),
+            KValue::Number(n) => n.to_string(),
+            KValue::Bool(b) => b.to_string(),
+            unexpected => {
+                return type_error("a String, Number, or Bool as label value", unexpected)
+            }
+        };
+        result.push((name, value));
+    }
+
+    result.sort();
+    Ok(result)
+}
+
+fn optional_labels(args: &[KValue]) -> Result<Labels> {
+    match args {
+        [] => Ok(Labels::new()),
+        [KValue::Map(labels)] => labels_from_map(labels),
+        unexpected => type_error_with_slice("an optional labels Map", unexpected),
+    }
+}
+
+fn amount_and_labels(args: &[KValue]) -> Result<(f64, Labels)> {
+    match args {
+        [] => Ok((1.0, Labels::new())),
+        [KValue::Number(amount)] => Ok((amount.into(), Labels::new())),
+        [KValue::Map(labels)] => Ok((1.0, labels_from_map(labels)?)),
+        [KValue::Number(amount), KValue::Map(labels)] => {
+            Ok((amount.into(), labels_from_map(labels)?))
+        }
+        unexpected => {
+            type_error_with_slice("an optional Number, and an optional labels Map", unexpected)
+        }
+    }
+}
+
+// Whole numbers are returned as integers, so that counts display without a fractional part
+fn number_value(n: f64) -> KValue {
+    if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+        KValue::Number((n as i64).into())
+    } else {
+        KValue::Number(n.into())
+    }
+}
@@ -0,0 +1,283 @@
+use koto_runtime::{prelude::*, PtrMut, Result};
+use std::{collections::BTreeMap, fmt::Write};
+
+/// The default histogram buckets, matching the defaults used by Prometheus client libraries
+pub const DEFAULT_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// A sorted list of label names and values
+pub type Labels = Vec<(String, String)>;
+
+/// The kinds of metric that can be registered
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetricKind {
+    /// A value that only increases
+    Counter,
+    /// A value that can increase and decrease
+    Gauge,
+    /// A distribution of observed values, counted in buckets
+    Histogram,
+}
+
+impl MetricKind {
+    /// Returns the kind's name, as used in Prometheus exports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Counter => "counter",
+            Self::Gauge => "gauge",
+            Self::Histogram => "histogram",
+        }
+    }
+}
+
+/// A registry of metrics, shared between scripts and the host application
+///
+/// Cloning a registry produces a handle to the same metrics, so a host can keep a clone of the
+/// registry that's passed to [crate::make_module_with_registry] and then export the script's
+/// metrics with [Registry::export_prometheus].
+#[derive(Clone, Default)]
+pub struct Registry(PtrMut<BTreeMap<String, PtrMut<MetricData>>>);
+
+impl Registry {
+    /// Makes a new empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the metric with the given name, registering it if necessary
+    ///
+    /// An error is returned if a metric with the same name but a different kind was already
+    /// registered.
+    pub(crate) fn register(
+        &self,
+        name: &str,
+        kind: MetricKind,
+        help: Option<String>,
+        buckets: Option<Vec<f64>>,
+    ) -> Result<PtrMut<MetricData>> {
+        if !is_valid_metric_name(name) {
+            return runtime_error!("'{name}' isn't a valid metric name");
+        }
+
+        let mut metrics = self.0.borrow_mut();
+        if let Some(existing) = metrics.get(name) {
+            let existing_kind = existing.borrow().kind;
+            if existing_kind != kind {
+                return runtime_error!(
+                    "'{name}' is already registered as a {}",
+                    existing_kind.as_str()
+                );
+            }
+            return Ok(existing.clone());
+        }
+
+        let metric = PtrMut::from(MetricData {
+            kind,
+            help: help.unwrap_or_default(),
+            buckets: buckets.unwrap_or_else(|| DEFAULT_BUCKETS.to_vec()),
+            series: BTreeMap::new(),
+        });
+        metrics.insert(name.to_string(), metric.clone());
+        Ok(metric)
+    }
+
+    /// Returns the names of the registered metrics, in alphabetical order
+    pub fn metric_names(&self) -> Vec<String> {
+        self.0.borrow().keys().cloned().collect()
+    }
+
+    /// Returns the kind of the metric with the given name
+    pub fn kind(&self, name: &str) -> Option<MetricKind> {
+        self.0.borrow().get(name).map(|metric| metric.borrow().kind)
+    }
+
+    /// Returns the current value of a counter or gauge
+    ///
+    /// For histograms, the sum of the observed values is returned.
+    pub fn value(&self, name: &str, labels: &[(&str, &str)]) -> Option<f64> {
+        let metrics = self.0.borrow();
+        let metric = metrics.get(name)?.borrow();
+        let mut labels: Labels = labels
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        labels.sort();
+        metric.series.get(&labels).map(|series| match series {
+            Series::Value(value) => *value,
+            Series::Histogram { sum, .. } => *sum,
+        })
+    }
+
+    /// Removes all of the registered metrics
+    pub fn reset(&self) {
+        self.0.borrow_mut().clear();
+    }
+
+    /// Exports the registry's metrics in the Prometheus text exposition format
+    pub fn export_prometheus(&self) -> String {
+        let mut result = String::new();
+
+        for (name, metric) in self.0.borrow().iter() {
+            let metric = metric.borrow();
+            if !metric.help.is_empty() {
+                let help = metric.help.replace('\\', r"\\").replace('\n', r"\n");
+                writeln!(result, "# HELP {name} {help}").ok();
+            }
+            writeln!(result, "# TYPE {name} {}", metric.kind.as_str()).ok();
+
+            for (labels, series) in metric.series.iter() {
+                match series {
+                    Series::Value(value) => {
+                        writeln!(
+                            result,
+                            "{name}{} {}",
+                            format_labels(labels, None),
+                            format_value(*value)
+                        )
+                        .ok();
+                    }
+                    Series::Histogram {
+                        bucket_counts,
+                        sum,
+                        count,
+                    } => {
+                        let mut cumulative = 0;
+                        for (bound, bucket_count) in metric.buckets.iter().zip(bucket_counts) {
+                            cumulative += bucket_count;
+                            let le = format_value(*bound);
+                            writeln!(
+                                result,
+                                "{name}_bucket{} {cumulative}",
+                                format_labels(labels, Some(&le))
+                            )
+                            .ok();
+                        }
+                        writeln!(
+                            result,
+                            "{name}_bucket{} {count}",
+                            format_labels(labels, Some("+Inf"))
+                        )
+                        .ok();
+                        let labels = format_labels(labels, None);
+                        writeln!(result, "{name}_sum{labels} {}", format_value(*sum)).ok();
+                        writeln!(result, "{name}_count{labels} {count}").ok();
+                    }
+                }
+            }
+        }
+
+        result
+    }
+}
+
+/// The data recorded for a single metric
+pub(crate) struct MetricData {
+    pub kind: MetricKind,
+    pub help: String,
+    // The upper bounds of the histogram's buckets, in ascending order
+    pub buckets: Vec<f64>,
+    pub series: BTreeMap<Labels, Series>,
+}
+
+impl MetricData {
+    pub fn value(&self, labels: &Labels) -> f64 {
+        match self.series.get(labels) {
+            Some(Series::Value(value)) => *value,
+            _ => 0.0,
+        }
+    }
+
+    pub fn add(&mut self, labels: Labels, amount: f64) {
+        if let Series::Value(value) = self.series.entry(labels).or_insert(Series::Value(0.0)) {
+            *value += amount;
+        }
+    }
+
+    pub fn set(&mut self, labels: Labels, value: f64) {
+        self.series.insert(labels, Series::Value(value));
+    }
+
+    pub fn observe(&mut self, labels: Labels, value: f64) {
+        let bucket_count = self.buckets.len();
+        let series = self
+            .series
+            .entry(labels)
+            .or_insert_with(|| Series::Histogram {
+                bucket_counts: vec![0; bucket_count],
+                sum: 0.0,
+                count: 0,
+            });
+        if let Series::Histogram {
+            bucket_counts,
+            sum,
+            count,
+        } = series
+        {
+            if let Some(bucket) = self.buckets.iter().position(|bound| value <= *bound) {
+                bucket_counts[bucket] += 1;
+            }
+            *sum += value;
+            *count += 1;
+        }
+    }
+}
+
+/// The values recorded for a combination of labels
+pub(crate) enum Series {
+    Value(f64),
+    Histogram {
+        // The number of observations in each bucket, excluding observations in lower buckets
+        bucket_counts: Vec<u64>,
+        sum: f64,
+        count: u64,
+    },
+}
+
+fn is_valid_metric_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == ':')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+}
+
+pub(crate) fn is_valid_label_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut pairs: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| {
+            let value = value
+                .replace('\\', r"\\")
+                .replace('"', "\\\"")
+                .replace('\n', r"\n");
+            format!("{name}=\"{value}\"")
+        })
+        .collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{le}\""));
+    }
+
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".into()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.into()
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,83 @@
+use koto_metrics::{make_module_with_registry, MetricKind, Registry};
+use koto_runtime::{prelude::*, Result};
+use koto_test_utils::check_script_output_with_vm;
+
+fn make_vm(registry: &Registry) -> KotoVm {
+    let vm = KotoVm::default();
+    vm.prelude()
+        .insert("metrics", make_module_with_registry(registry.clone()));
+    vm
+}
+
+#[test]
+fn export_script_metrics() -> Result<()> {
+    let registry = Registry::new();
+    let vm = make_vm(&registry);
+
+    let script = r#"
+metrics.counter('events_total', 'Handled events')
+  .increment {kind: 'click'}
+  .increment 2, {kind: 'key'}
+metrics.gauge('open_windows').set 2
+latency = metrics.histogram 'latency_seconds', {buckets: [0.1, 1]}
+latency.observe 0.05
+latency.observe 0.5, {path: 'a "quoted"\\path'}
+null
+"#;
+    check_script_output_with_vm(vm, script, KValue::Null)?;
+
+    assert_eq!(
+        registry.metric_names(),
+        ["events_total", "latency_seconds", "open_windows"]
+    );
+    assert_eq!(registry.kind("events_total"), Some(MetricKind::Counter));
+    assert_eq!(registry.kind("missing"), None);
+    assert_eq!(
+        registry.value("events_total", &[("kind", "key")]),
+        Some(2.0)
+    );
+    assert_eq!(registry.value("events_total", &[]), None);
+    assert_eq!(registry.value("open_windows", &[]), Some(2.0));
+
+    assert_eq!(
+        registry.export_prometheus(),
+        r#"# HELP events_total Handled events
+# TYPE events_total counter
+events_total{kind="click"} 1
+events_total{kind="key"} 2
+# TYPE latency_seconds histogram
+latency_seconds_bucket{le="0.1"} 1
+latency_seconds_bucket{le="1"} 1
+latency_seconds_bucket{le="+Inf"} 1
+latency_seconds_sum 0.05
+latency_seconds_count 1
+latency_seconds_bucket{path="a \"quoted\"\\path",le="0.1"} 0
+latency_seconds_bucket{path="a \"quoted\"\\path",le="1"} 1
+latency_seconds_bucket{path="a \"quoted\"\\path",le="+Inf"} 1
+latency_seconds_sum{path="a \"quoted\"\\path"} 0.5
+latency_seconds_count{path="a \"quoted\"\\path"} 1
+# TYPE open_windows gauge
+open_windows 2
+"#
+    );
+    Ok(())
+}
+
+#[test]
+fn metrics_persist_across_script_runs() -> Result<()> {
+    let registry = Registry::new();
+
+    let script = "
+metrics.counter('runs_total').increment()
+null
+";
+    for _ in 0..3 {
+        check_script_output_with_vm(make_vm(&registry), script, KValue::Null)?;
+    }
+
+    assert_eq!(registry.value("runs_total", &[]), Some(3.0));
+
+    registry.reset();
+    assert!(registry.metric_names().is_empty());
+    Ok(())
+}
@@ -0,0 +1,337 @@
+//! A Koto language module for working with MIDI messages
+//!
+//! MIDI ports are provided by the host application, which uses a [MidiHost] to take the messages
+//! that a script has sent with `midi.send`, and to pass incoming messages to the handlers that
+//! the script has registered with `midi.on`.
+
+mod message;
+
+pub use crate::message::Message;
+
+use koto_runtime::{prelude::*, PtrMut, Result};
+
+/// Makes the `midi` module, with ports that can't be used by a host
+///
+/// See [make_module_with_host].
+pub fn make_module() -> KMap {
+    make_module_with_host(&MidiHost::default())
+}
+
+/// Makes the `midi` module, with ports that are connected to the provided host
+pub fn make_module_with_host(host: &MidiHost) -> KMap {
+    let result = KMap::with_type("midi");
+
+    result.add_fn("aftertouch", |ctx| match ctx.args() {
+        [KValue::Number(channel), KValue::Number(pressure)] => Ok(Message::Aftertouch {
+            channel: channel_from_number(channel)?,
+            pressure: data_from_number(pressure)?,
+        }
+        .into()),
+        unexpected => type_error_with_slice("a channel and a pressure", unexpected),
+    });
+
+    result.add_fn("clock", |ctx| system_message(ctx.args(), Message::Clock));
+
+    result.add_fn("control_change", |ctx| match ctx.args() {
+        [KValue::Number(channel), KValue::Number(controller), KValue::Number(value)] => {
+            Ok(Message::ControlChange {
+                channel: channel_from_number(channel)?,
+                controller: data_from_number(controller)?,
+                value: data_from_number(value)?,
+            }
+            .into())
+        }
+        unexpected => type_error_with_slice("a channel, a controller, and a value", unexpected),
+    });
+
+    result.add_fn("frequency", |ctx| match ctx.args() {
+        [KValue::Number(note)] => Ok((440.0 * 2.0f64.powf((f64::from(note) - 69.0) / 12.0)).into()),
+        unexpected => type_error_with_slice("a note number", unexpected),
+    });
+
+    result.add_fn("note_name", |ctx| match ctx.args() {
+        [KValue::Number(note)] => {
+            const NAMES: [&str; 12] = [
+                "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+            ];
+            let note = data_from_number(note)?;
+            let octave = note as i32 / 12 - 1;
+            Ok(format!("{}{octave}", NAMES[note as usize % 12]).into())
+        }
+        unexpected => type_error_with_slice("a note number", unexpected),
+    });
+
+    result.add_fn("note_number", |ctx| match ctx.args() {
+        [KValue::Str(name)] => match note_number(name) {
+            Some(note) => Ok(note.into()),
+            None => runtime_error!("Invalid note name '{name}'"),
+        },
+        unexpected => type_error_with_slice("a note name", unexpected),
+    });
+
+    result.add_fn("note_off", |ctx| {
+        let (channel, note, velocity) = match ctx.args() {
+            [KValue::Number(channel), KValue::Number(note)] => (channel, note, 0),
+            [KValue::Number(channel), KValue::Number(note), KValue::Number(velocity)] => {
+                (channel, note, data_from_number(velocity)?)
+            }
+            unexpected => {
+                return type_error_with_slice(
+                    "a channel and a note, and an optional velocity",
+                    unexpected,
+                )
+            }
+        };
+        Ok(Message::NoteOff {
+            channel: channel_from_number(channel)?,
+            note: data_from_number(note)?,
+            velocity,
+        }
+        .into())
+    });
+
+    result.add_fn("note_on", |ctx| match ctx.args() {
+        [KValue::Number(channel), KValue::Number(note), KValue::Number(velocity)] => {
+            Ok(Message::NoteOn {
+                channel: channel_from_number(channel)?,
+                note: data_from_number(note)?,
+                velocity: data_from_number(velocity)?,
+            }
+            .into())
+        }
+        unexpected => type_error_with_slice("a channel, a note, and a velocity", unexpected),
+    });
+
+    result.add_fn("on", {
+        let host = host.clone();
+        move |ctx| {
+            let (kind, handler) = match ctx.args() {
+                [handler] if handler.is_callable() => (None, handler),
+                [KValue::Str(kind), handler] if handler.is_callable() => {
+                    (Some(kind.to_string()), handler)
+                }
+                unexpected => {
+                    return type_error_with_slice(
+                        "an optional message kind, and a Function",
+                        unexpected,
+                    )
+                }
+            };
+            host.handlers.borrow_mut().push((kind, handler.clone()));
+            Ok(KValue::Null)
+        }
+    });
+
+    result.add_fn("parse", |ctx| {
+        let bytes = match ctx.args() {
+            [bytes] if bytes.is_iterable() => bytes.clone(),
+            unexpected => return type_error_with_slice("an iterable of bytes", unexpected),
+        };
+        Message::from_bytes(&bytes_from_iterable(ctx.vm, bytes)?)
+            .map(KValue::from)
+            .map_err(|e| format!("Failed to parse MIDI message: {e}").into())
+    });
+
+    result.add_fn("pitch_bend", |ctx| match ctx.args() {
+        [KValue::Number(channel), KValue::Number(value)] => {
+            let value = match i16::try_from(i64::from(value)) {
+                Ok(value) if (-8192..=8191).contains(&value) => value,
+                _ => return runtime_error!("Expected a value in the range -8192..=8191"),
+            };
+            Ok(Message::PitchBend {
+                channel: channel_from_number(channel)?,
+                value,
+            }
+            .into())
+        }
+        unexpected => type_error_with_slice("a channel and a value", unexpected),
+    });
+
+    result.add_fn("poly_aftertouch", |ctx| match ctx.args() {
+        [KValue::Number(channel), KValue::Number(note), KValue::Number(pressure)] => {
+            Ok(Message::PolyAftertouch {
+                channel: channel_from_number(channel)?,
+                note: data_from_number(note)?,
+                pressure: data_from_number(pressure)?,
+            }
+            .into())
+        }
+        unexpected => type_error_with_slice("a channel, a note, and a pressure", unexpected),
+    });
+
+    result.add_fn("program_change", |ctx| match ctx.args() {
+        [KValue::Number(channel), KValue::Number(program)] => Ok(Message::ProgramChange {
+            channel: channel_from_number(channel)?,
+            program: data_from_number(program)?,
+        }
+        .into()),
+        unexpected => type_error_with_slice("a channel and a program", unexpected),
+    });
+
+    result.add_fn("receive", {
+        let host = host.clone();
+        move |ctx| {
+            let message = match ctx.args() {
+                [KValue::Object(o)] if o.is_a::<Message>() => o.cast::<Message>()?.clone(),
+                [bytes] if bytes.is_iterable() => {
+                    let bytes = bytes_from_iterable(ctx.vm, bytes.clone())?;
+                    Message::from_bytes(&bytes)
+                        .map_err(|e| format!("Failed to parse MIDI message: {e}"))?
+                }
+                unexpected => {
+                    return type_error_with_slice("a Message, or an iterable of bytes", unexpected)
+                }
+            };
+            let calls = host.receive(&mut ctx.vm.spawn_shared_vm(), &message)?;
+            Ok(calls.into())
+        }
+    });
+
+    result.add_fn("reset", |ctx| system_message(ctx.args(), Message::Reset));
+    result.add_fn("resume", |ctx| {
+        system_message(ctx.args(), Message::Continue)
+    });
+
+    result.add_fn("send", {
+        let host = host.clone();
+        move |ctx| match ctx.args() {
+            [KValue::Object(o)] if o.is_a::<Message>() => {
+                host.output.borrow_mut().push(o.cast::<Message>()?.clone());
+                Ok(KValue::Null)
+            }
+            unexpected => type_error_with_slice("a Message", unexpected),
+        }
+    });
+
+    result.add_fn("start", |ctx| system_message(ctx.args(), Message::Start));
+    result.add_fn("stop", |ctx| system_message(ctx.args(), Message::Stop));
+
+    result.add_fn("sysex", |ctx| {
+        let data = match ctx.args() {
+            [data] if data.is_iterable() => data.clone(),
+            unexpected => return type_error_with_slice("an iterable of bytes", unexpected),
+        };
+        let data = bytes_from_iterable(ctx.vm, data)?;
+        if let Some(byte) = data.iter().find(|byte| **byte > 127) {
+            return runtime_error!(
+                "Expected system exclusive data in the range 0..=127, found {byte}"
+            );
+        }
+        Ok(Message::SysEx(data).into())
+    });
+
+    result
+}
+
+/// Connects a script's MIDI messages to the host application's MIDI ports
+///
+/// Messages that are sent by the script with `midi.send` are queued until they're taken by the
+/// host with [MidiHost::take_output], which should be called regularly, e.g. once per frame.
+///
+/// Messages that are received by the host should be passed to [MidiHost::receive], which calls
+/// the handlers that the script has registered with `midi.on`.
+#[derive(Clone)]
+pub struct MidiHost {
+    output: PtrMut<Vec<Message>>,
+    handlers: PtrMut<Vec<(Option<String>, KValue)>>,
+}
+
+impl Default for MidiHost {
+    fn default() -> Self {
+        Self {
+            output: PtrMut::from(Vec::new()),
+            handlers: PtrMut::from(Vec::new()),
+        }
+    }
+}
+
+impl MidiHost {
+    /// Returns true if a script has registered any message handlers
+    pub fn has_handlers(&self) -> bool {
+        !self.handlers.borrow().is_empty()
+    }
+
+    /// Takes the messages that have been sent by the script
+    pub fn take_output(&self) -> Vec<Message> {
+        std::mem::take(&mut self.output.borrow_mut())
+    }
+
+    /// Calls the script's handlers for the message, returning the number of handlers called
+    pub fn receive(&self, vm: &mut KotoVm, message: &Message) -> Result<usize> {
+        // The handlers are cloned so that new handlers can be registered by the handlers
+        let handlers = self.handlers.borrow().clone();
+
+        let mut calls = 0;
+        for (kind, handler) in handlers {
+            if kind.as_ref().is_none_or(|kind| kind == message.name()) {
+                vm.call_function(handler, KValue::from(message.clone()))?;
+                calls += 1;
+            }
+        }
+        Ok(calls)
+    }
+}
+
+fn system_message(args: &[KValue], message: Message) -> Result<KValue> {
+    match args {
+        [] => Ok(message.into()),
+        unexpected => type_error_with_slice("no arguments", unexpected),
+    }
+}
+
+fn channel_from_number(n: &KNumber) -> Result<u8> {
+    match u8::try_from(i64::from(n)) {
+        Ok(channel) if channel < 16 => Ok(channel),
+        _ => runtime_error!("Expected a channel in the range 0..=15, found {n}"),
+    }
+}
+
+fn data_from_number(n: &KNumber) -> Result<u8> {
+    match u8::try_from(i64::from(n)) {
+        Ok(value) if value < 128 => Ok(value),
+        _ => runtime_error!("Expected a value in the range 0..=127, found {n}"),
+    }
+}
+
+fn bytes_from_iterable(vm: &mut KotoVm, iterable: KValue) -> Result<Vec<u8>> {
+    let mut result = Vec::new();
+    for output in vm.make_iterator(iterable)? {
+        match output {
+            KIteratorOutput::Value(KValue::Number(n)) => match u8::try_from(i64::from(&n)) {
+                Ok(byte) => result.push(byte),
+                Err(_) => return runtime_error!("Expected a byte in the range 0..=255, found {n}"),
+            },
+            KIteratorOutput::Value(unexpected) => return type_error("a Number", &unexpected),
+            KIteratorOutput::ValuePair(..) => return runtime_error!("Expected a Number"),
+            KIteratorOutput::Error(error) => return Err(error),
+        }
+    }
+    Ok(result)
+}
+
+// Parses a note name like 'C4', 'F#2', or 'Bb-1', with middle C as 'C4'
+fn note_number(name: &str) -> Option<u8> {
+    let mut chars = name.chars();
+    let semitone = match chars.next()?.to_ascii_uppercase() {
+        'C' => 0,
+        'D' => 2,
+        'E' => 4,
+        'F' => 5,
+        'G' => 7,
+        'A' => 9,
+        'B' => 11,
+        _ => return None,
+    };
+    let rest = chars.as_str();
+    let (accidental, octave) = match rest.strip_prefix('#') {
+        Some(octave) => (1, octave),
+        None => match rest.strip_prefix('b') {
+            Some(octave) => (-1, octave),
+            None => (0, rest),
+        },
+    };
+    let octave: i32 = octave.parse().ok()?;
+    u8::try_from((octave + 1) * 12 + semitone + accidental)
+        .ok()
+        .filter(|note| *note < 128)
+}
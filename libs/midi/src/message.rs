@@ -0,0 +1,313 @@
+use koto_runtime::{derive::*, prelude::*, Result};
+
+/// A MIDI message
+///
+/// Channels are in the range 0-15, and data values are in the range 0-127,
+/// apart from pitch bend values which are in the range -8192-8191.
+#[derive(Clone, Debug, PartialEq, Eq, KotoType, KotoCopy)]
+pub enum Message {
+    NoteOff {
+        channel: u8,
+        note: u8,
+        velocity: u8,
+    },
+    NoteOn {
+        channel: u8,
+        note: u8,
+        velocity: u8,
+    },
+    PolyAftertouch {
+        channel: u8,
+        note: u8,
+        pressure: u8,
+    },
+    ControlChange {
+        channel: u8,
+        controller: u8,
+        value: u8,
+    },
+    ProgramChange {
+        channel: u8,
+        program: u8,
+    },
+    Aftertouch {
+        channel: u8,
+        pressure: u8,
+    },
+    PitchBend {
+        channel: u8,
+        value: i16,
+    },
+    /// A system exclusive message, with the data between the start and end bytes
+    SysEx(Vec<u8>),
+    Clock,
+    Start,
+    Continue,
+    Stop,
+    ActiveSensing,
+    Reset,
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Message {
+    /// Parses a message from bytes
+    pub fn from_bytes(bytes: &[u8]) -> std::result::Result<Self, String> {
+        let Some((status, data)) = bytes.split_first() else {
+            return Err("Expected at least one byte".into());
+        };
+        if status & 0x80 == 0 {
+            return Err(format!("Expected a status byte, found {status}"));
+        }
+        // System exclusive messages end with a status byte, which is checked below
+        let data_end = if *status == 0xf0 {
+            data.len().saturating_sub(1)
+        } else {
+            data.len()
+        };
+        if let Some(byte) = data[..data_end].iter().find(|byte| **byte & 0x80 != 0) {
+            return Err(format!("Unexpected status byte {byte} in message data"));
+        }
+
+        let channel = status & 0x0f;
+        let expect = |count: usize| {
+            if data.len() == count {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Expected {count} data byte{} after status byte {status}, found {}",
+                    if count == 1 { "" } else { "s" },
+                    data.len()
+                ))
+            }
+        };
+
+        let result = match status & 0xf0 {
+            0x80 => {
+                expect(2)?;
+                Self::NoteOff {
+                    channel,
+                    note: data[0],
+                    velocity: data[1],
+                }
+            }
+            0x90 => {
+                expect(2)?;
+                Self::NoteOn {
+                    channel,
+                    note: data[0],
+                    velocity: data[1],
+                }
+            }
+            0xa0 => {
+                expect(2)?;
+                Self::PolyAftertouch {
+                    channel,
+                    note: data[0],
+                    pressure: data[1],
+                }
+            }
+            0xb0 => {
+                expect(2)?;
+                Self::ControlChange {
+                    channel,
+                    controller: data[0],
+                    value: data[1],
+                }
+            }
+            0xc0 => {
+                expect(1)?;
+                Self::ProgramChange {
+                    channel,
+                    program: data[0],
+                }
+            }
+            0xd0 => {
+                expect(1)?;
+                Self::Aftertouch {
+                    channel,
+                    pressure: data[0],
+                }
+            }
+            0xe0 => {
+                expect(2)?;
+                let value = ((data[1] as i16) << 7 | data[0] as i16) - 8192;
+                Self::PitchBend { channel, value }
+            }
+            _ => match status {
+                0xf0 => match data.split_last() {
+                    Some((0xf7, sysex_data)) => Self::SysEx(sysex_data.to_vec()),
+                    _ => return Err("Expected a system exclusive message to end with 247".into()),
+                },
+                0xf8 | 0xfa | 0xfb | 0xfc | 0xfe | 0xff => {
+                    expect(0)?;
+                    match status {
+                        0xf8 => Self::Clock,
+                        0xfa => Self::Start,
+                        0xfb => Self::Continue,
+                        0xfc => Self::Stop,
+                        0xfe => Self::ActiveSensing,
+                        _ => Self::Reset,
+                    }
+                }
+                _ => return Err(format!("Unsupported status byte {status}")),
+            },
+        };
+
+        Ok(result)
+    }
+
+    /// Encodes the message as bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::NoteOff {
+                channel,
+                note,
+                velocity,
+            } => vec![0x80 | channel, *note, *velocity],
+            Self::NoteOn {
+                channel,
+                note,
+                velocity,
+            } => vec![0x90 | channel, *note, *velocity],
+            Self::PolyAftertouch {
+                channel,
+                note,
+                pressure,
+            } => vec![0xa0 | channel, *note, *pressure],
+            Self::ControlChange {
+                channel,
+                controller,
+                value,
+            } => vec![0xb0 | channel, *controller, *value],
+            Self::ProgramChange { channel, program } => vec![0xc0 | channel, *program],
+            Self::Aftertouch { channel, pressure } => vec![0xd0 | channel, *pressure],
+            Self::PitchBend { channel, value } => {
+                let value = (value + 8192) as u16;
+                vec![0xe0 | channel, (value & 0x7f) as u8, (value >> 7) as u8]
+            }
+            Self::SysEx(data) => {
+                let mut result = Vec::with_capacity(data.len() + 2);
+                result.push(0xf0);
+                result.extend_from_slice(data);
+                result.push(0xf7);
+                result
+            }
+            Self::Clock => vec![0xf8],
+            Self::Start => vec![0xfa],
+            Self::Continue => vec![0xfb],
+            Self::Stop => vec![0xfc],
+            Self::ActiveSensing => vec![0xfe],
+            Self::Reset => vec![0xff],
+        }
+    }
+
+    /// The message's kind, as returned by the `kind` method in Koto
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::NoteOff { .. } => "note_off",
+            Self::NoteOn { .. } => "note_on",
+            Self::PolyAftertouch { .. } => "poly_aftertouch",
+            Self::ControlChange { .. } => "control_change",
+            Self::ProgramChange { .. } => "program_change",
+            Self::Aftertouch { .. } => "aftertouch",
+            Self::PitchBend { .. } => "pitch_bend",
+            Self::SysEx(_) => "sysex",
+            Self::Clock => "clock",
+            Self::Start => "start",
+            Self::Continue => "continue",
+            Self::Stop => "stop",
+            Self::ActiveSensing => "active_sensing",
+            Self::Reset => "reset",
+        }
+    }
+
+    /// The message's channel, if it's a channel message
+    pub fn channel_number(&self) -> Option<u8> {
+        match self {
+            Self::NoteOff { channel, .. }
+            | Self::NoteOn { channel, .. }
+            | Self::PolyAftertouch { channel, .. }
+            | Self::ControlChange { channel, .. }
+            | Self::ProgramChange { channel, .. }
+            | Self::Aftertouch { channel, .. }
+            | Self::PitchBend { channel, .. } => Some(*channel),
+            _ => None,
+        }
+    }
+
+    // The message's data entries, excluding the kind and channel
+    fn fields(&self) -> Vec<(&'static str, KValue)> {
+        match self {
+            Self::NoteOff { note, velocity, .. } | Self::NoteOn { note, velocity, .. } => {
+                vec![("note", (*note).into()), ("velocity", (*velocity).into())]
+            }
+            Self::PolyAftertouch { note, pressure, .. } => {
+                vec![("note", (*note).into()), ("pressure", (*pressure).into())]
+            }
+            Self::ControlChange {
+                controller, value, ..
+            } => vec![
+                ("controller", (*controller).into()),
+                ("value", (*value).into()),
+            ],
+            Self::ProgramChange { program, .. } => vec![("program", (*program).into())],
+            Self::Aftertouch { pressure, .. } => vec![("pressure", (*pressure).into())],
+            Self::PitchBend { value, .. } => vec![("value", (*value).into())],
+            Self::SysEx(data) => {
+                let data: Vec<KValue> = data.iter().map(|byte| (*byte).into()).collect();
+                vec![("data", KValue::Tuple(data.into()))]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    #[koto_method]
+    fn bytes(&self) -> KValue {
+        let bytes: Vec<KValue> = self.to_bytes().into_iter().map(KValue::from).collect();
+        KValue::Tuple(bytes.into())
+    }
+
+    #[koto_method]
+    fn channel(&self) -> KValue {
+        self.channel_number().map_or(KValue::Null, KValue::from)
+    }
+
+    #[koto_method]
+    fn to_map(&self) -> KValue {
+        let result = KMap::with_capacity(4);
+        result.insert("kind", self.name());
+        if let Some(channel) = self.channel_number() {
+            result.insert("channel", channel);
+        }
+        for (key, value) in self.fields() {
+            result.insert(key, value);
+        }
+        result.into()
+    }
+
+    #[koto_method]
+    fn kind(&self) -> KValue {
+        self.name().into()
+    }
+}
+
+impl KotoObject for Message {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!("Message({}", self.name()));
+        if let Some(channel) = self.channel_number() {
+            ctx.append(format!(", channel: {channel}"));
+        }
+        for (key, value) in self.fields() {
+            ctx.append(format!(", {key}: "));
+            value.display(ctx)?;
+        }
+        ctx.append(')');
+        Ok(())
+    }
+}
+
+impl From<Message> for KValue {
+    fn from(message: Message) -> Self {
+        KObject::from(message).into()
+    }
+}
@@ -0,0 +1,64 @@
+use koto_midi::{make_module_with_host, Message, MidiHost};
+use koto_runtime::{prelude::*, Result};
+use koto_test_utils::check_script_output_with_vm;
+
+fn make_vm(host: &MidiHost) -> KotoVm {
+    let vm = KotoVm::default();
+    vm.prelude().insert("midi", make_module_with_host(host));
+    vm
+}
+
+#[test]
+fn take_output() -> Result<()> {
+    let host = MidiHost::default();
+    let vm = make_vm(&host);
+
+    let script = "
+midi.send midi.note_on 0, 60, 100
+midi.send midi.note_off 0, 60
+";
+    check_script_output_with_vm(vm, script, KValue::Null)?;
+
+    assert_eq!(
+        host.take_output(),
+        [
+            Message::NoteOn {
+                channel: 0,
+                note: 60,
+                velocity: 100
+            },
+            Message::NoteOff {
+                channel: 0,
+                note: 60,
+                velocity: 0
+            },
+        ]
+    );
+    assert!(host.take_output().is_empty());
+    Ok(())
+}
+
+#[test]
+fn receive_messages() -> Result<()> {
+    let host = MidiHost::default();
+    let mut vm = make_vm(&host);
+    assert!(!host.has_handlers());
+
+    // Incoming notes are echoed an octave higher
+    let script = "
+midi.on 'note_on', |message|
+  m = message.to_map()
+  midi.send midi.note_on m.channel, m.note + 12, m.velocity
+";
+    check_script_output_with_vm(vm.spawn_shared_vm(), script, KValue::Null)?;
+    assert!(host.has_handlers());
+
+    let note = Message::from_bytes(&[0x92, 48, 90]).unwrap();
+    assert_eq!(host.receive(&mut vm, &note)?, 1);
+    assert_eq!(host.receive(&mut vm, &Message::Clock)?, 0);
+
+    let output = host.take_output();
+    assert_eq!(output.len(), 1);
+    assert_eq!(output[0].to_bytes(), [0x92, 60, 90]);
+    Ok(())
+}
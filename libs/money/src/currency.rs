@@ -0,0 +1,111 @@
+use koto_runtime::{runtime_error, Result};
+use std::collections::HashMap;
+
+/// A currency, identified by its code
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Currency {
+    /// The currency's code, e.g. `USD`
+    pub code: String,
+    /// The number of decimal places used by the currency's minor unit, e.g. 2 for cents
+    pub digits: u8,
+    /// The symbol used when formatting amounts, e.g. `$`
+    pub symbol: Option<String>,
+}
+
+impl Currency {
+    /// Returns the number of minor units in a major unit, e.g. 100 cents in a dollar
+    pub fn minor_per_major(&self) -> i128 {
+        10i128.pow(self.digits.into())
+    }
+}
+
+/// The table of currencies that are available to scripts
+pub struct CurrencyTable {
+    currencies: HashMap<String, Currency>,
+}
+
+impl Default for CurrencyTable {
+    fn default() -> Self {
+        let mut currencies = HashMap::new();
+
+        let mut add = |code: &str, digits: u8, symbol: Option<&str>| {
+            currencies.insert(
+                code.to_string(),
+                Currency {
+                    code: code.to_string(),
+                    digits,
+                    symbol: symbol.map(String::from),
+                },
+            );
+        };
+
+        add("AUD", 2, Some("A$"));
+        add("BHD", 3, None);
+        add("BRL", 2, Some("R$"));
+        add("CAD", 2, Some("CA$"));
+        add("CHF", 2, None);
+        add("CLP", 0, None);
+        add("CNY", 2, Some("CN¥"));
+        add("CZK", 2, None);
+        add("DKK", 2, None);
+        add("EUR", 2, Some("€"));
+        add("GBP", 2, Some("£"));
+        add("HKD", 2, Some("HK$"));
+        add("HUF", 2, None);
+        add("IDR", 2, None);
+        add("ILS", 2, Some("₪"));
+        add("INR", 2, Some("₹"));
+        add("ISK", 0, None);
+        add("JOD", 3, None);
+        add("JPY", 0, Some("¥"));
+        add("KRW", 0, Some("₩"));
+        add("KWD", 3, None);
+        add("MXN", 2, Some("MX$"));
+        add("NOK", 2, None);
+        add("NZD", 2, Some("NZ$"));
+        add("OMR", 3, None);
+        add("PLN", 2, None);
+        add("SEK", 2, None);
+        add("SGD", 2, None);
+        add("THB", 2, None);
+        add("TND", 3, None);
+        add("TRY", 2, None);
+        add("TWD", 2, Some("NT$"));
+        add("USD", 2, Some("$"));
+        add("VND", 0, Some("₫"));
+        add("ZAR", 2, None);
+
+        Self { currencies }
+    }
+}
+
+impl CurrencyTable {
+    /// Returns the currency with the given code
+    pub fn get(&self, code: &str) -> Result<Currency> {
+        match self.currencies.get(code) {
+            Some(currency) => Ok(currency.clone()),
+            None => runtime_error!("unknown currency '{code}'"),
+        }
+    }
+
+    /// Adds a currency to the table, replacing any existing currency with the same code
+    pub fn define(&mut self, currency: Currency) -> Result<()> {
+        if currency.code.is_empty() || !currency.code.chars().all(|c| c.is_ascii_uppercase()) {
+            return runtime_error!(
+                "invalid currency code '{}', expected uppercase letters",
+                currency.code
+            );
+        }
+        if currency.digits > MAX_DIGITS {
+            return runtime_error!(
+                "too many decimal places for '{}' (the maximum is {MAX_DIGITS})",
+                currency.code
+            );
+        }
+        self.currencies.insert(currency.code.clone(), currency);
+        Ok(())
+    }
+}
+
+/// The maximum number of decimal places that a currency can use
+pub const MAX_DIGITS: u8 = 12;
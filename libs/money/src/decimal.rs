@@ -0,0 +1,155 @@
+use koto_runtime::{prelude::*, runtime_error, Result};
+
+/// The rounding rule used when an amount can't be represented exactly in a currency's minor unit
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rounding {
+    /// Round to the nearest value, with ties rounded to the nearest even value (banker's rounding)
+    #[default]
+    HalfEven,
+    /// Round to the nearest value, with ties rounded away from zero
+    HalfUp,
+    /// Round to the nearest value, with ties rounded towards zero
+    HalfDown,
+    /// Round away from zero
+    Up,
+    /// Round towards zero
+    Down,
+    /// Round towards positive infinity
+    Ceiling,
+    /// Round towards negative infinity
+    Floor,
+}
+
+impl Rounding {
+    /// Returns the rounding rule with the given name, e.g. `half_even`
+    pub fn from_name(name: &str) -> Result<Self> {
+        let result = match name {
+            "half_even" => Self::HalfEven,
+            "half_up" => Self::HalfUp,
+            "half_down" => Self::HalfDown,
+            "up" => Self::Up,
+            "down" => Self::Down,
+            "ceiling" => Self::Ceiling,
+            "floor" => Self::Floor,
+            _ => return runtime_error!("unknown rounding rule '{name}'"),
+        };
+        Ok(result)
+    }
+
+    /// Divides `n` by `d`, rounding the result using the rounding rule
+    pub fn divide(self, n: i128, d: i128) -> Result<i128> {
+        if d == 0 {
+            return runtime_error!("division by zero");
+        }
+        let (n, d) = if d < 0 { (-n, -d) } else { (n, d) };
+
+        let quotient = n / d;
+        let remainder = n % d;
+        if remainder == 0 {
+            return Ok(quotient);
+        }
+
+        let away_from_zero = quotient + n.signum();
+        let result = match self {
+            Self::Down => quotient,
+            Self::Up => away_from_zero,
+            Self::Floor => quotient.min(away_from_zero),
+            Self::Ceiling => quotient.max(away_from_zero),
+            Self::HalfEven | Self::HalfUp | Self::HalfDown => match (remainder.abs() * 2).cmp(&d) {
+                std::cmp::Ordering::Less => quotient,
+                std::cmp::Ordering::Greater => away_from_zero,
+                std::cmp::Ordering::Equal => match self {
+                    Self::HalfUp => away_from_zero,
+                    Self::HalfDown => quotient,
+                    _ if quotient % 2 == 0 => quotient,
+                    _ => away_from_zero,
+                },
+            },
+        };
+        Ok(result)
+    }
+}
+
+/// An exact decimal number, represented as `mantissa * 10^-scale`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Decimal {
+    pub mantissa: i128,
+    pub scale: u32,
+}
+
+// Limits the number of digits in parsed decimals to avoid overflowing during arithmetic
+const MAX_DECIMAL_DIGITS: usize = 28;
+
+impl Decimal {
+    /// Parses a decimal number from a string, e.g. `-12.345`
+    pub fn parse(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+        if (integer.is_empty() && fraction.is_empty())
+            || !integer
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return runtime_error!("invalid decimal number '{s}'");
+        }
+
+        // Trailing zeros in the fraction don't affect the value
+        let fraction = fraction.trim_end_matches('0');
+        let integer = integer.trim_start_matches('0');
+        if integer.len() + fraction.len() > MAX_DECIMAL_DIGITS {
+            return runtime_error!("too many digits in '{s}'");
+        }
+
+        let mut mantissa = 0i128;
+        for c in integer.chars().chain(fraction.chars()) {
+            mantissa = mantissa * 10 + i128::from(c as u8 - b'0');
+        }
+
+        Ok(Self {
+            mantissa: if negative { -mantissa } else { mantissa },
+            scale: fraction.len() as u32,
+        })
+    }
+
+    /// Converts a Koto number into a decimal
+    ///
+    /// Floats are converted using their shortest string representation,
+    /// so that e.g. `0.1` is treated as exactly `0.1`.
+    pub fn from_number(n: &KNumber) -> Result<Self> {
+        match n {
+            KNumber::I64(n) => Ok(Self {
+                mantissa: (*n).into(),
+                scale: 0,
+            }),
+            KNumber::F64(n) if n.is_finite() => Self::parse(&n.to_string()),
+            KNumber::F64(n) => runtime_error!("expected a finite number, found {n}"),
+        }
+    }
+
+    /// Converts the decimal into a whole number of units with the given number of decimal places
+    pub fn to_scale(self, digits: u32, rounding: Rounding) -> Result<i128> {
+        if self.scale <= digits {
+            self.mantissa
+                .checked_mul(pow10(digits - self.scale)?)
+                .map_or_else(overflow_error, Ok)
+        } else {
+            rounding.divide(self.mantissa, pow10(self.scale - digits)?)
+        }
+    }
+}
+
+/// Returns 10 raised to the given power
+pub fn pow10(exponent: u32) -> Result<i128> {
+    10i128.checked_pow(exponent).map_or_else(overflow_error, Ok)
+}
+
+/// Returns an error reporting that an amount is too large
+pub fn overflow_error<T>() -> Result<T> {
+    runtime_error!("the amount is too large")
+}
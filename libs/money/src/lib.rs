@@ -0,0 +1,129 @@
+//! A Koto language module for working with monetary amounts
+//!
+//! Amounts are tagged with a currency, and are stored as a whole number of the currency's minor
+//! unit (e.g. cents), so that arithmetic between amounts is exact.
+
+mod currency;
+mod decimal;
+mod money;
+
+pub use crate::{
+    currency::{Currency, CurrencyTable},
+    decimal::{Decimal, Rounding},
+    money::Money,
+};
+
+use koto_runtime::{prelude::*, PtrMut};
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("money");
+    let currencies = PtrMut::from(CurrencyTable::default());
+
+    {
+        let currencies = currencies.clone();
+        result.add_fn("amount", move |ctx| {
+            let (value, code, rounding) = match ctx.args() {
+                [value, KValue::Str(code)] => (value, code, Rounding::default()),
+                [value, KValue::Str(code), KValue::Str(rounding)] => {
+                    (value, code, Rounding::from_name(rounding)?)
+                }
+                unexpected => {
+                    return type_error_with_slice(
+                        "a Number or String, a currency code, and an optional rounding rule",
+                        unexpected,
+                    )
+                }
+            };
+            let value = match value {
+                KValue::Number(n) => Decimal::from_number(n)?,
+                KValue::Str(s) => Decimal::parse(s)?,
+                unexpected => return type_error("a Number or String", unexpected),
+            };
+            let currency = currencies.borrow().get(code)?;
+            Ok(Money::from_decimal(value, currency, rounding)?.into())
+        });
+    }
+
+    {
+        let currencies = currencies.clone();
+        result.add_fn("define_currency", move |ctx| {
+            let (code, digits, symbol) = match ctx.args() {
+                [KValue::Str(code), KValue::Number(digits)] if *digits >= 0 => (code, digits, None),
+                [KValue::Str(code), KValue::Number(digits), KValue::Str(symbol)]
+                    if *digits >= 0 =>
+                {
+                    (code, digits, Some(symbol.to_string()))
+                }
+                unexpected => {
+                    return type_error_with_slice(
+                        "a currency code, the number of decimal places, and an optional symbol",
+                        unexpected,
+                    )
+                }
+            };
+            currencies.borrow_mut().define(Currency {
+                code: code.to_string(),
+                digits: u8::try_from(i64::from(digits)).unwrap_or(u8::MAX),
+                symbol,
+            })?;
+            Ok(KValue::Null)
+        });
+    }
+
+    {
+        let currencies = currencies.clone();
+        result.add_fn("from_minor", move |ctx| match ctx.args() {
+            [KValue::Number(minor), KValue::Str(code)] if minor.is_i64() => {
+                let currency = currencies.borrow().get(code)?;
+                Ok(Money::from_minor(minor.into(), currency).into())
+            }
+            unexpected => type_error_with_slice(
+                "an integer number of minor units, and a currency code",
+                unexpected,
+            ),
+        });
+    }
+
+    {
+        let currencies = currencies.clone();
+        result.add_fn("sum", move |ctx| match ctx.args() {
+            [KValue::List(amounts), KValue::Str(code)] => {
+                let currency = currencies.borrow().get(code)?;
+                let mut total = 0i128;
+                for amount in amounts.data().iter() {
+                    match amount {
+                        KValue::Object(o) if o.is_a::<Money>() => {
+                            let amount = o.cast::<Money>()?;
+                            if amount.currency().code != currency.code {
+                                return runtime_error!(
+                                    "mismatched currencies: '{}' and '{}'",
+                                    currency.code,
+                                    amount.currency().code
+                                );
+                            }
+                            total += i128::from(amount.minor());
+                        }
+                        unexpected => return type_error("Money", unexpected),
+                    }
+                }
+                match i64::try_from(total) {
+                    Ok(total) => Ok(Money::from_minor(total, currency).into()),
+                    Err(_) => runtime_error!("the amount is too large"),
+                }
+            }
+            unexpected => {
+                type_error_with_slice("a List of amounts and a currency code", unexpected)
+            }
+        });
+    }
+
+    result.add_fn("zero", move |ctx| match ctx.args() {
+        [KValue::Str(code)] => {
+            let currency = currencies.borrow().get(code)?;
+            Ok(Money::from_minor(0, currency).into())
+        }
+        unexpected => type_error_with_slice("a currency code", unexpected),
+    });
+
+    result
+}
@@ -0,0 +1,368 @@
+use crate::{
+    decimal::{overflow_error, pow10, Decimal},
+    Currency, Rounding,
+};
+use koto_runtime::{derive::*, prelude::*, Borrow, Result};
+use std::cmp::Ordering;
+
+/// An amount of money in a specific currency, created with `money.amount`
+///
+/// Amounts are stored as a whole number of the currency's minor unit (e.g. cents),
+/// so arithmetic between amounts is exact.
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "Money")]
+pub struct Money {
+    minor: i64,
+    currency: Currency,
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Money {
+    /// Makes an amount from a whole number of the currency's minor unit
+    pub fn from_minor(minor: i64, currency: Currency) -> Self {
+        Self { minor, currency }
+    }
+
+    /// Makes an amount from a decimal value, rounding if the value has too many decimal places
+    pub fn from_decimal(value: Decimal, currency: Currency, rounding: Rounding) -> Result<Self> {
+        let minor = value.to_scale(currency.digits.into(), rounding)?;
+        Ok(Self::from_minor(to_i64(minor)?, currency))
+    }
+
+    /// Returns the amount as a whole number of the currency's minor unit
+    pub fn minor(&self) -> i64 {
+        self.minor
+    }
+
+    /// Returns the amount's currency
+    pub fn currency(&self) -> &Currency {
+        &self.currency
+    }
+
+    /// Returns the amount as a decimal string, with optional grouping of the integer digits
+    pub fn to_decimal_string(&self, grouping: bool) -> String {
+        let digits = usize::from(self.currency.digits);
+        let mut result = self.minor.unsigned_abs().to_string();
+        if result.len() <= digits {
+            result = format!("{}{result}", "0".repeat(digits + 1 - result.len()));
+        }
+
+        let (integer, fraction) = result.split_at(result.len() - digits);
+        let mut integer = integer.to_string();
+        if grouping {
+            let mut grouped = String::with_capacity(integer.len() + integer.len() / 3);
+            for (i, c) in integer.chars().enumerate() {
+                if i > 0 && (integer.len() - i) % 3 == 0 {
+                    grouped.push(',');
+                }
+                grouped.push(c);
+            }
+            integer = grouped;
+        }
+
+        let sign = if self.minor < 0 { "-" } else { "" };
+        if fraction.is_empty() {
+            format!("{sign}{integer}")
+        } else {
+            format!("{sign}{integer}.{fraction}")
+        }
+    }
+
+    /// Returns the amount multiplied by the given factor, rounded using the rounding rule
+    pub fn scale(&self, factor: Decimal, rounding: Rounding) -> Result<Self> {
+        let n = i128::from(self.minor)
+            .checked_mul(factor.mantissa)
+            .map_or_else(overflow_error, Ok)?;
+        let minor = rounding.divide(n, pow10(factor.scale)?)?;
+        self.with_minor(minor)
+    }
+
+    /// Returns the amount divided by the given divisor, rounded using the rounding rule
+    pub fn divide_by(&self, divisor: Decimal, rounding: Rounding) -> Result<Self> {
+        if divisor.mantissa == 0 {
+            return runtime_error!("division by zero");
+        }
+        let n = i128::from(self.minor)
+            .checked_mul(pow10(divisor.scale)?)
+            .map_or_else(overflow_error, Ok)?;
+        let minor = rounding.divide(n, divisor.mantissa)?;
+        self.with_minor(minor)
+    }
+
+    /// Splits the amount into parts that are proportional to the given ratios
+    ///
+    /// The parts always add up to the original amount, with any remaining minor units
+    /// distributed to the parts with the largest remainders.
+    pub fn allocate(&self, ratios: &[Decimal]) -> Result<Vec<Self>> {
+        if ratios.is_empty() {
+            return runtime_error!("expected at least one ratio");
+        }
+        if ratios.iter().any(|ratio| ratio.mantissa < 0) {
+            return runtime_error!("ratios can't be negative");
+        }
+
+        // Bring the ratios to a common scale so that they can be treated as integer weights
+        let scale = ratios.iter().map(|ratio| ratio.scale).max().unwrap_or(0);
+        let weights = ratios
+            .iter()
+            .map(|ratio| {
+                ratio
+                    .mantissa
+                    .checked_mul(pow10(scale - ratio.scale)?)
+                    .map_or_else(overflow_error, Ok)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let total_weight: i128 = weights.iter().sum();
+        if total_weight == 0 {
+            return runtime_error!("the sum of the ratios must be greater than zero");
+        }
+
+        let total = i128::from(self.minor.unsigned_abs());
+        let mut shares = Vec::with_capacity(weights.len());
+        let mut remainders = Vec::with_capacity(weights.len());
+        for (i, weight) in weights.iter().enumerate() {
+            let n = total.checked_mul(*weight).map_or_else(overflow_error, Ok)?;
+            shares.push(n / total_weight);
+            remainders.push((n % total_weight, i));
+        }
+
+        // Distribute the leftover minor units, largest remainders first
+        let leftover = total - shares.iter().sum::<i128>();
+        remainders.sort_by(|(a, i), (b, j)| b.cmp(a).then(i.cmp(j)));
+        for (_, i) in remainders.iter().take(leftover as usize) {
+            shares[*i] += 1;
+        }
+
+        let sign = self.minor.signum() as i128;
+        shares
+            .into_iter()
+            .map(|share| self.with_minor(share * sign))
+            .collect()
+    }
+
+    fn with_minor(&self, minor: i128) -> Result<Self> {
+        Ok(Self::from_minor(to_i64(minor)?, self.currency.clone()))
+    }
+
+    fn check_currency(&self, other: &Self) -> Result<()> {
+        if self.currency.code == other.currency.code {
+            Ok(())
+        } else {
+            runtime_error!(
+                "mismatched currencies: '{}' and '{}'",
+                self.currency.code,
+                other.currency.code
+            )
+        }
+    }
+
+    fn rhs_money<'a>(&self, rhs: &'a KValue, op: &str) -> Result<Borrow<'a, Self>> {
+        match rhs {
+            KValue::Object(o) if o.is_a::<Self>() => {
+                let rhs = o.cast::<Self>()?;
+                self.check_currency(&rhs)?;
+                Ok(rhs)
+            }
+            _ => unexpected_rhs(op, rhs),
+        }
+    }
+
+    fn compare(&self, rhs: &KValue, op: &str) -> Result<Ordering> {
+        let rhs = self.rhs_money(rhs, op)?;
+        Ok(self.minor.cmp(&rhs.minor))
+    }
+
+    #[koto_method]
+    fn abs(&self) -> Result<KValue> {
+        Ok(self.with_minor(i128::from(self.minor).abs())?.into())
+    }
+
+    #[koto_method(alias = "allocate")]
+    fn allocate_koto(&self, args: &[KValue]) -> Result<KValue> {
+        let ratios = match args {
+            [KValue::List(ratios)] => ratios
+                .data()
+                .iter()
+                .map(|ratio| match ratio {
+                    KValue::Number(n) => Decimal::from_number(n),
+                    unexpected => type_error("a Number", unexpected),
+                })
+                .collect::<Result<Vec<_>>>()?,
+            unexpected => return type_error_with_slice("a List of ratios", unexpected),
+        };
+        parts_to_list(self.allocate(&ratios)?)
+    }
+
+    #[koto_method(alias = "amount")]
+    fn amount_koto(&self) -> KValue {
+        if self.currency.digits == 0 {
+            self.minor.into()
+        } else {
+            (self.minor as f64 / 10f64.powi(self.currency.digits.into())).into()
+        }
+    }
+
+    #[koto_method(alias = "currency")]
+    fn currency_koto(&self) -> KValue {
+        self.currency.code.as_str().into()
+    }
+
+    #[koto_method]
+    fn format(&self) -> KValue {
+        let amount = self.to_decimal_string(true);
+        let result = match &self.currency.symbol {
+            Some(symbol) => match amount.strip_prefix('-') {
+                Some(amount) => format!("-{symbol}{amount}"),
+                None => format!("{symbol}{amount}"),
+            },
+            None => format!("{amount} {}", self.currency.code),
+        };
+        result.into()
+    }
+
+    #[koto_method]
+    fn is_negative(&self) -> KValue {
+        (self.minor < 0).into()
+    }
+
+    #[koto_method]
+    fn is_positive(&self) -> KValue {
+        (self.minor > 0).into()
+    }
+
+    #[koto_method]
+    fn is_zero(&self) -> KValue {
+        (self.minor == 0).into()
+    }
+
+    #[koto_method(alias = "minor_units")]
+    fn minor_units_koto(&self) -> KValue {
+        self.minor.into()
+    }
+
+    #[koto_method(alias = "scale")]
+    fn scale_koto(&self, args: &[KValue]) -> Result<KValue> {
+        let (factor, rounding) = match args {
+            [KValue::Number(factor)] => (factor, Rounding::default()),
+            [KValue::Number(factor), KValue::Str(rounding)] => {
+                (factor, Rounding::from_name(rounding)?)
+            }
+            unexpected => {
+                return type_error_with_slice(
+                    "a Number, and an optional rounding rule String",
+                    unexpected,
+                )
+            }
+        };
+        Ok(self.scale(Decimal::from_number(factor)?, rounding)?.into())
+    }
+
+    #[koto_method]
+    fn split(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Number(n)] if *n > 0 && n.is_i64() => {
+                let ratios = vec![Decimal::from_number(&1.into())?; usize::from(n)];
+                parts_to_list(self.allocate(&ratios)?)
+            }
+            unexpected => type_error_with_slice("a positive integer", unexpected),
+        }
+    }
+}
+
+impl KotoObject for Money {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!(
+            "{} {}",
+            self.to_decimal_string(false),
+            self.currency.code
+        ));
+        Ok(())
+    }
+
+    fn negate(&self, _vm: &mut KotoVm) -> Result<KValue> {
+        Ok(self.with_minor(-i128::from(self.minor))?.into())
+    }
+
+    fn add(&self, rhs: &KValue) -> Result<KValue> {
+        let rhs = self.rhs_money(rhs, "+")?;
+        Ok(self
+            .with_minor(i128::from(self.minor) + i128::from(rhs.minor))?
+            .into())
+    }
+
+    fn subtract(&self, rhs: &KValue) -> Result<KValue> {
+        let rhs = self.rhs_money(rhs, "-")?;
+        Ok(self
+            .with_minor(i128::from(self.minor) - i128::from(rhs.minor))?
+            .into())
+    }
+
+    fn multiply(&self, rhs: &KValue) -> Result<KValue> {
+        match rhs {
+            KValue::Number(n) => Ok(self
+                .scale(Decimal::from_number(n)?, Rounding::default())?
+                .into()),
+            _ => unexpected_rhs("*", rhs),
+        }
+    }
+
+    fn divide(&self, rhs: &KValue) -> Result<KValue> {
+        match rhs {
+            KValue::Number(n) => Ok(self
+                .divide_by(Decimal::from_number(n)?, Rounding::default())?
+                .into()),
+            _ => unexpected_rhs("/", rhs),
+        }
+    }
+
+    fn less(&self, rhs: &KValue) -> Result<bool> {
+        Ok(self.compare(rhs, "<")?.is_lt())
+    }
+
+    fn less_or_equal(&self, rhs: &KValue) -> Result<bool> {
+        Ok(self.compare(rhs, "<=")?.is_le())
+    }
+
+    fn greater(&self, rhs: &KValue) -> Result<bool> {
+        Ok(self.compare(rhs, ">")?.is_gt())
+    }
+
+    fn greater_or_equal(&self, rhs: &KValue) -> Result<bool> {
+        Ok(self.compare(rhs, ">=")?.is_ge())
+    }
+
+    fn equal(&self, rhs: &KValue) -> Result<bool> {
+        match rhs {
+            KValue::Object(o) if o.is_a::<Self>() => {
+                let rhs = o.cast::<Self>()?;
+                Ok(self.currency.code == rhs.currency.code && self.minor == rhs.minor)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn not_equal(&self, rhs: &KValue) -> Result<bool> {
+        self.equal(rhs).map(|result| !result)
+    }
+}
+
+impl From<Money> for KValue {
+    fn from(money: Money) -> Self {
+        KObject::from(money).into()
+    }
+}
+
+fn to_i64(minor: i128) -> Result<i64> {
+    i64::try_from(minor).map_or_else(|_| overflow_error(), Ok)
+}
+
+fn parts_to_list(parts: Vec<Money>) -> Result<KValue> {
+    Ok(KList::with_data(parts.into_iter().map(KValue::from).collect()).into())
+}
+
+fn unexpected_rhs<T>(op: &str, rhs: &KValue) -> Result<T> {
+    runtime_error!(
+        "unexpected '{}' as the right-hand side of '{op}' with Money",
+        rhs.type_as_string()
+    )
+}
@@ -0,0 +1,325 @@
+use koto_runtime::{derive::*, prelude::*, Result};
+
+// Gradient directions used by simplex noise, pointing to the edges of a cube
+const GRADIENTS_3D: [[f64; 3]; 12] = [
+    [1.0, 1.0, 0.0],
+    [-1.0, 1.0, 0.0],
+    [1.0, -1.0, 0.0],
+    [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0],
+    [-1.0, 0.0, 1.0],
+    [1.0, 0.0, -1.0],
+    [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0],
+    [0.0, -1.0, 1.0],
+    [0.0, 1.0, -1.0],
+    [0.0, -1.0, -1.0],
+];
+
+/// The noise algorithms that are provided by [NoiseGenerator]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    Perlin,
+    Simplex,
+}
+
+/// A generator of Perlin and simplex noise, with a permutation table derived from a seed
+///
+/// Generators with the same seed produce the same noise.
+/// Noise values are in the range -1..=1.
+#[derive(Clone, KotoCopy, KotoType)]
+#[koto(type_name = "Noise")]
+pub struct NoiseGenerator {
+    seed: u64,
+    // The shuffled values 0..=255, repeated so that lookups don't need to wrap
+    permutation: Box<[u8; 512]>,
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl NoiseGenerator {
+    /// Makes a new generator from the given seed
+    pub fn new(seed: u64) -> Self {
+        let mut values: [u8; 256] = std::array::from_fn(|i| i as u8);
+
+        // Fisher-Yates shuffle, driven by splitmix64
+        let mut state = seed;
+        for i in (1..values.len()).rev() {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^= z >> 31;
+            values.swap(i, (z % (i as u64 + 1)) as usize);
+        }
+
+        let permutation = Box::new(std::array::from_fn(|i| values[i & 255]));
+        Self { seed, permutation }
+    }
+
+    /// Returns the seed that was used to make the generator
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns 2D Perlin noise at the given position
+    pub fn perlin_2d(&self, x: f64, y: f64) -> f64 {
+        let (xi, yi) = (lattice(x), lattice(y));
+        let (xf, yf) = (x - x.floor(), y - y.floor());
+        let (u, v) = (fade(xf), fade(yf));
+
+        let a = self.hash(xi) + yi;
+        let b = self.hash(xi + 1) + yi;
+
+        lerp(
+            v,
+            lerp(
+                u,
+                gradient_2d(self.hash(a), xf, yf),
+                gradient_2d(self.hash(b), xf - 1.0, yf),
+            ),
+            lerp(
+                u,
+                gradient_2d(self.hash(a + 1), xf, yf - 1.0),
+                gradient_2d(self.hash(b + 1), xf - 1.0, yf - 1.0),
+            ),
+        )
+    }
+
+    /// Returns 3D Perlin noise at the given position
+    pub fn perlin_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        let (xi, yi, zi) = (lattice(x), lattice(y), lattice(z));
+        let (xf, yf, zf) = (x - x.floor(), y - y.floor(), z - z.floor());
+        let (u, v, w) = (fade(xf), fade(yf), fade(zf));
+
+        let a = self.hash(xi) + yi;
+        let aa = self.hash(a) + zi;
+        let ab = self.hash(a + 1) + zi;
+        let b = self.hash(xi + 1) + yi;
+        let ba = self.hash(b) + zi;
+        let bb = self.hash(b + 1) + zi;
+
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(
+                    u,
+                    gradient_3d(self.hash(aa), xf, yf, zf),
+                    gradient_3d(self.hash(ba), xf - 1.0, yf, zf),
+                ),
+                lerp(
+                    u,
+                    gradient_3d(self.hash(ab), xf, yf - 1.0, zf),
+                    gradient_3d(self.hash(bb), xf - 1.0, yf - 1.0, zf),
+                ),
+            ),
+            lerp(
+                v,
+                lerp(
+                    u,
+                    gradient_3d(self.hash(aa + 1), xf, yf, zf - 1.0),
+                    gradient_3d(self.hash(ba + 1), xf - 1.0, yf, zf - 1.0),
+                ),
+                lerp(
+                    u,
+                    gradient_3d(self.hash(ab + 1), xf, yf - 1.0, zf - 1.0),
+                    gradient_3d(self.hash(bb + 1), xf - 1.0, yf - 1.0, zf - 1.0),
+                ),
+            ),
+        )
+    }
+
+    /// Returns 2D simplex noise at the given position
+    pub fn simplex_2d(&self, x: f64, y: f64) -> f64 {
+        let f2 = 0.5 * (3.0f64.sqrt() - 1.0);
+        let g2 = (3.0 - 3.0f64.sqrt()) / 6.0;
+
+        // Find the simplex cell that contains the position
+        let s = (x + y) * f2;
+        let (i, j) = ((x + s).floor(), (y + s).floor());
+        let t = (i + j) * g2;
+        let (x0, y0) = (x - (i - t), y - (j - t));
+
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+        let (x1, y1) = (x0 - i1 as f64 + g2, y0 - j1 as f64 + g2);
+        let (x2, y2) = (x0 - 1.0 + 2.0 * g2, y0 - 1.0 + 2.0 * g2);
+
+        let (ii, jj) = (lattice(i), lattice(j));
+        let corner = |t: f64, hash: usize, x: f64, y: f64| {
+            if t < 0.0 {
+                0.0
+            } else {
+                let g = GRADIENTS_3D[hash % 12];
+                t.powi(4) * (g[0] * x + g[1] * y)
+            }
+        };
+
+        let n0 = corner(
+            0.5 - x0 * x0 - y0 * y0,
+            self.hash(ii + self.hash(jj)),
+            x0,
+            y0,
+        );
+        let n1 = corner(
+            0.5 - x1 * x1 - y1 * y1,
+            self.hash(ii + i1 + self.hash(jj + j1)),
+            x1,
+            y1,
+        );
+        let n2 = corner(
+            0.5 - x2 * x2 - y2 * y2,
+            self.hash(ii + 1 + self.hash(jj + 1)),
+            x2,
+            y2,
+        );
+
+        // Scale the result to the range -1..=1
+        70.0 * (n0 + n1 + n2)
+    }
+
+    /// Returns 3D simplex noise at the given position
+    pub fn simplex_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        let f3 = 1.0 / 3.0;
+        let g3 = 1.0 / 6.0;
+
+        // Find the simplex cell that contains the position
+        let s = (x + y + z) * f3;
+        let (i, j, k) = ((x + s).floor(), (y + s).floor(), (z + s).floor());
+        let t = (i + j + k) * g3;
+        let (x0, y0, z0) = (x - (i - t), y - (j - t), z - (k - t));
+
+        // Find the offsets of the second and third corners of the simplex
+        let ((i1, j1, k1), (i2, j2, k2)) = if x0 >= y0 {
+            if y0 >= z0 {
+                ((1, 0, 0), (1, 1, 0))
+            } else if x0 >= z0 {
+                ((1, 0, 0), (1, 0, 1))
+            } else {
+                ((0, 0, 1), (1, 0, 1))
+            }
+        } else if y0 < z0 {
+            ((0, 0, 1), (0, 1, 1))
+        } else if x0 < z0 {
+            ((0, 1, 0), (0, 1, 1))
+        } else {
+            ((0, 1, 0), (1, 1, 0))
+        };
+
+        let offset = |x: f64, i: usize, n: f64| x - i as f64 + n * g3;
+        let (x1, y1, z1) = (
+            offset(x0, i1, 1.0),
+            offset(y0, j1, 1.0),
+            offset(z0, k1, 1.0),
+        );
+        let (x2, y2, z2) = (
+            offset(x0, i2, 2.0),
+            offset(y0, j2, 2.0),
+            offset(z0, k2, 2.0),
+        );
+        let (x3, y3, z3) = (offset(x0, 1, 3.0), offset(y0, 1, 3.0), offset(z0, 1, 3.0));
+
+        let (ii, jj, kk) = (lattice(i), lattice(j), lattice(k));
+        let hash = |i: usize, j: usize, k: usize| {
+            self.hash(ii + i + self.hash(jj + j + self.hash(kk + k)))
+        };
+        let corner = |hash: usize, x: f64, y: f64, z: f64| {
+            let t = 0.6 - x * x - y * y - z * z;
+            if t < 0.0 {
+                0.0
+            } else {
+                let g = GRADIENTS_3D[hash % 12];
+                t.powi(4) * (g[0] * x + g[1] * y + g[2] * z)
+            }
+        };
+
+        let n0 = corner(hash(0, 0, 0), x0, y0, z0);
+        let n1 = corner(hash(i1, j1, k1), x1, y1, z1);
+        let n2 = corner(hash(i2, j2, k2), x2, y2, z2);
+        let n3 = corner(hash(1, 1, 1), x3, y3, z3);
+
+        // Scale the result to the range -1..=1
+        32.0 * (n0 + n1 + n2 + n3)
+    }
+
+    /// Returns noise at a 1D, 2D, or 3D position provided as Koto arguments
+    pub fn sample(&self, algorithm: Algorithm, args: &[KValue]) -> Result<KValue> {
+        use KValue::Number;
+
+        let result = match (algorithm, args) {
+            (Algorithm::Perlin, [Number(x)]) => self.perlin_2d(x.into(), 0.0),
+            (Algorithm::Perlin, [Number(x), Number(y)]) => self.perlin_2d(x.into(), y.into()),
+            (Algorithm::Perlin, [Number(x), Number(y), Number(z)]) => {
+                self.perlin_3d(x.into(), y.into(), z.into())
+            }
+            (Algorithm::Simplex, [Number(x)]) => self.simplex_2d(x.into(), 0.0),
+            (Algorithm::Simplex, [Number(x), Number(y)]) => self.simplex_2d(x.into(), y.into()),
+            (Algorithm::Simplex, [Number(x), Number(y), Number(z)]) => {
+                self.simplex_3d(x.into(), y.into(), z.into())
+            }
+            (_, unexpected) => return type_error_with_slice("1, 2, or 3 Numbers", unexpected),
+        };
+
+        Ok(result.into())
+    }
+
+    fn hash(&self, i: usize) -> usize {
+        self.permutation[i] as usize
+    }
+
+    #[koto_method]
+    fn perlin(&self, args: &[KValue]) -> Result<KValue> {
+        self.sample(Algorithm::Perlin, args)
+    }
+
+    #[koto_method]
+    fn simplex(&self, args: &[KValue]) -> Result<KValue> {
+        self.sample(Algorithm::Simplex, args)
+    }
+}
+
+impl KotoObject for NoiseGenerator {}
+
+impl From<NoiseGenerator> for KValue {
+    fn from(generator: NoiseGenerator) -> Self {
+        KObject::from(generator).into()
+    }
+}
+
+// Returns the index of the lattice cell containing the coordinate, wrapped to the table size
+fn lattice(x: f64) -> usize {
+    (x.floor() as i64 & 255) as usize
+}
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+    a + t * (b - a)
+}
+
+fn gradient_2d(hash: usize, x: f64, y: f64) -> f64 {
+    match hash & 7 {
+        0 => x + y,
+        1 => -x + y,
+        2 => x - y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
+
+fn gradient_3d(hash: usize, x: f64, y: f64, z: f64) -> f64 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
@@ -0,0 +1,41 @@
+//! A Koto language module for generating Perlin and simplex noise
+
+mod generator;
+
+pub use generator::{Algorithm, NoiseGenerator};
+
+use koto_runtime::prelude::*;
+use std::cell::RefCell;
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("noise");
+
+    result.add_fn("generator", |ctx| match ctx.args() {
+        [KValue::Number(seed)] => Ok(NoiseGenerator::new(seed.to_bits()).into()),
+        unexpected => type_error_with_slice("a seed Number", unexpected),
+    });
+
+    result.add_fn("perlin", |ctx| {
+        THREAD_NOISE.with_borrow(|noise| noise.sample(Algorithm::Perlin, ctx.args()))
+    });
+
+    result.add_fn("seed", |ctx| match ctx.args() {
+        [KValue::Number(seed)] => {
+            THREAD_NOISE.replace(NoiseGenerator::new(seed.to_bits()));
+            Ok(KValue::Null)
+        }
+        unexpected => type_error_with_slice("a seed Number", unexpected),
+    });
+
+    result.add_fn("simplex", |ctx| {
+        THREAD_NOISE.with_borrow(|noise| noise.sample(Algorithm::Simplex, ctx.args()))
+    });
+
+    result
+}
+
+thread_local! {
+    // The generator used by the module's functions, with a fixed seed so that scripts produce the
+    // same noise each time they're run.
+    static THREAD_NOISE: RefCell<NoiseGenerator> = RefCell::new(NoiseGenerator::new(0));
+}
@@ -0,0 +1,116 @@
+//! A Koto language module for sending and receiving Open Sound Control (OSC) messages
+//!
+//! Packets are sent and received over UDP with a [Socket], and incoming messages can be passed
+//! to a [Router], which calls the handlers that have been registered with matching address
+//! patterns.
+
+mod objects;
+mod packet;
+mod pattern;
+mod router;
+mod socket;
+
+pub use crate::{
+    objects::{arg_from_koto, arg_to_koto, packet_from_koto, OscBundle, OscMessage},
+    packet::{Arg, Bundle, DecodeError, Message, Packet, TimeTag},
+    pattern::matches,
+    router::Router,
+    socket::Socket,
+};
+
+use crate::objects::{bytes_from_slice, time_from_koto};
+use koto_runtime::{prelude::*, Result};
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("osc");
+
+    result.add_fn("bundle", |ctx| {
+        let (contents, time) = match ctx.args() {
+            [contents] if contents.is_iterable() => (contents.clone(), TimeTag::IMMEDIATELY),
+            [contents, KValue::Null] if contents.is_iterable() => {
+                (contents.clone(), TimeTag::IMMEDIATELY)
+            }
+            [contents, KValue::Number(time)] if contents.is_iterable() => {
+                (contents.clone(), time_from_koto(time)?)
+            }
+            unexpected => {
+                return type_error_with_slice(
+                    "an iterable of Messages and Bundles, and an optional time",
+                    unexpected,
+                )
+            }
+        };
+
+        let mut elements = Vec::new();
+        for output in ctx.vm.make_iterator(contents)? {
+            match output {
+                KIteratorOutput::Value(value) => elements.push(packet_from_koto(&value)?),
+                KIteratorOutput::ValuePair(..) => {
+                    return runtime_error!("Expected a Message or Bundle")
+                }
+                KIteratorOutput::Error(error) => return Err(error),
+            }
+        }
+
+        Ok(Bundle {
+            time,
+            contents: elements,
+        }
+        .into())
+    });
+
+    result.add_fn("decode", |ctx| {
+        let bytes = match ctx.args() {
+            [bytes] if bytes.is_iterable() => bytes.clone(),
+            unexpected => return type_error_with_slice("an iterable of bytes", unexpected),
+        };
+
+        let mut values = Vec::new();
+        for output in ctx.vm.make_iterator(bytes)? {
+            match output {
+                KIteratorOutput::Value(value) => values.push(value),
+                KIteratorOutput::ValuePair(..) => return runtime_error!("Expected a Number"),
+                KIteratorOutput::Error(error) => return Err(error),
+            }
+        }
+        let bytes = bytes_from_slice(&values)?;
+        Packet::decode(&bytes)
+            .map(KValue::from)
+            .map_err(|e| format!("Failed to decode OSC packet: {e}").into())
+    });
+
+    result.add_fn("matches", |ctx| match ctx.args() {
+        [KValue::Str(pattern), KValue::Str(address)] => Ok(matches(pattern, address).into()),
+        unexpected => type_error_with_slice("an address pattern and an address", unexpected),
+    });
+
+    result.add_fn("message", |ctx| match ctx.args() {
+        [KValue::Str(address), args @ ..] => {
+            if !address.starts_with('/') {
+                return runtime_error!("Expected an address starting with '/', found '{address}'");
+            }
+            let args = args.iter().map(arg_from_koto).collect::<Result<_>>()?;
+            Ok(Message {
+                address: address.to_string(),
+                args,
+            }
+            .into())
+        }
+        unexpected => type_error_with_slice(
+            "an address String, followed by the message's arguments",
+            unexpected,
+        ),
+    });
+
+    result.add_fn("router", |ctx| match ctx.args() {
+        [] => Ok(Router::default().into()),
+        unexpected => type_error_with_slice("no arguments", unexpected),
+    });
+
+    result.add_fn("socket", |ctx| match ctx.args() {
+        [KValue::Str(address)] => Socket::bind(address).map(KValue::from),
+        unexpected => type_error_with_slice("an address String", unexpected),
+    });
+
+    result
+}
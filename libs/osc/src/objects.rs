@@ -0,0 +1,212 @@
+use crate::packet::{Arg, Bundle, Message, Packet, TimeTag};
+use koto_runtime::{derive::*, prelude::*, Result};
+use std::time::Duration;
+
+/// The Koto type for OSC messages
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "Message")]
+pub struct OscMessage(pub Message);
+
+#[koto_impl(runtime = koto_runtime)]
+impl OscMessage {
+    #[koto_method]
+    fn address(&self) -> KValue {
+        self.0.address.as_str().into()
+    }
+
+    #[koto_method]
+    fn args(&self) -> KValue {
+        let args: Vec<KValue> = self.0.args.iter().map(arg_to_koto).collect();
+        KValue::Tuple(args.into())
+    }
+
+    #[koto_method]
+    fn encode(&self) -> KValue {
+        bytes_to_koto(&Packet::Message(self.0.clone()).encode())
+    }
+}
+
+impl KotoObject for OscMessage {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!("Message('{}'", self.0.address));
+        for arg in self.0.args.iter() {
+            ctx.append(", ");
+            let arg = arg_to_koto(arg);
+            match &arg {
+                KValue::Str(s) => ctx.append(format!("'{s}'")),
+                _ => arg.display(ctx)?,
+            }
+        }
+        ctx.append(')');
+        Ok(())
+    }
+
+    fn index(&self, index: &KValue) -> Result<KValue> {
+        match index {
+            KValue::Number(n) => match self.0.args.get(usize::from(n)) {
+                Some(arg) if *n >= 0 => Ok(arg_to_koto(arg)),
+                _ => runtime_error!(
+                    "Index out of bounds - index: {n}, size: {}",
+                    self.0.args.len()
+                ),
+            },
+            unexpected => type_error("a Number as index", unexpected),
+        }
+    }
+
+    fn size(&self) -> Option<usize> {
+        Some(self.0.args.len())
+    }
+}
+
+impl From<Message> for KValue {
+    fn from(message: Message) -> Self {
+        KObject::from(OscMessage(message)).into()
+    }
+}
+
+/// The Koto type for OSC bundles
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "Bundle")]
+pub struct OscBundle(pub Bundle);
+
+#[koto_impl(runtime = koto_runtime)]
+impl OscBundle {
+    #[koto_method]
+    fn contents(&self) -> KValue {
+        let contents: Vec<KValue> = self.0.contents.iter().cloned().map(KValue::from).collect();
+        KValue::Tuple(contents.into())
+    }
+
+    #[koto_method]
+    fn encode(&self) -> KValue {
+        bytes_to_koto(&Packet::Bundle(self.0.clone()).encode())
+    }
+
+    #[koto_method]
+    fn time(&self) -> KValue {
+        time_to_koto(self.0.time)
+    }
+}
+
+impl KotoObject for OscBundle {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        let count = self.0.contents.len();
+        ctx.append(format!(
+            "Bundle({count} element{})",
+            if count == 1 { "" } else { "s" }
+        ));
+        Ok(())
+    }
+
+    fn size(&self) -> Option<usize> {
+        Some(self.0.contents.len())
+    }
+}
+
+impl From<Bundle> for KValue {
+    fn from(bundle: Bundle) -> Self {
+        KObject::from(OscBundle(bundle)).into()
+    }
+}
+
+impl From<Packet> for KValue {
+    fn from(packet: Packet) -> Self {
+        match packet {
+            Packet::Message(message) => message.into(),
+            Packet::Bundle(bundle) => bundle.into(),
+        }
+    }
+}
+
+/// Gets a packet from a Koto value, which must be a `Message` or a `Bundle`
+pub fn packet_from_koto(value: &KValue) -> Result<Packet> {
+    match value {
+        KValue::Object(o) if o.is_a::<OscMessage>() => {
+            Ok(Packet::Message(o.cast::<OscMessage>()?.0.clone()))
+        }
+        KValue::Object(o) if o.is_a::<OscBundle>() => {
+            Ok(Packet::Bundle(o.cast::<OscBundle>()?.0.clone()))
+        }
+        unexpected => type_error("a Message or Bundle", unexpected),
+    }
+}
+
+/// Converts a Koto value into an OSC argument
+///
+/// Tuples of numbers are converted into blobs.
+pub fn arg_from_koto(value: &KValue) -> Result<Arg> {
+    let result = match value {
+        KValue::Number(KNumber::I64(n)) => match i32::try_from(*n) {
+            Ok(n) => Arg::Int(n),
+            Err(_) => Arg::Long(*n),
+        },
+        KValue::Number(KNumber::F64(n)) => Arg::Float(*n as f32),
+        KValue::Str(s) => Arg::String(s.to_string()),
+        KValue::Bool(b) => Arg::Bool(*b),
+        KValue::Null => Arg::Nil,
+        KValue::Tuple(bytes) => Arg::Blob(bytes_from_slice(bytes)?),
+        unexpected => {
+            return type_error(
+                "a Number, String, Bool, null, or a Tuple of bytes",
+                unexpected,
+            )
+        }
+    };
+    Ok(result)
+}
+
+/// Converts an OSC argument into a Koto value
+pub fn arg_to_koto(arg: &Arg) -> KValue {
+    match arg {
+        Arg::Int(n) => (*n).into(),
+        Arg::Long(n) => (*n).into(),
+        Arg::Float(n) => (*n as f64).into(),
+        Arg::Double(n) => (*n).into(),
+        Arg::String(s) => s.as_str().into(),
+        Arg::Blob(bytes) => bytes_to_koto(bytes),
+        Arg::Bool(b) => (*b).into(),
+        Arg::Nil | Arg::Impulse => KValue::Null,
+        Arg::Time(time) => time_to_koto(*time),
+        Arg::Char(c) => c.to_string().into(),
+        Arg::Color(bytes) | Arg::Midi(bytes) => bytes_to_koto(bytes),
+    }
+}
+
+/// Gets a time tag from a number of seconds since the Unix epoch
+pub fn time_from_koto(seconds: &KNumber) -> Result<TimeTag> {
+    match Duration::try_from_secs_f64(f64::from(seconds)) {
+        Ok(time) => Ok(TimeTag::from_unix_time(time)),
+        Err(_) => runtime_error!("Expected a positive number of seconds, found {seconds}"),
+    }
+}
+
+// Time tags are converted into seconds since the Unix epoch,
+// with null representing 'immediately'
+fn time_to_koto(time: TimeTag) -> KValue {
+    if time == TimeTag::IMMEDIATELY {
+        return KValue::Null;
+    }
+    match time.to_unix_time() {
+        Some(time) => time.as_secs_f64().into(),
+        None => KValue::Null,
+    }
+}
+
+pub fn bytes_from_slice(values: &[KValue]) -> Result<Vec<u8>> {
+    values
+        .iter()
+        .map(|value| match value {
+            KValue::Number(n) => match u8::try_from(i64::from(n)) {
+                Ok(byte) => Ok(byte),
+                Err(_) => runtime_error!("Expected a byte in the range 0..=255, found {n}"),
+            },
+            unexpected => type_error("a Number", unexpected),
+        })
+        .collect()
+}
+
+fn bytes_to_koto(bytes: &[u8]) -> KValue {
+    let bytes: Vec<KValue> = bytes.iter().map(|byte| KValue::from(*byte)).collect();
+    KValue::Tuple(bytes.into())
+}
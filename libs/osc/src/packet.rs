@@ -0,0 +1,402 @@
+use std::{fmt, time::Duration};
+
+/// An argument of an OSC message
+#[derive(Clone, Debug, PartialEq)]
+pub enum Arg {
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Blob(Vec<u8>),
+    Bool(bool),
+    Nil,
+    Impulse,
+    Time(TimeTag),
+    Char(char),
+    Color([u8; 4]),
+    Midi([u8; 4]),
+}
+
+impl Arg {
+    fn type_tag(&self) -> char {
+        match self {
+            Self::Int(_) => 'i',
+            Self::Long(_) => 'h',
+            Self::Float(_) => 'f',
+            Self::Double(_) => 'd',
+            Self::String(_) => 's',
+            Self::Blob(_) => 'b',
+            Self::Bool(true) => 'T',
+            Self::Bool(false) => 'F',
+            Self::Nil => 'N',
+            Self::Impulse => 'I',
+            Self::Time(_) => 't',
+            Self::Char(_) => 'c',
+            Self::Color(_) => 'r',
+            Self::Midi(_) => 'm',
+        }
+    }
+}
+
+/// An OSC time tag, used to schedule the contents of a bundle
+///
+/// Time tags are NTP timestamps, with the number of seconds since 1900 and a fractional part.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeTag {
+    pub seconds: u32,
+    pub fraction: u32,
+}
+
+// The number of seconds between the NTP epoch (1900) and the Unix epoch (1970)
+const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+
+impl TimeTag {
+    /// The special time tag that indicates that a bundle should be handled immediately
+    pub const IMMEDIATELY: Self = Self {
+        seconds: 0,
+        fraction: 1,
+    };
+
+    /// Makes a time tag from a duration since the Unix epoch
+    pub fn from_unix_time(time: Duration) -> Self {
+        let fraction = (time.subsec_nanos() as u64 * (1 << 32) / 1_000_000_000) as u32;
+        Self {
+            seconds: (time.as_secs() + NTP_UNIX_OFFSET) as u32,
+            fraction,
+        }
+    }
+
+    /// Returns the time tag as a duration since the Unix epoch
+    ///
+    /// `None` is returned for times before the Unix epoch.
+    pub fn to_unix_time(self) -> Option<Duration> {
+        let seconds = (self.seconds as u64).checked_sub(NTP_UNIX_OFFSET)?;
+        let nanos = (self.fraction as u64 * 1_000_000_000) >> 32;
+        Some(Duration::new(seconds, nanos as u32))
+    }
+
+    fn to_u64(self) -> u64 {
+        ((self.seconds as u64) << 32) | self.fraction as u64
+    }
+
+    fn from_u64(n: u64) -> Self {
+        Self {
+            seconds: (n >> 32) as u32,
+            fraction: n as u32,
+        }
+    }
+}
+
+/// An OSC message, with an address and a list of arguments
+#[derive(Clone, Debug, PartialEq)]
+pub struct Message {
+    pub address: String,
+    pub args: Vec<Arg>,
+}
+
+/// An OSC bundle, containing messages and bundles that should be handled at the same time
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bundle {
+    pub time: TimeTag,
+    pub contents: Vec<Packet>,
+}
+
+/// An OSC packet, either a message or a bundle
+#[derive(Clone, Debug, PartialEq)]
+pub enum Packet {
+    Message(Message),
+    Bundle(Bundle),
+}
+
+/// An error that occurred while decoding an OSC packet
+#[derive(Debug)]
+pub struct DecodeError(String);
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+const BUNDLE_ID: &[u8] = b"#bundle\0";
+
+impl Packet {
+    /// Encodes the packet as bytes
+    pub fn encode(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+        encode_packet(self, &mut result);
+        result
+    }
+
+    /// Decodes a packet from bytes
+    pub fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut reader = Reader { bytes, position: 0 };
+        let result = reader.read_packet()?;
+        if reader.position != bytes.len() {
+            return reader.error("Unexpected data after the end of the packet");
+        }
+        Ok(result)
+    }
+}
+
+fn encode_packet(packet: &Packet, output: &mut Vec<u8>) {
+    match packet {
+        Packet::Message(message) => {
+            write_string(&message.address, output);
+            let type_tags: String = std::iter::once(',')
+                .chain(message.args.iter().map(Arg::type_tag))
+                .collect();
+            write_string(&type_tags, output);
+            for arg in message.args.iter() {
+                encode_arg(arg, output);
+            }
+        }
+        Packet::Bundle(bundle) => {
+            output.extend_from_slice(BUNDLE_ID);
+            output.extend_from_slice(&bundle.time.to_u64().to_be_bytes());
+            for element in bundle.contents.iter() {
+                // Each element is prefixed with its size, which is filled in after encoding
+                let size_position = output.len();
+                output.extend_from_slice(&[0; 4]);
+                encode_packet(element, output);
+                let size = (output.len() - size_position - 4) as u32;
+                output[size_position..size_position + 4].copy_from_slice(&size.to_be_bytes());
+            }
+        }
+    }
+}
+
+fn encode_arg(arg: &Arg, output: &mut Vec<u8>) {
+    match arg {
+        Arg::Int(n) => output.extend_from_slice(&n.to_be_bytes()),
+        Arg::Long(n) => output.extend_from_slice(&n.to_be_bytes()),
+        Arg::Float(n) => output.extend_from_slice(&n.to_be_bytes()),
+        Arg::Double(n) => output.extend_from_slice(&n.to_be_bytes()),
+        Arg::String(s) => write_string(s, output),
+        Arg::Blob(blob) => {
+            output.extend_from_slice(&(blob.len() as u32).to_be_bytes());
+            output.extend_from_slice(blob);
+            pad(output);
+        }
+        Arg::Bool(_) | Arg::Nil | Arg::Impulse => {}
+        Arg::Time(time) => output.extend_from_slice(&time.to_u64().to_be_bytes()),
+        Arg::Char(c) => output.extend_from_slice(&(*c as u32).to_be_bytes()),
+        Arg::Color(bytes) | Arg::Midi(bytes) => output.extend_from_slice(bytes),
+    }
+}
+
+// Writes a null-terminated string, padded to a multiple of 4 bytes
+fn write_string(s: &str, output: &mut Vec<u8>) {
+    output.extend_from_slice(s.as_bytes());
+    output.push(0);
+    pad(output);
+}
+
+fn pad(output: &mut Vec<u8>) {
+    while !output.len().is_multiple_of(4) {
+        output.push(0);
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn error<T>(&self, message: &str) -> Result<T, DecodeError> {
+        Err(DecodeError(format!(
+            "{message} (at byte {})",
+            self.position
+        )))
+    }
+
+    fn read_bytes(&mut self, count: usize) -> Result<&'a [u8], DecodeError> {
+        match self.bytes.get(self.position..self.position + count) {
+            Some(result) => {
+                self.position += count;
+                Ok(result)
+            }
+            None => self.error("Unexpected end of packet"),
+        }
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        let mut result = [0; N];
+        result.copy_from_slice(self.read_bytes(N)?);
+        Ok(result)
+    }
+
+    fn skip_padding(&mut self) -> Result<(), DecodeError> {
+        let padding = (4 - self.position % 4) % 4;
+        self.read_bytes(padding).map(|_| ())
+    }
+
+    fn read_string(&mut self) -> Result<String, DecodeError> {
+        let remaining = &self.bytes[self.position..];
+        let Some(length) = remaining.iter().position(|b| *b == 0) else {
+            return self.error("Unterminated string");
+        };
+        let Ok(result) = std::str::from_utf8(&remaining[..length]) else {
+            return self.error("Invalid UTF-8 in string");
+        };
+        self.position += length + 1;
+        self.skip_padding()?;
+        Ok(result.to_string())
+    }
+
+    fn read_packet(&mut self) -> Result<Packet, DecodeError> {
+        match self.bytes.get(self.position) {
+            Some(b'/') => self.read_message().map(Packet::Message),
+            Some(b'#') => self.read_bundle().map(Packet::Bundle),
+            Some(_) => self.error("Expected an address or a bundle"),
+            None => self.error("Unexpected end of packet"),
+        }
+    }
+
+    fn read_message(&mut self) -> Result<Message, DecodeError> {
+        let address = self.read_string()?;
+
+        // Type tags are optional in older implementations
+        if self.position == self.bytes.len() {
+            return Ok(Message {
+                address,
+                args: Vec::new(),
+            });
+        }
+
+        let type_tags = self.read_string()?;
+        let Some(type_tags) = type_tags.strip_prefix(',') else {
+            return self.error("Expected type tags");
+        };
+
+        let mut args = Vec::with_capacity(type_tags.len());
+        for tag in type_tags.chars() {
+            let arg = match tag {
+                'i' => Arg::Int(i32::from_be_bytes(self.read_array()?)),
+                'h' => Arg::Long(i64::from_be_bytes(self.read_array()?)),
+                'f' => Arg::Float(f32::from_be_bytes(self.read_array()?)),
+                'd' => Arg::Double(f64::from_be_bytes(self.read_array()?)),
+                's' | 'S' => Arg::String(self.read_string()?),
+                'b' => {
+                    let length = u32::from_be_bytes(self.read_array()?) as usize;
+                    let blob = self.read_bytes(length)?.to_vec();
+                    self.skip_padding()?;
+                    Arg::Blob(blob)
+                }
+                'T' => Arg::Bool(true),
+                'F' => Arg::Bool(false),
+                'N' => Arg::Nil,
+                'I' => Arg::Impulse,
+                't' => Arg::Time(TimeTag::from_u64(u64::from_be_bytes(self.read_array()?))),
+                'c' => match char::from_u32(u32::from_be_bytes(self.read_array()?)) {
+                    Some(c) => Arg::Char(c),
+                    None => return self.error("Invalid char"),
+                },
+                'r' => Arg::Color(self.read_array()?),
+                'm' => Arg::Midi(self.read_array()?),
+                // Arrays aren't supported, and neither are other unknown types,
+                // whose sizes can't be known
+                other => return self.error(&format!("Unsupported type tag '{other}'")),
+            };
+            args.push(arg);
+        }
+
+        Ok(Message { address, args })
+    }
+
+    fn read_bundle(&mut self) -> Result<Bundle, DecodeError> {
+        if self.read_bytes(BUNDLE_ID.len())? != BUNDLE_ID {
+            return self.error("Invalid bundle");
+        }
+        let time = TimeTag::from_u64(u64::from_be_bytes(self.read_array()?));
+
+        let mut contents = Vec::new();
+        while self.position < self.bytes.len() {
+            let size = u32::from_be_bytes(self.read_array()?) as usize;
+            let element_bytes = self.read_bytes(size)?;
+            let mut element_reader = Reader {
+                bytes: element_bytes,
+                position: 0,
+            };
+            let element = element_reader.read_packet()?;
+            contents.push(element);
+        }
+
+        Ok(Bundle { time, contents })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_message() {
+        let message = Packet::Message(Message {
+            address: "/oscillator/4/frequency".into(),
+            args: vec![Arg::Float(440.0)],
+        });
+
+        // The example from the OSC 1.0 specification
+        let expected = [
+            0x2f, 0x6f, 0x73, 0x63, 0x69, 0x6c, 0x6c, 0x61, 0x74, 0x6f, 0x72, 0x2f, 0x34, 0x2f,
+            0x66, 0x72, 0x65, 0x71, 0x75, 0x65, 0x6e, 0x63, 0x79, 0x00, 0x2c, 0x66, 0x00, 0x00,
+            0x43, 0xdc, 0x00, 0x00,
+        ];
+        assert_eq!(message.encode(), expected);
+        assert_eq!(Packet::decode(&expected).unwrap(), message);
+    }
+
+    #[test]
+    fn round_trip_bundle() {
+        let packet = Packet::Bundle(Bundle {
+            time: TimeTag::from_unix_time(Duration::from_millis(1_500)),
+            contents: vec![
+                Packet::Message(Message {
+                    address: "/a".into(),
+                    args: vec![
+                        Arg::Int(-1),
+                        Arg::Long(1 << 40),
+                        Arg::Double(0.5),
+                        Arg::String("hello".into()),
+                        Arg::Blob(vec![1, 2, 3]),
+                        Arg::Bool(true),
+                        Arg::Nil,
+                        Arg::Char('x'),
+                        Arg::Midi([0, 0x90, 60, 100]),
+                    ],
+                }),
+                Packet::Bundle(Bundle {
+                    time: TimeTag::IMMEDIATELY,
+                    contents: vec![],
+                }),
+            ],
+        });
+
+        let bytes = packet.encode();
+        assert_eq!(bytes.len() % 4, 0);
+        assert_eq!(Packet::decode(&bytes).unwrap(), packet);
+    }
+
+    #[test]
+    fn unix_time() {
+        let time = Duration::from_millis(1_700_000_000_250);
+        let time_tag = TimeTag::from_unix_time(time);
+        let round_tripped = time_tag.to_unix_time().unwrap();
+        assert!(round_tripped.abs_diff(time) < Duration::from_micros(1));
+    }
+
+    #[test]
+    fn truncated_packet() {
+        let bytes = Packet::Message(Message {
+            address: "/a".into(),
+            args: vec![Arg::Int(1)],
+        })
+        .encode();
+        assert!(Packet::decode(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,117 @@
+/// Returns true if the OSC address matches the address pattern
+///
+/// The pattern is matched one address part at a time, with the following syntax:
+///
+/// - `?` matches any single character.
+/// - `*` matches any sequence of characters, including an empty sequence.
+/// - `[abc]` matches any of the characters in the brackets, with ranges like `[a-z]`.
+///   If the first character in the brackets is `!` then the set is negated.
+/// - `{foo,bar}` matches any of the comma-separated strings in the braces.
+///
+/// Wildcards never match the `/` character that separates the parts of an address.
+pub fn matches(pattern: &str, address: &str) -> bool {
+    let mut pattern_parts = pattern.split('/');
+    let mut address_parts = address.split('/');
+
+    loop {
+        match (pattern_parts.next(), address_parts.next()) {
+            (Some(pattern_part), Some(address_part)) => {
+                let pattern_part: Vec<char> = pattern_part.chars().collect();
+                let address_part: Vec<char> = address_part.chars().collect();
+                if !matches_part(&pattern_part, &address_part) {
+                    return false;
+                }
+            }
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+fn matches_part(pattern: &[char], address: &[char]) -> bool {
+    let Some((first, rest)) = pattern.split_first() else {
+        return address.is_empty();
+    };
+
+    match first {
+        '*' => (0..=address.len()).any(|skipped| matches_part(rest, &address[skipped..])),
+        '?' => !address.is_empty() && matches_part(rest, &address[1..]),
+        '[' => {
+            let Some(end) = rest.iter().position(|c| *c == ']') else {
+                return false;
+            };
+            match address.split_first() {
+                Some((c, address_rest)) => {
+                    matches_set(&rest[..end], *c) && matches_part(&rest[end + 1..], address_rest)
+                }
+                None => false,
+            }
+        }
+        '{' => {
+            let Some(end) = rest.iter().position(|c| *c == '}') else {
+                return false;
+            };
+            let after = &rest[end + 1..];
+            rest[..end].split(|c| *c == ',').any(|alternative| {
+                address.starts_with(alternative)
+                    && matches_part(after, &address[alternative.len()..])
+            })
+        }
+        c => address.first() == Some(c) && matches_part(rest, &address[1..]),
+    }
+}
+
+fn matches_set(set: &[char], c: char) -> bool {
+    let (negated, set) = match set.split_first() {
+        Some(('!', rest)) => (true, rest),
+        _ => (false, set),
+    };
+
+    let mut found = false;
+    let mut i = 0;
+    while i < set.len() {
+        if i + 2 < set.len() && set[i + 1] == '-' {
+            found |= (set[i]..=set[i + 2]).contains(&c);
+            i += 3;
+        } else {
+            found |= set[i] == c;
+            i += 1;
+        }
+    }
+
+    found != negated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literal_addresses() {
+        assert!(matches("/synth/freq", "/synth/freq"));
+        assert!(!matches("/synth/freq", "/synth/amp"));
+        assert!(!matches("/synth", "/synth/freq"));
+        assert!(!matches("/synth/freq", "/synth"));
+    }
+
+    #[test]
+    fn wildcards() {
+        assert!(matches("/synth/*", "/synth/freq"));
+        assert!(matches("/*/freq", "/synth/freq"));
+        assert!(matches("/synth/f*q", "/synth/freq"));
+        assert!(!matches("/*", "/synth/freq"));
+        assert!(matches("/voice/?", "/voice/1"));
+        assert!(!matches("/voice/?", "/voice/10"));
+    }
+
+    #[test]
+    fn sets_and_alternatives() {
+        assert!(matches("/voice/[1-4]", "/voice/3"));
+        assert!(!matches("/voice/[1-4]", "/voice/5"));
+        assert!(matches("/voice/[!1-4]", "/voice/5"));
+        assert!(matches("/voice/[abc]", "/voice/b"));
+        assert!(matches("/synth/{freq,amp}", "/synth/amp"));
+        assert!(!matches("/synth/{freq,amp}", "/synth/pan"));
+        assert!(matches("/{a,ab}c", "/abc"));
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{
+    objects::packet_from_koto,
+    packet::{Message, Packet},
+    pattern,
+};
+use koto_runtime::{derive::*, prelude::*, Result};
+
+/// Dispatches OSC messages to the handlers with matching address patterns
+#[derive(Clone, Default, KotoType, KotoCopy)]
+pub struct Router {
+    handlers: Vec<(String, KValue)>,
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Router {
+    /// Calls the handlers that match the packet's messages, returning the number of calls made
+    ///
+    /// The messages contained in bundles are dispatched immediately, in order,
+    /// regardless of the bundle's time tag.
+    pub fn dispatch_packet(&self, vm: &mut KotoVm, packet: &Packet) -> Result<usize> {
+        match packet {
+            Packet::Message(message) => self.dispatch_message(vm, message),
+            Packet::Bundle(bundle) => bundle.contents.iter().try_fold(0, |calls, element| {
+                Ok(calls + self.dispatch_packet(vm, element)?)
+            }),
+        }
+    }
+
+    fn dispatch_message(&self, vm: &mut KotoVm, message: &Message) -> Result<usize> {
+        let mut calls = 0;
+        for (pattern, handler) in self.handlers.iter() {
+            if pattern::matches(pattern, &message.address) {
+                vm.call_function(handler.clone(), KValue::from(message.clone()))?;
+                calls += 1;
+            }
+        }
+        Ok(calls)
+    }
+
+    #[koto_method]
+    fn dispatch(ctx: MethodContext<Self>) -> Result<KValue> {
+        let packet = match ctx.args {
+            [packet] => packet_from_koto(packet)?,
+            unexpected => return type_error_with_slice("a Message or Bundle", unexpected),
+        };
+
+        let this = ctx.instance()?.clone();
+        let calls = this.dispatch_packet(&mut ctx.vm.spawn_shared_vm(), &packet)?;
+        Ok(calls.into())
+    }
+
+    #[koto_method]
+    fn on(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [KValue::Str(pattern), handler] if handler.is_callable() => {
+                ctx.instance_mut()?
+                    .handlers
+                    .push((pattern.to_string(), handler.clone()));
+                ctx.instance_result()
+            }
+            unexpected => type_error_with_slice("an address pattern and a Function", unexpected),
+        }
+    }
+}
+
+impl KotoObject for Router {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        let count = self.handlers.len();
+        ctx.append(format!(
+            "Router({count} handler{})",
+            if count == 1 { "" } else { "s" }
+        ));
+        Ok(())
+    }
+}
+
+impl From<Router> for KValue {
+    fn from(router: Router) -> Self {
+        KObject::from(router).into()
+    }
+}
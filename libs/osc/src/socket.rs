@@ -0,0 +1,111 @@
+use crate::{objects::packet_from_koto, packet::Packet};
+use koto_runtime::{derive::*, prelude::*, Ptr, Result};
+use std::{io, net::UdpSocket, time::Duration};
+
+// The largest packet that can be received
+const MAX_PACKET_SIZE: usize = 65_536;
+
+/// A UDP socket for sending and receiving OSC packets
+#[derive(Clone, KotoType, KotoCopy)]
+pub struct Socket(Ptr<UdpSocket>);
+
+#[koto_impl(runtime = koto_runtime)]
+impl Socket {
+    /// Makes a socket that's bound to the given address
+    pub fn bind(address: &str) -> Result<Self> {
+        UdpSocket::bind(address)
+            .map(|socket| Self(socket.into()))
+            .map_err(|e| format!("Failed to bind to '{address}': {e}").into())
+    }
+
+    /// Receives a packet, waiting for up to the given timeout
+    ///
+    /// `None` is returned if the timeout elapses before a packet is received.
+    pub fn receive_packet(&self, timeout: Option<Duration>) -> Result<Option<Packet>> {
+        self.0
+            .set_read_timeout(timeout)
+            .map_err(|e| format!("Failed to set the socket's timeout: {e}"))?;
+
+        let mut buffer = vec![0; MAX_PACKET_SIZE];
+        match self.0.recv(&mut buffer) {
+            Ok(size) => Packet::decode(&buffer[..size])
+                .map(Some)
+                .map_err(|e| format!("Failed to decode OSC packet: {e}").into()),
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                Ok(None)
+            }
+            Err(e) => runtime_error!("Failed to receive OSC packet: {e}"),
+        }
+    }
+
+    /// Sends a packet to the given address
+    pub fn send_packet(&self, packet: &Packet, address: &str) -> Result<()> {
+        self.0
+            .send_to(&packet.encode(), address)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to send OSC packet to '{address}': {e}").into())
+    }
+
+    #[koto_method]
+    fn local_address(&self) -> Result<KValue> {
+        self.0
+            .local_addr()
+            .map(|address| address.to_string().into())
+            .map_err(|e| format!("Failed to get the socket's address: {e}").into())
+    }
+
+    #[koto_method]
+    fn receive(&self, args: &[KValue]) -> Result<KValue> {
+        let timeout = match args {
+            [] => None,
+            [KValue::Number(seconds)] => match Duration::try_from_secs_f64(f64::from(seconds)) {
+                // A zero timeout isn't allowed by the socket, so the shortest timeout is used
+                Ok(timeout) => Some(timeout.max(Duration::from_nanos(1))),
+                Err(_) => return runtime_error!("Expected a positive timeout, found {seconds}"),
+            },
+            unexpected => {
+                return type_error_with_slice("an optional timeout in seconds", unexpected)
+            }
+        };
+
+        match self.receive_packet(timeout)? {
+            Some(packet) => Ok(packet.into()),
+            None => Ok(KValue::Null),
+        }
+    }
+
+    #[koto_method]
+    fn send(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [packet, KValue::Str(address)] => {
+                self.send_packet(&packet_from_koto(packet)?, address)?;
+                Ok(KValue::Null)
+            }
+            unexpected => type_error_with_slice(
+                "a Message or Bundle, followed by an address String",
+                unexpected,
+            ),
+        }
+    }
+}
+
+impl KotoObject for Socket {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        match self.0.local_addr() {
+            Ok(address) => ctx.append(format!("Socket({address})")),
+            Err(_) => ctx.append("Socket"),
+        }
+        Ok(())
+    }
+}
+
+impl From<Socket> for KValue {
+    fn from(socket: Socket) -> Self {
+        KObject::from(socket).into()
+    }
+}
@@ -0,0 +1,64 @@
+//! A Koto language module for reading Parquet files
+
+mod reader;
+
+use crate::reader::{metadata, open, ReadOptions, RowIterator};
+use koto_runtime::{prelude::*, Result};
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("parquet");
+
+    result.add_fn("metadata", |ctx| match ctx.args() {
+        [KValue::Str(path)] => Ok(metadata(&open(path)?).into()),
+        unexpected => type_error_with_slice("a path String", unexpected),
+    });
+
+    result.add_fn("read", |ctx| {
+        let (path, options) = path_and_options(ctx.args())?;
+        let reader = open(&path)?;
+        let iterator = RowIterator::new(reader, options)?;
+        Ok(KIterator::new(iterator).into())
+    });
+
+    result.add_fn("read_columns", |ctx| {
+        let (path, options) = path_and_options(ctx.args())?;
+        let reader = open(&path)?;
+        let iterator = RowIterator::new(reader, options)?;
+
+        let result = KMap::new();
+        let columns: Vec<_> = iterator
+            .output_columns()
+            .map(|column| {
+                let values = KList::default();
+                result.insert(column, values.clone());
+                (column.to_string(), values)
+            })
+            .collect();
+
+        for output in iterator {
+            let row = match output {
+                KIteratorOutput::Value(KValue::Map(row)) => row,
+                KIteratorOutput::Error(error) => return Err(error),
+                _ => unreachable!(),
+            };
+            for (column, values) in columns.iter() {
+                let value = row.get(column.as_str()).unwrap_or_default();
+                values.data_mut().push(value);
+            }
+        }
+
+        Ok(result.into())
+    });
+
+    result
+}
+
+fn path_and_options(args: &[KValue]) -> Result<(KString, ReadOptions)> {
+    match args {
+        [KValue::Str(path)] => Ok((path.clone(), ReadOptions::default())),
+        [KValue::Str(path), KValue::Map(options)] => {
+            Ok((path.clone(), ReadOptions::from_map(options)?))
+        }
+        unexpected => type_error_with_slice("a path String, and an optional Map", unexpected),
+    }
+}
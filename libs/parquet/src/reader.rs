@@ -0,0 +1,396 @@
+use koto_runtime::{prelude::*, Ptr, Result};
+use parquet::{
+    basic::ConvertedType,
+    file::{
+        metadata::RowGroupMetaData,
+        reader::{FileReader, SerializedFileReader},
+        statistics::Statistics,
+    },
+    record::{Field, Row},
+    schema::types::Type,
+};
+use std::{collections::VecDeque, fs::File};
+
+pub type Reader = SerializedFileReader<File>;
+
+pub fn open(path: &str) -> Result<Reader> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) => return runtime_error!("Failed to open '{path}': {e}"),
+    };
+    match SerializedFileReader::new(file) {
+        Ok(reader) => Ok(reader),
+        Err(e) => runtime_error!("Failed to read '{path}': {e}"),
+    }
+}
+
+/// Returns a map containing the row counts and column types of the file
+pub fn metadata(reader: &Reader) -> KMap {
+    let metadata = reader.metadata();
+
+    let columns = KMap::new();
+    for field in metadata.file_metadata().schema().get_fields() {
+        columns.insert(field.name(), field_type_name(field));
+    }
+
+    let result = KMap::with_capacity(3);
+    result.insert("rows", metadata.file_metadata().num_rows());
+    result.insert("row_groups", metadata.num_row_groups() as i64);
+    result.insert("columns", columns);
+    result
+}
+
+// Returns a name for the field's type, e.g. 'INT64' or 'UTF8'
+fn field_type_name(field: &Type) -> String {
+    let converted_type = field.get_basic_info().converted_type();
+    if converted_type != ConvertedType::NONE {
+        converted_type.to_string()
+    } else if field.is_primitive() {
+        field.get_physical_type().to_string()
+    } else {
+        "GROUP".into()
+    }
+}
+
+/// Options that control which columns and rows are read from a file
+#[derive(Default)]
+pub struct ReadOptions {
+    columns: Option<Vec<String>>,
+    filters: Vec<(String, Filter)>,
+}
+
+impl ReadOptions {
+    pub fn from_map(map: &KMap) -> Result<Self> {
+        let mut result = Self::default();
+
+        if let Some(columns) = map.get("columns") {
+            let columns = match &columns {
+                KValue::List(list) => list.data().to_vec(),
+                KValue::Tuple(tuple) => tuple.to_vec(),
+                unexpected => return type_error("a List or Tuple as 'columns'", unexpected),
+            };
+            let names = columns
+                .iter()
+                .map(|column| match column {
+                    KValue::Str(name) => Ok(name.to_string()),
+                    unexpected => type_error("a column name String", unexpected),
+                })
+                .collect::<Result<_>>()?;
+            result.columns = Some(names);
+        }
+
+        match map.get("filter") {
+            Some(KValue::Map(filter)) => {
+                for (column, value) in filter.data().iter() {
+                    let filter = match value {
+                        KValue::Range(range) => Filter::Range(range.clone()),
+                        KValue::Number(_) | KValue::Str(_) | KValue::Bool(_) => {
+                            Filter::Equal(value.clone())
+                        }
+                        unexpected => {
+                            return type_error(
+                                "a Number, String, Bool, or Range as filter",
+                                unexpected,
+                            )
+                        }
+                    };
+                    result.filters.push((column.to_string(), filter));
+                }
+            }
+            Some(unexpected) => return type_error("a Map as 'filter'", &unexpected),
+            None => {}
+        }
+
+        Ok(result)
+    }
+}
+
+// A condition that a column's value must satisfy for a row to be included in the output
+enum Filter {
+    Equal(KValue),
+    Range(KRange),
+}
+
+impl Filter {
+    fn matches(&self, value: &KValue) -> bool {
+        match (self, value) {
+            (Filter::Equal(KValue::Number(expected)), KValue::Number(n)) => expected == n,
+            (Filter::Equal(KValue::Str(expected)), KValue::Str(s)) => expected == s,
+            (Filter::Equal(KValue::Bool(expected)), KValue::Bool(b)) => expected == b,
+            (Filter::Range(range), KValue::Number(n)) => range.contains(*n),
+            _ => false,
+        }
+    }
+
+    // Returns false if the column statistics show that no value in the row group can match
+    fn may_match(&self, statistics: &Statistics) -> bool {
+        use Statistics::*;
+
+        let numbers = match statistics {
+            Int32(s) => s
+                .min_opt()
+                .zip(s.max_opt())
+                .map(|(a, b)| (*a as f64, *b as f64)),
+            Int64(s) => s
+                .min_opt()
+                .zip(s.max_opt())
+                .map(|(a, b)| (*a as f64, *b as f64)),
+            Float(s) => s
+                .min_opt()
+                .zip(s.max_opt())
+                .map(|(a, b)| (*a as f64, *b as f64)),
+            Double(s) => s.min_opt().zip(s.max_opt()).map(|(a, b)| (*a, *b)),
+            ByteArray(s) => {
+                let strings = s
+                    .min_opt()
+                    .zip(s.max_opt())
+                    .and_then(|(a, b)| a.as_utf8().ok().zip(b.as_utf8().ok()));
+                return match (self, strings) {
+                    (Filter::Equal(KValue::Str(expected)), Some((min, max))) => {
+                        min <= expected.as_str() && expected.as_str() <= max
+                    }
+                    _ => true,
+                };
+            }
+            _ => None,
+        };
+
+        match (self, numbers) {
+            (Filter::Equal(KValue::Number(expected)), Some((min, max))) => {
+                let expected = f64::from(expected);
+                min <= expected && expected <= max
+            }
+            (Filter::Range(range), Some((min, max))) => {
+                let range = range.as_sorted_range();
+                max >= range.start as f64 && min < range.end as f64
+            }
+            _ => true,
+        }
+    }
+}
+
+/// An iterator that produces the rows of a Parquet file as maps
+///
+/// Row groups are read one at a time as iteration progresses.
+/// Row groups are skipped when their column statistics show that they can't contain rows that
+/// match the filter.
+#[derive(Clone)]
+pub struct RowIterator {
+    reader: Ptr<Reader>,
+    options: Ptr<ReadOptions>,
+    projection: Option<Type>,
+    // Columns that were only read so that they could be used by filters
+    filter_only_columns: Ptr<Vec<String>>,
+    next_row_group: usize,
+    rows: VecDeque<KValue>,
+}
+
+impl RowIterator {
+    pub fn new(reader: Reader, options: ReadOptions) -> Result<Self> {
+        let schema = reader.metadata().file_metadata().schema();
+        let fields = schema.get_fields();
+
+        for name in options
+            .columns
+            .iter()
+            .flatten()
+            .chain(options.filters.iter().map(|(name, _)| name))
+        {
+            if !fields.iter().any(|field| field.name() == name) {
+                return runtime_error!("Unknown column '{name}'");
+            }
+        }
+
+        let (projection, filter_only_columns) = match &options.columns {
+            Some(columns) => {
+                let filter_only_columns: Vec<String> = options
+                    .filters
+                    .iter()
+                    .map(|(name, _)| name)
+                    .filter(|name| !columns.contains(name))
+                    .cloned()
+                    .collect();
+
+                // Projected fields need to follow the order of the file's schema
+                let projected_fields = fields
+                    .iter()
+                    .filter(|field| {
+                        let name = field.name().to_string();
+                        columns.contains(&name) || filter_only_columns.contains(&name)
+                    })
+                    .cloned()
+                    .collect();
+
+                let projection = Type::group_type_builder(schema.name())
+                    .with_fields(projected_fields)
+                    .build();
+                match projection {
+                    Ok(projection) => (Some(projection), filter_only_columns),
+                    Err(e) => return runtime_error!("Failed to select columns: {e}"),
+                }
+            }
+            None => (None, Vec::new()),
+        };
+
+        Ok(Self {
+            reader: reader.into(),
+            options: options.into(),
+            projection,
+            filter_only_columns: filter_only_columns.into(),
+            next_row_group: 0,
+            rows: VecDeque::new(),
+        })
+    }
+
+    /// Returns the names of the columns that will be included in each row
+    pub fn output_columns(&self) -> impl Iterator<Item = &str> {
+        let schema_columns = self
+            .reader
+            .metadata()
+            .file_metadata()
+            .schema()
+            .get_fields()
+            .iter()
+            .map(|field| field.name());
+
+        schema_columns.filter(|name| match &self.options.columns {
+            Some(columns) => columns.iter().any(|column| column == name),
+            None => true,
+        })
+    }
+
+    fn row_group_may_match(&self, metadata: &RowGroupMetaData) -> bool {
+        self.options.filters.iter().all(|(name, filter)| {
+            metadata
+                .columns()
+                .iter()
+                .find(|column| column.column_path().string() == *name)
+                .and_then(|column| column.statistics())
+                .is_none_or(|statistics| filter.may_match(statistics))
+        })
+    }
+
+    fn read_row_group(&self, index: usize) -> Result<VecDeque<KValue>> {
+        let read_error = |e| runtime_error!("Failed to read row group {index}: {e}");
+
+        let row_group = match self.reader.get_row_group(index) {
+            Ok(row_group) => row_group,
+            Err(e) => return read_error(e),
+        };
+        let rows = match row_group.get_row_iter(self.projection.clone()) {
+            Ok(rows) => rows,
+            Err(e) => return read_error(e),
+        };
+
+        let mut result = VecDeque::new();
+        for row in rows {
+            let row = match row {
+                Ok(row) => row,
+                Err(e) => return read_error(e),
+            };
+            let row = row_to_map(&row);
+
+            let matches_filters = self.options.filters.iter().all(|(name, filter)| {
+                row.get(name.as_str())
+                    .is_some_and(|value| filter.matches(&value))
+            });
+
+            if matches_filters {
+                for name in self.filter_only_columns.iter() {
+                    row.data_mut().shift_remove(name.as_str());
+                }
+                result.push_back(row.into());
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl KotoIterator for RowIterator {
+    fn make_copy(&self) -> Result<KIterator> {
+        Ok(KIterator::new(self.clone()))
+    }
+}
+
+impl Iterator for RowIterator {
+    type Item = KIteratorOutput;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(row) = self.rows.pop_front() {
+                return Some(KIteratorOutput::Value(row));
+            }
+
+            let index = self.next_row_group;
+            if index >= self.reader.metadata().num_row_groups() {
+                return None;
+            }
+            self.next_row_group += 1;
+
+            if !self.row_group_may_match(self.reader.metadata().row_group(index)) {
+                continue;
+            }
+
+            match self.read_row_group(index) {
+                Ok(rows) => self.rows = rows,
+                Err(error) => return Some(KIteratorOutput::Error(error)),
+            }
+        }
+    }
+}
+
+fn row_to_map(row: &Row) -> KMap {
+    let result = KMap::with_capacity(row.len());
+    for (name, field) in row.get_column_iter() {
+        result.insert(name.as_str(), field_to_value(field));
+    }
+    result
+}
+
+// Converts a Parquet field into a Koto value
+//
+// Dates, timestamps, and decimals are converted into strings.
+fn field_to_value(field: &Field) -> KValue {
+    match field {
+        Field::Null => KValue::Null,
+        Field::Bool(b) => (*b).into(),
+        Field::Byte(n) => (*n).into(),
+        Field::Short(n) => (*n).into(),
+        Field::Int(n) => (*n).into(),
+        Field::Long(n) => (*n).into(),
+        Field::UByte(n) => (*n).into(),
+        Field::UShort(n) => (*n).into(),
+        Field::UInt(n) => (*n).into(),
+        Field::ULong(n) => match i64::try_from(*n) {
+            Ok(n) => n.into(),
+            Err(_) => (*n as f64).into(),
+        },
+        Field::Float16(n) => f64::from(n.to_f32()).into(),
+        Field::Float(n) => f64::from(*n).into(),
+        Field::Double(n) => (*n).into(),
+        Field::Str(s) => s.as_str().into(),
+        Field::Bytes(bytes) => {
+            let bytes: Vec<KValue> = bytes.data().iter().map(|b| KValue::from(*b)).collect();
+            KValue::Tuple(bytes.into())
+        }
+        Field::Group(row) => row_to_map(row).into(),
+        Field::ListInternal(list) => {
+            let values = list.elements().iter().map(field_to_value).collect();
+            KList::with_data(values).into()
+        }
+        Field::MapInternal(map) => {
+            let result = KMap::new();
+            for (key, value) in map.entries() {
+                if let Ok(key) = ValueKey::try_from(field_to_value(key)) {
+                    result.data_mut().insert(key, field_to_value(value));
+                }
+            }
+            result.into()
+        }
+        Field::Date(_)
+        | Field::TimestampMillis(_)
+        | Field::TimestampMicros(_)
+        | Field::Decimal(_) => field.to_string().into(),
+    }
+}
@@ -0,0 +1,97 @@
+//! A Koto language module of parser combinators
+//!
+//! Parsers are built by combining simple parsers, e.g. `parse.sequence(parse.tag('-'), digits)`,
+//! and report the line and column of the input where parsing failed.
+
+mod parser;
+
+pub use parser::{ParseError, Parser, ParserKind};
+
+use koto_runtime::prelude::*;
+use parser::min_count_arg;
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("parse");
+
+    result.add_fn("choice", |ctx| {
+        Ok(Parser::new(ParserKind::Choice(parsers_from_args(ctx.args())?)).into())
+    });
+
+    result.add_fn("many", |ctx| match ctx.args() {
+        [KValue::Object(o), rest @ ..] if o.is_a::<Parser>() => {
+            let parser = o.cast::<Parser>()?.clone();
+            let min = min_count_arg(rest)?;
+            Ok(Parser::new(ParserKind::Many { parser, min }).into())
+        }
+        unexpected => type_error_with_slice(
+            "a Parser, and an optional non-negative minimum count",
+            unexpected,
+        ),
+    });
+
+    result.add_fn("map", |ctx| match ctx.args() {
+        [KValue::Object(o), f] if o.is_a::<Parser>() && f.is_callable() => {
+            let parser = o.cast::<Parser>()?.clone();
+            Ok(Parser::new(ParserKind::Map {
+                parser,
+                f: f.clone(),
+            })
+            .into())
+        }
+        unexpected => type_error_with_slice("a Parser and a function", unexpected),
+    });
+
+    result.add_fn("position", |ctx| match ctx.args() {
+        [] => Ok(Parser::new(ParserKind::Position).into()),
+        unexpected => type_error_with_slice("no arguments", unexpected),
+    });
+
+    result.add_fn("sequence", |ctx| {
+        Ok(Parser::new(ParserKind::Sequence(parsers_from_args(ctx.args())?)).into())
+    });
+
+    result.add_fn("tag", |ctx| match ctx.args() {
+        [KValue::Str(tag)] if !tag.is_empty() => {
+            Ok(Parser::new(ParserKind::Tag(tag.clone())).into())
+        }
+        unexpected => type_error_with_slice("a non-empty String", unexpected),
+    });
+
+    result.add_fn("take_while", |ctx| match ctx.args() {
+        [predicate, rest @ ..] if predicate.is_callable() => {
+            let min = min_count_arg(rest)?;
+            Ok(Parser::new(ParserKind::TakeWhile {
+                predicate: predicate.clone(),
+                min,
+            })
+            .into())
+        }
+        unexpected => type_error_with_slice(
+            "a predicate function, and an optional non-negative minimum count",
+            unexpected,
+        ),
+    });
+
+    result
+}
+
+// Parsers can be provided as separate arguments, or in a List or Tuple
+fn parsers_from_args(args: &[KValue]) -> koto_runtime::Result<Vec<Parser>> {
+    let parsers = match args {
+        [KValue::List(list)] => list.data().to_vec(),
+        [KValue::Tuple(tuple)] => tuple.to_vec(),
+        _ => args.to_vec(),
+    };
+
+    if parsers.is_empty() {
+        return runtime_error!("expected at least one Parser");
+    }
+
+    parsers
+        .iter()
+        .map(|parser| match parser {
+            KValue::Object(o) if o.is_a::<Parser>() => Ok(o.cast::<Parser>()?.clone()),
+            unexpected => type_error("a Parser", unexpected),
+        })
+        .collect()
+}
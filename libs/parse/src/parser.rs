@@ -0,0 +1,349 @@
+use koto_runtime::{derive::*, prelude::*, Ptr, Result};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A parser, created by the `parse` module's combinators
+///
+/// Parsers are immutable, and can be reused and combined to make new parsers.
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "Parser")]
+pub struct Parser(Ptr<ParserKind>);
+
+/// The different kinds of [Parser]
+pub enum ParserKind {
+    /// Matches a string
+    Tag(KString),
+    /// Matches characters while a predicate function returns true
+    TakeWhile {
+        /// The function that's called with each character
+        predicate: KValue,
+        /// The minimum number of characters that need to match
+        min: usize,
+    },
+    /// Matches each of the parsers in order
+    Sequence(Vec<Parser>),
+    /// Matches the first successful parser
+    Choice(Vec<Parser>),
+    /// Matches the parser repeatedly
+    Many {
+        /// The parser that's matched repeatedly
+        parser: Parser,
+        /// The minimum number of matches
+        min: usize,
+    },
+    /// Passes the parser's result to a function, producing a new result
+    Map {
+        /// The parser that produces the value that gets mapped
+        parser: Parser,
+        /// The function that maps the value
+        f: KValue,
+    },
+    /// Produces the current position in the input without consuming anything
+    Position,
+    /// Names the parser in error messages
+    Label {
+        /// The parser that's being labeled
+        parser: Parser,
+        /// The name that's used in error messages
+        label: KString,
+    },
+}
+
+/// An error produced when parsing fails
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// The byte offset in the input where parsing failed
+    pub offset: usize,
+    /// The line where parsing failed, starting at 1
+    pub line: usize,
+    /// The column where parsing failed, starting at 1
+    pub column: usize,
+    /// Descriptions of what was expected at the failure position
+    pub expected: Vec<String>,
+    /// The character found at the failure position, or `None` at the end of the input
+    pub found: Option<String>,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.expected.as_slice() {
+            [] => write!(f, "unexpected input")?,
+            [expected] => write!(f, "expected {expected}")?,
+            [first @ .., last] => write!(f, "expected {} or {last}", first.join(", "))?,
+        }
+        write!(f, " at line {}, column {}", self.line, self.column)?;
+        match &self.found {
+            Some(found) => write!(f, ", found '{found}'"),
+            None => write!(f, ", found the end of the input"),
+        }
+    }
+}
+
+// The state that's shared by parsers while parsing an input
+struct ParseState<'a> {
+    input: &'a KString,
+    vm: &'a mut KotoVm,
+    // The furthest offset at which a parser failed
+    error_offset: usize,
+    // The things that were expected at the error offset
+    expected: Vec<String>,
+}
+
+impl ParseState<'_> {
+    fn fail(&mut self, offset: usize, expected: impl Into<String>) {
+        if offset > self.error_offset {
+            self.error_offset = offset;
+            self.expected.clear();
+        }
+        if offset == self.error_offset {
+            let expected = expected.into();
+            if !self.expected.contains(&expected) {
+                self.expected.push(expected);
+            }
+        }
+    }
+}
+
+// The result of running a parser, a value and the offset following the match
+type ParseOutput = Option<(KValue, usize)>;
+
+#[koto_impl(runtime = koto_runtime)]
+impl Parser {
+    pub(crate) fn new(kind: ParserKind) -> Self {
+        Self(kind.into())
+    }
+
+    /// Returns the kind of parser
+    pub fn kind(&self) -> &ParserKind {
+        &self.0
+    }
+
+    /// Parses the input, which needs to be matched completely
+    ///
+    /// The outer result contains runtime errors, e.g. errors thrown in mapping functions,
+    /// while the inner result contains the parsed value or a description of the parse error.
+    pub fn parse_input(
+        &self,
+        input: &KString,
+        vm: &mut KotoVm,
+    ) -> Result<std::result::Result<KValue, ParseError>> {
+        let mut state = ParseState {
+            input,
+            vm,
+            error_offset: 0,
+            expected: Vec::new(),
+        };
+
+        match self.run(0, &mut state)? {
+            Some((value, end)) if end == input.len() => return Ok(Ok(value)),
+            Some((_, end)) => state.fail(end, "the end of the input"),
+            None => {}
+        }
+
+        let offset = state.error_offset;
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Ok(Err(ParseError {
+            offset,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].graphemes(true).count() + 1,
+            expected: state.expected,
+            found: input[offset..].graphemes(true).next().map(String::from),
+        }))
+    }
+
+    fn run(&self, offset: usize, state: &mut ParseState) -> Result<ParseOutput> {
+        use ParserKind::*;
+
+        let result = match self.kind() {
+            Tag(tag) => {
+                if state.input[offset..].starts_with(tag.as_str()) {
+                    Some((tag.clone().into(), offset + tag.len()))
+                } else {
+                    state.fail(offset, format!("'{}'", tag.escape_debug()));
+                    None
+                }
+            }
+            TakeWhile { predicate, min } => {
+                let input = state.input.clone();
+                let mut end = offset;
+                let mut count = 0;
+                for c in input[offset..].graphemes(true) {
+                    match state.vm.call_function(predicate.clone(), c)? {
+                        KValue::Bool(true) => {
+                            end += c.len();
+                            count += 1;
+                        }
+                        KValue::Bool(false) => break,
+                        unexpected => {
+                            return type_error("a Bool from the predicate function", &unexpected)
+                        }
+                    }
+                }
+                if count >= *min {
+                    let matched = match input.with_bounds(offset..end) {
+                        Some(matched) => matched.into(),
+                        None => input[offset..end].into(),
+                    };
+                    Some((matched, end))
+                } else {
+                    state.fail(end, "a matching character");
+                    None
+                }
+            }
+            Sequence(parsers) => {
+                let mut values = ValueVec::with_capacity(parsers.len());
+                let mut position = offset;
+                for parser in parsers {
+                    match parser.run(position, state)? {
+                        Some((value, next)) => {
+                            values.push(value);
+                            position = next;
+                        }
+                        None => return Ok(None),
+                    }
+                }
+                Some((KValue::Tuple(values.into_vec().into()), position))
+            }
+            Choice(parsers) => {
+                let mut result = None;
+                for parser in parsers {
+                    if let Some(output) = parser.run(offset, state)? {
+                        result = Some(output);
+                        break;
+                    }
+                }
+                result
+            }
+            Many { parser, min } => {
+                let mut values = ValueVec::new();
+                let mut position = offset;
+                // Matches that don't consume any input end the loop to avoid repeating forever
+                while let Some((value, next)) = parser.run(position, state)? {
+                    if next == position {
+                        break;
+                    }
+                    values.push(value);
+                    position = next;
+                }
+                if values.len() >= *min {
+                    Some((KList::with_data(values).into(), position))
+                } else {
+                    None
+                }
+            }
+            Map { parser, f } => match parser.run(offset, state)? {
+                Some((value, next)) => Some((state.vm.call_function(f.clone(), value)?, next)),
+                None => None,
+            },
+            Position => {
+                let before = &state.input[..offset];
+                let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+                let position = KMap::with_capacity(3);
+                position.insert("offset", offset);
+                position.insert("line", before.matches('\n').count() + 1);
+                position.insert("column", before[line_start..].graphemes(true).count() + 1);
+                Some((position.into(), offset))
+            }
+            Label { parser, label } => {
+                let error_offset = state.error_offset;
+                let expected = state.expected.clone();
+                let result = parser.run(offset, state)?;
+                // The label replaces the inner parser's expectations when it fails at its start
+                if result.is_none() && state.error_offset == offset {
+                    state.error_offset = error_offset;
+                    state.expected = expected;
+                    state.fail(offset, label.as_str());
+                }
+                result
+            }
+        };
+
+        Ok(result)
+    }
+
+    #[koto_method]
+    fn label(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Str(label)] => Ok(Self::new(ParserKind::Label {
+                parser: self.clone(),
+                label: label.clone(),
+            })
+            .into()),
+            unexpected => type_error_with_slice("a String", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn many(&self, args: &[KValue]) -> Result<KValue> {
+        let min = min_count_arg(args)?;
+        Ok(Self::new(ParserKind::Many {
+            parser: self.clone(),
+            min,
+        })
+        .into())
+    }
+
+    #[koto_method]
+    fn map(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [f] if f.is_callable() => Ok(Self::new(ParserKind::Map {
+                parser: self.clone(),
+                f: f.clone(),
+            })
+            .into()),
+            unexpected => type_error_with_slice("a function", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn parse(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [KValue::Str(input)] => {
+                let input = input.clone();
+                let parser = ctx.instance()?.clone();
+                match parser.parse_input(&input, &mut ctx.vm.spawn_shared_vm())? {
+                    Ok(value) => Ok(value),
+                    Err(error) => runtime_error!("{error}"),
+                }
+            }
+            unexpected => type_error_with_slice("a String", unexpected),
+        }
+    }
+}
+
+impl KotoObject for Parser {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        let kind = match self.kind() {
+            ParserKind::Tag(tag) => {
+                ctx.append(format!("{}('{tag}')", Self::type_static()));
+                return Ok(());
+            }
+            ParserKind::Label { label, .. } => {
+                ctx.append(format!("{}({label})", Self::type_static()));
+                return Ok(());
+            }
+            ParserKind::TakeWhile { .. } => "take_while",
+            ParserKind::Sequence(_) => "sequence",
+            ParserKind::Choice(_) => "choice",
+            ParserKind::Many { .. } => "many",
+            ParserKind::Map { .. } => "map",
+            ParserKind::Position => "position",
+        };
+        ctx.append(format!("{}({kind})", Self::type_static()));
+        Ok(())
+    }
+}
+
+impl From<Parser> for KValue {
+    fn from(parser: Parser) -> Self {
+        KObject::from(parser).into()
+    }
+}
+
+pub(crate) fn min_count_arg(args: &[KValue]) -> Result<usize> {
+    match args {
+        [] => Ok(0),
+        [KValue::Number(n)] if *n >= 0 => Ok(n.into()),
+        unexpected => type_error_with_slice("an optional non-negative minimum count", unexpected),
+    }
+}
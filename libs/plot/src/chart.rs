@@ -0,0 +1,366 @@
+use crate::{svg, text};
+use koto_runtime::{derive::*, prelude::*, Result};
+use std::path::Path;
+
+/// The kinds of chart that can be plotted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Bar,
+    Line,
+    Scatter,
+}
+
+/// A named set of points in a chart
+#[derive(Clone, Debug)]
+pub struct Series {
+    pub name: Option<String>,
+    pub points: Vec<(f64, f64)>,
+}
+
+/// The range of values covered by a chart's axes
+#[derive(Clone, Copy, Debug)]
+pub struct Bounds {
+    pub x_min: f64,
+    pub x_max: f64,
+    pub y_min: f64,
+    pub y_max: f64,
+}
+
+/// A chart containing one or more series of data
+///
+/// Charts can be displayed in the terminal with unicode characters, or rendered as SVG.
+#[derive(Clone, Debug, KotoType, KotoCopy)]
+pub struct Chart {
+    pub kind: Kind,
+    pub series: Vec<Series>,
+    // Category labels for bar charts, taken from the keys of a Map
+    pub labels: Option<Vec<String>>,
+    pub title: Option<String>,
+    pub x_label: Option<String>,
+    pub y_label: Option<String>,
+    // The size of SVG output in pixels
+    pub width: f64,
+    pub height: f64,
+}
+
+// The size of terminal output in characters, not including axes and labels
+const DEFAULT_TEXT_WIDTH: usize = 60;
+const DEFAULT_TEXT_HEIGHT: usize = 12;
+
+#[koto_impl(runtime = koto_runtime)]
+impl Chart {
+    pub fn new(kind: Kind) -> Self {
+        Self {
+            kind,
+            series: Vec::new(),
+            labels: None,
+            title: None,
+            x_label: None,
+            y_label: None,
+            width: 640.0,
+            height: 400.0,
+        }
+    }
+
+    /// Adds a series of data to the chart
+    ///
+    /// The data can be an iterable of numbers, which are plotted against their index, or an
+    /// iterable of `(x, y)` pairs. Maps of labels to numbers can be used for bar charts.
+    pub fn add_series(
+        &mut self,
+        vm: &mut KotoVm,
+        data: KValue,
+        name: Option<String>,
+    ) -> Result<()> {
+        let mut points = Vec::new();
+
+        if let KValue::Map(map) = &data {
+            if self.kind != Kind::Bar {
+                return runtime_error!("Maps can only be used as data for bar charts");
+            }
+            let mut labels = Vec::with_capacity(map.len());
+            for (i, (key, value)) in map.data().iter().enumerate() {
+                labels.push(key.to_string());
+                points.push((i as f64, number_from_value(value)?));
+            }
+            self.labels.get_or_insert(labels);
+        } else if data.is_iterable() {
+            for (i, output) in vm.make_iterator(data)?.enumerate() {
+                let point = match output {
+                    KIteratorOutput::Value(KValue::Number(y)) => (i as f64, f64::from(y)),
+                    KIteratorOutput::Value(KValue::Tuple(xy)) => point_from_slice(&xy)?,
+                    KIteratorOutput::Value(KValue::List(xy)) => point_from_slice(&xy.data())?,
+                    KIteratorOutput::ValuePair(x, y) => {
+                        (number_from_value(&x)?, number_from_value(&y)?)
+                    }
+                    KIteratorOutput::Value(unexpected) => {
+                        return type_error("a Number or an (x, y) pair", &unexpected)
+                    }
+                    KIteratorOutput::Error(error) => return Err(error),
+                };
+                points.push(point);
+            }
+        } else {
+            return type_error("an iterable or a Map as data", &data);
+        }
+
+        points.retain(|(x, y)| x.is_finite() && y.is_finite());
+        self.series.push(Series { name, points });
+        Ok(())
+    }
+
+    /// Returns the range of values covered by the chart's data
+    ///
+    /// Bar charts always include zero in their y range.
+    pub fn bounds(&self) -> Bounds {
+        let points = self.series.iter().flat_map(|series| series.points.iter());
+
+        let mut bounds = Bounds {
+            x_min: f64::INFINITY,
+            x_max: f64::NEG_INFINITY,
+            y_min: f64::INFINITY,
+            y_max: f64::NEG_INFINITY,
+        };
+        for (x, y) in points {
+            bounds.x_min = bounds.x_min.min(*x);
+            bounds.x_max = bounds.x_max.max(*x);
+            bounds.y_min = bounds.y_min.min(*y);
+            bounds.y_max = bounds.y_max.max(*y);
+        }
+
+        if bounds.x_min > bounds.x_max {
+            bounds.x_min = 0.0;
+            bounds.x_max = 1.0;
+        }
+        if bounds.y_min > bounds.y_max {
+            bounds.y_min = 0.0;
+            bounds.y_max = 1.0;
+        }
+        if self.kind == Kind::Bar {
+            bounds.y_min = bounds.y_min.min(0.0);
+            bounds.y_max = bounds.y_max.max(0.0);
+        }
+        if bounds.x_min == bounds.x_max {
+            bounds.x_min -= 1.0;
+            bounds.x_max += 1.0;
+        }
+        if bounds.y_min == bounds.y_max {
+            bounds.y_min -= 1.0;
+            bounds.y_max += 1.0;
+        }
+
+        bounds
+    }
+
+    /// The number of categories in a bar chart
+    pub fn bar_count(&self) -> usize {
+        self.series
+            .iter()
+            .map(|series| series.points.len())
+            .max()
+            .unwrap_or_default()
+    }
+
+    pub fn render_text(&self, width: usize, height: usize) -> String {
+        text::render(self, width.max(2), height.max(2))
+    }
+
+    pub fn render_svg(&self) -> String {
+        svg::render(self)
+    }
+
+    // Sets one of the chart's text properties, returning the chart
+    fn set_text(
+        ctx: MethodContext<Self>,
+        set: impl Fn(&mut Self, Option<String>),
+    ) -> Result<KValue> {
+        let text = match ctx.args {
+            [KValue::Str(text)] => Some(text.to_string()),
+            [KValue::Null] => None,
+            unexpected => return type_error_with_slice("a String or null", unexpected),
+        };
+        set(&mut *ctx.instance_mut()?, text);
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn add(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (data, name) = match ctx.args {
+            [data] => (data.clone(), None),
+            [data, KValue::Str(name)] => (data.clone(), Some(name.to_string())),
+            unexpected => {
+                return type_error_with_slice(
+                    "data to plot, and an optional series name",
+                    unexpected,
+                )
+            }
+        };
+
+        // The series is prepared before borrowing the chart, in case the data needs to call back
+        // into the chart
+        let mut series = Self::new(ctx.instance()?.kind);
+        series.add_series(&mut ctx.vm.spawn_shared_vm(), data, name)?;
+
+        {
+            let mut this = ctx.instance_mut()?;
+            if this.labels.is_none() {
+                this.labels = series.labels;
+            }
+            this.series.extend(series.series);
+        }
+
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn render(&self, args: &[KValue]) -> Result<KValue> {
+        let (width, height) = match args {
+            [] => (DEFAULT_TEXT_WIDTH, DEFAULT_TEXT_HEIGHT),
+            [KValue::Number(width), KValue::Number(height)] if *width > 0 && *height > 0 => {
+                (usize::from(width), usize::from(height))
+            }
+            unexpected => return type_error_with_slice("an optional width and height", unexpected),
+        };
+        Ok(self.render_text(width, height).into())
+    }
+
+    #[koto_method]
+    fn save(&self, args: &[KValue]) -> Result<KValue> {
+        let path = match args {
+            [KValue::Str(path)] => Path::new(path.as_str()),
+            unexpected => return type_error_with_slice("a path String", unexpected),
+        };
+
+        let contents = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("svg") => self.render_svg(),
+            Some("txt") => self.render_text(DEFAULT_TEXT_WIDTH, DEFAULT_TEXT_HEIGHT),
+            _ => {
+                return runtime_error!(
+                    "Expected a path with an .svg or .txt extension, found '{}'",
+                    path.display()
+                )
+            }
+        };
+
+        match std::fs::write(path, contents) {
+            Ok(()) => Ok(KValue::Null),
+            Err(e) => runtime_error!("Failed to write '{}': {e}", path.display()),
+        }
+    }
+
+    #[koto_method]
+    fn size(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [KValue::Number(width), KValue::Number(height)] if *width > 0 && *height > 0 => {
+                let mut this = ctx.instance_mut()?;
+                this.width = width.into();
+                this.height = height.into();
+            }
+            unexpected => return type_error_with_slice("a positive width and height", unexpected),
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn title(ctx: MethodContext<Self>) -> Result<KValue> {
+        Self::set_text(ctx, |chart, text| chart.title = text)
+    }
+
+    #[koto_method]
+    fn to_svg(&self) -> KValue {
+        self.render_svg().into()
+    }
+
+    #[koto_method]
+    fn x_label(ctx: MethodContext<Self>) -> Result<KValue> {
+        Self::set_text(ctx, |chart, text| chart.x_label = text)
+    }
+
+    #[koto_method]
+    fn y_label(ctx: MethodContext<Self>) -> Result<KValue> {
+        Self::set_text(ctx, |chart, text| chart.y_label = text)
+    }
+}
+
+impl KotoObject for Chart {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        if ctx.is_contained() {
+            ctx.append(format!("Chart({:?})", self.kind).to_lowercase());
+        } else {
+            ctx.append(self.render_text(DEFAULT_TEXT_WIDTH, DEFAULT_TEXT_HEIGHT));
+        }
+        Ok(())
+    }
+}
+
+impl From<Chart> for KValue {
+    fn from(chart: Chart) -> Self {
+        KObject::from(chart).into()
+    }
+}
+
+fn number_from_value(value: &KValue) -> Result<f64> {
+    match value {
+        KValue::Number(n) => Ok(n.into()),
+        unexpected => type_error("a Number", unexpected),
+    }
+}
+
+fn point_from_slice(xy: &[KValue]) -> Result<(f64, f64)> {
+    match xy {
+        [x, y] => Ok((number_from_value(x)?, number_from_value(y)?)),
+        unexpected => type_error_with_slice("an (x, y) pair", unexpected),
+    }
+}
+
+/// Formats a number for display in a text chart's axis labels
+///
+/// Whole numbers are shown without a decimal point, and other numbers are rounded to two decimal
+/// places.
+pub fn format_value(n: f64) -> String {
+    if n.fract() == 0.0 {
+        format_number(n, 1.0)
+    } else {
+        let result = format!("{n:.2}");
+        result
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    }
+}
+
+/// Formats a number for display in an axis label
+///
+/// The number of decimal places is chosen based on the distance between labels.
+pub fn format_number(n: f64, step: f64) -> String {
+    let decimals = if step > 0.0 && step < 1.0 {
+        (-step.log10().floor()) as usize
+    } else {
+        0
+    };
+    let result = format!("{n:.decimals$}");
+    // Avoid displaying '-0'
+    if result.trim_start_matches(['-', '0', '.']).is_empty() {
+        result.trim_start_matches('-').to_string()
+    } else {
+        result
+    }
+}
+
+/// Returns evenly spaced 'nice' values that cover the given range
+///
+/// Steps between values are 1, 2, or 5 multiplied by a power of 10.
+pub fn nice_ticks(min: f64, max: f64, target_count: usize) -> (Vec<f64>, f64) {
+    let rough_step = (max - min) / target_count.max(1) as f64;
+    let magnitude = 10f64.powf(rough_step.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|m| m * magnitude)
+        .find(|step| *step >= rough_step)
+        .unwrap_or(rough_step);
+
+    let first = (min / step).floor() as i64;
+    let last = (max / step).ceil() as i64;
+    let ticks = (first..=last).map(|i| i as f64 * step).collect();
+    (ticks, step)
+}
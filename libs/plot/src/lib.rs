@@ -0,0 +1,39 @@
+//! A Koto language module for plotting charts
+
+mod chart;
+mod svg;
+mod text;
+
+pub use crate::chart::Chart;
+
+use crate::chart::Kind;
+use koto_runtime::prelude::*;
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("plot");
+
+    for (name, kind) in [
+        ("bar", Kind::Bar),
+        ("line", Kind::Line),
+        ("scatter", Kind::Scatter),
+    ] {
+        result.add_fn(name, move |ctx| {
+            let (data, name) = match ctx.args() {
+                [data] => (data.clone(), None),
+                [data, KValue::Str(name)] => (data.clone(), Some(name.to_string())),
+                unexpected => {
+                    return type_error_with_slice(
+                        "data to plot, and an optional series name",
+                        unexpected,
+                    )
+                }
+            };
+
+            let mut chart = Chart::new(kind);
+            chart.add_series(ctx.vm, data, name)?;
+            Ok(chart.into())
+        });
+    }
+
+    result
+}
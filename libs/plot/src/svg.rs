@@ -0,0 +1,233 @@
+//! Rendering of charts as SVG documents
+
+use crate::chart::{format_number, nice_ticks, Chart, Kind};
+use std::fmt::Write;
+
+// The colors used for each series, repeating if there are more series than colors
+const PALETTE: [&str; 6] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948",
+];
+
+const FONT_SIZE: f64 = 12.0;
+
+/// Renders the chart as an SVG document
+pub fn render(chart: &Chart) -> String {
+    let width = chart.width;
+    let height = chart.height;
+    let bounds = chart.bounds();
+
+    let (y_ticks, y_step) = nice_ticks(bounds.y_min, bounds.y_max, 5);
+    let y_min = y_ticks.first().copied().unwrap_or(bounds.y_min);
+    let y_max = y_ticks.last().copied().unwrap_or(bounds.y_max);
+
+    // Space is left around the plot area for the title and axis labels
+    let left = 60.0 + if chart.y_label.is_some() { 20.0 } else { 0.0 };
+    let right = 20.0;
+    let top = if chart.title.is_some() { 40.0 } else { 20.0 };
+    let bottom = 30.0 + if chart.x_label.is_some() { 20.0 } else { 0.0 };
+    let plot_width = (width - left - right).max(1.0);
+    let plot_height = (height - top - bottom).max(1.0);
+
+    let to_y = |y: f64| top + (y_max - y) / (y_max - y_min) * plot_height;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="{FONT_SIZE}">"#,
+        w = num(width),
+        h = num(height),
+    );
+    let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+
+    if let Some(title) = &chart.title {
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" text-anchor="middle" font-size="{}" font-weight="bold">{}</text>"#,
+            num(left + plot_width / 2.0),
+            num(top / 2.0 + FONT_SIZE / 2.0),
+            num(FONT_SIZE * 1.25),
+            escape(title)
+        );
+    }
+
+    // Horizontal gridlines with y axis labels
+    for tick in y_ticks.iter() {
+        let y = num(to_y(*tick));
+        let _ = writeln!(
+            svg,
+            r##"<line x1="{}" y1="{y}" x2="{}" y2="{y}" stroke="#ddd"/>"##,
+            num(left),
+            num(left + plot_width),
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{y}" text-anchor="end" dominant-baseline="middle">{}</text>"#,
+            num(left - 6.0),
+            format_number(*tick, y_step),
+        );
+    }
+
+    let x_label_y = num(top + plot_height + FONT_SIZE + 6.0);
+
+    if chart.kind == Kind::Bar {
+        let count = chart.bar_count().max(1);
+        let slot_width = plot_width / count as f64;
+        let series_count = chart.series.len().max(1) as f64;
+        let bar_width = slot_width * 0.8 / series_count;
+
+        for i in 0..count {
+            let label = match &chart.labels {
+                Some(labels) => labels.get(i).cloned().unwrap_or_default(),
+                None => i.to_string(),
+            };
+            let _ = writeln!(
+                svg,
+                r#"<text x="{}" y="{x_label_y}" text-anchor="middle">{}</text>"#,
+                num(left + slot_width * (i as f64 + 0.5)),
+                escape(&label),
+            );
+        }
+
+        let zero = to_y(0.0);
+        for (series_index, series) in chart.series.iter().enumerate() {
+            let color = PALETTE[series_index % PALETTE.len()];
+            for (i, (_, y)) in series.points.iter().enumerate() {
+                let x = left + slot_width * (i as f64 + 0.1) + bar_width * series_index as f64;
+                let bar_top = to_y(*y).min(zero);
+                let bar_height = (to_y(*y) - zero).abs();
+                let _ = writeln!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{color}"/>"#,
+                    num(x),
+                    num(bar_top),
+                    num(bar_width),
+                    num(bar_height),
+                );
+            }
+        }
+    } else {
+        let (x_ticks, x_step) = nice_ticks(bounds.x_min, bounds.x_max, 6);
+        let x_min = x_ticks.first().copied().unwrap_or(bounds.x_min);
+        let x_max = x_ticks.last().copied().unwrap_or(bounds.x_max);
+        let to_x = |x: f64| left + (x - x_min) / (x_max - x_min) * plot_width;
+
+        for tick in x_ticks.iter() {
+            let _ = writeln!(
+                svg,
+                r#"<text x="{}" y="{x_label_y}" text-anchor="middle">{}</text>"#,
+                num(to_x(*tick)),
+                format_number(*tick, x_step),
+            );
+        }
+
+        for (series_index, series) in chart.series.iter().enumerate() {
+            let color = PALETTE[series_index % PALETTE.len()];
+            if chart.kind == Kind::Line {
+                let points = series
+                    .points
+                    .iter()
+                    .map(|(x, y)| format!("{},{}", num(to_x(*x)), num(to_y(*y))))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let _ = writeln!(
+                    svg,
+                    r#"<polyline points="{points}" fill="none" stroke="{color}" stroke-width="2"/>"#,
+                );
+            } else {
+                for (x, y) in series.points.iter() {
+                    let _ = writeln!(
+                        svg,
+                        r#"<circle cx="{}" cy="{}" r="3" fill="{color}"/>"#,
+                        num(to_x(*x)),
+                        num(to_y(*y)),
+                    );
+                }
+            }
+        }
+    }
+
+    // Axes
+    let _ = writeln!(
+        svg,
+        r##"<path d="M{l},{t} V{b} H{r}" fill="none" stroke="#333"/>"##,
+        l = num(left),
+        t = num(top),
+        b = num(top + plot_height),
+        r = num(left + plot_width),
+    );
+
+    if let Some(x_label) = &chart.x_label {
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" text-anchor="middle">{}</text>"#,
+            num(left + plot_width / 2.0),
+            num(height - 8.0),
+            escape(x_label),
+        );
+    }
+    if let Some(y_label) = &chart.y_label {
+        let x = num(FONT_SIZE + 4.0);
+        let y = num(top + plot_height / 2.0);
+        let _ = writeln!(
+            svg,
+            r#"<text x="{x}" y="{y}" text-anchor="middle" transform="rotate(-90 {x} {y})">{}</text>"#,
+            escape(y_label),
+        );
+    }
+
+    // A legend is included when any of the series have names
+    if chart.series.iter().any(|series| series.name.is_some()) {
+        let x = left + plot_width - 100.0;
+        for (i, series) in chart.series.iter().enumerate() {
+            let y = top + 6.0 + i as f64 * (FONT_SIZE + 6.0);
+            let color = PALETTE[i % PALETTE.len()];
+            let name = match &series.name {
+                Some(name) => name.clone(),
+                None => format!("series {}", i + 1),
+            };
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="10" height="10" fill="{color}"/>"#,
+                num(x),
+                num(y),
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{}" y="{}" dominant-baseline="middle">{}</text>"#,
+                num(x + 16.0),
+                num(y + 5.0),
+                escape(&name),
+            );
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// Formats a coordinate, with up to two decimal places
+fn num(n: f64) -> String {
+    let result = format!("{n:.2}");
+    let result = result.trim_end_matches('0').trim_end_matches('.');
+    if result == "-0" {
+        "0".into()
+    } else {
+        result.into()
+    }
+}
+
+// Escapes text for inclusion in an SVG document
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
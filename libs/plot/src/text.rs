@@ -0,0 +1,265 @@
+//! Rendering of charts with unicode characters, for display in the terminal
+
+use crate::chart::{format_value, Bounds, Chart, Kind};
+use unicode_width::UnicodeWidthStr;
+
+// The dot bits of a braille character, indexed by [row][column]
+const BRAILLE_DOTS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+// Block characters used for bars, in eighths of a character's height
+const BAR_BLOCKS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Renders the chart as text, with the plot area having the given size in characters
+pub fn render(chart: &Chart, width: usize, height: usize) -> String {
+    let bounds = chart.bounds();
+
+    let rows = match chart.kind {
+        Kind::Bar => bar_rows(chart, &bounds, width, height),
+        Kind::Line | Kind::Scatter => braille_rows(chart, &bounds, width, height),
+    };
+    // Bar charts may need more or less than the requested width
+    let width = rows.first().map_or(width, |row| row.chars().count());
+
+    let y_top = format_value(bounds.y_max);
+    let y_bottom = format_value(bounds.y_min);
+    let label_width = y_top.width().max(y_bottom.width());
+    let indent = " ".repeat(label_width + 1);
+
+    let mut lines = Vec::with_capacity(height + 5);
+
+    if let Some(title) = &chart.title {
+        lines.push(centered(title, label_width + 2, width));
+    }
+    if let Some(y_label) = &chart.y_label {
+        lines.push(y_label.clone());
+    }
+
+    let last_row = rows.len() - 1;
+    for (i, row) in rows.iter().enumerate() {
+        let (label, axis) = match i {
+            0 => (y_top.as_str(), '┤'),
+            _ if i == last_row => (y_bottom.as_str(), '┤'),
+            _ => ("", '│'),
+        };
+        lines.push(format!("{label:>label_width$} {axis}{row}"));
+    }
+
+    lines.push(format!("{indent}└{}", "─".repeat(width)));
+    lines.push(x_labels(chart, &bounds, &indent, width));
+
+    if let Some(x_label) = &chart.x_label {
+        lines.push(centered(x_label, label_width + 2, width));
+    }
+
+    let mut result = String::new();
+    for line in lines {
+        let line = line.trim_end();
+        if !result.is_empty() {
+            result.push('\n');
+        }
+        result.push_str(line);
+    }
+    result
+}
+
+// Plots line and scatter charts on a canvas of braille characters
+//
+// Each character contains a 2x4 grid of dots, giving a higher resolution than whole characters.
+fn braille_rows(chart: &Chart, bounds: &Bounds, width: usize, height: usize) -> Vec<String> {
+    let dots_x = width * 2;
+    let dots_y = height * 4;
+    let mut cells = vec![0u8; width * height];
+
+    let mut set_dot = |x: usize, y: usize| {
+        if x < dots_x && y < dots_y {
+            cells[(y / 4) * width + x / 2] |= BRAILLE_DOTS[y % 4][x % 2];
+        }
+    };
+
+    let to_dot = |(x, y): (f64, f64)| {
+        let dot_x = (x - bounds.x_min) / (bounds.x_max - bounds.x_min) * (dots_x - 1) as f64;
+        let dot_y = (bounds.y_max - y) / (bounds.y_max - bounds.y_min) * (dots_y - 1) as f64;
+        (dot_x.round() as i64, dot_y.round() as i64)
+    };
+
+    for series in chart.series.iter() {
+        let dots = series.points.iter().copied().map(to_dot);
+        if chart.kind == Kind::Line {
+            let mut previous = None;
+            for dot in dots {
+                match previous {
+                    Some(start) => draw_line(start, dot, &mut set_dot),
+                    None => set_dot(dot.0 as usize, dot.1 as usize),
+                }
+                previous = Some(dot);
+            }
+        } else {
+            for (x, y) in dots {
+                set_dot(x as usize, y as usize);
+            }
+        }
+    }
+
+    cells
+        .chunks(width)
+        .map(|row| {
+            row.iter()
+                .map(|cell| match cell {
+                    0 => ' ',
+                    _ => char::from_u32(0x2800 + *cell as u32).unwrap_or(' '),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// Bresenham's line algorithm
+fn draw_line(start: (i64, i64), end: (i64, i64), set_dot: &mut impl FnMut(usize, usize)) {
+    let (mut x, mut y) = start;
+    let dx = (end.0 - x).abs();
+    let dy = -(end.1 - y).abs();
+    let step_x = if x < end.0 { 1 } else { -1 };
+    let step_y = if y < end.1 { 1 } else { -1 };
+    let mut error = dx + dy;
+
+    loop {
+        set_dot(x as usize, y as usize);
+        if (x, y) == end {
+            break;
+        }
+        let e2 = 2 * error;
+        if e2 >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if e2 <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+}
+
+// Plots bar charts with block characters
+//
+// Bars are grouped by category, with a bar for each series and a gap between categories.
+fn bar_rows(chart: &Chart, bounds: &Bounds, width: usize, height: usize) -> Vec<String> {
+    let layout = BarLayout::new(chart, width);
+    let total_eighths = (height * 8) as f64;
+    let to_eighths = |y: f64| {
+        ((y - bounds.y_min) / (bounds.y_max - bounds.y_min) * total_eighths).round() as usize
+    };
+    // The baseline is kept on a character boundary so that bars can be drawn with lower blocks
+    let zero = (to_eighths(0.0) as f64 / 8.0).round() as usize * 8;
+
+    let mut columns = vec![(0, 0); layout.total_width()];
+    for (series_index, series) in chart.series.iter().enumerate() {
+        for (category, (_, y)) in series.points.iter().enumerate() {
+            let top = to_eighths(*y);
+            // Negative bars hang down from the baseline, rounded to whole characters
+            let span = if top >= zero {
+                (zero, top)
+            } else {
+                ((top as f64 / 8.0).round() as usize * 8, zero)
+            };
+            let start = layout.bar_start(category, series_index);
+            for column in &mut columns[start..start + layout.bar_width] {
+                *column = span;
+            }
+        }
+    }
+
+    (0..height)
+        .rev()
+        .map(|row| {
+            let row_bottom = row * 8;
+            columns
+                .iter()
+                .map(|(start, end)| {
+                    if *start > row_bottom || *end <= row_bottom {
+                        ' '
+                    } else {
+                        BAR_BLOCKS[(end - row_bottom).min(8)]
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// The horizontal positions of bars in a text bar chart
+struct BarLayout {
+    slot_width: usize,
+    bar_width: usize,
+    slot_count: usize,
+    series_count: usize,
+}
+
+impl BarLayout {
+    fn new(chart: &Chart, width: usize) -> Self {
+        let slot_count = chart.bar_count().max(1);
+        let series_count = chart.series.len().max(1);
+        let slot_width = (width / slot_count).max(series_count);
+        let gap = usize::from(slot_width > series_count);
+        let bar_width = ((slot_width - gap) / series_count).max(1);
+
+        Self {
+            slot_width,
+            bar_width,
+            slot_count,
+            series_count,
+        }
+    }
+
+    fn total_width(&self) -> usize {
+        self.slot_width * self.slot_count
+    }
+
+    fn bar_start(&self, category: usize, series: usize) -> usize {
+        let bars_width = self.bar_width * self.series_count;
+        let padding = (self.slot_width - bars_width) / 2;
+        category * self.slot_width + padding + series * self.bar_width
+    }
+}
+
+// Makes the line of x axis labels
+//
+// Bar charts show a label for each category if there's room, otherwise the first and last
+// labels are shown.
+fn x_labels(chart: &Chart, bounds: &Bounds, indent: &str, width: usize) -> String {
+    let (first, last) = match (chart.kind, &chart.labels) {
+        (Kind::Bar, labels) => {
+            let layout = BarLayout::new(chart, width);
+            let label = |i: usize| match labels {
+                Some(labels) => labels.get(i).cloned().unwrap_or_default(),
+                None => i.to_string(),
+            };
+
+            let labels: Vec<String> = (0..layout.slot_count).map(label).collect();
+            if labels.iter().all(|label| label.width() < layout.slot_width) {
+                let mut result = format!("{indent} ");
+                for label in labels {
+                    result.push_str(&centered(&label, 0, layout.slot_width));
+                    let padding = layout.slot_width.saturating_sub(label.width());
+                    result.push_str(&" ".repeat(padding - padding / 2));
+                }
+                return result;
+            }
+
+            (label(0), label(layout.slot_count - 1))
+        }
+        _ => (format_value(bounds.x_min), format_value(bounds.x_max)),
+    };
+
+    let gap = width.saturating_sub(first.width() + last.width());
+    if gap > 0 {
+        format!("{indent} {first}{}{last}", " ".repeat(gap))
+    } else {
+        format!("{indent} {first}")
+    }
+}
+
+// Centers the text within a column of the given width, after indenting it
+fn centered(text: &str, indent: usize, width: usize) -> String {
+    let padding = width.saturating_sub(text.width()) / 2;
+    format!("{}{text}", " ".repeat(indent + padding))
+}
@@ -0,0 +1,59 @@
+//! A Koto language module for reporting the progress of long-running scripts
+//!
+//! Scripts track progress with [Progress] trackers created by `progress.start`, and changes to the
+//! trackers are forwarded to a [ProgressReporter] provided by the host application with
+//! [make_module_with_reporter]. [ProgressBar] is a reporter that renders progress bars in a
+//! terminal, and is used by the Koto CLI.
+//!
+//! Without a reporter, progress can still be tracked, but nothing is reported.
+
+mod progress;
+mod progress_bar;
+mod reporter;
+
+pub use crate::{
+    progress::Progress,
+    progress_bar::ProgressBar,
+    reporter::{ProgressId, ProgressReporter, ProgressState},
+};
+
+use koto_runtime::{make_ptr, prelude::*, Ptr};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub fn make_module() -> KMap {
+    make_progress_module(None)
+}
+
+/// Makes the module with a reporter that receives the script's progress
+pub fn make_module_with_reporter(reporter: impl ProgressReporter + 'static) -> KMap {
+    make_progress_module(Some(make_ptr!(reporter)))
+}
+
+fn make_progress_module(reporter: Option<Ptr<dyn ProgressReporter>>) -> KMap {
+    let result = KMap::with_type("progress");
+    let next_id: Ptr<AtomicU64> = Ptr::from(AtomicU64::new(1));
+
+    result.add_fn("start", move |ctx| {
+        let (total, message) = match ctx.args() {
+            [] => (None, None),
+            [KValue::Null] => (None, None),
+            [KValue::Number(total)] if *total >= 0 => (Some(total.into()), None),
+            [KValue::Str(message)] => (None, Some(message.to_string())),
+            [KValue::Null, KValue::Str(message)] => (None, Some(message.to_string())),
+            [KValue::Number(total), KValue::Str(message)] if *total >= 0 => {
+                (Some(total.into()), Some(message.to_string()))
+            }
+            unexpected => {
+                return type_error_with_slice(
+                    "an optional total Number, and an optional message String",
+                    unexpected,
+                )
+            }
+        };
+
+        let id = next_id.fetch_add(1, Ordering::Relaxed);
+        Ok(Progress::start(id, total, message, reporter.clone()).into())
+    });
+
+    result
+}
@@ -0,0 +1,164 @@
+use crate::{ProgressId, ProgressReporter, ProgressState};
+use koto_runtime::{derive::*, prelude::*, Ptr, PtrMut, Result};
+
+/// A progress tracker, created with `progress.start`
+///
+/// Changes to the tracker are reported to the module's [ProgressReporter].
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "Progress")]
+pub struct Progress(PtrMut<ProgressTracker>);
+
+struct ProgressTracker {
+    state: ProgressState,
+    reporter: Option<Ptr<dyn ProgressReporter>>,
+    finished: bool,
+}
+
+impl ProgressTracker {
+    fn report_update(&self) {
+        if let Some(reporter) = &self.reporter {
+            reporter.update(&self.state);
+        }
+    }
+
+    fn finish(&mut self) {
+        if !self.finished {
+            self.finished = true;
+            if let Some(reporter) = &self.reporter {
+                reporter.finish(&self.state);
+            }
+        }
+    }
+}
+
+impl Drop for ProgressTracker {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Progress {
+    pub(crate) fn start(
+        id: ProgressId,
+        total: Option<u64>,
+        message: Option<String>,
+        reporter: Option<Ptr<dyn ProgressReporter>>,
+    ) -> Self {
+        let state = ProgressState {
+            id,
+            position: 0,
+            total,
+            message,
+        };
+        if let Some(reporter) = &reporter {
+            reporter.start(&state);
+        }
+        Self(PtrMut::from(ProgressTracker {
+            state,
+            reporter,
+            finished: false,
+        }))
+    }
+
+    /// Returns the tracker's current state
+    pub fn state(&self) -> ProgressState {
+        self.0.borrow().state.clone()
+    }
+
+    fn check_not_finished(&self) -> Result<()> {
+        if self.0.borrow().finished {
+            runtime_error!("the progress has already been finished")
+        } else {
+            Ok(())
+        }
+    }
+
+    #[koto_method]
+    fn advance(ctx: MethodContext<Self>) -> Result<KValue> {
+        let amount = match ctx.args {
+            [] => 1,
+            [KValue::Number(n)] if *n >= 0 => u64::from(n),
+            unexpected => return type_error_with_slice("an optional positive Number", unexpected),
+        };
+
+        let this = ctx.instance()?;
+        this.check_not_finished()?;
+        {
+            let mut tracker = this.0.borrow_mut();
+            tracker.state.position += amount;
+            tracker.report_update();
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn finish(&self) -> KValue {
+        self.0.borrow_mut().finish();
+        KValue::Null
+    }
+
+    #[koto_method]
+    fn is_finished(&self) -> KValue {
+        self.0.borrow().finished.into()
+    }
+
+    #[koto_method]
+    fn message(ctx: MethodContext<Self>) -> Result<KValue> {
+        let message = match ctx.args {
+            [] => {
+                return Ok(ctx
+                    .instance()?
+                    .0
+                    .borrow()
+                    .state
+                    .message
+                    .as_deref()
+                    .map_or(KValue::Null, KValue::from))
+            }
+            [KValue::Str(message)] => Some(message.to_string()),
+            [KValue::Null] => None,
+            unexpected => return type_error_with_slice("an optional String", unexpected),
+        };
+
+        let this = ctx.instance()?;
+        this.check_not_finished()?;
+        {
+            let mut tracker = this.0.borrow_mut();
+            tracker.state.message = message;
+            tracker.report_update();
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn position(&self) -> KValue {
+        self.0.borrow().state.position.into()
+    }
+
+    #[koto_method]
+    fn total(&self) -> KValue {
+        self.0
+            .borrow()
+            .state
+            .total
+            .map_or(KValue::Null, KValue::from)
+    }
+}
+
+impl KotoObject for Progress {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        let state = &self.0.borrow().state;
+        match state.total {
+            Some(total) => ctx.append(format!("Progress({}/{total})", state.position)),
+            None => ctx.append(format!("Progress({})", state.position)),
+        }
+        Ok(())
+    }
+}
+
+impl From<Progress> for KValue {
+    fn from(progress: Progress) -> Self {
+        KObject::from(progress).into()
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{ProgressReporter, ProgressState};
+use std::{
+    io::{self, Write},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+// The minimum time between redraws, avoiding excessive output when progress is made rapidly
+const REDRAW_INTERVAL: Duration = Duration::from_millis(50);
+
+const BAR_WIDTH: usize = 30;
+
+/// A [ProgressReporter] that renders progress bars in a terminal
+///
+/// The progress bar is redrawn in place on a single line, e.g. `Copying [=====>    ] 5/10 50%`,
+/// and a newline is written when the progress is finished.
+pub struct ProgressBar {
+    output: Mutex<ProgressBarOutput>,
+}
+
+struct ProgressBarOutput {
+    writer: Box<dyn Write + Send>,
+    last_draw: Option<Instant>,
+}
+
+impl ProgressBar {
+    /// Creates a progress bar that's rendered to the given output
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            output: Mutex::new(ProgressBarOutput {
+                writer: Box::new(writer),
+                last_draw: None,
+            }),
+        }
+    }
+
+    /// Creates a progress bar that's rendered to stderr
+    pub fn stderr() -> Self {
+        Self::new(io::stderr())
+    }
+
+    fn draw(&self, state: &ProgressState, force: bool) {
+        let Ok(mut output) = self.output.lock() else {
+            return;
+        };
+
+        let now = Instant::now();
+        if !force
+            && output
+                .last_draw
+                .is_some_and(|last_draw| now - last_draw < REDRAW_INTERVAL)
+        {
+            return;
+        }
+        output.last_draw = Some(now);
+
+        // Errors are ignored, failing to draw the progress bar shouldn't interrupt the script
+        let _ = write!(output.writer, "\r\x1b[2K{}", render(state));
+        let _ = output.writer.flush();
+    }
+}
+
+impl ProgressReporter for ProgressBar {
+    fn start(&self, state: &ProgressState) {
+        self.draw(state, true);
+    }
+
+    fn update(&self, state: &ProgressState) {
+        self.draw(state, false);
+    }
+
+    fn finish(&self, state: &ProgressState) {
+        self.draw(state, true);
+        if let Ok(mut output) = self.output.lock() {
+            let _ = writeln!(output.writer);
+            output.last_draw = None;
+        }
+    }
+}
+
+// Renders the progress as a single line of text
+fn render(state: &ProgressState) -> String {
+    let mut result = String::new();
+
+    if let Some(message) = &state.message {
+        result.push_str(message);
+        result.push(' ');
+    }
+
+    match (state.total, state.fraction()) {
+        (Some(total), Some(fraction)) => {
+            let filled = (fraction * BAR_WIDTH as f64).round() as usize;
+            let mut bar = "=".repeat(filled);
+            if filled < BAR_WIDTH {
+                bar.push('>');
+                bar.push_str(&" ".repeat(BAR_WIDTH - filled - 1));
+            }
+            let percent = (fraction * 100.0).floor();
+            result.push_str(&format!("[{bar}] {}/{total} {percent}%", state.position));
+        }
+        _ => result.push_str(&state.position.to_string()),
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(position: u64, total: Option<u64>, message: Option<&str>) -> ProgressState {
+        ProgressState {
+            id: 1,
+            position,
+            total,
+            message: message.map(String::from),
+        }
+    }
+
+    #[test]
+    fn render_with_total() {
+        assert_eq!(
+            render(&state(0, Some(10), None)),
+            "[>                             ] 0/10 0%"
+        );
+        assert_eq!(
+            render(&state(5, Some(10), Some("Copying"))),
+            "Copying [===============>              ] 5/10 50%"
+        );
+        assert_eq!(
+            render(&state(10, Some(10), None)),
+            "[==============================] 10/10 100%"
+        );
+    }
+
+    #[test]
+    fn render_without_total() {
+        assert_eq!(render(&state(42, None, Some("Processed"))), "Processed 42");
+    }
+}
@@ -0,0 +1,50 @@
+use koto_runtime::{KotoSend, KotoSync};
+
+/// An identifier for a progress tracker, unique within a progress module
+pub type ProgressId = u64;
+
+/// The current state of a progress tracker, passed to a [ProgressReporter]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProgressState {
+    /// The tracker's ID
+    pub id: ProgressId,
+    /// The amount of progress that has been made
+    pub position: u64,
+    /// The total amount of progress that's expected, if known
+    pub total: Option<u64>,
+    /// The tracker's current message
+    pub message: Option<String>,
+}
+
+impl ProgressState {
+    /// Returns the progress as a fraction between 0 and 1, if the total is known
+    pub fn fraction(&self) -> Option<f64> {
+        self.total.map(|total| {
+            if total == 0 {
+                1.0
+            } else {
+                (self.position as f64 / total as f64).min(1.0)
+            }
+        })
+    }
+}
+
+/// Receives progress reports from scripts
+///
+/// Host applications implement this trait to display the progress of scripts, e.g. by updating
+/// a progress bar in the application's UI.
+///
+/// See [crate::ProgressBar] for a reporter that renders progress bars in a terminal.
+pub trait ProgressReporter: KotoSend + KotoSync {
+    /// Called when a script starts tracking progress with `progress.start`
+    fn start(&self, state: &ProgressState);
+
+    /// Called when progress has been made, or when the message has changed
+    fn update(&self, state: &ProgressState);
+
+    /// Called when the progress is finished
+    ///
+    /// Progress is finished when `Progress.finish` is called, or when the tracker is no longer
+    /// referenced by the script.
+    fn finish(&self, state: &ProgressState);
+}
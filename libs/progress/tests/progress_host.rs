@@ -0,0 +1,133 @@
+use koto_progress::{make_module_with_reporter, ProgressBar, ProgressReporter, ProgressState};
+use koto_runtime::{prelude::*, Result};
+use koto_test_utils::check_script_output_with_vm;
+use std::{
+    io::{self, Write},
+    sync::{Arc, Mutex},
+};
+
+// Records each report as a line of text
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+impl Recorder {
+    fn push(&self, event: &str, state: &ProgressState) {
+        self.0.lock().unwrap().push(format!(
+            "{event} {} {}/{:?} {:?}",
+            state.id, state.position, state.total, state.message
+        ));
+    }
+
+    fn lines(&self) -> Vec<String> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl ProgressReporter for Recorder {
+    fn start(&self, state: &ProgressState) {
+        self.push("start", state);
+    }
+
+    fn update(&self, state: &ProgressState) {
+        self.push("update", state);
+    }
+
+    fn finish(&self, state: &ProgressState) {
+        self.push("finish", state);
+    }
+}
+
+fn run_script(reporter: impl ProgressReporter + 'static, script: &str) -> Result<()> {
+    let vm = KotoVm::default();
+    vm.prelude()
+        .insert("progress", make_module_with_reporter(reporter));
+    check_script_output_with_vm(vm, script, KValue::Null)
+}
+
+#[test]
+fn progress_is_reported() -> Result<()> {
+    let recorder = Recorder::default();
+    let script = "
+p = progress.start 10, 'Loading'
+p.advance 4
+p.message('Parsing').advance()
+p.finish()
+p.finish()
+
+# Trackers are finished when they're no longer used
+progress.start().advance()
+null
+";
+    run_script(recorder.clone(), script)?;
+
+    assert_eq!(
+        recorder.lines(),
+        [
+            r#"start 1 0/Some(10) Some("Loading")"#,
+            r#"update 1 4/Some(10) Some("Loading")"#,
+            r#"update 1 4/Some(10) Some("Parsing")"#,
+            r#"update 1 5/Some(10) Some("Parsing")"#,
+            r#"finish 1 5/Some(10) Some("Parsing")"#,
+            "start 2 0/None None",
+            "update 2 1/None None",
+            "finish 2 1/None None",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn advancing_a_finished_tracker_throws_an_error() -> Result<()> {
+    let script = "
+p = progress.start 10
+p.finish()
+try
+  p.advance()
+catch error
+  assert error.contains 'finished'
+null
+";
+    run_script(Recorder::default(), script)
+}
+
+// A writer that can be inspected after being passed to the progress bar
+#[derive(Clone, Default)]
+struct SharedOutput(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn progress_bar_output() -> Result<()> {
+    let output = SharedOutput::default();
+    let script = "
+p = progress.start 2, 'Copying'
+p.advance 2
+p.finish()
+null
+";
+    run_script(ProgressBar::new(output.clone()), script)?;
+
+    let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+    let lines: Vec<&str> = output
+        .split("\r\x1b[2K")
+        .filter(|s| !s.is_empty())
+        .collect();
+    // Updates are throttled, but the start and finish are always drawn
+    assert_eq!(
+        lines.first(),
+        Some(&"Copying [>                             ] 0/2 0%")
+    );
+    assert_eq!(
+        lines.last(),
+        Some(&"Copying [==============================] 2/2 100%\n")
+    );
+    Ok(())
+}
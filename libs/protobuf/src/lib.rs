@@ -0,0 +1,85 @@
+//! A Koto language module for encoding and decoding Protocol Buffers messages
+
+mod schema;
+mod wire;
+
+use koto_runtime::{derive::*, prelude::*, Ptr, Result};
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("protobuf");
+
+    result.add_fn("schema", |ctx| match ctx.args() {
+        [KValue::Str(source)] => match schema::Schema::parse(source) {
+            Ok(schema) => Ok(Schema(schema.into()).into()),
+            Err(e) => runtime_error!("protobuf.schema: {e}"),
+        },
+        unexpected => type_error_with_slice("a schema String", unexpected),
+    });
+
+    result
+}
+
+/// A set of message definitions that can be used to encode and decode messages
+#[derive(Clone, Debug, KotoType, KotoCopy)]
+pub struct Schema(Ptr<schema::Schema>);
+
+#[koto_impl(runtime = koto_runtime)]
+impl Schema {
+    fn find_message(&self, name: &str) -> Result<&schema::Message> {
+        match self.0.message(name) {
+            Some(message) => Ok(message),
+            None => runtime_error!("Unknown message '{name}'"),
+        }
+    }
+
+    #[koto_method]
+    fn decode(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Str(name), bytes] => {
+                let message = self.find_message(name)?;
+                let bytes = match wire::bytes_from_value("bytes", bytes) {
+                    Ok(bytes) => bytes,
+                    Err(e) => return runtime_error!("{e}"),
+                };
+                match wire::decode_message(&self.0, message, &bytes) {
+                    Ok(result) => Ok(result.into()),
+                    Err(e) => runtime_error!("Failed to decode '{name}': {e}"),
+                }
+            }
+            unexpected => {
+                type_error_with_slice("a message name String and a Tuple of bytes", unexpected)
+            }
+        }
+    }
+
+    #[koto_method]
+    fn encode(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Str(name), KValue::Map(map)] => {
+                let message = self.find_message(name)?;
+                let mut output = Vec::new();
+                match wire::encode_message(&self.0, name, message, map, &mut output) {
+                    Ok(()) => Ok(wire::bytes_to_value(&output)),
+                    Err(e) => runtime_error!("Failed to encode '{name}': {e}"),
+                }
+            }
+            unexpected => type_error_with_slice("a message name String and a Map", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn messages(&self) -> KValue {
+        let mut names: Vec<_> = self.0.messages.keys().cloned().collect();
+        names.sort();
+        let names: Vec<KValue> = names.into_iter().map(KValue::from).collect();
+        KValue::Tuple(names.into())
+    }
+}
+
+impl KotoObject for Schema {}
+
+impl From<Schema> for KValue {
+    fn from(schema: Schema) -> Self {
+        KObject::from(schema).into()
+    }
+}
@@ -0,0 +1,537 @@
+//! Protobuf schema definitions, parsed from a subset of the `.proto` language
+
+use std::collections::HashMap;
+
+/// The type of a message field
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldType {
+    Double,
+    Float,
+    Int32,
+    Int64,
+    UInt32,
+    UInt64,
+    SInt32,
+    SInt64,
+    Fixed32,
+    Fixed64,
+    SFixed32,
+    SFixed64,
+    Bool,
+    String,
+    Bytes,
+    /// A message type, referred to by its fully qualified name
+    Message(String),
+    /// An enum type, referred to by its fully qualified name
+    Enum(String),
+    /// A map field, with key and value types
+    Map(Box<FieldType>, Box<FieldType>),
+    /// A reference to a message or enum type that hasn't been resolved yet
+    Named(String),
+}
+
+impl FieldType {
+    fn from_scalar_name(name: &str) -> Option<Self> {
+        use FieldType::*;
+
+        let result = match name {
+            "double" => Double,
+            "float" => Float,
+            "int32" => Int32,
+            "int64" => Int64,
+            "uint32" => UInt32,
+            "uint64" => UInt64,
+            "sint32" => SInt32,
+            "sint64" => SInt64,
+            "fixed32" => Fixed32,
+            "fixed64" => Fixed64,
+            "sfixed32" => SFixed32,
+            "sfixed64" => SFixed64,
+            "bool" => Bool,
+            "string" => String,
+            "bytes" => Bytes,
+            _ => return None,
+        };
+
+        Some(result)
+    }
+
+    /// Returns true if repeated fields of this type can use packed encoding
+    pub fn is_packable(&self) -> bool {
+        !matches!(
+            self,
+            FieldType::String | FieldType::Bytes | FieldType::Message(_) | FieldType::Map(..)
+        )
+    }
+}
+
+/// A field in a message definition
+#[derive(Clone, Debug)]
+pub struct Field {
+    pub name: String,
+    pub number: u32,
+    pub field_type: FieldType,
+    pub repeated: bool,
+}
+
+/// A message definition
+#[derive(Clone, Debug, Default)]
+pub struct Message {
+    pub fields: Vec<Field>,
+}
+
+impl Message {
+    pub fn field_by_number(&self, number: u32) -> Option<&Field> {
+        self.fields.iter().find(|field| field.number == number)
+    }
+
+    pub fn field_by_name(&self, name: &str) -> Option<&Field> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+}
+
+/// An enum definition
+#[derive(Clone, Debug, Default)]
+pub struct Enum {
+    pub values: Vec<(String, i32)>,
+}
+
+impl Enum {
+    pub fn name_for_value(&self, value: i32) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(_, v)| *v == value)
+            .map(|(name, _)| name.as_str())
+    }
+
+    pub fn value_for_name(&self, name: &str) -> Option<i32> {
+        self.values
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| *value)
+    }
+}
+
+/// The message and enum definitions contained in a schema
+#[derive(Clone, Debug, Default)]
+pub struct Schema {
+    pub messages: HashMap<String, Message>,
+    pub enums: HashMap<String, Enum>,
+    package: Option<String>,
+}
+
+impl Schema {
+    /// Parses a schema from `.proto` source
+    ///
+    /// Messages, enums, nested definitions, `repeated`/`optional` fields, `oneof` groups,
+    /// and `map<K, V>` fields are supported. Services, extensions, and options are ignored.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let tokens = tokenize(source)?;
+        let mut parser = SchemaParser {
+            tokens,
+            position: 0,
+            schema: Schema::default(),
+        };
+        parser.parse_file()?;
+        parser.schema.resolve_types()?;
+        Ok(parser.schema)
+    }
+
+    /// Finds a message by name, with or without the schema's package prefix
+    pub fn message(&self, name: &str) -> Option<&Message> {
+        let name = self.strip_package(name);
+        self.messages.get(name)
+    }
+
+    pub fn enum_def(&self, name: &str) -> Option<&Enum> {
+        self.enums.get(name)
+    }
+
+    fn strip_package<'a>(&self, name: &'a str) -> &'a str {
+        let name = name.strip_prefix('.').unwrap_or(name);
+        match &self.package {
+            Some(package) => name
+                .strip_prefix(package.as_str())
+                .and_then(|name| name.strip_prefix('.'))
+                .unwrap_or(name),
+            None => name,
+        }
+    }
+
+    // Replaces named types with references to fully qualified message or enum names
+    fn resolve_types(&mut self) -> Result<(), String> {
+        let mut resolved = self.messages.clone();
+
+        for (message_name, message) in resolved.iter_mut() {
+            for field in message.fields.iter_mut() {
+                self.resolve_field_type(message_name, &mut field.field_type)?;
+            }
+        }
+
+        self.messages = resolved;
+        Ok(())
+    }
+
+    fn resolve_field_type(&self, scope: &str, field_type: &mut FieldType) -> Result<(), String> {
+        match field_type {
+            FieldType::Named(name) => {
+                *field_type = self
+                    .resolve_name(scope, name)
+                    .ok_or_else(|| format!("Unknown type '{name}' used in message '{scope}'"))?;
+            }
+            FieldType::Map(key, value) => {
+                self.resolve_field_type(scope, key)?;
+                self.resolve_field_type(scope, value)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn resolve_name(&self, scope: &str, name: &str) -> Option<FieldType> {
+        let lookup = |full_name: &str| {
+            if self.messages.contains_key(full_name) {
+                Some(FieldType::Message(full_name.to_string()))
+            } else if self.enums.contains_key(full_name) {
+                Some(FieldType::Enum(full_name.to_string()))
+            } else {
+                None
+            }
+        };
+
+        if name.starts_with('.') {
+            return lookup(self.strip_package(name));
+        }
+
+        let name = self.strip_package(name);
+
+        // Search from the innermost scope outwards, following protobuf's scoping rules
+        let mut scope = Some(scope);
+        while let Some(current) = scope {
+            if let Some(result) = lookup(&format!("{current}.{name}")) {
+                return Some(result);
+            }
+            scope = current.rsplit_once('.').map(|(parent, _)| parent);
+        }
+
+        lookup(name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Id(String),
+    Number(i64),
+    Str(String),
+    Symbol(char),
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut result = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '/' => {
+                chars.next();
+                match chars.next() {
+                    Some('/') => {
+                        for c in chars.by_ref() {
+                            if c == '\n' {
+                                break;
+                            }
+                        }
+                    }
+                    Some('*') => {
+                        let mut previous = ' ';
+                        loop {
+                            match chars.next() {
+                                Some('/') if previous == '*' => break,
+                                Some(c) => previous = c,
+                                None => return Err("Unterminated block comment".into()),
+                            }
+                        }
+                    }
+                    _ => return Err("Unexpected '/'".into()),
+                }
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some(end) if end == c => break,
+                        Some(c) => s.push(c),
+                        None => return Err("Unterminated string".into()),
+                    }
+                }
+                result.push(Token::Str(s));
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let mut s = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                        s.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let number = if let Some(hex) = s.strip_prefix("0x") {
+                    i64::from_str_radix(hex, 16).ok()
+                } else {
+                    s.parse().ok()
+                };
+                match number {
+                    Some(n) => result.push(Token::Number(n)),
+                    // Non-integer numbers can only appear in options, which are ignored
+                    None => result.push(Token::Id(s)),
+                }
+            }
+            c if c.is_alphabetic() || c == '_' || c == '.' => {
+                let mut s = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || c == '_' || c == '.' {
+                        s.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                result.push(Token::Id(s));
+            }
+            c => {
+                chars.next();
+                result.push(Token::Symbol(c));
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+struct SchemaParser {
+    tokens: Vec<Token>,
+    position: usize,
+    schema: Schema,
+}
+
+impl SchemaParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let result = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        result
+    }
+
+    fn expect_symbol(&mut self, expected: char) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Symbol(c)) if c == expected => Ok(()),
+            other => Err(format!("Expected '{expected}', found {}", describe(&other))),
+        }
+    }
+
+    fn expect_id(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Id(id)) => Ok(id),
+            other => Err(format!(
+                "Expected an identifier, found {}",
+                describe(&other)
+            )),
+        }
+    }
+
+    fn expect_number(&mut self) -> Result<i64, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(n),
+            other => Err(format!("Expected a number, found {}", describe(&other))),
+        }
+    }
+
+    // Skips tokens up to and including the next ';', or a balanced '{ ... }' block
+    fn skip_statement(&mut self) -> Result<(), String> {
+        let mut depth = 0;
+        loop {
+            match self.next() {
+                Some(Token::Symbol(';')) if depth == 0 => return Ok(()),
+                Some(Token::Symbol('{')) => depth += 1,
+                Some(Token::Symbol('}')) => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                Some(_) => {}
+                None => return Err("Unexpected end of schema".into()),
+            }
+        }
+    }
+
+    fn parse_file(&mut self) -> Result<(), String> {
+        while let Some(token) = self.next() {
+            match token {
+                Token::Id(id) => match id.as_str() {
+                    "message" => self.parse_message(None)?,
+                    "enum" => self.parse_enum(None)?,
+                    "package" => {
+                        self.schema.package = Some(self.expect_id()?);
+                        self.expect_symbol(';')?;
+                    }
+                    "syntax" | "edition" | "import" | "option" | "service" | "extend" => {
+                        self.skip_statement()?
+                    }
+                    other => return Err(format!("Unexpected '{other}'")),
+                },
+                Token::Symbol(';') => {}
+                other => return Err(format!("Unexpected {}", describe(&Some(other)))),
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_message(&mut self, scope: Option<&str>) -> Result<(), String> {
+        let name = qualified_name(scope, &self.expect_id()?);
+        self.expect_symbol('{')?;
+
+        let mut message = Message::default();
+        self.parse_message_body(&name, &mut message, false)?;
+        self.schema.messages.insert(name, message);
+        Ok(())
+    }
+
+    fn parse_message_body(
+        &mut self,
+        name: &str,
+        message: &mut Message,
+        in_oneof: bool,
+    ) -> Result<(), String> {
+        loop {
+            match self.next() {
+                Some(Token::Symbol('}')) => return Ok(()),
+                Some(Token::Symbol(';')) => {}
+                Some(Token::Id(id)) => match id.as_str() {
+                    "message" if !in_oneof => self.parse_message(Some(name))?,
+                    "enum" if !in_oneof => self.parse_enum(Some(name))?,
+                    "oneof" if !in_oneof => {
+                        self.expect_id()?;
+                        self.expect_symbol('{')?;
+                        self.parse_message_body(name, message, true)?;
+                    }
+                    "option" | "reserved" | "extensions" | "extend" => self.skip_statement()?,
+                    "repeated" => {
+                        let type_name = self.expect_id()?;
+                        let field = self.parse_field(type_name, true)?;
+                        message.fields.push(field);
+                    }
+                    "optional" | "required" => {
+                        let type_name = self.expect_id()?;
+                        let field = self.parse_field(type_name, false)?;
+                        message.fields.push(field);
+                    }
+                    _ => {
+                        let field = self.parse_field(id, false)?;
+                        message.fields.push(field);
+                    }
+                },
+                other => return Err(format!("Unexpected {} in '{name}'", describe(&other))),
+            }
+        }
+    }
+
+    fn parse_field(&mut self, type_name: String, repeated: bool) -> Result<Field, String> {
+        let (field_type, repeated) = if type_name == "map" {
+            self.expect_symbol('<')?;
+            let key = self.parse_type_name()?;
+            self.expect_symbol(',')?;
+            let value = self.parse_type_name()?;
+            self.expect_symbol('>')?;
+            (FieldType::Map(Box::new(key), Box::new(value)), false)
+        } else {
+            let field_type = match FieldType::from_scalar_name(&type_name) {
+                Some(scalar) => scalar,
+                None => FieldType::Named(type_name),
+            };
+            (field_type, repeated)
+        };
+
+        let name = self.expect_id()?;
+        self.expect_symbol('=')?;
+        let number = self.expect_number()?;
+        let number = u32::try_from(number)
+            .ok()
+            .filter(|n| *n > 0)
+            .ok_or_else(|| format!("Invalid field number {number} for '{name}'"))?;
+
+        // Field options (e.g. [packed = true]) are ignored
+        if self.peek() == Some(&Token::Symbol('[')) {
+            while !matches!(self.next(), Some(Token::Symbol(']')) | None) {}
+        }
+        self.expect_symbol(';')?;
+
+        Ok(Field {
+            name,
+            number,
+            field_type,
+            repeated,
+        })
+    }
+
+    fn parse_type_name(&mut self) -> Result<FieldType, String> {
+        let name = self.expect_id()?;
+        Ok(FieldType::from_scalar_name(&name).unwrap_or(FieldType::Named(name)))
+    }
+
+    fn parse_enum(&mut self, scope: Option<&str>) -> Result<(), String> {
+        let name = qualified_name(scope, &self.expect_id()?);
+        self.expect_symbol('{')?;
+
+        let mut enum_def = Enum::default();
+        loop {
+            match self.next() {
+                Some(Token::Symbol('}')) => break,
+                Some(Token::Symbol(';')) => {}
+                Some(Token::Id(id)) if id == "option" || id == "reserved" => {
+                    self.skip_statement()?
+                }
+                Some(Token::Id(value_name)) => {
+                    self.expect_symbol('=')?;
+                    let value = self.expect_number()?;
+                    let value = i32::try_from(value)
+                        .map_err(|_| format!("Enum value {value} is out of range"))?;
+                    if self.peek() == Some(&Token::Symbol('[')) {
+                        while !matches!(self.next(), Some(Token::Symbol(']')) | None) {}
+                    }
+                    self.expect_symbol(';')?;
+                    enum_def.values.push((value_name, value));
+                }
+                other => return Err(format!("Unexpected {} in enum '{name}'", describe(&other))),
+            }
+        }
+
+        self.schema.enums.insert(name, enum_def);
+        Ok(())
+    }
+}
+
+fn qualified_name(scope: Option<&str>, name: &str) -> String {
+    match scope {
+        Some(scope) => format!("{scope}.{name}"),
+        None => name.to_string(),
+    }
+}
+
+fn describe(token: &Option<Token>) -> String {
+    match token {
+        Some(Token::Id(id)) => format!("'{id}'"),
+        Some(Token::Number(n)) => format!("'{n}'"),
+        Some(Token::Str(s)) => format!("\"{s}\""),
+        Some(Token::Symbol(c)) => format!("'{c}'"),
+        None => "end of input".into(),
+    }
+}
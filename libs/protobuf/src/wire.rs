@@ -0,0 +1,497 @@
+//! Encoding and decoding of the protobuf binary wire format
+//!
+//! See https://protobuf.dev/programming-guides/encoding/
+
+use crate::schema::{FieldType, Message, Schema};
+use koto_runtime::prelude::*;
+
+type Result<T> = std::result::Result<T, String>;
+
+const VARINT: u8 = 0;
+const FIXED_64: u8 = 1;
+const LENGTH_DELIMITED: u8 = 2;
+const START_GROUP: u8 = 3;
+const END_GROUP: u8 = 4;
+const FIXED_32: u8 = 5;
+
+fn wire_type(field_type: &FieldType) -> u8 {
+    use FieldType::*;
+
+    match field_type {
+        Int32 | Int64 | UInt32 | UInt64 | SInt32 | SInt64 | Bool | Enum(_) => VARINT,
+        Fixed64 | SFixed64 | Double => FIXED_64,
+        Fixed32 | SFixed32 | Float => FIXED_32,
+        String | Bytes | Message(_) | Map(..) | Named(_) => LENGTH_DELIMITED,
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn is_done(&self) -> bool {
+        self.position >= self.bytes.len()
+    }
+
+    fn read_bytes(&mut self, count: usize) -> Result<&'a [u8]> {
+        let end = self
+            .position
+            .checked_add(count)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| format!("Unexpected end of input at byte {}", self.position))?;
+        let result = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(result)
+    }
+
+    fn read_varint(&mut self) -> Result<u64> {
+        let mut result = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_bytes(1)?[0];
+            result |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+        Err(format!("Invalid varint at byte {}", self.position))
+    }
+
+    fn read_fixed_32(&mut self) -> Result<[u8; 4]> {
+        Ok(self.read_bytes(4)?.try_into().unwrap())
+    }
+
+    fn read_fixed_64(&mut self) -> Result<[u8; 8]> {
+        Ok(self.read_bytes(8)?.try_into().unwrap())
+    }
+
+    fn read_length_delimited(&mut self) -> Result<&'a [u8]> {
+        let length = self.read_varint()?;
+        let length = usize::try_from(length).map_err(|_| "Invalid length".to_string())?;
+        self.read_bytes(length)
+    }
+
+    fn read_tag(&mut self) -> Result<(u32, u8)> {
+        let tag = self.read_varint()?;
+        let number = u32::try_from(tag >> 3).map_err(|_| "Invalid field number".to_string())?;
+        Ok((number, (tag & 0x7) as u8))
+    }
+
+    fn skip(&mut self, wire_type: u8) -> Result<()> {
+        match wire_type {
+            VARINT => self.read_varint().map(|_| ()),
+            FIXED_64 => self.read_fixed_64().map(|_| ()),
+            LENGTH_DELIMITED => self.read_length_delimited().map(|_| ()),
+            FIXED_32 => self.read_fixed_32().map(|_| ()),
+            START_GROUP => loop {
+                let (_, wire_type) = self.read_tag()?;
+                if wire_type == END_GROUP {
+                    return Ok(());
+                }
+                self.skip(wire_type)?;
+            },
+            other => Err(format!("Unsupported wire type {other}")),
+        }
+    }
+}
+
+/// Decodes a message's binary data into a map
+///
+/// Fields that aren't present in the data are left out of the map,
+/// and fields that aren't in the message definition are skipped.
+pub fn decode_message(schema: &Schema, message: &Message, bytes: &[u8]) -> Result<KMap> {
+    let result = KMap::new();
+    let mut reader = Reader::new(bytes);
+
+    while !reader.is_done() {
+        let (number, wire_type) = reader.read_tag()?;
+
+        let Some(field) = message.field_by_number(number) else {
+            reader.skip(wire_type)?;
+            continue;
+        };
+
+        let name = field.name.as_str();
+        match &field.field_type {
+            FieldType::Map(key_type, value_type) => {
+                let entry = reader.read_length_delimited()?;
+                let (key, value) = decode_map_entry(schema, key_type, value_type, entry)?;
+                let key = ValueKey::try_from(key).map_err(|e| e.to_string())?;
+
+                let entries = match result.get(name) {
+                    Some(KValue::Map(entries)) => entries,
+                    _ => {
+                        let entries = KMap::new();
+                        result.insert(name, entries.clone());
+                        entries
+                    }
+                };
+                entries.data_mut().insert(key, value);
+            }
+            field_type if field.repeated => {
+                let list = match result.get(name) {
+                    Some(KValue::List(list)) => list,
+                    _ => {
+                        let list = KList::default();
+                        result.insert(name, list.clone());
+                        list
+                    }
+                };
+
+                if wire_type == LENGTH_DELIMITED && field_type.is_packable() {
+                    let mut packed = Reader::new(reader.read_length_delimited()?);
+                    while !packed.is_done() {
+                        let value = decode_value(schema, field_type, &mut packed)?;
+                        list.data_mut().push(value);
+                    }
+                } else {
+                    check_wire_type(name, field_type, wire_type)?;
+                    let value = decode_value(schema, field_type, &mut reader)?;
+                    list.data_mut().push(value);
+                }
+            }
+            field_type => {
+                check_wire_type(name, field_type, wire_type)?;
+                let value = decode_value(schema, field_type, &mut reader)?;
+                result.insert(name, value);
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn check_wire_type(name: &str, field_type: &FieldType, wire_type: u8) -> Result<()> {
+    if wire_type == self::wire_type(field_type) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Unexpected wire type {wire_type} for field '{name}'"
+        ))
+    }
+}
+
+fn decode_map_entry(
+    schema: &Schema,
+    key_type: &FieldType,
+    value_type: &FieldType,
+    bytes: &[u8],
+) -> Result<(KValue, KValue)> {
+    let mut reader = Reader::new(bytes);
+    let mut key = None;
+    let mut value = None;
+
+    while !reader.is_done() {
+        match reader.read_tag()? {
+            (1, wire_type) => {
+                check_wire_type("key", key_type, wire_type)?;
+                key = Some(decode_value(schema, key_type, &mut reader)?);
+            }
+            (2, wire_type) => {
+                check_wire_type("value", value_type, wire_type)?;
+                value = Some(decode_value(schema, value_type, &mut reader)?);
+            }
+            (_, wire_type) => reader.skip(wire_type)?,
+        }
+    }
+
+    // Missing keys or values in map entries take their type's default value
+    let key = key.map_or_else(|| default_value(schema, key_type), Ok)?;
+    let value = value.map_or_else(|| default_value(schema, value_type), Ok)?;
+    Ok((key, value))
+}
+
+fn default_value(schema: &Schema, field_type: &FieldType) -> Result<KValue> {
+    use FieldType::*;
+
+    let result = match field_type {
+        Double | Float => 0.0.into(),
+        Bool => false.into(),
+        String => "".into(),
+        Bytes => KValue::Tuple(KTuple::default()),
+        Message(_) | Map(..) | Named(_) => KMap::new().into(),
+        Enum(_) => decode_enum(schema, field_type, 0),
+        _ => 0.into(),
+    };
+    Ok(result)
+}
+
+fn decode_value(schema: &Schema, field_type: &FieldType, reader: &mut Reader) -> Result<KValue> {
+    use FieldType::*;
+
+    let result = match field_type {
+        Int32 => (reader.read_varint()? as i32).into(),
+        Int64 => (reader.read_varint()? as i64).into(),
+        UInt32 => (reader.read_varint()? as u32).into(),
+        UInt64 => u64_to_value(reader.read_varint()?),
+        SInt32 => (zigzag_decode(reader.read_varint()?) as i32).into(),
+        SInt64 => zigzag_decode(reader.read_varint()?).into(),
+        Bool => (reader.read_varint()? != 0).into(),
+        Enum(_) => decode_enum(schema, field_type, reader.read_varint()? as i32),
+        Fixed32 => u32::from_le_bytes(reader.read_fixed_32()?).into(),
+        SFixed32 => i32::from_le_bytes(reader.read_fixed_32()?).into(),
+        Float => f64::from(f32::from_le_bytes(reader.read_fixed_32()?)).into(),
+        Fixed64 => u64_to_value(u64::from_le_bytes(reader.read_fixed_64()?)),
+        SFixed64 => i64::from_le_bytes(reader.read_fixed_64()?).into(),
+        Double => f64::from_le_bytes(reader.read_fixed_64()?).into(),
+        String => match std::str::from_utf8(reader.read_length_delimited()?) {
+            Ok(s) => s.into(),
+            Err(_) => return Err("String field failed UTF-8 validation".into()),
+        },
+        Bytes => bytes_to_value(reader.read_length_delimited()?),
+        Message(name) => {
+            let bytes = reader.read_length_delimited()?;
+            decode_message(schema, find_message(schema, name)?, bytes)?.into()
+        }
+        Map(..) | Named(_) => unreachable!(),
+    };
+
+    Ok(result)
+}
+
+// Enum values are decoded as their names, with unknown values decoded as numbers
+fn decode_enum(schema: &Schema, field_type: &FieldType, value: i32) -> KValue {
+    let FieldType::Enum(name) = field_type else {
+        unreachable!()
+    };
+    match schema
+        .enum_def(name)
+        .and_then(|enum_def| enum_def.name_for_value(value))
+    {
+        Some(name) => name.into(),
+        None => value.into(),
+    }
+}
+
+// Numbers that don't fit in an i64 are converted to floats
+fn u64_to_value(n: u64) -> KValue {
+    match i64::try_from(n) {
+        Ok(n) => n.into(),
+        Err(_) => (n as f64).into(),
+    }
+}
+
+/// Converts bytes into a tuple of numbers
+pub fn bytes_to_value(bytes: &[u8]) -> KValue {
+    let bytes: Vec<KValue> = bytes.iter().map(|byte| KValue::from(*byte)).collect();
+    KValue::Tuple(bytes.into())
+}
+
+fn zigzag_decode(n: u64) -> i64 {
+    ((n >> 1) as i64) ^ -((n & 1) as i64)
+}
+
+fn zigzag_encode(n: i64) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+
+fn find_message<'a>(schema: &'a Schema, name: &str) -> Result<&'a Message> {
+    schema
+        .message(name)
+        .ok_or_else(|| format!("Unknown message '{name}'"))
+}
+
+/// Encodes a map as a message's binary data
+///
+/// Fields are written in order of their field numbers, and entries with null values are skipped.
+pub fn encode_message(
+    schema: &Schema,
+    message_name: &str,
+    message: &Message,
+    map: &KMap,
+    output: &mut Vec<u8>,
+) -> Result<()> {
+    for key in map.data().keys() {
+        let known = match key.value() {
+            KValue::Str(name) => message.field_by_name(name).is_some(),
+            _ => false,
+        };
+        if !known {
+            return Err(format!(
+                "Unknown field '{key}' for message '{message_name}'"
+            ));
+        }
+    }
+
+    let mut fields: Vec<_> = message.fields.iter().collect();
+    fields.sort_by_key(|field| field.number);
+
+    for field in fields {
+        let value = match map.get(field.name.as_str()) {
+            None | Some(KValue::Null) => continue,
+            Some(value) => value,
+        };
+        let name = field.name.as_str();
+
+        match &field.field_type {
+            FieldType::Map(key_type, value_type) => {
+                let KValue::Map(entries) = &value else {
+                    return Err(unexpected_type("a Map", name, &value));
+                };
+                let entries: Vec<_> = entries
+                    .data()
+                    .iter()
+                    .map(|(key, value)| (key.value().clone(), value.clone()))
+                    .collect();
+
+                for (key, value) in entries {
+                    let mut entry = Vec::new();
+                    encode_tagged(schema, key_type, 1, name, &key, &mut entry)?;
+                    encode_tagged(schema, value_type, 2, name, &value, &mut entry)?;
+                    write_tag(field.number, LENGTH_DELIMITED, output);
+                    write_length_delimited(&entry, output);
+                }
+            }
+            field_type if field.repeated => {
+                let values = match &value {
+                    KValue::List(list) => list.data().to_vec(),
+                    KValue::Tuple(tuple) => tuple.to_vec(),
+                    unexpected => return Err(unexpected_type("a List or Tuple", name, unexpected)),
+                };
+
+                if field_type.is_packable() {
+                    if values.is_empty() {
+                        continue;
+                    }
+                    let mut packed = Vec::new();
+                    for value in values.iter() {
+                        encode_value(schema, field_type, name, value, &mut packed)?;
+                    }
+                    write_tag(field.number, LENGTH_DELIMITED, output);
+                    write_length_delimited(&packed, output);
+                } else {
+                    for value in values.iter() {
+                        encode_tagged(schema, field_type, field.number, name, value, output)?;
+                    }
+                }
+            }
+            field_type => encode_tagged(schema, field_type, field.number, name, &value, output)?,
+        }
+    }
+
+    Ok(())
+}
+
+fn encode_tagged(
+    schema: &Schema,
+    field_type: &FieldType,
+    number: u32,
+    name: &str,
+    value: &KValue,
+    output: &mut Vec<u8>,
+) -> Result<()> {
+    write_tag(number, wire_type(field_type), output);
+    encode_value(schema, field_type, name, value, output)
+}
+
+fn encode_value(
+    schema: &Schema,
+    field_type: &FieldType,
+    name: &str,
+    value: &KValue,
+    output: &mut Vec<u8>,
+) -> Result<()> {
+    use FieldType::*;
+
+    let number = |value: &KValue| match value {
+        KValue::Number(n) => Ok(*n),
+        unexpected => Err(unexpected_type("a Number", name, unexpected)),
+    };
+
+    match field_type {
+        Int32 | Int64 | UInt32 | UInt64 => write_varint(number(value)?.as_i64() as u64, output),
+        SInt32 | SInt64 => write_varint(zigzag_encode(number(value)?.as_i64()), output),
+        Bool => match value {
+            KValue::Bool(b) => write_varint(u64::from(*b), output),
+            unexpected => return Err(unexpected_type("a Bool", name, unexpected)),
+        },
+        Enum(enum_name) => {
+            let n = match value {
+                KValue::Number(n) => n.as_i64(),
+                KValue::Str(s) => schema
+                    .enum_def(enum_name)
+                    .and_then(|enum_def| enum_def.value_for_name(s))
+                    .ok_or_else(|| format!("'{s}' isn't a value of '{enum_name}'"))?
+                    .into(),
+                unexpected => return Err(unexpected_type("a String or Number", name, unexpected)),
+            };
+            write_varint(n as u64, output)
+        }
+        Fixed32 => output.extend((number(value)?.as_i64() as u32).to_le_bytes()),
+        SFixed32 => output.extend((number(value)?.as_i64() as i32).to_le_bytes()),
+        Float => output.extend((f64::from(number(value)?) as f32).to_le_bytes()),
+        Fixed64 | SFixed64 => output.extend(number(value)?.as_i64().to_le_bytes()),
+        Double => output.extend(f64::from(number(value)?).to_le_bytes()),
+        String => match value {
+            KValue::Str(s) => write_length_delimited(s.as_bytes(), output),
+            unexpected => return Err(unexpected_type("a String", name, unexpected)),
+        },
+        Bytes => write_length_delimited(&bytes_from_value(name, value)?, output),
+        Message(message_name) => match value {
+            KValue::Map(map) => {
+                let mut nested = Vec::new();
+                let message = find_message(schema, message_name)?;
+                encode_message(schema, message_name, message, map, &mut nested)?;
+                write_length_delimited(&nested, output);
+            }
+            unexpected => return Err(unexpected_type("a Map", name, unexpected)),
+        },
+        Map(..) | Named(_) => unreachable!(),
+    }
+
+    Ok(())
+}
+
+/// Converts a String, or a List or Tuple of numbers, into bytes
+pub fn bytes_from_value(name: &str, value: &KValue) -> Result<Vec<u8>> {
+    let values = match value {
+        KValue::Str(s) => return Ok(s.as_bytes().to_vec()),
+        KValue::List(list) => list.data().to_vec(),
+        KValue::Tuple(tuple) => tuple.to_vec(),
+        unexpected => {
+            return Err(unexpected_type(
+                "a String, or a List or Tuple of bytes",
+                name,
+                unexpected,
+            ))
+        }
+    };
+
+    values
+        .iter()
+        .map(|value| match value {
+            KValue::Number(n) => u8::try_from(n.as_i64())
+                .map_err(|_| format!("'{n}' is out of the valid byte range")),
+            unexpected => Err(unexpected_type("a byte Number", name, unexpected)),
+        })
+        .collect()
+}
+
+fn unexpected_type(expected: &str, name: &str, unexpected: &KValue) -> String {
+    format!(
+        "Expected {expected} for '{name}', found '{}'",
+        unexpected.type_as_string()
+    )
+}
+
+fn write_varint(mut n: u64, output: &mut Vec<u8>) {
+    while n >= 0x80 {
+        output.push((n as u8 & 0x7f) | 0x80);
+        n >>= 7;
+    }
+    output.push(n as u8);
+}
+
+fn write_tag(number: u32, wire_type: u8, output: &mut Vec<u8>) {
+    write_varint((u64::from(number) << 3) | u64::from(wire_type), output);
+}
+
+fn write_length_delimited(bytes: &[u8], output: &mut Vec<u8>) {
+    write_varint(bytes.len() as u64, output);
+    output.extend_from_slice(bytes);
+}
@@ -0,0 +1,318 @@
+use koto_runtime::{derive::*, prelude::*, Error, PtrMut, Result};
+use rustyline::{
+    completion::Completer, error::ReadlineError, highlight::Highlighter, hint::Hinter,
+    history::DefaultHistory, validate::Validator, Config, EditMode, Helper,
+};
+use std::io;
+
+/// The settings used when making an [Editor]
+#[derive(Clone)]
+pub struct EditorSettings {
+    /// Emacs or vi key bindings
+    pub edit_mode: EditMode,
+    /// The maximum number of history entries, or `None` to use rustyline's default
+    pub max_history: Option<usize>,
+    /// When true, lines are added to the history after they're read
+    pub auto_add_history: bool,
+}
+
+impl Default for EditorSettings {
+    fn default() -> Self {
+        Self {
+            edit_mode: EditMode::Emacs,
+            max_history: None,
+            auto_add_history: true,
+        }
+    }
+}
+
+impl EditorSettings {
+    /// Makes settings from a Koto settings map
+    pub fn from_map(settings: &KMap) -> Result<Self> {
+        let edit_mode = match settings.get("edit_mode") {
+            Some(KValue::Str(mode)) if mode.as_str() == "emacs" => EditMode::Emacs,
+            Some(KValue::Str(mode)) if mode.as_str() == "vi" => EditMode::Vi,
+            Some(KValue::Null) | None => EditMode::Emacs,
+            Some(unexpected) => return type_error("'emacs' or 'vi' as edit_mode", &unexpected),
+        };
+        let max_history = match settings.get("max_history") {
+            Some(KValue::Number(n)) if n >= 0 => Some(usize::from(n)),
+            Some(KValue::Null) | None => None,
+            Some(unexpected) => {
+                return type_error("a non-negative Number as max_history", &unexpected)
+            }
+        };
+        let auto_add_history = match settings.get("auto_add_history") {
+            Some(KValue::Bool(value)) => value,
+            Some(KValue::Null) | None => true,
+            Some(unexpected) => return type_error("a Bool as auto_add_history", &unexpected),
+        };
+
+        Ok(Self {
+            edit_mode,
+            max_history,
+            auto_add_history,
+        })
+    }
+}
+
+/// A line editor with history and completion, used for building interactive prompts
+///
+/// The editor is shared between copies, so history that's added via one copy is available in
+/// the others.
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "Editor")]
+pub struct Editor(PtrMut<rustyline::Editor<EditorHelper, DefaultHistory>>);
+
+#[koto_impl(runtime = koto_runtime)]
+impl Editor {
+    /// Makes a new editor
+    pub fn new(settings: EditorSettings) -> Result<Self> {
+        let mut config = Config::builder()
+            .edit_mode(settings.edit_mode)
+            .auto_add_history(settings.auto_add_history);
+        if let Some(max_history) = settings.max_history {
+            config = config
+                .max_history_size(max_history)
+                .map_err(map_readline_err)?;
+        }
+
+        let mut editor =
+            rustyline::Editor::with_config(config.build()).map_err(map_readline_err)?;
+        editor.set_helper(Some(EditorHelper::default()));
+
+        Ok(Self(editor.into()))
+    }
+
+    /// Reads a line of input, returning `None` if input was ended with Ctrl-C or Ctrl-D
+    ///
+    /// The initial input is placed before the cursor, e.g. for indenting a continued line.
+    pub fn read_line(&self, prompt: &str, initial: &str) -> Result<Option<String>> {
+        let mut editor = self.0.borrow_mut();
+        let result = editor.readline_with_initial(prompt, (initial, ""));
+
+        // An error thrown by the completer takes priority over the error it caused
+        if let Some(error) = editor.helper().and_then(EditorHelper::take_error) {
+            return Err(error);
+        }
+
+        match result {
+            Ok(line) => Ok(Some(line)),
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => Ok(None),
+            Err(error) => Err(map_readline_err(error)),
+        }
+    }
+
+    /// Returns the completion candidates for the input, using the editor's completer
+    ///
+    /// The candidates replace the word at the end of the input, and the word's start position is
+    /// returned along with the candidates.
+    pub fn completions(&self, input: &str) -> Result<(usize, Vec<String>)> {
+        match self.0.borrow().helper() {
+            Some(helper) => helper.complete_input(input),
+            None => Ok((input.len(), Vec::new())),
+        }
+    }
+
+    #[koto_method]
+    fn add_history(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Str(line)] => self
+                .0
+                .borrow_mut()
+                .add_history_entry(line.as_str())
+                .map(KValue::from)
+                .map_err(map_readline_err),
+            unexpected => type_error_with_slice("a String", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn clear_history(&self) -> Result<KValue> {
+        self.0
+            .borrow_mut()
+            .clear_history()
+            .map_err(map_readline_err)?;
+        Ok(KValue::Null)
+    }
+
+    #[koto_method]
+    fn history(&self) -> KValue {
+        let entries: Vec<KValue> = self
+            .0
+            .borrow()
+            .history()
+            .iter()
+            .map(|entry| entry.as_str().into())
+            .collect();
+        KList::with_data(entries.into()).into()
+    }
+
+    #[koto_method]
+    fn load_history(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Str(path)] => {
+                self.0
+                    .borrow_mut()
+                    .load_history(path.as_str())
+                    .map_err(|e| Error::from(format!("failed to load '{path}': {e}")))?;
+                Ok(KValue::Null)
+            }
+            unexpected => type_error_with_slice("a path String", unexpected),
+        }
+    }
+
+    #[koto_method(alias = "read_line")]
+    fn read_line_koto(&self, args: &[KValue]) -> Result<KValue> {
+        let (prompt, initial) = match args {
+            [KValue::Str(prompt)] => (prompt.as_str(), ""),
+            [KValue::Str(prompt), KValue::Str(initial)] => (prompt.as_str(), initial.as_str()),
+            unexpected => {
+                return type_error_with_slice(
+                    "a prompt String, with an optional String of initial input",
+                    unexpected,
+                )
+            }
+        };
+        Ok(self
+            .read_line(prompt, initial)?
+            .map_or(KValue::Null, KValue::from))
+    }
+
+    #[koto_method]
+    fn save_history(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Str(path)] => {
+                self.0
+                    .borrow_mut()
+                    .save_history(path.as_str())
+                    .map_err(|e| Error::from(format!("failed to save '{path}': {e}")))?;
+                Ok(KValue::Null)
+            }
+            unexpected => type_error_with_slice("a path String", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn set_completer(ctx: MethodContext<Self>) -> Result<KValue> {
+        let completer = match ctx.args {
+            [f] if f.is_callable() => Some(f.clone()),
+            [KValue::Null] => None,
+            unexpected => return type_error_with_slice("a Function or null", unexpected),
+        };
+        let vm = ctx.vm.spawn_shared_vm();
+
+        let this = ctx.instance()?;
+        if let Some(helper) = this.0.borrow_mut().helper_mut() {
+            *helper.0.borrow_mut() = CompletionState {
+                completer: completer.map(|completer| (completer, vm)),
+                error: None,
+            };
+        }
+
+        ctx.instance_result()
+    }
+}
+
+impl KotoObject for Editor {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(Self::type_static());
+        Ok(())
+    }
+}
+
+impl From<Editor> for KValue {
+    fn from(editor: Editor) -> Self {
+        KObject::from(editor).into()
+    }
+}
+
+// The rustyline helper that calls a completer function that's been provided by a script
+//
+// Rustyline's helper traits take `&self`, so the completion state is kept in a cell to allow
+// the completer's VM to be used, and for errors to be stored until reading has finished.
+#[derive(Default)]
+struct EditorHelper(PtrMut<CompletionState>);
+
+#[derive(Default)]
+struct CompletionState {
+    completer: Option<(KValue, KotoVm)>,
+    error: Option<Error>,
+}
+
+impl EditorHelper {
+    // Calls the completer with the word that's being completed, along with the input that
+    // precedes the cursor
+    //
+    // The candidates that are returned by the completer replace the word, so the word's start
+    // position is returned along with the candidates.
+    fn complete_input(&self, input: &str) -> Result<(usize, Vec<String>)> {
+        let word_start = input.rfind(char::is_whitespace).map_or(0, |index| {
+            index + input[index..].chars().next().unwrap().len_utf8()
+        });
+
+        let mut state = self.0.borrow_mut();
+        let Some((completer, vm)) = state.completer.as_mut() else {
+            return Ok((word_start, Vec::new()));
+        };
+
+        let word = &input[word_start..];
+        let output = vm.call_function(completer.clone(), &[word.into(), input.into()])?;
+
+        let mut candidates = Vec::new();
+        for candidate in vm.make_iterator(output)? {
+            match candidate {
+                KIteratorOutput::Value(KValue::Str(candidate)) => {
+                    candidates.push(candidate.to_string())
+                }
+                KIteratorOutput::Value(unexpected) => {
+                    return type_error("a String as a completion candidate", &unexpected)
+                }
+                KIteratorOutput::ValuePair(..) => {
+                    return runtime_error!("Expected a String as a completion candidate")
+                }
+                KIteratorOutput::Error(error) => return Err(error),
+            }
+        }
+
+        Ok((word_start, candidates))
+    }
+
+    fn take_error(&self) -> Option<Error> {
+        self.0.borrow_mut().error.take()
+    }
+}
+
+impl Completer for EditorHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &rustyline::Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        match self.complete_input(&line[..pos]) {
+            Ok(result) => Ok(result),
+            Err(error) => {
+                // Reading is aborted, with the error being returned from read_line
+                self.0.borrow_mut().error = Some(error);
+                Err(ReadlineError::Io(io::Error::other("the completer failed")))
+            }
+        }
+    }
+}
+
+impl Hinter for EditorHelper {
+    type Hint = String;
+}
+
+impl Highlighter for EditorHelper {}
+
+impl Validator for EditorHelper {}
+
+impl Helper for EditorHelper {}
+
+fn map_readline_err(error: ReadlineError) -> Error {
+    Error::from(error.to_string())
+}
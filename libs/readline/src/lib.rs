@@ -0,0 +1,19 @@
+//! A Koto language module for reading lines of input with a line editor
+
+mod editor;
+
+pub use editor::{Editor, EditorSettings};
+
+use koto_runtime::prelude::*;
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("readline");
+
+    result.add_fn("editor", |ctx| match ctx.args() {
+        [] => Ok(Editor::new(EditorSettings::default())?.into()),
+        [KValue::Map(settings)] => Ok(Editor::new(EditorSettings::from_map(settings)?)?.into()),
+        unexpected => type_error_with_slice("an optional settings Map", unexpected),
+    });
+
+    result
+}
@@ -0,0 +1,66 @@
+use koto_readline::{Editor, EditorSettings};
+use koto_runtime::{prelude::*, Result};
+use koto_test_utils::check_script_output_with_vm;
+
+fn set_completer(editor: &Editor, script: &str) -> Result<()> {
+    let vm = KotoVm::default();
+    vm.prelude().insert("editor", editor.clone());
+    check_script_output_with_vm(vm, script, KValue::Null)
+}
+
+#[test]
+fn completer_is_called_with_the_word_and_input() -> Result<()> {
+    let editor = Editor::new(EditorSettings::default())?;
+    let script = "
+editor.set_completer |word, input|
+  candidates = if input.starts_with 'open '
+    ['file.txt', 'files/', 'other.txt']
+  else
+    ['help', 'hello', 'open']
+  candidates.keep |c| c.starts_with word
+null
+";
+    set_completer(&editor, script)?;
+
+    assert_eq!(
+        editor.completions("he")?,
+        (0, vec!["help".to_string(), "hello".to_string()])
+    );
+    assert_eq!(
+        editor.completions("open fi")?,
+        (5, vec!["file.txt".to_string(), "files/".to_string()])
+    );
+    assert_eq!(
+        editor.completions("open ")?,
+        (
+            5,
+            vec![
+                "file.txt".to_string(),
+                "files/".to_string(),
+                "other.txt".to_string()
+            ]
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn completions_without_a_completer() -> Result<()> {
+    let editor = Editor::new(EditorSettings::default())?;
+    assert_eq!(editor.completions("open fi")?, (5, vec![]));
+
+    set_completer(&editor, "editor.set_completer |word| [word]\nnull")?;
+    assert_eq!(editor.completions("x")?, (0, vec!["x".to_string()]));
+
+    set_completer(&editor, "editor.set_completer null\nnull")?;
+    assert_eq!(editor.completions("x")?, (0, vec![]));
+    Ok(())
+}
+
+#[test]
+fn completer_errors_are_returned() -> Result<()> {
+    let editor = Editor::new(EditorSettings::default())?;
+    set_completer(&editor, "editor.set_completer |word| [word, 42]\nnull")?;
+    assert!(editor.completions("x").is_err());
+    Ok(())
+}
@@ -118,6 +118,36 @@ impl Regex {
             unexpected => type_error_with_slice("two strings", unexpected),
         }
     }
+
+    #[koto_method]
+    fn split(&self, args: &[KValue]) -> Result<KValue> {
+        let (text, limit) = match args {
+            [KValue::Str(text)] => (text, None),
+            [KValue::Str(text), KValue::Number(n)] if *n >= 0.0 => (text, Some(usize::from(n) + 1)),
+            unexpected => {
+                return type_error_with_slice(
+                    "a string, with an optional non-negative max splits number",
+                    unexpected,
+                )
+            }
+        };
+
+        let mut parts = Vec::new();
+        let mut start = 0;
+        for m in self.0.find_iter(text) {
+            if limit.is_some_and(|limit| parts.len() + 1 >= limit) {
+                break;
+            }
+            parts.extend(text.with_bounds(start..m.start()));
+            start = m.end();
+        }
+        parts.extend(text.with_bounds(start..text.len()));
+
+        let parts = parts
+            .into_iter()
+            .map(|part| KIteratorOutput::Value(part.into()));
+        Ok(KIterator::with_std_iter(parts).into())
+    }
 }
 
 impl KotoObject for Regex {}
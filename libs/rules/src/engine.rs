@@ -0,0 +1,402 @@
+use koto_runtime::{derive::*, prelude::*, BinaryOp, PtrMut, Result};
+
+/// A collection of rules that are matched against incoming events
+///
+/// Each rule has a condition and an action, with the action being called for each event that
+/// matches the condition. Rules are checked in order of priority, with rules of equal priority
+/// being checked in the order that they were added.
+#[derive(Clone, KotoType)]
+#[koto(type_name = "Engine")]
+pub struct Engine(PtrMut<EngineState>);
+
+#[derive(Clone, Default)]
+struct EngineState {
+    // Rules are kept sorted by priority, highest first
+    rules: Vec<Rule>,
+    next_id: usize,
+}
+
+#[derive(Clone)]
+struct Rule {
+    id: usize,
+    name: KString,
+    condition: Condition,
+    action: KValue,
+    priority: KNumber,
+    once: bool,
+    fired: bool,
+}
+
+/// A rule's condition, either a predicate function or a pattern map
+#[derive(Clone)]
+pub enum Condition {
+    Function(KValue),
+    Pattern(KMap),
+}
+
+impl Condition {
+    /// Makes a condition from a Koto value, returning `None` if the value isn't a condition
+    pub fn from_value(value: &KValue) -> Option<Self> {
+        match value {
+            f if f.is_callable() => Some(Self::Function(f.clone())),
+            KValue::Map(pattern) => Some(Self::Pattern(pattern.clone())),
+            _ => None,
+        }
+    }
+
+    /// Checks the condition against an event
+    ///
+    /// If the event doesn't match then the reason for the mismatch is returned as an error string.
+    pub fn check(
+        &self,
+        vm: &mut KotoVm,
+        event: &KValue,
+    ) -> Result<std::result::Result<(), String>> {
+        match self {
+            Self::Function(f) => match vm.call_function(f.clone(), event.clone())? {
+                KValue::Bool(true) => Ok(Ok(())),
+                KValue::Bool(false) => Ok(Err("condition returned false".into())),
+                unexpected => type_error("a Bool to be returned from the condition", &unexpected),
+            },
+            Self::Pattern(pattern) => match event {
+                KValue::Map(event) => match_pattern(vm, pattern, event, ""),
+                unexpected => Ok(Err(format!(
+                    "expected a Map to match against the pattern, found '{}'",
+                    unexpected.type_as_string()
+                ))),
+            },
+        }
+    }
+}
+
+// The result of checking a rule against an event
+struct Outcome {
+    rule: Rule,
+    reason: std::result::Result<&'static str, String>,
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Engine {
+    /// Makes a new engine without any rules
+    pub fn new() -> Self {
+        Self(PtrMut::from(EngineState::default()))
+    }
+
+    /// Adds a rule, replacing any existing rule with the same name
+    pub fn add_rule(
+        &self,
+        name: KString,
+        condition: Condition,
+        action: KValue,
+        priority: KNumber,
+        once: bool,
+    ) {
+        self.remove_rule(&name);
+
+        let mut state = self.0.borrow_mut();
+        let id = state.next_id;
+        state.next_id += 1;
+
+        // New rules go after existing rules with the same priority
+        let index = state
+            .rules
+            .iter()
+            .position(|rule| f64::from(rule.priority) < f64::from(priority))
+            .unwrap_or(state.rules.len());
+        state.rules.insert(
+            index,
+            Rule {
+                id,
+                name,
+                condition,
+                action,
+                priority,
+                once,
+                fired: false,
+            },
+        );
+    }
+
+    /// Removes the rule with the given name, returning true if the rule was found
+    pub fn remove_rule(&self, name: &str) -> bool {
+        let mut state = self.0.borrow_mut();
+        let count = state.rules.len();
+        state.rules.retain(|rule| rule.name.as_str() != name);
+        state.rules.len() != count
+    }
+
+    /// Runs the rules against an event, returning the names of the rules that fired
+    ///
+    /// The actions of matching rules are called with the event in order of priority, and rules
+    /// that were added with the `once` option are disabled after firing.
+    pub fn run_event(&self, vm: &mut KotoVm, event: &KValue) -> Result<Vec<KString>> {
+        let mut fired = Vec::new();
+
+        // The rules are cloned so that they aren't borrowed while conditions and actions are
+        // running, allowing rules to be modified by actions.
+        let rules = self.0.borrow().rules.clone();
+        for rule in rules {
+            if rule.fired {
+                continue;
+            }
+            if rule.condition.check(vm, event)?.is_err() {
+                continue;
+            }
+
+            if rule.once {
+                let mut state = self.0.borrow_mut();
+                if let Some(rule) = state.rules.iter_mut().find(|r| r.id == rule.id) {
+                    rule.fired = true;
+                }
+            }
+            vm.call_function(rule.action.clone(), event.clone())?;
+            fired.push(rule.name);
+        }
+
+        Ok(fired)
+    }
+
+    fn explain_event(&self, vm: &mut KotoVm, event: &KValue) -> Result<Vec<Outcome>> {
+        let rules = self.0.borrow().rules.clone();
+        rules
+            .into_iter()
+            .map(|rule| {
+                let reason = if rule.fired {
+                    Err("once-only rule has already fired".into())
+                } else {
+                    let matched = match &rule.condition {
+                        Condition::Function(_) => "condition returned true",
+                        Condition::Pattern(_) => "pattern matched",
+                    };
+                    rule.condition.check(vm, event)?.map(|_| matched)
+                };
+                Ok(Outcome { rule, reason })
+            })
+            .collect()
+    }
+
+    /// Returns the number of rules
+    pub fn len(&self) -> usize {
+        self.0.borrow().rules.len()
+    }
+
+    /// Returns true if the engine contains no rules
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[koto_method]
+    fn add(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (name, condition, action, options) = match ctx.args {
+            [KValue::Str(name), condition, action] => (name, condition, action, None),
+            [KValue::Str(name), condition, action, KValue::Map(options)] => {
+                (name, condition, action, Some(options))
+            }
+            unexpected => {
+                return type_error_with_slice(
+                    "a name, a condition, an action, and an optional options Map",
+                    unexpected,
+                )
+            }
+        };
+
+        let Some(condition) = Condition::from_value(condition) else {
+            return type_error("a Function or pattern Map as condition", condition);
+        };
+        if !action.is_callable() {
+            return type_error("a Function as action", action);
+        }
+
+        let mut priority = KNumber::from(0);
+        let mut once = false;
+        if let Some(options) = options {
+            match options.get("priority") {
+                Some(KValue::Number(n)) => priority = n,
+                Some(KValue::Null) | None => {}
+                Some(unexpected) => return type_error("a Number as priority", &unexpected),
+            }
+            match options.get("once") {
+                Some(KValue::Bool(b)) => once = b,
+                Some(KValue::Null) | None => {}
+                Some(unexpected) => return type_error("a Bool as once", &unexpected),
+            }
+        }
+
+        ctx.instance()?
+            .add_rule(name.clone(), condition, action.clone(), priority, once);
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn clear(ctx: MethodContext<Self>) -> Result<KValue> {
+        ctx.instance()?.0.borrow_mut().rules.clear();
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn explain(ctx: MethodContext<Self>) -> Result<KValue> {
+        let event = match ctx.args {
+            [event] => event,
+            unexpected => return type_error_with_slice("an event", unexpected),
+        };
+
+        let this = ctx.instance()?.clone();
+        let mut vm = ctx.vm.spawn_shared_vm();
+        let report: Vec<KValue> = this
+            .explain_event(&mut vm, event)?
+            .into_iter()
+            .map(|outcome| {
+                let entry = KMap::with_capacity(4);
+                entry.insert("rule", outcome.rule.name);
+                entry.insert("priority", outcome.rule.priority);
+                let (fired, reason) = match outcome.reason {
+                    Ok(reason) => (true, reason.to_string()),
+                    Err(reason) => (false, reason),
+                };
+                entry.insert("fired", fired);
+                entry.insert("reason", reason);
+                entry.into()
+            })
+            .collect();
+
+        Ok(KList::with_data(report.into()).into())
+    }
+
+    #[koto_method]
+    fn names(&self) -> KValue {
+        let names: Vec<KValue> = self
+            .0
+            .borrow()
+            .rules
+            .iter()
+            .map(|rule| rule.name.clone().into())
+            .collect();
+        KList::with_data(names.into()).into()
+    }
+
+    #[koto_method]
+    fn remove(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Str(name)] => Ok(self.remove_rule(name).into()),
+            unexpected => type_error_with_slice("a rule name", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn reset(ctx: MethodContext<Self>) -> Result<KValue> {
+        for rule in ctx.instance()?.0.borrow_mut().rules.iter_mut() {
+            rule.fired = false;
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn run(ctx: MethodContext<Self>) -> Result<KValue> {
+        let event = match ctx.args {
+            [event] => event,
+            unexpected => return type_error_with_slice("an event", unexpected),
+        };
+
+        // The instance is cloned so that it isn't borrowed while actions are running
+        let this = ctx.instance()?.clone();
+        let mut vm = ctx.vm.spawn_shared_vm();
+        let fired: Vec<KValue> = this
+            .run_event(&mut vm, event)?
+            .into_iter()
+            .map(KValue::from)
+            .collect();
+        Ok(KList::with_data(fired.into()).into())
+    }
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KotoCopy for Engine {
+    fn copy(&self) -> KObject {
+        // Copies get their own rules
+        Self(PtrMut::from(self.0.borrow().clone())).into()
+    }
+}
+
+impl KotoObject for Engine {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!("Engine({} rules)", self.len()));
+        Ok(())
+    }
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl From<Engine> for KValue {
+    fn from(engine: Engine) -> Self {
+        KObject::from(engine).into()
+    }
+}
+
+// Matches an event map against a pattern map
+//
+// Each of the pattern's entries needs to have a matching entry in the event, with pattern values
+// being compared for equality, or being called as predicates if they're functions.
+// Nested maps are matched recursively, with the path to the current map in `prefix`.
+fn match_pattern(
+    vm: &mut KotoVm,
+    pattern: &KMap,
+    event: &KMap,
+    prefix: &str,
+) -> Result<std::result::Result<(), String>> {
+    // The entries are cloned so that the maps aren't borrowed while predicates are running
+    let entries: Vec<(ValueKey, KValue)> = pattern
+        .data()
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+
+    for (key, expected) in entries {
+        let path = format!("{prefix}{key}");
+        let Some(actual) = event.data().get(&key).cloned() else {
+            return Ok(Err(format!("'{path}' is missing")));
+        };
+
+        match (&expected, &actual) {
+            (f, _) if f.is_callable() => match vm.call_function(f.clone(), actual.clone())? {
+                KValue::Bool(true) => {}
+                KValue::Bool(false) => {
+                    let actual = describe(vm, &actual)?;
+                    return Ok(Err(format!("'{path}' is {actual}, which failed the check")));
+                }
+                unexpected => {
+                    return type_error("a Bool to be returned from the check", &unexpected)
+                }
+            },
+            (KValue::Map(expected), KValue::Map(actual)) => {
+                if let Err(reason) = match_pattern(vm, expected, actual, &format!("{path}."))? {
+                    return Ok(Err(reason));
+                }
+            }
+            _ => match vm.run_binary_op(BinaryOp::Equal, actual.clone(), expected.clone())? {
+                KValue::Bool(true) => {}
+                _ => {
+                    let actual = describe(vm, &actual)?;
+                    let expected = describe(vm, &expected)?;
+                    return Ok(Err(format!("'{path}' is {actual}, expected {expected}")));
+                }
+            },
+        }
+    }
+
+    Ok(Ok(()))
+}
+
+// Renders a value for use in explanations, with quotes around strings
+fn describe(vm: &mut KotoVm, value: &KValue) -> Result<String> {
+    match value {
+        KValue::Str(s) => Ok(format!("'{s}'")),
+        other => vm.value_to_string(other),
+    }
+}
@@ -0,0 +1,18 @@
+//! A Koto language module for rule-based event dispatch
+
+mod engine;
+
+pub use engine::{Condition, Engine};
+
+use koto_runtime::prelude::*;
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("rules");
+
+    result.add_fn("engine", |ctx| match ctx.args() {
+        [] => Ok(Engine::new().into()),
+        unexpected => type_error_with_slice("no arguments", unexpected),
+    });
+
+    result
+}
@@ -0,0 +1,305 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+use koto_runtime::{core_lib::os::DateTime, derive::*, prelude::*, Ptr, Result};
+
+// The number of years to search ahead for an occurrence before giving up,
+// long enough to find a schedule that only runs on leap days.
+const MAX_YEARS_AHEAD: i32 = 10;
+
+const MONTH_NAMES: &[&str] = &[
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAY_NAMES: &[&str] = &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// A schedule defined by a cron expression
+///
+/// Expressions have five fields: minute, hour, day of the month, month, and day of the week.
+/// Each field can be `*`, a value, a range like `1-5`, a step like `*/15` or `0-30/10`, or a
+/// comma-separated list of any of these. Months and days of the week can also be given by name.
+#[derive(Clone, KotoCopy, KotoType)]
+#[koto(type_name = "Cron")]
+pub struct Cron(Ptr<Schedule>);
+
+/// The parsed fields of a cron expression
+///
+/// Each field is a bit set, with a bit set for each matching value.
+pub struct Schedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    // True if the day of month or day of week fields aren't `*`
+    //
+    // When both day fields are restricted, then a day matches if either of the fields match.
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl Schedule {
+    /// Parses a cron expression
+    ///
+    /// `@yearly`, `@annually`, `@monthly`, `@weekly`, `@daily`, `@midnight`, and `@hourly` are
+    /// also supported.
+    pub fn parse(expression: &str) -> Result<Self> {
+        let fields = match expression.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = fields.split_whitespace().collect();
+        let [minutes, hours, days, months, weekdays] = fields.as_slice() else {
+            return invalid(
+                expression,
+                &format!("expected 5 fields, found {}", fields.len()),
+            );
+        };
+
+        let parse =
+            |field: &str, name: &str, min, max, names| match parse_field(field, min, max, names) {
+                Ok(bits) => Ok(bits),
+                Err(message) => {
+                    invalid(expression, &format!("{message} in {name} field '{field}'"))
+                }
+            };
+
+        let mut weekday_bits = parse(weekdays, "day of week", 0, 7, Some((WEEKDAY_NAMES, 0)))?;
+        // 7 is an alternative value for Sunday
+        if weekday_bits & (1 << 7) != 0 {
+            weekday_bits = (weekday_bits & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            expression: expression.to_string(),
+            minutes: parse(minutes, "minute", 0, 59, None)?,
+            hours: parse(hours, "hour", 0, 23, None)?,
+            days: parse(days, "day of month", 1, 31, None)?,
+            months: parse(months, "month", 1, 12, Some((MONTH_NAMES, 1)))?,
+            weekdays: weekday_bits,
+            days_restricted: !days.starts_with('*'),
+            weekdays_restricted: !weekdays.starts_with('*'),
+        })
+    }
+
+    /// Returns true if the schedule has an occurrence at the given time
+    ///
+    /// Seconds are ignored.
+    pub fn matches(&self, time: NaiveDateTime) -> bool {
+        contains(self.months, time.month())
+            && self.day_matches(time.date())
+            && contains(self.hours, time.hour())
+            && contains(self.minutes, time.minute())
+    }
+
+    /// Returns the first occurrence that's strictly after the given time
+    ///
+    /// `None` is returned if there's no occurrence in the next few years,
+    /// e.g. for a schedule that runs on the 30th of February.
+    pub fn next_after(&self, time: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut t = time.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let last_year = t.year() + MAX_YEARS_AHEAD;
+
+        while t.year() <= last_year {
+            if !contains(self.months, t.month()) {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.day_matches(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if !contains(self.hours, t.hour()) {
+                t = t.date().and_hms_opt(t.hour(), 0, 0)? + Duration::hours(1);
+            } else if !contains(self.minutes, t.minute()) {
+                t += Duration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+
+        None
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day = contains(self.days, date.day());
+        let weekday = contains(self.weekdays, date.weekday().num_days_from_sunday());
+        if self.days_restricted && self.weekdays_restricted {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Cron {
+    /// Makes a new schedule from a cron expression
+    pub fn new(expression: &str) -> Result<Self> {
+        Schedule::parse(expression).map(|schedule| Self(schedule.into()))
+    }
+
+    /// Returns the first occurrence that's strictly after the given time
+    ///
+    /// The occurrence has the same timezone offset as the given time,
+    /// with the schedule being interpreted in the time's local time.
+    pub fn next_occurrence(
+        &self,
+        time: chrono::DateTime<chrono::FixedOffset>,
+    ) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        let next = self.0.next_after(time.naive_local())?;
+        time.offset().from_local_datetime(&next).single()
+    }
+
+    #[koto_method]
+    fn expression(&self) -> KValue {
+        self.0.expression.as_str().into()
+    }
+
+    #[koto_method]
+    fn matches(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Object(o)] if o.is_a::<DateTime>() => {
+                let time = o.cast::<DateTime>()?.inner();
+                Ok(self.0.matches(time.naive_local()).into())
+            }
+            unexpected => type_error_with_slice("a DateTime", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn next_after(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Object(o)] if o.is_a::<DateTime>() => {
+                let time = o.cast::<DateTime>()?.inner();
+                Ok(self
+                    .next_occurrence(time)
+                    .map_or(KValue::Null, |next| DateTime::from_chrono(next).into()))
+            }
+            unexpected => type_error_with_slice("a DateTime", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn upcoming(&self, args: &[KValue]) -> Result<KValue> {
+        let start = match args {
+            [] => chrono::Local::now().fixed_offset(),
+            [KValue::Object(o)] if o.is_a::<DateTime>() => o.cast::<DateTime>()?.inner(),
+            unexpected => return type_error_with_slice("an optional DateTime", unexpected),
+        };
+        Ok(KIterator::new(Occurrences {
+            cron: self.clone(),
+            last: Some(start),
+        })
+        .into())
+    }
+}
+
+impl KotoObject for Cron {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!("Cron({})", self.0.expression));
+        Ok(())
+    }
+}
+
+impl From<Cron> for KValue {
+    fn from(cron: Cron) -> Self {
+        KObject::from(cron).into()
+    }
+}
+
+/// An iterator that produces a schedule's occurrences as DateTimes
+#[derive(Clone)]
+struct Occurrences {
+    cron: Cron,
+    last: Option<chrono::DateTime<chrono::FixedOffset>>,
+}
+
+impl KotoIterator for Occurrences {
+    fn make_copy(&self) -> Result<KIterator> {
+        Ok(KIterator::new(self.clone()))
+    }
+}
+
+impl Iterator for Occurrences {
+    type Item = KIteratorOutput;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.last = self.cron.next_occurrence(self.last?);
+        self.last
+            .map(|next| KValue::from(DateTime::from_chrono(next)).into())
+    }
+}
+
+// Parses a cron field into a bit set of matching values
+//
+// Names are matched case-insensitively, with the index of the name added to the given offset.
+fn parse_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: Option<(&[&str], u32)>,
+) -> std::result::Result<u64, String> {
+    let value = |s: &str| -> std::result::Result<u32, String> {
+        let value = match s.parse::<u32>() {
+            Ok(n) => n,
+            Err(_) => names
+                .and_then(|(names, offset)| {
+                    names
+                        .iter()
+                        .position(|name| name.eq_ignore_ascii_case(s))
+                        .map(|i| i as u32 + offset)
+                })
+                .ok_or_else(|| format!("unexpected value '{s}'"))?,
+        };
+        if (min..=max).contains(&value) {
+            Ok(value)
+        } else {
+            Err(format!("{value} is out of range ({min}-{max})"))
+        }
+    };
+
+    let mut result = 0;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<u32>() {
+                Ok(step) if step > 0 => (range, Some(step)),
+                _ => return Err(format!("invalid step '{step}'")),
+            },
+            None => (part, None),
+        };
+
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (value(start)?, value(end)?),
+            // A single value with a step runs until the end of the field's range
+            None if step.is_some() => (value(range)?, max),
+            None => {
+                let n = value(range)?;
+                (n, n)
+            }
+        };
+        if start > end {
+            return Err(format!("invalid range '{range}'"));
+        }
+
+        for n in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            result |= 1 << n;
+        }
+    }
+
+    Ok(result)
+}
+
+fn invalid<T>(expression: &str, message: &str) -> Result<T> {
+    runtime_error!("Invalid cron expression '{expression}': {message}")
+}
+
+fn contains(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
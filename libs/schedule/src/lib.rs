@@ -0,0 +1,18 @@
+//! A Koto language module for cron-style scheduling
+
+mod cron;
+
+pub use cron::{Cron, Schedule};
+
+use koto_runtime::prelude::*;
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("schedule");
+
+    result.add_fn("cron", |ctx| match ctx.args() {
+        [KValue::Str(expression)] => Ok(Cron::new(expression)?.into()),
+        unexpected => type_error_with_slice("a cron expression String", unexpected),
+    });
+
+    result
+}
@@ -0,0 +1,139 @@
+//! A Koto language module for working with semantic versions
+
+use koto_runtime::{derive::*, prelude::*, Result};
+use std::fmt;
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("semver");
+
+    result.add_fn("matches", |ctx| match ctx.args() {
+        [version, KValue::Str(requirement)] => {
+            let version = Version::from_value(version)?;
+            version.matches_requirement(requirement)
+        }
+        unexpected => type_error_with_slice(
+            "a Version (or version String) and a requirement String",
+            unexpected,
+        ),
+    });
+
+    result.add_fn("parse", |ctx| match ctx.args() {
+        [KValue::Str(version)] => Ok(Version::parse(version)?.into()),
+        unexpected => type_error_with_slice("a version String", unexpected),
+    });
+
+    result
+}
+
+/// A semantic version, wrapping [semver::Version]
+#[derive(Clone, Debug, KotoType, KotoCopy)]
+pub struct Version(semver::Version);
+
+#[koto_impl(runtime = koto_runtime)]
+impl Version {
+    pub fn parse(version: &str) -> Result<Self> {
+        match semver::Version::parse(version) {
+            Ok(version) => Ok(Self(version)),
+            Err(e) => runtime_error!("Failed to parse version '{version}': {e}"),
+        }
+    }
+
+    fn from_value(value: &KValue) -> Result<Self> {
+        match value {
+            KValue::Object(o) if o.is_a::<Self>() => Ok(o.cast::<Self>()?.clone()),
+            KValue::Str(s) => Self::parse(s),
+            unexpected => type_error("a Version or a version String", unexpected),
+        }
+    }
+
+    fn matches_requirement(&self, requirement: &str) -> Result<KValue> {
+        match semver::VersionReq::parse(requirement) {
+            Ok(requirement) => Ok(requirement.matches(&self.0).into()),
+            Err(e) => runtime_error!("Failed to parse version requirement '{requirement}': {e}"),
+        }
+    }
+
+    #[koto_method]
+    fn major(&self) -> KValue {
+        self.0.major.into()
+    }
+
+    #[koto_method]
+    fn minor(&self) -> KValue {
+        self.0.minor.into()
+    }
+
+    #[koto_method]
+    fn patch(&self) -> KValue {
+        self.0.patch.into()
+    }
+
+    #[koto_method]
+    fn pre(&self) -> KValue {
+        if self.0.pre.is_empty() {
+            KValue::Null
+        } else {
+            self.0.pre.as_str().into()
+        }
+    }
+
+    #[koto_method]
+    fn build(&self) -> KValue {
+        if self.0.build.is_empty() {
+            KValue::Null
+        } else {
+            self.0.build.as_str().into()
+        }
+    }
+
+    #[koto_method]
+    fn matches(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Str(requirement)] => self.matches_requirement(requirement),
+            unexpected => type_error_with_slice("a requirement String", unexpected),
+        }
+    }
+}
+
+impl KotoObject for Version {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(self.to_string());
+        Ok(())
+    }
+
+    fn less(&self, rhs: &KValue) -> Result<bool> {
+        Ok(self.0 < Self::from_value(rhs)?.0)
+    }
+
+    fn less_or_equal(&self, rhs: &KValue) -> Result<bool> {
+        Ok(self.0 <= Self::from_value(rhs)?.0)
+    }
+
+    fn greater(&self, rhs: &KValue) -> Result<bool> {
+        Ok(self.0 > Self::from_value(rhs)?.0)
+    }
+
+    fn greater_or_equal(&self, rhs: &KValue) -> Result<bool> {
+        Ok(self.0 >= Self::from_value(rhs)?.0)
+    }
+
+    fn equal(&self, rhs: &KValue) -> Result<bool> {
+        Ok(self.0 == Self::from_value(rhs)?.0)
+    }
+
+    fn not_equal(&self, rhs: &KValue) -> Result<bool> {
+        Ok(self.0 != Self::from_value(rhs)?.0)
+    }
+}
+
+impl From<Version> for KValue {
+    fn from(version: Version) -> Self {
+        KObject::from(version).into()
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,299 @@
+use crate::{
+    envelope::Envelope,
+    wav::{self, SampleFormat},
+};
+use koto_runtime::{derive::*, prelude::*, Result};
+use std::path::Path;
+
+/// A buffer of audio samples
+///
+/// Samples are stored as 32 bit floats, nominally in the range -1 to 1, with the samples of
+/// multi-channel buffers interleaved.
+#[derive(Clone, Debug, KotoType, KotoCopy)]
+pub struct Buffer {
+    pub(crate) samples: Vec<f32>,
+    pub(crate) sample_rate: u32,
+    pub(crate) channels: u16,
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Buffer {
+    pub fn new(samples: Vec<f32>, sample_rate: u32, channels: u16) -> Self {
+        Self {
+            samples,
+            sample_rate,
+            channels,
+        }
+    }
+
+    /// Loads a buffer from a WAV file
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes =
+            std::fs::read(path).map_err(|e| format!("Failed to read '{}': {e}", path.display()))?;
+        wav::decode(&bytes).map_err(|e| format!("Failed to load '{}': {e}", path.display()).into())
+    }
+
+    /// Saves the buffer to a WAV file
+    pub fn save_to_path(&self, path: &Path, sample_format: SampleFormat) -> Result<()> {
+        std::fs::write(path, wav::encode(self, sample_format))
+            .map_err(|e| format!("Failed to write '{}': {e}", path.display()).into())
+    }
+
+    /// The buffer's samples, with the samples of multi-channel buffers interleaved
+    pub fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+
+    /// The number of frames in the buffer, with each frame containing a sample per channel
+    pub fn frame_count(&self) -> usize {
+        self.samples.len() / self.channels as usize
+    }
+
+    fn with_samples(&self, samples: Vec<f32>) -> KValue {
+        Self::new(samples, self.sample_rate, self.channels).into()
+    }
+
+    // Gets another buffer from the arguments, checking that its layout matches this buffer
+    fn other_buffer<'a>(&self, args: &'a [KValue]) -> Result<&'a KObject> {
+        let other = match args {
+            [KValue::Object(o)] if o.is_a::<Self>() => o,
+            unexpected => return type_error_with_slice("a Buffer", unexpected),
+        };
+        let other_buffer = other.cast::<Self>()?;
+        if other_buffer.sample_rate != self.sample_rate || other_buffer.channels != self.channels {
+            return runtime_error!(
+                "Mismatched buffers (sample rate: {} vs. {}, channels: {} vs. {})",
+                self.sample_rate,
+                other_buffer.sample_rate,
+                self.channels,
+                other_buffer.channels
+            );
+        }
+        Ok(other)
+    }
+
+    #[koto_method]
+    fn append(&self, args: &[KValue]) -> Result<KValue> {
+        let other = self.other_buffer(args)?.cast::<Self>()?;
+        let mut samples = self.samples.clone();
+        samples.extend_from_slice(&other.samples);
+        Ok(self.with_samples(samples))
+    }
+
+    #[koto_method]
+    fn apply(&self, args: &[KValue]) -> Result<KValue> {
+        let envelope = match args {
+            [KValue::Object(o)] if o.is_a::<Envelope>() => o.cast::<Envelope>()?,
+            unexpected => return type_error_with_slice("an Envelope", unexpected),
+        };
+
+        let duration = self.frame_count() as f64 / self.sample_rate as f64;
+        let channels = self.channels as usize;
+        let samples = self
+            .samples
+            .iter()
+            .enumerate()
+            .map(|(i, sample)| {
+                let time = (i / channels) as f64 / self.sample_rate as f64;
+                sample * envelope.gain(time, duration) as f32
+            })
+            .collect();
+
+        Ok(self.with_samples(samples))
+    }
+
+    #[koto_method]
+    fn channels(&self) -> KValue {
+        self.channels.into()
+    }
+
+    #[koto_method]
+    fn duration(&self) -> KValue {
+        (self.frame_count() as f64 / self.sample_rate as f64).into()
+    }
+
+    #[koto_method]
+    fn frames(&self) -> KValue {
+        self.frame_count().into()
+    }
+
+    #[koto_method]
+    fn gain(&self, args: &[KValue]) -> Result<KValue> {
+        let gain = match args {
+            [KValue::Number(gain)] => f32::from(gain),
+            unexpected => return type_error_with_slice("a Number", unexpected),
+        };
+        Ok(self.with_samples(self.samples.iter().map(|s| s * gain).collect()))
+    }
+
+    #[koto_method]
+    fn map(ctx: MethodContext<Self>) -> Result<KValue> {
+        let f = match ctx.args {
+            [f] if f.is_callable() => f.clone(),
+            unexpected => return type_error_with_slice("a Function", unexpected),
+        };
+
+        let this = ctx.instance()?.clone();
+        let mut vm = ctx.vm.spawn_shared_vm();
+        let mut samples = Vec::with_capacity(this.samples.len());
+        for sample in this.samples.iter() {
+            match vm.call_function(f.clone(), KValue::from(*sample as f64))? {
+                KValue::Number(n) => samples.push(f32::from(n)),
+                unexpected => return type_error("a Number", &unexpected),
+            }
+        }
+
+        Ok(this.with_samples(samples))
+    }
+
+    #[koto_method]
+    fn mix(&self, args: &[KValue]) -> Result<KValue> {
+        let other = self.other_buffer(args)?.cast::<Self>()?;
+        let length = self.samples.len().max(other.samples.len());
+        let samples = (0..length)
+            .map(|i| {
+                self.samples.get(i).copied().unwrap_or_default()
+                    + other.samples.get(i).copied().unwrap_or_default()
+            })
+            .collect();
+        Ok(self.with_samples(samples))
+    }
+
+    #[koto_method]
+    fn normalize(&self) -> KValue {
+        let peak = self.peak_level();
+        if peak > 0.0 {
+            self.with_samples(self.samples.iter().map(|s| s / peak).collect())
+        } else {
+            self.with_samples(self.samples.clone())
+        }
+    }
+
+    #[koto_method]
+    fn peak(&self) -> KValue {
+        self.peak_level().into()
+    }
+
+    /// The largest absolute sample value in the buffer
+    pub fn peak_level(&self) -> f32 {
+        self.samples.iter().fold(0.0, |peak, s| peak.max(s.abs()))
+    }
+
+    #[koto_method]
+    fn reverse(&self) -> KValue {
+        // Frames are reversed rather than samples, so that channels aren't swapped
+        let samples = self
+            .samples
+            .chunks_exact(self.channels as usize)
+            .rev()
+            .flatten()
+            .copied()
+            .collect();
+        self.with_samples(samples)
+    }
+
+    #[koto_method]
+    fn sample_rate(&self) -> KValue {
+        self.sample_rate.into()
+    }
+
+    #[koto_method]
+    fn save(&self, args: &[KValue]) -> Result<KValue> {
+        let (path, sample_format) = match args {
+            [KValue::Str(path)] => (path, SampleFormat::Int16),
+            [KValue::Str(path), KValue::Number(bits)] => {
+                let sample_format = match i64::from(bits) {
+                    16 => SampleFormat::Int16,
+                    24 => SampleFormat::Int24,
+                    32 => SampleFormat::Float32,
+                    other => return runtime_error!("Expected 16, 24, or 32 bits, found {other}"),
+                };
+                (path, sample_format)
+            }
+            unexpected => {
+                return type_error_with_slice(
+                    "a path String, and an optional number of bits",
+                    unexpected,
+                )
+            }
+        };
+
+        self.save_to_path(Path::new(path.as_str()), sample_format)?;
+        Ok(KValue::Null)
+    }
+
+    #[koto_method]
+    fn slice(&self, args: &[KValue]) -> Result<KValue> {
+        let to_frame = |seconds: &KNumber| {
+            let frame = (f64::from(seconds) * self.sample_rate as f64)
+                .round()
+                .max(0.0) as usize;
+            frame.min(self.frame_count())
+        };
+
+        let (start, end) = match args {
+            [KValue::Number(start)] => (to_frame(start), self.frame_count()),
+            [KValue::Number(start), KValue::Number(end)] => (to_frame(start), to_frame(end)),
+            unexpected => {
+                return type_error_with_slice(
+                    "a start time, and an optional end time in seconds",
+                    unexpected,
+                )
+            }
+        };
+
+        let channels = self.channels as usize;
+        let samples = if start < end {
+            self.samples[start * channels..end * channels].to_vec()
+        } else {
+            Vec::new()
+        };
+        Ok(self.with_samples(samples))
+    }
+
+    #[koto_method]
+    fn to_list(&self) -> KValue {
+        let samples = self
+            .samples
+            .iter()
+            .map(|s| KValue::from(*s as f64))
+            .collect();
+        KList::with_data(samples).into()
+    }
+}
+
+impl KotoObject for Buffer {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!(
+            "Buffer({} frames, {} channel{}, {}Hz)",
+            self.frame_count(),
+            self.channels,
+            if self.channels == 1 { "" } else { "s" },
+            self.sample_rate
+        ));
+        Ok(())
+    }
+
+    fn index(&self, index: &KValue) -> Result<KValue> {
+        match index {
+            KValue::Number(n) => match self.samples.get(usize::from(n)) {
+                Some(sample) if *n >= 0 => Ok((*sample as f64).into()),
+                _ => runtime_error!(
+                    "Index out of bounds - index: {n}, size: {}",
+                    self.samples.len()
+                ),
+            },
+            unexpected => type_error("a Number as index", unexpected),
+        }
+    }
+
+    fn size(&self) -> Option<usize> {
+        Some(self.samples.len())
+    }
+}
+
+impl From<Buffer> for KValue {
+    fn from(buffer: Buffer) -> Self {
+        KObject::from(buffer).into()
+    }
+}
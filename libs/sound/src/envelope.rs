@@ -0,0 +1,62 @@
+use koto_runtime::{derive::*, prelude::*, Result};
+
+/// An ADSR envelope, used to shape the volume of a sound over time
+///
+/// The attack, decay, and release stages are measured in seconds, and the sustain stage holds the
+/// sustain level until the release stage begins at the end of the sound.
+#[derive(Clone, Copy, Debug, KotoType, KotoCopy)]
+pub struct Envelope {
+    pub attack: f64,
+    pub decay: f64,
+    pub sustain: f64,
+    pub release: f64,
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Envelope {
+    /// Returns the envelope's gain at the given time, for a sound with the given duration
+    pub fn gain(&self, time: f64, duration: f64) -> f64 {
+        let level = if time < self.attack {
+            time / self.attack
+        } else if time < self.attack + self.decay {
+            let progress = (time - self.attack) / self.decay;
+            1.0 - (1.0 - self.sustain) * progress
+        } else {
+            self.sustain
+        };
+
+        let release_start = duration - self.release;
+        if time >= release_start && self.release > 0.0 {
+            let remaining = ((duration - time) / self.release).clamp(0.0, 1.0);
+            level * remaining
+        } else {
+            level
+        }
+    }
+
+    #[koto_method]
+    fn gain_at(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Number(time), KValue::Number(duration)] => {
+                Ok(self.gain(time.into(), duration.into()).into())
+            }
+            unexpected => type_error_with_slice("a time and a duration in seconds", unexpected),
+        }
+    }
+}
+
+impl KotoObject for Envelope {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!(
+            "Envelope(attack: {}, decay: {}, sustain: {}, release: {})",
+            self.attack, self.decay, self.sustain, self.release
+        ));
+        Ok(())
+    }
+}
+
+impl From<Envelope> for KValue {
+    fn from(envelope: Envelope) -> Self {
+        KObject::from(envelope).into()
+    }
+}
@@ -0,0 +1,283 @@
+//! A Koto language module for audio synthesis and sample buffers
+//!
+//! Sounds are generated and processed in [Buffer]s, which can be loaded from and saved to WAV
+//! files.
+//!
+//! Playback is left to the host application, which can use a [SoundHost] to pull buffers from a
+//! generator function that's been registered by a script with `sound.set_generator`.
+
+mod buffer;
+mod envelope;
+mod wav;
+
+pub use crate::{buffer::Buffer, envelope::Envelope, wav::SampleFormat};
+
+use koto_runtime::{prelude::*, PtrMut, Result};
+use std::{
+    f64::consts::TAU,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The sample rate that's used when a script doesn't provide one
+pub const DEFAULT_SAMPLE_RATE: u32 = 44100;
+
+/// Makes the `sound` module, with generator functions that can't be used by a host
+///
+/// See [make_module_with_host].
+pub fn make_module() -> KMap {
+    make_module_with_host(&SoundHost::default())
+}
+
+/// Makes the `sound` module, with generator functions registered with the provided host
+pub fn make_module_with_host(host: &SoundHost) -> KMap {
+    let result = KMap::with_type("sound");
+
+    result.add_fn("buffer", |ctx| {
+        let (samples, sample_rate, channels) = match ctx.args() {
+            [samples] if samples.is_iterable() => (samples.clone(), DEFAULT_SAMPLE_RATE, 1),
+            [samples, KValue::Number(sample_rate)] if samples.is_iterable() => {
+                (samples.clone(), positive_u32(sample_rate)?, 1)
+            }
+            [samples, KValue::Number(sample_rate), KValue::Number(channels)]
+                if samples.is_iterable() =>
+            {
+                let channels = match u16::try_from(i64::from(channels)) {
+                    Ok(channels) if channels > 0 => channels,
+                    _ => return runtime_error!("Expected a positive number of channels"),
+                };
+                (samples.clone(), positive_u32(sample_rate)?, channels)
+            }
+            unexpected => {
+                return type_error_with_slice(
+                    "an iterable of samples, and an optional sample rate and number of channels",
+                    unexpected,
+                )
+            }
+        };
+
+        let samples = samples_from_iterable(ctx.vm, samples)?;
+        if samples.len() % channels as usize != 0 {
+            return runtime_error!(
+                "The number of samples ({}) isn't a multiple of the number of channels ({channels})",
+                samples.len()
+            );
+        }
+
+        Ok(Buffer::new(samples, sample_rate, channels).into())
+    });
+
+    result.add_fn("envelope", |ctx| match ctx.args() {
+        [KValue::Number(attack), KValue::Number(decay), KValue::Number(sustain), KValue::Number(release)] =>
+        {
+            let envelope = Envelope {
+                attack: f64::from(attack).max(0.0),
+                decay: f64::from(decay).max(0.0),
+                sustain: f64::from(sustain).clamp(0.0, 1.0),
+                release: f64::from(release).max(0.0),
+            };
+            Ok(envelope.into())
+        }
+        unexpected => type_error_with_slice(
+            "attack, decay, sustain, and release Numbers",
+            unexpected,
+        ),
+    });
+
+    result.add_fn("load", |ctx| match ctx.args() {
+        [KValue::Str(path)] => Buffer::load(Path::new(path.as_str())).map(KValue::from),
+        unexpected => type_error_with_slice("a path String", unexpected),
+    });
+
+    result.add_fn("noise", {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |t| t.as_nanos() as u64);
+        let state = PtrMut::from(seed | 1);
+
+        move |ctx| {
+            let (frames, sample_rate) = duration_args(ctx.args())?;
+            let mut state = state.borrow_mut();
+            let samples = (0..frames)
+                .map(|_| {
+                    // xorshift64
+                    *state ^= *state << 13;
+                    *state ^= *state >> 7;
+                    *state ^= *state << 17;
+                    (*state >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+                })
+                .map(|sample| sample as f32)
+                .collect();
+            Ok(Buffer::new(samples, sample_rate, 1).into())
+        }
+    });
+
+    result.add_fn("set_generator", {
+        let host = host.clone();
+        move |ctx| match ctx.args() {
+            [f] if f.is_callable() => {
+                *host.generator.borrow_mut() = Some(f.clone());
+                Ok(KValue::Null)
+            }
+            [KValue::Null] => {
+                *host.generator.borrow_mut() = None;
+                Ok(KValue::Null)
+            }
+            unexpected => type_error_with_slice("a Function or null", unexpected),
+        }
+    });
+
+    result.add_fn("silence", |ctx| {
+        let (frames, sample_rate) = duration_args(ctx.args())?;
+        Ok(Buffer::new(vec![0.0; frames], sample_rate, 1).into())
+    });
+
+    add_oscillator(&result, "saw", |phase| 2.0 * phase - 1.0);
+    add_oscillator(&result, "sine", |phase| (phase * TAU).sin());
+    add_oscillator(
+        &result,
+        "square",
+        |phase| if phase < 0.5 { 1.0 } else { -1.0 },
+    );
+    add_oscillator(&result, "triangle", |phase| 1.0 - 4.0 * (phase - 0.5).abs());
+
+    result
+}
+
+/// Allows a host application to pull audio from a generator function provided by a script
+///
+/// A script registers its generator with `sound.set_generator`, which is then called each time
+/// that the host needs more audio, e.g. from an audio thread's callback.
+#[derive(Clone)]
+pub struct SoundHost {
+    generator: PtrMut<Option<KValue>>,
+}
+
+impl Default for SoundHost {
+    fn default() -> Self {
+        Self {
+            generator: PtrMut::from(None),
+        }
+    }
+}
+
+impl SoundHost {
+    /// Returns true if a script has registered a generator function
+    pub fn has_generator(&self) -> bool {
+        self.generator.borrow().is_some()
+    }
+
+    /// Fills the output with interleaved samples from the script's generator function
+    ///
+    /// The generator is called with the number of frames that are needed and the sample rate,
+    /// and should return a `Buffer` or a list of samples. Mono audio is copied to each of the
+    /// output's channels, and any frames that aren't provided by the generator are filled with
+    /// silence.
+    ///
+    /// If no generator has been registered then the output is filled with silence.
+    pub fn fill(
+        &self,
+        vm: &mut KotoVm,
+        output: &mut [f32],
+        channels: u16,
+        sample_rate: u32,
+    ) -> Result<()> {
+        output.fill(0.0);
+
+        let Some(generator) = self.generator.borrow().clone() else {
+            return Ok(());
+        };
+
+        let output_channels = channels.max(1) as usize;
+        let frames = output.len() / output_channels;
+        let generated = vm.call_function(generator, &[frames.into(), sample_rate.into()])?;
+
+        let (samples, generated_channels) = match &generated {
+            KValue::Object(o) if o.is_a::<Buffer>() => {
+                let buffer = o.cast::<Buffer>()?;
+                (buffer.samples.clone(), buffer.channels as usize)
+            }
+            samples if samples.is_iterable() => (samples_from_iterable(vm, samples.clone())?, 1),
+            unexpected => return type_error("a Buffer or a List of samples", unexpected),
+        };
+
+        for (frame_index, frame) in output.chunks_exact_mut(output_channels).enumerate() {
+            let generated_frame = frame_index * generated_channels;
+            for (channel, sample) in frame.iter_mut().enumerate() {
+                let source = generated_frame + channel.min(generated_channels - 1);
+                if let Some(generated_sample) = samples.get(source) {
+                    *sample = *generated_sample;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Adds an oscillator function to the module
+//
+// The waveform function takes a phase in the range 0..1 and returns a sample.
+fn add_oscillator(module: &KMap, name: &str, waveform: fn(f64) -> f64) {
+    module.add_fn(name, move |ctx| {
+        let (frequency, rest) = match ctx.args() {
+            [KValue::Number(frequency), rest @ ..] => (f64::from(frequency), rest),
+            unexpected => {
+                return type_error_with_slice(
+                    "a frequency and a duration, and an optional sample rate",
+                    unexpected,
+                )
+            }
+        };
+        let (frames, sample_rate) = duration_args(rest)?;
+
+        let samples = (0..frames)
+            .map(|i| {
+                let phase = (i as f64 * frequency / sample_rate as f64).fract();
+                waveform(phase) as f32
+            })
+            .collect();
+        Ok(Buffer::new(samples, sample_rate, 1).into())
+    });
+}
+
+// Gets a duration in seconds and an optional sample rate from the arguments
+//
+// The number of frames is returned along with the sample rate.
+fn duration_args(args: &[KValue]) -> Result<(usize, u32)> {
+    let (duration, sample_rate) = match args {
+        [KValue::Number(duration)] => (duration, DEFAULT_SAMPLE_RATE),
+        [KValue::Number(duration), KValue::Number(sample_rate)] => {
+            (duration, positive_u32(sample_rate)?)
+        }
+        unexpected => {
+            return type_error_with_slice(
+                "a duration in seconds, and an optional sample rate",
+                unexpected,
+            )
+        }
+    };
+
+    let frames = (f64::from(duration) * sample_rate as f64).round().max(0.0) as usize;
+    Ok((frames, sample_rate))
+}
+
+fn positive_u32(n: &KNumber) -> Result<u32> {
+    match u32::try_from(i64::from(n)) {
+        Ok(n) if n > 0 => Ok(n),
+        _ => runtime_error!("Expected a positive sample rate, found {n}"),
+    }
+}
+
+fn samples_from_iterable(vm: &mut KotoVm, iterable: KValue) -> Result<Vec<f32>> {
+    let mut result = Vec::new();
+    for output in vm.make_iterator(iterable)? {
+        match output {
+            KIteratorOutput::Value(KValue::Number(n)) => result.push(f32::from(n)),
+            KIteratorOutput::Value(unexpected) => return type_error("a Number", &unexpected),
+            KIteratorOutput::ValuePair(..) => return runtime_error!("Expected a Number"),
+            KIteratorOutput::Error(error) => return Err(error),
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,210 @@
+//! Reading and writing of WAV files
+//!
+//! Integer PCM samples with 8, 16, 24, or 32 bits, and 32 bit floating point samples are
+//! supported.
+
+use crate::buffer::Buffer;
+use std::fmt;
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_FLOAT: u16 = 3;
+const FORMAT_EXTENSIBLE: u16 = 0xfffe;
+
+/// The sample formats that can be used when writing WAV data
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SampleFormat {
+    Int16,
+    Int24,
+    Float32,
+}
+
+impl SampleFormat {
+    fn bits(self) -> u16 {
+        match self {
+            Self::Int16 => 16,
+            Self::Int24 => 24,
+            Self::Float32 => 32,
+        }
+    }
+}
+
+/// An error that occurred while reading WAV data
+#[derive(Debug)]
+pub struct WavError(String);
+
+impl fmt::Display for WavError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+fn error<T>(message: impl Into<String>) -> Result<T, WavError> {
+    Err(WavError(message.into()))
+}
+
+/// Decodes WAV data into a buffer
+pub fn decode(bytes: &[u8]) -> Result<Buffer, WavError> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return error("Missing RIFF/WAVE header");
+    }
+
+    let mut format = None;
+    let mut data = None;
+
+    let mut position = 12;
+    while position + 8 <= bytes.len() {
+        let id = &bytes[position..position + 4];
+        let size = read_u32(bytes, position + 4) as usize;
+        let start = position + 8;
+        let end = (start + size).min(bytes.len());
+        let chunk = &bytes[start..end];
+
+        match id {
+            b"fmt " => format = Some(Format::parse(chunk)?),
+            b"data" => data = Some(chunk),
+            _ => {}
+        }
+
+        // Chunks are padded to an even number of bytes
+        position = start + size + size % 2;
+    }
+
+    let Some(format) = format else {
+        return error("Missing 'fmt ' chunk");
+    };
+    let Some(data) = data else {
+        return error("Missing 'data' chunk");
+    };
+
+    let bytes_per_sample = (format.bits / 8) as usize;
+    let samples = data
+        .chunks_exact(bytes_per_sample)
+        .map(|sample| match (format.float, format.bits) {
+            (true, 32) => f32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]]),
+            // 8 bit samples are unsigned
+            (false, 8) => (sample[0] as f32 - 128.0) / 128.0,
+            (false, 16) => i16::from_le_bytes([sample[0], sample[1]]) as f32 / 32768.0,
+            (false, 24) => {
+                // Shift the 3 bytes into the top of an i32 to preserve the sign
+                let value = i32::from_le_bytes([0, sample[0], sample[1], sample[2]]) >> 8;
+                value as f32 / 8_388_608.0
+            }
+            (false, 32) => {
+                let value = i32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]]);
+                value as f32 / 2_147_483_648.0
+            }
+            _ => unreachable!(),
+        })
+        .collect();
+
+    Ok(Buffer::new(samples, format.sample_rate, format.channels))
+}
+
+/// Encodes a buffer as WAV data
+pub fn encode(buffer: &Buffer, sample_format: SampleFormat) -> Vec<u8> {
+    let bits = sample_format.bits();
+    let bytes_per_sample = bits as u32 / 8;
+    let channels = buffer.channels;
+    let sample_rate = buffer.sample_rate;
+    let data_size = buffer.samples.len() as u32 * bytes_per_sample;
+    let format_tag = match sample_format {
+        SampleFormat::Float32 => FORMAT_FLOAT,
+        _ => FORMAT_PCM,
+    };
+
+    let mut result = Vec::with_capacity(44 + data_size as usize);
+    result.extend_from_slice(b"RIFF");
+    result.extend_from_slice(&(36 + data_size).to_le_bytes());
+    result.extend_from_slice(b"WAVE");
+
+    result.extend_from_slice(b"fmt ");
+    result.extend_from_slice(&16u32.to_le_bytes());
+    result.extend_from_slice(&format_tag.to_le_bytes());
+    result.extend_from_slice(&channels.to_le_bytes());
+    result.extend_from_slice(&sample_rate.to_le_bytes());
+    result.extend_from_slice(&(sample_rate * channels as u32 * bytes_per_sample).to_le_bytes());
+    result.extend_from_slice(&(channels * bytes_per_sample as u16).to_le_bytes());
+    result.extend_from_slice(&bits.to_le_bytes());
+
+    result.extend_from_slice(b"data");
+    result.extend_from_slice(&data_size.to_le_bytes());
+    for sample in buffer.samples.iter() {
+        let sample = sample.clamp(-1.0, 1.0);
+        match sample_format {
+            SampleFormat::Int16 => {
+                let value = (sample * i16::MAX as f32).round() as i16;
+                result.extend_from_slice(&value.to_le_bytes());
+            }
+            SampleFormat::Int24 => {
+                let value = (sample * 8_388_607.0).round() as i32;
+                result.extend_from_slice(&value.to_le_bytes()[0..3]);
+            }
+            SampleFormat::Float32 => result.extend_from_slice(&sample.to_le_bytes()),
+        }
+    }
+
+    result
+}
+
+struct Format {
+    channels: u16,
+    sample_rate: u32,
+    bits: u16,
+    float: bool,
+}
+
+impl Format {
+    fn parse(chunk: &[u8]) -> Result<Self, WavError> {
+        if chunk.len() < 16 {
+            return error("Invalid 'fmt ' chunk");
+        }
+
+        let mut format_tag = read_u16(chunk, 0);
+        let channels = read_u16(chunk, 2);
+        let sample_rate = read_u32(chunk, 4);
+        let bits = read_u16(chunk, 14);
+
+        // The extensible format stores the actual format tag in the first two bytes of the
+        // sub-format GUID
+        if format_tag == FORMAT_EXTENSIBLE {
+            if chunk.len() < 26 {
+                return error("Invalid extensible 'fmt ' chunk");
+            }
+            format_tag = read_u16(chunk, 24);
+        }
+
+        let float = match (format_tag, bits) {
+            (FORMAT_PCM, 8 | 16 | 24 | 32) => false,
+            (FORMAT_FLOAT, 32) => true,
+            _ => {
+                return error(format!(
+                    "Unsupported sample format (format tag: {format_tag}, bits: {bits})"
+                ))
+            }
+        };
+
+        if channels == 0 || sample_rate == 0 {
+            return error("Invalid channel count or sample rate");
+        }
+
+        Ok(Self {
+            channels,
+            sample_rate,
+            bits,
+            float,
+        })
+    }
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
@@ -0,0 +1,71 @@
+use koto_runtime::{prelude::*, Result};
+use koto_sound::{make_module_with_host, SoundHost};
+use koto_test_utils::check_script_output_with_vm;
+
+fn make_vm(host: &SoundHost) -> KotoVm {
+    let vm = KotoVm::default();
+    vm.prelude().insert("sound", make_module_with_host(host));
+    vm
+}
+
+#[test]
+fn fill_without_generator() -> Result<()> {
+    let host = SoundHost::default();
+    let mut vm = make_vm(&host);
+
+    let mut output = [1.0; 4];
+    host.fill(&mut vm, &mut output, 1, 44100)?;
+
+    assert!(!host.has_generator());
+    assert_eq!(output, [0.0; 4]);
+    Ok(())
+}
+
+#[test]
+fn fill_from_buffer_generator() -> Result<()> {
+    let host = SoundHost::default();
+    let mut vm = make_vm(&host);
+
+    let script = "
+sound.set_generator |frames, sample_rate|
+  sound.buffer (1..=frames).each(|n| n / 10), sample_rate
+";
+    check_script_output_with_vm(vm.spawn_shared_vm(), script, KValue::Null)?;
+    assert!(host.has_generator());
+
+    // Mono buffers are copied to each output channel
+    let mut output = [0.0; 6];
+    host.fill(&mut vm, &mut output, 2, 44100)?;
+    assert_eq!(output, [0.1, 0.1, 0.2, 0.2, 0.3, 0.3]);
+    Ok(())
+}
+
+#[test]
+fn fill_from_list_generator_with_missing_frames() -> Result<()> {
+    let host = SoundHost::default();
+    let mut vm = make_vm(&host);
+
+    let script = "
+sound.set_generator |_frames, _sample_rate| [0.5, -0.5]
+";
+    check_script_output_with_vm(vm.spawn_shared_vm(), script, KValue::Null)?;
+
+    let mut output = [1.0; 4];
+    host.fill(&mut vm, &mut output, 1, 44100)?;
+    assert_eq!(output, [0.5, -0.5, 0.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn clear_generator() -> Result<()> {
+    let host = SoundHost::default();
+    let vm = make_vm(&host);
+
+    let script = "
+sound.set_generator |_, _| [1]
+sound.set_generator null
+";
+    check_script_output_with_vm(vm, script, KValue::Null)?;
+    assert!(!host.has_generator());
+    Ok(())
+}
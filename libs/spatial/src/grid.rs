@@ -0,0 +1,297 @@
+use koto_geometry::{Circle, Shape, Vec2};
+use koto_runtime::{derive::*, prelude::*, PtrMut, Result};
+use std::collections::{HashMap, HashSet};
+
+// The types accepted as item bounds and query regions, used in error messages
+const SHAPE_TYPES: &str = "a Vec2, Rect, Circle, Segment, or Polygon";
+
+/// A spatial hash that divides the plane into square cells, used for fast region queries
+///
+/// Items are inserted with a key and a geometry shape, and each item is stored in the cells that
+/// are overlapped by the shape's bounds. Queries then only need to test the items in the cells
+/// that are overlapped by the query region.
+#[derive(Clone, KotoType)]
+#[koto(type_name = "Grid")]
+pub struct Grid(PtrMut<GridState>);
+
+#[derive(Clone)]
+struct GridState {
+    cell_size: f64,
+    // Items are stored by id, with ids increasing in insertion order
+    items: HashMap<usize, Item>,
+    ids: HashMap<ValueKey, usize>,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+    next_id: usize,
+}
+
+#[derive(Clone)]
+struct Item {
+    key: KValue,
+    shape: Shape,
+    cells: CellRange,
+}
+
+// An inclusive range of cells, covering the bounds of a shape
+#[derive(Clone, Copy)]
+struct CellRange {
+    min: (i64, i64),
+    max: (i64, i64),
+}
+
+impl CellRange {
+    fn cells(self) -> impl Iterator<Item = (i64, i64)> {
+        (self.min.0..=self.max.0).flat_map(move |x| (self.min.1..=self.max.1).map(move |y| (x, y)))
+    }
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Grid {
+    /// Makes a new grid with the given cell size
+    ///
+    /// The cell size should be roughly the size of a typical item, or of a typical query region.
+    pub fn new(cell_size: f64) -> Result<Self> {
+        if !(cell_size > 0.0 && cell_size.is_finite()) {
+            return runtime_error!("Expected a positive cell size, found {cell_size}");
+        }
+
+        Ok(Self(PtrMut::from(GridState {
+            cell_size,
+            items: HashMap::new(),
+            ids: HashMap::new(),
+            cells: HashMap::new(),
+            next_id: 0,
+        })))
+    }
+
+    /// Inserts an item, replacing any existing item with the same key
+    pub fn insert_item(&self, key: KValue, shape: Shape) -> Result<()> {
+        let value_key = ValueKey::try_from(key.clone())?;
+        self.remove_item(&value_key);
+
+        let mut state = self.0.borrow_mut();
+        let id = state.next_id;
+        state.next_id += 1;
+
+        let cells = state.cell_range(&shape);
+        for cell in cells.cells() {
+            state.cells.entry(cell).or_default().push(id);
+        }
+        state.ids.insert(value_key, id);
+        state.items.insert(id, Item { key, shape, cells });
+        Ok(())
+    }
+
+    /// Removes the item with the given key, returning true if the item was in the grid
+    pub fn remove_item(&self, key: &ValueKey) -> bool {
+        let mut state = self.0.borrow_mut();
+        let Some(id) = state.ids.remove(key) else {
+            return false;
+        };
+        let Some(item) = state.items.remove(&id) else {
+            return false;
+        };
+
+        for cell in item.cells.cells() {
+            if let Some(ids) = state.cells.get_mut(&cell) {
+                ids.retain(|cell_id| *cell_id != id);
+                if ids.is_empty() {
+                    state.cells.remove(&cell);
+                }
+            }
+        }
+        true
+    }
+
+    /// Returns the keys of the items with shapes that intersect the region, in insertion order
+    pub fn query_region(&self, region: &Shape) -> Vec<KValue> {
+        let state = self.0.borrow();
+
+        let mut candidates: Vec<usize> = state
+            .cell_range(region)
+            .cells()
+            .filter_map(|cell| state.cells.get(&cell))
+            .flatten()
+            .copied()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        candidates.sort_unstable();
+
+        candidates
+            .into_iter()
+            .filter_map(|id| state.items.get(&id))
+            .filter(|item| item.shape.intersects(region))
+            .map(|item| item.key.clone())
+            .collect()
+    }
+
+    /// Returns the pairs of keys for items with shapes that intersect each other
+    ///
+    /// The pairs are sorted by insertion order, with the earlier inserted item first in each pair.
+    pub fn intersecting_pairs(&self) -> Vec<(KValue, KValue)> {
+        let state = self.0.borrow();
+
+        let mut pairs = HashSet::new();
+        for ids in state.cells.values() {
+            for (i, a) in ids.iter().enumerate() {
+                for b in &ids[i + 1..] {
+                    pairs.insert((*a.min(b), *a.max(b)));
+                }
+            }
+        }
+        let mut pairs: Vec<_> = pairs.into_iter().collect();
+        pairs.sort_unstable();
+
+        pairs
+            .into_iter()
+            .filter_map(|(a, b)| {
+                let a = state.items.get(&a)?;
+                let b = state.items.get(&b)?;
+                a.shape
+                    .intersects(&b.shape)
+                    .then(|| (a.key.clone(), b.key.clone()))
+            })
+            .collect()
+    }
+
+    /// Returns the number of items in the grid
+    pub fn len(&self) -> usize {
+        self.0.borrow().items.len()
+    }
+
+    /// Returns true if the grid contains no items
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[koto_method]
+    fn cell_size(&self) -> KValue {
+        self.0.borrow().cell_size.into()
+    }
+
+    #[koto_method]
+    fn clear(ctx: MethodContext<Self>) -> Result<KValue> {
+        {
+            let this = ctx.instance()?;
+            let mut state = this.0.borrow_mut();
+            state.items.clear();
+            state.ids.clear();
+            state.cells.clear();
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn contains(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [key] => {
+                let key = ValueKey::try_from(key.clone())?;
+                Ok(self.0.borrow().ids.contains_key(&key).into())
+            }
+            unexpected => type_error_with_slice("a key", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn insert(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [key, shape] => match Shape::from_value(shape) {
+                Some(shape) => ctx.instance()?.insert_item(key.clone(), shape)?,
+                None => return type_error(SHAPE_TYPES, shape),
+            },
+            unexpected => return type_error_with_slice("a key and a shape", unexpected),
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn keys(&self) -> KValue {
+        let state = self.0.borrow();
+        let mut items: Vec<_> = state.items.iter().collect();
+        items.sort_unstable_by_key(|(id, _)| **id);
+        let keys: Vec<KValue> = items
+            .into_iter()
+            .map(|(_, item)| item.key.clone())
+            .collect();
+        KList::with_data(keys.into()).into()
+    }
+
+    #[koto_method]
+    fn pairs(&self) -> KValue {
+        let pairs: Vec<KValue> = self
+            .intersecting_pairs()
+            .into_iter()
+            .map(|(a, b)| KValue::Tuple(vec![a, b].into()))
+            .collect();
+        KList::with_data(pairs.into()).into()
+    }
+
+    #[koto_method]
+    fn query(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [region] => match Shape::from_value(region) {
+                Some(region) => Ok(KList::with_data(self.query_region(&region).into()).into()),
+                None => type_error(SHAPE_TYPES, region),
+            },
+            unexpected => type_error_with_slice(SHAPE_TYPES, unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn query_radius(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Object(center), KValue::Number(radius)] if center.is_a::<Vec2>() => {
+                let center = center.cast::<Vec2>().unwrap().inner();
+                let region = Shape::Circle(Circle::new(center.x, center.y, radius.into()));
+                Ok(KList::with_data(self.query_region(&region).into()).into())
+            }
+            unexpected => type_error_with_slice("a Vec2 and a radius", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn remove(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [key] => {
+                let key = ValueKey::try_from(key.clone())?;
+                Ok(self.remove_item(&key).into())
+            }
+            unexpected => type_error_with_slice("a key", unexpected),
+        }
+    }
+}
+
+impl GridState {
+    fn cell_range(&self, shape: &Shape) -> CellRange {
+        let bounds = shape.bounds().inner();
+        let cell = |x: f64| (x / self.cell_size).floor() as i64;
+        CellRange {
+            min: (cell(bounds.left()), cell(bounds.bottom())),
+            max: (cell(bounds.right()), cell(bounds.top())),
+        }
+    }
+}
+
+impl KotoCopy for Grid {
+    fn copy(&self) -> KObject {
+        // Copies get their own items
+        Self(PtrMut::from(self.0.borrow().clone())).into()
+    }
+}
+
+impl KotoObject for Grid {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!("Grid({} items)", self.len()));
+        Ok(())
+    }
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl From<Grid> for KValue {
+    fn from(grid: Grid) -> Self {
+        KObject::from(grid).into()
+    }
+}
@@ -0,0 +1,18 @@
+//! A Koto language module for fast spatial queries
+
+mod grid;
+
+pub use grid::Grid;
+
+use koto_runtime::prelude::*;
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("spatial");
+
+    result.add_fn("grid", |ctx| match ctx.args() {
+        [KValue::Number(cell_size)] => Ok(Grid::new(cell_size.into())?.into()),
+        unexpected => type_error_with_slice("a cell size Number", unexpected),
+    });
+
+    result
+}
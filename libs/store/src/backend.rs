@@ -0,0 +1,151 @@
+use fd_lock::RwLock;
+use koto_runtime::{prelude::*, KotoSend, KotoSync, Result};
+use serde_json::Value as JsonValue;
+use std::{
+    collections::BTreeMap,
+    fs::{self, File, OpenOptions},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// The entries in a store, with values serialized as JSON
+pub type StoreEntries = BTreeMap<String, JsonValue>;
+
+/// The storage used by a [crate::Store]
+///
+/// Backends are shared by all of the scripts that use the store, so changes need to be made
+/// atomically with [StoreBackend::transaction].
+pub trait StoreBackend: KotoSend + KotoSync {
+    /// Returns the store's entries
+    fn read(&self) -> Result<StoreEntries>;
+
+    /// Reads the store's entries, modifies them with the provided function, and then writes them
+    ///
+    /// The store must not be modified by other scripts while the transaction is in progress.
+    /// If the function returns an error then the store is left unmodified.
+    fn transaction(&self, f: &mut dyn FnMut(&mut StoreEntries) -> Result<()>) -> Result<()>;
+}
+
+/// A backend that keeps its entries in a JSON file
+///
+/// Transactions are serialized between processes with an advisory lock on a `.lock` file that's
+/// placed next to the store's file, and the file is replaced atomically when it's written.
+pub struct FileBackend {
+    path: PathBuf,
+}
+
+impl FileBackend {
+    /// Creates a backend that stores its entries in the file at the given path
+    ///
+    /// The file is created when the store is first modified.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// The path of the store's file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn lock_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".lock");
+        path.into()
+    }
+
+    fn write(&self, entries: &StoreEntries) -> Result<()> {
+        let contents = match serde_json::to_string_pretty(entries) {
+            Ok(contents) => contents,
+            Err(e) => return runtime_error!("failed to serialize the store: {e}"),
+        };
+
+        // The entries are written to a temporary file which then replaces the store's file,
+        // so that readers never see a partially written store.
+        let mut temp_path = self.path.clone().into_os_string();
+        temp_path.push(".tmp");
+        let temp_path = PathBuf::from(temp_path);
+        if let Err(e) = fs::write(&temp_path, contents) {
+            return runtime_error!("failed to write '{}': {e}", temp_path.display());
+        }
+        match fs::rename(&temp_path, &self.path) {
+            Ok(()) => Ok(()),
+            Err(e) => runtime_error!("failed to write '{}': {e}", self.path.display()),
+        }
+    }
+}
+
+impl StoreBackend for FileBackend {
+    fn read(&self) -> Result<StoreEntries> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(StoreEntries::new()),
+            Err(e) => return runtime_error!("failed to read '{}': {e}", self.path.display()),
+        };
+        if contents.trim().is_empty() {
+            return Ok(StoreEntries::new());
+        }
+        match serde_json::from_str(&contents) {
+            Ok(entries) => Ok(entries),
+            Err(e) => runtime_error!("failed to parse '{}': {e}", self.path.display()),
+        }
+    }
+
+    fn transaction(&self, f: &mut dyn FnMut(&mut StoreEntries) -> Result<()>) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            if !parent.as_os_str().is_empty() {
+                if let Err(e) = fs::create_dir_all(parent) {
+                    return runtime_error!("failed to create '{}': {e}", parent.display());
+                }
+            }
+        }
+
+        let lock_path = self.lock_path();
+        let lock_file: File = match OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+        {
+            Ok(file) => file,
+            Err(e) => return runtime_error!("failed to open '{}': {e}", lock_path.display()),
+        };
+        let mut lock = RwLock::new(lock_file);
+        let _guard = match lock.write() {
+            Ok(guard) => guard,
+            Err(e) => return runtime_error!("failed to lock '{}': {e}", lock_path.display()),
+        };
+
+        let mut entries = self.read()?;
+        f(&mut entries)?;
+        self.write(&entries)
+    }
+}
+
+/// A backend that keeps its entries in memory
+///
+/// Entries are shared by the stores that share the backend, but not between processes.
+#[derive(Default)]
+pub struct MemoryBackend {
+    entries: Mutex<StoreEntries>,
+}
+
+impl StoreBackend for MemoryBackend {
+    fn read(&self) -> Result<StoreEntries> {
+        match self.entries.lock() {
+            Ok(entries) => Ok(entries.clone()),
+            Err(_) => runtime_error!("the store's lock is poisoned"),
+        }
+    }
+
+    fn transaction(&self, f: &mut dyn FnMut(&mut StoreEntries) -> Result<()>) -> Result<()> {
+        let mut entries = match self.entries.lock() {
+            Ok(entries) => entries,
+            Err(_) => return runtime_error!("the store's lock is poisoned"),
+        };
+        // The function modifies a copy of the entries so that errors leave the store unmodified
+        let mut modified = entries.clone();
+        f(&mut modified)?;
+        *entries = modified;
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+//! A Koto language module for sharing state between scripts
+
+mod backend;
+mod store;
+
+pub use backend::{FileBackend, MemoryBackend, StoreBackend, StoreEntries};
+pub use store::Store;
+
+use koto_runtime::{prelude::*, Ptr};
+use std::{env, path::PathBuf};
+
+/// Makes the module, using a file backend at the default path
+///
+/// The default path is taken from the `KOTO_STORE_PATH` environment variable,
+/// falling back to `$HOME/.koto/store.json`.
+pub fn make_module() -> KMap {
+    make_module_with_backend(make_ptr!(FileBackend::new(default_store_path())))
+}
+
+/// Makes the module with the given backend
+///
+/// The module's functions operate on a store that uses the backend, allowing the host to decide
+/// where the shared state is kept.
+pub fn make_module_with_backend(backend: Ptr<dyn StoreBackend>) -> KMap {
+    let result = KMap::with_type("store");
+
+    // The module's functions are forwarded to the default store's methods
+    let store = KValue::from(Store::new(backend));
+    if let KValue::Object(o) = &store {
+        if let Some(methods) = o.try_borrow().ok().and_then(|o| o.entries()) {
+            for (name, method) in methods.data().iter() {
+                let (store, method) = (store.clone(), method.clone());
+                result.add_fn(&name.to_string(), move |ctx| {
+                    let args = ctx.args().to_vec();
+                    ctx.vm
+                        .call_instance_function(store.clone(), method.clone(), args.as_slice())
+                });
+            }
+        }
+    }
+
+    result.add_fn("memory", |ctx| match ctx.args() {
+        [] => Ok(Store::new(make_ptr!(MemoryBackend::default())).into()),
+        unexpected => type_error_with_slice("no arguments", unexpected),
+    });
+
+    result.add_fn("open", |ctx| match ctx.args() {
+        [KValue::Str(path)] => Ok(Store::new(make_ptr!(FileBackend::new(path.as_str()))).into()),
+        unexpected => type_error_with_slice("a path String", unexpected),
+    });
+
+    result
+}
+
+fn default_store_path() -> PathBuf {
+    if let Some(path) = env::var_os("KOTO_STORE_PATH") {
+        return path.into();
+    }
+
+    let mut path = home::home_dir().unwrap_or_else(env::temp_dir);
+    path.push(".koto");
+    path.push("store.json");
+    path
+}
@@ -0,0 +1,318 @@
+use crate::backend::{StoreBackend, StoreEntries};
+use koto_json::json_value_to_koto_value;
+use koto_runtime::{derive::*, prelude::*, Ptr, PtrMut, Result};
+use koto_serialize::SerializableValue;
+use serde_json::Value as JsonValue;
+
+/// A key/value store that can be shared between scripts
+///
+/// Values are serialized as JSON when they're added to the store, so only null, bools, numbers,
+/// strings, lists, tuples, and maps can be stored.
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "Store")]
+pub struct Store(PtrMut<StoreState>);
+
+struct StoreState {
+    backend: Ptr<dyn StoreBackend>,
+    watches: Vec<Watch>,
+    next_watch_id: i64,
+    // True while an update function is being called
+    updating: bool,
+}
+
+struct Watch {
+    id: i64,
+    key: String,
+    callback: KValue,
+    // The most recently seen value for the watched key
+    last_value: Option<JsonValue>,
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Store {
+    /// Creates a new store with the given backend
+    pub fn new(backend: Ptr<dyn StoreBackend>) -> Self {
+        Self(PtrMut::from(StoreState {
+            backend,
+            watches: Vec::new(),
+            next_watch_id: 1,
+            updating: false,
+        }))
+    }
+
+    /// Returns the value for the given key, or `None` if the key isn't in the store
+    pub fn get_value(&self, key: &str) -> Result<Option<KValue>> {
+        self.check_not_updating()?;
+        let entries = self.backend().read()?;
+        entries.get(key).map(json_value_to_koto_value).transpose()
+    }
+
+    /// Sets the value for the given key, calling any watchers if the value has changed
+    ///
+    /// Setting a value of `null` removes the key from the store.
+    pub fn set_value(&self, vm: &mut KotoVm, key: &str, value: &KValue) -> Result<()> {
+        self.check_not_updating()?;
+        let value = to_json(value)?;
+        self.backend().transaction(&mut |entries| {
+            set_entry(entries, key, value.clone());
+            Ok(())
+        })?;
+        self.notify_watchers(vm, key, value).map(|_| ())
+    }
+
+    /// Atomically updates the value for the given key
+    ///
+    /// The function is called with the key's current value (or `null` if the key isn't in the
+    /// store), and the function's result is stored as the key's new value.
+    ///
+    /// The store is locked while the function is being called, so other scripts are unable to
+    /// modify the store until the update is complete.
+    pub fn update_value(&self, vm: &mut KotoVm, key: &str, f: KValue) -> Result<KValue> {
+        self.check_not_updating()?;
+        self.0.borrow_mut().updating = true;
+
+        let mut new_value = JsonValue::Null;
+        let mut result = KValue::Null;
+        let transaction = self.backend().transaction(&mut |entries| {
+            let old_value = match entries.get(key) {
+                Some(value) => json_value_to_koto_value(value)?,
+                None => KValue::Null,
+            };
+            result = vm.call_function(f.clone(), old_value)?;
+            new_value = to_json(&result)?;
+            set_entry(entries, key, new_value.clone());
+            Ok(())
+        });
+
+        self.0.borrow_mut().updating = false;
+        transaction?;
+
+        self.notify_watchers(vm, key, new_value)?;
+        Ok(result)
+    }
+
+    /// Registers a function that's called when the value for the given key changes
+    ///
+    /// Returns an ID that can be used to remove the watch with [Self::remove_watch].
+    pub fn add_watch(&self, key: &str, callback: KValue) -> Result<i64> {
+        self.check_not_updating()?;
+        let last_value = self.backend().read()?.get(key).cloned();
+        let mut state = self.0.borrow_mut();
+        let id = state.next_watch_id;
+        state.next_watch_id += 1;
+        state.watches.push(Watch {
+            id,
+            key: key.into(),
+            callback,
+            last_value,
+        });
+        Ok(id)
+    }
+
+    /// Removes the watch with the given ID, returning true if the watch was removed
+    pub fn remove_watch(&self, id: i64) -> bool {
+        let mut state = self.0.borrow_mut();
+        let count = state.watches.len();
+        state.watches.retain(|watch| watch.id != id);
+        state.watches.len() != count
+    }
+
+    /// Checks the store for changes to watched keys, including changes made by other scripts
+    ///
+    /// Returns the number of watch functions that were called.
+    pub fn poll_watches(&self, vm: &mut KotoVm) -> Result<usize> {
+        self.check_not_updating()?;
+        let entries = self.backend().read()?;
+        let keys: Vec<String> = {
+            let state = self.0.borrow();
+            let mut keys: Vec<String> = state.watches.iter().map(|w| w.key.clone()).collect();
+            keys.sort();
+            keys.dedup();
+            keys
+        };
+
+        let mut called = 0;
+        for key in keys {
+            let value = entries.get(&key).cloned().unwrap_or(JsonValue::Null);
+            called += self.notify_watchers(vm, &key, value)?;
+        }
+        Ok(called)
+    }
+
+    fn backend(&self) -> Ptr<dyn StoreBackend> {
+        self.0.borrow().backend.clone()
+    }
+
+    fn check_not_updating(&self) -> Result<()> {
+        if self.0.borrow().updating {
+            runtime_error!("the store can't be used while an update is in progress")
+        } else {
+            Ok(())
+        }
+    }
+
+    // Calls the watchers for the key if the value has changed since they last saw it
+    fn notify_watchers(&self, vm: &mut KotoVm, key: &str, value: JsonValue) -> Result<usize> {
+        let new_value = (!value.is_null()).then_some(value);
+
+        // The callbacks are collected before being called so that the store isn't borrowed
+        // while the callbacks are running.
+        let changed: Vec<(KValue, Option<JsonValue>)> = {
+            let mut state = self.0.borrow_mut();
+            state
+                .watches
+                .iter_mut()
+                .filter(|watch| watch.key == key && watch.last_value != new_value)
+                .map(|watch| {
+                    let old_value = std::mem::replace(&mut watch.last_value, new_value.clone());
+                    (watch.callback.clone(), old_value)
+                })
+                .collect()
+        };
+
+        let new_koto_value = match &new_value {
+            Some(value) => json_value_to_koto_value(value)?,
+            None => KValue::Null,
+        };
+        for (callback, old_value) in changed.iter() {
+            let old_value = match old_value {
+                Some(value) => json_value_to_koto_value(value)?,
+                None => KValue::Null,
+            };
+            vm.call_function(callback.clone(), &[new_koto_value.clone(), old_value])?;
+        }
+
+        Ok(changed.len())
+    }
+
+    #[koto_method]
+    fn get(&self, args: &[KValue]) -> Result<KValue> {
+        let (key, default) = match args {
+            [KValue::Str(key)] => (key, KValue::Null),
+            [KValue::Str(key), default] => (key, default.clone()),
+            unexpected => {
+                return type_error_with_slice("a String as key, with optional default", unexpected)
+            }
+        };
+        Ok(self.get_value(key)?.unwrap_or(default))
+    }
+
+    #[koto_method]
+    fn contains(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Str(key)] => Ok(self.get_value(key)?.is_some().into()),
+            unexpected => type_error_with_slice("a String as key", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn keys(&self) -> Result<KValue> {
+        self.check_not_updating()?;
+        let keys = self
+            .backend()
+            .read()?
+            .into_keys()
+            .map(|key| KValue::from(key.as_str()))
+            .collect::<Vec<_>>();
+        Ok(KValue::Tuple(keys.into()))
+    }
+
+    #[koto_method]
+    fn poll(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [] => {
+                let this = ctx.instance()?.clone();
+                let called = this.poll_watches(&mut ctx.vm.spawn_shared_vm())?;
+                Ok(KValue::Number((called as i64).into()))
+            }
+            unexpected => type_error_with_slice("no arguments", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn remove(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [KValue::Str(key)] => {
+                let key = key.clone();
+                let this = ctx.instance()?.clone();
+                let previous = this.get_value(&key)?;
+                this.set_value(&mut ctx.vm.spawn_shared_vm(), &key, &KValue::Null)?;
+                Ok(previous.unwrap_or_default())
+            }
+            unexpected => type_error_with_slice("a String as key", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn set(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [KValue::Str(key), value] => {
+                let (key, value) = (key.clone(), value.clone());
+                let this = ctx.instance()?.clone();
+                this.set_value(&mut ctx.vm.spawn_shared_vm(), &key, &value)?;
+                Ok(value)
+            }
+            unexpected => type_error_with_slice("a String as key, and a value", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn unwatch(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Number(id)] => Ok(self.remove_watch(id.into()).into()),
+            unexpected => type_error_with_slice("a watch ID", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn update(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [KValue::Str(key), f] if f.is_callable() => {
+                let (key, f) = (key.clone(), f.clone());
+                let this = ctx.instance()?.clone();
+                this.update_value(&mut ctx.vm.spawn_shared_vm(), &key, f)
+            }
+            unexpected => type_error_with_slice("a String as key, and a Function", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn watch(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Str(key), f] if f.is_callable() => {
+                let id = self.add_watch(key, f.clone())?;
+                Ok(KValue::Number(id.into()))
+            }
+            unexpected => type_error_with_slice("a String as key, and a Function", unexpected),
+        }
+    }
+}
+
+impl KotoObject for Store {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append("Store");
+        Ok(())
+    }
+}
+
+impl From<Store> for KValue {
+    fn from(store: Store) -> Self {
+        KObject::from(store).into()
+    }
+}
+
+fn to_json(value: &KValue) -> Result<JsonValue> {
+    match serde_json::to_value(SerializableValue(value)) {
+        Ok(value) => Ok(value),
+        Err(e) => runtime_error!("failed to serialize value: {e}"),
+    }
+}
+
+// Null values remove the key from the store
+fn set_entry(entries: &mut StoreEntries, key: &str, value: JsonValue) {
+    if value.is_null() {
+        entries.remove(key);
+    } else {
+        entries.insert(key.into(), value);
+    }
+}
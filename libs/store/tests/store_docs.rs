@@ -0,0 +1,19 @@
+use koto_runtime::{prelude::*, Ptr, Result};
+use koto_store::MemoryBackend;
+use koto_test_utils::run_koto_examples_in_markdown;
+
+#[test]
+fn store_docs() -> Result<()> {
+    let mut prelude_entries = ValueMap::default();
+    // The examples use a memory backend for the default store to avoid writing to the user's store
+    prelude_entries.insert(
+        "store".into(),
+        koto_store::make_module_with_backend(make_ptr!(MemoryBackend::default())).into(),
+    );
+    prelude_entries.insert("tempfile".into(), koto_tempfile::make_module().into());
+    let markdown = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../docs/libs/store.md"
+    ));
+    run_koto_examples_in_markdown(markdown, prelude_entries)
+}
@@ -0,0 +1,93 @@
+use koto_runtime::{prelude::*, Ptr, Result};
+use koto_store::{make_module_with_backend, FileBackend, StoreBackend, StoreEntries};
+use koto_test_utils::check_script_output_with_vm;
+use std::{
+    env, fs,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+// A backend that records the number of transactions that have been run
+#[derive(Clone, Default)]
+struct CountingBackend {
+    entries: Arc<Mutex<StoreEntries>>,
+    transactions: Arc<Mutex<usize>>,
+}
+
+impl StoreBackend for CountingBackend {
+    fn read(&self) -> Result<StoreEntries> {
+        Ok(self.entries.lock().unwrap().clone())
+    }
+
+    fn transaction(&self, f: &mut dyn FnMut(&mut StoreEntries) -> Result<()>) -> Result<()> {
+        *self.transactions.lock().unwrap() += 1;
+        let mut entries = self.read()?;
+        f(&mut entries)?;
+        *self.entries.lock().unwrap() = entries;
+        Ok(())
+    }
+}
+
+fn run_script(backend: Ptr<dyn StoreBackend>, script: &str, expected: KValue) -> Result<()> {
+    let vm = KotoVm::default();
+    vm.prelude()
+        .insert("store", make_module_with_backend(backend));
+    check_script_output_with_vm(vm, script, expected)
+}
+
+#[test]
+fn custom_backend() -> Result<()> {
+    let backend = CountingBackend::default();
+    let script = "
+store.set 'x', 1
+store.update 'x', |x| x + 1
+store.get 'x'
+";
+    run_script(make_ptr!(backend.clone()), script, 2.into())?;
+
+    assert_eq!(*backend.transactions.lock().unwrap(), 2);
+    assert_eq!(
+        backend.entries.lock().unwrap().get("x"),
+        Some(&serde_json::json!(2))
+    );
+    Ok(())
+}
+
+#[test]
+fn concurrent_file_updates() -> Result<()> {
+    let path = env::temp_dir().join(format!("koto_store_test_{}.json", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    const THREADS: usize = 4;
+    const UPDATES: usize = 25;
+
+    let threads = (0..THREADS)
+        .map(|_| {
+            let path = path.clone();
+            thread::spawn(move || {
+                let script = format!(
+                    "
+for _ in 0..{UPDATES}
+  store.update 'count', |n| (n or 0) + 1
+null
+"
+                );
+                run_script(make_ptr!(FileBackend::new(path)), &script, KValue::Null)
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for thread in threads {
+        thread.join().unwrap()?;
+    }
+
+    let entries = FileBackend::new(&path).read()?;
+    assert_eq!(
+        entries.get("count"),
+        Some(&serde_json::json!(THREADS * UPDATES))
+    );
+
+    let _ = fs::remove_file(&path);
+    let _ = fs::remove_file(path.with_extension("json.lock"));
+    Ok(())
+}
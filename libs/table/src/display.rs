@@ -0,0 +1,109 @@
+use crate::table::TableData;
+use koto_runtime::{prelude::*, Result};
+use unicode_width::UnicodeWidthStr;
+
+// Tables with more rows than this are displayed with rows omitted from the middle
+const MAX_DISPLAY_ROWS: usize = 20;
+
+/// Displays a table with aligned columns
+///
+/// Numbers are aligned to the right of their column, with other values aligned to the left.
+pub fn display_table(data: &TableData, ctx: &mut DisplayContext) -> Result<()> {
+    let row_count = data.row_count();
+
+    let rows: Vec<Option<usize>> = if row_count > MAX_DISPLAY_ROWS {
+        let half = MAX_DISPLAY_ROWS / 2;
+        (0..half)
+            .map(Some)
+            .chain(std::iter::once(None))
+            .chain((row_count - half..row_count).map(Some))
+            .collect()
+    } else {
+        (0..row_count).map(Some).collect()
+    };
+
+    let mut columns = Vec::with_capacity(data.names.len());
+    for (name, values) in data.names.iter().zip(data.columns.iter()) {
+        let cells = rows
+            .iter()
+            .map(|row| match row {
+                Some(row) => display_cell(&values[*row], ctx),
+                None => Ok(("...".to_string(), false)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let width = cells
+            .iter()
+            .map(|(cell, _)| cell.width())
+            .chain(std::iter::once(name.width()))
+            .max()
+            .unwrap_or_default();
+        columns.push((name, cells, width));
+    }
+
+    let mut lines = Vec::with_capacity(rows.len() + 3);
+    lines.push(
+        columns
+            .iter()
+            .map(|(name, _, width)| pad(name, *width, false))
+            .collect::<Vec<_>>(),
+    );
+    lines.push(
+        columns
+            .iter()
+            .map(|(_, _, width)| "-".repeat(*width))
+            .collect(),
+    );
+    for i in 0..rows.len() {
+        lines.push(
+            columns
+                .iter()
+                .map(|(_, cells, width)| {
+                    let (cell, align_right) = &cells[i];
+                    pad(cell, *width, *align_right)
+                })
+                .collect(),
+        );
+    }
+
+    let mut output = lines
+        .iter()
+        .map(|line| line.join("  ").trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    if rows.len() < row_count {
+        output.push_str(&format!(
+            "\n[{row_count} rows x {} columns]",
+            data.names.len()
+        ));
+    }
+
+    ctx.append(output);
+    Ok(())
+}
+
+// Returns the display string for a value, along with whether or not it should be right-aligned
+fn display_cell(value: &KValue, ctx: &DisplayContext) -> Result<(String, bool)> {
+    let result = match value {
+        KValue::Str(s) => (s.to_string(), false),
+        KValue::Number(n) => (n.to_string(), true),
+        _ => {
+            let mut cell_ctx = match ctx.vm() {
+                Some(vm) => DisplayContext::with_vm(vm),
+                None => DisplayContext::default(),
+            };
+            value.display(&mut cell_ctx)?;
+            (cell_ctx.result(), false)
+        }
+    };
+    Ok(result)
+}
+
+fn pad(s: &str, width: usize, align_right: bool) -> String {
+    let padding = " ".repeat(width.saturating_sub(s.width()));
+    if align_right {
+        format!("{padding}{s}")
+    } else {
+        format!("{s}{padding}")
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{
+    column_names,
+    table::{Table, TableData},
+};
+use indexmap::IndexMap;
+use koto_runtime::{core_lib::value_sort::compare_values, derive::*, prelude::*, Ptr, Result};
+use std::cmp::Ordering;
+
+/// A table's rows, grouped by key
+///
+/// Grouped tables are created by `Table.group_by`, with groups kept in the order that their keys
+/// first appear in the table.
+#[derive(Clone, KotoType, KotoCopy)]
+pub struct GroupedTable {
+    data: Ptr<TableData>,
+    // The names of the key columns, or None if the keys were produced by a function
+    key_names: Option<Vec<KString>>,
+    groups: Ptr<Vec<(KValue, Vec<usize>)>>,
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl GroupedTable {
+    pub fn new(vm: &mut KotoVm, data: Ptr<TableData>, key: &KValue) -> Result<Self> {
+        let key_names = if key.is_callable() {
+            None
+        } else {
+            Some(column_names(key)?)
+        };
+
+        let mut groups: IndexMap<ValueKey, (KValue, Vec<usize>)> = IndexMap::new();
+        for (i, key) in data.row_keys(vm, key)?.into_iter().enumerate() {
+            let hashable_key = match ValueKey::try_from(key.clone()) {
+                Ok(hashable_key) => hashable_key,
+                Err(_) => return type_error("a hashable value as group key", &key),
+            };
+            groups
+                .entry(hashable_key)
+                .or_insert_with(|| (key, Vec::new()))
+                .1
+                .push(i);
+        }
+
+        Ok(Self {
+            data,
+            key_names,
+            groups: Ptr::from(groups.into_values().collect::<Vec<_>>()),
+        })
+    }
+
+    // Makes a table containing the group keys
+    //
+    // Multiple key columns are split back into separate columns.
+    fn key_table(&self) -> TableData {
+        let mut result = TableData::default();
+
+        match self.key_names.as_deref() {
+            Some(names) if names.len() > 1 => {
+                for (i, name) in names.iter().enumerate() {
+                    result.names.push(name.clone());
+                    result.columns.push(
+                        self.groups
+                            .iter()
+                            .map(|(key, _)| match key {
+                                KValue::Tuple(values) => values[i].clone(),
+                                _ => unreachable!(),
+                            })
+                            .collect(),
+                    );
+                }
+            }
+            names => {
+                // Keys produced by a function are placed in a 'key' column
+                let name = match names {
+                    Some([name]) => name.clone(),
+                    _ => "key".into(),
+                };
+                result.names.push(name);
+                result
+                    .columns
+                    .push(self.groups.iter().map(|(key, _)| key.clone()).collect());
+            }
+        }
+
+        result
+    }
+
+    #[koto_method]
+    fn aggregate(ctx: MethodContext<Self>) -> Result<KValue> {
+        let aggregations = match ctx.args {
+            [KValue::Map(aggregations)] => aggregations.clone(),
+            unexpected => return type_error_with_slice("a Map of aggregations", unexpected),
+        };
+
+        let grouped = ctx.instance()?.clone();
+        let mut vm = ctx.vm.spawn_shared_vm();
+
+        let aggregations: Vec<_> = aggregations
+            .data()
+            .iter()
+            .map(|(key, value)| (key.value().clone(), value.clone()))
+            .collect();
+
+        let mut result = grouped.key_table();
+        for (name, aggregation) in aggregations {
+            let KValue::Str(name) = name else {
+                return type_error("a String as column name", &name);
+            };
+
+            let column = grouped
+                .groups
+                .iter()
+                .map(|(_, rows)| aggregate(&mut vm, &grouped.data, rows, &aggregation))
+                .collect::<Result<Vec<_>>>()?;
+
+            result.names.push(name);
+            result.columns.push(column);
+        }
+
+        Ok(Table::from(result).into())
+    }
+
+    #[koto_method]
+    fn groups(&self) -> KValue {
+        let groups = self
+            .groups
+            .iter()
+            .map(|(key, rows)| {
+                let table = Table::from(self.data.take_rows(rows));
+                KValue::Tuple(vec![key.clone(), table.into()].into())
+            })
+            .collect();
+        KList::with_data(groups).into()
+    }
+}
+
+impl KotoObject for GroupedTable {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!("GroupedTable({} groups)", self.groups.len()));
+        Ok(())
+    }
+
+    fn size(&self) -> Option<usize> {
+        Some(self.groups.len())
+    }
+}
+
+impl From<GroupedTable> for KValue {
+    fn from(grouped: GroupedTable) -> Self {
+        KObject::from(grouped).into()
+    }
+}
+
+// Produces a single value from a group of rows
+//
+// The aggregation is either:
+//   - 'count', which returns the number of rows in the group.
+//   - A column name and an operation, e.g. `('score', 'mean')`.
+//   - A function that's called with the group as a table.
+fn aggregate(
+    vm: &mut KotoVm,
+    data: &TableData,
+    rows: &[usize],
+    aggregation: &KValue,
+) -> Result<KValue> {
+    let (column, op) = match aggregation {
+        KValue::Str(op) if op.as_str() == "count" => return Ok(rows.len().into()),
+        f if f.is_callable() => {
+            let group = Table::from(data.take_rows(rows));
+            return vm.call_function(f.clone(), KValue::from(group));
+        }
+        KValue::List(_) | KValue::Tuple(_) => {
+            let values = match aggregation {
+                KValue::List(list) => list.data().to_vec(),
+                KValue::Tuple(tuple) => tuple.to_vec(),
+                _ => unreachable!(),
+            };
+            match values.as_slice() {
+                [KValue::Str(column), KValue::Str(op)] => (column.clone(), op.clone()),
+                unexpected => {
+                    return type_error_with_slice("a column name and an operation", unexpected)
+                }
+            }
+        }
+        unexpected => {
+            return type_error(
+                "'count', a column name and an operation, or a Function as aggregation",
+                unexpected,
+            )
+        }
+    };
+
+    let column = data.column(&column)?;
+    // Null values are ignored by all operations other than 'first' and 'last'
+    let mut values = rows
+        .iter()
+        .map(|i| &column[*i])
+        .filter(|value| !matches!(value, KValue::Null));
+
+    match op.as_str() {
+        "count" => Ok(values.count().into()),
+        "first" => Ok(rows.first().map_or(KValue::Null, |i| column[*i].clone())),
+        "last" => Ok(rows.last().map_or(KValue::Null, |i| column[*i].clone())),
+        "sum" => {
+            let mut sum = KValue::Number(0.into());
+            for value in values {
+                sum = vm.run_binary_op(BinaryOp::Add, sum, value.clone())?;
+            }
+            Ok(sum)
+        }
+        "mean" => {
+            let mut sum = 0.0;
+            let mut count = 0;
+            for value in values {
+                match value {
+                    KValue::Number(n) => sum += f64::from(n),
+                    unexpected => return type_error("a Number", unexpected),
+                }
+                count += 1;
+            }
+            if count > 0 {
+                Ok((sum / count as f64).into())
+            } else {
+                Ok(KValue::Null)
+            }
+        }
+        "min" | "max" => {
+            let keep = if op.as_str() == "min" {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            };
+            let Some(mut result) = values.next() else {
+                return Ok(KValue::Null);
+            };
+            for value in values {
+                if compare_values(vm, value, result)? == keep {
+                    result = value;
+                }
+            }
+            Ok(result.clone())
+        }
+        other => runtime_error!(
+            "Unknown aggregation '{other}', \
+             expected one of 'count', 'first', 'last', 'max', 'mean', 'min', or 'sum'"
+        ),
+    }
+}
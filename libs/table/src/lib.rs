@@ -0,0 +1,73 @@
+//! A Koto language module for working with tables of data
+
+mod display;
+mod group;
+mod table;
+
+pub use crate::{group::GroupedTable, table::Table};
+
+use crate::table::TableData;
+use koto_runtime::{prelude::*, Result};
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("table");
+
+    result.add_fn("from_columns", |ctx| match ctx.args() {
+        [KValue::Map(columns)] => {
+            let columns = columns.clone();
+            let data = TableData::from_columns(ctx.vm, &columns)?;
+            Ok(Table::from(data).into())
+        }
+        unexpected => type_error_with_slice("a Map of columns", unexpected),
+    });
+
+    result.add_fn("from_rows", |ctx| match ctx.args() {
+        [rows] if rows.is_iterable() => {
+            let rows = rows.clone();
+            let data = TableData::from_rows(ctx.vm, rows)?;
+            Ok(Table::from(data).into())
+        }
+        unexpected => type_error_with_slice("an iterable of row Maps", unexpected),
+    });
+
+    result
+}
+
+// Collects the values produced by an iterable into a Vec
+fn collect_values(vm: &mut KotoVm, iterable: KValue) -> Result<Vec<KValue>> {
+    match iterable {
+        KValue::List(list) => Ok(list.data().to_vec()),
+        KValue::Tuple(tuple) => Ok(tuple.to_vec()),
+        iterable => {
+            let mut result = Vec::new();
+            for output in vm.make_iterator(iterable)? {
+                match output {
+                    KIteratorOutput::Value(value) => result.push(value),
+                    KIteratorOutput::ValuePair(first, second) => {
+                        result.push(KValue::Tuple(vec![first, second].into()))
+                    }
+                    KIteratorOutput::Error(error) => return Err(error),
+                }
+            }
+            Ok(result)
+        }
+    }
+}
+
+// Gets a list of column names from either a single String, or a List or Tuple of Strings
+fn column_names(value: &KValue) -> Result<Vec<KString>> {
+    let names = match value {
+        KValue::Str(name) => return Ok(vec![name.clone()]),
+        KValue::List(list) => list.data().to_vec(),
+        KValue::Tuple(tuple) => tuple.to_vec(),
+        unexpected => return type_error("a column name, or a List of column names", unexpected),
+    };
+
+    names
+        .iter()
+        .map(|name| match name {
+            KValue::Str(name) => Ok(name.clone()),
+            unexpected => type_error("a column name String", unexpected),
+        })
+        .collect()
+}
@@ -0,0 +1,565 @@
+use crate::{collect_values, column_names, display::display_table, group::GroupedTable};
+use indexmap::IndexMap;
+use koto_runtime::{core_lib::value_sort::compare_values, derive::*, prelude::*, Ptr, Result};
+use std::cmp::Ordering;
+
+/// The columnar storage used by [Table]
+///
+/// Each column has the same number of values.
+#[derive(Clone, Default)]
+pub struct TableData {
+    pub names: Vec<KString>,
+    pub columns: Vec<Vec<KValue>>,
+}
+
+impl TableData {
+    /// Makes a table from a map of column names to iterables
+    pub fn from_columns(vm: &mut KotoVm, map: &KMap) -> Result<Self> {
+        let entries: Vec<_> = map
+            .data()
+            .iter()
+            .map(|(key, value)| (key.value().clone(), value.clone()))
+            .collect();
+
+        let mut result = Self::default();
+        for (name, values) in entries {
+            let KValue::Str(name) = name else {
+                return type_error("a String as column name", &name);
+            };
+            if !values.is_iterable() {
+                return type_error("an iterable as column", &values);
+            }
+            let values = collect_values(vm, values)?;
+            if let Some(first) = result.columns.first() {
+                if first.len() != values.len() {
+                    return runtime_error!(
+                        "Expected {} values in column '{name}', found {}",
+                        first.len(),
+                        values.len()
+                    );
+                }
+            }
+            result.names.push(name);
+            result.columns.push(values);
+        }
+
+        Ok(result)
+    }
+
+    /// Makes a table from an iterable of maps
+    ///
+    /// The table's columns are taken from the keys of the maps, in the order that they're first
+    /// encountered. Missing values are filled with `null`.
+    pub fn from_rows(vm: &mut KotoVm, rows: KValue) -> Result<Self> {
+        let mut columns: IndexMap<KString, Vec<KValue>> = IndexMap::new();
+
+        for (i, row) in collect_values(vm, rows)?.into_iter().enumerate() {
+            let KValue::Map(row) = row else {
+                return type_error("a Map as row", &row);
+            };
+
+            for (key, value) in row.data().iter() {
+                let KValue::Str(name) = key.value() else {
+                    return type_error("a String as column name", key.value());
+                };
+                columns
+                    .entry(name.clone())
+                    .or_insert_with(|| vec![KValue::Null; i])
+                    .push(value.clone());
+            }
+
+            for column in columns.values_mut() {
+                column.resize(i + 1, KValue::Null);
+            }
+        }
+
+        let (names, columns) = columns.into_iter().unzip();
+        Ok(Self { names, columns })
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.columns.first().map_or(0, |column| column.len())
+    }
+
+    pub fn column_index(&self, name: &str) -> Result<usize> {
+        match self.names.iter().position(|column| column.as_str() == name) {
+            Some(index) => Ok(index),
+            None => runtime_error!("Column '{name}' not found"),
+        }
+    }
+
+    pub fn column(&self, name: &str) -> Result<&[KValue]> {
+        Ok(&self.columns[self.column_index(name)?])
+    }
+
+    /// Returns a map containing the values of the row at the given index
+    pub fn row(&self, index: usize) -> KMap {
+        let result = KMap::with_capacity(self.names.len());
+        for (name, column) in self.names.iter().zip(self.columns.iter()) {
+            result.insert(name.clone(), column[index].clone());
+        }
+        result
+    }
+
+    /// Makes a new table containing the rows at the given indices
+    pub fn take_rows(&self, indices: &[usize]) -> Self {
+        Self {
+            names: self.names.clone(),
+            columns: self
+                .columns
+                .iter()
+                .map(|column| indices.iter().map(|i| column[*i].clone()).collect())
+                .collect(),
+        }
+    }
+
+    // Produces a key for each row, either from the values in one or more columns,
+    // or by calling a function with each row
+    pub fn row_keys(&self, vm: &mut KotoVm, key: &KValue) -> Result<Vec<KValue>> {
+        if key.is_callable() {
+            return (0..self.row_count())
+                .map(|i| vm.call_function(key.clone(), KValue::Map(self.row(i))))
+                .collect();
+        }
+
+        let names = column_names(key)?;
+        let columns = names
+            .iter()
+            .map(|name| self.column(name))
+            .collect::<Result<Vec<_>>>()?;
+
+        let keys = (0..self.row_count())
+            .map(|i| match columns.as_slice() {
+                [column] => column[i].clone(),
+                _ => {
+                    let values: Vec<_> = columns.iter().map(|column| column[i].clone()).collect();
+                    KValue::Tuple(values.into())
+                }
+            })
+            .collect();
+        Ok(keys)
+    }
+}
+
+/// A table of data, with values stored in named columns
+///
+/// Tables are immutable, with operations like `filter` and `sort` producing new tables.
+#[derive(Clone, KotoType, KotoCopy)]
+pub struct Table(Ptr<TableData>);
+
+#[koto_impl(runtime = koto_runtime)]
+impl Table {
+    pub fn data(&self) -> &TableData {
+        &self.0
+    }
+
+    // Gets the table's data, releasing the borrow of the instance so that functions can be called
+    fn data_from_context(ctx: &MethodContext<Self>) -> Result<Ptr<TableData>> {
+        Ok(ctx.instance()?.0.clone())
+    }
+
+    #[koto_method]
+    fn column(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Str(name)] => Ok(KList::from_slice(self.0.column(name)?).into()),
+            unexpected => type_error_with_slice("a column name", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn columns(&self) -> KValue {
+        let names: Vec<KValue> = self.0.names.iter().cloned().map(KValue::from).collect();
+        KValue::Tuple(names.into())
+    }
+
+    #[koto_method]
+    fn filter(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [f] if f.is_callable() => {
+                let data = Self::data_from_context(&ctx)?;
+                let mut vm = ctx.vm.spawn_shared_vm();
+
+                let mut indices = Vec::new();
+                for i in 0..data.row_count() {
+                    match vm.call_function(f.clone(), KValue::Map(data.row(i)))? {
+                        KValue::Bool(true) => indices.push(i),
+                        KValue::Bool(false) => {}
+                        unexpected => {
+                            return type_error("a Bool from the filter function", &unexpected)
+                        }
+                    }
+                }
+
+                Ok(Self::from(data.take_rows(&indices)).into())
+            }
+            unexpected => type_error_with_slice("a filter Function", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn group_by(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [key] => {
+                let data = Self::data_from_context(&ctx)?;
+                let mut vm = ctx.vm.spawn_shared_vm();
+                Ok(GroupedTable::new(&mut vm, data, key)?.into())
+            }
+            unexpected => type_error_with_slice(
+                "a column name, a List of column names, or a key Function",
+                unexpected,
+            ),
+        }
+    }
+
+    #[koto_method]
+    fn head(&self, args: &[KValue]) -> Result<KValue> {
+        let n = match args {
+            [] => 5,
+            [KValue::Number(n)] if *n >= 0 => usize::from(n),
+            unexpected => {
+                return type_error_with_slice("an optional non-negative Number", unexpected)
+            }
+        };
+        let indices: Vec<_> = (0..n.min(self.0.row_count())).collect();
+        Ok(Self::from(self.0.take_rows(&indices)).into())
+    }
+
+    #[koto_method]
+    fn join(ctx: MethodContext<Self>) -> Result<KValue> {
+        let expected_error = "a Table, a column name, and an optional join type";
+
+        let (other, on, how) = match ctx.args {
+            [KValue::Object(other), KValue::Str(on)] if other.is_a::<Self>() => {
+                (other, on, "inner")
+            }
+            [KValue::Object(other), KValue::Str(on), KValue::Str(how)] if other.is_a::<Self>() => {
+                (other, on, how.as_str())
+            }
+            unexpected => return type_error_with_slice(expected_error, unexpected),
+        };
+
+        let keep_unmatched = match how {
+            "inner" => false,
+            "left" => true,
+            other => {
+                return runtime_error!("Expected 'inner' or 'left' as join type, found '{other}'")
+            }
+        };
+
+        let left = Self::data_from_context(&ctx)?;
+        let right = other.cast::<Self>()?.0.clone();
+        Ok(Self::from(join(&left, &right, on, keep_unmatched)?).into())
+    }
+
+    #[koto_method]
+    fn reverse(&self) -> KValue {
+        let indices: Vec<_> = (0..self.0.row_count()).rev().collect();
+        Self::from(self.0.take_rows(&indices)).into()
+    }
+
+    #[koto_method]
+    fn row(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Number(n)] => {
+                let index = i64::from(n);
+                match usize::try_from(index) {
+                    Ok(index) if index < self.0.row_count() => Ok(self.0.row(index).into()),
+                    _ => runtime_error!("Row index {index} is out of bounds"),
+                }
+            }
+            unexpected => type_error_with_slice("a row index", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn rows(&self) -> KValue {
+        KIterator::new(RowIterator::new(self.0.clone())).into()
+    }
+
+    #[koto_method]
+    fn select(&self, args: &[KValue]) -> Result<KValue> {
+        let names = match args {
+            [names @ (KValue::List(_) | KValue::Tuple(_))] => column_names(names)?,
+            names if names.iter().all(|name| matches!(name, KValue::Str(_))) => names
+                .iter()
+                .map(column_names)
+                .collect::<Result<Vec<_>>>()?
+                .concat(),
+            unexpected => return type_error_with_slice("column names", unexpected),
+        };
+
+        let mut result = TableData::default();
+        for name in names {
+            result.columns.push(self.0.column(&name)?.to_vec());
+            result.names.push(name);
+        }
+        Ok(Self::from(result).into())
+    }
+
+    #[koto_method]
+    fn sort(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [key] => {
+                let data = Self::data_from_context(&ctx)?;
+                let mut vm = ctx.vm.spawn_shared_vm();
+                let keys = data.row_keys(&mut vm, key)?;
+
+                let mut indices: Vec<_> = (0..data.row_count()).collect();
+                let mut error = None;
+                indices.sort_by(|a, b| {
+                    if error.is_some() {
+                        return Ordering::Equal;
+                    }
+                    compare_keys(&mut vm, &keys[*a], &keys[*b]).unwrap_or_else(|e| {
+                        error = Some(e);
+                        Ordering::Equal
+                    })
+                });
+
+                match error {
+                    Some(error) => Err(error),
+                    None => Ok(Self::from(data.take_rows(&indices)).into()),
+                }
+            }
+            unexpected => type_error_with_slice(
+                "a column name, a List of column names, or a key Function",
+                unexpected,
+            ),
+        }
+    }
+
+    #[koto_method]
+    fn tail(&self, args: &[KValue]) -> Result<KValue> {
+        let n = match args {
+            [] => 5,
+            [KValue::Number(n)] if *n >= 0 => usize::from(n),
+            unexpected => {
+                return type_error_with_slice("an optional non-negative Number", unexpected)
+            }
+        };
+        let row_count = self.0.row_count();
+        let indices: Vec<_> = (row_count.saturating_sub(n)..row_count).collect();
+        Ok(Self::from(self.0.take_rows(&indices)).into())
+    }
+
+    #[koto_method]
+    fn to_columns(&self) -> KValue {
+        let result = KMap::with_capacity(self.0.names.len());
+        for (name, column) in self.0.names.iter().zip(self.0.columns.iter()) {
+            result.insert(name.clone(), KList::from_slice(column));
+        }
+        result.into()
+    }
+
+    #[koto_method]
+    fn to_rows(&self) -> KValue {
+        let rows = (0..self.0.row_count())
+            .map(|i| KValue::Map(self.0.row(i)))
+            .collect();
+        KList::with_data(rows).into()
+    }
+
+    #[koto_method]
+    fn with_column(ctx: MethodContext<Self>) -> Result<KValue> {
+        let (name, values) = match ctx.args {
+            [KValue::Str(name), values] if values.is_callable() || values.is_iterable() => {
+                (name, values)
+            }
+            unexpected => {
+                return type_error_with_slice(
+                    "a column name, and a Function or an iterable of values",
+                    unexpected,
+                )
+            }
+        };
+
+        let data = Self::data_from_context(&ctx)?;
+        let mut vm = ctx.vm.spawn_shared_vm();
+
+        let values = if values.is_callable() {
+            (0..data.row_count())
+                .map(|i| vm.call_function(values.clone(), KValue::Map(data.row(i))))
+                .collect::<Result<Vec<_>>>()?
+        } else {
+            collect_values(&mut vm, values.clone())?
+        };
+
+        if !data.names.is_empty() && values.len() != data.row_count() {
+            return runtime_error!(
+                "Expected {} values for column '{name}', found {}",
+                data.row_count(),
+                values.len()
+            );
+        }
+
+        let mut result = TableData::clone(&data);
+        match result.column_index(name) {
+            Ok(index) => result.columns[index] = values,
+            Err(_) => {
+                result.names.push(name.clone());
+                result.columns.push(values);
+            }
+        }
+        Ok(Self::from(result).into())
+    }
+}
+
+impl KotoObject for Table {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        display_table(&self.0, ctx)
+    }
+
+    fn index(&self, index: &KValue) -> Result<KValue> {
+        match index {
+            KValue::Number(_) => self.row(std::slice::from_ref(index)),
+            KValue::Str(_) => self.column(std::slice::from_ref(index)),
+            unexpected => type_error("a row index or a column name", unexpected),
+        }
+    }
+
+    fn size(&self) -> Option<usize> {
+        Some(self.0.row_count())
+    }
+
+    fn is_iterable(&self) -> IsIterable {
+        IsIterable::Iterable
+    }
+
+    fn make_iterator(&self, _vm: &mut KotoVm) -> Result<KIterator> {
+        Ok(KIterator::new(RowIterator::new(self.0.clone())))
+    }
+}
+
+impl From<TableData> for Table {
+    fn from(data: TableData) -> Self {
+        Self(data.into())
+    }
+}
+
+impl From<Table> for KValue {
+    fn from(table: Table) -> Self {
+        KObject::from(table).into()
+    }
+}
+
+/// An iterator that produces a table's rows as maps
+#[derive(Clone)]
+struct RowIterator {
+    data: Ptr<TableData>,
+    next: usize,
+    end: usize,
+}
+
+impl RowIterator {
+    fn new(data: Ptr<TableData>) -> Self {
+        let end = data.row_count();
+        Self { data, next: 0, end }
+    }
+}
+
+impl KotoIterator for RowIterator {
+    fn make_copy(&self) -> Result<KIterator> {
+        Ok(KIterator::new(self.clone()))
+    }
+
+    fn is_bidirectional(&self) -> bool {
+        true
+    }
+
+    fn next_back(&mut self) -> Option<KIteratorOutput> {
+        if self.next < self.end {
+            self.end -= 1;
+            Some(KValue::Map(self.data.row(self.end)).into())
+        } else {
+            None
+        }
+    }
+}
+
+impl Iterator for RowIterator {
+    type Item = KIteratorOutput;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next < self.end {
+            let result = self.data.row(self.next);
+            self.next += 1;
+            Some(KValue::Map(result).into())
+        } else {
+            None
+        }
+    }
+}
+
+// Compares sort keys, with null values placed at the end
+fn compare_keys(vm: &mut KotoVm, a: &KValue, b: &KValue) -> Result<Ordering> {
+    match (a, b) {
+        (KValue::Null, KValue::Null) => Ok(Ordering::Equal),
+        (KValue::Null, _) => Ok(Ordering::Greater),
+        (_, KValue::Null) => Ok(Ordering::Less),
+        (KValue::Tuple(a), KValue::Tuple(b)) => {
+            for (a, b) in a.iter().zip(b.iter()) {
+                match compare_keys(vm, a, b)? {
+                    Ordering::Equal => {}
+                    ordering => return Ok(ordering),
+                }
+            }
+            Ok(a.len().cmp(&b.len()))
+        }
+        _ => compare_values(vm, a, b),
+    }
+}
+
+fn join(left: &TableData, right: &TableData, on: &str, keep_unmatched: bool) -> Result<TableData> {
+    let left_key = left.column(on)?;
+    let right_key_index = right.column_index(on)?;
+
+    let mut right_rows: IndexMap<ValueKey, Vec<usize>> = IndexMap::new();
+    for (i, key) in right.columns[right_key_index].iter().enumerate() {
+        if let Ok(key) = ValueKey::try_from(key.clone()) {
+            right_rows.entry(key).or_default().push(i);
+        }
+    }
+
+    let mut left_indices = Vec::new();
+    let mut right_indices = Vec::new();
+    for (i, key) in left_key.iter().enumerate() {
+        let matches = ValueKey::try_from(key.clone())
+            .ok()
+            .and_then(|key| right_rows.get(&key));
+        match matches {
+            Some(matches) => {
+                for j in matches {
+                    left_indices.push(i);
+                    right_indices.push(Some(*j));
+                }
+            }
+            None if keep_unmatched => {
+                left_indices.push(i);
+                right_indices.push(None);
+            }
+            None => {}
+        }
+    }
+
+    let mut result = left.take_rows(&left_indices);
+    for (index, (name, column)) in right.names.iter().zip(right.columns.iter()).enumerate() {
+        if index == right_key_index {
+            continue;
+        }
+
+        let name = if left.names.contains(name) {
+            format!("{name}_right").into()
+        } else {
+            name.clone()
+        };
+        result.names.push(name);
+        result.columns.push(
+            right_indices
+                .iter()
+                .map(|j| j.map_or(KValue::Null, |j| column[j].clone()))
+                .collect(),
+        );
+    }
+
+    Ok(result)
+}
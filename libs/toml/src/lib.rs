@@ -1,7 +1,11 @@
 //! A Koto language module for working with TOML data
 
-use koto_runtime::{prelude::*, Result};
-use koto_serialize::SerializableValue;
+mod writer;
+
+pub use writer::TomlWriter;
+
+use koto_runtime::{core_lib::io::File, prelude::*, Result};
+use koto_serialize::{NonFiniteMode, NullMode, SerializeOptions};
 use toml::Value as Toml;
 
 pub fn toml_to_koto_value(value: &Toml) -> Result<KValue> {
@@ -41,13 +45,70 @@ pub fn make_module() -> KMap {
         unexpected => type_error_with_slice("a String as argument", unexpected),
     });
 
-    result.add_fn("to_string", |ctx| match ctx.args() {
-        [value] => match toml::to_string_pretty(&SerializableValue(value)) {
+    result.add_fn("to_string", |ctx| {
+        let (value, options) = serialize_args("toml.to_string", ctx.args())?;
+        match toml::to_string_pretty(&options.serializable(value)) {
             Ok(result) => Ok(result.into()),
             Err(e) => runtime_error!("toml.to_string: {e}"),
-        },
-        unexpected => type_error_with_slice("a Value as argument", unexpected),
+        }
+    });
+
+    result.add_fn("to_string_canonical", |ctx| {
+        let (value, mut options) = serialize_args("toml.to_string_canonical", ctx.args())?;
+        options.canonical = true;
+        // Converting to a TOML value first ensures that values are written before tables,
+        // while preserving the sorted order of keys
+        match toml::Value::try_from(options.serializable(value))
+            .and_then(|toml| toml::to_string(&toml))
+        {
+            Ok(result) => Ok(result.into()),
+            Err(e) => runtime_error!("toml.to_string_canonical: {e}"),
+        }
+    });
+
+    result.add_fn("writer", |ctx| match ctx.args() {
+        [KValue::Object(o)] if o.is_a::<File>() => {
+            Ok(TomlWriter::new(o.cast::<File>()?.inner().clone()).into())
+        }
+        unexpected => type_error_with_slice("a File", unexpected),
     });
 
     result
 }
+
+/// Makes the options used when serializing values to TOML
+///
+/// TOML doesn't have a null value, so by default nulls produce an error,
+/// while non-finite numbers are kept as `nan` and `inf`.
+pub fn serialize_options(options: Option<&KMap>) -> Result<SerializeOptions> {
+    let mut result = SerializeOptions {
+        nulls: NullMode::Error,
+        nan: NonFiniteMode::Keep,
+        ..Default::default()
+    };
+    if let Some(options) = options {
+        result.update_from_map(options)?;
+    }
+    if result.nulls == NullMode::Keep || result.nan == NonFiniteMode::Null {
+        return runtime_error!(
+            "TOML doesn't support null values, see the 'nulls' and 'nan' options"
+        );
+    }
+    Ok(result)
+}
+
+// Gets the value to serialize from a function's arguments, along with the serialization options
+fn serialize_args<'a>(fn_name: &str, args: &'a [KValue]) -> Result<(&'a KValue, SerializeOptions)> {
+    let (value, options) = match args {
+        [value] => (value, None),
+        [value, KValue::Map(options)] => (value, Some(options)),
+        unexpected => {
+            return type_error_with_slice(
+                "a Value as argument, with an optional Map of options",
+                unexpected,
+            )
+        }
+    };
+    let options = serialize_options(options).map_err(|e| format!("{fn_name}: {e}"))?;
+    Ok((value, options))
+}
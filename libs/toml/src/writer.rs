@@ -0,0 +1,402 @@
+use koto_runtime::{derive::*, prelude::*, Ptr, PtrMut, Result};
+use koto_serialize::SerializableValue;
+use toml::{value::Table, Value as Toml};
+
+/// A writer that serializes TOML data incrementally to a file
+///
+/// Values are written as they're provided, so large outputs can be produced without first
+/// building the complete structure in memory.
+///
+/// TOML documents are tables, so the writer expects a map to be started before any other
+/// values are written. Nested maps are written as tables with headers, and lists of maps are
+/// written as arrays of tables. The values in a table need to be written before any of its
+/// nested tables.
+///
+/// The writer is shared between copies.
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "TomlWriter")]
+pub struct TomlWriter(PtrMut<WriterState>);
+
+#[koto_impl(runtime = koto_runtime)]
+impl TomlWriter {
+    /// Makes a new writer that writes to the provided file
+    pub fn new(file: Ptr<dyn KotoFile>) -> Self {
+        Self(
+            WriterState {
+                file,
+                containers: Vec::new(),
+                complete: false,
+                written: false,
+            }
+            .into(),
+        )
+    }
+
+    /// Starts writing a map, which is written until a matching call to [Self::end]
+    ///
+    /// The first map is the document's root table, nested maps are written as tables.
+    pub fn begin_map(&self) -> Result<()> {
+        let state = &mut *self.0.borrow_mut();
+        match state.containers.last_mut() {
+            None if state.complete => {
+                runtime_error!("toml.writer: the document has already been completed")
+            }
+            None => {
+                state.containers.push(Container::Table {
+                    path: Vec::new(),
+                    key: None,
+                    has_tables: false,
+                });
+                Ok(())
+            }
+            Some(Container::Table {
+                path,
+                key,
+                has_tables,
+            }) => {
+                let Some(key) = key.take() else {
+                    return runtime_error!("toml.writer: expected a key before the map");
+                };
+                *has_tables = true;
+                let mut path = path.clone();
+                path.push(key);
+                state.write_header(&format!("[{}]", format_path(&path)?))?;
+                state.containers.push(Container::Table {
+                    path,
+                    key: None,
+                    has_tables: false,
+                });
+                Ok(())
+            }
+            Some(Container::List { path, items }) => match items {
+                ListItems::Empty | ListItems::Tables => {
+                    *items = ListItems::Tables;
+                    let path = path.clone();
+                    state.set_parent_has_tables();
+                    state.write_header(&format!("[[{}]]", format_path(&path)?))?;
+                    state.containers.push(Container::Table {
+                        path,
+                        key: None,
+                        has_tables: false,
+                    });
+                    Ok(())
+                }
+                ListItems::Values => {
+                    runtime_error!("toml.writer: maps can't follow other values in a list")
+                }
+            },
+        }
+    }
+
+    /// Starts writing a list, which is written until a matching call to [Self::end]
+    ///
+    /// Lists that contain maps are written as arrays of tables, otherwise the list is written
+    /// as an array.
+    pub fn begin_list(&self) -> Result<()> {
+        let state = &mut *self.0.borrow_mut();
+        match state.containers.last_mut() {
+            Some(Container::Table { path, key, .. }) => {
+                let Some(key) = key.take() else {
+                    return runtime_error!("toml.writer: expected a key before the list");
+                };
+                let mut path = path.clone();
+                path.push(key);
+                state.containers.push(Container::List {
+                    path,
+                    items: ListItems::Empty,
+                });
+                Ok(())
+            }
+            Some(Container::List { .. }) => {
+                runtime_error!("toml.writer: nested lists need to be written as complete values")
+            }
+            None => runtime_error!("toml.writer: expected a map for the document's root table"),
+        }
+    }
+
+    /// Writes the key for the next entry in the current map
+    pub fn key(&self, key: &str) -> Result<()> {
+        let state = &mut *self.0.borrow_mut();
+        match state.containers.last_mut() {
+            Some(Container::Table { key: Some(_), .. }) => {
+                runtime_error!("toml.writer: expected a value for the previous key")
+            }
+            Some(Container::Table { key: pending, .. }) => {
+                *pending = Some(key.to_string());
+                Ok(())
+            }
+            _ => runtime_error!("toml.writer: keys can only be written in maps"),
+        }
+    }
+
+    /// Writes a complete value
+    pub fn value(&self, value: &KValue) -> Result<()> {
+        let state = &mut *self.0.borrow_mut();
+        let toml =
+            Toml::try_from(SerializableValue(value)).map_err(|e| format!("toml.writer: {e}"))?;
+
+        match state.containers.last_mut() {
+            Some(Container::Table {
+                path,
+                key,
+                has_tables,
+            }) => {
+                let Some(key) = key.take() else {
+                    return runtime_error!("toml.writer: expected a key before the value");
+                };
+                if is_table_or_array_of_tables(&toml) {
+                    // Tables are written with headers that include the path to the current table
+                    *has_tables = true;
+                    let mut root = Table::new();
+                    root.insert(key, toml);
+                    for key in path.iter().rev() {
+                        let mut parent = Table::new();
+                        parent.insert(key.clone(), Toml::Table(root));
+                        root = parent;
+                    }
+                    let output = to_string(&Toml::Table(root))?;
+                    state.write_header(output.trim_end_matches('\n'))
+                } else if *has_tables {
+                    runtime_error!(
+                        "toml.writer: values need to be written before any nested tables"
+                    )
+                } else {
+                    let mut entry = Table::new();
+                    entry.insert(key, toml);
+                    let output = to_string(&Toml::Table(entry))?;
+                    state.write(&output)
+                }
+            }
+            Some(Container::List { path, items }) => match (&items, &toml) {
+                (ListItems::Empty | ListItems::Tables, Toml::Table(_)) => {
+                    *items = ListItems::Tables;
+                    let mut root = Table::new();
+                    root.insert(path.last().unwrap().clone(), Toml::Array(vec![toml]));
+                    for key in path.iter().rev().skip(1) {
+                        let mut parent = Table::new();
+                        parent.insert(key.clone(), Toml::Table(root));
+                        root = parent;
+                    }
+                    state.set_parent_has_tables();
+                    let output = to_string(&Toml::Table(root))?;
+                    state.write_header(output.trim_end_matches('\n'))
+                }
+                (ListItems::Tables, _) => {
+                    runtime_error!("toml.writer: only maps can follow maps in a list")
+                }
+                (ListItems::Values, Toml::Table(_)) => {
+                    runtime_error!("toml.writer: maps can't follow other values in a list")
+                }
+                (ListItems::Empty, _) => {
+                    *items = ListItems::Values;
+                    let key = format_key(path.last().unwrap())?;
+                    state.check_parent_accepts_values()?;
+                    let element = to_inline_string(&toml)?;
+                    state.write(&format!("{key} = [{element}"))
+                }
+                (ListItems::Values, _) => {
+                    let element = to_inline_string(&toml)?;
+                    state.write(&format!(", {element}"))
+                }
+            },
+            None => runtime_error!("toml.writer: expected a map for the document's root table"),
+        }
+    }
+
+    /// Finishes writing the current map or list
+    pub fn end(&self) -> Result<()> {
+        let state = &mut *self.0.borrow_mut();
+        match state.containers.pop() {
+            Some(Container::Table { key: Some(_), .. }) => {
+                runtime_error!("toml.writer: expected a value for the previous key")
+            }
+            Some(Container::Table { .. }) => {
+                if state.containers.is_empty() {
+                    state.complete = true;
+                }
+                Ok(())
+            }
+            Some(Container::List { path, items }) => match items {
+                ListItems::Empty => {
+                    state.check_parent_accepts_values()?;
+                    let key = format_key(path.last().unwrap())?;
+                    state.write(&format!("{key} = []\n"))
+                }
+                ListItems::Values => state.write("]\n"),
+                ListItems::Tables => Ok(()),
+            },
+            None => runtime_error!("toml.writer: there's no map or list to end"),
+        }
+    }
+
+    #[koto_method(alias = "begin_list")]
+    fn begin_list_koto(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [] => ctx.instance()?.begin_list()?,
+            unexpected => return type_error_with_slice("no arguments", unexpected),
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method(alias = "begin_map")]
+    fn begin_map_koto(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [] => ctx.instance()?.begin_map()?,
+            unexpected => return type_error_with_slice("no arguments", unexpected),
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method(alias = "end")]
+    fn end_koto(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [] => ctx.instance()?.end()?,
+            unexpected => return type_error_with_slice("no arguments", unexpected),
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method(alias = "key")]
+    fn key_koto(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [KValue::Str(key)] => ctx.instance()?.key(key)?,
+            unexpected => return type_error_with_slice("a String", unexpected),
+        }
+        ctx.instance_result()
+    }
+
+    #[koto_method(alias = "value")]
+    fn value_koto(ctx: MethodContext<Self>) -> Result<KValue> {
+        match ctx.args {
+            [value] => ctx.instance()?.value(value)?,
+            unexpected => return type_error_with_slice("a single value", unexpected),
+        }
+        ctx.instance_result()
+    }
+}
+
+impl KotoObject for TomlWriter {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(Self::type_static());
+        Ok(())
+    }
+}
+
+impl From<TomlWriter> for KValue {
+    fn from(writer: TomlWriter) -> Self {
+        KObject::from(writer).into()
+    }
+}
+
+struct WriterState {
+    file: Ptr<dyn KotoFile>,
+    containers: Vec<Container>,
+    // Set to true once the root table has been ended
+    complete: bool,
+    // Set to true once any output has been written, used to separate tables with empty lines
+    written: bool,
+}
+
+enum Container {
+    Table {
+        // The keys that lead to the table from the root
+        path: Vec<String>,
+        // The key that's waiting for a value
+        key: Option<String>,
+        // Set to true once a nested table has been written,
+        // after which the table can't accept further values.
+        has_tables: bool,
+    },
+    List {
+        // The keys that lead to the list from the root, including the list's key
+        path: Vec<String>,
+        items: ListItems,
+    },
+}
+
+// Lists are either written as arrays of values, or as arrays of tables,
+// which is decided by the first item in the list.
+enum ListItems {
+    Empty,
+    Values,
+    Tables,
+}
+
+impl WriterState {
+    fn write(&mut self, output: &str) -> Result<()> {
+        self.written = true;
+        self.file.write(output.as_bytes())
+    }
+
+    // Writes a table's header (or a complete table), separated from any previous output
+    fn write_header(&mut self, header: &str) -> Result<()> {
+        let separator = if self.written { "\n" } else { "" };
+        self.write(&format!("{separator}{header}\n"))
+    }
+
+    // Called when a list's items are written as tables, which prevents the list's parent table
+    // from accepting further values
+    fn set_parent_has_tables(&mut self) {
+        if let Some(Container::Table { has_tables, .. }) = self.parent_of_list() {
+            *has_tables = true;
+        }
+    }
+
+    fn check_parent_accepts_values(&mut self) -> Result<()> {
+        match self.parent_of_list() {
+            Some(Container::Table {
+                has_tables: true, ..
+            }) => runtime_error!("toml.writer: values need to be written before any nested tables"),
+            _ => Ok(()),
+        }
+    }
+
+    fn parent_of_list(&mut self) -> Option<&mut Container> {
+        let len = self.containers.len();
+        if len >= 2 {
+            self.containers.get_mut(len - 2)
+        } else {
+            None
+        }
+    }
+}
+
+fn is_table_or_array_of_tables(value: &Toml) -> bool {
+    match value {
+        Toml::Table(_) => true,
+        Toml::Array(entries) => {
+            !entries.is_empty() && entries.iter().all(|entry| matches!(entry, Toml::Table(_)))
+        }
+        _ => false,
+    }
+}
+
+fn format_path(path: &[String]) -> Result<String> {
+    let keys = path
+        .iter()
+        .map(|key| format_key(key))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(keys.join("."))
+}
+
+// Keys are written without quotes when possible
+fn format_key(key: &str) -> Result<String> {
+    let is_bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if is_bare {
+        Ok(key.to_string())
+    } else {
+        to_inline_string(&Toml::String(key.to_string()))
+    }
+}
+
+fn to_string(value: &Toml) -> Result<String> {
+    toml::to_string_pretty(value).map_err(|e| format!("toml.writer: {e}").into())
+}
+
+// Values in arrays and quoted keys are written on a single line
+fn to_inline_string(value: &Toml) -> Result<String> {
+    toml::to_string(value).map_err(|e| format!("toml.writer: {e}").into())
+}
@@ -0,0 +1,110 @@
+//! A Koto language module for tracing script execution
+//!
+//! Scripts create [Span]s with `trace.span`, and emit events with `trace.event`. The spans and
+//! events are forwarded to a [TraceSubscriber] provided by the host application with
+//! [make_module_with_subscriber], allowing them to be bridged into the host's own tracing, e.g.
+//! with the `tracing` crate.
+//!
+//! Without a subscriber, spans can still be entered and exited, but nothing is emitted.
+
+mod span;
+mod subscriber;
+
+pub use crate::{
+    span::Span,
+    subscriber::{Attributes, SpanId, TraceSubscriber, TraceValue},
+};
+
+use crate::span::attributes_from_map;
+use koto_runtime::{make_ptr, prelude::*, Ptr, PtrMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub fn make_module() -> KMap {
+    make_tracer_module(None)
+}
+
+/// Makes the module with a subscriber that receives the script's spans and events
+pub fn make_module_with_subscriber(subscriber: impl TraceSubscriber + 'static) -> KMap {
+    make_tracer_module(Some(make_ptr!(subscriber)))
+}
+
+fn make_tracer_module(subscriber: Option<Ptr<dyn TraceSubscriber>>) -> KMap {
+    let result = KMap::with_type("trace");
+    let tracer: Ptr<Tracer> = Ptr::from(Tracer {
+        subscriber,
+        next_id: AtomicU64::new(1),
+        stack: PtrMut::default(),
+    });
+
+    result.add_fn("current", {
+        let tracer = tracer.clone();
+        move |ctx| match ctx.args() {
+            [] => Ok(tracer
+                .stack
+                .borrow()
+                .last()
+                .map_or(KValue::Null, |span| span.clone().into())),
+            unexpected => type_error_with_slice("no arguments", unexpected),
+        }
+    });
+
+    result.add_fn("event", {
+        let tracer = tracer.clone();
+        move |ctx| {
+            let (message, attributes) = match ctx.args() {
+                [KValue::Str(message)] => (message.clone(), Attributes::new()),
+                [KValue::Str(message), KValue::Map(attributes)] => {
+                    let attributes = attributes.clone();
+                    (message.clone(), attributes_from_map(ctx.vm, &attributes)?)
+                }
+                unexpected => {
+                    return type_error_with_slice(
+                        "a message String, with optional attributes Map",
+                        unexpected,
+                    )
+                }
+            };
+            if let Some(subscriber) = &tracer.subscriber {
+                subscriber.event(tracer.current_id(), &message, &attributes);
+            }
+            Ok(KValue::Null)
+        }
+    });
+
+    result.add_fn("span", move |ctx| {
+        let (name, attributes) = match ctx.args() {
+            [KValue::Str(name)] => (name.clone(), Attributes::new()),
+            [KValue::Str(name), KValue::Map(attributes)] => {
+                let attributes = attributes.clone();
+                (name.clone(), attributes_from_map(ctx.vm, &attributes)?)
+            }
+            unexpected => {
+                return type_error_with_slice(
+                    "a name String, with optional attributes Map",
+                    unexpected,
+                )
+            }
+        };
+        Ok(Span::new(tracer.clone(), name, &attributes).into())
+    });
+
+    result
+}
+
+// The state shared by the module's functions and spans
+struct Tracer {
+    subscriber: Option<Ptr<dyn TraceSubscriber>>,
+    next_id: AtomicU64,
+    // The spans that have been entered and not yet exited, with the current span last
+    stack: PtrMut<Vec<Span>>,
+}
+
+impl Tracer {
+    fn next_id(&self) -> SpanId {
+        self.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn current_id(&self) -> Option<SpanId> {
+        self.stack.borrow().last().map(Span::span_id)
+    }
+}
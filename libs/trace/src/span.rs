@@ -0,0 +1,203 @@
+use crate::{
+    subscriber::{Attributes, SpanId, TraceValue},
+    Tracer,
+};
+use koto_runtime::{derive::*, prelude::*, Ptr, PtrMut, Result};
+
+/// A span of script execution, created with `trace.span`
+///
+/// Spans are reported to the tracer's [crate::TraceSubscriber] when they're created, entered, exited,
+/// and when they're no longer referenced.
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "Span")]
+pub struct Span(PtrMut<SpanState>);
+
+struct SpanState {
+    id: SpanId,
+    name: KString,
+    tracer: Ptr<Tracer>,
+    // The number of times that the span has been entered without being exited
+    entered: usize,
+}
+
+impl Drop for SpanState {
+    fn drop(&mut self) {
+        if let Some(subscriber) = &self.tracer.subscriber {
+            subscriber.close(self.id);
+        }
+    }
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Span {
+    pub(crate) fn new(tracer: Ptr<Tracer>, name: KString, attributes: &Attributes) -> Self {
+        let id = tracer.next_id();
+        if let Some(subscriber) = &tracer.subscriber {
+            subscriber.new_span(id, tracer.current_id(), &name, attributes);
+        }
+        Self(PtrMut::from(SpanState {
+            id,
+            name,
+            tracer,
+            entered: 0,
+        }))
+    }
+
+    /// Returns the span's ID
+    pub fn span_id(&self) -> SpanId {
+        self.0.borrow().id
+    }
+
+    /// Enters the span, making it the current span
+    pub fn enter_span(&self) {
+        let tracer = {
+            let mut state = self.0.borrow_mut();
+            state.entered += 1;
+            state.tracer.clone()
+        };
+        tracer.stack.borrow_mut().push(self.clone());
+        if let Some(subscriber) = &tracer.subscriber {
+            subscriber.enter(self.span_id());
+        }
+    }
+
+    /// Exits the span
+    ///
+    /// An error is returned if the span hasn't been entered.
+    pub fn exit_span(&self) -> Result<()> {
+        let id = self.span_id();
+        let tracer = {
+            let mut state = self.0.borrow_mut();
+            if state.entered == 0 {
+                return runtime_error!("span '{}' hasn't been entered", state.name);
+            }
+            state.entered -= 1;
+            state.tracer.clone()
+        };
+        {
+            let mut stack = tracer.stack.borrow_mut();
+            if let Some(position) = stack.iter().rposition(|span| span.span_id() == id) {
+                stack.remove(position);
+            }
+        }
+        if let Some(subscriber) = &tracer.subscriber {
+            subscriber.exit(id);
+        }
+        Ok(())
+    }
+
+    #[koto_method]
+    fn enter(ctx: MethodContext<Self>) -> Result<KValue> {
+        ctx.instance()?.enter_span();
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn exit(ctx: MethodContext<Self>) -> Result<KValue> {
+        ctx.instance()?.exit_span()?;
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn id(&self) -> KValue {
+        KValue::Number((self.span_id() as i64).into())
+    }
+
+    #[koto_method]
+    fn is_entered(&self) -> KValue {
+        (self.0.borrow().entered > 0).into()
+    }
+
+    #[koto_method]
+    fn name(&self) -> KValue {
+        self.0.borrow().name.clone().into()
+    }
+
+    #[koto_method]
+    fn record(ctx: MethodContext<Self>) -> Result<KValue> {
+        let mut vm = ctx.vm.spawn_shared_vm();
+        let attributes = match ctx.args {
+            [KValue::Str(name), value] => {
+                vec![(name.to_string(), trace_value(&mut vm, value)?)]
+            }
+            [KValue::Map(attributes)] => attributes_from_map(&mut vm, attributes)?,
+            unexpected => {
+                return type_error_with_slice(
+                    "an attribute name and value, or a Map of attributes",
+                    unexpected,
+                )
+            }
+        };
+
+        let this = ctx.instance()?;
+        let state = this.0.borrow();
+        if let Some(subscriber) = &state.tracer.subscriber {
+            for (name, value) in attributes.iter() {
+                subscriber.record(state.id, name, value);
+            }
+        }
+        drop(state);
+
+        ctx.instance_result()
+    }
+
+    #[koto_method]
+    fn run(ctx: MethodContext<Self>) -> Result<KValue> {
+        let f = match ctx.args {
+            [f] if f.is_callable() => f.clone(),
+            unexpected => return type_error_with_slice("a Function", unexpected),
+        };
+
+        let this = ctx.instance()?.clone();
+        this.enter_span();
+        // The span is exited even if the function throws an error
+        let result = ctx.vm.spawn_shared_vm().call_function(f, &[]);
+        this.exit_span()?;
+        result
+    }
+}
+
+impl KotoObject for Span {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(format!("Span({})", self.0.borrow().name));
+        Ok(())
+    }
+}
+
+impl From<Span> for KValue {
+    fn from(span: Span) -> Self {
+        KObject::from(span).into()
+    }
+}
+
+/// Converts a Koto map into a list of attributes
+pub(crate) fn attributes_from_map(vm: &mut KotoVm, map: &KMap) -> Result<Attributes> {
+    // The map's entries are cloned so that the map isn't borrowed while values are being
+    // converted to strings.
+    let entries: Vec<(ValueKey, KValue)> = map
+        .data()
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+
+    let mut result = Attributes::with_capacity(entries.len());
+    for (key, value) in entries {
+        let name = match key.value() {
+            KValue::Str(name) => name.to_string(),
+            unexpected => return type_error("a String as attribute name", unexpected),
+        };
+        result.push((name, trace_value(vm, &value)?));
+    }
+    Ok(result)
+}
+
+fn trace_value(vm: &mut KotoVm, value: &KValue) -> Result<TraceValue> {
+    let result = match value {
+        KValue::Bool(b) => TraceValue::Bool(*b),
+        KValue::Number(KNumber::I64(n)) => TraceValue::Int(*n),
+        KValue::Number(KNumber::F64(n)) => TraceValue::Float(*n),
+        KValue::Str(s) => TraceValue::Str(s.to_string()),
+        other => TraceValue::Str(vm.value_to_string(other)?),
+    };
+    Ok(result)
+}
@@ -0,0 +1,60 @@
+use koto_runtime::{KotoSend, KotoSync};
+use std::fmt;
+
+/// An identifier for a span, unique within a tracing module
+pub type SpanId = u64;
+
+/// A list of attribute names and values
+pub type Attributes = Vec<(String, TraceValue)>;
+
+/// An attribute value attached to a span or event
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceValue {
+    /// A bool
+    Bool(bool),
+    /// An integer
+    Int(i64),
+    /// A float
+    Float(f64),
+    /// A string, used for any value that isn't a bool or number
+    Str(String),
+}
+
+impl fmt::Display for TraceValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Int(n) => write!(f, "{n}"),
+            Self::Float(n) => write!(f, "{n}"),
+            Self::Str(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+/// Receives the spans and events produced by scripts
+///
+/// Host applications implement this trait to forward script traces into their own tracing
+/// infrastructure, e.g. by creating a `tracing::Span` for each of the script's spans, and then
+/// entering and exiting it when notified.
+///
+/// Spans are created with the ID of their parent span, which is the span that was most recently
+/// entered and not yet exited.
+pub trait TraceSubscriber: KotoSend + KotoSync {
+    /// Called when a span is created
+    fn new_span(&self, id: SpanId, parent: Option<SpanId>, name: &str, attributes: &Attributes);
+
+    /// Called when an attribute is recorded on a span after it was created
+    fn record(&self, _id: SpanId, _name: &str, _value: &TraceValue) {}
+
+    /// Called when a span is entered
+    fn enter(&self, id: SpanId);
+
+    /// Called when a span is exited
+    fn exit(&self, id: SpanId);
+
+    /// Called when a span is no longer referenced by the script
+    fn close(&self, _id: SpanId) {}
+
+    /// Called when the script emits an event, along with the ID of the current span
+    fn event(&self, span: Option<SpanId>, message: &str, attributes: &Attributes);
+}
@@ -0,0 +1,137 @@
+use koto_runtime::{prelude::*, Result};
+use koto_test_utils::check_script_output_with_vm;
+use koto_trace::{make_module_with_subscriber, Attributes, SpanId, TraceSubscriber, TraceValue};
+use std::sync::{Arc, Mutex};
+
+// Records each notification as a line of text
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+impl Recorder {
+    fn push(&self, line: String) {
+        self.0.lock().unwrap().push(line);
+    }
+
+    fn lines(&self) -> Vec<String> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+fn format_attributes(attributes: &Attributes) -> String {
+    attributes
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl TraceSubscriber for Recorder {
+    fn new_span(&self, id: SpanId, parent: Option<SpanId>, name: &str, attributes: &Attributes) {
+        self.push(format!(
+            "new {id} {name} parent={parent:?} [{}]",
+            format_attributes(attributes)
+        ));
+    }
+
+    fn record(&self, id: SpanId, name: &str, value: &TraceValue) {
+        self.push(format!("record {id} {name}={value:?}"));
+    }
+
+    fn enter(&self, id: SpanId) {
+        self.push(format!("enter {id}"));
+    }
+
+    fn exit(&self, id: SpanId) {
+        self.push(format!("exit {id}"));
+    }
+
+    fn close(&self, id: SpanId) {
+        self.push(format!("close {id}"));
+    }
+
+    fn event(&self, span: Option<SpanId>, message: &str, attributes: &Attributes) {
+        self.push(format!(
+            "event {message} span={span:?} [{}]",
+            format_attributes(attributes)
+        ));
+    }
+}
+
+fn run_script(script: &str) -> Result<Vec<String>> {
+    let recorder = Recorder::default();
+    let vm = KotoVm::default();
+    vm.prelude()
+        .insert("trace", make_module_with_subscriber(recorder.clone()));
+    check_script_output_with_vm(vm, script, KValue::Null)?;
+    Ok(recorder.lines())
+}
+
+#[test]
+fn nested_spans() -> Result<()> {
+    let script = "
+trace.span('outer', {n: 1, x: 0.5, ok: true}).run ||
+  trace.event 'started'
+  trace.span('inner', {items: [1, 2]}).run ||
+    trace.event 'working', {step: 'a'}
+null
+";
+    assert_eq!(
+        run_script(script)?,
+        [
+            "new 1 outer parent=None [n=1 x=0.5 ok=true]",
+            "enter 1",
+            "event started span=Some(1) []",
+            "new 2 inner parent=Some(1) [items=[1, 2]]",
+            "enter 2",
+            "event working span=Some(2) [step=a]",
+            "exit 2",
+            "close 2",
+            "exit 1",
+            "close 1",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn record_attributes() -> Result<()> {
+    let script = "
+span = trace.span 'request'
+span.record 'status', 200
+span.record {cached: false}
+null
+";
+    assert_eq!(
+        run_script(script)?,
+        [
+            "new 1 request parent=None []",
+            "record 1 status=Int(200)",
+            "record 1 cached=Bool(false)",
+            "close 1",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn spans_are_exited_when_errors_are_thrown() -> Result<()> {
+    let script = "
+span = trace.span 'failing'
+try
+  span.run || throw 'oops'
+catch _
+  trace.event 'caught'
+null
+";
+    assert_eq!(
+        run_script(script)?,
+        [
+            "new 1 failing parent=None []",
+            "enter 1",
+            "exit 1",
+            "event caught span=None []",
+            "close 1",
+        ]
+    );
+    Ok(())
+}
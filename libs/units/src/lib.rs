@@ -0,0 +1,87 @@
+//! A Koto language module for working with unit-aware quantities
+//!
+//! Quantities combine a number with a unit, and arithmetic between quantities checks that the
+//! units are compatible, converting values between units where needed.
+
+mod quantity;
+mod unit;
+
+pub use crate::{
+    quantity::Quantity,
+    unit::{Dimensions, Unit, UnitTable},
+};
+
+use koto_runtime::{prelude::*, PtrMut, Result};
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("units");
+    let table = PtrMut::from(UnitTable::default());
+
+    {
+        let table = table.clone();
+        result.add_fn("convert", move |ctx| match ctx.args() {
+            [KValue::Number(value), KValue::Str(from), KValue::Str(to)] => {
+                let table = table.borrow();
+                let from = table.parse(from)?;
+                let to = table.parse(to)?;
+                Ok(from.convert(*value, &to)?.into())
+            }
+            unexpected => type_error_with_slice(
+                "a Number, followed by the unit to convert from and the unit to convert to",
+                unexpected,
+            ),
+        });
+    }
+
+    {
+        let table = table.clone();
+        result.add_fn("define", move |ctx| match ctx.args() {
+            [KValue::Str(name), KValue::Object(o)] if o.is_a::<Quantity>() => {
+                let quantity = o.cast::<Quantity>()?;
+                let unit = quantity.unit();
+                let factor = f64::from(quantity.value()) * unit.factor;
+                table.borrow_mut().define(name, factor, unit.dimensions)?;
+                Ok(KValue::Null)
+            }
+            unexpected => type_error_with_slice(
+                "a unit name, followed by a Quantity that defines the unit",
+                unexpected,
+            ),
+        });
+    }
+
+    {
+        let table = table.clone();
+        result.add_fn("parse", move |ctx| match ctx.args() {
+            [KValue::Str(s)] => parse_quantity(s, &table),
+            unexpected => type_error_with_slice("a String", unexpected),
+        });
+    }
+
+    result.add_fn("quantity", move |ctx| match ctx.args() {
+        [KValue::Number(value), KValue::Str(unit)] => {
+            let unit = table.borrow().parse(unit)?;
+            Ok(Quantity::new(*value, unit, table.clone()).into())
+        }
+        unexpected => type_error_with_slice("a Number and a unit String", unexpected),
+    });
+
+    result
+}
+
+fn parse_quantity(s: &str, table: &PtrMut<UnitTable>) -> Result<KValue> {
+    let Some((value, unit)) = s.trim().split_once(char::is_whitespace) else {
+        return runtime_error!("expected a value followed by a unit, found '{s}'");
+    };
+
+    let value = if let Ok(n) = value.parse::<i64>() {
+        KNumber::from(n)
+    } else if let Ok(n) = value.parse::<f64>() {
+        KNumber::from(n)
+    } else {
+        return runtime_error!("invalid value in '{s}'");
+    };
+
+    let unit = table.borrow().parse(unit)?;
+    Ok(Quantity::new(value, unit, table.clone()).into())
+}
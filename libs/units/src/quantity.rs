@@ -0,0 +1,219 @@
+use crate::{Unit, UnitTable};
+use koto_runtime::{derive::*, prelude::*, PtrMut, Result};
+
+/// A numeric value with a unit, created with `units.quantity`
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "Quantity")]
+pub struct Quantity {
+    value: KNumber,
+    unit: Unit,
+    table: PtrMut<UnitTable>,
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Quantity {
+    pub(crate) fn new(value: KNumber, unit: Unit, table: PtrMut<UnitTable>) -> Self {
+        Self { value, unit, table }
+    }
+
+    /// Returns the quantity's value, in the quantity's unit
+    pub fn value(&self) -> KNumber {
+        self.value
+    }
+
+    /// Returns the quantity's unit
+    pub fn unit(&self) -> &Unit {
+        &self.unit
+    }
+
+    /// Returns the quantity's value converted to the given unit
+    ///
+    /// An error is returned if the units are incompatible.
+    pub fn value_in(&self, unit: &Unit) -> Result<KNumber> {
+        self.unit.convert(self.value, unit)
+    }
+
+    fn with_value(&self, value: KNumber) -> Self {
+        Self::new(value, self.unit.clone(), self.table.clone())
+    }
+
+    // Makes a quantity from the result of multiplying or dividing two quantities
+    //
+    // If the result has no dimensions then it's returned as a Number.
+    fn make_combined(&self, value: KNumber, unit: Unit) -> KValue {
+        if unit.is_dimensionless() {
+            if unit.factor == 1.0 {
+                value.into()
+            } else {
+                (f64::from(value) * unit.factor).into()
+            }
+        } else {
+            Self::new(value, unit, self.table.clone()).into()
+        }
+    }
+
+    fn rhs_value(&self, rhs: &KValue, op: &str) -> Result<KNumber> {
+        match rhs {
+            KValue::Object(o) => match o.cast::<Self>() {
+                Ok(rhs) => rhs.value_in(&self.unit),
+                Err(_) => unexpected_rhs(op, rhs),
+            },
+            _ => unexpected_rhs(op, rhs),
+        }
+    }
+
+    fn compare(&self, rhs: &KValue, op: &str) -> Result<(f64, f64)> {
+        let rhs = self.rhs_value(rhs, op)?;
+        Ok((self.value.into(), rhs.into()))
+    }
+
+    /// Formats the quantity, with an optional number of decimal places for the value
+    pub fn format(&self, precision: Option<usize>) -> String {
+        match precision {
+            Some(precision) => format!("{:.precision$} {}", f64::from(self.value), self.unit.name),
+            None => format!("{} {}", self.value, self.unit.name),
+        }
+    }
+
+    #[koto_method(alias = "value")]
+    fn value_koto(&self) -> KValue {
+        self.value.into()
+    }
+
+    #[koto_method(alias = "unit")]
+    fn unit_koto(&self) -> KValue {
+        self.unit.name.as_str().into()
+    }
+
+    #[koto_method]
+    fn to(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Str(unit)] => {
+                let unit = self.table.borrow().parse(unit)?;
+                let value = self.value_in(&unit)?;
+                Ok(Self::new(value, unit, self.table.clone()).into())
+            }
+            unexpected => type_error_with_slice("a unit String", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn is_compatible(&self, args: &[KValue]) -> Result<KValue> {
+        let compatible = match args {
+            [KValue::Str(unit)] => {
+                let unit = self.table.borrow().parse(unit)?;
+                self.unit.is_compatible(&unit)
+            }
+            [KValue::Object(o)] if o.is_a::<Self>() => {
+                self.unit.is_compatible(&o.cast::<Self>()?.unit)
+            }
+            unexpected => return type_error_with_slice("a unit String or Quantity", unexpected),
+        };
+        Ok(compatible.into())
+    }
+
+    #[koto_method(alias = "format")]
+    fn format_koto(&self, args: &[KValue]) -> Result<KValue> {
+        let precision = match args {
+            [] => None,
+            [KValue::Number(n)] if *n >= 0 => Some(n.into()),
+            unexpected => return type_error_with_slice("an optional precision", unexpected),
+        };
+        Ok(self.format(precision).into())
+    }
+}
+
+impl KotoObject for Quantity {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        ctx.append(self.format(None));
+        Ok(())
+    }
+
+    fn negate(&self, _vm: &mut KotoVm) -> Result<KValue> {
+        Ok(self.with_value(-self.value).into())
+    }
+
+    fn add(&self, rhs: &KValue) -> Result<KValue> {
+        let rhs = self.rhs_value(rhs, "+")?;
+        Ok(self.with_value(self.value + rhs).into())
+    }
+
+    fn subtract(&self, rhs: &KValue) -> Result<KValue> {
+        let rhs = self.rhs_value(rhs, "-")?;
+        Ok(self.with_value(self.value - rhs).into())
+    }
+
+    fn multiply(&self, rhs: &KValue) -> Result<KValue> {
+        match rhs {
+            KValue::Number(n) => Ok(self.with_value(self.value * *n).into()),
+            KValue::Object(o) if o.is_a::<Self>() => {
+                let rhs = o.cast::<Self>()?;
+                let unit = self.unit.multiply(&rhs.unit);
+                Ok(self.make_combined(self.value * rhs.value, unit))
+            }
+            _ => unexpected_rhs("*", rhs),
+        }
+    }
+
+    fn divide(&self, rhs: &KValue) -> Result<KValue> {
+        match rhs {
+            KValue::Number(n) => Ok(self.with_value(self.value / *n).into()),
+            KValue::Object(o) if o.is_a::<Self>() => {
+                let rhs = o.cast::<Self>()?;
+                let unit = self.unit.divide(&rhs.unit);
+                Ok(self.make_combined(self.value / rhs.value, unit))
+            }
+            _ => unexpected_rhs("/", rhs),
+        }
+    }
+
+    fn less(&self, rhs: &KValue) -> Result<bool> {
+        let (a, b) = self.compare(rhs, "<")?;
+        Ok(a < b)
+    }
+
+    fn less_or_equal(&self, rhs: &KValue) -> Result<bool> {
+        let (a, b) = self.compare(rhs, "<=")?;
+        Ok(a <= b)
+    }
+
+    fn greater(&self, rhs: &KValue) -> Result<bool> {
+        let (a, b) = self.compare(rhs, ">")?;
+        Ok(a > b)
+    }
+
+    fn greater_or_equal(&self, rhs: &KValue) -> Result<bool> {
+        let (a, b) = self.compare(rhs, ">=")?;
+        Ok(a >= b)
+    }
+
+    fn equal(&self, rhs: &KValue) -> Result<bool> {
+        match rhs {
+            KValue::Object(o) if o.is_a::<Self>() => {
+                let rhs = o.cast::<Self>()?;
+                match rhs.value_in(&self.unit) {
+                    Ok(rhs) => Ok(f64::from(self.value) == f64::from(rhs)),
+                    Err(_) => Ok(false),
+                }
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn not_equal(&self, rhs: &KValue) -> Result<bool> {
+        self.equal(rhs).map(|result| !result)
+    }
+}
+
+impl From<Quantity> for KValue {
+    fn from(quantity: Quantity) -> Self {
+        KObject::from(quantity).into()
+    }
+}
+
+fn unexpected_rhs<T>(op: &str, rhs: &KValue) -> Result<T> {
+    runtime_error!(
+        "unexpected '{}' as the right-hand side of '{op}' with a Quantity",
+        rhs.type_as_string()
+    )
+}
@@ -0,0 +1,269 @@
+use koto_runtime::{prelude::*, Result};
+use std::collections::HashMap;
+
+/// The exponents of the base dimensions of a unit
+///
+/// The dimensions are, in order: length, mass, time, electric current, temperature, amount of
+/// substance, and luminous intensity.
+pub type Dimensions = [i8; 7];
+
+const DIMENSIONLESS: Dimensions = [0; 7];
+
+/// A unit of measurement
+#[derive(Clone, Debug, PartialEq)]
+pub struct Unit {
+    /// The unit's name, as used when displaying quantities
+    pub name: String,
+    /// The factor that converts values in this unit to SI base units
+    pub factor: f64,
+    /// The unit's dimensions
+    pub dimensions: Dimensions,
+}
+
+impl Unit {
+    /// Returns true if values in the units can be converted between each other
+    pub fn is_compatible(&self, other: &Unit) -> bool {
+        self.dimensions == other.dimensions
+    }
+
+    /// Returns true if the unit has no dimensions, e.g. after dividing a length by a length
+    pub fn is_dimensionless(&self) -> bool {
+        self.dimensions == DIMENSIONLESS
+    }
+
+    /// Converts a value in this unit to the given unit
+    ///
+    /// An error is returned if the units are incompatible.
+    pub fn convert(&self, value: KNumber, to: &Unit) -> Result<KNumber> {
+        if !self.is_compatible(to) {
+            return runtime_error!("incompatible units: '{}' and '{}'", self.name, to.name);
+        }
+
+        if self.factor == to.factor {
+            Ok(value)
+        } else {
+            Ok(remove_rounding_error(f64::from(value) * self.factor / to.factor).into())
+        }
+    }
+
+    /// Returns the product of the two units
+    pub fn multiply(&self, other: &Unit) -> Unit {
+        Unit {
+            name: format!("{}*{}", self.name, other.name),
+            factor: self.factor * other.factor,
+            dimensions: combine_dimensions(&self.dimensions, &other.dimensions, 1),
+        }
+    }
+
+    /// Returns the quotient of the two units
+    pub fn divide(&self, other: &Unit) -> Unit {
+        // Units are parsed from left to right, so the operators in the divisor are flipped,
+        // e.g. `m / (s*s)` is named `m/s/s`.
+        let divisor: String = other
+            .name
+            .chars()
+            .map(|c| match c {
+                '*' => '/',
+                '/' => '*',
+                other => other,
+            })
+            .collect();
+        Unit {
+            name: format!("{}/{divisor}", self.name),
+            factor: self.factor / other.factor,
+            dimensions: combine_dimensions(&self.dimensions, &other.dimensions, -1),
+        }
+    }
+
+    fn pow(&self, exponent: i8) -> Unit {
+        Unit {
+            name: self.name.clone(),
+            factor: self.factor.powi(exponent.into()),
+            dimensions: self.dimensions.map(|d| d * exponent),
+        }
+    }
+}
+
+// Conversion factors often can't be represented exactly, so converted values are rounded to 15
+// significant digits to avoid results like `36.00000000000001`.
+fn remove_rounding_error(value: f64) -> f64 {
+    if value.is_finite() {
+        format!("{value:.14e}").parse().unwrap_or(value)
+    } else {
+        value
+    }
+}
+
+fn combine_dimensions(a: &Dimensions, b: &Dimensions, sign: i8) -> Dimensions {
+    let mut result = *a;
+    for (result, b) in result.iter_mut().zip(b.iter()) {
+        *result += b * sign;
+    }
+    result
+}
+
+/// The table of named units that are available for parsing
+pub struct UnitTable {
+    units: HashMap<String, (f64, Dimensions)>,
+}
+
+impl Default for UnitTable {
+    fn default() -> Self {
+        let mut units = HashMap::new();
+
+        let mut add = |names: &[&str], factor: f64, dimensions: Dimensions| {
+            for name in names {
+                units.insert(name.to_string(), (factor, dimensions));
+            }
+        };
+
+        const LENGTH: Dimensions = [1, 0, 0, 0, 0, 0, 0];
+        const MASS: Dimensions = [0, 1, 0, 0, 0, 0, 0];
+        const TIME: Dimensions = [0, 0, 1, 0, 0, 0, 0];
+        const CURRENT: Dimensions = [0, 0, 0, 1, 0, 0, 0];
+        const TEMPERATURE: Dimensions = [0, 0, 0, 0, 1, 0, 0];
+        const AMOUNT: Dimensions = [0, 0, 0, 0, 0, 1, 0];
+        const LUMINOSITY: Dimensions = [0, 0, 0, 0, 0, 0, 1];
+        const AREA: Dimensions = [2, 0, 0, 0, 0, 0, 0];
+        const VOLUME: Dimensions = [3, 0, 0, 0, 0, 0, 0];
+        const SPEED: Dimensions = [1, 0, -1, 0, 0, 0, 0];
+        const FREQUENCY: Dimensions = [0, 0, -1, 0, 0, 0, 0];
+        const FORCE: Dimensions = [1, 1, -2, 0, 0, 0, 0];
+        const PRESSURE: Dimensions = [-1, 1, -2, 0, 0, 0, 0];
+        const ENERGY: Dimensions = [2, 1, -2, 0, 0, 0, 0];
+        const POWER: Dimensions = [2, 1, -3, 0, 0, 0, 0];
+        const CHARGE: Dimensions = [0, 0, 1, 1, 0, 0, 0];
+        const VOLTAGE: Dimensions = [2, 1, -3, -1, 0, 0, 0];
+
+        add(&["1"], 1.0, DIMENSIONLESS);
+
+        add(&["m"], 1.0, LENGTH);
+        add(&["km"], 1e3, LENGTH);
+        add(&["cm"], 1e-2, LENGTH);
+        add(&["mm"], 1e-3, LENGTH);
+        add(&["um"], 1e-6, LENGTH);
+        add(&["nm"], 1e-9, LENGTH);
+        add(&["in"], 0.0254, LENGTH);
+        add(&["ft"], 0.3048, LENGTH);
+        add(&["yd"], 0.9144, LENGTH);
+        add(&["mi"], 1609.344, LENGTH);
+        add(&["nmi"], 1852.0, LENGTH);
+
+        add(&["kg"], 1.0, MASS);
+        add(&["g"], 1e-3, MASS);
+        add(&["mg"], 1e-6, MASS);
+        add(&["t"], 1e3, MASS);
+        add(&["lb"], 0.453_592_37, MASS);
+        add(&["oz"], 0.028_349_523_125, MASS);
+
+        add(&["s"], 1.0, TIME);
+        add(&["ms"], 1e-3, TIME);
+        add(&["us"], 1e-6, TIME);
+        add(&["ns"], 1e-9, TIME);
+        add(&["min"], 60.0, TIME);
+        add(&["h"], 3600.0, TIME);
+        add(&["day"], 86400.0, TIME);
+        add(&["week"], 604800.0, TIME);
+
+        add(&["A"], 1.0, CURRENT);
+        add(&["mA"], 1e-3, CURRENT);
+        add(&["K"], 1.0, TEMPERATURE);
+        add(&["mol"], 1.0, AMOUNT);
+        add(&["cd"], 1.0, LUMINOSITY);
+
+        add(&["ha"], 1e4, AREA);
+        add(&["L"], 1e-3, VOLUME);
+        add(&["mL"], 1e-6, VOLUME);
+        add(&["mph"], 0.44704, SPEED);
+        add(&["kn"], 1852.0 / 3600.0, SPEED);
+        add(&["Hz"], 1.0, FREQUENCY);
+        add(&["kHz"], 1e3, FREQUENCY);
+        add(&["N"], 1.0, FORCE);
+        add(&["kN"], 1e3, FORCE);
+        add(&["Pa"], 1.0, PRESSURE);
+        add(&["kPa"], 1e3, PRESSURE);
+        add(&["bar"], 1e5, PRESSURE);
+        add(&["J"], 1.0, ENERGY);
+        add(&["kJ"], 1e3, ENERGY);
+        add(&["Wh"], 3600.0, ENERGY);
+        add(&["kWh"], 3.6e6, ENERGY);
+        add(&["W"], 1.0, POWER);
+        add(&["kW"], 1e3, POWER);
+        add(&["C"], 1.0, CHARGE);
+        add(&["V"], 1.0, VOLTAGE);
+
+        Self { units }
+    }
+}
+
+impl UnitTable {
+    /// Adds a named unit to the table, replacing any existing unit with the same name
+    pub fn define(&mut self, name: &str, factor: f64, dimensions: Dimensions) -> Result<()> {
+        if !is_valid_unit_name(name) {
+            return runtime_error!("invalid unit name '{name}'");
+        }
+        self.units.insert(name.to_string(), (factor, dimensions));
+        Ok(())
+    }
+
+    /// Parses a unit, e.g. `km`, `m/s^2`, or `kg*m/s^2`
+    ///
+    /// Terms are applied from left to right, so `m/s/s` is equivalent to `m/s^2`.
+    pub fn parse(&self, unit: &str) -> Result<Unit> {
+        let unit = unit.trim();
+        let mut result = Unit {
+            name: unit.to_string(),
+            factor: 1.0,
+            dimensions: DIMENSIONLESS,
+        };
+
+        let mut divide = false;
+        let mut rest = unit;
+        loop {
+            let end = rest.find(['*', '/']).unwrap_or(rest.len());
+            let term = self.parse_term(rest[..end].trim(), unit)?;
+            let combined = if divide {
+                result.divide(&term)
+            } else {
+                result.multiply(&term)
+            };
+            result.factor = combined.factor;
+            result.dimensions = combined.dimensions;
+
+            match rest[end..].chars().next() {
+                Some(op) => {
+                    divide = op == '/';
+                    rest = &rest[end + 1..];
+                }
+                None => break,
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn parse_term(&self, term: &str, unit: &str) -> Result<Unit> {
+        let (name, exponent) = match term.split_once('^') {
+            Some((name, exponent)) => match exponent.trim().parse::<i8>() {
+                Ok(exponent) => (name.trim(), exponent),
+                Err(_) => return runtime_error!("invalid exponent in unit '{unit}'"),
+            },
+            None => (term, 1),
+        };
+
+        match self.units.get(name) {
+            Some((factor, dimensions)) => Ok(Unit {
+                name: name.to_string(),
+                factor: *factor,
+                dimensions: *dimensions,
+            }
+            .pow(exponent)),
+            None if name.is_empty() => runtime_error!("missing unit in '{unit}'"),
+            None => runtime_error!("unknown unit '{name}'"),
+        }
+    }
+}
+
+fn is_valid_unit_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
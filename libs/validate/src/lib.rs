@@ -0,0 +1,60 @@
+//! A Koto language module for validating data with reusable validators
+//!
+//! Validators are built from combinators, e.g. `validate.string().min_len(3)`, and report all of
+//! the violations found in a value in a single pass, along with the paths to the invalid values.
+
+mod validator;
+
+pub use validator::{Constraint, Validator, ValidatorKind, Violation};
+
+use koto_runtime::prelude::*;
+
+pub fn make_module() -> KMap {
+    let result = KMap::with_type("validate");
+
+    macro_rules! add_simple_validator {
+        ($name:expr, $kind:ident) => {
+            result.add_fn($name, |ctx| match ctx.args() {
+                [] => Ok(Validator::new(ValidatorKind::$kind).into()),
+                unexpected => type_error_with_slice("no arguments", unexpected),
+            });
+        };
+    }
+
+    add_simple_validator!("any", Any);
+    add_simple_validator!("bool", Bool);
+    add_simple_validator!("int", Int);
+
+    result.add_fn("list", |ctx| match ctx.args() {
+        [] => Ok(Validator::new(ValidatorKind::List(None)).into()),
+        [KValue::Object(o)] if o.is_a::<Validator>() => {
+            let item_validator = o.cast::<Validator>()?.clone();
+            Ok(Validator::new(ValidatorKind::List(Some(item_validator))).into())
+        }
+        unexpected => type_error_with_slice("an optional Validator for the items", unexpected),
+    });
+
+    result.add_fn("map", |ctx| match ctx.args() {
+        [] => Ok(Validator::new(ValidatorKind::Map(Vec::new())).into()),
+        [KValue::Map(schema)] => {
+            let mut fields = Vec::with_capacity(schema.len());
+            for (key, value) in schema.data().iter() {
+                match (key.value(), value) {
+                    (KValue::Str(key), KValue::Object(o)) if o.is_a::<Validator>() => {
+                        fields.push((key.clone(), o.cast::<Validator>()?.clone()));
+                    }
+                    (_, unexpected) => {
+                        return type_error("a Validator for each field in the schema", unexpected)
+                    }
+                }
+            }
+            Ok(Validator::new(ValidatorKind::Map(fields)).into())
+        }
+        unexpected => type_error_with_slice("an optional schema Map", unexpected),
+    });
+
+    add_simple_validator!("number", Number);
+    add_simple_validator!("string", String);
+
+    result
+}
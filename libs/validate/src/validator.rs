@@ -0,0 +1,465 @@
+use koto_runtime::{core_lib::koto::KResult, derive::*, prelude::*, Ptr, Result};
+use regex::Regex;
+use std::fmt::Write;
+
+/// A reusable validator, created by the `validate` module's constructors
+///
+/// Validators are immutable, each constraint method returns a new validator with the
+/// constraint added.
+#[derive(Clone, KotoType, KotoCopy)]
+#[koto(type_name = "Validator")]
+pub struct Validator(Ptr<ValidatorData>);
+
+#[derive(Clone)]
+struct ValidatorData {
+    kind: ValidatorKind,
+    constraints: Vec<Constraint>,
+    optional: bool,
+    strict: bool,
+}
+
+/// The type of value that's accepted by a [Validator]
+#[derive(Clone)]
+pub enum ValidatorKind {
+    /// Any value, including `null`
+    Any,
+    /// Bools
+    Bool,
+    /// Integers
+    Int,
+    /// Lists, with an optional validator for the list's items
+    List(Option<Validator>),
+    /// Maps, with validators for each of the entries in the map's schema
+    Map(Vec<(KString, Validator)>),
+    /// Numbers
+    Number,
+    /// Strings
+    String,
+}
+
+/// A constraint that's checked by a [Validator]
+#[derive(Clone)]
+pub enum Constraint {
+    /// A minimum length for strings and containers
+    MinLen(usize),
+    /// A maximum length for strings and containers
+    MaxLen(usize),
+    /// A minimum value for numbers
+    Min(KNumber),
+    /// A maximum value for numbers
+    Max(KNumber),
+    /// A pattern that strings need to match
+    Pattern(Regex),
+    /// A set of values, one of which should be equal to the validated value
+    OneOf(KTuple),
+    /// A custom check function, along with a message to report if the check fails
+    Check {
+        /// The function that performs the check
+        f: KValue,
+        /// The message that's reported when the check fails
+        message: KString,
+    },
+}
+
+/// A single violation found during validation
+pub struct Violation {
+    /// The path to the invalid value, e.g. `users[0].name`
+    ///
+    /// The path is empty when the violation applies to the validated value itself.
+    pub path: String,
+    /// A description of the violation
+    pub message: String,
+}
+
+impl From<Violation> for KValue {
+    fn from(violation: Violation) -> Self {
+        let result = KMap::with_capacity(2);
+        result.insert("path", violation.path);
+        result.insert("message", violation.message);
+        result.into()
+    }
+}
+
+// An element in the path to a value that's being validated
+#[derive(Clone)]
+enum PathElement {
+    Key(KString),
+    Index(usize),
+}
+
+fn render_path(path: &[PathElement]) -> String {
+    let mut result = String::new();
+    for element in path {
+        match element {
+            PathElement::Key(key) => {
+                if !result.is_empty() {
+                    result.push('.');
+                }
+                result.push_str(key);
+            }
+            PathElement::Index(i) => {
+                let _ = write!(result, "[{i}]");
+            }
+        }
+    }
+    result
+}
+
+#[koto_impl(runtime = koto_runtime)]
+impl Validator {
+    pub(crate) fn new(kind: ValidatorKind) -> Self {
+        Self(
+            ValidatorData {
+                kind,
+                constraints: Vec::new(),
+                optional: false,
+                strict: false,
+            }
+            .into(),
+        )
+    }
+
+    /// Returns the type of value that's accepted by the validator
+    pub fn kind(&self) -> &ValidatorKind {
+        &self.0.kind
+    }
+
+    /// Returns the validator's constraints
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.0.constraints
+    }
+
+    /// Returns true if the validator accepts `null` or missing values
+    pub fn is_optional(&self) -> bool {
+        self.0.optional
+    }
+
+    /// Validates the value, returning any violations that were found
+    pub fn violations(&self, value: &KValue, vm: &mut KotoVm) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+        self.validate_value(value, &mut Vec::new(), &mut violations, vm)?;
+        Ok(violations)
+    }
+
+    fn validate_value(
+        &self,
+        value: &KValue,
+        path: &mut Vec<PathElement>,
+        violations: &mut Vec<Violation>,
+        vm: &mut KotoVm,
+    ) -> Result<()> {
+        let mut add_violation = |message: String| {
+            violations.push(Violation {
+                path: render_path(path),
+                message,
+            })
+        };
+
+        if matches!(value, KValue::Null) {
+            if !(self.0.optional || matches!(self.0.kind, ValidatorKind::Any)) {
+                add_violation("a value is required".into());
+            }
+            return Ok(());
+        }
+
+        let expected_type = match &self.0.kind {
+            ValidatorKind::Any => None,
+            ValidatorKind::Bool if !matches!(value, KValue::Bool(_)) => Some("a Bool"),
+            ValidatorKind::Int if !matches!(value, KValue::Number(n) if n.is_i64()) => {
+                Some("an Int")
+            }
+            ValidatorKind::List(_) if !matches!(value, KValue::List(_)) => Some("a List"),
+            ValidatorKind::Map(_) if !matches!(value, KValue::Map(_)) => Some("a Map"),
+            ValidatorKind::Number if !matches!(value, KValue::Number(_)) => Some("a Number"),
+            ValidatorKind::String if !matches!(value, KValue::Str(_)) => Some("a String"),
+            _ => None,
+        };
+        if let Some(expected) = expected_type {
+            // Constraints aren't checked when the value has the wrong type
+            add_violation(format!(
+                "expected {expected}, found {}",
+                value.type_as_string()
+            ));
+            return Ok(());
+        }
+
+        for constraint in self.0.constraints.iter() {
+            if let Some(message) = constraint.check(value, vm)? {
+                add_violation(message);
+            }
+        }
+
+        match (&self.0.kind, value) {
+            (ValidatorKind::List(Some(item_validator)), KValue::List(list)) => {
+                let items = list.data().clone();
+                for (i, item) in items.iter().enumerate() {
+                    path.push(PathElement::Index(i));
+                    item_validator.validate_value(item, path, violations, vm)?;
+                    path.pop();
+                }
+            }
+            (ValidatorKind::Map(schema), KValue::Map(map)) => {
+                for (key, field_validator) in schema.iter() {
+                    let field = map.get(key.as_str()).unwrap_or_default();
+                    path.push(PathElement::Key(key.clone()));
+                    field_validator.validate_value(&field, path, violations, vm)?;
+                    path.pop();
+                }
+
+                if self.0.strict {
+                    let keys: Vec<_> = map.data().keys().cloned().collect();
+                    for key in keys {
+                        let known = match key.value() {
+                            KValue::Str(key) => schema.iter().any(|(k, _)| k == key),
+                            _ => false,
+                        };
+                        if !known {
+                            let key_string = vm.value_to_string(key.value())?;
+                            path.push(PathElement::Key(key_string.into()));
+                            violations.push(Violation {
+                                path: render_path(path),
+                                message: "unexpected key".into(),
+                            });
+                            path.pop();
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn with_constraint(&self, constraint: Constraint) -> Self {
+        let mut data = (*self.0).clone();
+        data.constraints.push(constraint);
+        Self(data.into())
+    }
+
+    #[koto_method]
+    fn check(&self, args: &[KValue]) -> Result<KValue> {
+        let (f, message) = match args {
+            [f] if f.is_callable() => (f.clone(), "failed check".into()),
+            [f, KValue::Str(message)] if f.is_callable() => (f.clone(), message.clone()),
+            unexpected => {
+                return type_error_with_slice(
+                    "a function, and an optional message String",
+                    unexpected,
+                )
+            }
+        };
+        Ok(self
+            .with_constraint(Constraint::Check { f, message })
+            .into())
+    }
+
+    #[koto_method]
+    fn errors(ctx: MethodContext<Self>) -> Result<KValue> {
+        let value = single_value(ctx.args)?;
+        let violations = ctx
+            .instance()?
+            .violations(value, &mut ctx.vm.spawn_shared_vm())?;
+        Ok(KList::from_slice(&violations.into_iter().map(KValue::from).collect::<Vec<_>>()).into())
+    }
+
+    #[koto_method]
+    fn is_valid(ctx: MethodContext<Self>) -> Result<KValue> {
+        let value = single_value(ctx.args)?;
+        let violations = ctx
+            .instance()?
+            .violations(value, &mut ctx.vm.spawn_shared_vm())?;
+        Ok(violations.is_empty().into())
+    }
+
+    #[koto_method]
+    fn matches(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Str(pattern)] => match Regex::new(pattern) {
+                Ok(regex) => Ok(self.with_constraint(Constraint::Pattern(regex)).into()),
+                Err(error) => runtime_error!("invalid pattern: {error}"),
+            },
+            unexpected => type_error_with_slice("a pattern String", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn max(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Number(n)] => Ok(self.with_constraint(Constraint::Max(*n)).into()),
+            unexpected => type_error_with_slice("a Number", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn max_len(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Number(n)] if *n >= 0 => {
+                Ok(self.with_constraint(Constraint::MaxLen(n.into())).into())
+            }
+            unexpected => type_error_with_slice("a non-negative Number", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn min(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Number(n)] => Ok(self.with_constraint(Constraint::Min(*n)).into()),
+            unexpected => type_error_with_slice("a Number", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn min_len(&self, args: &[KValue]) -> Result<KValue> {
+        match args {
+            [KValue::Number(n)] if *n >= 0 => {
+                Ok(self.with_constraint(Constraint::MinLen(n.into())).into())
+            }
+            unexpected => type_error_with_slice("a non-negative Number", unexpected),
+        }
+    }
+
+    #[koto_method]
+    fn one_of(&self, args: &[KValue]) -> Result<KValue> {
+        let values = match args {
+            [KValue::List(values)] => KTuple::from(values.data().as_slice()),
+            [KValue::Tuple(values)] => values.clone(),
+            unexpected => return type_error_with_slice("a List or Tuple of values", unexpected),
+        };
+        Ok(self.with_constraint(Constraint::OneOf(values)).into())
+    }
+
+    #[koto_method]
+    fn optional(&self) -> KValue {
+        let mut data = (*self.0).clone();
+        data.optional = true;
+        Self(data.into()).into()
+    }
+
+    #[koto_method]
+    fn strict(&self) -> Result<KValue> {
+        if !matches!(self.0.kind, ValidatorKind::Map(_)) {
+            return runtime_error!("strict is only supported by map validators");
+        }
+        let mut data = (*self.0).clone();
+        data.strict = true;
+        Ok(Self(data.into()).into())
+    }
+
+    #[koto_method]
+    fn validate(ctx: MethodContext<Self>) -> Result<KValue> {
+        let value = single_value(ctx.args)?;
+        let violations = ctx
+            .instance()?
+            .violations(value, &mut ctx.vm.spawn_shared_vm())?;
+        let result = if violations.is_empty() {
+            KResult::ok(value.clone())
+        } else {
+            KResult::err(
+                KList::from_slice(&violations.into_iter().map(KValue::from).collect::<Vec<_>>())
+                    .into(),
+            )
+        };
+        Ok(result.into())
+    }
+}
+
+impl Constraint {
+    // Checks the value against the constraint, returning a message if the check fails
+    fn check(&self, value: &KValue, vm: &mut KotoVm) -> Result<Option<String>> {
+        use Constraint::*;
+
+        let result = match self {
+            MinLen(min) => match value_len(value) {
+                Some(len) if len < *min => {
+                    Some(format!("expected a length of at least {min}, found {len}"))
+                }
+                _ => None,
+            },
+            MaxLen(max) => match value_len(value) {
+                Some(len) if len > *max => {
+                    Some(format!("expected a length of at most {max}, found {len}"))
+                }
+                _ => None,
+            },
+            Min(min) => match value {
+                KValue::Number(n) if n < min => Some(format!("expected at least {min}, found {n}")),
+                _ => None,
+            },
+            Max(max) => match value {
+                KValue::Number(n) if n > max => Some(format!("expected at most {max}, found {n}")),
+                _ => None,
+            },
+            Pattern(regex) => match value {
+                KValue::Str(s) if !regex.is_match(s) => {
+                    Some(format!("expected a match for the pattern '{regex}'"))
+                }
+                _ => None,
+            },
+            OneOf(values) => {
+                let mut found = false;
+                for candidate in values.iter() {
+                    let equal =
+                        vm.run_binary_op(BinaryOp::Equal, value.clone(), candidate.clone())?;
+                    if matches!(equal, KValue::Bool(true)) {
+                        found = true;
+                        break;
+                    }
+                }
+                if found {
+                    None
+                } else {
+                    let expected = vm.value_to_string(&KValue::Tuple(values.clone()))?;
+                    Some(format!("expected one of {expected}"))
+                }
+            }
+            Check { f, message } => match vm.call_function(f.clone(), value.clone())? {
+                KValue::Bool(true) => None,
+                KValue::Bool(false) => Some(message.to_string()),
+                unexpected => return type_error("a Bool from the check function", &unexpected),
+            },
+        };
+
+        Ok(result)
+    }
+}
+
+impl KotoObject for Validator {
+    fn display(&self, ctx: &mut DisplayContext) -> Result<()> {
+        let kind = match &self.0.kind {
+            ValidatorKind::Any => "Any",
+            ValidatorKind::Bool => "Bool",
+            ValidatorKind::Int => "Int",
+            ValidatorKind::List(_) => "List",
+            ValidatorKind::Map(_) => "Map",
+            ValidatorKind::Number => "Number",
+            ValidatorKind::String => "String",
+        };
+        ctx.append(format!("{}({kind})", Self::type_static()));
+        Ok(())
+    }
+}
+
+impl From<Validator> for KValue {
+    fn from(validator: Validator) -> Self {
+        KObject::from(validator).into()
+    }
+}
+
+// Returns the length of strings (in characters), lists, tuples, and maps
+fn value_len(value: &KValue) -> Option<usize> {
+    match value {
+        KValue::Str(s) => Some(s.chars().count()),
+        KValue::List(l) => Some(l.len()),
+        KValue::Tuple(t) => Some(t.len()),
+        KValue::Map(m) => Some(m.len()),
+        _ => None,
+    }
+}
+
+fn single_value(args: &[KValue]) -> Result<&KValue> {
+    match args {
+        [value] => Ok(value),
+        unexpected => type_error_with_slice("a single value", unexpected),
+    }
+}